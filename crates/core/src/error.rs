@@ -5,6 +5,7 @@ use crate::layout::LayoutError;
 use crate::parser::ParseError;
 use petty_render_core::RenderError;
 use petty_template_core::TemplateError;
+use petty_traits::ResourceError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -42,6 +43,8 @@ pub enum PipelineError {
     TemplateExecution(#[from] ExecutionError),
     #[error("PDF processing error: {0}")]
     Pdf(#[from] lopdf::Error),
+    #[error("Resource error: {0}")]
+    Resource(#[from] ResourceError),
     #[error("Other pipeline error: {0}")]
     Other(String),
 }
@@ -42,7 +42,7 @@ pub use types_base::{BoxConstraints, Color, Rect, Size};
 
 // Re-export from internal modules
 pub use error::PipelineError;
-pub use types::{ApiIndexEntry, LaidOutSequence, TocEntry};
+pub use types::{ApiIndexEntry, CaptionEntry, LaidOutSequence, TocEntry};
 
 // Re-export platform abstraction traits
 pub use traits::{
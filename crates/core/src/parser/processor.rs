@@ -168,6 +168,19 @@ pub trait TemplateParser {
         template_source: &str,
         resource_base_path: PathBuf,
     ) -> Result<TemplateFeatures, PipelineError>;
+
+    /// Parses a template source string layered on top of a base `theme` stylesheet.
+    /// Styles defined by the template take precedence over the theme's.
+    fn parse_with_theme(
+        &self,
+        _template_source: &str,
+        _resource_base_path: PathBuf,
+        _theme: &Stylesheet,
+    ) -> Result<TemplateFeatures, PipelineError> {
+        Err(PipelineError::Config(
+            "This template language does not support themes".to_string(),
+        ))
+    }
 }
 
 /// Adapter for core TemplateParser to work with PipelineError
@@ -197,4 +210,17 @@ where
             self.inner.parse(template_source, resource_base_path)?,
         ))
     }
+
+    fn parse_with_theme(
+        &self,
+        template_source: &str,
+        resource_base_path: PathBuf,
+        theme: &Stylesheet,
+    ) -> Result<TemplateFeatures, PipelineError> {
+        Ok(TemplateFeatures::from_core(self.inner.parse_with_theme(
+            template_source,
+            resource_base_path,
+            theme,
+        )?))
+    }
 }
@@ -4,7 +4,7 @@
 //! by the rendering phase.
 
 // Re-export from foundation crate
-pub use petty_types::{ApiIndexEntry, TocEntry};
+pub use petty_types::{ApiIndexEntry, CaptionEntry, TocEntry};
 
 // Re-export from render-core crate (moved to avoid circular dependencies)
 pub use petty_render_core::LaidOutSequence;
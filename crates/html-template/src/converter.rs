@@ -0,0 +1,336 @@
+//! Converts a parsed HTML tree into IDF nodes.
+
+use crate::dom::{HtmlElement, HtmlNode};
+use crate::error::HtmlTemplateError;
+use petty_idf::{
+    IRNode, InlineMetadata, InlineNode, NodeMetadata, TableBody, TableCell, TableColumnDefinition,
+    TableFooter, TableHeader, TableRow,
+};
+use petty_style::parsers::parse_inline_css;
+use petty_style::stylesheet::ElementStyle;
+use petty_template_core::TemplateFlags;
+
+/// Elements whose tag and end tag are dropped, keeping their content.
+const TRANSPARENT_ELEMENTS: &[&str] = &["html", "body"];
+
+/// Elements that form part of a paragraph's inline content.
+const INLINE_ELEMENTS: &[&str] = &["a", "b", "strong", "i", "em", "span", "img", "br"];
+
+pub(crate) struct Converter {
+    pub flags: TemplateFlags,
+}
+
+impl Converter {
+    pub fn new() -> Self {
+        Self {
+            flags: TemplateFlags::default(),
+        }
+    }
+
+    /// Converts a sequence of nodes in block context. Runs of text and inline
+    /// elements between blocks are wrapped in anonymous paragraphs.
+    pub fn convert_blocks(&mut self, nodes: &[HtmlNode]) -> Result<Vec<IRNode>, HtmlTemplateError> {
+        let mut blocks = Vec::new();
+        let mut inline_run: Vec<&HtmlNode> = Vec::new();
+
+        for node in nodes {
+            match node {
+                HtmlNode::Element(e) if TRANSPARENT_ELEMENTS.contains(&e.name.as_str()) => {
+                    self.flush_inline_run(&mut inline_run, &mut blocks)?;
+                    blocks.extend(self.convert_blocks(&e.children)?);
+                }
+                HtmlNode::Element(e) if !INLINE_ELEMENTS.contains(&e.name.as_str()) => {
+                    self.flush_inline_run(&mut inline_run, &mut blocks)?;
+                    if let Some(block) = self.convert_block(e)? {
+                        blocks.push(block);
+                    }
+                }
+                _ => inline_run.push(node),
+            }
+        }
+        self.flush_inline_run(&mut inline_run, &mut blocks)?;
+        Ok(blocks)
+    }
+
+    fn flush_inline_run(
+        &mut self,
+        run: &mut Vec<&HtmlNode>,
+        blocks: &mut Vec<IRNode>,
+    ) -> Result<(), HtmlTemplateError> {
+        let nodes: Vec<HtmlNode> = run.drain(..).cloned().collect();
+        let children = self.convert_paragraph_content(&nodes)?;
+        if !children.is_empty() {
+            blocks.push(IRNode::Paragraph {
+                meta: NodeMetadata::default(),
+                children,
+            });
+        }
+        Ok(())
+    }
+
+    fn convert_block(&mut self, e: &HtmlElement) -> Result<Option<IRNode>, HtmlTemplateError> {
+        let node = match e.name.as_str() {
+            "div" => IRNode::Block {
+                meta: self.node_meta(e, None)?,
+                children: self.convert_blocks(&e.children)?,
+            },
+            "p" => IRNode::Paragraph {
+                meta: self.node_meta(e, None)?,
+                children: self.convert_paragraph_content(&e.children)?,
+            },
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => IRNode::Heading {
+                meta: self.node_meta(e, None)?,
+                level: e.name[1..].parse().unwrap_or(1),
+                children: self.convert_paragraph_content(&e.children)?,
+            },
+            "ul" | "ol" => {
+                let ordered = e.name == "ol";
+                let mut children = Vec::new();
+                for child in element_children(e) {
+                    if child.name == "li" {
+                        children.push(IRNode::ListItem {
+                            meta: self.node_meta(child, None)?,
+                            children: self.convert_blocks(&child.children)?,
+                        });
+                    } else {
+                        warn_skipped(child, "inside a list");
+                    }
+                }
+                IRNode::List {
+                    meta: self.node_meta(e, ordered.then_some("list-style-type: decimal"))?,
+                    start: e.attribute("start").and_then(|s| s.trim().parse().ok()),
+                    children,
+                }
+            }
+            "table" => self.convert_table(e)?,
+            _ => {
+                warn_skipped(e, "at block level");
+                return Ok(None);
+            }
+        };
+        Ok(Some(node))
+    }
+
+    fn convert_table(&mut self, e: &HtmlElement) -> Result<IRNode, HtmlTemplateError> {
+        let mut header_rows = Vec::new();
+        let mut body_rows = Vec::new();
+        let mut footer_rows = Vec::new();
+        for child in element_children(e) {
+            match child.name.as_str() {
+                "thead" => header_rows.extend(self.convert_rows(child)?),
+                "tbody" => body_rows.extend(self.convert_rows(child)?),
+                "tfoot" => footer_rows.extend(self.convert_rows(child)?),
+                "tr" => body_rows.push(self.convert_row(child)?),
+                _ => warn_skipped(child, "inside a table"),
+            }
+        }
+
+        let column_count = header_rows
+            .iter()
+            .chain(&body_rows)
+            .chain(&footer_rows)
+            .map(|row| row.cells.iter().map(|c| c.col_span).sum::<usize>())
+            .max()
+            .unwrap_or(0);
+
+        Ok(IRNode::Table {
+            meta: self.node_meta(e, None)?,
+            columns: vec![TableColumnDefinition::default(); column_count],
+            header: (!header_rows.is_empty()).then(|| Box::new(TableHeader { rows: header_rows })),
+            body: Box::new(TableBody { rows: body_rows }),
+            footer: (!footer_rows.is_empty()).then(|| Box::new(TableFooter { rows: footer_rows })),
+        })
+    }
+
+    fn convert_rows(&mut self, section: &HtmlElement) -> Result<Vec<TableRow>, HtmlTemplateError> {
+        let mut rows = Vec::new();
+        for child in element_children(section) {
+            if child.name == "tr" {
+                rows.push(self.convert_row(child)?);
+            } else {
+                warn_skipped(child, "inside a table section");
+            }
+        }
+        Ok(rows)
+    }
+
+    fn convert_row(&mut self, row: &HtmlElement) -> Result<TableRow, HtmlTemplateError> {
+        let mut cells = Vec::new();
+        for child in element_children(row) {
+            if child.name != "td" && child.name != "th" {
+                warn_skipped(child, "inside a table row");
+                continue;
+            }
+            let default_css = (child.name == "th").then_some("font-weight: bold");
+            let span = |name: &str| {
+                child
+                    .attribute(name)
+                    .and_then(|s| s.trim().parse().ok())
+                    .filter(|&n: &usize| n > 0)
+                    .unwrap_or(1)
+            };
+            cells.push(TableCell {
+                style_sets: Vec::new(),
+                style_override: parse_style(child, default_css)?,
+                children: self.convert_blocks(&child.children)?,
+                col_span: span("colspan"),
+                row_span: span("rowspan"),
+            });
+        }
+        Ok(TableRow { cells })
+    }
+
+    /// Converts the content of a paragraph-like element, collapsing whitespace.
+    fn convert_paragraph_content(
+        &mut self,
+        nodes: &[HtmlNode],
+    ) -> Result<Vec<InlineNode>, HtmlTemplateError> {
+        let mut inlines = self.convert_inlines(nodes)?;
+        trim_edges(&mut inlines);
+        Ok(inlines)
+    }
+
+    fn convert_inlines(
+        &mut self,
+        nodes: &[HtmlNode],
+    ) -> Result<Vec<InlineNode>, HtmlTemplateError> {
+        let mut inlines = Vec::new();
+        for node in nodes {
+            let e = match node {
+                HtmlNode::Text(text) => {
+                    let text = collapse_whitespace(text);
+                    if !text.is_empty() {
+                        inlines.push(InlineNode::Text(text));
+                    }
+                    continue;
+                }
+                HtmlNode::Element(e) => e,
+            };
+            let inline = match e.name.as_str() {
+                "br" => InlineNode::LineBreak,
+                "img" => InlineNode::Image {
+                    meta: self.inline_meta(e, None)?,
+                    src: e.attribute("src").unwrap_or_default().to_string(),
+                },
+                "a" => {
+                    let href = e.attribute("href").unwrap_or_default().to_string();
+                    if href.starts_with('#') {
+                        self.flags.has_internal_links = true;
+                    }
+                    InlineNode::Hyperlink {
+                        meta: self.inline_meta(e, None)?,
+                        href,
+                        children: self.convert_inlines(&e.children)?,
+                    }
+                }
+                "b" | "strong" | "i" | "em" | "span" => {
+                    let default_css = match e.name.as_str() {
+                        "b" | "strong" => Some("font-weight: bold"),
+                        "i" | "em" => Some("font-style: italic"),
+                        _ => None,
+                    };
+                    InlineNode::StyledSpan {
+                        meta: self.inline_meta(e, default_css)?,
+                        children: self.convert_inlines(&e.children)?,
+                    }
+                }
+                _ => {
+                    warn_skipped(e, "in inline content");
+                    continue;
+                }
+            };
+            inlines.push(inline);
+        }
+        Ok(inlines)
+    }
+
+    fn node_meta(
+        &mut self,
+        e: &HtmlElement,
+        default_css: Option<&str>,
+    ) -> Result<NodeMetadata, HtmlTemplateError> {
+        let id = e.attribute("id").map(str::to_string);
+        if id.is_some() {
+            self.flags.has_internal_links = true;
+        }
+        Ok(NodeMetadata {
+            id,
+            style_sets: Vec::new(),
+            style_override: parse_style(e, default_css)?,
+            classes: e
+                .attribute("class")
+                .map(|c| c.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    fn inline_meta(
+        &mut self,
+        e: &HtmlElement,
+        default_css: Option<&str>,
+    ) -> Result<InlineMetadata, HtmlTemplateError> {
+        Ok(InlineMetadata {
+            style_sets: Vec::new(),
+            style_override: parse_style(e, default_css)?,
+        })
+    }
+}
+
+/// Builds an element's style override from the tag's default presentation,
+/// followed by its `style` attribute.
+fn parse_style(
+    e: &HtmlElement,
+    default_css: Option<&str>,
+) -> Result<Option<ElementStyle>, HtmlTemplateError> {
+    let inline_css = e.attribute("style");
+    if default_css.is_none() && inline_css.is_none() {
+        return Ok(None);
+    }
+    let mut style = ElementStyle::default();
+    for css in default_css.into_iter().chain(inline_css) {
+        parse_inline_css(css, &mut style).map_err(|err| HtmlTemplateError::InvalidStyle {
+            style: css.to_string(),
+            message: err.to_string(),
+        })?;
+    }
+    Ok(Some(style))
+}
+
+fn element_children(e: &HtmlElement) -> impl Iterator<Item = &HtmlElement> {
+    e.children.iter().filter_map(|child| match child {
+        HtmlNode::Element(element) => Some(element),
+        HtmlNode::Text(_) => None,
+    })
+}
+
+fn warn_skipped(e: &HtmlElement, context: &str) {
+    log::warn!("Skipping unsupported HTML element <{}> {}", e.name, context);
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_space = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
+
+/// Removes leading and trailing whitespace of a paragraph's top-level text runs.
+fn trim_edges(inlines: &mut Vec<InlineNode>) {
+    if let Some(InlineNode::Text(text)) = inlines.first_mut() {
+        *text = text.trim_start().to_string();
+    }
+    if let Some(InlineNode::Text(text)) = inlines.last_mut() {
+        *text = text.trim_end().to_string();
+    }
+    inlines.retain(|node| !matches!(node, InlineNode::Text(text) if text.is_empty()));
+}
@@ -0,0 +1,310 @@
+//! A small, lenient HTML tokenizer and tree builder.
+//!
+//! This is not a conforming HTML5 parser. It handles the constructs that appear in
+//! typical hand-written fragments: void elements, unquoted and valueless attributes,
+//! comments, doctypes, character references, and implicitly closed `p`, `li`, `tr`
+//! and table cells. Mismatched end tags close the nearest matching open element.
+
+use crate::error::HtmlTemplateError;
+
+/// A node of the parsed HTML tree.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum HtmlNode {
+    Element(HtmlElement),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HtmlElement {
+    /// The lowercased tag name.
+    pub name: String,
+    /// Attributes with lowercased names, in source order.
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<HtmlNode>,
+}
+
+impl HtmlElement {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Elements that never have content or an end tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is raw text rather than markup.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// Parses an HTML fragment into a list of top-level nodes.
+pub(crate) fn parse_fragment(source: &str) -> Result<Vec<HtmlNode>, HtmlTemplateError> {
+    let mut builder = TreeBuilder::default();
+    let mut rest = source;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            builder.text(rest);
+            break;
+        };
+        builder.text(&rest[..lt]);
+        rest = &rest[lt..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = match comment.find("-->") {
+                Some(end) => &comment[end + 3..],
+                None => return Err(HtmlTemplateError::Parse("Unterminated comment".to_string())),
+            };
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = match rest.find('>') {
+                Some(end) => &rest[end + 1..],
+                None => return Err(unterminated(rest)),
+            };
+        } else if let Some(end_tag) = rest.strip_prefix("</") {
+            let end = end_tag.find('>').ok_or_else(|| unterminated(rest))?;
+            builder.close(&end_tag[..end].trim().to_ascii_lowercase());
+            rest = &end_tag[end + 1..];
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let (element, self_closing, remaining) = parse_start_tag(&rest[1..])?;
+            rest = remaining;
+            let name = element.name.clone();
+            builder.open(element);
+            if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+                builder.close(&name);
+            } else if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+                let end = rest
+                    .to_ascii_lowercase()
+                    .find(&format!("</{}", name))
+                    .unwrap_or(rest.len());
+                builder.text(&rest[..end]);
+                rest = &rest[end..];
+            }
+        } else {
+            // A stray '<' is treated as text.
+            builder.text("<");
+            rest = &rest[1..];
+        }
+    }
+
+    Ok(builder.finish())
+}
+
+fn unterminated(input: &str) -> HtmlTemplateError {
+    let snippet: String = input.chars().take(20).collect();
+    HtmlTemplateError::Parse(format!("Unterminated tag starting at '{}'", snippet))
+}
+
+/// Parses the inside of a start tag (after `<`), returning the element, whether it
+/// was self-closing, and the input following the closing `>`.
+fn parse_start_tag(input: &str) -> Result<(HtmlElement, bool, &str), HtmlTemplateError> {
+    let name_end = input
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .ok_or_else(|| unterminated(input))?;
+    let mut element = HtmlElement {
+        name: input[..name_end].to_ascii_lowercase(),
+        attributes: Vec::new(),
+        children: Vec::new(),
+    };
+    let mut rest = &input[name_end..];
+
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Ok((element, true, after));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Ok((element, false, after));
+        }
+        if let Some(after) = rest.strip_prefix('/') {
+            rest = after;
+            continue;
+        }
+        if rest.is_empty() {
+            return Err(unterminated(input));
+        }
+
+        let key_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .ok_or_else(|| unterminated(input))?;
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let value = if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let close = after_eq[1..]
+                        .find(quote)
+                        .ok_or_else(|| unterminated(input))?;
+                    rest = &after_eq[close + 2..];
+                    decode_entities(&after_eq[1..close + 1])
+                }
+                _ => {
+                    let end = after_eq
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .ok_or_else(|| unterminated(input))?;
+                    rest = &after_eq[end..];
+                    decode_entities(&after_eq[..end])
+                }
+            }
+        } else {
+            String::new()
+        };
+        element.attributes.push((key, value));
+    }
+}
+
+/// Decodes the common named character references and all numeric ones.
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|semi| {
+            let entity = &rest[1..semi];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, semi))
+        });
+        match decoded {
+            Some((c, semi)) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[derive(Default)]
+struct TreeBuilder {
+    roots: Vec<HtmlNode>,
+    open: Vec<HtmlElement>,
+}
+
+impl TreeBuilder {
+    fn text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let text = match self.open.last() {
+            Some(e) if RAW_TEXT_ELEMENTS.contains(&e.name.as_str()) => text.to_string(),
+            _ => decode_entities(text),
+        };
+        self.append(HtmlNode::Text(text));
+    }
+
+    fn open(&mut self, element: HtmlElement) {
+        // A new paragraph, list item, row or cell implicitly closes an open sibling.
+        let closes: &[&str] = match element.name.as_str() {
+            "p" => &["p"],
+            "li" => &["li"],
+            "tr" => &["tr", "td", "th"],
+            "td" | "th" => &["td", "th"],
+            _ => &[],
+        };
+        while self
+            .open
+            .last()
+            .is_some_and(|e| closes.contains(&e.name.as_str()))
+        {
+            self.pop();
+        }
+        self.open.push(element);
+    }
+
+    /// Closes the innermost open element named `name`, along with any elements
+    /// opened inside it. End tags without a matching open element are ignored.
+    fn close(&mut self, name: &str) {
+        if let Some(pos) = self.open.iter().rposition(|e| e.name == name) {
+            while self.open.len() > pos {
+                self.pop();
+            }
+        }
+    }
+
+    fn pop(&mut self) {
+        if let Some(element) = self.open.pop() {
+            self.append(HtmlNode::Element(element));
+        }
+    }
+
+    fn append(&mut self, node: HtmlNode) {
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.roots.push(node),
+        }
+    }
+
+    fn finish(mut self) -> Vec<HtmlNode> {
+        while !self.open.is_empty() {
+            self.pop();
+        }
+        self.roots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(node: &HtmlNode) -> &HtmlElement {
+        match node {
+            HtmlNode::Element(e) => e,
+            HtmlNode::Text(t) => panic!("Expected element, got text {:?}", t),
+        }
+    }
+
+    #[test]
+    fn test_parse_lenient_fragment() {
+        let nodes = parse_fragment(
+            "<!DOCTYPE html><!-- note --><UL class=list><li>One &amp; two<li data-x>Three<br></ul>\
+             <p>First<p>Second &#x41;&lt;",
+        )
+        .unwrap();
+        assert_eq!(nodes.len(), 3);
+
+        let list = element(&nodes[0]);
+        assert_eq!(list.name, "ul");
+        assert_eq!(list.attribute("class"), Some("list"));
+        assert_eq!(list.children.len(), 2);
+        let first = element(&list.children[0]);
+        assert_eq!(
+            first.children,
+            vec![HtmlNode::Text("One & two".to_string())]
+        );
+        let second = element(&list.children[1]);
+        assert_eq!(second.attribute("data-x"), Some(""));
+        assert_eq!(element(&second.children[1]).name, "br");
+
+        assert_eq!(element(&nodes[1]).name, "p");
+        assert_eq!(
+            element(&nodes[2]).children,
+            vec![HtmlNode::Text("Second A<".to_string())]
+        );
+
+        assert!(parse_fragment("<p class=\"x").is_err());
+    }
+}
@@ -0,0 +1,17 @@
+use petty_template_core::TemplateError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HtmlTemplateError {
+    #[error("HTML parse error: {0}")]
+    Parse(String),
+
+    #[error("Invalid style attribute '{style}': {message}")]
+    InvalidStyle { style: String, message: String },
+}
+
+impl From<HtmlTemplateError> for TemplateError {
+    fn from(err: HtmlTemplateError) -> Self {
+        TemplateError::ParseError(err.to_string())
+    }
+}
@@ -0,0 +1,15 @@
+//! HTML-subset template parser.
+//!
+//! This crate converts static HTML fragments directly into the IDF tree, for
+//! documents that do not need data binding. Only a small subset of HTML is
+//! supported: headings, paragraphs, divs, lists, tables, links, images and
+//! `b`/`i`/`span` inline formatting, each with optional `style`, `class` and
+//! `id` attributes. Unsupported elements are skipped with a warning.
+
+mod converter;
+mod dom;
+pub mod error;
+pub mod processor;
+
+pub use error::HtmlTemplateError;
+pub use processor::{CompiledHtmlTemplate, HtmlParser};
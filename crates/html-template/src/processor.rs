@@ -0,0 +1,238 @@
+// Processor that implements TemplateParser trait for HTML fragments
+use crate::converter::Converter;
+use crate::dom::parse_fragment;
+use crate::error::HtmlTemplateError;
+use petty_idf::{IRNode, apply_style_rules};
+use petty_style::stylesheet::Stylesheet;
+use petty_template_core::{
+    ExecutionConfig, TemplateError, TemplateFeatures, TemplateFlags, TemplateParser,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// HTML-subset template parser
+pub struct HtmlParser;
+
+/// Compiled HTML template. The document is static, so execution ignores the data source.
+pub struct CompiledHtmlTemplate {
+    nodes: Vec<IRNode>,
+    stylesheet: Arc<Stylesheet>,
+    features: TemplateFlags,
+    resource_base_path: PathBuf,
+}
+
+impl petty_template_core::TemplateExecutor for CompiledHtmlTemplate {
+    fn execute(
+        &self,
+        _data_source: &str,
+        _config: ExecutionConfig,
+    ) -> Result<Vec<IRNode>, TemplateError> {
+        Ok(self.nodes.clone())
+    }
+}
+
+impl petty_template_core::TemplateMetadata for CompiledHtmlTemplate {
+    fn stylesheet(&self) -> Arc<Stylesheet> {
+        Arc::clone(&self.stylesheet)
+    }
+
+    fn resource_base_path(&self) -> &Path {
+        &self.resource_base_path
+    }
+
+    fn features(&self) -> TemplateFlags {
+        self.features
+    }
+}
+
+impl TemplateParser for HtmlParser {
+    fn parse(
+        &self,
+        template_source: &str,
+        resource_base_path: PathBuf,
+    ) -> Result<TemplateFeatures, TemplateError> {
+        compile_template(template_source, resource_base_path, None)
+    }
+
+    fn parse_with_theme(
+        &self,
+        template_source: &str,
+        resource_base_path: PathBuf,
+        theme: &Stylesheet,
+    ) -> Result<TemplateFeatures, TemplateError> {
+        compile_template(template_source, resource_base_path, Some(theme))
+    }
+}
+
+fn compile_template(
+    template_source: &str,
+    resource_base_path: PathBuf,
+    theme: Option<&Stylesheet>,
+) -> Result<TemplateFeatures, TemplateError> {
+    let dom = parse_fragment(template_source)?;
+    let mut converter = Converter::new();
+    let mut nodes = converter.convert_blocks(&dom)?;
+
+    let mut stylesheet = Stylesheet::default();
+    if let Some(theme) = theme {
+        stylesheet.apply_theme(theme);
+    }
+    // The tree is fixed at compile time, so selector rules can be resolved once.
+    apply_style_rules(&mut nodes, &stylesheet);
+
+    Ok(TemplateFeatures {
+        main_template: Arc::new(CompiledHtmlTemplate {
+            nodes,
+            stylesheet: Arc::new(stylesheet),
+            features: converter.flags,
+            resource_base_path,
+        }),
+        role_templates: HashMap::new(),
+    })
+}
+
+impl HtmlParser {
+    /// Converts an HTML fragment directly into IDF nodes, without a stylesheet.
+    pub fn to_ir(template_source: &str) -> Result<Vec<IRNode>, HtmlTemplateError> {
+        Converter::new().convert_blocks(&parse_fragment(template_source)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petty_idf::{InlineNode, NodeMetadata};
+    use petty_style::font::{FontStyle, FontWeight};
+    use petty_style::list::ListStyleType;
+
+    fn text(s: &str) -> InlineNode {
+        InlineNode::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_convert_html_fragment() {
+        let html = r##"
+            <h2 id="intro" class="title">Hello <b>bold</b> world</h2>
+            <div style="margin-top: 10pt">
+                Loose text
+                <p>A <a href="#intro">link</a>, <i>italic</i> and <img src="logo.png"></p>
+                <marquee>ignored</marquee>
+            </div>
+            <ol start="3"><li>First<li><p>Second</p></ol>
+            <table>
+                <thead><tr><th>Name</th><th>Qty</th></tr></thead>
+                <tr><td colspan="2">Apples</td></tr>
+                <tfoot><tr><td>Total</td><td>3</td></tr></tfoot>
+            </table>
+        "##;
+        let nodes = HtmlParser::to_ir(html).unwrap();
+        assert_eq!(nodes.len(), 4);
+
+        let IRNode::Heading {
+            meta,
+            level,
+            children,
+        } = &nodes[0]
+        else {
+            panic!("Expected heading, got {:?}", nodes[0]);
+        };
+        assert_eq!(*level, 2);
+        assert_eq!(meta.id.as_deref(), Some("intro"));
+        assert_eq!(meta.classes, vec!["title".to_string()]);
+        assert_eq!(children.len(), 3);
+        assert_eq!(children[0], text("Hello "));
+        let InlineNode::StyledSpan {
+            meta,
+            children: bold,
+        } = &children[1]
+        else {
+            panic!("Expected span, got {:?}", children[1]);
+        };
+        let bold_style = meta.style_override.as_ref().unwrap();
+        assert_eq!(bold_style.font_weight, Some(FontWeight::Bold));
+        assert_eq!(bold, &vec![text("bold")]);
+
+        let IRNode::Block { meta, children } = &nodes[1] else {
+            panic!("Expected block, got {:?}", nodes[1]);
+        };
+        let block_style = meta.style_override.as_ref().unwrap();
+        assert_eq!(block_style.margin.as_ref().unwrap().top, 10.0);
+        // The loose text is wrapped in a paragraph and <marquee> is skipped.
+        assert_eq!(children.len(), 2);
+        assert_eq!(
+            children[0],
+            IRNode::Paragraph {
+                meta: NodeMetadata::default(),
+                children: vec![text("Loose text")],
+            }
+        );
+        let IRNode::Paragraph { children, .. } = &children[1] else {
+            panic!("Expected paragraph, got {:?}", children[1]);
+        };
+        assert!(matches!(
+            &children[1],
+            InlineNode::Hyperlink { href, .. } if href == "#intro"
+        ));
+        let InlineNode::StyledSpan { meta, .. } = &children[3] else {
+            panic!("Expected span, got {:?}", children[3]);
+        };
+        let italic_style = meta.style_override.as_ref().unwrap();
+        assert_eq!(italic_style.font_style, Some(FontStyle::Italic));
+        assert!(matches!(&children[5], InlineNode::Image { src, .. } if src == "logo.png"));
+
+        let IRNode::List {
+            meta,
+            start,
+            children,
+        } = &nodes[2]
+        else {
+            panic!("Expected list, got {:?}", nodes[2]);
+        };
+        assert_eq!(*start, Some(3));
+        let list_style = meta.style_override.as_ref().unwrap();
+        assert_eq!(list_style.list_style_type, Some(ListStyleType::Decimal));
+        assert_eq!(children.len(), 2);
+        for item in children {
+            let IRNode::ListItem { children, .. } = item else {
+                panic!("Expected list item, got {:?}", item);
+            };
+            assert!(matches!(children.as_slice(), [IRNode::Paragraph { .. }]));
+        }
+
+        let IRNode::Table {
+            columns,
+            header,
+            body,
+            footer,
+            ..
+        } = &nodes[3]
+        else {
+            panic!("Expected table, got {:?}", nodes[3]);
+        };
+        assert_eq!(columns.len(), 2);
+        assert_eq!(header.as_ref().unwrap().rows[0].cells.len(), 2);
+        assert_eq!(body.rows.len(), 1);
+        assert_eq!(body.rows[0].cells[0].col_span, 2);
+        assert_eq!(footer.as_ref().unwrap().rows[0].cells.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_reports_features_and_style_errors() {
+        let features = HtmlParser
+            .parse(r##"<p><a href="#top">Top</a></p>"##, PathBuf::new())
+            .unwrap();
+        assert!(features.main_template.features().has_internal_links);
+        let nodes = features
+            .main_template
+            .execute("", ExecutionConfig::default())
+            .unwrap();
+        assert_eq!(nodes.len(), 1);
+
+        let err = HtmlParser
+            .parse(r#"<p style="margin: nonsense">x</p>"#, PathBuf::new())
+            .err()
+            .unwrap();
+        assert!(matches!(err, TemplateError::ParseError(_)));
+    }
+}
@@ -4,9 +4,12 @@
 //! structure and content after parsing but before layout.
 
 use petty_style::dimension::Dimension;
-use petty_style::stylesheet::ElementStyle;
+use petty_style::selector::SelectorSubject;
+use petty_style::stylesheet::{ElementStyle, Stylesheet};
 use std::sync::Arc;
 
+pub use petty_types::{CaptionKind, CaptionPosition};
+
 // --- Shared Types ---
 
 /// A string type for the document.
@@ -22,6 +25,8 @@ pub struct NodeMetadata {
     pub id: Option<TextStr>,
     pub style_sets: Vec<Arc<ElementStyle>>,
     pub style_override: Option<ElementStyle>,
+    /// Class names assigned by the template, matched by stylesheet selectors.
+    pub classes: Vec<TextStr>,
 }
 
 /// A common metadata structure for all `InlineNode`s.
@@ -78,11 +83,47 @@ pub enum IRNode {
         columns: Vec<TableColumnDefinition>,
         header: Option<Box<TableHeader>>,
         body: Box<TableBody>,
+        /// Rows repeated at the bottom of every page the table occupies, e.g. totals.
+        footer: Option<Box<TableFooter>>,
     },
     /// A hard page break.
     PageBreak { master_name: Option<TextStr> },
     /// A marker for generating an index entry, with no visual output.
     IndexMarker { meta: NodeMetadata, term: TextStr },
+    /// An interactive (AcroForm) form field.
+    FormField {
+        meta: NodeMetadata,
+        /// The fully qualified field name, unique within the document.
+        name: TextStr,
+        kind: FormFieldKind,
+    },
+    /// A numbered caption attached to a table or image, e.g. "Table 1: Results".
+    Caption {
+        meta: NodeMetadata,
+        kind: CaptionKind,
+        position: CaptionPosition,
+        /// The number among captions of the same kind, assigned by [`number_captions`].
+        number: Option<usize>,
+        /// The caption text, without its label.
+        children: Vec<InlineNode>,
+        /// The captioned table or image.
+        content: Box<IRNode>,
+    },
+    /// A vertical bar chart drawn as vector graphics, sized by its style's `width`
+    /// and `height`.
+    BarChart {
+        meta: NodeMetadata,
+        bars: Vec<ChartEntry>,
+    },
+    /// A pie chart with a legend, drawn as vector graphics and sized by its style's
+    /// `width` and `height`. Each slice's angle is its share of the total value.
+    PieChart {
+        meta: NodeMetadata,
+        slices: Vec<ChartEntry>,
+        /// The radius of the hole as a fraction of the pie's radius; a non-zero value
+        /// draws a donut chart.
+        inner_radius: f32,
+    },
 }
 
 impl IRNode {
@@ -98,6 +139,10 @@ impl IRNode {
             IRNode::ListItem { meta, .. } => Some(meta),
             IRNode::Table { meta, .. } => Some(meta),
             IRNode::IndexMarker { meta, .. } => Some(meta),
+            IRNode::FormField { meta, .. } => Some(meta),
+            IRNode::Caption { meta, .. } => Some(meta),
+            IRNode::BarChart { meta, .. } => Some(meta),
+            IRNode::PieChart { meta, .. } => Some(meta),
             IRNode::Root(_) | IRNode::PageBreak { .. } => None,
         }
     }
@@ -114,6 +159,10 @@ impl IRNode {
             IRNode::ListItem { meta, .. } => Some(meta),
             IRNode::Table { meta, .. } => Some(meta),
             IRNode::IndexMarker { meta, .. } => Some(meta),
+            IRNode::FormField { meta, .. } => Some(meta),
+            IRNode::Caption { meta, .. } => Some(meta),
+            IRNode::BarChart { meta, .. } => Some(meta),
+            IRNode::PieChart { meta, .. } => Some(meta),
             IRNode::Root(_) | IRNode::PageBreak { .. } => None,
         }
     }
@@ -140,10 +189,121 @@ impl IRNode {
             IRNode::Table { .. } => "table",
             IRNode::PageBreak { .. } => "page-break",
             IRNode::IndexMarker { .. } => "index-marker",
+            IRNode::FormField { .. } => "form-field",
+            IRNode::Caption { .. } => "caption",
+            IRNode::BarChart { .. } => "bar-chart",
+            IRNode::PieChart { .. } => "pie-chart",
         }
     }
 }
 
+/// Resolves the stylesheet's selector rules against `nodes` and prepends the matching
+/// styles to each node's style sets, so named styles and overrides still take precedence.
+pub fn apply_style_rules(nodes: &mut [IRNode], stylesheet: &Stylesheet) {
+    if !stylesheet.rules.is_empty() {
+        apply_rules_recursive(nodes, stylesheet, &mut Vec::new());
+    }
+}
+
+fn apply_rules_recursive(
+    nodes: &mut [IRNode],
+    stylesheet: &Stylesheet,
+    path: &mut Vec<(&'static str, Vec<TextStr>)>,
+) {
+    for node in nodes {
+        let kind = node.kind();
+        let Some(meta) = node.meta_mut() else {
+            if let IRNode::Root(children) = node {
+                apply_rules_recursive(children, stylesheet, path);
+            }
+            continue;
+        };
+        path.push((kind, meta.classes.clone()));
+        let subjects: Vec<SelectorSubject> = path
+            .iter()
+            .map(|(element, classes)| SelectorSubject { element, classes })
+            .collect();
+        let matched = stylesheet.matching_rule_styles(&subjects);
+        if !matched.is_empty() {
+            meta.style_sets.splice(0..0, matched);
+        }
+
+        match node {
+            IRNode::Block { children, .. }
+            | IRNode::FlexContainer { children, .. }
+            | IRNode::List { children, .. }
+            | IRNode::ListItem { children, .. } => {
+                apply_rules_recursive(children, stylesheet, path);
+            }
+            IRNode::Table { .. } => {
+                for row in table_rows_mut(node) {
+                    for cell in &mut row.cells {
+                        apply_rules_recursive(&mut cell.children, stylesheet, path);
+                    }
+                }
+            }
+            IRNode::Caption { content, .. } => {
+                apply_rules_recursive(std::slice::from_mut(content.as_mut()), stylesheet, path);
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+}
+
+/// Numbers the captions in `nodes` in document order, with a separate sequence for
+/// each [`CaptionKind`], so the second captioned table is "Table 2".
+pub fn number_captions(nodes: &mut [IRNode]) {
+    number_captions_recursive(nodes, &mut [0; 2]);
+}
+
+fn number_captions_recursive(nodes: &mut [IRNode], counters: &mut [usize; 2]) {
+    for node in nodes {
+        match node {
+            IRNode::Caption {
+                kind,
+                number,
+                content,
+                ..
+            } => {
+                let counter = &mut counters[*kind as usize];
+                *counter += 1;
+                *number = Some(*counter);
+                number_captions_recursive(std::slice::from_mut(content.as_mut()), counters);
+            }
+            IRNode::Root(children)
+            | IRNode::Block { children, .. }
+            | IRNode::FlexContainer { children, .. }
+            | IRNode::List { children, .. }
+            | IRNode::ListItem { children, .. } => number_captions_recursive(children, counters),
+            IRNode::Table { .. } => {
+                for row in table_rows_mut(node) {
+                    for cell in &mut row.cells {
+                        number_captions_recursive(&mut cell.children, counters);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The type of a form field, together with its default value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormFieldKind {
+    /// A single-line text input.
+    Text { value: TextStr },
+    /// A checkbox.
+    Checkbox { checked: bool },
+}
+
+/// A labelled value in a [`IRNode::BarChart`] or [`IRNode::PieChart`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartEntry {
+    pub label: TextStr,
+    pub value: f64,
+}
+
 /// Represents an inline-level element within a block like a `Paragraph`.
 #[derive(Debug, Clone, PartialEq)]
 pub enum InlineNode {
@@ -168,6 +328,13 @@ pub enum InlineNode {
     },
     /// An inline image.
     Image { meta: InlineMetadata, src: TextStr },
+    /// A small line chart of `values` drawn inline with text, `width` by `height` points.
+    Sparkline {
+        meta: InlineMetadata,
+        values: Vec<f64>,
+        width: f32,
+        height: f32,
+    },
     /// A soft line break.
     LineBreak,
 }
@@ -180,6 +347,7 @@ pub struct TableNode {
     pub columns: Vec<TableColumnDefinition>,
     pub header: Option<Box<TableHeader>>,
     pub body: Box<TableBody>,
+    pub footer: Option<Box<TableFooter>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -199,6 +367,52 @@ pub struct TableBody {
     pub rows: Vec<TableRow>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableFooter {
+    pub rows: Vec<TableRow>,
+}
+
+/// Iterates over the header, body and footer rows of a table, in that order.
+/// Yields nothing for other nodes.
+pub fn table_rows(node: &IRNode) -> impl Iterator<Item = &TableRow> {
+    let (header, body, footer) = match node {
+        IRNode::Table {
+            header,
+            body,
+            footer,
+            ..
+        } => (header.as_deref(), Some(body.as_ref()), footer.as_deref()),
+        _ => (None, None, None),
+    };
+    header
+        .into_iter()
+        .flat_map(|h| &h.rows)
+        .chain(body.into_iter().flat_map(|b| &b.rows))
+        .chain(footer.into_iter().flat_map(|f| &f.rows))
+}
+
+/// Mutable version of [`table_rows`].
+pub fn table_rows_mut(node: &mut IRNode) -> impl Iterator<Item = &mut TableRow> {
+    let (header, body, footer) = match node {
+        IRNode::Table {
+            header,
+            body,
+            footer,
+            ..
+        } => (
+            header.as_deref_mut(),
+            Some(body.as_mut()),
+            footer.as_deref_mut(),
+        ),
+        _ => (None, None, None),
+    };
+    header
+        .into_iter()
+        .flat_map(|h| &mut h.rows)
+        .chain(body.into_iter().flat_map(|b| &mut b.rows))
+        .chain(footer.into_iter().flat_map(|f| &mut f.rows))
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TableRow {
     pub cells: Vec<TableCell>,
@@ -10,6 +10,23 @@ pub enum Expression {
     Selection(Selection),
     /// A call to a registered function.
     FunctionCall { name: String, args: Vec<Expression> },
+    /// A comparison of two expressions, evaluating to a boolean (e.g., `amount > 100`).
+    Comparison {
+        left: Box<Expression>,
+        op: ComparisonOp,
+        right: Box<Expression>,
+    },
+}
+
+/// The operator of a comparison expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
 }
 
 /// Represents a segment in a JPath selection.
@@ -19,6 +36,15 @@ pub enum PathSegment {
     Key(String),
     /// An array index (e.g., `[0]`).
     Index(usize),
+    /// Every element of an array (`[*]`), so the rest of the path selects one value
+    /// per element.
+    Wildcard,
+    /// The values of a key at any depth below the current value, depth first
+    /// (e.g., `..name`).
+    Descendant(String),
+    /// The elements of an array for which a predicate, evaluated with the element as
+    /// the context, is truthy (e.g., `[status == 'paid']`).
+    Filter(Box<Expression>),
 }
 
 /// Represents a path for selecting data.
@@ -26,8 +52,27 @@ pub enum PathSegment {
 pub enum Selection {
     /// Selects the current context node (`.`).
     CurrentContext,
-    /// Selects a value from the current variable scope.
-    Variable(String),
+    /// Selects a value from the current variable scope, followed by optional
+    /// key/index lookups into it (e.g., `$root.title`).
+    Variable(String, Vec<PathSegment>),
     /// Selects a node using a sequence of key/index lookups.
     Path(Vec<PathSegment>),
 }
+
+impl Selection {
+    /// Whether the selection can match several values, i.e. contains a `[*]`, `..` or
+    /// filter segment.
+    pub fn is_multi_valued(&self) -> bool {
+        match self {
+            Selection::CurrentContext => false,
+            Selection::Variable(_, segments) | Selection::Path(segments) => {
+                segments.iter().any(|segment| {
+                    matches!(
+                        segment,
+                        PathSegment::Wildcard | PathSegment::Descendant(_) | PathSegment::Filter(_)
+                    )
+                })
+            }
+        }
+    }
+}
@@ -1,8 +1,9 @@
 //! The evaluation engine for executing a parsed JPath AST.
-use super::ast::{Expression, PathSegment, Selection};
+use super::ast::{ComparisonOp, Expression, PathSegment, Selection};
 use super::functions::FunctionRegistry;
 use crate::error::JPathError;
 use serde_json::Value;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 /// A container for all state needed during expression evaluation.
@@ -18,9 +19,10 @@ pub struct EvaluationContext<'a> {
 pub fn evaluate(expr: &Expression, e_ctx: &EvaluationContext) -> Result<Value, JPathError> {
     match expr {
         Expression::Literal(val) => Ok(val.clone()),
-        Expression::Selection(sel) => Ok(select_first(sel, e_ctx.context_node, e_ctx.variables)
-            .cloned()
-            .unwrap_or(Value::Null)),
+        Expression::Selection(sel) if sel.is_multi_valued() => Ok(Value::Array(
+            select(sel, e_ctx)?.into_iter().cloned().collect(),
+        )),
+        Expression::Selection(sel) => Ok(select_first(sel, e_ctx)?.cloned().unwrap_or(Value::Null)),
         Expression::FunctionCall { name, args } => {
             let function = e_ctx
                 .functions
@@ -32,6 +34,32 @@ pub fn evaluate(expr: &Expression, e_ctx: &EvaluationContext) -> Result<Value, J
                 .collect::<Result<Vec<_>, _>>()?;
             Ok(function(e_ctx, evaluated_args))
         }
+        Expression::Comparison { left, op, right } => {
+            let left = evaluate(left, e_ctx)?;
+            let right = evaluate(right, e_ctx)?;
+            Ok(Value::Bool(compare(&left, *op, &right)))
+        }
+    }
+}
+
+/// Numbers compare numerically and strings lexicographically. Other values can only
+/// be equal or unequal, so ordering comparisons involving them are false.
+fn compare(left: &Value, op: ComparisonOp, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .zip(b.as_f64())
+            .and_then(|(a, b)| a.partial_cmp(&b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match op {
+        ComparisonOp::Eq => ordering.map_or(left == right, Ordering::is_eq),
+        ComparisonOp::NotEq => ordering.map_or(left != right, Ordering::is_ne),
+        ComparisonOp::Lt => ordering.is_some_and(Ordering::is_lt),
+        ComparisonOp::LtEq => ordering.is_some_and(Ordering::is_le),
+        ComparisonOp::Gt => ordering.is_some_and(Ordering::is_gt),
+        ComparisonOp::GtEq => ordering.is_some_and(Ordering::is_ge),
     }
 }
 
@@ -64,38 +92,82 @@ pub fn evaluate_as_string(
     })
 }
 
-/// Selects values based on a `Selection` path.
+/// Selects values based on a `Selection` path, from the context node or a variable
+/// of `e_ctx`.
 pub fn select<'a>(
     sel: &Selection,
-    context: &'a Value,
-    variables: &'a HashMap<String, Value>,
-) -> Vec<&'a Value> {
+    e_ctx: &EvaluationContext<'a>,
+) -> Result<Vec<&'a Value>, JPathError> {
     match sel {
-        Selection::CurrentContext => vec![context],
-        Selection::Variable(name) => variables.get(name).map_or(vec![], |v| vec![v]),
-        Selection::Path(segments) => {
-            let mut current = context;
-            for segment in segments {
-                let next_val = match segment {
-                    PathSegment::Key(k) => current.get(k),
-                    PathSegment::Index(i) => current.get(i),
-                };
-                if let Some(next) = next_val {
-                    current = next;
-                } else {
-                    return vec![]; // Path does not exist
+        Selection::CurrentContext => Ok(vec![e_ctx.context_node]),
+        Selection::Variable(name, segments) => match e_ctx.variables.get(name) {
+            Some(value) => follow_path(value, segments, e_ctx),
+            None => Ok(vec![]),
+        },
+        Selection::Path(segments) => follow_path(e_ctx.context_node, segments, e_ctx),
+    }
+}
+
+/// Follows key/index lookups from `start`, continuing from every element of an array
+/// at a `[*]` segment, from every match at a `..` segment and from every element a
+/// filter keeps. Values on which the path does not exist are dropped.
+fn follow_path<'a>(
+    start: &'a Value,
+    segments: &[PathSegment],
+    e_ctx: &EvaluationContext<'a>,
+) -> Result<Vec<&'a Value>, JPathError> {
+    let mut current = vec![start];
+    for segment in segments {
+        let mut next = Vec::new();
+        for value in current {
+            match segment {
+                PathSegment::Key(k) => next.extend(value.get(k)),
+                PathSegment::Index(i) => next.extend(value.get(i)),
+                PathSegment::Wildcard => next.extend(value.as_array().into_iter().flatten()),
+                PathSegment::Descendant(k) => collect_descendants(value, k, &mut next),
+                PathSegment::Filter(predicate) => {
+                    for item in value.as_array().into_iter().flatten() {
+                        let item_ctx = EvaluationContext {
+                            context_node: item,
+                            ..e_ctx.clone()
+                        };
+                        if evaluate_as_bool(predicate, &item_ctx)? {
+                            next.push(item);
+                        }
+                    }
                 }
             }
-            vec![current] // Return final value
         }
+        current = next;
+    }
+    Ok(current)
+}
+
+/// Collects the values of `key` in `value` and everything nested in it, depth first,
+/// visiting object entries in the order the map iterates them.
+fn collect_descendants<'a>(value: &'a Value, key: &str, matches: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(map) => {
+            for (k, child) in map {
+                if k == key {
+                    matches.push(child);
+                }
+                collect_descendants(child, key, matches);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_descendants(item, key, matches);
+            }
+        }
+        _ => {}
     }
 }
 
 /// Selects the first value from a `Selection` path.
 pub fn select_first<'a>(
     sel: &Selection,
-    context: &'a Value,
-    variables: &'a HashMap<String, Value>,
-) -> Option<&'a Value> {
-    select(sel, context, variables).into_iter().next()
+    e_ctx: &EvaluationContext<'a>,
+) -> Result<Option<&'a Value>, JPathError> {
+    Ok(select(sel, e_ctx)?.into_iter().next())
 }
@@ -1,5 +1,6 @@
 //! Defines the registry and built-in implementations for JPath functions.
-use super::engine::EvaluationContext;
+use super::engine::{EvaluationContext, evaluate};
+use super::parser::parse_expression;
 use serde_json::{Value, json};
 use std::collections::HashMap;
 
@@ -69,14 +70,82 @@ fn contains(_e_ctx: &EvaluationContext, args: Vec<Value>) -> Value {
     }
 }
 
-fn count(_e_ctx: &EvaluationContext, args: Vec<Value>) -> Value {
-    args.first()
-        .and_then(|v| v.as_array())
-        .map(|arr| arr.len() as f64)
+// --- Helpers for aggregates ---
+
+/// The values an aggregate runs over: the array of the first argument, or, when a
+/// key expression such as `'amount'` is given as the second argument, that
+/// expression evaluated against each element. Returns `None` for an invalid key.
+fn aggregate_values(e_ctx: &EvaluationContext, args: &[Value]) -> Option<Vec<Value>> {
+    let items = args.first().and_then(|v| v.as_array())?;
+    match args.get(1).and_then(|v| v.as_str()) {
+        None => Some(items.clone()),
+        Some(key) => {
+            let key = parse_expression(key).ok()?;
+            items
+                .iter()
+                .map(|item| {
+                    let item_ctx = EvaluationContext {
+                        context_node: item,
+                        ..e_ctx.clone()
+                    };
+                    evaluate(&key, &item_ctx).ok()
+                })
+                .collect()
+        }
+    }
+}
+
+/// The numbers among the aggregated values; other values are skipped.
+fn aggregate_numbers(e_ctx: &EvaluationContext, args: &[Value]) -> Option<Vec<f64>> {
+    aggregate_values(e_ctx, args).map(|values| values.iter().filter_map(Value::as_f64).collect())
+}
+
+/// Whole numbers become integers, so a total renders as `30` rather than `30.0`.
+fn number(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        json!(value as i64)
+    } else {
+        json!(value)
+    }
+}
+
+fn count(e_ctx: &EvaluationContext, args: Vec<Value>) -> Value {
+    // With a key, only elements where it resolves to a value are counted.
+    let keyed = args.get(1).is_some();
+    aggregate_values(e_ctx, &args)
+        .map(|values| values.iter().filter(|v| !keyed || !v.is_null()).count() as f64)
         .map(|len_f64| json!(len_f64))
         .unwrap_or(json!(0.0))
 }
 
+fn sum(e_ctx: &EvaluationContext, args: Vec<Value>) -> Value {
+    aggregate_numbers(e_ctx, &args)
+        .map(|numbers| number(numbers.iter().sum()))
+        .unwrap_or(json!(0))
+}
+
+/// `null` for an empty array, as there is no average to show.
+fn avg(e_ctx: &EvaluationContext, args: Vec<Value>) -> Value {
+    match aggregate_numbers(e_ctx, &args) {
+        Some(numbers) if !numbers.is_empty() => {
+            number(numbers.iter().sum::<f64>() / numbers.len() as f64)
+        }
+        _ => Value::Null,
+    }
+}
+
+fn min(e_ctx: &EvaluationContext, args: Vec<Value>) -> Value {
+    aggregate_numbers(e_ctx, &args)
+        .and_then(|numbers| numbers.into_iter().reduce(f64::min))
+        .map_or(Value::Null, number)
+}
+
+fn max(e_ctx: &EvaluationContext, args: Vec<Value>) -> Value {
+    aggregate_numbers(e_ctx, &args)
+        .and_then(|numbers| numbers.into_iter().reduce(f64::max))
+        .map_or(Value::Null, number)
+}
+
 fn position(e_ctx: &EvaluationContext, _args: Vec<Value>) -> Value {
     // Position is 1-based for user-facing templates.
     json!(e_ctx.loop_position.unwrap_or(0).saturating_add(1))
@@ -99,6 +168,10 @@ impl Default for FunctionRegistry {
         registry.register("concat", concat);
         registry.register("contains", contains);
         registry.register("count", count);
+        registry.register("sum", sum);
+        registry.register("avg", avg);
+        registry.register("min", min);
+        registry.register("max", max);
         registry.register("position", position);
         registry.register("equals", equals);
         registry
@@ -11,7 +11,7 @@ pub mod functions;
 mod parser;
 
 // --- Public API ---
-pub use ast::{Expression, PathSegment, Selection};
+pub use ast::{ComparisonOp, Expression, PathSegment, Selection};
 pub use engine::{EvaluationContext, evaluate, evaluate_as_bool, evaluate_as_string, select};
 pub use error::JPathError;
 pub use functions::{FunctionRegistry, JPathFunction};
@@ -102,4 +102,159 @@ mod tests {
         let result = evaluate(&expr, &e_ctx).unwrap();
         assert_eq!(result, data);
     }
+
+    #[test]
+    fn test_variable_selection_with_path() {
+        let expr = parse_expression("$root.meta.tags[1]").unwrap();
+        let data = json!("current value");
+        let vars = HashMap::from([(
+            "root".to_string(),
+            json!({ "meta": { "tags": ["a", "b"] } }),
+        )]);
+        let funcs = FunctionRegistry::default();
+        let e_ctx = EvaluationContext {
+            context_node: &data,
+            variables: &vars,
+            functions: &funcs,
+            loop_position: None,
+        };
+        let result = evaluate(&expr, &e_ctx).unwrap();
+        assert_eq!(result, json!("b"));
+    }
+
+    fn eval_with_data(expression: &str, data: &serde_json::Value) -> serde_json::Value {
+        let expr = parse_expression(expression).unwrap();
+        let vars = HashMap::new();
+        let funcs = FunctionRegistry::default();
+        let e_ctx = EvaluationContext {
+            context_node: data,
+            variables: &vars,
+            functions: &funcs,
+            loop_position: None,
+        };
+        evaluate(&expr, &e_ctx).unwrap()
+    }
+
+    #[test]
+    fn test_aggregates_over_wildcard_path() {
+        let data = json!({ "orders": [ { "amount": 10 }, { "amount": 25.5 }, { "amount": 4.5 } ] });
+        assert_eq!(
+            eval_with_data("orders[*].amount", &data),
+            json!([10, 25.5, 4.5])
+        );
+        assert_eq!(eval_with_data("sum(orders[*].amount)", &data), json!(40));
+        assert_eq!(eval_with_data("min(orders[*].amount)", &data), json!(4.5));
+        assert_eq!(eval_with_data("max(orders[*].amount)", &data), json!(25.5));
+        assert_eq!(eval_with_data("count(orders[*].amount)", &data), json!(3.0));
+    }
+
+    #[test]
+    fn test_aggregates_with_key_expression() {
+        let data = json!({ "orders": [
+            { "line": { "amount": 10 } },
+            { "line": { "amount": 20 } },
+            { "line": {} }
+        ] });
+        assert_eq!(
+            eval_with_data("avg(orders, 'line.amount')", &data),
+            json!(15)
+        );
+        assert_eq!(
+            eval_with_data("sum(orders, 'line.amount')", &data),
+            json!(30)
+        );
+        assert_eq!(
+            eval_with_data("count(orders, 'line.amount')", &data),
+            json!(2.0)
+        );
+    }
+
+    #[test]
+    fn test_aggregates_over_empty_array() {
+        let data = json!({ "orders": [] });
+        assert_eq!(eval_with_data("sum(orders[*].amount)", &data), json!(0));
+        assert_eq!(eval_with_data("avg(orders, 'amount')", &data), json!(null));
+        assert_eq!(eval_with_data("min(orders[*].amount)", &data), json!(null));
+        assert_eq!(eval_with_data("max(orders[*].amount)", &data), json!(null));
+        assert_eq!(eval_with_data("count(orders)", &data), json!(0.0));
+    }
+
+    #[test]
+    fn test_recursive_descent_selects_keys_at_any_depth() {
+        let data = json!({
+            "name": "root",
+            "sections": [
+                { "name": "intro", "items": [ { "name": "a" } ] },
+                { "title": "untitled", "items": [ { "name": "b" }, { "id": 3 } ] }
+            ]
+        });
+        let names = |expression| {
+            let mut names: Vec<String> = eval_with_data(expression, &data)
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|name| name.as_str().unwrap().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names("..name"), ["a", "b", "intro", "root"]);
+        assert_eq!(names("sections..name"), ["a", "b", "intro"]);
+        assert_eq!(eval_with_data("count(..missing)", &data), json!(0.0));
+    }
+
+    #[test]
+    fn test_wildcard_selects_every_element() {
+        let data = json!({ "items": [ { "id": 1 }, { "id": 2 }, { "name": "no id" } ] });
+        assert_eq!(eval_with_data("items[*].id", &data), json!([1, 2]));
+        assert_eq!(eval_with_data("items[*]", &data), data["items"]);
+        assert_eq!(eval_with_data("items[*].id[0]", &data), json!([]));
+    }
+
+    #[test]
+    fn test_filter_by_string_equality() {
+        let data = json!({ "orders": [
+            { "id": "A", "status": "paid", "amount": 10 },
+            { "id": "B", "status": "open", "amount": 20 },
+            { "id": "C", "status": "paid", "amount": 30 }
+        ] });
+        assert_eq!(
+            eval_with_data("orders[status == 'paid'].id", &data),
+            json!(["A", "C"])
+        );
+        assert_eq!(
+            eval_with_data("orders[status != 'paid']", &data),
+            json!([data["orders"][1]])
+        );
+        assert_eq!(
+            eval_with_data("sum(orders[status == 'paid'].amount)", &data),
+            json!(40)
+        );
+        assert_eq!(
+            eval_with_data("orders[status == 'refunded']", &data),
+            json!([])
+        );
+    }
+
+    #[test]
+    fn test_filter_by_numeric_comparison() {
+        let data = json!({ "orders": [
+            { "id": "A", "amount": 10 },
+            { "id": "B", "amount": 20.5 },
+            { "id": "C" }
+        ] });
+        assert_eq!(
+            eval_with_data("orders[amount > 10].id", &data),
+            json!(["B"])
+        );
+        assert_eq!(
+            eval_with_data("orders[amount <= 20.5].id", &data),
+            json!(["A", "B"])
+        );
+        assert_eq!(eval_with_data("orders[amount >= 100].id", &data), json!([]));
+        assert_eq!(
+            eval_with_data("count(orders[amount < 0])", &data),
+            json!(0.0)
+        );
+    }
 }
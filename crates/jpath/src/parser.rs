@@ -1,12 +1,12 @@
 //! A `nom`-based parser for the JPath expression language.
-use super::ast::{Expression, PathSegment, Selection};
+use super::ast::{ComparisonOp, Expression, PathSegment, Selection};
 use crate::error::JPathError;
 use nom::{
     IResult, Parser,
     branch::alt,
     bytes::complete::{is_not, tag, take_while},
     character::complete::{alpha1, char, multispace0, u64 as nom_u64},
-    combinator::{map, recognize},
+    combinator::{map, opt, recognize},
     multi::{many0, separated_list0},
     number::complete::double,
     sequence::{delimited, pair, preceded},
@@ -29,6 +29,21 @@ pub fn parse_expression(input: &str) -> Result<Expression, JPathError> {
 // --- Combinators ---
 
 fn expression(input: &str) -> IResult<&str, Expression> {
+    map(
+        pair(operand, opt(pair(ws(comparison_op), operand))),
+        |(left, comparison)| match comparison {
+            Some((op, right)) => Expression::Comparison {
+                left: Box::new(left),
+                op,
+                right: Box::new(right),
+            },
+            None => left,
+        },
+    )
+    .parse(input)
+}
+
+fn operand(input: &str) -> IResult<&str, Expression> {
     ws(alt((
         map(literal, Expression::Literal),
         function_call, // Must be before selection to parse `func()` not `func`
@@ -37,6 +52,18 @@ fn expression(input: &str) -> IResult<&str, Expression> {
     .parse(input)
 }
 
+fn comparison_op(input: &str) -> IResult<&str, ComparisonOp> {
+    alt((
+        map(tag("=="), |_| ComparisonOp::Eq),
+        map(tag("!="), |_| ComparisonOp::NotEq),
+        map(tag("<="), |_| ComparisonOp::LtEq), // Must be before `<`
+        map(tag(">="), |_| ComparisonOp::GtEq),
+        map(tag("<"), |_| ComparisonOp::Lt),
+        map(tag(">"), |_| ComparisonOp::Gt),
+    ))
+    .parse(input)
+}
+
 // --- Literal Parsers ---
 
 fn boolean(input: &str) -> IResult<&str, Value> {
@@ -90,8 +117,33 @@ fn index_segment(input: &str) -> IResult<&str, PathSegment> {
     .parse(input)
 }
 
+fn wildcard_segment(input: &str) -> IResult<&str, PathSegment> {
+    map(tag("[*]"), |_| PathSegment::Wildcard).parse(input)
+}
+
+fn descendant_segment(input: &str) -> IResult<&str, PathSegment> {
+    map(preceded(tag(".."), identifier), |s| {
+        PathSegment::Descendant(s.to_string())
+    })
+    .parse(input)
+}
+
+fn filter_segment(input: &str) -> IResult<&str, PathSegment> {
+    map(delimited(char('['), expression, char(']')), |predicate| {
+        PathSegment::Filter(Box::new(predicate))
+    })
+    .parse(input)
+}
+
 fn path_segment(input: &str) -> IResult<&str, PathSegment> {
-    alt((key_segment, index_segment)).parse(input)
+    alt((
+        descendant_segment,
+        key_segment,
+        index_segment,
+        wildcard_segment,
+        filter_segment, // Must be after the other bracketed segments
+    ))
+    .parse(input)
 }
 
 fn full_path(input: &str) -> IResult<&str, Selection> {
@@ -106,12 +158,27 @@ fn full_path(input: &str) -> IResult<&str, Selection> {
     .parse(input)
 }
 
+/// A path starting with recursive descent from the current context (`..name`).
+fn descendant_path(input: &str) -> IResult<&str, Selection> {
+    map(
+        pair(descendant_segment, many0(path_segment)),
+        |(start, mut rest)| {
+            let mut segments = vec![start];
+            segments.append(&mut rest);
+            Selection::Path(segments)
+        },
+    )
+    .parse(input)
+}
+
 fn selection(input: &str) -> IResult<&str, Selection> {
     alt((
+        descendant_path, // Must be before `.` to parse `..name` not `.`
         map(tag("."), |_| Selection::CurrentContext),
-        map(preceded(char('$'), identifier), |name| {
-            Selection::Variable(name.to_string())
-        }),
+        map(
+            preceded(char('$'), pair(identifier, many0(path_segment))),
+            |(name, segments)| Selection::Variable(name.to_string(), segments),
+        ),
         full_path,
     ))
     .parse(input)
@@ -5,6 +5,7 @@
 
 use petty_style::dimension::Dimension;
 use petty_style::stylesheet::{ElementStyle, PageLayout};
+use petty_types::{CaptionKind, CaptionPosition};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 // --- Template Structure ---
@@ -60,6 +61,11 @@ pub enum JsonNode {
     IndexMarker {
         term: String,
     },
+    TextField(JsonTextField),
+    Checkbox(JsonCheckbox),
+    Caption(JsonCaption),
+    BarChart(JsonBarChart),
+    PieChart(JsonPieChart),
     // Inline-level variants
     Text {
         content: String,
@@ -71,6 +77,7 @@ pub enum JsonNode {
         target_id: String,
     },
     InlineImage(JsonImage),
+    Sparkline(JsonSparkline),
     LineBreak,
     // New control-flow nodes
     PageBreak {
@@ -130,6 +137,125 @@ pub struct JsonImage {
     pub style_override: ElementStyle,
 }
 
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonTextField {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The form field name, unique within the document.
+    pub name: String,
+    /// The default value, which may contain `{{ }}` expressions.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub value: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub style_names: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub style_override: ElementStyle,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonCheckbox {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The form field name, unique within the document.
+    pub name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub checked: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub style_names: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub style_override: ElementStyle,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonCaption {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Whether a `table` or a `figure` is captioned, which selects the numbering sequence.
+    pub kind: CaptionKind,
+    /// Defaults to above for tables and below for figures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<CaptionPosition>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub style_names: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub style_override: ElementStyle,
+    /// The inline caption text.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TemplateNode>,
+    /// The captioned table or image.
+    pub content: Box<TemplateNode>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonBarChart {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub bars: Vec<JsonChartEntry>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub style_names: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub style_override: ElementStyle,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonPieChart {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub slices: Vec<JsonChartEntry>,
+    /// The radius of the hole as a fraction of the pie's radius, for a donut chart.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_radius: Option<f32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub style_names: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub style_override: ElementStyle,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct JsonChartEntry {
+    /// The bar or legend label, which may contain `{{ }}` expressions.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub label: String,
+    /// A number, or a string whose `{{ }}` expressions evaluate to one.
+    pub value: serde_json::Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonSparkline {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// An array of numbers, or a path (like `each`) selecting one from the data.
+    pub values: serde_json::Value,
+    pub width: f32,
+    pub height: f32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub style_names: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub style_override: ElementStyle,
+}
+
 #[derive(Deserialize, Serialize, Debug, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct JsonInlineContainer {
@@ -180,6 +306,9 @@ pub struct JsonTable {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub header: Option<JsonTableHeader>,
     pub body: JsonTableBody,
+    /// Rows repeated at the bottom of every page the table occupies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footer: Option<JsonTableHeader>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -248,11 +377,23 @@ pub struct StylesheetDef {
     #[serde(skip_serializing_if = "HashMap::is_empty")]
     #[serde(deserialize_with = "crate::style_deser::deserialize_styles")]
     pub styles: HashMap<String, ElementStyle>,
+    /// Selector-based rules, applied in order of specificity and then source order.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<StyleRuleDef>,
     #[serde(default)]
     #[serde(skip_serializing_if = "HashMap::is_empty")]
     pub definitions: HashMap<String, TemplateNode>,
 }
 
+/// A style applied to every node matching a CSS-like selector, e.g. `.note paragraph`.
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct StyleRuleDef {
+    pub selector: String,
+    #[serde(deserialize_with = "crate::style_deser::deserialize_style")]
+    pub style: ElementStyle,
+}
+
 /// Helper for serde to skip serializing default empty values for cleaner JSON.
 fn is_default<T: Default + PartialEq>(t: &T) -> bool {
     *t == T::default()
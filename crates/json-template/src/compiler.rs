@@ -4,7 +4,7 @@
 use super::ast::{self, ControlNode, JsonNode, TemplateNode};
 use crate::error::JsonTemplateError;
 use itertools::Itertools;
-use petty_idf::TableColumnDefinition;
+use petty_idf::{CaptionKind, CaptionPosition, TableColumnDefinition};
 use petty_jpath::{self, Expression};
 use petty_style::stylesheet::{ElementStyle, Stylesheet};
 use std::collections::HashMap;
@@ -59,6 +59,30 @@ pub fn parse_expression_string(text: &str) -> Result<CompiledString, JsonTemplat
     Ok(CompiledString::Dynamic(parts))
 }
 
+/// Compiles chart labels and values, where each value is a number or an expression string.
+fn compile_chart_entries(
+    entries: &[ast::JsonChartEntry],
+) -> Result<Vec<CompiledChartEntry>, JsonTemplateError> {
+    entries
+        .iter()
+        .map(|entry| {
+            Ok(CompiledChartEntry {
+                label: parse_expression_string(&entry.label)?,
+                value: match &entry.value {
+                    serde_json::Value::Number(n) => CompiledString::Static(n.to_string()),
+                    serde_json::Value::String(s) => parse_expression_string(s)?,
+                    other => {
+                        return Err(JsonTemplateError::Compilation(format!(
+                            "Chart value must be a number or a string, got {}",
+                            other
+                        )));
+                    }
+                },
+            })
+        })
+        .collect()
+}
+
 // --- Executable Instruction Set ---
 
 /// A pre-compiled, executable instruction. This is the output of the `Compiler`.
@@ -88,6 +112,32 @@ pub enum JsonInstruction {
         styles: CompiledStyles,
         src: CompiledString,
     },
+    TextField {
+        styles: CompiledStyles,
+        name: CompiledString,
+        value: CompiledString,
+    },
+    Checkbox {
+        styles: CompiledStyles,
+        name: CompiledString,
+        checked: bool,
+    },
+    Caption {
+        styles: CompiledStyles,
+        kind: CaptionKind,
+        position: CaptionPosition,
+        children: Vec<JsonInstruction>,
+        content: Vec<JsonInstruction>,
+    },
+    BarChart {
+        styles: CompiledStyles,
+        bars: Vec<CompiledChartEntry>,
+    },
+    PieChart {
+        styles: CompiledStyles,
+        slices: Vec<CompiledChartEntry>,
+        inner_radius: f32,
+    },
     Table(CompiledTable),
     Text {
         content: CompiledString,
@@ -108,6 +158,12 @@ pub enum JsonInstruction {
         styles: CompiledStyles,
         src: CompiledString,
     },
+    Sparkline {
+        styles: CompiledStyles,
+        values: SparklineValues,
+        width: f32,
+        height: f32,
+    },
     LineBreak,
     PageBreak {
         master_name: Option<String>,
@@ -140,17 +196,33 @@ pub enum JsonInstruction {
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct CompiledStyles {
     pub id: Option<String>,
+    /// The static style names, which double as the node's selector classes.
+    pub classes: Vec<String>,
     pub static_styles: Vec<Arc<ElementStyle>>,
     pub dynamic_style_templates: Vec<CompiledString>,
     pub style_override: Option<ElementStyle>,
 }
 
+/// The series of a sparkline, given in the template or selected from the data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SparklineValues {
+    Static(Vec<f64>),
+    Dynamic(Expression),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledChartEntry {
+    pub label: CompiledString,
+    pub value: CompiledString,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct CompiledTable {
     pub styles: CompiledStyles,
     pub columns: Vec<TableColumnDefinition>,
     pub header: Option<Vec<JsonInstruction>>,
     pub body: Vec<JsonInstruction>,
+    pub footer: Option<Vec<JsonInstruction>>,
 }
 
 /// A stateful compiler that transforms a Serde-parsed JSON AST into an executable instruction set.
@@ -240,6 +312,32 @@ impl<'a> Compiler<'a> {
                 styles: self.compile_styles(&i.style_names, &i.style_override, i.id.clone())?,
                 src: parse_expression_string(&i.src)?,
             }),
+            JsonNode::TextField(f) => Ok(JsonInstruction::TextField {
+                styles: self.compile_styles(&f.style_names, &f.style_override, f.id.clone())?,
+                name: parse_expression_string(&f.name)?,
+                value: parse_expression_string(&f.value)?,
+            }),
+            JsonNode::Checkbox(f) => Ok(JsonInstruction::Checkbox {
+                styles: self.compile_styles(&f.style_names, &f.style_override, f.id.clone())?,
+                name: parse_expression_string(&f.name)?,
+                checked: f.checked,
+            }),
+            JsonNode::Caption(c) => Ok(JsonInstruction::Caption {
+                styles: self.compile_styles(&c.style_names, &c.style_override, c.id.clone())?,
+                kind: c.kind,
+                position: c.position.unwrap_or(c.kind.default_position()),
+                children: self.compile_children(&c.children)?,
+                content: self.compile_node(&c.content)?,
+            }),
+            JsonNode::BarChart(c) => Ok(JsonInstruction::BarChart {
+                styles: self.compile_styles(&c.style_names, &c.style_override, c.id.clone())?,
+                bars: compile_chart_entries(&c.bars)?,
+            }),
+            JsonNode::PieChart(c) => Ok(JsonInstruction::PieChart {
+                styles: self.compile_styles(&c.style_names, &c.style_override, c.id.clone())?,
+                slices: compile_chart_entries(&c.slices)?,
+                inner_radius: c.inner_radius.unwrap_or(0.0),
+            }),
             JsonNode::Table(t) => self.compile_table_node(t),
             JsonNode::Heading(h) => Ok(JsonInstruction::Heading {
                 level: h.level,
@@ -271,6 +369,35 @@ impl<'a> Compiler<'a> {
                 styles: self.compile_styles(&i.style_names, &i.style_override, i.id.clone())?,
                 src: parse_expression_string(&i.src)?,
             }),
+            JsonNode::Sparkline(s) => Ok(JsonInstruction::Sparkline {
+                styles: self.compile_styles(&s.style_names, &s.style_override, s.id.clone())?,
+                values: match &s.values {
+                    serde_json::Value::String(path) => {
+                        SparklineValues::Dynamic(petty_jpath::parse_expression(path)?)
+                    }
+                    serde_json::Value::Array(items) => SparklineValues::Static(
+                        items
+                            .iter()
+                            .map(|v| {
+                                v.as_f64().ok_or_else(|| {
+                                    JsonTemplateError::Compilation(format!(
+                                        "Sparkline value must be a number, got {}",
+                                        v
+                                    ))
+                                })
+                            })
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    other => {
+                        return Err(JsonTemplateError::Compilation(format!(
+                            "Sparkline values must be an array or a path, got {}",
+                            other
+                        )));
+                    }
+                },
+                width: s.width,
+                height: s.height,
+            }),
             JsonNode::LineBreak => Ok(JsonInstruction::LineBreak),
             JsonNode::PageBreak { master_name } => Ok(JsonInstruction::PageBreak {
                 master_name: master_name.clone(),
@@ -328,6 +455,11 @@ impl<'a> Compiler<'a> {
                 .map(|h| self.compile_children(&h.rows))
                 .transpose()?,
             body: self.compile_children(&table.body.rows)?,
+            footer: table
+                .footer
+                .as_ref()
+                .map(|f| self.compile_children(&f.rows))
+                .transpose()?,
         }))
     }
 
@@ -348,6 +480,7 @@ impl<'a> Compiler<'a> {
         style_override: &ElementStyle,
         id: Option<String>,
     ) -> Result<CompiledStyles, JsonTemplateError> {
+        let mut classes = Vec::new();
         let mut static_styles = Vec::new();
         let mut dynamic_style_templates = Vec::new();
         for name_str in names {
@@ -355,18 +488,24 @@ impl<'a> Compiler<'a> {
                 dynamic_style_templates.push(parse_expression_string(name_str)?);
             } else {
                 for name in name_str.split_whitespace().filter(|s| !s.is_empty()) {
-                    let style = self.stylesheet.styles.get(name).cloned().ok_or_else(|| {
-                        JsonTemplateError::TemplateParse(format!(
-                            "Style '{}' not found in stylesheet",
-                            name
-                        ))
-                    })?;
-                    static_styles.push(style);
+                    match self.stylesheet.styles.get(name) {
+                        Some(style) => static_styles.push(style.clone()),
+                        // A name used only by selector rules is a plain class.
+                        None if self.stylesheet.uses_class(name) => {}
+                        None => {
+                            return Err(JsonTemplateError::TemplateParse(format!(
+                                "Style '{}' not found in stylesheet",
+                                name
+                            )));
+                        }
+                    }
+                    classes.push(name.to_string());
                 }
             }
         }
         Ok(CompiledStyles {
             id,
+            classes,
             static_styles,
             dynamic_style_templates,
             style_override: if *style_override == ElementStyle::default() {
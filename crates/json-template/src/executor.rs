@@ -3,11 +3,13 @@
 //! It walks the compiled instruction set and generates the `IRNode` tree.
 
 use super::compiler::{
-    CompiledString, CompiledStyles, CompiledTable, ExpressionPart, JsonInstruction,
+    CompiledChartEntry, CompiledString, CompiledStyles, CompiledTable, ExpressionPart,
+    JsonInstruction, SparklineValues,
 };
 use crate::error::JsonTemplateError;
 use petty_idf::{
-    IRNode, InlineMetadata, InlineNode, NodeMetadata, TableBody, TableCell, TableHeader, TableRow,
+    ChartEntry, FormFieldKind, IRNode, InlineMetadata, InlineNode, NodeMetadata, TableBody,
+    TableCell, TableFooter, TableHeader, TableRow,
 };
 use petty_jpath::{self, engine, functions::FunctionRegistry};
 use petty_style::stylesheet::{ElementStyle, Stylesheet};
@@ -20,9 +22,13 @@ pub struct TemplateExecutor<'s, 'd> {
     stylesheet: &'s Stylesheet,
     definitions: &'d HashMap<String, Vec<JsonInstruction>>,
     functions: FunctionRegistry,
-    empty_vars: HashMap<String, Value>,
+    /// Variables visible to every expression; `$root` is the data the tree is built
+    /// from, so templates can reach document-level data from inside an `each`.
+    variables: HashMap<String, Value>,
     node_stack: Vec<IRNode>,
     inline_stack: Vec<InlineNode>,
+    step_limit: Option<u64>,
+    steps: u64,
 }
 
 impl<'s, 'd> TemplateExecutor<'s, 'd> {
@@ -34,12 +40,20 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
             stylesheet,
             definitions,
             functions: FunctionRegistry::default(),
-            empty_vars: HashMap::new(),
+            variables: HashMap::new(),
             node_stack: vec![],
             inline_stack: vec![],
+            step_limit: None,
+            steps: 0,
         }
     }
 
+    /// Abort with an error once more than `limit` instructions have run.
+    pub fn with_step_limit(mut self, limit: Option<u64>) -> Self {
+        self.step_limit = limit;
+        self
+    }
+
     pub fn build_tree(
         &mut self,
         instructions: &[JsonInstruction],
@@ -47,6 +61,7 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
     ) -> Result<Vec<IRNode>, JsonTemplateError> {
         self.node_stack.clear();
         self.inline_stack.clear();
+        self.variables.insert("root".to_string(), context.clone());
         self.node_stack.push(IRNode::Root(Vec::with_capacity(16)));
         self.execute_instructions(instructions, context, None)?;
         if let Some(IRNode::Root(children)) = self.node_stack.pop() {
@@ -70,6 +85,20 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
         Ok(())
     }
 
+    /// Runs `build` on a fresh executor that shares this executor's variables, for
+    /// building a subtree in isolation while keeping the current stacks intact.
+    fn with_sub_executor<T>(
+        &mut self,
+        build: impl FnOnce(&mut TemplateExecutor<'s, 'd>) -> Result<T, JsonTemplateError>,
+    ) -> Result<T, JsonTemplateError> {
+        let mut sub_executor = TemplateExecutor::new(self.stylesheet, self.definitions);
+        // Lent rather than cloned, as `$root` holds the whole data source.
+        sub_executor.variables = std::mem::take(&mut self.variables);
+        let result = build(&mut sub_executor);
+        self.variables = sub_executor.variables;
+        result
+    }
+
     fn get_eval_context<'a>(
         &'a self,
         context_node: &'a Value,
@@ -77,7 +106,7 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
     ) -> engine::EvaluationContext<'a> {
         engine::EvaluationContext {
             context_node,
-            variables: &self.empty_vars, // JSON parser does not currently use variables
+            variables: &self.variables,
             functions: &self.functions,
             loop_position,
         }
@@ -89,6 +118,16 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
         context: &Value,
         loop_pos: Option<usize>,
     ) -> Result<(), JsonTemplateError> {
+        self.steps += 1;
+        if let Some(limit) = self.step_limit
+            && self.steps > limit
+        {
+            return Err(JsonTemplateError::Execution(format!(
+                "Step limit of {} instructions exceeded",
+                limit
+            )));
+        }
+
         match instruction {
             JsonInstruction::ForEach { select, body } => {
                 let e_ctx = self.get_eval_context(context, loop_pos);
@@ -210,6 +249,81 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
                 src: self.render_string(src, context, loop_pos)?,
                 meta: self.build_node_meta(styles, context, loop_pos)?,
             }),
+            JsonInstruction::TextField {
+                styles,
+                name,
+                value,
+            } => self.push_block_to_parent(IRNode::FormField {
+                meta: self.build_node_meta(styles, context, loop_pos)?,
+                name: self.render_string(name, context, loop_pos)?,
+                kind: FormFieldKind::Text {
+                    value: self.render_string(value, context, loop_pos)?,
+                },
+            }),
+            JsonInstruction::Checkbox {
+                styles,
+                name,
+                checked,
+            } => self.push_block_to_parent(IRNode::FormField {
+                meta: self.build_node_meta(styles, context, loop_pos)?,
+                name: self.render_string(name, context, loop_pos)?,
+                kind: FormFieldKind::Checkbox { checked: *checked },
+            }),
+            JsonInstruction::BarChart { styles, bars } => {
+                let bars = self.render_chart_entries(bars, context, loop_pos)?;
+                self.push_block_to_parent(IRNode::BarChart {
+                    meta: self.build_node_meta(styles, context, loop_pos)?,
+                    bars,
+                });
+            }
+            JsonInstruction::PieChart {
+                styles,
+                slices,
+                inner_radius,
+            } => {
+                let slices = self.render_chart_entries(slices, context, loop_pos)?;
+                self.push_block_to_parent(IRNode::PieChart {
+                    meta: self.build_node_meta(styles, context, loop_pos)?,
+                    slices,
+                    inner_radius: *inner_radius,
+                });
+            }
+            JsonInstruction::Caption {
+                styles,
+                kind,
+                position,
+                children,
+                content,
+            } => {
+                // The content is built in its own frame, then attached to the caption.
+                self.node_stack.push(IRNode::Root(vec![]));
+                self.execute_instructions(content, context, loop_pos)?;
+                let mut content_nodes = match self.node_stack.pop() {
+                    Some(IRNode::Root(nodes)) => nodes,
+                    _ => Vec::new(),
+                };
+                let content = if content_nodes.len() == 1 {
+                    content_nodes.remove(0)
+                } else {
+                    IRNode::Block {
+                        meta: NodeMetadata::default(),
+                        children: content_nodes,
+                    }
+                };
+
+                self.node_stack.push(IRNode::Caption {
+                    meta: self.build_node_meta(styles, context, loop_pos)?,
+                    kind: *kind,
+                    position: *position,
+                    number: None,
+                    children: vec![],
+                    content: Box::new(content),
+                });
+                self.execute_instructions(children, context, loop_pos)?;
+                if let Some(completed_caption) = self.node_stack.pop() {
+                    self.push_block_to_parent(completed_caption);
+                }
+            }
             JsonInstruction::Table(table) => self.execute_table(table, context, loop_pos)?,
             JsonInstruction::Text { content } => self.push_inline_to_parent(InlineNode::Text(
                 self.render_string(content, context, loop_pos)?,
@@ -221,6 +335,42 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
                     meta: self.build_inline_meta(styles, context, loop_pos)?,
                 })
             }
+            JsonInstruction::Sparkline {
+                styles,
+                values,
+                width,
+                height,
+            } => {
+                let values = match values {
+                    SparklineValues::Static(values) => values.clone(),
+                    SparklineValues::Dynamic(select) => {
+                        let e_ctx = self.get_eval_context(context, loop_pos);
+                        let result_val = engine::evaluate(select, &e_ctx)?;
+                        let items = result_val.as_array().ok_or_else(|| {
+                            JsonTemplateError::TemplateRender(
+                                "Sparkline values did not resolve to an array".to_string(),
+                            )
+                        })?;
+                        items
+                            .iter()
+                            .map(|v| {
+                                v.as_f64().ok_or_else(|| {
+                                    JsonTemplateError::TemplateRender(format!(
+                                        "Sparkline value '{}' is not a number",
+                                        v
+                                    ))
+                                })
+                            })
+                            .collect::<Result<_, _>>()?
+                    }
+                };
+                self.push_inline_to_parent(InlineNode::Sparkline {
+                    meta: self.build_inline_meta(styles, context, loop_pos)?,
+                    values,
+                    width: *width,
+                    height: *height,
+                })
+            }
             JsonInstruction::StyledSpan { styles, children } => {
                 self.inline_stack.push(InlineNode::StyledSpan {
                     meta: self.build_inline_meta(styles, context, loop_pos)?,
@@ -263,10 +413,12 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
         context: &Value,
         loop_pos: Option<usize>,
     ) -> Result<NodeMetadata, JsonTemplateError> {
+        let (style_sets, classes) = self.gather_styles(styles, context, loop_pos)?;
         Ok(NodeMetadata {
             id: styles.id.clone(),
-            style_sets: self.gather_styles(styles, context, loop_pos)?,
+            style_sets,
             style_override: styles.style_override.clone(),
+            classes,
         })
     }
 
@@ -277,7 +429,7 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
         loop_pos: Option<usize>,
     ) -> Result<InlineMetadata, JsonTemplateError> {
         Ok(InlineMetadata {
-            style_sets: self.gather_styles(styles, context, loop_pos)?,
+            style_sets: self.gather_styles(styles, context, loop_pos)?.0,
             style_override: styles.style_override.clone(),
         })
     }
@@ -287,25 +439,29 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
         styles: &CompiledStyles,
         context: &Value,
         loop_pos: Option<usize>,
-    ) -> Result<Vec<Arc<ElementStyle>>, JsonTemplateError> {
+    ) -> Result<(Vec<Arc<ElementStyle>>, Vec<String>), JsonTemplateError> {
         let mut resolved_styles = styles.static_styles.clone();
+        let mut classes = styles.classes.clone();
         for name_template in &styles.dynamic_style_templates {
             for name in self
                 .render_string(name_template, context, loop_pos)?
                 .split_whitespace()
                 .filter(|s| !s.is_empty())
             {
-                resolved_styles.push(self.stylesheet.styles.get(name).cloned().ok_or_else(
-                    || {
-                        JsonTemplateError::TemplateParse(format!(
+                match self.stylesheet.styles.get(name) {
+                    Some(style) => resolved_styles.push(style.clone()),
+                    None if self.stylesheet.uses_class(name) => {}
+                    None => {
+                        return Err(JsonTemplateError::TemplateParse(format!(
                             "Style '{}' (rendered) not found",
                             name
-                        ))
-                    },
-                )?);
+                        )));
+                    }
+                }
+                classes.push(name.to_string());
             }
         }
-        Ok(resolved_styles)
+        Ok((resolved_styles, classes))
     }
 
     fn execute_container(
@@ -315,19 +471,18 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
         context: &Value,
         loop_pos: Option<usize>,
     ) -> Result<(), JsonTemplateError> {
-        // Create a new executor to build a sub-tree in isolation, preserving the current stack.
-        let mut sub_executor = TemplateExecutor::new(self.stylesheet, self.definitions);
-
         // Manually set up the sub-executor and call execute_instructions to pass loop_pos.
-        sub_executor.node_stack.push(IRNode::Root(Vec::new()));
-        sub_executor.execute_instructions(children, context, loop_pos)?;
-        let child_nodes = if let Some(IRNode::Root(nodes)) = sub_executor.node_stack.pop() {
-            nodes
-        } else {
-            return Err(JsonTemplateError::TemplateParse(
-                "Failed to build sub-tree for container.".to_string(),
-            ));
-        };
+        let child_nodes = self.with_sub_executor(|sub_executor| {
+            sub_executor.node_stack.push(IRNode::Root(Vec::new()));
+            sub_executor.execute_instructions(children, context, loop_pos)?;
+            if let Some(IRNode::Root(nodes)) = sub_executor.node_stack.pop() {
+                Ok(nodes)
+            } else {
+                Err(JsonTemplateError::TemplateParse(
+                    "Failed to build sub-tree for container.".to_string(),
+                ))
+            }
+        })?;
 
         match &mut node {
             IRNode::Block { children: c, .. }
@@ -347,34 +502,73 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
         loop_pos: Option<usize>,
     ) -> Result<(), JsonTemplateError> {
         let header = if let Some(instructions) = &table.header {
-            let mut sub_executor = TemplateExecutor::new(self.stylesheet, self.definitions);
             Some(Box::new(TableHeader {
-                rows: sub_executor
-                    .build_tree(instructions, context)?
-                    .into_iter()
-                    .map(ir_node_to_table_row)
-                    .collect::<Result<_, _>>()?,
+                rows: self.build_table_rows(instructions, context)?,
             }))
         } else {
             None
         };
-        let mut sub_executor = TemplateExecutor::new(self.stylesheet, self.definitions);
         let body = Box::new(TableBody {
-            rows: sub_executor
-                .build_tree(&table.body, context)?
-                .into_iter()
-                .map(ir_node_to_table_row)
-                .collect::<Result<_, _>>()?,
+            rows: self.build_table_rows(&table.body, context)?,
         });
+        let footer = if let Some(instructions) = &table.footer {
+            Some(Box::new(TableFooter {
+                rows: self.build_table_rows(instructions, context)?,
+            }))
+        } else {
+            None
+        };
         self.push_block_to_parent(IRNode::Table {
             meta: self.build_node_meta(&table.styles, context, loop_pos)?,
             columns: table.columns.clone(),
             header,
             body,
+            footer,
         });
         Ok(())
     }
 
+    fn build_table_rows(
+        &mut self,
+        instructions: &[JsonInstruction],
+        context: &Value,
+    ) -> Result<Vec<TableRow>, JsonTemplateError> {
+        self.with_sub_executor(|sub_executor| {
+            sub_executor.node_stack.push(IRNode::Root(Vec::new()));
+            sub_executor.execute_instructions(instructions, context, None)?;
+            match sub_executor.node_stack.pop() {
+                Some(IRNode::Root(nodes)) => nodes.into_iter().map(ir_node_to_table_row).collect(),
+                _ => Err(JsonTemplateError::TemplateParse(
+                    "Failed to build sub-tree for table rows.".to_string(),
+                )),
+            }
+        })
+    }
+
+    /// Renders chart labels and values, failing on values that are not numbers.
+    fn render_chart_entries(
+        &self,
+        entries: &[CompiledChartEntry],
+        context: &Value,
+        loop_pos: Option<usize>,
+    ) -> Result<Vec<ChartEntry>, JsonTemplateError> {
+        entries
+            .iter()
+            .map(|entry| {
+                let value = self.render_string(&entry.value, context, loop_pos)?;
+                Ok(ChartEntry {
+                    label: self.render_string(&entry.label, context, loop_pos)?,
+                    value: value.trim().parse().map_err(|_| {
+                        JsonTemplateError::TemplateRender(format!(
+                            "Chart value '{}' is not a number",
+                            value
+                        ))
+                    })?,
+                })
+            })
+            .collect()
+    }
+
     fn render_string(
         &self,
         compiled_str: &CompiledString,
@@ -423,8 +617,11 @@ impl<'s, 'd> TemplateExecutor<'s, 'd> {
             c.push(node);
             return;
         }
-        if let Some(IRNode::Paragraph { children: c, .. } | IRNode::Heading { children: c, .. }) =
-            self.node_stack.last_mut()
+        if let Some(
+            IRNode::Paragraph { children: c, .. }
+            | IRNode::Heading { children: c, .. }
+            | IRNode::Caption { children: c, .. },
+        ) = self.node_stack.last_mut()
         {
             c.push(node);
             return;
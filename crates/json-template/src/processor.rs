@@ -2,7 +2,8 @@
 use crate::ast::JsonTemplateFile;
 use crate::compiler::{Compiler, JsonInstruction};
 use crate::executor::TemplateExecutor;
-use petty_idf::IRNode;
+use petty_idf::{IRNode, apply_style_rules};
+use petty_style::selector::{Selector, StyleRule};
 use petty_style::stylesheet::Stylesheet;
 use petty_template_core::{
     CompiledTemplate, ExecutionConfig, TemplateError, TemplateFeatures, TemplateFlags,
@@ -28,15 +29,18 @@ impl petty_template_core::TemplateExecutor for CompiledJsonTemplate {
     fn execute(
         &self,
         data_source: &str,
-        _config: ExecutionConfig,
+        config: ExecutionConfig,
     ) -> Result<Vec<IRNode>, TemplateError> {
         let data: serde_json::Value = serde_json::from_str(data_source)
             .map_err(|e| TemplateError::ParseError(format!("JSON parse error: {}", e)))?;
 
-        let mut executor = TemplateExecutor::new(&self.stylesheet, &self.definitions);
-        executor
+        let mut executor = TemplateExecutor::new(&self.stylesheet, &self.definitions)
+            .with_step_limit(config.max_steps);
+        let mut nodes = executor
             .build_tree(&self.instructions, &data)
-            .map_err(|e: crate::error::JsonTemplateError| -> TemplateError { e.into() })
+            .map_err(|e: crate::error::JsonTemplateError| -> TemplateError { e.into() })?;
+        apply_style_rules(&mut nodes, &self.stylesheet);
+        Ok(nodes)
     }
 }
 
@@ -60,80 +64,194 @@ impl TemplateParser for JsonParser {
         template_source: &str,
         resource_base_path: PathBuf,
     ) -> Result<TemplateFeatures, TemplateError> {
-        let template_file: JsonTemplateFile = serde_json::from_str(template_source)
-            .map_err(|e| TemplateError::ParseError(format!("JSON parse error: {}", e)))?;
+        compile_template(template_source, resource_base_path, None)
+    }
+
+    fn parse_with_theme(
+        &self,
+        template_source: &str,
+        resource_base_path: PathBuf,
+        theme: &Stylesheet,
+    ) -> Result<TemplateFeatures, TemplateError> {
+        compile_template(template_source, resource_base_path, Some(theme))
+    }
+}
 
-        // Build stylesheet from the template file
-        let mut stylesheet = Stylesheet::default();
+fn compile_template(
+    template_source: &str,
+    resource_base_path: PathBuf,
+    theme: Option<&Stylesheet>,
+) -> Result<TemplateFeatures, TemplateError> {
+    let template_file: JsonTemplateFile = serde_json::from_str(template_source)
+        .map_err(|e| TemplateError::ParseError(format!("JSON parse error: {}", e)))?;
 
-        // Set default page master
-        if let Some(default_master) = &template_file._stylesheet.default_page_master {
-            stylesheet.default_page_master_name = Some(default_master.clone());
-        }
+    // Build stylesheet from the template file
+    let mut stylesheet = Stylesheet::default();
 
-        // Add page masters
-        for (name, layout) in template_file._stylesheet.page_masters {
-            stylesheet.page_masters.insert(name, layout);
-        }
+    // Set default page master
+    if let Some(default_master) = &template_file._stylesheet.default_page_master {
+        stylesheet.default_page_master_name = Some(default_master.clone());
+    }
 
-        // Add styles
-        for (name, style) in template_file._stylesheet.styles {
-            stylesheet.styles.insert(name, Arc::new(style));
-        }
+    // Add page masters
+    for (name, layout) in template_file._stylesheet.page_masters {
+        stylesheet.page_masters.insert(name, layout);
+    }
 
-        let stylesheet = Arc::new(stylesheet);
-
-        // Extract definitions from stylesheet
-        let mut definitions = HashMap::new();
-        let empty_defs = HashMap::new();
-        for (name, template_node) in template_file._stylesheet.definitions {
-            let compiler = Compiler::new(&stylesheet, &empty_defs);
-            let instructions = compiler
-                .compile(&template_node)
-                .map_err(|e: crate::error::JsonTemplateError| -> TemplateError { e.into() })?;
-            definitions.insert(name, instructions);
-        }
+    // Add styles
+    for (name, style) in template_file._stylesheet.styles {
+        stylesheet.styles.insert(name, Arc::new(style));
+    }
 
-        // Compile the main template into executable instructions
-        let compiler = Compiler::new(&stylesheet, &definitions);
+    // Add selector rules
+    for rule in template_file._stylesheet.rules {
+        let selector = Selector::parse(&rule.selector).map_err(|e| {
+            TemplateError::ParseError(format!("Invalid selector '{}': {}", rule.selector, e))
+        })?;
+        stylesheet.rules.push(StyleRule {
+            selector,
+            style: Arc::new(rule.style),
+        });
+    }
+
+    if let Some(theme) = theme {
+        stylesheet.apply_theme(theme);
+    }
+
+    let template_value: serde_json::Value = serde_json::from_str(template_source)
+        .map_err(|e| TemplateError::ParseError(format!("JSON parse error: {}", e)))?;
+    check_style_references(&template_value, &stylesheet)?;
+
+    let stylesheet = Arc::new(stylesheet);
+
+    // Extract definitions from stylesheet
+    let mut definitions = HashMap::new();
+    let empty_defs = HashMap::new();
+    for (name, template_node) in template_file._stylesheet.definitions {
+        let compiler = Compiler::new(&stylesheet, &empty_defs);
         let instructions = compiler
-            .compile(&template_file._template)
+            .compile(&template_node)
             .map_err(|e: crate::error::JsonTemplateError| -> TemplateError { e.into() })?;
+        definitions.insert(name, instructions);
+    }
 
-        // Detect features from the compiled instructions
-        let features = detect_features(&instructions);
-
-        // Compile role templates
-        let mut role_templates = HashMap::new();
-        for (role_name, role_template_node) in template_file._roles {
-            let role_compiler = Compiler::new(&stylesheet, &definitions);
-            let role_instructions = role_compiler
-                .compile(&role_template_node)
-                .map_err(|e: crate::error::JsonTemplateError| -> TemplateError { e.into() })?;
-
-            role_templates.insert(
-                role_name,
-                Arc::new(CompiledJsonTemplate {
-                    instructions: role_instructions,
-                    definitions: definitions.clone(),
-                    stylesheet: Arc::clone(&stylesheet),
-                    features,
-                    resource_base_path: resource_base_path.clone(),
-                }) as Arc<dyn CompiledTemplate>,
-            );
-        }
+    // Compile the main template into executable instructions
+    let compiler = Compiler::new(&stylesheet, &definitions);
+    let instructions = compiler
+        .compile(&template_file._template)
+        .map_err(|e: crate::error::JsonTemplateError| -> TemplateError { e.into() })?;
+
+    // Detect features from the compiled instructions
+    let features = detect_features(&instructions);
 
-        Ok(TemplateFeatures {
-            main_template: Arc::new(CompiledJsonTemplate {
-                instructions,
-                definitions,
-                stylesheet,
+    // Compile role templates
+    let mut role_templates = HashMap::new();
+    for (role_name, role_template_node) in template_file._roles {
+        let role_compiler = Compiler::new(&stylesheet, &definitions);
+        let role_instructions = role_compiler
+            .compile(&role_template_node)
+            .map_err(|e: crate::error::JsonTemplateError| -> TemplateError { e.into() })?;
+
+        role_templates.insert(
+            role_name,
+            Arc::new(CompiledJsonTemplate {
+                instructions: role_instructions,
+                definitions: definitions.clone(),
+                stylesheet: Arc::clone(&stylesheet),
                 features,
-                resource_base_path,
-            }),
-            role_templates,
-        })
+                resource_base_path: resource_base_path.clone(),
+            }) as Arc<dyn CompiledTemplate>,
+        );
     }
+
+    Ok(TemplateFeatures {
+        main_template: Arc::new(CompiledJsonTemplate {
+            instructions,
+            definitions,
+            stylesheet,
+            features,
+            resource_base_path,
+        }),
+        role_templates,
+    })
+}
+
+/// Checks every static `styleNames` entry in the template, its roles and its
+/// definitions against the stylesheet, reporting all undefined names at once
+/// together with the JSON pointer of the node that references them.
+fn check_style_references(
+    template: &serde_json::Value,
+    stylesheet: &Stylesheet,
+) -> Result<(), TemplateError> {
+    let mut missing = Vec::new();
+    if let Some(node) = template.get("_template") {
+        collect_missing_styles(node, "/_template".to_string(), stylesheet, &mut missing);
+    }
+    let sections = [
+        ("/_roles", template.get("_roles")),
+        (
+            "/_stylesheet/definitions",
+            template
+                .get("_stylesheet")
+                .and_then(|s| s.get("definitions")),
+        ),
+    ];
+    for (prefix, section) in sections {
+        if let Some(serde_json::Value::Object(nodes)) = section {
+            for (name, node) in nodes {
+                let path = format!("{}/{}", prefix, escape_pointer_token(name));
+                collect_missing_styles(node, path, stylesheet, &mut missing);
+            }
+        }
+    }
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(TemplateError::ParseError(missing.join("; ")))
+    }
+}
+
+fn collect_missing_styles(
+    value: &serde_json::Value,
+    path: String,
+    stylesheet: &Stylesheet,
+    missing: &mut Vec<String>,
+) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            if let Some(serde_json::Value::Array(names)) = fields.get("styleNames") {
+                for name in names
+                    .iter()
+                    .filter_map(|n| n.as_str())
+                    .filter(|n| !n.contains("{{"))
+                    .flat_map(str::split_whitespace)
+                {
+                    if !stylesheet.styles.contains_key(name) && !stylesheet.uses_class(name) {
+                        missing.push(format!(
+                            "Style '{}' not found in stylesheet (referenced at {})",
+                            name, path
+                        ));
+                    }
+                }
+            }
+            for (key, child) in fields {
+                let child_path = format!("{}/{}", path, escape_pointer_token(key));
+                collect_missing_styles(child, child_path, stylesheet, missing);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_missing_styles(item, format!("{}/{}", path, i), stylesheet, missing);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Escapes a key for use in a JSON pointer (RFC 6901).
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
 }
 
 /// Detect template features by scanning the compiled instructions
@@ -175,6 +293,15 @@ fn scan_instructions_for_features(instructions: &[JsonInstruction], flags: &mut
                     scan_instructions_for_features(header, flags);
                 }
                 scan_instructions_for_features(&table.body, flags);
+                if let Some(footer) = &table.footer {
+                    scan_instructions_for_features(footer, flags);
+                }
+            }
+            JsonInstruction::Caption {
+                children, content, ..
+            } => {
+                scan_instructions_for_features(children, flags);
+                scan_instructions_for_features(content, flags);
             }
             JsonInstruction::ForEach { body, .. } => {
                 scan_instructions_for_features(body, flags);
@@ -191,3 +318,207 @@ fn scan_instructions_for_features(instructions: &[JsonInstruction], flags: &mut
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petty_idf::{CaptionKind, CaptionPosition, InlineNode};
+    use petty_types::Color;
+    use serde_json::json;
+
+    #[test]
+    fn test_selector_rules_style_nodes_without_style_names() {
+        let template = json!({
+            "_stylesheet": {
+                "rules": [
+                    { "selector": "paragraph", "style": { "fontSize": 11 } },
+                    { "selector": ".note paragraph", "style": { "color": "#ff0000" } }
+                ]
+            },
+            "_template": { "type": "Block", "children": [
+                { "type": "Paragraph", "children": [ { "type": "Text", "content": "Plain" } ] },
+                { "type": "Block", "styleNames": ["note"], "children": [
+                    { "type": "Paragraph", "children": [ { "type": "Text", "content": "Noted" } ] }
+                ] }
+            ] }
+        });
+        let features = JsonParser
+            .parse(&template.to_string(), PathBuf::new())
+            .unwrap();
+        let config = ExecutionConfig {
+            format: petty_template_core::DataSourceFormat::Json,
+            strict: false,
+            ..Default::default()
+        };
+        let nodes = features.main_template.execute("{}", config).unwrap();
+
+        let IRNode::Block { children, .. } = &nodes[0] else {
+            panic!("expected a block");
+        };
+        let IRNode::Block {
+            meta: note_meta,
+            children: note_children,
+        } = &children[1]
+        else {
+            panic!("expected the note block");
+        };
+        assert_eq!(note_meta.classes, vec!["note".to_string()]);
+
+        let plain = children[0].style_sets();
+        assert_eq!(plain.len(), 1);
+        assert_eq!(plain[0].font_size, Some(11.0));
+
+        // The more specific descendant rule comes last, so it takes precedence.
+        let noted = note_children[0].style_sets();
+        assert_eq!(noted.len(), 2);
+        assert_eq!(noted[0].font_size, Some(11.0));
+        assert_eq!(
+            noted[1].color,
+            Some(Color {
+                r: 255,
+                g: 0,
+                b: 0,
+                a: 1.0
+            })
+        );
+    }
+
+    #[test]
+    fn test_undefined_style_names_are_reported_with_node_path() {
+        let template = json!({
+            "_stylesheet": {
+                "styles": { "body": { "fontSize": 10 } },
+                "definitions": {
+                    "footer": { "type": "Paragraph", "styleNames": ["small"], "children": [] }
+                }
+            },
+            "_template": { "type": "Block", "styleNames": ["body"], "children": [
+                { "type": "Paragraph", "children": [ { "type": "Text", "content": "Ok" } ] },
+                { "type": "Paragraph", "styleNames": ["body callout", "{{kind}}"], "children": [] }
+            ] }
+        });
+        let Err(err) = JsonParser.parse(&template.to_string(), PathBuf::new()) else {
+            panic!("undefined styles should fail to compile");
+        };
+        let err = err.to_string();
+
+        assert!(
+            err.contains(
+                "Style 'callout' not found in stylesheet (referenced at /_template/children/1)"
+            ),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("Style 'small' not found in stylesheet (referenced at /_stylesheet/definitions/footer)"),
+            "{}",
+            err
+        );
+        assert!(!err.contains("'body'"), "{}", err);
+        assert!(!err.contains("kind"), "{}", err);
+    }
+
+    #[test]
+    fn test_caption_wraps_its_content() {
+        let template = json!({
+            "_stylesheet": {},
+            "_template": { "type": "Block", "children": [
+                { "type": "Caption", "kind": "table", "id": "sales",
+                  "children": [ { "type": "Text", "content": "Sales by {{region}}" } ],
+                  "content": { "type": "Table", "columns": [ {} ], "body": { "rows": [] } } },
+                { "type": "Caption", "kind": "figure", "position": "above",
+                  "children": [ { "type": "Text", "content": "Logo" } ],
+                  "content": { "type": "Image", "src": "logo.png" } }
+            ] }
+        });
+        let features = JsonParser
+            .parse(&template.to_string(), PathBuf::new())
+            .unwrap();
+        let config = ExecutionConfig {
+            format: petty_template_core::DataSourceFormat::Json,
+            strict: false,
+            ..Default::default()
+        };
+        let nodes = features
+            .main_template
+            .execute(r#"{"region": "North"}"#, config)
+            .unwrap();
+
+        let IRNode::Block { children, .. } = &nodes[0] else {
+            panic!("expected a block");
+        };
+        let IRNode::Caption {
+            meta,
+            kind,
+            position,
+            children: text,
+            content,
+            ..
+        } = &children[0]
+        else {
+            panic!("expected a caption");
+        };
+        assert_eq!(meta.id.as_deref(), Some("sales"));
+        assert_eq!(*kind, CaptionKind::Table);
+        assert_eq!(*position, CaptionPosition::Above);
+        assert_eq!(text, &vec![InlineNode::Text("Sales by North".to_string())]);
+        assert!(matches!(**content, IRNode::Table { .. }));
+
+        let IRNode::Caption {
+            kind,
+            position,
+            content,
+            ..
+        } = &children[1]
+        else {
+            panic!("expected a caption");
+        };
+        assert_eq!(*kind, CaptionKind::Figure);
+        assert_eq!(*position, CaptionPosition::Above);
+        assert!(matches!(**content, IRNode::Image { .. }));
+    }
+
+    #[test]
+    fn test_root_variable_is_visible_inside_each() {
+        let template = json!({
+            "_stylesheet": {},
+            "_template": { "type": "Block", "children": [
+                { "each": "records", "template": {
+                    "type": "Paragraph",
+                    "children": [ { "type": "Text", "content": "{{$root.title}}: {{name}}" } ]
+                } }
+            ] }
+        });
+        let features = JsonParser
+            .parse(&template.to_string(), PathBuf::new())
+            .unwrap();
+        let config = ExecutionConfig {
+            format: petty_template_core::DataSourceFormat::Json,
+            strict: false,
+            ..Default::default()
+        };
+        let data = json!({ "title": "Q3 Report", "records": [ { "name": "North" }, { "name": "South" } ] });
+        let nodes = features
+            .main_template
+            .execute(&data.to_string(), config)
+            .unwrap();
+
+        let IRNode::Block { children, .. } = &nodes[0] else {
+            panic!("expected a block");
+        };
+        let texts: Vec<_> = children
+            .iter()
+            .map(|child| match child {
+                IRNode::Paragraph { children, .. } => children.clone(),
+                other => panic!("expected a paragraph, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                vec![InlineNode::Text("Q3 Report: North".to_string())],
+                vec![InlineNode::Text("Q3 Report: South".to_string())],
+            ]
+        );
+    }
+}
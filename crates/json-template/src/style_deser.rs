@@ -6,7 +6,7 @@
 //! - Kebab-case or camelCase field names
 
 use petty_style::dimension::Margins;
-use petty_style::stylesheet::ElementStyle;
+use petty_style::stylesheet::{ElementStyle, StyleKeyword};
 use petty_style::{Border, BorderStyle, FontWeight, TextAlign};
 use petty_types::Color;
 use serde::{Deserialize, Deserializer};
@@ -35,6 +35,22 @@ fn parse_dimension(value: &Value) -> Option<f32> {
     }
 }
 
+/// Converts a camelCase or snake_case property name to kebab-case.
+fn kebab_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('-');
+            out.push(c.to_ascii_lowercase());
+        } else if c == '_' {
+            out.push('-');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
 /// Deserialize a HashMap<String, ElementStyle> with flexible value parsing
 pub fn deserialize_styles<'de, D>(
     deserializer: D,
@@ -55,6 +71,15 @@ where
     Ok(styles)
 }
 
+/// Deserialize a single ElementStyle with flexible value parsing
+pub fn deserialize_style<'de, D>(deserializer: D) -> Result<ElementStyle, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    parse_element_style(&value).map_err(serde::de::Error::custom)
+}
+
 /// Parse a single ElementStyle from a JSON value with flexible field parsing
 fn parse_element_style(value: &Value) -> Result<ElementStyle, String> {
     let obj = value
@@ -64,147 +89,172 @@ fn parse_element_style(value: &Value) -> Result<ElementStyle, String> {
     let mut style = ElementStyle::default();
 
     for (key, val) in obj {
-        // Handle both camelCase and kebab-case
-        let normalized_key = key.replace("-", "_").to_lowercase();
-
-        match normalized_key.as_str() {
-            "font_family" | "fontfamily" => {
-                if let Some(s) = val.as_str() {
-                    style.font_family = Some(s.to_string());
-                }
+        match val
+            .as_str()
+            .and_then(|s| s.trim_end().strip_suffix("!important"))
+        {
+            Some(value) => {
+                apply_property(&mut style, key, &Value::String(value.trim().to_string()));
+                style.mark_important(&kebab_case(key));
             }
-            "font_size" | "fontsize" => {
-                style.font_size = parse_dimension(val);
-            }
-            "font_weight" | "fontweight" => {
-                if let Some(s) = val.as_str() {
-                    style.font_weight = match s.to_lowercase().as_str() {
-                        "bold" => Some(FontWeight::Bold),
-                        "normal" | "regular" => Some(FontWeight::Regular),
-                        "light" => Some(FontWeight::Light),
-                        "thin" => Some(FontWeight::Thin),
-                        "medium" => Some(FontWeight::Medium),
-                        "black" => Some(FontWeight::Black),
-                        _ => None,
-                    };
-                }
+            None => apply_property(&mut style, key, val),
+        }
+    }
+
+    Ok(style)
+}
+
+/// Applies a single JSON style property to `style`.
+fn apply_property(style: &mut ElementStyle, key: &str, val: &Value) {
+    if let Some(keyword) = val.as_str().and_then(StyleKeyword::parse)
+        && style.set_keyword(&kebab_case(key), keyword)
+    {
+        return;
+    }
+
+    // Handle both camelCase and kebab-case
+    let normalized_key = key.replace("-", "_").to_lowercase();
+
+    match normalized_key.as_str() {
+        "font_family" | "fontfamily" => {
+            if let Some(s) = val.as_str() {
+                style.font_family = Some(s.to_string());
             }
-            "line_height" | "lineheight" => {
-                style.line_height = parse_dimension(val);
+        }
+        "font_size" | "fontsize" => {
+            style.font_size = parse_dimension(val);
+        }
+        "font_weight" | "fontweight" => {
+            if let Some(s) = val.as_str() {
+                style.font_weight = match s.to_lowercase().as_str() {
+                    "bold" => Some(FontWeight::Bold),
+                    "normal" | "regular" => Some(FontWeight::Regular),
+                    "light" => Some(FontWeight::Light),
+                    "thin" => Some(FontWeight::Thin),
+                    "medium" => Some(FontWeight::Medium),
+                    "black" => Some(FontWeight::Black),
+                    _ => None,
+                };
             }
-            "color" => {
-                if let Some(s) = val.as_str()
-                    && let Ok(color) = parse_color(s)
-                {
-                    style.color = Some(color);
-                }
+        }
+        "font_source" | "fontsource" => {
+            style.font_source = val
+                .as_str()
+                .and_then(|s| petty_style::parsers::parse_font_source(s).ok());
+        }
+        "line_height" | "lineheight" => {
+            style.line_height = parse_dimension(val);
+        }
+        "color" => {
+            if let Some(s) = val.as_str()
+                && let Ok(color) = parse_color(s)
+            {
+                style.color = Some(color);
             }
-            "text_align" | "textalign" => {
-                if let Some(s) = val.as_str() {
-                    style.text_align = match s.to_lowercase().as_str() {
-                        "left" => Some(TextAlign::Left),
-                        "right" => Some(TextAlign::Right),
-                        "center" => Some(TextAlign::Center),
-                        "justify" => Some(TextAlign::Justify),
-                        _ => None,
-                    };
-                }
+        }
+        "text_align" | "textalign" => {
+            if let Some(s) = val.as_str() {
+                style.text_align = match s.to_lowercase().as_str() {
+                    "left" => Some(TextAlign::Left),
+                    "right" => Some(TextAlign::Right),
+                    "center" => Some(TextAlign::Center),
+                    "justify" => Some(TextAlign::Justify),
+                    _ => None,
+                };
             }
-            "margin_top" | "margintop" => {
-                if let Some(val) = parse_dimension(val) {
-                    let margins = style.margin.get_or_insert(Margins::default());
-                    margins.top = val;
-                }
+        }
+        "margin_top" | "margintop" => {
+            if let Some(val) = parse_dimension(val) {
+                let margins = style.margin.get_or_insert(Margins::default());
+                margins.top = val;
             }
-            "margin_bottom" | "marginbottom" => {
-                if let Some(val) = parse_dimension(val) {
-                    let margins = style.margin.get_or_insert(Margins::default());
-                    margins.bottom = val;
-                }
+        }
+        "margin_bottom" | "marginbottom" => {
+            if let Some(val) = parse_dimension(val) {
+                let margins = style.margin.get_or_insert(Margins::default());
+                margins.bottom = val;
             }
-            "margin_left" | "marginleft" => {
-                if let Some(val) = parse_dimension(val) {
-                    let margins = style.margin.get_or_insert(Margins::default());
-                    margins.left = val;
-                }
+        }
+        "margin_left" | "marginleft" => {
+            if let Some(val) = parse_dimension(val) {
+                let margins = style.margin.get_or_insert(Margins::default());
+                margins.left = val;
             }
-            "margin_right" | "marginright" => {
-                if let Some(val) = parse_dimension(val) {
-                    let margins = style.margin.get_or_insert(Margins::default());
-                    margins.right = val;
-                }
+        }
+        "margin_right" | "marginright" => {
+            if let Some(val) = parse_dimension(val) {
+                let margins = style.margin.get_or_insert(Margins::default());
+                margins.right = val;
             }
-            "padding_top" | "paddingtop" => {
-                if let Some(val) = parse_dimension(val) {
-                    let padding = style.padding.get_or_insert(Margins::default());
-                    padding.top = val;
-                }
+        }
+        "padding_top" | "paddingtop" => {
+            if let Some(val) = parse_dimension(val) {
+                let padding = style.padding.get_or_insert(Margins::default());
+                padding.top = val;
             }
-            "padding_bottom" | "paddingbottom" => {
-                if let Some(val) = parse_dimension(val) {
-                    let padding = style.padding.get_or_insert(Margins::default());
-                    padding.bottom = val;
-                }
+        }
+        "padding_bottom" | "paddingbottom" => {
+            if let Some(val) = parse_dimension(val) {
+                let padding = style.padding.get_or_insert(Margins::default());
+                padding.bottom = val;
             }
-            "padding_left" | "paddingleft" => {
-                if let Some(val) = parse_dimension(val) {
-                    let padding = style.padding.get_or_insert(Margins::default());
-                    padding.left = val;
-                }
+        }
+        "padding_left" | "paddingleft" => {
+            if let Some(val) = parse_dimension(val) {
+                let padding = style.padding.get_or_insert(Margins::default());
+                padding.left = val;
             }
-            "padding_right" | "paddingright" => {
-                if let Some(val) = parse_dimension(val) {
-                    let padding = style.padding.get_or_insert(Margins::default());
-                    padding.right = val;
-                }
+        }
+        "padding_right" | "paddingright" => {
+            if let Some(val) = parse_dimension(val) {
+                let padding = style.padding.get_or_insert(Margins::default());
+                padding.right = val;
             }
-            "padding" => {
-                // Parse "4pt 5pt" style padding or single value
-                if let Some(padding_val) = parse_dimension(val) {
-                    style.padding = Some(Margins {
-                        top: padding_val,
-                        right: padding_val,
-                        bottom: padding_val,
-                        left: padding_val,
-                    });
-                }
+        }
+        "padding" => {
+            // Parse "4pt 5pt" style padding or single value
+            if let Some(padding_val) = parse_dimension(val) {
+                style.padding = Some(Margins {
+                    top: padding_val,
+                    right: padding_val,
+                    bottom: padding_val,
+                    left: padding_val,
+                });
             }
-            "background_color" | "backgroundcolor" => {
-                if let Some(s) = val.as_str()
-                    && let Ok(color) = parse_color(s)
-                {
-                    style.background_color = Some(color);
-                }
+        }
+        "background_color" | "backgroundcolor" => {
+            if let Some(s) = val.as_str()
+                && let Ok(color) = parse_color(s)
+            {
+                style.background_color = Some(color);
             }
-            "border_top" | "bordertop" | "border" => {
-                // Parse "1pt solid #cccccc" style borders
-                if let Some(s) = val.as_str()
-                    && let Ok(border) = parse_border(s)
-                {
-                    if normalized_key.contains("top") || normalized_key == "border" {
-                        style.border_top = Some(border.clone());
-                    }
-                    if normalized_key == "border" {
-                        style.border_bottom = Some(border.clone());
-                        style.border_left = Some(border.clone());
-                        style.border_right = Some(border);
-                    }
+        }
+        "border_top" | "bordertop" | "border" => {
+            // Parse "1pt solid #cccccc" style borders
+            if let Some(s) = val.as_str()
+                && let Ok(border) = parse_border(s)
+            {
+                if normalized_key.contains("top") || normalized_key == "border" {
+                    style.border_top = Some(border.clone());
                 }
-            }
-            "border_bottom" | "borderbottom" => {
-                if let Some(s) = val.as_str()
-                    && let Ok(border) = parse_border(s)
-                {
-                    style.border_bottom = Some(border);
+                if normalized_key == "border" {
+                    style.border_bottom = Some(border.clone());
+                    style.border_left = Some(border.clone());
+                    style.border_right = Some(border);
                 }
             }
-            _ => {
-                // Ignore unknown fields for forward compatibility
+        }
+        "border_bottom" | "borderbottom" => {
+            if let Some(s) = val.as_str()
+                && let Ok(border) = parse_border(s)
+            {
+                style.border_bottom = Some(border);
             }
         }
+        _ => {
+            // Ignore unknown fields for forward compatibility
+        }
     }
-
-    Ok(style)
 }
 
 /// Parse a color string like "#2a4d69" or "red"
@@ -321,6 +371,23 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_parse_style_keywords() {
+        let style = parse_element_style(&json!({
+            "color": "inherit",
+            "backgroundColor": "initial",
+            "font_size": 12
+        }))
+        .unwrap();
+        assert_eq!(style.color, None);
+        assert_eq!(style.keywords.get("color"), Some(&StyleKeyword::Inherit));
+        assert_eq!(
+            style.keywords.get("background-color"),
+            Some(&StyleKeyword::Initial)
+        );
+        assert_eq!(style.font_size, Some(12.0));
+    }
+
     #[test]
     fn test_parse_dimension_from_number() {
         let val = json!(24);
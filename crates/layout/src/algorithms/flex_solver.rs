@@ -15,9 +15,14 @@ pub fn computed_style_to_taffy(style: &ComputedStyle) -> taffy::style::Style {
             width: taffy::style::Dimension::auto(),
             height: to_taffy_dimension(&Some(style.box_model.min_height.clone())),
         },
+        aspect_ratio: style.box_model.aspect_ratio,
         margin: to_taffy_margin(&style.box_model.margin),
         padding: to_taffy_padding(&style.box_model.padding),
         border: to_taffy_border(&style.border),
+        gap: taffy::geometry::Size {
+            width: LengthPercentage::length(style.box_model.column_gap),
+            height: LengthPercentage::length(style.box_model.row_gap),
+        },
         align_items: to_taffy_align_items(style.flex.align_items.clone()),
         align_self: to_taffy_align_self(style.flex.align_self.clone()),
         justify_content: to_taffy_justify_content(style.flex.justify_content.clone()),
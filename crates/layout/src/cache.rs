@@ -34,7 +34,6 @@ pub struct LayoutCache {
     pub fonts: RwLock<HashMap<FontCacheKey, Option<FontData>>>,
     pub shaping: RwLock<HashMap<ShapingCacheKey, Arc<Vec<ShapedRun>>>>,
     pub multi_span: RwLock<HashMap<MultiSpanCacheKey, Arc<Vec<ShapedRun>>>>,
-    pub measurements: RwLock<HashMap<(String, u64), f32>>,
 }
 
 impl Default for LayoutCache {
@@ -43,7 +42,6 @@ impl Default for LayoutCache {
             fonts: RwLock::new(HashMap::new()),
             shaping: RwLock::new(HashMap::new()),
             multi_span: RwLock::new(HashMap::new()),
-            measurements: RwLock::new(HashMap::new()),
         }
     }
 }
@@ -63,9 +61,6 @@ impl LayoutCache {
         if let Ok(mut c) = self.multi_span.write() {
             c.clear();
         }
-        if let Ok(mut c) = self.measurements.write() {
-            c.clear();
-        }
     }
 }
 
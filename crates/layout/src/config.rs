@@ -1,4 +1,20 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub use crate::style::DEFAULT_FONT_FAMILY;
+
+/// What the layout engine does with an element that cannot fit on an empty page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OversizedPolicy {
+    /// Abort layout with `LayoutError::ElementTooLarge`.
+    #[default]
+    Error,
+    /// Place the element and discard whatever extends past the bottom of the page.
+    Clip,
+    /// Scale the element down uniformly until it fits in the remaining space.
+    Shrink,
+    /// Place the element as-is, letting it extend past the bottom of the page.
+    AllowOverflow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LayoutConfig {
     /// The maximum number of layout items (sequences) to process before the layout engine's
     /// internal caches (e.g., text shaping results) are pruned or reset.
@@ -10,12 +26,25 @@ pub struct LayoutConfig {
     ///
     /// Defaults to `10000`.
     pub cache_capacity: usize,
+
+    /// How to handle an unbreakable element that is taller than the page.
+    ///
+    /// Defaults to [`OversizedPolicy::Error`].
+    pub oversized_policy: OversizedPolicy,
+
+    /// The font family of text whose style does not set one. Text in a family
+    /// that cannot be resolved also falls back to this family's font.
+    ///
+    /// Defaults to [`DEFAULT_FONT_FAMILY`].
+    pub default_font_family: String,
 }
 
 impl Default for LayoutConfig {
     fn default() -> Self {
         Self {
             cache_capacity: 10000,
+            oversized_policy: OversizedPolicy::default(),
+            default_font_family: DEFAULT_FONT_FAMILY.to_string(),
         }
     }
 }
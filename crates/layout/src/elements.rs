@@ -1,6 +1,7 @@
 // src/core/layout/elements.rs
 
 use crate::style::ComputedStyle;
+use petty_idf::FormFieldKind;
 use petty_style::text::TextDecoration;
 use petty_types::geometry;
 use std::sync::Arc;
@@ -40,6 +41,8 @@ pub enum LayoutElement {
     Text(TextElement),
     Rectangle(RectElement),
     Image(ImageElement),
+    Path(PathElement),
+    FormField(FormFieldElement),
     PageNumberPlaceholder {
         target_id: String,
         href: Option<String>,
@@ -52,6 +55,8 @@ impl std::fmt::Display for LayoutElement {
             LayoutElement::Text(t) => write!(f, "Text(\"{}\")", t.content),
             LayoutElement::Rectangle(_) => write!(f, "Rectangle"),
             LayoutElement::Image(i) => write!(f, "Image(src=\"{}\")", i.src),
+            LayoutElement::Path(p) => write!(f, "Path({} segments)", p.segments.len()),
+            LayoutElement::FormField(field) => write!(f, "FormField(name=\"{}\")", field.name),
             LayoutElement::PageNumberPlaceholder { target_id, .. } => {
                 write!(f, "PageNumberPlaceholder(target=\"{}\")", target_id)
             }
@@ -74,8 +79,55 @@ pub struct TextElement {
 #[derive(Clone, Debug)]
 pub struct RectElement;
 
+/// A path drawn in the element's text color: stroked as a line, or filled when
+/// `filled` is set. Coordinates are fractions of the element's width and height,
+/// measured from its top-left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathElement {
+    pub segments: Vec<PathSegment>,
+    pub filled: bool,
+}
+
+impl PathElement {
+    /// A stroked line through `points`.
+    pub fn polyline(points: &[(f32, f32)]) -> Self {
+        let segments = points
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| {
+                if i == 0 {
+                    PathSegment::MoveTo(p)
+                } else {
+                    PathSegment::LineTo(p)
+                }
+            })
+            .collect();
+        Self {
+            segments,
+            filled: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathSegment {
+    MoveTo((f32, f32)),
+    LineTo((f32, f32)),
+    /// A cubic Bézier curve: two control points, then the end point.
+    CurveTo([(f32, f32); 3]),
+    /// Closes the current subpath with a line back to its start.
+    Close,
+}
+
 /// Represents an image to be drawn.
 #[derive(Clone, Debug)]
 pub struct ImageElement {
     pub src: String,
 }
+
+/// Represents an interactive form field widget.
+#[derive(Clone, Debug)]
+pub struct FormFieldElement {
+    pub name: String,
+    pub kind: FormFieldKind,
+}
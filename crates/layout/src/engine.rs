@@ -25,8 +25,10 @@ use crate::config::LayoutConfig;
 use crate::fonts::FontData;
 use crate::perf::{DebugProfiler, NoOpProfiler, Profiler};
 use crate::text::shaper::ShapedRun;
-use petty_idf::{IRNode, TextStr};
-use petty_style::font::{FontStyle, FontWeight};
+use crate::text::standard_fonts::StandardFont;
+use crate::warnings::LayoutWarning;
+use petty_idf::{IRNode, TextStr, table_rows};
+use petty_style::font::{FontSource, FontStyle, FontWeight};
 use petty_style::stylesheet::{ElementStyle, Stylesheet};
 use petty_types::geometry::{self as geom, BoxConstraints};
 
@@ -35,12 +37,10 @@ use bumpalo::Bump;
 use instant::Instant;
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
-use std::sync::Arc;
 #[cfg(feature = "system-fonts")]
 use std::sync::RwLock;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 pub struct LayoutStore {
@@ -94,13 +94,19 @@ pub struct PageOutput {
     pub anchors: HashMap<TextStr, AnchorLocation>,
     pub index_entries: HashMap<TextStr, Vec<IndexEntry>>,
     pub page_number: usize,
+    /// The name of the page master this page was laid out with.
+    pub master_name: TextStr,
 }
 
 pub struct LayoutEngine {
     font_library: SharedFontLibrary,
     cache: LayoutCache,
     profiler: Box<dyn Profiler>,
-    _config: LayoutConfig,
+    config: LayoutConfig,
+    /// The root style, carrying the configured default font family.
+    default_style: Arc<ComputedStyle>,
+    /// Warnings recorded since the last call to `take_warnings`.
+    warnings: Mutex<Vec<LayoutWarning>>,
 }
 
 impl LayoutEngine {
@@ -112,14 +118,28 @@ impl LayoutEngine {
             Box::new(NoOpProfiler)
         };
 
+        let default_style = if config.default_font_family == style::DEFAULT_FONT_FAMILY {
+            style::get_default_style()
+        } else {
+            let mut data = style::get_default_style().inner.clone();
+            data.text.font_family = Arc::new(config.default_font_family.clone());
+            Arc::new(ComputedStyle::new(data))
+        };
+
         Self {
             font_library: library.clone(),
             cache: LayoutCache::new(),
             profiler,
-            _config: config,
+            config,
+            default_style,
+            warnings: Mutex::new(Vec::new()),
         }
     }
 
+    pub fn config(&self) -> &LayoutConfig {
+        &self.config
+    }
+
     /// Exposes the underlying font database lock.
     ///
     /// # Deprecated
@@ -175,6 +195,25 @@ impl LayoutEngine {
         self.profiler.reset();
     }
 
+    /// Records a non-fatal layout issue. Repeats of an already recorded warning are
+    /// ignored, as nodes may be measured and laid out more than once.
+    pub fn report_warning(&self, warning: LayoutWarning) {
+        if let Ok(mut warnings) = self.warnings.lock()
+            && !warnings.contains(&warning)
+        {
+            warnings.push(warning);
+        }
+    }
+
+    /// Returns the warnings recorded since the last call, leaving none behind. Call
+    /// this after each sequence to attribute warnings to it.
+    pub fn take_warnings(&self) -> Vec<LayoutWarning> {
+        self.warnings
+            .lock()
+            .map(|mut warnings| std::mem::take(&mut *warnings))
+            .unwrap_or_default()
+    }
+
     pub fn compute_style(
         &self,
         style_sets: &[Arc<ElementStyle>],
@@ -185,7 +224,59 @@ impl LayoutEngine {
     }
 
     pub fn get_default_style(&self) -> Arc<ComputedStyle> {
-        style::get_default_style()
+        self.default_style.clone()
+    }
+
+    /// Resolves the fully cascaded and inherited style of the node with the given `id`,
+    /// mirroring how the render tree builders compute styles. Intended for debugging
+    /// styling issues; the result serializes to a readable structure.
+    pub fn computed_style_for_id(&self, ir_root: &IRNode, id: &str) -> Option<Arc<ComputedStyle>> {
+        self.find_computed_style(std::slice::from_ref(ir_root), id, &self.get_default_style())
+    }
+
+    fn find_computed_style(
+        &self,
+        nodes: &[IRNode],
+        id: &str,
+        parent_style: &Arc<ComputedStyle>,
+    ) -> Option<Arc<ComputedStyle>> {
+        nodes.iter().find_map(|node| {
+            let style = match node {
+                IRNode::Root(_) => self.get_default_style(),
+                IRNode::PageBreak { .. } => return None,
+                _ => self.compute_style(node.style_sets(), node.style_override(), parent_style),
+            };
+            if node.meta().and_then(|m| m.id.as_deref()) == Some(id) {
+                return Some(style);
+            }
+            match node {
+                IRNode::Root(children)
+                | IRNode::Block { children, .. }
+                | IRNode::FlexContainer { children, .. }
+                | IRNode::List { children, .. }
+                | IRNode::ListItem { children, .. } => {
+                    self.find_computed_style(children, id, &style)
+                }
+                IRNode::Caption { content, .. } => self.find_computed_style(
+                    std::slice::from_ref(content.as_ref()),
+                    id,
+                    parent_style,
+                ),
+                IRNode::Table { .. } => {
+                    table_rows(node)
+                        .flat_map(|row| row.cells.iter())
+                        .find_map(|cell| {
+                            let cell_style = self.compute_style(
+                                &cell.style_sets,
+                                cell.style_override.as_ref(),
+                                &style,
+                            );
+                            self.find_computed_style(&cell.children, id, &cell_style)
+                        })
+                }
+                _ => None,
+            }
+        })
     }
 
     pub fn build_render_tree<'a>(
@@ -272,43 +363,76 @@ impl LayoutEngine {
         font_data
     }
 
-    pub fn measure_text_width(&self, text: &str, style: &ComputedStyle) -> f32 {
-        let mut hasher = DefaultHasher::new();
-        style.hash(&mut hasher);
-        let style_hash = hasher.finish();
+    /// The font for text whose family cannot be resolved: the configured default
+    /// family's, or the built-in [`DEFAULT_FONT_FAMILY`](style::DEFAULT_FONT_FAMILY)
+    /// when that cannot be resolved either.
+    pub fn get_fallback_font(&self) -> Option<FontData> {
+        self.get_font_for_style(&self.default_style).or_else(|| {
+            (self.config.default_font_family != style::DEFAULT_FONT_FAMILY)
+                .then(|| self.get_font_for_style(&style::get_default_style()))
+                .flatten()
+        })
+    }
 
-        let key = (text.to_string(), style_hash);
+    /// The PDF standard font text in `style` is set in, when it asks for one with
+    /// `font-source: standard` and its family has one.
+    pub fn standard_font(style: &ComputedStyle) -> Option<StandardFont> {
+        (style.text.font_source == FontSource::Standard)
+            .then(|| StandardFont::for_style(&style.text))
+            .flatten()
+    }
 
-        if let Ok(cache) = self.cache.measurements.read()
-            && let Some(&width) = cache.get(&key)
-        {
-            return width;
+    /// Whether text in `style` is set in a font other than the requested family,
+    /// either a substituted face or the default style's font.
+    pub fn uses_fallback_font(&self, style: &ComputedStyle) -> bool {
+        if Self::standard_font(style).is_some() {
+            return false;
         }
+        self.get_font_for_style(style)
+            .is_none_or(|font_data| font_data.is_substitute)
+    }
 
-        let font_data = match self.get_font_for_style(style) {
+    /// Measures the advance width of `text` set in `style`. Results are kept in the
+    /// font library's measurement cache, so they are shared with other engines.
+    pub fn measure_text_width(&self, text: &str, style: &ComputedStyle) -> f32 {
+        if let Some(font) = Self::standard_font(style) {
+            return font.text_width(text, style.text.font_size);
+        }
+        let font_data = match self
+            .get_font_for_style(style)
+            .or_else(|| self.get_fallback_font())
+        {
             Some(d) => d,
             None => return 0.0,
         };
 
-        let face = match font_data.as_face() {
-            Some(f) => f,
-            None => return 0.0,
-        };
-
-        let mut buffer = rustybuzz::UnicodeBuffer::new();
-        buffer.push_str(text);
-        buffer.guess_segment_properties();
-
-        let glyph_buffer = rustybuzz::shape(&face, &[], buffer);
-        let positions = glyph_buffer.glyph_positions();
-        let scale = style.text.font_size / face.units_per_em() as f32;
-        let width: f32 = positions.iter().map(|p| p.x_advance as f32 * scale).sum();
-
-        if let Ok(mut cache) = self.cache.measurements.write() {
-            cache.insert(key, width);
-        }
+        let font_size = style.text.font_size;
+        self.font_library.measurement_cache().get_or_measure(
+            font_data.id(),
+            font_size,
+            text,
+            || {
+                let face = match font_data.as_face() {
+                    Some(f) => f,
+                    None => return 0.0,
+                };
+
+                let mut buffer = rustybuzz::UnicodeBuffer::new();
+                buffer.push_str(text);
+                buffer.guess_segment_properties();
+
+                let glyph_buffer = rustybuzz::shape(&face, &[], buffer);
+                let positions = glyph_buffer.glyph_positions();
+                let scale = font_size / face.units_per_em() as f32;
+                positions.iter().map(|p| p.x_advance as f32 * scale).sum()
+            },
+        )
+    }
 
-        width
+    /// The byte offsets in `word` where it may be hyphenated, using the font
+    /// library's dictionary for `language`. Empty when none is registered.
+    pub fn hyphenate(&self, word: &str, language: &str) -> Vec<usize> {
+        self.font_library.hyphenation().hyphenate(language, word)
     }
 
     // Helpers used by builders
@@ -432,6 +556,22 @@ struct PaginationIterator<'a> {
     finished: bool,
 }
 
+impl PaginationIterator<'_> {
+    /// Returns `name` if the stylesheet defines it, otherwise warns and falls back
+    /// to the default page master.
+    fn known_master_or_default(&self, name: String) -> String {
+        if self.stylesheet.page_masters.contains_key(&name) {
+            return name;
+        }
+        self.engine
+            .report_warning(LayoutWarning::UnknownPageMaster { name: name.clone() });
+        self.stylesheet
+            .default_page_master_name
+            .clone()
+            .unwrap_or(name)
+    }
+}
+
 impl<'a> Iterator for PaginationIterator<'a> {
     type Item = Result<PageOutput, LayoutError>;
 
@@ -455,13 +595,13 @@ impl<'a> Iterator for PaginationIterator<'a> {
             }
 
             let master_name = match &self.current_master_name {
-                Some(n) => n,
+                Some(n) => n.clone(),
                 None => {
                     self.finished = true;
                     return Some(Err(LayoutError::Generic("No page master".into())));
                 }
             };
-            let page_layout = match self.stylesheet.page_masters.get(master_name) {
+            let page_layout = match self.stylesheet.page_masters.get(&master_name) {
                 Some(l) => l,
                 None => {
                     self.finished = true;
@@ -481,10 +621,12 @@ impl<'a> Iterator for PaginationIterator<'a> {
             let mut elements = Vec::new();
             let mut anchors = HashMap::new();
             let mut indices = HashMap::new();
+            let mut next_master = None;
 
             let env = LayoutEnvironment {
                 engine: self.engine,
                 local_page_index: self.page_count - 1,
+                baseline_grid: page_layout.baseline_grid.filter(|grid| *grid > 0.0),
                 // Using the thread-local node_layouts cache
                 cache: &self.thread_cache.node_layouts,
             };
@@ -496,6 +638,7 @@ impl<'a> Iterator for PaginationIterator<'a> {
                 &mut elements,
                 &mut anchors,
                 &mut indices,
+                &mut next_master,
             );
             let constraints = BoxConstraints::tight_width(bounds.width);
 
@@ -511,11 +654,18 @@ impl<'a> Iterator for PaginationIterator<'a> {
                         anchors,
                         index_entries: indices,
                         page_number: self.page_count,
+                        master_name,
                     }))
                 }
                 Ok(LayoutResult::Break(next)) => {
-                    if let Some(Some(nm)) = self.root_node.check_for_page_break() {
-                        self.current_master_name = Some(nm.to_string());
+                    let requested = next_master.or_else(|| {
+                        self.root_node
+                            .check_for_page_break()
+                            .flatten()
+                            .map(|nm| nm.to_string())
+                    });
+                    if let Some(nm) = requested {
+                        self.current_master_name = Some(self.known_master_or_default(nm));
                     }
                     self.current_state = Some(next);
                     Some(Ok(PageOutput {
@@ -523,6 +673,7 @@ impl<'a> Iterator for PaginationIterator<'a> {
                         anchors,
                         index_entries: indices,
                         page_number: self.page_count,
+                        master_name,
                     }))
                 }
                 Err(e) => {
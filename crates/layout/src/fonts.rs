@@ -12,6 +12,8 @@
 //! For WASM targets, only provider mode is available.
 
 use crate::ComputedStyle;
+use crate::text::hyphenation::HyphenationRegistry;
+use crate::text::measure::TextMeasurementCache;
 use petty_style::font::{FontStyle, FontWeight};
 use petty_traits::{FontProvider, FontQuery, SharedFontData};
 use std::collections::HashMap;
@@ -23,6 +25,8 @@ use fontdb;
 /// A thread-safe handle to font data with rustybuzz Face creation.
 pub struct FontInstance {
     pub data: Arc<Vec<u8>>,
+    /// Whether this face was substituted because the requested family was not found.
+    pub is_substitute: bool,
 }
 
 impl std::fmt::Debug for FontInstance {
@@ -35,7 +39,16 @@ impl std::fmt::Debug for FontInstance {
 
 impl FontInstance {
     pub fn new(data: Arc<Vec<u8>>) -> Self {
-        Self { data }
+        Self {
+            data,
+            is_substitute: false,
+        }
+    }
+
+    /// An identifier for the loaded font, derived from the address of its data.
+    /// Fonts are never unloaded from a library, so it is stable for the library's lifetime.
+    pub fn id(&self) -> usize {
+        Arc::as_ptr(&self.data) as usize
     }
 
     /// Creates a lightweight Face view over the font data.
@@ -103,6 +116,12 @@ pub struct SharedFontLibrary {
 
     /// Registry of font face metadata for PDF embedding.
     font_registry: Arc<RwLock<Vec<FontFaceInfo>>>,
+
+    /// String advance widths, shared by every layout engine using this library.
+    measurement_cache: Arc<TextMeasurementCache>,
+
+    /// Hyphenation dictionaries, shared by every layout engine using this library.
+    hyphenation: Arc<HyphenationRegistry>,
 }
 
 impl SharedFontLibrary {
@@ -114,6 +133,8 @@ impl SharedFontLibrary {
             external_provider: None,
             font_data_cache: Arc::new(RwLock::new(HashMap::new())),
             font_registry: Arc::new(RwLock::new(Vec::new())),
+            measurement_cache: Arc::new(TextMeasurementCache::new()),
+            hyphenation: Arc::new(HyphenationRegistry::new()),
         }
     }
 
@@ -147,6 +168,29 @@ impl SharedFontLibrary {
         self
     }
 
+    /// Replaces the text measurement cache, for example to share one between libraries
+    /// or to bound it with [`TextMeasurementCache::with_capacity`].
+    pub fn with_measurement_cache(mut self, cache: Arc<TextMeasurementCache>) -> Self {
+        self.measurement_cache = cache;
+        self
+    }
+
+    /// The text measurement cache shared by layout engines using this library.
+    pub fn measurement_cache(&self) -> &Arc<TextMeasurementCache> {
+        &self.measurement_cache
+    }
+
+    /// Registers the hyphenation patterns for `language`, in TeX pattern format.
+    /// They are parsed the first time a word in that language is hyphenated.
+    pub fn register_hyphenation_patterns(&self, language: &str, patterns: impl Into<Vec<u8>>) {
+        self.hyphenation.register(language, patterns);
+    }
+
+    /// The hyphenation dictionaries shared by layout engines using this library.
+    pub fn hyphenation(&self) -> &Arc<HyphenationRegistry> {
+        &self.hyphenation
+    }
+
     /// Enables system font loading (native platforms only).
     ///
     /// Only available with the `system-fonts` feature enabled.
@@ -502,13 +546,21 @@ impl SharedFontLibrary {
 
         // Get postscript name for registry
         let postscript_name = face_info.post_script_name.clone();
+        // The query falls back to any sans-serif face when the family is unknown.
+        let is_substitute = !face_info
+            .families
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case(family));
 
         // Drop the db lock before caching
         drop(db);
 
         self.cache_font_data_with_psname(
             cache_key,
-            font_bytes,
+            FontInstance {
+                data: font_bytes,
+                is_substitute,
+            },
             family,
             weight,
             font_style,
@@ -532,7 +584,7 @@ impl SharedFontLibrary {
 
         self.cache_font_data_with_psname(
             cache_key,
-            font_bytes,
+            FontInstance::new(font_bytes),
             family,
             weight,
             style,
@@ -543,13 +595,13 @@ impl SharedFontLibrary {
     fn cache_font_data_with_psname(
         &self,
         cache_key: FontCacheKey,
-        font_bytes: SharedFontData,
+        instance: FontInstance,
         family: &str,
         weight: &FontWeight,
         style: &FontStyle,
         postscript_name: String,
     ) -> Result<FontData, petty_traits::FontError> {
-        let instance = Arc::new(FontInstance::new(font_bytes));
+        let instance = Arc::new(instance);
 
         // Cache the FontData
         if let Ok(mut cache) = self.font_data_cache.write() {
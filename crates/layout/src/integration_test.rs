@@ -1,4 +1,8 @@
-use crate::test_utils::{create_paragraph, find_first_text_box_with_content, paginate_test_nodes};
+use crate::LayoutWarning;
+use crate::engine::LayoutStore;
+use crate::test_utils::{
+    create_paragraph, create_test_engine, find_first_text_box_with_content, paginate_test_nodes,
+};
 use petty_idf::{IRNode, InlineNode, NodeMetadata};
 use petty_style::dimension::{Dimension, Margins, PageSize};
 use petty_style::stylesheet::{ElementStyle, PageLayout, Stylesheet};
@@ -143,3 +147,45 @@ fn test_flex_container_with_percentages() {
         expected_right_x
     );
 }
+
+#[test]
+fn test_page_break_to_unknown_master_falls_back_to_default() {
+    let stylesheet = Stylesheet {
+        page_masters: HashMap::from([(
+            "master".to_string(),
+            PageLayout {
+                size: PageSize::A4,
+                margins: Some(Margins::all(72.0)),
+                ..Default::default()
+            },
+        )]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    };
+    let ir_root = IRNode::Root(vec![
+        create_paragraph("Before"),
+        IRNode::PageBreak {
+            master_name: Some("missing".to_string()),
+        },
+        create_paragraph("After"),
+    ]);
+
+    let engine = create_test_engine();
+    let store = LayoutStore::new();
+    let root = engine.build_render_tree(&ir_root, &store).unwrap();
+    let pages: Vec<_> = engine
+        .paginate(&stylesheet, root, &store)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let masters: Vec<&str> = pages.iter().map(|p| p.master_name.as_str()).collect();
+    assert_eq!(masters, ["master", "master"]);
+    assert!(find_first_text_box_with_content(&pages[1].elements, "After").is_some());
+    assert_eq!(
+        engine.take_warnings(),
+        vec![LayoutWarning::UnknownPageMaster {
+            name: "missing".to_string()
+        }]
+    );
+}
@@ -1,4 +1,4 @@
-use crate::{ComputedStyle, LayoutEngine, LayoutError, PositionedElement};
+use crate::{ComputedStyle, LayoutElement, LayoutEngine, LayoutError, PositionedElement};
 use bumpalo::Bump;
 use petty_idf::TextStr;
 use petty_types::geometry::{self, BoxConstraints, Size};
@@ -6,6 +6,7 @@ use std::any::Any;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::sync::Arc;
 
 // --- State Definitions (Type-Safe) ---
 
@@ -113,6 +114,10 @@ pub struct IndexEntry {
 pub struct LayoutEnvironment<'a> {
     pub engine: &'a LayoutEngine,
     pub local_page_index: usize,
+    /// The page master's baseline grid spacing, see [`PageLayout::baseline_grid`].
+    ///
+    /// [`PageLayout::baseline_grid`]: petty_style::stylesheet::PageLayout::baseline_grid
+    pub baseline_grid: Option<f32>,
     /// A cache for transient layout data (e.g. shaped text Buffers, Taffy trees).
     pub cache: &'a RefCell<HashMap<u64, Box<dyn Any + Send>>>,
 }
@@ -125,6 +130,8 @@ pub struct LayoutContext<'a> {
     elements: &'a mut Vec<PositionedElement>,
     defined_anchors: &'a mut HashMap<TextStr, AnchorLocation>,
     index_entries: &'a mut HashMap<TextStr, Vec<IndexEntry>>,
+    /// The page master requested by a page break on this page, if any.
+    next_page_master: &'a mut Option<TextStr>,
     pub last_v_margin: f32,
     root_top_y: f32,
 }
@@ -138,6 +145,7 @@ impl<'a> LayoutContext<'a> {
         elements: &'a mut Vec<PositionedElement>,
         defined_anchors: &'a mut HashMap<TextStr, AnchorLocation>,
         index_entries: &'a mut HashMap<TextStr, Vec<IndexEntry>>,
+        next_page_master: &'a mut Option<TextStr>,
     ) -> Self {
         let root_top_y = bounds.y;
         Self {
@@ -148,6 +156,7 @@ impl<'a> LayoutContext<'a> {
             elements,
             defined_anchors,
             index_entries,
+            next_page_master,
             last_v_margin: 0.0,
             root_top_y,
         }
@@ -162,8 +171,12 @@ impl<'a> LayoutContext<'a> {
     }
 
     pub fn is_at_page_top(&self) -> bool {
-        let absolute_y = self.bounds.y + self.cursor.1;
-        (absolute_y - self.root_top_y).abs() < 0.1
+        self.page_y().abs() < 0.1
+    }
+
+    /// The cursor's distance below the top of the page's content area.
+    pub fn page_y(&self) -> f32 {
+        self.bounds.y + self.cursor.1 - self.root_top_y
     }
 
     pub fn bounds(&self) -> geometry::Rect {
@@ -227,10 +240,58 @@ impl<'a> LayoutContext<'a> {
         self.elements.is_empty()
     }
 
+    /// Number of elements pushed to the page so far, for use with the `*_elements_from` helpers.
+    pub fn element_count(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Drops elements pushed since `start` that extend below `bottom_y` (in context coordinates).
+    /// Rectangles straddling the edge are cut off instead of dropped.
+    pub fn clip_elements_from(&mut self, start: usize, bottom_y: f32) {
+        let bottom = self.bounds.y + bottom_y;
+        let mut index = 0;
+        self.elements.retain_mut(|el| {
+            let keep = if index < start || el.y + el.height <= bottom {
+                true
+            } else if matches!(el.element, LayoutElement::Rectangle(_)) && el.y < bottom {
+                el.height = bottom - el.y;
+                true
+            } else {
+                false
+            };
+            index += 1;
+            keep
+        });
+    }
+
+    /// Uniformly scales elements pushed since `start` about the point `(x, y)` in context
+    /// coordinates. Text is scaled by adjusting its font size.
+    pub fn scale_elements_from(&mut self, start: usize, x: f32, y: f32, factor: f32) {
+        let (origin_x, origin_y) = (self.bounds.x + x, self.bounds.y + y);
+        for el in &mut self.elements[start..] {
+            el.x = origin_x + (el.x - origin_x) * factor;
+            el.y = origin_y + (el.y - origin_y) * factor;
+            el.width *= factor;
+            el.height *= factor;
+            if matches!(el.element, LayoutElement::Text(_)) {
+                let mut data = el.style.inner.clone();
+                data.text.font_size *= factor;
+                data.text.line_height *= factor;
+                el.style = Arc::new(ComputedStyle::new(data));
+            }
+        }
+    }
+
+    /// Requests that the page following this one uses the named page master.
+    pub fn request_page_master(&mut self, name: &str) {
+        *self.next_page_master = Some(name.to_string());
+    }
+
     pub fn child<'child>(&'child mut self, bounds: geometry::Rect) -> LayoutContext<'child> {
         let sub_env = LayoutEnvironment {
             engine: self.env.engine,
             local_page_index: self.env.local_page_index,
+            baseline_grid: self.env.baseline_grid,
             cache: self.env.cache,
         };
 
@@ -242,6 +303,7 @@ impl<'a> LayoutContext<'a> {
             elements: &mut *self.elements,
             defined_anchors: &mut *self.defined_anchors,
             index_entries: &mut *self.index_entries,
+            next_page_master: &mut *self.next_page_master,
             last_v_margin: 0.0,
             root_top_y: self.root_top_y,
         }
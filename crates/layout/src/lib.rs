@@ -33,17 +33,22 @@ pub mod nodes;
 pub mod perf;
 pub mod style;
 pub mod util;
+pub mod warnings;
 
 pub mod algorithms;
 pub mod painting;
 pub mod text;
 
 // Re-exports for convenience within the layout crate
-pub use self::config::LayoutConfig;
-pub use self::elements::{ImageElement, LayoutElement, PositionedElement, TextElement};
+pub use self::config::{LayoutConfig, OversizedPolicy};
+pub use self::elements::{
+    FormFieldElement, ImageElement, LayoutElement, PathElement, PathSegment, PositionedElement,
+    RectElement, TextElement,
+};
 pub use self::fonts::{FontFaceInfo, SharedFontLibrary};
 pub use self::output::LaidOutSequence;
 pub use self::style::ComputedStyle;
+pub use self::warnings::LayoutWarning;
 
 // Re-export geometry types used by nodes from base to prevent type mismatches
 pub use petty_types::geometry::{BoxConstraints, Rect, Size};
@@ -17,6 +17,10 @@ pub enum NodeKind {
     Table,
     PageBreak,
     IndexMarker,
+    FormField,
+    Caption,
+    BarChart,
+    PieChart,
 }
 
 impl NodeKind {
@@ -34,6 +38,10 @@ impl NodeKind {
             IRNode::Table { .. } => NodeKind::Table,
             IRNode::PageBreak { .. } => NodeKind::PageBreak,
             IRNode::IndexMarker { .. } => NodeKind::IndexMarker,
+            IRNode::FormField { .. } => NodeKind::FormField,
+            IRNode::Caption { .. } => NodeKind::Caption,
+            IRNode::BarChart { .. } => NodeKind::BarChart,
+            IRNode::PieChart { .. } => NodeKind::PieChart,
         }
     }
 
@@ -51,6 +59,10 @@ impl NodeKind {
             NodeKind::Table => "Table",
             NodeKind::PageBreak => "PageBreak",
             NodeKind::IndexMarker => "IndexMarker",
+            NodeKind::FormField => "FormField",
+            NodeKind::Caption => "Caption",
+            NodeKind::BarChart => "BarChart",
+            NodeKind::PieChart => "PieChart",
         }
     }
 }
@@ -0,0 +1,224 @@
+use super::RenderNode;
+use crate::elements::RectElement;
+use crate::engine::{LayoutEngine, LayoutStore};
+use crate::interface::{LayoutContext, LayoutEnvironment, LayoutNode, LayoutResult, NodeState};
+use crate::painting::box_painter::create_background_and_borders;
+use crate::style::{BorderModel, ComputedStyle, ComputedStyleData};
+use crate::{LayoutElement, LayoutError, PositionedElement, TextElement};
+use petty_idf::IRNode;
+use petty_style::dimension::Dimension;
+use petty_style::text::TextDecoration;
+use petty_types::geometry::{BoxConstraints, Rect, Size};
+use std::sync::Arc;
+
+const DEFAULT_SIZE: (f32, f32) = (300.0, 150.0);
+/// The thickness of the axis lines.
+const AXIS_WIDTH: f32 = 1.0;
+/// The fraction of each bar's slot filled by the bar itself.
+const BAR_FILL: f32 = 0.6;
+
+/// An atomic box drawing a bar chart: bars scaled to the largest value, standing on
+/// an x-axis, with their labels below it.
+///
+/// Bars, axes and labels are all drawn in the chart's text `color`.
+#[derive(Debug, Clone)]
+pub struct BarChartNode<'a> {
+    id: Option<&'a str>,
+    bars: Vec<(&'a str, f64)>,
+    style: Arc<ComputedStyle>,
+    /// The fill used for bars and axes.
+    fill_style: Arc<ComputedStyle>,
+    /// The chart's text style without its background and borders, used for labels.
+    label_style: Arc<ComputedStyle>,
+}
+
+impl<'a> BarChartNode<'a> {
+    pub fn build(
+        node: &IRNode,
+        engine: &LayoutEngine,
+        parent_style: Arc<ComputedStyle>,
+        store: &'a LayoutStore,
+    ) -> Result<RenderNode<'a>, LayoutError> {
+        let IRNode::BarChart { meta, bars } = node else {
+            return Err(LayoutError::BuilderMismatch("BarChart", node.kind()));
+        };
+
+        let style = engine.compute_style(
+            &meta.style_sets,
+            meta.style_override.as_ref(),
+            &parent_style,
+        );
+
+        let mut fill_data = ComputedStyleData::default();
+        fill_data.misc.background_color = Some(style.text.color.clone());
+        let mut label_data = style.inner.clone();
+        label_data.misc.background_color = None;
+        label_data.border = BorderModel::default();
+
+        let node = store.bump.alloc(Self {
+            id: meta.id.as_ref().map(|s| store.alloc_str(s)),
+            bars: bars
+                .iter()
+                .map(|bar| (store.alloc_str(&bar.label), bar.value))
+                .collect(),
+            fill_style: store.cache_style(Arc::new(ComputedStyle::new(fill_data))),
+            label_style: store.cache_style(Arc::new(ComputedStyle::new(label_data))),
+            style: store.cache_style(style),
+        });
+        Ok(RenderNode::BarChart(node))
+    }
+
+    fn filled_rect(&self, rect: Rect) -> PositionedElement {
+        PositionedElement {
+            element: LayoutElement::Rectangle(RectElement),
+            style: self.fill_style.clone(),
+            ..PositionedElement::from_rect(rect)
+        }
+    }
+
+    /// Draws the axes, bars and labels into the chart's content box.
+    fn draw_chart(&self, ctx: &mut LayoutContext, content: Rect) {
+        let label_height = self.label_style.text.line_height;
+        let plot_height = (content.height - label_height).max(AXIS_WIDTH);
+        let baseline = content.y + plot_height - AXIS_WIDTH;
+
+        ctx.push_element_at(
+            self.filled_rect(Rect {
+                width: AXIS_WIDTH,
+                height: plot_height,
+                ..content
+            }),
+            0.0,
+            0.0,
+        );
+        ctx.push_element_at(
+            self.filled_rect(Rect {
+                y: baseline,
+                height: AXIS_WIDTH,
+                ..content
+            }),
+            0.0,
+            0.0,
+        );
+
+        if self.bars.is_empty() {
+            return;
+        }
+        let max_value = self.bars.iter().map(|&(_, v)| v).fold(0.0, f64::max);
+        let slot_width = (content.width - AXIS_WIDTH).max(0.0) / self.bars.len() as f32;
+        let bar_width = slot_width * BAR_FILL;
+        let max_bar_height = (plot_height - AXIS_WIDTH).max(0.0);
+
+        for (i, &(label, value)) in self.bars.iter().enumerate() {
+            let slot_x = content.x + AXIS_WIDTH + i as f32 * slot_width;
+
+            if max_value > 0.0 && value > 0.0 {
+                let bar_height = (value / max_value) as f32 * max_bar_height;
+                ctx.push_element_at(
+                    self.filled_rect(Rect {
+                        x: slot_x + (slot_width - bar_width) / 2.0,
+                        y: baseline - bar_height,
+                        width: bar_width,
+                        height: bar_height,
+                    }),
+                    0.0,
+                    0.0,
+                );
+            }
+
+            if !label.is_empty() {
+                let label_width = ctx.env.engine.measure_text_width(label, &self.label_style);
+                let label_el = PositionedElement {
+                    element: LayoutElement::Text(TextElement {
+                        content: label.to_string(),
+                        href: None,
+                        text_decoration: TextDecoration::None,
+                    }),
+                    style: self.label_style.clone(),
+                    ..PositionedElement::from_rect(Rect {
+                        x: slot_x + (slot_width - label_width) / 2.0,
+                        y: content.y + plot_height,
+                        width: label_width,
+                        height: label_height,
+                    })
+                };
+                ctx.push_element_at(label_el, 0.0, 0.0);
+            }
+        }
+    }
+}
+
+impl<'a> LayoutNode for BarChartNode<'a> {
+    fn style(&self) -> &ComputedStyle {
+        self.style.as_ref()
+    }
+
+    fn measure(
+        &self,
+        _env: &LayoutEnvironment,
+        constraints: BoxConstraints,
+    ) -> Result<Size, LayoutError> {
+        let w = match self.style.box_model.width {
+            Some(Dimension::Pt(v)) => v,
+            _ => DEFAULT_SIZE.0,
+        };
+        let h = match self.style.box_model.height {
+            Some(Dimension::Pt(v)) => v,
+            _ => DEFAULT_SIZE.1,
+        };
+
+        let width = constraints.constrain_width(w + self.style.padding_x() + self.style.border_x());
+        let height =
+            constraints.constrain_height(h + self.style.padding_y() + self.style.border_y());
+
+        Ok(Size::new(width, height))
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut LayoutContext,
+        constraints: BoxConstraints,
+        _break_state: Option<NodeState>,
+    ) -> Result<LayoutResult, LayoutError> {
+        if let Some(id) = self.id {
+            ctx.register_anchor(id);
+        }
+
+        let size = self.measure(&ctx.env, constraints)?;
+
+        if ctx.prepare_for_block(self.style.box_model.margin.top) {
+            return Ok(LayoutResult::Break(NodeState::Atomic));
+        }
+
+        if size.height > ctx.available_height() && !ctx.is_empty() {
+            return Ok(LayoutResult::Break(NodeState::Atomic));
+        }
+
+        let start_y = ctx.cursor_y();
+
+        let bg_elements = create_background_and_borders(
+            ctx.bounds(),
+            &self.style,
+            start_y,
+            size.height,
+            true,
+            true,
+        );
+        for el in bg_elements {
+            ctx.push_element_at(el, 0.0, 0.0);
+        }
+
+        let content = Rect {
+            x: self.style.border_left_width() + self.style.box_model.padding.left,
+            y: start_y + self.style.border_top_width() + self.style.box_model.padding.top,
+            width: size.width - self.style.padding_x() - self.style.border_x(),
+            height: size.height - self.style.padding_y() - self.style.border_y(),
+        };
+        self.draw_chart(ctx, content);
+
+        ctx.set_cursor_y(start_y + size.height);
+        ctx.finish_block(self.style.box_model.margin.bottom);
+
+        Ok(LayoutResult::Finished)
+    }
+}
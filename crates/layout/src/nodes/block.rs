@@ -1,11 +1,13 @@
 use super::RenderNode;
 use crate::LayoutError;
+use crate::config::OversizedPolicy;
 use crate::engine::{LayoutEngine, LayoutStore};
 use crate::interface::{
     BlockState, LayoutContext, LayoutEnvironment, LayoutNode, LayoutResult, NodeState,
 };
 use crate::painting::box_painter::create_background_and_borders;
 use crate::style::ComputedStyle;
+use crate::warnings::LayoutWarning;
 use petty_idf::{IRNode, TextStr};
 use petty_style::dimension::Dimension;
 use petty_types::geometry::{self, BoxConstraints, Size};
@@ -72,6 +74,22 @@ impl<'a> BlockNode<'a> {
     }
 }
 
+impl<'a> BlockNode<'a> {
+    /// The border-box height implied by `aspect-ratio` for a block `available_width`
+    /// wide, when the block has no height of its own.
+    fn aspect_ratio_height(&self, available_width: f32) -> Option<f32> {
+        let ratio = self.style.box_model.aspect_ratio?;
+        if self.style.box_model.height.is_some() {
+            return None;
+        }
+        let width = match self.style.box_model.width {
+            Some(Dimension::Pt(w)) => w + self.style.padding_x() + self.style.border_x(),
+            _ => available_width,
+        };
+        Some(width / ratio)
+    }
+}
+
 impl<'a> LayoutNode for BlockNode<'a> {
     fn style(&self) -> &ComputedStyle {
         self.style.as_ref()
@@ -103,12 +121,8 @@ impl<'a> LayoutNode for BlockNode<'a> {
             max_child_width = max_child_width.max(child_size.width);
             total_content_height += child_size.height;
         }
-
-        let height = if let Some(Dimension::Pt(h)) = self.style.box_model.height {
-            margin_y + h
-        } else {
-            margin_y + border_y + padding_y + total_content_height
-        };
+        total_content_height +=
+            self.style.box_model.row_gap * self.children.len().saturating_sub(1) as f32;
 
         let computed_width = if constraints.has_bounded_width() {
             constraints.max_width
@@ -118,6 +132,14 @@ impl<'a> LayoutNode for BlockNode<'a> {
             max_child_width + h_deduction
         };
 
+        let height = if let Some(Dimension::Pt(h)) = self.style.box_model.height {
+            margin_y + h
+        } else {
+            let content_based = border_y + padding_y + total_content_height;
+            let ratio_based = self.aspect_ratio_height(computed_width).unwrap_or(0.0);
+            margin_y + content_based.max(ratio_based)
+        };
+
         Ok(Size::new(computed_width, height))
     }
 
@@ -140,14 +162,50 @@ impl<'a> LayoutNode for BlockNode<'a> {
 
         let is_continuation = start_index > 0 || child_resume_state.is_some();
 
+        // Set when a keep-together block cannot fit on an empty page and the
+        // configured policy lets layout continue.
+        let mut oversized_policy = None;
+
         // Use LayoutContext helpers for margin collapsing
         if !is_continuation {
+            let at_page_top = ctx.is_at_page_top();
             if ctx.prepare_for_block(self.style.box_model.margin.top) {
                 return Ok(LayoutResult::Break(NodeState::Block(BlockState {
                     child_index: 0,
                     child_state: None,
                 })));
             }
+
+            // A block sized by its aspect ratio is moved whole to the next page.
+            if let Some(ratio_height) = self.aspect_ratio_height(ctx.bounds().width)
+                && ratio_height > ctx.available_height()
+                && !at_page_top
+            {
+                return Ok(LayoutResult::Break(NodeState::Block(BlockState {
+                    child_index: 0,
+                    child_state: None,
+                })));
+            }
+
+            if self.style.misc.keep_together {
+                let margin_y = self.style.box_model.margin.top + self.style.box_model.margin.bottom;
+                let required = self.measure(&ctx.env, constraints)?.height - margin_y;
+                let available = ctx.available_height();
+                if required > available {
+                    if !at_page_top {
+                        return Ok(LayoutResult::Break(NodeState::Block(BlockState {
+                            child_index: 0,
+                            child_state: None,
+                        })));
+                    }
+                    match ctx.env.engine.config().oversized_policy {
+                        OversizedPolicy::Error => {
+                            return Err(LayoutError::ElementTooLarge(required, available));
+                        }
+                        policy => oversized_policy = Some(policy),
+                    }
+                }
+            }
         } else {
             // If continuing, ensure previous margins are cleared so we don't double add them
             ctx.last_v_margin = 0.0;
@@ -168,12 +226,19 @@ impl<'a> LayoutNode for BlockNode<'a> {
 
         let child_constraints = self.style.content_constraints(constraints);
         let ctx_bounds = ctx.bounds();
+        let page_bottom_y = ctx_bounds.height;
+        let first_element = ctx.element_count();
 
         let child_bounds = geometry::Rect {
             x: ctx_bounds.x + border_left + self.style.box_model.padding.left,
             y: ctx_bounds.y + content_start_y_in_ctx,
             width: ctx_bounds.width - self.style.padding_x() - self.style.border_x(),
-            height: ctx.available_height(),
+            // An oversized block is laid out unbroken and fixed up afterwards.
+            height: if oversized_policy.is_some() {
+                f32::INFINITY
+            } else {
+                ctx.available_height()
+            },
         };
 
         let mut child_ctx = ctx.child(child_bounds);
@@ -184,6 +249,11 @@ impl<'a> LayoutNode for BlockNode<'a> {
             } else {
                 None
             };
+            // Gaps go between children only, so none is added before the first child
+            // or where the block resumes at the top of a new page.
+            if i > start_index {
+                child_ctx.advance_cursor(self.style.box_model.row_gap);
+            }
 
             let res = child.layout(&mut child_ctx, child_constraints, resume)?;
 
@@ -199,7 +269,15 @@ impl<'a> LayoutNode for BlockNode<'a> {
             }
         }
         let child_cursor_y = child_ctx.cursor_y();
-        let actual_used_height = child_cursor_y;
+        let mut actual_used_height = child_cursor_y;
+        if !is_continuation
+            && matches!(split_res, LayoutResult::Finished)
+            && let Some(ratio_height) = self.aspect_ratio_height(ctx_bounds.width)
+        {
+            let ratio_content_height =
+                ratio_height - self.style.padding_y() - self.style.border_y();
+            actual_used_height = actual_used_height.max(ratio_content_height);
+        }
 
         let bg_elements = create_background_and_borders(
             ctx.bounds(),
@@ -222,6 +300,25 @@ impl<'a> LayoutNode for BlockNode<'a> {
                 let border_bottom = self.style.border_bottom_width();
                 let bottom_spacing = self.style.box_model.padding.bottom + border_bottom;
                 ctx.set_cursor_y(content_start_y_in_ctx + actual_used_height + bottom_spacing);
+                match oversized_policy {
+                    Some(OversizedPolicy::Clip) => {
+                        ctx.clip_elements_from(first_element, page_bottom_y);
+                        ctx.env
+                            .engine
+                            .report_warning(LayoutWarning::OverflowClipped {
+                                node_id: self.id.map(str::to_string),
+                                page: ctx.env.local_page_index + 1,
+                            });
+                        ctx.set_cursor_y(page_bottom_y);
+                    }
+                    Some(OversizedPolicy::Shrink) => {
+                        let used = ctx.cursor_y() - block_start_y_in_ctx;
+                        let factor = (page_bottom_y - block_start_y_in_ctx) / used;
+                        ctx.scale_elements_from(first_element, 0.0, block_start_y_in_ctx, factor);
+                        ctx.set_cursor_y(page_bottom_y);
+                    }
+                    _ => {}
+                }
                 // Use finish_block helper
                 ctx.finish_block(self.style.box_model.margin.bottom);
                 Ok(LayoutResult::Finished)
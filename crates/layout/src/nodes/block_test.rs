@@ -1,10 +1,16 @@
 #![cfg(test)]
 
 use crate::LayoutElement;
-use crate::test_utils::{create_paragraph, find_first_text_box_with_content, paginate_test_nodes};
+use crate::LayoutWarning;
+use crate::config::{LayoutConfig, OversizedPolicy};
+use crate::engine::LayoutStore;
+use crate::test_utils::{
+    create_paragraph, create_test_engine, create_test_engine_with_config,
+    find_first_text_box_with_content, paginate_test_nodes, paginate_test_nodes_with_config,
+};
 use petty_idf::{IRNode, NodeMetadata};
 use petty_style::border::{Border, BorderStyle};
-use petty_style::dimension::{Margins, PageSize};
+use petty_style::dimension::{Dimension, Margins, PageSize};
 use petty_style::stylesheet::{ElementStyle, PageLayout, Stylesheet};
 use petty_types::color::Color;
 use std::collections::HashMap;
@@ -215,6 +221,86 @@ fn test_block_splits_across_pages() {
     assert_eq!(line6.y, 10.0); // Should be at the top of the new page.
 }
 
+#[test]
+fn test_row_gap_separates_block_children() {
+    let layout_with_gap = |row_gap: Option<f32>| {
+        let nodes = vec![IRNode::Block {
+            meta: NodeMetadata {
+                style_override: Some(ElementStyle {
+                    row_gap,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            children: ["P1", "P2", "P3", "P4"]
+                .into_iter()
+                .map(create_paragraph)
+                .collect(),
+        }];
+        let stylesheet = Stylesheet {
+            page_masters: HashMap::from([("master".to_string(), PageLayout::default())]),
+            default_page_master_name: Some("master".to_string()),
+            ..Default::default()
+        };
+        let (pages, _, _) = paginate_test_nodes(stylesheet, nodes).unwrap();
+        ["P1", "P2", "P3", "P4"]
+            .map(|text| find_first_text_box_with_content(&pages[0], text).unwrap().y)
+    };
+
+    let plain = layout_with_gap(None);
+    let gapped = layout_with_gap(Some(10.0));
+
+    // The first child is not pushed down; each of the 3 following children gets one
+    // more 10pt gap than the one before it.
+    assert_eq!(gapped[0], plain[0]);
+    for i in 1..4 {
+        assert!(
+            (gapped[i] - plain[i] - 10.0 * i as f32).abs() < 0.01,
+            "child {} should be offset by {} gaps",
+            i + 1,
+            i
+        );
+    }
+}
+
+#[test]
+fn test_block_height_from_aspect_ratio() {
+    let stylesheet = Stylesheet {
+        page_masters: HashMap::from([(
+            "master".to_string(),
+            PageLayout {
+                size: PageSize::Custom {
+                    width: 500.0,
+                    height: 500.0,
+                },
+                margins: Some(Margins::all(10.0)),
+                ..Default::default()
+            },
+        )]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    };
+    let nodes = vec![
+        IRNode::Block {
+            meta: NodeMetadata {
+                style_override: Some(ElementStyle {
+                    width: Some(Dimension::Pt(320.0)),
+                    aspect_ratio: Some(16.0 / 9.0),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            children: vec![create_paragraph("Placeholder")],
+        },
+        create_paragraph("After"),
+    ];
+
+    let (pages, _, _) = paginate_test_nodes(stylesheet, nodes).unwrap();
+    let after = find_first_text_box_with_content(&pages[0], "After").unwrap();
+    // 320pt wide at 16:9 is 180pt tall, however little content the block holds.
+    assert!((after.y - (10.0 + 180.0)).abs() < 0.01, "got {}", after.y);
+}
+
 #[test]
 fn test_vertical_margin_collapse() {
     let stylesheet = Stylesheet {
@@ -287,3 +373,186 @@ fn test_vertical_margin_collapse() {
         text2.y
     );
 }
+
+fn short_page_stylesheet() -> Stylesheet {
+    // Page content height = 80. Line height is 14.4. 5 lines fit.
+    Stylesheet {
+        page_masters: HashMap::from([(
+            "master".to_string(),
+            PageLayout {
+                size: PageSize::Custom {
+                    width: 500.0,
+                    height: 100.0,
+                },
+                margins: Some(Margins::all(10.0)),
+                ..Default::default()
+            },
+        )]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_keep_together_block_moves_to_next_page() {
+    let keep_style = ElementStyle {
+        keep_together: Some(true),
+        widows: Some(1),
+        orphans: Some(1),
+        ..Default::default()
+    };
+    let nodes = vec![
+        create_paragraph("Intro 1\nIntro 2\nIntro 3"),
+        IRNode::Block {
+            meta: NodeMetadata {
+                style_override: Some(keep_style),
+                ..Default::default()
+            },
+            children: vec![create_paragraph("Kept 1\nKept 2\nKept 3")],
+        },
+    ];
+
+    let (pages, _, _) = paginate_test_nodes(short_page_stylesheet(), nodes).unwrap();
+    assert_eq!(pages.len(), 2, "Expected two pages");
+
+    // Without keep-together, two of the kept lines would fit on page 1.
+    assert!(find_first_text_box_with_content(&pages[0], "Kept").is_none());
+
+    let kept1 = find_first_text_box_with_content(&pages[1], "Kept 1").unwrap();
+    assert_eq!(kept1.y, 10.0);
+    assert!(find_first_text_box_with_content(&pages[1], "Kept 3").is_some());
+}
+
+fn oversized_keep_together_block() -> Vec<IRNode> {
+    // Seven lines (100.8pt) cannot fit in the 80pt page content area.
+    let keep_style = ElementStyle {
+        keep_together: Some(true),
+        ..Default::default()
+    };
+    vec![IRNode::Block {
+        meta: NodeMetadata {
+            style_override: Some(keep_style),
+            ..Default::default()
+        },
+        children: vec![create_paragraph(
+            "Line 1\nLine 2\nLine 3\nLine 4\nLine 5\nLine 6\nLine 7",
+        )],
+    }]
+}
+
+fn with_policy(oversized_policy: OversizedPolicy) -> LayoutConfig {
+    LayoutConfig {
+        oversized_policy,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_keep_together_block_larger_than_page_errors() {
+    let err =
+        paginate_test_nodes(short_page_stylesheet(), oversized_keep_together_block()).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("exceeds the total page content height")
+    );
+}
+
+#[test]
+fn test_oversized_policy_clip() {
+    let (pages, _, _) = paginate_test_nodes_with_config(
+        short_page_stylesheet(),
+        oversized_keep_together_block(),
+        with_policy(OversizedPolicy::Clip),
+    )
+    .unwrap();
+    assert_eq!(pages.len(), 1);
+    assert!(find_first_text_box_with_content(&pages[0], "Line 5").is_some());
+    assert!(find_first_text_box_with_content(&pages[0], "Line 6").is_none());
+    assert!(pages[0].iter().all(|el| el.y + el.height <= 90.0 + 0.01));
+}
+
+#[test]
+fn test_oversized_policy_clip_reports_warning() {
+    let engine = create_test_engine_with_config(with_policy(OversizedPolicy::Clip));
+    let store = LayoutStore::new();
+    let root = engine
+        .build_render_tree(&IRNode::Root(oversized_keep_together_block()), &store)
+        .unwrap();
+    let stylesheet = short_page_stylesheet();
+    for page in engine.paginate(&stylesheet, root, &store).unwrap() {
+        page.unwrap();
+    }
+
+    assert_eq!(
+        engine.take_warnings(),
+        vec![LayoutWarning::OverflowClipped {
+            node_id: None,
+            page: 1,
+        }]
+    );
+    assert!(engine.take_warnings().is_empty());
+}
+
+#[test]
+fn test_oversized_policy_shrink() {
+    let (pages, _, _) = paginate_test_nodes_with_config(
+        short_page_stylesheet(),
+        oversized_keep_together_block(),
+        with_policy(OversizedPolicy::Shrink),
+    )
+    .unwrap();
+    assert_eq!(pages.len(), 1);
+    let line1 = find_first_text_box_with_content(&pages[0], "Line 1").unwrap();
+    let line7 = find_first_text_box_with_content(&pages[0], "Line 7").unwrap();
+    assert_eq!(line1.y, 10.0);
+    assert!(line7.y + line7.height <= 90.0 + 0.01);
+    assert!(line7.style.text.font_size < 12.0);
+}
+
+#[test]
+fn test_oversized_policy_allow_overflow() {
+    let (pages, _, _) = paginate_test_nodes_with_config(
+        short_page_stylesheet(),
+        oversized_keep_together_block(),
+        with_policy(OversizedPolicy::AllowOverflow),
+    )
+    .unwrap();
+    assert_eq!(pages.len(), 1);
+    let line7 = find_first_text_box_with_content(&pages[0], "Line 7").unwrap();
+    assert!(
+        line7.y > 90.0,
+        "Expected line 7 to extend past the page content area"
+    );
+    assert_eq!(line7.style.text.font_size, 12.0);
+}
+
+#[test]
+fn test_page_break_inside_block_switches_page_master() {
+    let mut stylesheet = short_page_stylesheet();
+    let body = stylesheet.page_masters["master"].clone();
+    stylesheet.page_masters.insert("body".to_string(), body);
+
+    let nodes = vec![IRNode::Block {
+        meta: NodeMetadata::default(),
+        children: vec![
+            create_paragraph("Front"),
+            IRNode::PageBreak {
+                master_name: Some("body".to_string()),
+            },
+            create_paragraph("Body"),
+        ],
+    }];
+
+    let engine = create_test_engine();
+    let store = LayoutStore::new();
+    let root = engine
+        .build_render_tree(&IRNode::Root(nodes), &store)
+        .unwrap();
+    let masters: Vec<String> = engine
+        .paginate(&stylesheet, root, &store)
+        .unwrap()
+        .map(|page| page.unwrap().master_name)
+        .collect();
+
+    assert_eq!(masters, vec!["master".to_string(), "body".to_string()]);
+}
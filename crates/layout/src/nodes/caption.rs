@@ -0,0 +1,66 @@
+// src/core/layout/nodes/caption.rs
+
+use super::RenderNode;
+use crate::LayoutError;
+use crate::engine::{LayoutEngine, LayoutStore};
+use crate::nodes::block::BlockNode;
+use crate::nodes::paragraph::ParagraphNode;
+use crate::style::ComputedStyle;
+use petty_idf::{CaptionPosition, IRNode, InlineNode, NodeMetadata};
+use std::sync::Arc;
+
+/// Builds a captioned table or image as a block holding the caption paragraph and the
+/// content, in the order given by the caption's position.
+///
+/// The caption's style applies to the caption paragraph, and its `id` anchors the
+/// whole block so lists of figures and tables can reference it.
+pub fn build_caption<'a>(
+    node: &IRNode,
+    engine: &LayoutEngine,
+    parent_style: Arc<ComputedStyle>,
+    store: &'a LayoutStore,
+) -> Result<RenderNode<'a>, LayoutError> {
+    let IRNode::Caption {
+        meta,
+        kind,
+        position,
+        number,
+        children,
+        content,
+    } = node
+    else {
+        return Err(LayoutError::BuilderMismatch("Caption", node.kind()));
+    };
+
+    let mut caption_children = Vec::with_capacity(children.len() + 1);
+    if let Some(number) = number {
+        caption_children.push(InlineNode::Text(format!("{} {}: ", kind.label(), number)));
+    }
+    caption_children.extend(children.iter().cloned());
+
+    // Construct a temporary Paragraph IR node to delegate logic
+    let caption_ir = IRNode::Paragraph {
+        meta: NodeMetadata {
+            id: None,
+            ..meta.clone()
+        },
+        children: caption_children,
+    };
+
+    // The wrapper only inherits, so the parent's box styles are not applied twice.
+    let style = engine.compute_style(&[], None, &parent_style);
+    let caption = ParagraphNode::build(&caption_ir, engine, style.clone(), store)?;
+    let content = engine.build_layout_node_tree(content, style.clone(), store)?;
+
+    let children = match position {
+        CaptionPosition::Above => vec![caption, content],
+        CaptionPosition::Below => vec![content, caption],
+    };
+    let node = store.bump.alloc(BlockNode::new_from_children(
+        meta.id.clone(),
+        children,
+        style,
+        store,
+    ));
+    Ok(RenderNode::Block(node))
+}
@@ -0,0 +1,101 @@
+#![cfg(test)]
+use crate::test_utils::{create_paragraph, find_first_text_box_with_content, paginate_test_nodes};
+use petty_idf::{
+    CaptionKind, CaptionPosition, IRNode, InlineNode, NodeMetadata, TableBody, TableCell,
+    TableColumnDefinition, TableRow, number_captions,
+};
+use petty_style::dimension::{Margins, PageSize};
+use petty_style::stylesheet::{PageLayout, Stylesheet};
+use std::collections::HashMap;
+
+fn get_stylesheet() -> Stylesheet {
+    Stylesheet {
+        page_masters: HashMap::from([(
+            "master".to_string(),
+            PageLayout {
+                size: PageSize::Custom {
+                    width: 500.0,
+                    height: 500.0,
+                },
+                margins: Some(Margins::all(10.0)),
+                ..Default::default()
+            },
+        )]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    }
+}
+
+fn create_caption(kind: CaptionKind, text: &str, content: IRNode) -> IRNode {
+    IRNode::Caption {
+        meta: NodeMetadata::default(),
+        kind,
+        position: kind.default_position(),
+        number: None,
+        children: vec![InlineNode::Text(text.to_string())],
+        content: Box::new(content),
+    }
+}
+
+fn create_table(text: &str) -> IRNode {
+    IRNode::Table {
+        meta: NodeMetadata::default(),
+        columns: vec![TableColumnDefinition::default()],
+        header: None,
+        body: Box::new(TableBody {
+            rows: vec![TableRow {
+                cells: vec![TableCell {
+                    children: vec![create_paragraph(text)],
+                    ..Default::default()
+                }],
+            }],
+        }),
+        footer: None,
+    }
+}
+
+#[test]
+fn test_captions_are_numbered_per_kind() {
+    let mut nodes = vec![
+        create_caption(CaptionKind::Table, "Sales", create_table("Cell A")),
+        create_caption(
+            CaptionKind::Figure,
+            "Chart",
+            create_paragraph("Figure body"),
+        ),
+        create_caption(CaptionKind::Table, "Costs", create_table("Cell B")),
+    ];
+    number_captions(&mut nodes);
+
+    let (pages, _, _) = paginate_test_nodes(get_stylesheet(), nodes).unwrap();
+    let page1 = &pages[0];
+
+    let table1 = find_first_text_box_with_content(page1, "Table 1").unwrap();
+    let table2 = find_first_text_box_with_content(page1, "Table 2").unwrap();
+    let figure1 = find_first_text_box_with_content(page1, "Figure 1").unwrap();
+    assert!(find_first_text_box_with_content(page1, "Sales").is_some());
+    assert!(find_first_text_box_with_content(page1, "Costs").is_some());
+
+    // Table captions go above their table, figure captions below their content.
+    let cell_a = find_first_text_box_with_content(page1, "Cell A").unwrap();
+    let figure_body = find_first_text_box_with_content(page1, "Figure body").unwrap();
+    let cell_b = find_first_text_box_with_content(page1, "Cell B").unwrap();
+    assert!(table1.y < cell_a.y);
+    assert!(figure_body.y < figure1.y);
+    assert!(figure1.y < table2.y && table2.y < cell_b.y);
+}
+
+#[test]
+fn test_caption_position_can_be_overridden() {
+    let mut caption = create_caption(CaptionKind::Table, "Totals", create_table("Cell"));
+    if let IRNode::Caption { position, .. } = &mut caption {
+        *position = CaptionPosition::Below;
+    }
+    let mut nodes = vec![caption];
+    number_captions(&mut nodes);
+
+    let (pages, _, _) = paginate_test_nodes(get_stylesheet(), nodes).unwrap();
+    let label = find_first_text_box_with_content(&pages[0], "Table 1").unwrap();
+    let cell = find_first_text_box_with_content(&pages[0], "Cell").unwrap();
+    assert!(cell.y < label.y);
+}
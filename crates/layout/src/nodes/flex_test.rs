@@ -3,7 +3,7 @@
 use crate::test_utils::{create_paragraph, find_first_text_box_with_content, paginate_test_nodes};
 use petty_idf::{IRNode, NodeMetadata};
 use petty_style::dimension::{Dimension, Margins, PageSize};
-use petty_style::flex::{AlignItems, AlignSelf, FlexDirection, FlexWrap};
+use petty_style::flex::{AlignItems, AlignSelf, FlexDirection, FlexWrap, JustifyContent};
 use petty_style::stylesheet::{ElementStyle, PageLayout, Stylesheet};
 use std::collections::HashMap;
 
@@ -207,6 +207,79 @@ fn test_flex_shrink() {
     );
 }
 
+#[test]
+fn test_column_gap_separates_flex_items() {
+    let stylesheet = get_stylesheet(500.0, 100.0);
+    let nodes = vec![IRNode::FlexContainer {
+        meta: NodeMetadata {
+            style_override: Some(ElementStyle {
+                column_gap: Some(10.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        children: ["1", "2", "3"]
+            .into_iter()
+            .map(|text| {
+                create_flex_item_with_style(
+                    text,
+                    ElementStyle {
+                        width: Some(Dimension::Pt(100.0)),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect(),
+    }];
+    let (pages, _, _) = paginate_test_nodes(stylesheet, nodes).unwrap();
+    let xs: Vec<f32> = ["1", "2", "3"]
+        .iter()
+        .map(|text| find_first_text_box_with_content(&pages[0], text).unwrap().x)
+        .collect();
+    // Two gaps of 10pt between three items, and none before the first.
+    assert_eq!(xs, vec![0.0, 110.0, 220.0]);
+}
+
+#[test]
+fn test_flex_grow_distributes_space_per_wrapped_line() {
+    let stylesheet = get_stylesheet(300.0, 200.0);
+    let item = |text: &str, basis: f32, grow: f32| {
+        create_flex_item_with_style(
+            text,
+            ElementStyle {
+                flex_basis: Some(Dimension::Pt(basis)),
+                flex_grow: Some(grow),
+                ..Default::default()
+            },
+        )
+    };
+    let nodes = vec![IRNode::FlexContainer {
+        meta: NodeMetadata {
+            style_override: Some(ElementStyle {
+                flex_wrap: Some(FlexWrap::Wrap),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        children: vec![
+            item("A", 100.0, 1.0),
+            item("B", 100.0, 2.0),
+            item("C", 200.0, 1.0),
+        ],
+    }];
+    let (pages, _, _) = paginate_test_nodes(stylesheet, nodes).unwrap();
+    let a = find_first_text_box_with_content(&pages[0], "A").unwrap();
+    let b = find_first_text_box_with_content(&pages[0], "B").unwrap();
+    let c = find_first_text_box_with_content(&pages[0], "C").unwrap();
+    // C does not fit beside A and B, so it wraps. The 100pt left on the first line is
+    // split 1:2, making A 133.3pt wide, which layout rounds to whole
+    // points. C grows alone to fill the second line.
+    assert_eq!(a.x, 0.0);
+    assert_eq!(b.x, 133.0);
+    assert_eq!(c.x, 0.0);
+    assert!(c.y > a.y, "C should wrap onto a second line");
+}
+
 #[test]
 fn test_order_property() {
     let stylesheet = get_stylesheet(500.0, 100.0);
@@ -278,6 +351,66 @@ fn test_margins_on_flex_items() {
     assert_eq!(item2.x, 20.0 + 100.0 + 30.0);
 }
 
+/// Lays out 100pt-wide items in a 500pt row and returns each item's x position.
+fn justified_item_positions(justify_content: JustifyContent, labels: &[&str]) -> Vec<f32> {
+    let stylesheet = get_stylesheet(500.0, 100.0);
+    let nodes = vec![IRNode::FlexContainer {
+        meta: NodeMetadata {
+            style_override: Some(ElementStyle {
+                justify_content: Some(justify_content),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        children: labels
+            .iter()
+            .map(|label| {
+                create_flex_item_with_style(
+                    label,
+                    ElementStyle {
+                        width: Some(Dimension::Pt(100.0)),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect(),
+    }];
+    let (pages, _, _) = paginate_test_nodes(stylesheet, nodes).unwrap();
+    labels
+        .iter()
+        .map(|label| {
+            find_first_text_box_with_content(&pages[0], label)
+                .unwrap()
+                .x
+        })
+        .collect()
+}
+
+#[test]
+fn test_justify_content_space_between() {
+    // Free space = 500 - 300 = 200, split into the two gaps between the items.
+    let xs = justified_item_positions(JustifyContent::SpaceBetween, &["1", "2", "3"]);
+    assert_eq!(xs, vec![0.0, 200.0, 400.0]);
+}
+
+#[test]
+fn test_justify_content_space_around() {
+    // Each item gets 200 / 3 of free space, half on either side.
+    let xs = justified_item_positions(JustifyContent::SpaceAround, &["1", "2", "3"]);
+    let expected = [100.0 / 3.0, 200.0, 400.0 - 100.0 / 3.0];
+    for (x, expected) in xs.iter().zip(expected) {
+        assert!((x - expected).abs() < 1.0, "{:?}", xs);
+    }
+}
+
+#[test]
+fn test_justify_content_center_and_end() {
+    let xs = justified_item_positions(JustifyContent::Center, &["1", "2"]);
+    assert_eq!(xs, vec![150.0, 250.0]);
+    let xs = justified_item_positions(JustifyContent::FlexEnd, &["1", "2"]);
+    assert_eq!(xs, vec![300.0, 400.0]);
+}
+
 #[test]
 fn test_align_items_center() {
     let stylesheet = get_stylesheet(500.0, 100.0);
@@ -357,6 +490,44 @@ fn test_align_self_override() {
     assert!((short.y - 80.0).abs() < 0.1);
 }
 
+#[test]
+fn test_align_self_overrides_centered_container() {
+    let stylesheet = get_stylesheet(500.0, 200.0);
+    let item = |text: &str, align_self: Option<AlignSelf>| {
+        create_flex_item_with_style(
+            text,
+            ElementStyle {
+                width: Some(Dimension::Pt(100.0)),
+                height: Some(Dimension::Pt(20.0)),
+                align_self,
+                ..Default::default()
+            },
+        )
+    };
+    let nodes = vec![IRNode::FlexContainer {
+        meta: NodeMetadata {
+            style_override: Some(ElementStyle {
+                height: Some(Dimension::Pt(100.0)),
+                align_items: Some(AlignItems::Center),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        children: vec![
+            item("a", None),
+            item("b", Some(AlignSelf::FlexEnd)),
+            item("c", Some(AlignSelf::Auto)),
+        ],
+    }];
+    let (pages, _, _) = paginate_test_nodes(stylesheet, nodes).unwrap();
+    let y = |text: &str| find_first_text_box_with_content(&pages[0], text).unwrap().y;
+    // Siblings follow the container and center in the 100pt line: (100 - 20) / 2 = 40.
+    assert_eq!(y("a"), 40.0);
+    assert_eq!(y("c"), 40.0);
+    // The overriding child sits at the end of the line instead.
+    assert_eq!(y("b"), 80.0);
+}
+
 #[test]
 fn test_flex_wrap_with_page_break() {
     let stylesheet = Stylesheet {
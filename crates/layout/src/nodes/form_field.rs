@@ -0,0 +1,142 @@
+use super::RenderNode;
+use crate::engine::{LayoutEngine, LayoutStore};
+use crate::interface::{LayoutContext, LayoutEnvironment, LayoutNode, LayoutResult, NodeState};
+use crate::painting::box_painter::create_background_and_borders;
+use crate::style::ComputedStyle;
+use crate::{FormFieldElement, LayoutElement, LayoutError, PositionedElement};
+use petty_idf::{FormFieldKind, IRNode};
+use petty_style::dimension::Dimension;
+use petty_types::geometry::{self, BoxConstraints, Size};
+use std::sync::Arc;
+
+const TEXT_FIELD_SIZE: (f32, f32) = (150.0, 18.0);
+const CHECKBOX_SIZE: (f32, f32) = (12.0, 12.0);
+
+/// An atomic box holding an interactive form field widget.
+#[derive(Debug, Clone)]
+pub struct FormFieldNode<'a> {
+    id: Option<&'a str>,
+    name: &'a str,
+    kind: &'a FormFieldKind,
+    style: Arc<ComputedStyle>,
+}
+
+impl<'a> FormFieldNode<'a> {
+    pub fn build(
+        node: &IRNode,
+        engine: &LayoutEngine,
+        parent_style: Arc<ComputedStyle>,
+        store: &'a LayoutStore,
+    ) -> Result<RenderNode<'a>, LayoutError> {
+        let (meta, name, kind) = match node {
+            IRNode::FormField { meta, name, kind } => (meta, name, kind),
+            _ => return Err(LayoutError::BuilderMismatch("FormField", node.kind())),
+        };
+
+        let style = engine.compute_style(
+            &meta.style_sets,
+            meta.style_override.as_ref(),
+            &parent_style,
+        );
+
+        let node = store.bump.alloc(Self {
+            id: meta.id.as_ref().map(|s| store.alloc_str(s)),
+            name: store.alloc_str(name),
+            kind: store.bump.alloc(kind.clone()),
+            style: store.cache_style(style),
+        });
+        Ok(RenderNode::FormField(node))
+    }
+
+    fn default_size(&self) -> (f32, f32) {
+        match self.kind {
+            FormFieldKind::Text { .. } => TEXT_FIELD_SIZE,
+            FormFieldKind::Checkbox { .. } => CHECKBOX_SIZE,
+        }
+    }
+}
+
+impl<'a> LayoutNode for FormFieldNode<'a> {
+    fn style(&self) -> &ComputedStyle {
+        self.style.as_ref()
+    }
+
+    fn measure(
+        &self,
+        _env: &LayoutEnvironment,
+        constraints: BoxConstraints,
+    ) -> Result<Size, LayoutError> {
+        let (default_w, default_h) = self.default_size();
+        let w = match self.style.box_model.width {
+            Some(Dimension::Pt(v)) => v,
+            _ => default_w,
+        };
+        let h = match self.style.box_model.height {
+            Some(Dimension::Pt(v)) => v,
+            _ => default_h,
+        };
+
+        let width = constraints.constrain_width(w + self.style.padding_x() + self.style.border_x());
+        let height =
+            constraints.constrain_height(h + self.style.padding_y() + self.style.border_y());
+
+        Ok(Size::new(width, height))
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut LayoutContext,
+        constraints: BoxConstraints,
+        _break_state: Option<NodeState>,
+    ) -> Result<LayoutResult, LayoutError> {
+        if let Some(id) = self.id {
+            ctx.register_anchor(id);
+        }
+
+        let size = self.measure(&ctx.env, constraints)?;
+
+        if ctx.prepare_for_block(self.style.box_model.margin.top) {
+            return Ok(LayoutResult::Break(NodeState::Atomic));
+        }
+
+        if size.height > ctx.available_height() && !ctx.is_empty() {
+            return Ok(LayoutResult::Break(NodeState::Atomic));
+        }
+
+        let start_y = ctx.cursor_y();
+
+        let bg_elements = create_background_and_borders(
+            ctx.bounds(),
+            &self.style,
+            start_y,
+            size.height,
+            true,
+            true,
+        );
+        for el in bg_elements {
+            ctx.push_element_at(el, 0.0, 0.0);
+        }
+
+        let widget_rect = geometry::Rect {
+            x: self.style.border_left_width() + self.style.box_model.padding.left,
+            y: start_y + self.style.border_top_width() + self.style.box_model.padding.top,
+            width: size.width - self.style.padding_x() - self.style.border_x(),
+            height: size.height - self.style.padding_y() - self.style.border_y(),
+        };
+
+        let field_el = PositionedElement {
+            element: LayoutElement::FormField(FormFieldElement {
+                name: self.name.to_string(),
+                kind: self.kind.clone(),
+            }),
+            style: self.style.clone(),
+            ..PositionedElement::from_rect(widget_rect)
+        };
+        ctx.push_element_at(field_el, 0.0, 0.0);
+
+        ctx.set_cursor_y(start_y + size.height);
+        ctx.finish_block(self.style.box_model.margin.bottom);
+
+        Ok(LayoutResult::Finished)
+    }
+}
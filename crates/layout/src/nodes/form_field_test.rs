@@ -0,0 +1,59 @@
+#![cfg(test)]
+use crate::LayoutElement;
+use crate::test_utils::paginate_test_nodes;
+use petty_idf::{FormFieldKind, IRNode, NodeMetadata};
+use petty_style::dimension::{Margins, PageSize};
+use petty_style::stylesheet::{PageLayout, Stylesheet};
+use std::collections::HashMap;
+
+fn create_field(name: &str, kind: FormFieldKind) -> IRNode {
+    IRNode::FormField {
+        meta: NodeMetadata::default(),
+        name: name.into(),
+        kind,
+    }
+}
+
+#[test]
+fn test_form_fields_use_default_widget_sizes() {
+    let nodes = vec![
+        create_field(
+            "customer",
+            FormFieldKind::Text {
+                value: "Jane".into(),
+            },
+        ),
+        create_field("agree", FormFieldKind::Checkbox { checked: true }),
+    ];
+
+    let stylesheet = Stylesheet {
+        page_masters: HashMap::from([(
+            "master".to_string(),
+            PageLayout {
+                size: PageSize::Custom {
+                    width: 500.0,
+                    height: 500.0,
+                },
+                margins: Some(Margins::all(10.0)),
+                ..Default::default()
+            },
+        )]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    };
+
+    let (pages, _, _) = paginate_test_nodes(stylesheet, nodes).unwrap();
+    let fields: Vec<_> = pages[0]
+        .iter()
+        .filter(|el| matches!(el.element, LayoutElement::FormField(_)))
+        .collect();
+
+    assert_eq!(fields.len(), 2);
+    assert_eq!((fields[0].width, fields[0].height), (150.0, 18.0));
+    assert_eq!((fields[1].width, fields[1].height), (12.0, 12.0));
+    assert!(fields[1].y >= fields[0].y + fields[0].height);
+    let LayoutElement::FormField(checkbox) = &fields[1].element else {
+        unreachable!()
+    };
+    assert_eq!(checkbox.name, "agree");
+}
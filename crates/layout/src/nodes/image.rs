@@ -91,14 +91,18 @@ impl<'a> LayoutNode for ImageNode<'a> {
         _env: &LayoutEnvironment,
         constraints: BoxConstraints,
     ) -> Result<Size, LayoutError> {
-        let w = match self.style.box_model.width {
-            Some(Dimension::Pt(v)) => v,
-            _ => 100.0,
-        };
-
-        let h = match self.style.box_model.height {
-            Some(Dimension::Pt(v)) => v,
-            _ => 100.0,
+        let (w, h) = match (
+            &self.style.box_model.width,
+            &self.style.box_model.height,
+            self.style.box_model.aspect_ratio,
+        ) {
+            (Some(Dimension::Pt(w)), Some(Dimension::Pt(h)), _) => (*w, *h),
+            (Some(Dimension::Pt(w)), _, Some(ratio)) => (*w, w / ratio),
+            (_, Some(Dimension::Pt(h)), Some(ratio)) => (h * ratio, *h),
+            (Some(Dimension::Pt(w)), _, None) => (*w, 100.0),
+            (_, Some(Dimension::Pt(h)), None) => (100.0, *h),
+            (_, _, Some(ratio)) => (100.0, 100.0 / ratio),
+            _ => (100.0, 100.0),
         };
 
         let width = constraints.constrain_width(w + self.style.padding_x() + self.style.border_x());
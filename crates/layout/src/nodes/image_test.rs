@@ -140,3 +140,28 @@ fn test_image_taller_than_page_is_skipped() {
     assert!(find_first_text_box_with_content(page1, "Before").is_some());
     assert!(find_first_text_box_with_content(page1, "After").is_some());
 }
+
+#[test]
+fn test_image_height_from_aspect_ratio() {
+    let stylesheet = Stylesheet {
+        page_masters: HashMap::from([("master".to_string(), PageLayout::default())]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    };
+    let nodes = vec![IRNode::Image {
+        src: "test.png".to_string(),
+        meta: NodeMetadata {
+            style_override: Some(ElementStyle {
+                width: Some(Dimension::Pt(320.0)),
+                aspect_ratio: Some(16.0 / 9.0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    }];
+
+    let (pages, _, _) = paginate_test_nodes(stylesheet, nodes).unwrap();
+    let image = &pages[0][0];
+    assert_eq!(image.width, 320.0);
+    assert!((image.height - 180.0).abs() < 0.01, "got {}", image.height);
+}
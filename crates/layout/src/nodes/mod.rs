@@ -1,7 +1,10 @@
 //! Contains the implementations of the LayoutNode trait for each document element type.
 
+pub mod bar_chart;
 pub mod block;
+pub mod caption;
 pub mod flex;
+pub mod form_field;
 pub mod heading;
 pub mod image;
 pub mod index_marker;
@@ -10,6 +13,7 @@ pub mod list_item;
 pub mod list_utils;
 pub mod page_break;
 pub mod paragraph;
+pub mod pie_chart;
 pub mod table;
 
 #[cfg(test)]
@@ -17,10 +21,14 @@ mod block_split_test;
 #[cfg(test)]
 mod block_test;
 #[cfg(test)]
+mod caption_test;
+#[cfg(test)]
 mod flex_break_test;
 #[cfg(test)]
 mod flex_test;
 #[cfg(test)]
+mod form_field_test;
+#[cfg(test)]
 mod image_test;
 #[cfg(test)]
 mod index_marker_test;
@@ -39,8 +47,11 @@ use crate::{LayoutEngine, LayoutEnvironment, LayoutError};
 use petty_types::geometry::{BoxConstraints, Size};
 
 // Import specific nodes
+use self::bar_chart::BarChartNode;
 use self::block::BlockNode;
+use self::caption::build_caption;
 use self::flex::FlexNode;
+use self::form_field::FormFieldNode;
 use self::heading::HeadingNode;
 use self::image::ImageNode;
 use self::index_marker::IndexMarkerNode;
@@ -48,6 +59,7 @@ use self::list::ListNode;
 use self::list_item::ListItemNode;
 use self::page_break::PageBreakNode;
 use self::paragraph::ParagraphNode;
+use self::pie_chart::PieChartNode;
 use self::table::TableNode;
 
 use std::sync::Arc;
@@ -71,6 +83,10 @@ pub fn build_node_tree<'a>(
         IRNode::Table { .. } => TableNode::build(node, engine, parent_style, store),
         IRNode::PageBreak { .. } => PageBreakNode::build(node, engine, parent_style, store),
         IRNode::IndexMarker { .. } => IndexMarkerNode::build(node, engine, parent_style, store),
+        IRNode::FormField { .. } => FormFieldNode::build(node, engine, parent_style, store),
+        IRNode::Caption { .. } => build_caption(node, engine, parent_style, store),
+        IRNode::BarChart { .. } => BarChartNode::build(node, engine, parent_style, store),
+        IRNode::PieChart { .. } => PieChartNode::build(node, engine, parent_style, store),
     }
 }
 
@@ -123,8 +139,10 @@ macro_rules! define_render_node {
 }
 
 define_render_node!(
+    BarChart(BarChartNode),
     Block(BlockNode),
     Flex(FlexNode),
+    FormField(FormFieldNode),
     Heading(HeadingNode),
     Image(ImageNode),
     IndexMarker(IndexMarkerNode),
@@ -132,5 +150,6 @@ define_render_node!(
     ListItem(ListItemNode),
     PageBreak(PageBreakNode),
     Paragraph(ParagraphNode),
+    PieChart(PieChartNode),
     Table(TableNode)
 );
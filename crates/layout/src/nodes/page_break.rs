@@ -67,6 +67,9 @@ impl<'a> LayoutNode for PageBreakNode<'a> {
         // Force a break unless we are at the very top of an empty page (unlikely for a manual break)
         // If we just return Break, the engine will create a new page.
         if !ctx.is_empty() || ctx.cursor_y() > 0.0 {
+            if let Some(name) = self.master_name {
+                ctx.request_page_master(name);
+            }
             Ok(LayoutResult::Break(NodeState::Atomic))
         } else {
             // Already at top of page, consume the break
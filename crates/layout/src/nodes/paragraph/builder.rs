@@ -4,6 +4,7 @@ use crate::engine::{LayoutEngine, LayoutStore};
 use crate::nodes::RenderNode;
 use crate::style::ComputedStyle;
 use crate::text::builder::TextBuilder;
+use crate::warnings::LayoutWarning;
 use petty_idf::IRNode;
 use std::sync::Arc;
 
@@ -28,7 +29,17 @@ impl<'a> ParagraphNode<'a> {
         let mut builder = TextBuilder::new(engine, store, &style);
         builder.process_inlines(inlines, &style);
 
-        let (full_text, spans, inline_images_vec, links_vec) = builder.finish();
+        let (full_text, spans, inline_images_vec, inline_sparklines_vec, links_vec) =
+            builder.finish();
+
+        for span in spans.iter() {
+            if engine.uses_fallback_font(&span.style) {
+                engine.report_warning(LayoutWarning::FontFallbackUsed {
+                    node_id: meta.id.clone(),
+                    family: span.style.text.font_family.to_string(),
+                });
+            }
+        }
 
         let mut link_refs = Vec::with_capacity(links_vec.len());
         for link in links_vec {
@@ -36,6 +47,7 @@ impl<'a> ParagraphNode<'a> {
         }
         let links_slice = store.bump.alloc_slice_copy(&link_refs);
         let images_slice = store.bump.alloc_slice_clone(&inline_images_vec);
+        let sparklines_slice = store.bump.alloc_slice_clone(&inline_sparklines_vec);
         let style_ref = store.cache_style(style);
 
         let unique_id = store.next_node_id();
@@ -48,6 +60,7 @@ impl<'a> ParagraphNode<'a> {
             full_text,
             links: links_slice,
             inline_images: images_slice,
+            inline_sparklines: sparklines_slice,
             style: style_ref,
         });
 
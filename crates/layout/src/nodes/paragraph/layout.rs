@@ -2,14 +2,21 @@ use crate::LayoutError;
 use crate::cache::{MultiSpanCacheKey, ShapingCacheKey};
 use crate::interface::{LayoutContext, LayoutEnvironment};
 use crate::text::shaper::{ShapedRun, shape_text};
-use crate::text::wrapper::{LineLayout, break_lines, render_lines};
+use crate::text::wrapper::{
+    LineLayout, break_lines, end_line_with_ellipsis, render_lines, truncate_line,
+};
+use crate::{LayoutElement, PositionedElement, TextElement};
 use crate::{LayoutResult, NodeState, ParagraphState};
+use petty_style::text::TextOverflow;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 use super::node::ParagraphNode;
 
+/// Ends lines cut short by `text-overflow: ellipsis`.
+const ELLIPSIS: &str = "\u{2026}";
+
 #[derive(Debug, Clone)]
 pub struct ParagraphLayout {
     pub lines: Vec<LineLayout>,
@@ -59,6 +66,20 @@ impl<'a> ParagraphNode<'a> {
     }
 
     fn compute_shaped_runs(&self, engine: &crate::LayoutEngine) -> Arc<Vec<ShapedRun>> {
+        let shape = || {
+            shape_text(
+                engine,
+                self.spans,
+                self.inline_images,
+                self.inline_sparklines,
+            )
+        };
+
+        // The text of an inline object is a placeholder, so its runs can't be cached by text.
+        if !self.inline_images.is_empty() || !self.inline_sparklines.is_empty() {
+            return Arc::new(shape());
+        }
+
         if self.spans.len() == 1 {
             let span = &self.spans[0];
             let key = ShapingCacheKey {
@@ -70,35 +91,30 @@ impl<'a> ParagraphNode<'a> {
                 return runs;
             }
 
-            let runs = Arc::new(shape_text(engine, self.spans, self.inline_images));
+            let runs = Arc::new(shape());
             engine.cache_shaping_run(key, runs.clone());
             return runs;
         }
 
-        if self.inline_images.is_empty() {
-            let mut key_spans = Vec::with_capacity(self.spans.len());
+        let mut key_spans = Vec::with_capacity(self.spans.len());
 
-            for span in self.spans {
-                let mut hasher = DefaultHasher::new();
-                span.style.hash(&mut hasher);
-                let style_hash = hasher.finish();
-
-                key_spans.push((span.text.to_string(), style_hash));
-            }
+        for span in self.spans {
+            let mut hasher = DefaultHasher::new();
+            span.style.hash(&mut hasher);
+            let style_hash = hasher.finish();
 
-            let multi_key = MultiSpanCacheKey { spans: key_spans };
+            key_spans.push((span.text.to_string(), style_hash));
+        }
 
-            if let Some(runs) = engine.get_cached_multi_span_run(&multi_key) {
-                return runs;
-            }
+        let multi_key = MultiSpanCacheKey { spans: key_spans };
 
-            let runs = Arc::new(shape_text(engine, self.spans, self.inline_images));
-            engine.cache_multi_span_run(multi_key, runs.clone());
+        if let Some(runs) = engine.get_cached_multi_span_run(&multi_key) {
             return runs;
         }
 
-        let runs = shape_text(engine, self.spans, self.inline_images);
-        Arc::new(runs)
+        let runs = Arc::new(shape());
+        engine.cache_multi_span_run(multi_key, runs.clone());
+        runs
     }
 
     pub(super) fn resolve_layout(
@@ -119,7 +135,7 @@ impl<'a> ParagraphNode<'a> {
             return layout.clone();
         }
 
-        let layout = self.compute_layout(shaped_runs, width);
+        let layout = self.compute_layout(env.engine, shaped_runs, width);
         env.cache
             .borrow_mut()
             .insert(layout_key, Box::new(layout.clone()));
@@ -128,10 +144,34 @@ impl<'a> ParagraphNode<'a> {
 
     fn compute_layout(
         &self,
+        engine: &crate::LayoutEngine,
         shaped_runs: &Arc<Vec<ShapedRun>>,
         max_width: f32,
     ) -> Arc<ParagraphLayout> {
-        let lines = break_lines(shaped_runs, max_width, &self.style, self.full_text);
+        let mut lines = break_lines(shaped_runs, max_width, &self.style, self.full_text);
+
+        if self.style.text.text_overflow == TextOverflow::Ellipsis && max_width.is_finite() {
+            let ellipsis_width = engine.measure_text_width(ELLIPSIS, &self.style);
+            for line in &mut lines {
+                truncate_line(line, max_width, ellipsis_width, shaped_runs, self.full_text);
+            }
+        }
+
+        if let Some(max_lines) = self.style.text.max_lines
+            && lines.len() > max_lines
+        {
+            lines.truncate(max_lines);
+            if let Some(last) = lines.last_mut() {
+                let ellipsis_width = engine.measure_text_width(ELLIPSIS, &self.style);
+                end_line_with_ellipsis(
+                    last,
+                    max_width,
+                    ellipsis_width,
+                    shaped_runs,
+                    self.full_text,
+                );
+            }
+        }
 
         let total_height = lines.iter().map(|l| l.height).sum();
         let max_line_width = lines.iter().map(|l| l.width).fold(0.0f32, f32::max);
@@ -169,12 +209,15 @@ impl<'a> ParagraphNode<'a> {
         }
 
         // Determine how many lines fit on this page
+        let page_y = ctx.page_y();
+        let grid = ctx.env.baseline_grid;
         let mut lines_to_render = 0;
         let mut height_to_render = 0.0;
         let mut split = false;
 
         for i in start_line_index..layout.lines.len() {
-            let line_height = layout.lines[i].height;
+            let line = &layout.lines[i];
+            let line_height = grid_shift(grid, page_y + height_to_render, line) + line.height;
             if height_to_render + line_height > available_height + 0.1 {
                 split = true;
                 break;
@@ -242,9 +285,11 @@ impl<'a> ParagraphNode<'a> {
 
         // --- Rendering ---
         let mut rendered_height_actual = 0.0;
+        let mut rendered_line_height = 0.0;
         for i in 0..lines_final {
             let line_idx = start_line_index + i;
             let line = &layout.lines[line_idx];
+            rendered_height_actual += grid_shift(grid, page_y + rendered_height_actual, line);
             render_lines(
                 ctx,
                 line,
@@ -253,12 +298,27 @@ impl<'a> ParagraphNode<'a> {
                 self.links,
                 self.full_text,
             );
+            if let Some(ellipsis) = line.ellipsis {
+                ctx.push_element(PositionedElement {
+                    x: ellipsis.x,
+                    y: rendered_height_actual,
+                    width: ellipsis.width,
+                    height: self.style.text.line_height,
+                    element: LayoutElement::Text(TextElement {
+                        content: ELLIPSIS.to_string(),
+                        href: None,
+                        text_decoration: self.style.text.text_decoration.clone(),
+                    }),
+                    style: self.style.clone(),
+                });
+            }
             rendered_height_actual += line.height;
+            rendered_line_height += line.height;
         }
 
         ctx.advance_cursor(rendered_height_actual);
 
-        let next_offset = current_y + rendered_height_actual;
+        let next_offset = current_y + rendered_line_height;
 
         if start_line_index + lines_final < total_lines {
             Ok(LayoutResult::Break(NodeState::Paragraph(ParagraphState {
@@ -270,3 +330,13 @@ impl<'a> ParagraphNode<'a> {
         }
     }
 }
+
+/// How far a line starting `top` below the top of the page's content area moves down
+/// to put its baseline on the next line of the baseline `grid`.
+fn grid_shift(grid: Option<f32>, top: f32, line: &LineLayout) -> f32 {
+    let Some(grid) = grid else {
+        return 0.0;
+    };
+    let baseline = top + line.baseline;
+    ((baseline / grid - 0.001).ceil() * grid - baseline).max(0.0)
+}
@@ -4,7 +4,7 @@ use crate::{
     ParagraphState,
 };
 // Use explicit geometry types from base to match Trait definition
-use crate::text::builder::{InlineImageEntry, TextSpan};
+use crate::text::builder::{InlineImageEntry, InlineSparklineEntry, TextSpan};
 use petty_style::dimension::Dimension;
 use petty_types::geometry::{BoxConstraints, Size};
 use std::sync::Arc;
@@ -19,6 +19,7 @@ pub struct ParagraphNode<'a> {
     pub full_text: &'a str,
     pub links: &'a [&'a str],
     pub inline_images: &'a [InlineImageEntry<'a>],
+    pub inline_sparklines: &'a [InlineSparklineEntry<'a>],
     pub style: Arc<ComputedStyle>,
 }
 
@@ -104,14 +104,14 @@ pub fn shape_text(
                 last_font_data.clone()
             } else {
                 let fd = engine.get_font_for_style(&span.style)
-                    .or_else(|| engine.get_font_for_style(&engine.get_default_style()));
+                    .or_else(|| engine.get_fallback_font());
                 last_style_ref = Some(&span.style);
                 last_font_data = fd.clone();
                 fd
             }
         } else {
             let fd = engine.get_font_for_style(&span.style)
-                .or_else(|| engine.get_font_for_style(&engine.get_default_style()));
+                .or_else(|| engine.get_fallback_font());
             last_style_ref = Some(&span.style);
             last_font_data = fd.clone();
             fd
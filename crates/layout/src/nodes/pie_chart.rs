@@ -0,0 +1,310 @@
+use super::RenderNode;
+use crate::elements::{PathSegment, RectElement};
+use crate::engine::{LayoutEngine, LayoutStore};
+use crate::interface::{LayoutContext, LayoutEnvironment, LayoutNode, LayoutResult, NodeState};
+use crate::painting::box_painter::create_background_and_borders;
+use crate::style::{BorderModel, ComputedStyle, ComputedStyleData};
+use crate::{LayoutElement, LayoutError, PathElement, PositionedElement, TextElement};
+use petty_idf::IRNode;
+use petty_style::dimension::Dimension;
+use petty_style::text::TextDecoration;
+use petty_types::color::Color;
+use petty_types::geometry::{BoxConstraints, Rect, Size};
+use std::f32::consts::{FRAC_PI_2, TAU};
+use std::sync::Arc;
+
+const DEFAULT_SIZE: (f32, f32) = (300.0, 150.0);
+/// The space between the pie and its legend, and between a swatch and its label.
+const LEGEND_GAP: f32 = 10.0;
+/// The slice colors, reused in order when there are more slices than colors.
+const PALETTE: [(u8, u8, u8); 8] = [
+    (78, 121, 167),
+    (242, 142, 43),
+    (225, 87, 89),
+    (118, 183, 178),
+    (89, 161, 79),
+    (237, 201, 72),
+    (176, 122, 161),
+    (156, 117, 95),
+];
+
+#[derive(Debug, Clone)]
+struct Slice<'a> {
+    label: &'a str,
+    value: f64,
+    /// Fills the wedge, through its text color.
+    wedge_style: Arc<ComputedStyle>,
+    /// Fills the legend swatch, through its background color.
+    swatch_style: Arc<ComputedStyle>,
+}
+
+/// An atomic box drawing a pie or donut chart, with a legend of the slice labels to
+/// its right.
+///
+/// Slices are filled from a fixed palette, clockwise from twelve o'clock, and their
+/// angles are their share of the total of the positive values.
+#[derive(Debug, Clone)]
+pub struct PieChartNode<'a> {
+    id: Option<&'a str>,
+    slices: Vec<Slice<'a>>,
+    inner_radius: f32,
+    style: Arc<ComputedStyle>,
+    /// The chart's text style without its background and borders, used for labels.
+    label_style: Arc<ComputedStyle>,
+}
+
+impl<'a> PieChartNode<'a> {
+    pub fn build(
+        node: &IRNode,
+        engine: &LayoutEngine,
+        parent_style: Arc<ComputedStyle>,
+        store: &'a LayoutStore,
+    ) -> Result<RenderNode<'a>, LayoutError> {
+        let IRNode::PieChart {
+            meta,
+            slices,
+            inner_radius,
+        } = node
+        else {
+            return Err(LayoutError::BuilderMismatch("PieChart", node.kind()));
+        };
+
+        let style = engine.compute_style(
+            &meta.style_sets,
+            meta.style_override.as_ref(),
+            &parent_style,
+        );
+
+        let mut label_data = style.inner.clone();
+        label_data.misc.background_color = None;
+        label_data.border = BorderModel::default();
+
+        let slices = slices
+            .iter()
+            .zip(PALETTE.iter().cycle())
+            .map(|(slice, &(r, g, b))| {
+                let color = Color { r, g, b, a: 1.0 };
+                let mut wedge_data = ComputedStyleData::default();
+                wedge_data.text.color = color.clone();
+                let mut swatch_data = ComputedStyleData::default();
+                swatch_data.misc.background_color = Some(color);
+                Slice {
+                    label: store.alloc_str(&slice.label),
+                    value: slice.value,
+                    wedge_style: store.cache_style(Arc::new(ComputedStyle::new(wedge_data))),
+                    swatch_style: store.cache_style(Arc::new(ComputedStyle::new(swatch_data))),
+                }
+            })
+            .collect();
+
+        let node = store.bump.alloc(Self {
+            id: meta.id.as_ref().map(|s| store.alloc_str(s)),
+            slices,
+            inner_radius: inner_radius.clamp(0.0, 1.0),
+            label_style: store.cache_style(Arc::new(ComputedStyle::new(label_data))),
+            style: store.cache_style(style),
+        });
+        Ok(RenderNode::PieChart(node))
+    }
+
+    /// Draws the wedges and the legend into the chart's content box.
+    fn draw_chart(&self, ctx: &mut LayoutContext, content: Rect) {
+        let line_height = self.label_style.text.line_height;
+        let swatch_size = self.label_style.text.font_size * 0.8;
+        let label_widths: Vec<f32> = self
+            .slices
+            .iter()
+            .map(|slice| {
+                ctx.env
+                    .engine
+                    .measure_text_width(slice.label, &self.label_style)
+            })
+            .collect();
+        let legend_width =
+            label_widths.iter().fold(0.0_f32, |max, &w| max.max(w)) + swatch_size + LEGEND_GAP;
+        let diameter = content
+            .height
+            .min(content.width - legend_width - LEGEND_GAP)
+            .max(0.0);
+
+        let total: f64 = self.slices.iter().map(|s| s.value.max(0.0)).sum();
+        if diameter > 0.0 && total > 0.0 {
+            let pie = Rect {
+                y: content.y + (content.height - diameter) / 2.0,
+                width: diameter,
+                height: diameter,
+                ..content
+            };
+            let mut start = 0.0;
+            for slice in self.slices.iter().filter(|s| s.value > 0.0) {
+                let end = start + (slice.value / total) as f32 * TAU;
+                let wedge = PositionedElement {
+                    element: LayoutElement::Path(wedge_path(start, end, self.inner_radius)),
+                    style: slice.wedge_style.clone(),
+                    ..PositionedElement::from_rect(pie)
+                };
+                ctx.push_element_at(wedge, 0.0, 0.0);
+                start = end;
+            }
+        }
+
+        let legend_x = content.x + diameter + LEGEND_GAP;
+        let legend_height = self.slices.len() as f32 * line_height;
+        let mut y = content.y + ((content.height - legend_height) / 2.0).max(0.0);
+        for (slice, label_width) in self.slices.iter().zip(label_widths) {
+            let swatch = PositionedElement {
+                element: LayoutElement::Rectangle(RectElement),
+                style: slice.swatch_style.clone(),
+                ..PositionedElement::from_rect(Rect {
+                    x: legend_x,
+                    y: y + (line_height - swatch_size) / 2.0,
+                    width: swatch_size,
+                    height: swatch_size,
+                })
+            };
+            ctx.push_element_at(swatch, 0.0, 0.0);
+
+            if !slice.label.is_empty() {
+                let label_el = PositionedElement {
+                    element: LayoutElement::Text(TextElement {
+                        content: slice.label.to_string(),
+                        href: None,
+                        text_decoration: TextDecoration::None,
+                    }),
+                    style: self.label_style.clone(),
+                    ..PositionedElement::from_rect(Rect {
+                        x: legend_x + swatch_size + LEGEND_GAP,
+                        y,
+                        width: label_width,
+                        height: line_height,
+                    })
+                };
+                ctx.push_element_at(label_el, 0.0, 0.0);
+            }
+            y += line_height;
+        }
+    }
+}
+
+/// The point at `angle` radians clockwise from twelve o'clock on a circle of `radius`,
+/// in the unit box the pie is drawn in.
+fn point_at(angle: f32, radius: f32) -> (f32, f32) {
+    (0.5 + radius * angle.sin(), 0.5 - radius * angle.cos())
+}
+
+/// Appends a circular arc from `from` to `to` (radians, clockwise when increasing) as
+/// cubic Bézier curves of at most a quarter turn each.
+fn push_arc(segments: &mut Vec<PathSegment>, radius: f32, from: f32, to: f32) {
+    let pieces = ((to - from).abs() / FRAC_PI_2).ceil().max(1.0);
+    let step = (to - from) / pieces;
+    let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+    let tangent = |a: f32| (a.cos(), a.sin());
+    for i in 0..pieces as usize {
+        let a0 = from + i as f32 * step;
+        let a1 = a0 + step;
+        let (p0, p1) = (point_at(a0, radius), point_at(a1, radius));
+        let (t0, t1) = (tangent(a0), tangent(a1));
+        segments.push(PathSegment::CurveTo([
+            (p0.0 + k * t0.0, p0.1 + k * t0.1),
+            (p1.0 - k * t1.0, p1.1 - k * t1.1),
+            p1,
+        ]));
+    }
+}
+
+/// A filled wedge between two angles, cut out around the center for a donut.
+fn wedge_path(start: f32, end: f32, inner_radius: f32) -> PathElement {
+    const RADIUS: f32 = 0.5;
+    let mut segments = Vec::new();
+    if inner_radius > 0.0 {
+        let inner = RADIUS * inner_radius;
+        segments.push(PathSegment::MoveTo(point_at(start, RADIUS)));
+        push_arc(&mut segments, RADIUS, start, end);
+        segments.push(PathSegment::LineTo(point_at(end, inner)));
+        push_arc(&mut segments, inner, end, start);
+    } else {
+        segments.push(PathSegment::MoveTo((0.5, 0.5)));
+        segments.push(PathSegment::LineTo(point_at(start, RADIUS)));
+        push_arc(&mut segments, RADIUS, start, end);
+    }
+    segments.push(PathSegment::Close);
+    PathElement {
+        segments,
+        filled: true,
+    }
+}
+
+impl<'a> LayoutNode for PieChartNode<'a> {
+    fn style(&self) -> &ComputedStyle {
+        self.style.as_ref()
+    }
+
+    fn measure(
+        &self,
+        _env: &LayoutEnvironment,
+        constraints: BoxConstraints,
+    ) -> Result<Size, LayoutError> {
+        let w = match self.style.box_model.width {
+            Some(Dimension::Pt(v)) => v,
+            _ => DEFAULT_SIZE.0,
+        };
+        let h = match self.style.box_model.height {
+            Some(Dimension::Pt(v)) => v,
+            _ => DEFAULT_SIZE.1,
+        };
+
+        let width = constraints.constrain_width(w + self.style.padding_x() + self.style.border_x());
+        let height =
+            constraints.constrain_height(h + self.style.padding_y() + self.style.border_y());
+
+        Ok(Size::new(width, height))
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut LayoutContext,
+        constraints: BoxConstraints,
+        _break_state: Option<NodeState>,
+    ) -> Result<LayoutResult, LayoutError> {
+        if let Some(id) = self.id {
+            ctx.register_anchor(id);
+        }
+
+        let size = self.measure(&ctx.env, constraints)?;
+
+        if ctx.prepare_for_block(self.style.box_model.margin.top) {
+            return Ok(LayoutResult::Break(NodeState::Atomic));
+        }
+
+        if size.height > ctx.available_height() && !ctx.is_empty() {
+            return Ok(LayoutResult::Break(NodeState::Atomic));
+        }
+
+        let start_y = ctx.cursor_y();
+
+        let bg_elements = create_background_and_borders(
+            ctx.bounds(),
+            &self.style,
+            start_y,
+            size.height,
+            true,
+            true,
+        );
+        for el in bg_elements {
+            ctx.push_element_at(el, 0.0, 0.0);
+        }
+
+        let content = Rect {
+            x: self.style.border_left_width() + self.style.box_model.padding.left,
+            y: start_y + self.style.border_top_width() + self.style.box_model.padding.top,
+            width: size.width - self.style.padding_x() - self.style.border_x(),
+            height: size.height - self.style.padding_y() - self.style.border_y(),
+        };
+        self.draw_chart(ctx, content);
+
+        ctx.set_cursor_y(start_y + size.height);
+        ctx.finish_block(self.style.box_model.margin.bottom);
+
+        Ok(LayoutResult::Finished)
+    }
+}
@@ -3,8 +3,9 @@ use crate::LayoutError;
 use crate::engine::{LayoutEngine, LayoutStore};
 use crate::nodes::RenderNode;
 use crate::nodes::block::BlockNode;
-use crate::style::ComputedStyle;
-use petty_idf::IRNode;
+use crate::style::{ComputedStyle, ComputedStyleData};
+use petty_idf::{IRNode, TableRow};
+use petty_style::border::{Border, BorderCollapse};
 use std::sync::Arc;
 
 impl<'a> TableNode<'a> {
@@ -31,7 +32,7 @@ impl<'a> TableNode<'a> {
             columns,
             header,
             body,
-            ..
+            footer,
         } = node
         else {
             return Err(LayoutError::BuilderMismatch("Table", node.kind()));
@@ -43,13 +44,31 @@ impl<'a> TableNode<'a> {
             &parent_style,
         );
 
-        let header_vec = if let Some(h) = header {
-            Self::build_rows(&h.rows, &style, engine, store)?
-        } else {
-            Vec::new()
-        };
+        let header_rows = header.as_ref().map_or(&[][..], |h| h.rows.as_slice());
+        let footer_rows = footer.as_ref().map_or(&[][..], |f| f.rows.as_slice());
+        let rows: Vec<&TableRow> = header_rows
+            .iter()
+            .chain(&body.rows)
+            .chain(footer_rows)
+            .collect();
+        let mut cell_styles: Vec<Vec<Arc<ComputedStyle>>> = rows
+            .iter()
+            .map(|row| {
+                row.cells
+                    .iter()
+                    .map(|c| engine.compute_style(&c.style_sets, c.style_override.as_ref(), &style))
+                    .collect()
+            })
+            .collect();
+        if style.table.border_collapse == BorderCollapse::Collapse {
+            collapse_cell_borders(&rows, &mut cell_styles);
+        }
 
-        let body_vec = Self::build_rows(&body.rows, &style, engine, store)?;
+        let (header_styles, rest) = cell_styles.split_at(header_rows.len());
+        let (body_styles, footer_styles) = rest.split_at(body.rows.len());
+        let header_vec = Self::build_rows(header_rows, header_styles, engine, store)?;
+        let body_vec = Self::build_rows(&body.rows, body_styles, engine, store)?;
+        let footer_vec = Self::build_rows(footer_rows, footer_styles, engine, store)?;
 
         let id = meta.id.as_ref().map(|s| store.alloc_str(s));
         let style_ref = store.cache_style(style);
@@ -60,34 +79,37 @@ impl<'a> TableNode<'a> {
             id,
             header_rows: store.bump.alloc_slice_clone(&header_vec),
             body_rows: store.bump.alloc_slice_clone(&body_vec),
+            footer_rows: store.bump.alloc_slice_clone(&footer_vec),
             style: style_ref,
             columns: columns.clone(),
         })
     }
 
     fn build_rows(
-        rows: &[petty_idf::TableRow],
-        style: &Arc<ComputedStyle>,
+        rows: &[TableRow],
+        cell_styles: &[Vec<Arc<ComputedStyle>>],
         engine: &LayoutEngine,
         store: &'a LayoutStore,
     ) -> Result<Vec<TableRowNode<'a>>, LayoutError> {
         rows.iter()
-            .map(|r| TableRowNode::new(r, style, engine, store))
+            .zip(cell_styles)
+            .map(|(r, styles)| TableRowNode::new(r, styles, engine, store))
             .collect()
     }
 }
 
 impl<'a> TableRowNode<'a> {
     fn new(
-        row: &petty_idf::TableRow,
-        style: &Arc<ComputedStyle>,
+        row: &TableRow,
+        cell_styles: &[Arc<ComputedStyle>],
         engine: &LayoutEngine,
         store: &'a LayoutStore,
     ) -> Result<Self, LayoutError> {
         let cells = row
             .cells
             .iter()
-            .map(|c| TableCellNode::new(c, style, engine, store))
+            .zip(cell_styles)
+            .map(|(c, style)| TableCellNode::new(c, style.clone(), engine, store))
             .collect::<Result<Vec<_>, _>>()?;
         Ok(Self {
             cells: store.bump.alloc_slice_clone(&cells),
@@ -98,13 +120,10 @@ impl<'a> TableRowNode<'a> {
 impl<'a> TableCellNode<'a> {
     fn new(
         cell: &petty_idf::TableCell,
-        style: &Arc<ComputedStyle>,
+        cell_style: Arc<ComputedStyle>,
         engine: &LayoutEngine,
         store: &'a LayoutStore,
     ) -> Result<Self, LayoutError> {
-        let cell_style =
-            engine.compute_style(&cell.style_sets, cell.style_override.as_ref(), style);
-
         let mut children = Vec::new();
         for c in &cell.children {
             children.push(engine.build_layout_node_tree(c, cell_style.clone(), store)?);
@@ -117,3 +136,65 @@ impl<'a> TableCellNode<'a> {
         })
     }
 }
+
+/// Merges the borders shared by adjacent cells for `border-collapse: collapse`.
+///
+/// Each shared edge is drawn once, by the cell above or to the left of it, using the
+/// wider of the two borders. Cells are placed on a grid honouring their spans; a
+/// spanning cell is merged with the neighbours at its first row and column.
+fn collapse_cell_borders(rows: &[&TableRow], cell_styles: &mut [Vec<Arc<ComputedStyle>>]) {
+    let mut data: Vec<Vec<ComputedStyleData>> = cell_styles
+        .iter()
+        .map(|row| row.iter().map(|s| s.inner.clone()).collect())
+        .collect();
+    // The (row, cell) index of the cell covering each grid slot.
+    let mut grid: Vec<Vec<Option<(usize, usize)>>> = vec![Vec::new(); rows.len()];
+
+    for (r, row) in rows.iter().enumerate() {
+        let mut col = 0;
+        for (i, cell) in row.cells.iter().enumerate() {
+            while grid[r].get(col).is_some_and(Option::is_some) {
+                col += 1;
+            }
+            let colspan = cell.col_span.max(1);
+            let rowspan = cell.row_span.max(1);
+            for slots in &mut grid[r..(r + rowspan).min(rows.len())] {
+                if slots.len() < col + colspan {
+                    slots.resize(col + colspan, None);
+                }
+                slots[col..col + colspan].fill(Some((r, i)));
+            }
+
+            if col > 0
+                && let Some((nr, ni)) = grid[r][col - 1]
+            {
+                let left = data[r][i].border.left.take();
+                let right = &mut data[nr][ni].border.right;
+                *right = wider_border(right.take(), left);
+            }
+            if r > 0
+                && let Some((nr, ni)) = grid[r - 1].get(col).copied().flatten()
+            {
+                let top = data[r][i].border.top.take();
+                let bottom = &mut data[nr][ni].border.bottom;
+                *bottom = wider_border(bottom.take(), top);
+            }
+            col += colspan;
+        }
+    }
+
+    for (styles, row_data) in cell_styles.iter_mut().zip(data) {
+        for (style, collapsed) in styles.iter_mut().zip(row_data) {
+            if style.inner != collapsed {
+                *style = Arc::new(ComputedStyle::new(collapsed));
+            }
+        }
+    }
+}
+
+fn wider_border(a: Option<Border>, b: Option<Border>) -> Option<Border> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b.width > a.width { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
@@ -5,6 +5,9 @@ use crate::{
     NodeState, Size,
 };
 use petty_idf::TableColumnDefinition;
+use petty_style::border::BorderCollapse;
+use petty_style::dimension::Dimension;
+use petty_style::flex::AlignSelf;
 // Use explicit geometry types from base to match Trait definition
 use crate::algorithms::table_solver::{TableCellInfo, TableSolver};
 #[cfg(feature = "profiling")]
@@ -30,6 +33,8 @@ pub struct TableNode<'a> {
     pub id: Option<&'a str>,
     pub header_rows: &'a [TableRowNode<'a>],
     pub body_rows: &'a [TableRowNode<'a>],
+    /// Rows repeated at the bottom of every page the table occupies.
+    pub footer_rows: &'a [TableRowNode<'a>],
     pub style: Arc<ComputedStyle>,
     pub columns: Vec<TableColumnDefinition>,
 }
@@ -66,7 +71,7 @@ impl<'a> LayoutNode for TableNode<'a> {
             .get(&key)
             .and_then(|v| v.downcast_ref::<TableLayoutOutput>())
         {
-            let width = if constraints.has_bounded_width() {
+            let width = if constraints.has_bounded_width() && !self.is_fit_content() {
                 constraints.max_width
             } else {
                 output.total_width
@@ -79,7 +84,7 @@ impl<'a> LayoutNode for TableNode<'a> {
             .borrow_mut()
             .insert(key, Box::new(layout_output.clone()));
 
-        let width = if constraints.has_bounded_width() {
+        let width = if constraints.has_bounded_width() && !self.is_fit_content() {
             constraints.max_width
         } else {
             layout_output.total_width
@@ -143,7 +148,9 @@ impl<'a> LayoutNode for TableNode<'a> {
         let start_y = ctx.cursor_y();
         let border_left = self.style.border_left_width();
         let padding_left = self.style.box_model.padding.left;
-        let table_x_start = border_left + padding_left;
+        let table_x_start = border_left
+            + padding_left
+            + self.alignment_offset(available_width, &layout_output.col_widths);
 
         let paginator = TablePagination::new(
             self,
@@ -160,6 +167,40 @@ impl<'a> LayoutNode for TableNode<'a> {
 }
 
 impl<'a> TableNode<'a> {
+    /// Whether the table is as wide as its content rather than its container, which
+    /// is requested with an explicit `width: auto` (or `fit-content`).
+    fn is_fit_content(&self) -> bool {
+        matches!(self.style.box_model.width, Some(Dimension::Auto))
+    }
+
+    /// The `border-spacing` around each cell, which only applies to separated borders.
+    pub(super) fn cell_spacing(&self) -> f32 {
+        match self.style.table.border_collapse {
+            BorderCollapse::Separate => self.style.table.border_spacing,
+            BorderCollapse::Collapse => 0.0,
+        }
+    }
+
+    /// The total spacing along an edge crossing `count` cells, including both ends.
+    fn spacing_across(&self, count: usize) -> f32 {
+        self.cell_spacing() * (count + 1) as f32
+    }
+
+    /// How far a fit-content table is shifted right within the available width,
+    /// according to its `align-self`.
+    fn alignment_offset(&self, available_width: Option<f32>, col_widths: &[f32]) -> f32 {
+        let Some(available_width) = available_width.filter(|_| self.is_fit_content()) else {
+            return 0.0;
+        };
+        let table_width = col_widths.iter().sum::<f32>() + self.spacing_across(col_widths.len());
+        let free_space = (available_width - table_width).max(0.0);
+        match self.style.flex.align_self {
+            AlignSelf::Center => free_space / 2.0,
+            AlignSelf::FlexEnd => free_space,
+            _ => 0.0,
+        }
+    }
+
     fn get_cache_key(&self, available_width: Option<f32>) -> u64 {
         let mut s = DefaultHasher::new();
         self.unique_id.hash(&mut s);
@@ -179,28 +220,42 @@ impl<'a> TableNode<'a> {
         max_height_hint: Option<f32>,
     ) -> Result<TableLayoutOutput, LayoutError> {
         let solver = TableSolver::new(env, &self.columns);
-        let all_rows = self
-            .header_rows
-            .iter()
-            .chain(self.body_rows.iter())
-            .map(|r| r.cells.iter());
+        let spacing = self.cell_spacing();
+        let h_spacing = self.spacing_across(self.columns.len());
+        let available_width = available_width.map(|w| (w - h_spacing).max(0.0));
+        let all_rows = || {
+            self.header_rows
+                .iter()
+                .chain(self.body_rows.iter())
+                .chain(self.footer_rows.iter())
+                .map(|r| r.cells.iter())
+        };
 
-        let col_widths = solver.resolve_widths(available_width, all_rows)?;
-        let row_heights = self.calculate_all_row_heights(env, &col_widths, max_height_hint)?;
+        let col_widths = if self.is_fit_content() {
+            // Columns take their preferred widths, shrinking only if that overflows.
+            let natural = solver.resolve_widths(None, all_rows())?;
+            match available_width {
+                Some(max_width) if natural.iter().sum::<f32>() > max_width => {
+                    solver.resolve_widths(available_width, all_rows())?
+                }
+                _ => natural,
+            }
+        } else {
+            solver.resolve_widths(available_width, all_rows())?
+        };
+        let row_heights =
+            self.calculate_all_row_heights(env, &col_widths, spacing, max_height_hint)?;
 
         let padding_y = self.style.padding_y();
         let border_y = self.style.border_y();
         let margin_y = self.style.box_model.margin.top + self.style.box_model.margin.bottom;
 
-        let content_height: f32 = row_heights.iter().sum();
+        let content_height: f32 =
+            row_heights.iter().sum::<f32>() + self.spacing_across(row_heights.len());
         let total_height = margin_y + padding_y + border_y + content_height;
 
         let h_deduction = self.style.padding_x() + self.style.border_x();
-        let total_width = if let Some(_w) = available_width {
-            col_widths.iter().sum::<f32>() + h_deduction
-        } else {
-            col_widths.iter().sum::<f32>() + h_deduction
-        };
+        let total_width = col_widths.iter().sum::<f32>() + h_spacing + h_deduction;
 
         Ok(TableLayoutOutput {
             col_widths,
@@ -214,15 +269,18 @@ impl<'a> TableNode<'a> {
         &self,
         env: &LayoutEnvironment,
         col_widths: &[f32],
+        spacing: f32,
         max_height_hint: Option<f32>,
     ) -> Result<Vec<f32>, LayoutError> {
         #[cfg(feature = "profiling")]
         let start = Instant::now();
-        let mut row_heights = Vec::with_capacity(self.header_rows.len() + self.body_rows.len());
+        let mut row_heights = Vec::with_capacity(
+            self.header_rows.len() + self.body_rows.len() + self.footer_rows.len(),
+        );
         let mut total_accumulated = 0.0;
 
         for row in self.header_rows {
-            let h = row.measure_height(env, col_widths)?;
+            let h = row.measure_height(env, col_widths, spacing)?;
             row_heights.push(h);
             total_accumulated += h;
         }
@@ -234,11 +292,16 @@ impl<'a> TableNode<'a> {
                 row_heights.push(0.0);
                 continue;
             }
-            let h = row.measure_height(env, col_widths)?;
+            let h = row.measure_height(env, col_widths, spacing)?;
             row_heights.push(h);
             total_accumulated += h;
         }
 
+        // The footer is drawn on every page, so it is always measured.
+        for row in self.footer_rows {
+            row_heights.push(row.measure_height(env, col_widths, spacing)?);
+        }
+
         #[cfg(feature = "profiling")]
         env.engine
             .record_perf("TableNode::calculate_all_row_heights", start.elapsed());
@@ -254,10 +317,13 @@ pub struct TableRowNode<'a> {
 }
 
 impl<'a> TableRowNode<'a> {
+    /// Measures the row's height. A cell spanning several columns also spans the
+    /// `spacing` between them.
     pub fn measure_height(
         &self,
         env: &LayoutEnvironment,
         col_widths: &[f32],
+        spacing: f32,
     ) -> Result<f32, LayoutError> {
         let mut max_height: f32 = 0.0;
         let mut col_cursor = 0;
@@ -267,7 +333,8 @@ impl<'a> TableRowNode<'a> {
                 break;
             }
             let end_col = (col_cursor + cell.colspan).min(col_widths.len());
-            let cell_width: f32 = col_widths[col_cursor..end_col].iter().sum();
+            let cell_width: f32 = col_widths[col_cursor..end_col].iter().sum::<f32>()
+                + spacing * (end_col - col_cursor).saturating_sub(1) as f32;
 
             let h = cell.measure_height(env, cell_width)?;
             if cell.rowspan == 1 {
@@ -12,6 +12,8 @@ pub struct TablePagination<'node, 'ctx, 'data, 'a, 'b> {
     start_y: f32,
     table_x_start: f32,
     header_count: usize,
+    footer_start: usize,
+    spacing: f32,
 }
 
 impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
@@ -33,6 +35,8 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
             start_y,
             table_x_start,
             header_count: node.header_rows.len(),
+            footer_start: node.header_rows.len() + node.body_rows.len(),
+            spacing: node.cell_spacing(),
         }
     }
 
@@ -45,6 +49,7 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
             let mut header_occupied = vec![0usize; self.node.columns.len().max(1)];
 
             for (i, row) in self.node.header_rows.iter().enumerate() {
+                current_y_offset += self.spacing;
                 let height = self.row_heights.get(i).copied().unwrap_or(0.0);
                 self.render_row(
                     row,
@@ -58,6 +63,12 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
             }
         }
 
+        // The footer repeats below the body rows on every page, so room is kept for it.
+        let footer_height: f32 = self.row_heights[self.footer_start..]
+            .iter()
+            .map(|h| h + self.spacing)
+            .sum();
+
         // 2. Render Body Rows
         for (i, row) in self
             .node
@@ -69,12 +80,16 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
             let height_idx = self.header_count + i;
             let row_height = *self.row_heights.get(height_idx).unwrap_or(&0.0);
 
-            if self.start_y + current_y_offset + row_height > self.ctx.bounds().height {
+            if self.start_y + current_y_offset + self.spacing + row_height + footer_height
+                > self.ctx.bounds().height
+            {
+                self.render_footer(current_y_offset)?;
                 return Ok(LayoutResult::Break(NodeState::Table(TableState {
                     row_index: i,
                 })));
             }
 
+            current_y_offset += self.spacing;
             self.render_row(
                 row,
                 self.start_y + current_y_offset,
@@ -86,9 +101,13 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
             current_y_offset += row_height;
         }
 
+        // 3. Render Footer
+        current_y_offset = self.render_footer(current_y_offset)?;
+
         self.ctx.set_cursor_y(
             self.start_y
                 + current_y_offset
+                + self.spacing
                 + self.node.style.box_model.padding.bottom
                 + self.node.style.border_bottom_width(),
         );
@@ -97,6 +116,26 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
         Ok(LayoutResult::Finished)
     }
 
+    /// Renders the footer rows starting at `y_offset`, returning the offset below them.
+    fn render_footer(&mut self, mut y_offset: f32) -> Result<f32, LayoutError> {
+        let mut footer_occupied = vec![0usize; self.node.columns.len().max(1)];
+        for (i, row) in self.node.footer_rows.iter().enumerate() {
+            let height_idx = self.footer_start + i;
+            y_offset += self.spacing;
+            let height = self.row_heights.get(height_idx).copied().unwrap_or(0.0);
+            self.render_row(
+                row,
+                self.start_y + y_offset,
+                height,
+                &mut footer_occupied,
+                i,
+                &self.row_heights[height_idx..],
+            )?;
+            y_offset += height;
+        }
+        Ok(y_offset)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn render_row(
         &mut self,
@@ -107,7 +146,7 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
         current_row_idx: usize,
         future_heights: &[f32],
     ) -> Result<(), LayoutError> {
-        let mut x_offset = 0.0;
+        let mut x_offset = self.spacing;
         let mut col_cursor = 0;
         let mut cell_iter = row.cells.iter();
 
@@ -115,7 +154,7 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
             if col_cursor < occupied_until_row_idx.len()
                 && occupied_until_row_idx[col_cursor] > current_row_idx
             {
-                x_offset += self.col_widths[col_cursor];
+                x_offset += self.col_widths[col_cursor] + self.spacing;
                 col_cursor += 1;
                 continue;
             }
@@ -125,12 +164,15 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
                 let rowspan = cell.rowspan;
 
                 let end_col = (col_cursor + colspan).min(self.col_widths.len());
-                let cell_width: f32 = self.col_widths[col_cursor..end_col].iter().sum();
+                let spanned_gaps = (end_col - col_cursor).saturating_sub(1) as f32;
+                let cell_width: f32 = self.col_widths[col_cursor..end_col].iter().sum::<f32>()
+                    + self.spacing * spanned_gaps;
 
                 let mut cell_height = height;
                 if rowspan > 1 {
                     let limit = rowspan.min(future_heights.len());
-                    cell_height = future_heights[0..limit].iter().sum();
+                    cell_height = future_heights[0..limit].iter().sum::<f32>()
+                        + self.spacing * limit.saturating_sub(1) as f32;
                     let free_at_index = current_row_idx + rowspan;
                     for k in 0..colspan {
                         if col_cursor + k < occupied_until_row_idx.len() {
@@ -153,7 +195,7 @@ impl<'node, 'ctx, 'data, 'a, 'b> TablePagination<'node, 'ctx, 'data, 'a, 'b> {
                     None,
                 )?;
 
-                x_offset += cell_width;
+                x_offset += cell_width + self.spacing;
                 col_cursor += colspan;
             } else {
                 break;
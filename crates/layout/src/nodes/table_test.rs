@@ -1,9 +1,16 @@
 #![cfg(test)]
 
+use crate::LayoutElement;
 use crate::test_utils::{create_paragraph, find_first_text_box_with_content, paginate_test_nodes};
-use petty_idf::{IRNode, TableBody, TableCell, TableColumnDefinition, TableHeader, TableRow};
+use petty_idf::{
+    IRNode, NodeMetadata, TableBody, TableCell, TableColumnDefinition, TableFooter, TableHeader,
+    TableRow,
+};
+use petty_style::border::{Border, BorderCollapse, BorderStyle};
 use petty_style::dimension::{Dimension, Margins, PageSize};
-use petty_style::stylesheet::{PageLayout, Stylesheet};
+use petty_style::flex::AlignSelf;
+use petty_style::stylesheet::{ElementStyle, PageLayout, Stylesheet};
+use petty_types::color::Color;
 use std::collections::HashMap;
 
 fn get_stylesheet(width: f32, height: f32) -> Stylesheet {
@@ -65,6 +72,7 @@ fn create_test_table(rows: usize) -> IRNode {
             }],
         })),
         body: Box::new(TableBody { rows: body_rows }),
+        footer: None,
     }
 }
 
@@ -119,6 +127,45 @@ fn test_table_splits_across_pages_and_repeats_header() {
     );
 }
 
+#[test]
+fn test_table_footer_repeats_at_bottom_of_each_page() {
+    // Page content height = 70. Header, two rows and the footer take 57.6,
+    // so each page holds two body rows.
+    let stylesheet = get_stylesheet(520.0, 90.0);
+    let mut table = create_test_table(5);
+    if let IRNode::Table { footer, .. } = &mut table {
+        *footer = Some(Box::new(TableFooter {
+            rows: vec![TableRow {
+                cells: vec![
+                    TableCell {
+                        children: vec![create_paragraph("Total")],
+                        ..Default::default()
+                    },
+                    TableCell {
+                        children: vec![create_paragraph("5")],
+                        ..Default::default()
+                    },
+                ],
+            }],
+        }));
+    }
+
+    let (pages, _, _) = paginate_test_nodes(stylesheet, vec![table]).unwrap();
+    assert_eq!(pages.len(), 3);
+
+    for (page, last_row) in pages.iter().zip(["R2C1", "R4C1", "R5C1"]) {
+        let last = find_first_text_box_with_content(page, last_row).unwrap();
+        let total = find_first_text_box_with_content(page, "Total").unwrap_or_else(|| {
+            panic!("Footer should repeat below {last_row}");
+        });
+        assert!(
+            (total.y - (last.y + 14.4)).abs() < 0.1,
+            "Footer should sit directly below {last_row}"
+        );
+    }
+    assert!(find_first_text_box_with_content(&pages[0], "R3C1").is_none());
+}
+
 #[test]
 fn test_table_colspan_and_rowspan() {
     let stylesheet = get_stylesheet(520.0, 500.0); // content width 500
@@ -189,6 +236,7 @@ fn test_table_colspan_and_rowspan() {
                 },
             ],
         }),
+        footer: None,
     };
     let (pages, _, _) = paginate_test_nodes(stylesheet, vec![table]).unwrap();
     let page1 = &pages[0];
@@ -226,3 +274,172 @@ fn test_table_colspan_and_rowspan() {
         "E should be on the third line"
     );
 }
+
+fn create_lookup_table(style: ElementStyle) -> IRNode {
+    let row = |key: &str, value: &str| TableRow {
+        cells: vec![
+            TableCell {
+                children: vec![create_paragraph(key)],
+                ..Default::default()
+            },
+            TableCell {
+                children: vec![create_paragraph(value)],
+                ..Default::default()
+            },
+        ],
+    };
+    IRNode::Table {
+        meta: NodeMetadata {
+            style_override: Some(style),
+            ..Default::default()
+        },
+        columns: vec![
+            TableColumnDefinition::default(),
+            TableColumnDefinition::default(),
+        ],
+        header: None,
+        body: Box::new(TableBody {
+            rows: vec![row("Code", "42"), row("Name", "Pen")],
+        }),
+        footer: None,
+    }
+}
+
+#[test]
+fn test_fit_content_table_is_as_wide_as_its_content() {
+    // Content width = 500.
+    let fit_content = ElementStyle {
+        width: Some(Dimension::Auto),
+        ..Default::default()
+    };
+    let (pages, _, _) = paginate_test_nodes(
+        get_stylesheet(520.0, 500.0),
+        vec![create_lookup_table(fit_content)],
+    )
+    .unwrap();
+    let page1 = &pages[0];
+
+    let code = find_first_text_box_with_content(page1, "Code").unwrap();
+    let name = find_first_text_box_with_content(page1, "Name").unwrap();
+    let value = find_first_text_box_with_content(page1, "42").unwrap();
+    let pen = find_first_text_box_with_content(page1, "Pen").unwrap();
+
+    // The second column starts right after the widest key.
+    assert_eq!(code.x, 10.0);
+    assert!((value.x - (10.0 + code.width.max(name.width))).abs() < 0.5);
+    let table_width = pen.x + pen.width.max(value.width) - 10.0;
+    assert!(
+        table_width < 100.0,
+        "Table width {} should fit its content",
+        table_width
+    );
+
+    // Without `width: auto` the columns share the full width.
+    let (pages, _, _) = paginate_test_nodes(
+        get_stylesheet(520.0, 500.0),
+        vec![create_lookup_table(ElementStyle::default())],
+    )
+    .unwrap();
+    let stretched = find_first_text_box_with_content(&pages[0], "42").unwrap();
+    assert!(stretched.x > 100.0);
+
+    // A centered fit-content table splits the free space on either side.
+    let centered = ElementStyle {
+        width: Some(Dimension::Auto),
+        align_self: Some(AlignSelf::Center),
+        ..Default::default()
+    };
+    let (pages, _, _) = paginate_test_nodes(
+        get_stylesheet(520.0, 500.0),
+        vec![create_lookup_table(centered)],
+    )
+    .unwrap();
+    let centered_code = find_first_text_box_with_content(&pages[0], "Code").unwrap();
+    assert!((centered_code.x - (10.0 + (500.0 - table_width) / 2.0)).abs() < 0.5);
+}
+
+fn create_bordered_grid(style: ElementStyle) -> IRNode {
+    let cell = |text: &str| TableCell {
+        style_override: Some(ElementStyle {
+            border: Some(Border {
+                width: 1.0,
+                style: BorderStyle::Solid,
+                color: Color::default(),
+            }),
+            ..Default::default()
+        }),
+        children: vec![create_paragraph(text)],
+        ..Default::default()
+    };
+    IRNode::Table {
+        meta: NodeMetadata {
+            style_override: Some(style),
+            ..Default::default()
+        },
+        columns: vec![
+            TableColumnDefinition {
+                width: Some(Dimension::Percent(50.0)),
+                ..Default::default()
+            },
+            TableColumnDefinition {
+                width: Some(Dimension::Percent(50.0)),
+                ..Default::default()
+            },
+        ],
+        header: None,
+        body: Box::new(TableBody {
+            rows: vec![
+                TableRow {
+                    cells: vec![cell("A"), cell("B")],
+                },
+                TableRow {
+                    cells: vec![cell("C"), cell("D")],
+                },
+            ],
+        }),
+        footer: None,
+    }
+}
+
+#[test]
+fn test_border_collapse_merges_shared_cell_borders() {
+    let count_border_lines = |style: ElementStyle| {
+        let (pages, _, _) = paginate_test_nodes(
+            get_stylesheet(520.0, 500.0),
+            vec![create_bordered_grid(style)],
+        )
+        .unwrap();
+        let lines = pages[0]
+            .iter()
+            .filter(|el| matches!(el.element, LayoutElement::Rectangle(_)))
+            .count();
+        (lines, pages)
+    };
+
+    // Separate: every cell draws all four of its borders.
+    let (separate_lines, separate_pages) = count_border_lines(ElementStyle {
+        border_spacing: Some(4.0),
+        ..Default::default()
+    });
+    assert_eq!(separate_lines, 16);
+
+    // Collapse: the shared vertical and horizontal edges are drawn once each.
+    let (collapsed_lines, collapsed_pages) = count_border_lines(ElementStyle {
+        border_collapse: Some(BorderCollapse::Collapse),
+        border_spacing: Some(4.0),
+        ..Default::default()
+    });
+    assert_eq!(collapsed_lines, 12);
+
+    // Spacing surrounds separated cells but is ignored when collapsed.
+    let a = find_first_text_box_with_content(&separate_pages[0], "A").unwrap();
+    let c = find_first_text_box_with_content(&separate_pages[0], "C").unwrap();
+    assert!((a.x - (10.0 + 4.0 + 1.0)).abs() < 0.1);
+    assert!((a.y - (10.0 + 4.0 + 1.0)).abs() < 0.1);
+    assert!((c.y - (a.y + 14.4 + 2.0 + 4.0)).abs() < 0.1);
+
+    let a = find_first_text_box_with_content(&collapsed_pages[0], "A").unwrap();
+    let c = find_first_text_box_with_content(&collapsed_pages[0], "C").unwrap();
+    assert!((a.x - 11.0).abs() < 0.1);
+    assert!((c.y - (a.y + 14.4 + 1.0)).abs() < 0.1);
+}
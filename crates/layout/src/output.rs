@@ -3,9 +3,9 @@
 //! This module contains types that represent the output of the layout process,
 //! primarily `LaidOutSequence` which contains positioned elements ready for rendering.
 
-use crate::{AnchorLocation, IndexEntry, LayoutElement, PositionedElement};
+use crate::{AnchorLocation, IndexEntry, LayoutElement, LayoutWarning, PositionedElement};
 use petty_idf::SharedData;
-use petty_types::TocEntry;
+use petty_types::{CaptionEntry, TocEntry};
 use std::collections::HashMap;
 
 /// The result of laying out a single document/data item.
@@ -15,14 +15,20 @@ use std::collections::HashMap;
 pub struct LaidOutSequence {
     /// Pages of positioned elements ready for rendering.
     pub pages: Vec<Vec<PositionedElement>>,
+    /// The page master each page was laid out with, parallel to `pages`.
+    pub page_masters: Vec<String>,
     /// Resources (images, etc.) referenced by the document.
     pub resources: HashMap<String, SharedData>,
     /// Defined anchors and their locations for cross-references.
     pub defined_anchors: HashMap<String, AnchorLocation>,
     /// Table of contents entries extracted during layout.
     pub toc_entries: Vec<TocEntry>,
+    /// Numbered table and figure captions, for lists of tables and figures.
+    pub caption_entries: Vec<CaptionEntry>,
     /// Index entries grouped by term.
     pub index_entries: HashMap<String, Vec<IndexEntry>>,
+    /// Issues worked around while laying out this sequence.
+    pub warnings: Vec<LayoutWarning>,
 }
 
 impl LaidOutSequence {
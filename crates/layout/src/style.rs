@@ -1,14 +1,15 @@
 // src/core/layout/style.rs
 
-use petty_style::border::Border;
+use petty_style::border::{Border, BorderCollapse};
 use petty_style::dimension::{Dimension, Margins};
 use petty_style::flex::{AlignItems, AlignSelf, FlexDirection, FlexWrap, JustifyContent};
-use petty_style::font::{FontStyle, FontWeight};
+use petty_style::font::{FontSource, FontStyle, FontWeight};
 use petty_style::list::{ListStylePosition, ListStyleType};
-use petty_style::stylesheet::ElementStyle;
-use petty_style::text::{TextAlign, TextDecoration};
+use petty_style::stylesheet::{ElementStyle, StyleKeyword};
+use petty_style::text::{TextAlign, TextDecoration, TextOverflow, WhiteSpace};
 use petty_types::color::Color;
 use petty_types::geometry::BoxConstraints;
+use serde::Serialize;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
@@ -20,13 +21,19 @@ fn hash_f32<H: Hasher>(v: &f32, state: &mut H) {
 
 // Grouped Style Structures
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
 pub struct BoxModel {
     pub margin: Margins,
     pub padding: Margins,
     pub width: Option<Dimension>,
     pub height: Option<Dimension>,
     pub min_height: Dimension,
+    /// Not inherited; width divided by height, used when only one dimension is known.
+    pub aspect_ratio: Option<f32>,
+    /// Not inherited; space inserted between adjacent rows of children.
+    pub row_gap: f32,
+    /// Not inherited; space inserted between adjacent items on a flex line.
+    pub column_gap: f32,
 }
 
 impl Eq for BoxModel {}
@@ -38,10 +45,13 @@ impl Hash for BoxModel {
         self.width.hash(state);
         self.height.hash(state);
         self.min_height.hash(state);
+        self.aspect_ratio.map(f32::to_bits).hash(state);
+        hash_f32(&self.row_gap, state);
+        hash_f32(&self.column_gap, state);
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
 pub struct BorderModel {
     pub top: Option<Border>,
     pub right: Option<Border>,
@@ -60,28 +70,42 @@ impl Hash for BorderModel {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct TextModel {
     pub font_family: Arc<String>,
     pub font_size: f32,
     pub font_weight: FontWeight,
     pub font_style: FontStyle,
+    pub font_source: FontSource,
     pub line_height: f32,
     pub text_align: TextAlign,
     pub text_decoration: TextDecoration,
+    pub white_space: WhiteSpace,
+    /// Not inherited; applies to the lines of the block it is set on.
+    pub text_overflow: TextOverflow,
+    /// Not inherited; the most lines a paragraph is laid out in.
+    pub max_lines: Option<usize>,
     pub color: Color,
 }
 
+/// The built-in font family, used when neither a style nor the layout
+/// configuration names one. The bundled fallback font provides it.
+pub const DEFAULT_FONT_FAMILY: &str = "Helvetica";
+
 impl Default for TextModel {
     fn default() -> Self {
         Self {
-            font_family: Arc::new("Helvetica".to_string()),
+            font_family: Arc::new(DEFAULT_FONT_FAMILY.to_string()),
             font_size: 12.0,
             font_weight: FontWeight::Regular,
             font_style: FontStyle::Normal,
+            font_source: FontSource::Embedded,
             line_height: 14.4,
             text_align: TextAlign::Left,
             text_decoration: TextDecoration::None,
+            white_space: WhiteSpace::Normal,
+            text_overflow: TextOverflow::Clip,
+            max_lines: None,
             color: Color::default(),
         }
     }
@@ -95,14 +119,18 @@ impl Hash for TextModel {
         hash_f32(&self.font_size, state);
         self.font_weight.hash(state);
         self.font_style.hash(state);
+        self.font_source.hash(state);
         hash_f32(&self.line_height, state);
         self.text_align.hash(state);
         self.text_decoration.hash(state);
+        self.white_space.hash(state);
+        self.text_overflow.hash(state);
+        self.max_lines.hash(state);
         self.color.hash(state);
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
 pub struct FlexModel {
     pub direction: FlexDirection,
     pub wrap: FlexWrap,
@@ -134,7 +162,7 @@ impl Hash for FlexModel {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
 pub struct ListModel {
     pub style_type: ListStyleType,
     pub style_position: ListStylePosition,
@@ -149,9 +177,10 @@ impl Hash for ListModel {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
 pub struct TableModel {
     pub border_spacing: f32,
+    pub border_collapse: BorderCollapse,
 }
 
 impl Eq for TableModel {}
@@ -159,14 +188,16 @@ impl Eq for TableModel {}
 impl Hash for TableModel {
     fn hash<H: Hasher>(&self, state: &mut H) {
         hash_f32(&self.border_spacing, state);
+        self.border_collapse.hash(state);
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MiscModel {
     pub widows: usize,
     pub orphans: usize,
     pub background_color: Option<Color>,
+    pub keep_together: bool,
 }
 
 impl Eq for MiscModel {}
@@ -176,6 +207,7 @@ impl Hash for MiscModel {
         self.widows.hash(state);
         self.orphans.hash(state);
         self.background_color.hash(state);
+        self.keep_together.hash(state);
     }
 }
 
@@ -185,12 +217,13 @@ impl Default for MiscModel {
             widows: 2,
             orphans: 2,
             background_color: None,
+            keep_together: false,
         }
     }
 }
 
 /// Holds the raw styling data. Separated from `ComputedStyle` to enforce safe hashing.
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub struct ComputedStyleData {
     pub box_model: BoxModel,
     pub border: BorderModel,
@@ -278,11 +311,13 @@ impl ComputedStyleData {
 
 /// A wrapper around style data that enforces hashing on construction.
 /// This prevents bugs where data changes but the hash doesn't.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
 pub struct ComputedStyle {
     /// The actual style data.
     pub inner: ComputedStyleData,
     /// Pre-calculated hash for rapid HashMap lookups (caching).
+    #[serde(skip)]
     cached_hash: u64,
 }
 
@@ -345,6 +380,7 @@ pub fn compute_style(
         computed_data.box_model = BoxModel::default();
         computed_data.border = BorderModel::default();
         computed_data.misc.background_color = None;
+        computed_data.misc.keep_together = false;
         computed_data.flex = FlexModel {
             shrink: 1.0, // Default shrink is 1.0
             ..Default::default()
@@ -354,15 +390,17 @@ pub fn compute_style(
         return Arc::new(ComputedStyle::new(computed_data));
     }
 
+    let declarations = || style_sets.iter().map(AsRef::as_ref).chain(style_override);
     let mut merged = ElementStyle::default();
-    for style_def in style_sets {
-        merge_element_styles(&mut merged, style_def);
+    for style_def in declarations() {
+        merged.merge(style_def);
     }
-    if let Some(override_style_def) = style_override {
-        merge_element_styles(&mut merged, override_style_def);
+    // `!important` declarations win over normal ones, wherever they come from.
+    for style_def in declarations().filter(|s| !s.important.is_empty()) {
+        merged.merge(&style_def.important_declarations());
     }
 
-    let computed_data = ComputedStyleData {
+    let mut computed_data = ComputedStyleData {
         text: TextModel {
             font_family: merged
                 .font_family
@@ -375,6 +413,7 @@ pub fn compute_style(
             font_style: merged
                 .font_style
                 .unwrap_or_else(|| parent_style.text.font_style.clone()),
+            font_source: merged.font_source.unwrap_or(parent_style.text.font_source),
             line_height: merged.line_height.unwrap_or_else(|| {
                 merged
                     .font_size
@@ -387,6 +426,9 @@ pub fn compute_style(
             text_decoration: merged
                 .text_decoration
                 .unwrap_or_else(|| parent_style.text.text_decoration.clone()),
+            white_space: merged.white_space.unwrap_or(parent_style.text.white_space),
+            text_overflow: merged.text_overflow.unwrap_or_default(),
+            max_lines: merged.max_lines,
             color: merged
                 .color
                 .unwrap_or_else(|| parent_style.text.color.clone()),
@@ -395,6 +437,7 @@ pub fn compute_style(
             widows: merged.widows.unwrap_or(parent_style.misc.widows),
             orphans: merged.orphans.unwrap_or(parent_style.misc.orphans),
             background_color: merged.background_color,
+            keep_together: merged.keep_together.unwrap_or(false),
         },
         list: ListModel {
             style_type: merged
@@ -408,6 +451,9 @@ pub fn compute_style(
             border_spacing: merged
                 .border_spacing
                 .unwrap_or(parent_style.table.border_spacing),
+            border_collapse: merged
+                .border_collapse
+                .unwrap_or(parent_style.table.border_collapse),
         },
         // Non-inherited properties
         box_model: BoxModel {
@@ -416,6 +462,9 @@ pub fn compute_style(
             width: merged.width,
             height: merged.height,
             min_height: Dimension::Auto,
+            aspect_ratio: merged.aspect_ratio,
+            row_gap: merged.row_gap.unwrap_or_default(),
+            column_gap: merged.column_gap.unwrap_or_default(),
         },
         border: BorderModel {
             top: merged.border_top.or_else(|| merged.border.clone()),
@@ -437,113 +486,74 @@ pub fn compute_style(
         },
     };
 
+    for (property, keyword) in &merged.keywords {
+        let source = match keyword {
+            StyleKeyword::Inherit => &parent_style.inner,
+            StyleKeyword::Initial => &initial_style_data(),
+        };
+        apply_style_keyword(&mut computed_data, source, property);
+    }
+
     Arc::new(ComputedStyle::new(computed_data))
 }
 
-/// Returns the default style for the document root.
-pub fn get_default_style() -> Arc<ComputedStyle> {
-    Arc::new(ComputedStyle::default())
+/// The computed values a property is reset to by the `initial` keyword.
+fn initial_style_data() -> ComputedStyleData {
+    let mut data = ComputedStyleData::default();
+    data.flex.shrink = 1.0;
+    data
 }
 
-/// Merges properties from `to_apply` into `base`.
-fn merge_element_styles(base: &mut ElementStyle, to_apply: &ElementStyle) {
-    if to_apply.font_family.is_some() {
-        base.font_family = to_apply.font_family.clone();
-    }
-    if to_apply.font_size.is_some() {
-        base.font_size = to_apply.font_size;
-    }
-    if to_apply.font_weight.is_some() {
-        base.font_weight = to_apply.font_weight.clone();
-    }
-    if to_apply.font_style.is_some() {
-        base.font_style = to_apply.font_style.clone();
-    }
-    if to_apply.line_height.is_some() {
-        base.line_height = to_apply.line_height;
-    }
-    if to_apply.text_align.is_some() {
-        base.text_align = to_apply.text_align.clone();
-    }
-    if to_apply.color.is_some() {
-        base.color = to_apply.color.clone();
-    }
-    if to_apply.text_decoration.is_some() {
-        base.text_decoration = to_apply.text_decoration.clone();
-    }
-    if to_apply.widows.is_some() {
-        base.widows = to_apply.widows;
-    }
-    if to_apply.orphans.is_some() {
-        base.orphans = to_apply.orphans;
-    }
-    if to_apply.background_color.is_some() {
-        base.background_color = to_apply.background_color.clone();
-    }
-    if to_apply.border.is_some() {
-        base.border = to_apply.border.clone();
-    }
-    if to_apply.border_top.is_some() {
-        base.border_top = to_apply.border_top.clone();
-    }
-    if to_apply.border_right.is_some() {
-        base.border_right = to_apply.border_right.clone();
-    }
-    if to_apply.border_bottom.is_some() {
-        base.border_bottom = to_apply.border_bottom.clone();
-    }
-    if to_apply.border_left.is_some() {
-        base.border_left = to_apply.border_left.clone();
-    }
-    if to_apply.margin.is_some() {
-        base.margin = to_apply.margin.clone();
-    }
-    if to_apply.padding.is_some() {
-        base.padding = to_apply.padding.clone();
-    }
-    if to_apply.width.is_some() {
-        base.width = to_apply.width.clone();
-    }
-    if to_apply.height.is_some() {
-        base.height = to_apply.height.clone();
-    }
-    if to_apply.list_style_type.is_some() {
-        base.list_style_type = to_apply.list_style_type.clone();
-    }
-    if to_apply.list_style_position.is_some() {
-        base.list_style_position = to_apply.list_style_position.clone();
-    }
-    if to_apply.list_style_image.is_some() {
-        base.list_style_image = to_apply.list_style_image.clone();
-    }
-    if to_apply.border_spacing.is_some() {
-        base.border_spacing = to_apply.border_spacing;
-    }
-    if to_apply.flex_direction.is_some() {
-        base.flex_direction = to_apply.flex_direction.clone();
-    }
-    if to_apply.flex_wrap.is_some() {
-        base.flex_wrap = to_apply.flex_wrap.clone();
-    }
-    if to_apply.justify_content.is_some() {
-        base.justify_content = to_apply.justify_content.clone();
-    }
-    if to_apply.align_items.is_some() {
-        base.align_items = to_apply.align_items.clone();
-    }
-    if to_apply.order.is_some() {
-        base.order = to_apply.order;
-    }
-    if to_apply.flex_grow.is_some() {
-        base.flex_grow = to_apply.flex_grow;
-    }
-    if to_apply.flex_shrink.is_some() {
-        base.flex_shrink = to_apply.flex_shrink;
-    }
-    if to_apply.flex_basis.is_some() {
-        base.flex_basis = to_apply.flex_basis.clone();
-    }
-    if to_apply.align_self.is_some() {
-        base.align_self = to_apply.align_self.clone();
+/// Copies the value of `property` from `source`, which is either the parent style
+/// (`inherit`) or the initial style (`initial`).
+fn apply_style_keyword(data: &mut ComputedStyleData, source: &ComputedStyleData, property: &str) {
+    match property {
+        "font-family" => data.text.font_family = source.text.font_family.clone(),
+        "font-size" => data.text.font_size = source.text.font_size,
+        "font-weight" => data.text.font_weight = source.text.font_weight.clone(),
+        "font-style" => data.text.font_style = source.text.font_style.clone(),
+        "font-source" => data.text.font_source = source.text.font_source,
+        "line-height" => data.text.line_height = source.text.line_height,
+        "text-align" => data.text.text_align = source.text.text_align.clone(),
+        "text-decoration" => data.text.text_decoration = source.text.text_decoration.clone(),
+        "white-space" => data.text.white_space = source.text.white_space,
+        "text-overflow" => data.text.text_overflow = source.text.text_overflow,
+        "max-lines" => data.text.max_lines = source.text.max_lines,
+        "color" => data.text.color = source.text.color.clone(),
+        "widows" => data.misc.widows = source.misc.widows,
+        "orphans" => data.misc.orphans = source.misc.orphans,
+        "background-color" => data.misc.background_color = source.misc.background_color.clone(),
+        "border" => data.border = source.border.clone(),
+        "border-top" => data.border.top = source.border.top.clone(),
+        "border-right" => data.border.right = source.border.right.clone(),
+        "border-bottom" => data.border.bottom = source.border.bottom.clone(),
+        "border-left" => data.border.left = source.border.left.clone(),
+        "margin" => data.box_model.margin = source.box_model.margin.clone(),
+        "padding" => data.box_model.padding = source.box_model.padding.clone(),
+        "width" => data.box_model.width = source.box_model.width.clone(),
+        "height" => data.box_model.height = source.box_model.height.clone(),
+        "aspect-ratio" => data.box_model.aspect_ratio = source.box_model.aspect_ratio,
+        "row-gap" => data.box_model.row_gap = source.box_model.row_gap,
+        "column-gap" => data.box_model.column_gap = source.box_model.column_gap,
+        "list-style-type" => data.list.style_type = source.list.style_type.clone(),
+        "list-style-position" => data.list.style_position = source.list.style_position.clone(),
+        "border-spacing" => data.table.border_spacing = source.table.border_spacing,
+        "border-collapse" => data.table.border_collapse = source.table.border_collapse,
+        "keep-together" => data.misc.keep_together = source.misc.keep_together,
+        "flex-direction" => data.flex.direction = source.flex.direction.clone(),
+        "flex-wrap" => data.flex.wrap = source.flex.wrap.clone(),
+        "justify-content" => data.flex.justify_content = source.flex.justify_content.clone(),
+        "align-items" => data.flex.align_items = source.flex.align_items.clone(),
+        "order" => data.flex.order = source.flex.order,
+        "flex-grow" => data.flex.grow = source.flex.grow,
+        "flex-shrink" => data.flex.shrink = source.flex.shrink,
+        "flex-basis" => data.flex.basis = source.flex.basis.clone(),
+        "align-self" => data.flex.align_self = source.flex.align_self.clone(),
+        _ => {}
     }
 }
+
+/// Returns the default style for the document root.
+pub fn get_default_style() -> Arc<ComputedStyle> {
+    Arc::new(ComputedStyle::default())
+}
@@ -1,6 +1,9 @@
 use crate::style::{ComputedStyle, compute_style, get_default_style};
+use crate::test_utils::create_test_engine;
+use petty_idf::{IRNode, InlineNode, NodeMetadata};
 use petty_style::dimension::{Dimension, Margins};
 use petty_style::font::FontWeight;
+use petty_style::parsers::parse_inline_css;
 use petty_style::stylesheet::ElementStyle;
 use petty_style::text::TextAlign;
 use petty_types::color::Color;
@@ -144,3 +147,124 @@ fn test_style_cascade_precedence() {
     // font_weight is only defined in Named Style 1. It should be applied.
     assert_eq!(computed.text.font_weight, FontWeight::Bold);
 }
+
+#[test]
+fn test_inherit_and_initial_keywords() {
+    let mut parent_data = get_default_style().inner.clone();
+    parent_data.text.color = Color::gray(100);
+    parent_data.box_model.padding = Margins::all(8.0);
+    let parent_arc = Arc::new(ComputedStyle::new(parent_data));
+
+    // A class sets its own color; the inline override takes it from the parent.
+    let class_style = Arc::new(ElementStyle {
+        color: Some(Color::gray(200)),
+        ..Default::default()
+    });
+    let mut inherit = ElementStyle::default();
+    parse_inline_css("color: inherit; padding: inherit", &mut inherit).unwrap();
+    let computed = compute_style(&[class_style], Some(&inherit), &parent_arc);
+    assert_eq!(computed.text.color, Color::gray(100));
+    assert_eq!(computed.box_model.padding, Margins::all(8.0));
+
+    // `initial` resets an inherited property to its default.
+    let mut initial = ElementStyle::default();
+    parse_inline_css("color: initial", &mut initial).unwrap();
+    let computed = compute_style(&[], Some(&initial), &parent_arc);
+    assert_eq!(computed.text.color, Color::default());
+
+    // A later explicit value wins over an earlier keyword.
+    let explicit = ElementStyle {
+        color: Some(Color::gray(200)),
+        ..Default::default()
+    };
+    let computed = compute_style(&[Arc::new(inherit)], Some(&explicit), &parent_arc);
+    assert_eq!(computed.text.color, Color::gray(200));
+}
+
+#[test]
+fn test_important_declarations_win_the_cascade() {
+    let parent = get_default_style();
+    let red = Color {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 1.0,
+    };
+    let blue = Color {
+        r: 0,
+        g: 0,
+        b: 255,
+        a: 1.0,
+    };
+
+    let mut important_red = ElementStyle::default();
+    parse_inline_css("color: #ff0000 !important", &mut important_red).unwrap();
+    assert_eq!(important_red.color, Some(red.clone()));
+    assert!(important_red.important.contains("color"));
+
+    let named_red = Arc::new(ElementStyle {
+        color: Some(red.clone()),
+        ..Default::default()
+    });
+    let mut important_blue = ElementStyle::default();
+    parse_inline_css(
+        "color: #0000ff !important; margin-top: 4pt !important",
+        &mut important_blue,
+    )
+    .unwrap();
+    let later_red = Arc::new(ElementStyle {
+        color: Some(red.clone()),
+        margin: Some(Margins::all(1.0)),
+        ..Default::default()
+    });
+
+    // An important inline override wins over named style sets...
+    let computed = compute_style(&[named_red, later_red], Some(&important_blue), &parent);
+    assert_eq!(computed.text.color, blue);
+    assert_eq!(computed.box_model.margin.top, 4.0);
+
+    // ...and an important named style wins over a normal inline override.
+    let normal_blue = ElementStyle {
+        color: Some(blue),
+        ..Default::default()
+    };
+    let computed = compute_style(&[Arc::new(important_red)], Some(&normal_blue), &parent);
+    assert_eq!(computed.text.color, red);
+}
+
+#[test]
+fn test_computed_style_for_id_resolves_cascade() {
+    let engine = create_test_engine();
+    let mut block_style = ElementStyle::default();
+    parse_inline_css("font-size: 20pt; color: #ff0000", &mut block_style).unwrap();
+    let mut paragraph_style = ElementStyle::default();
+    parse_inline_css("font-weight: bold; margin: 6pt", &mut paragraph_style).unwrap();
+
+    let root = IRNode::Root(vec![IRNode::Block {
+        meta: NodeMetadata {
+            style_sets: vec![Arc::new(block_style)],
+            ..Default::default()
+        },
+        children: vec![IRNode::Paragraph {
+            meta: NodeMetadata {
+                id: Some("intro".into()),
+                style_override: Some(paragraph_style),
+                ..Default::default()
+            },
+            children: vec![InlineNode::Text("Hello".into())],
+        }],
+    }]);
+
+    let computed = engine.computed_style_for_id(&root, "intro").unwrap();
+    assert_eq!(computed.text.font_size, 20.0);
+    assert_eq!(computed.text.font_weight, FontWeight::Bold);
+    assert_eq!(computed.box_model.margin, Margins::all(6.0));
+
+    let dumped = serde_json::to_value(&*computed).unwrap();
+    assert_eq!(dumped["text"]["font_size"], 20.0);
+    assert_eq!(dumped["text"]["color"]["r"], 255);
+    assert_eq!(dumped["box_model"]["margin"]["top"], 6.0);
+    assert!(dumped.get("cached_hash").is_none());
+
+    assert!(engine.computed_style_for_id(&root, "missing").is_none());
+}
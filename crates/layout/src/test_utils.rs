@@ -1,4 +1,5 @@
 use crate::LayoutError;
+use crate::config::LayoutConfig;
 use crate::engine::{LayoutEngine, LayoutStore};
 use crate::fonts::SharedFontLibrary;
 use crate::interface::{AnchorLocation, IndexEntry};
@@ -8,25 +9,37 @@ use std::collections::HashMap;
 
 use petty_idf::{IRNode, InlineNode, NodeMetadata};
 
+/// Pages of positioned elements, plus the anchors and index entries collected across them.
+pub type PaginatedTestOutput = (
+    Vec<Vec<PositionedElement>>,
+    HashMap<String, AnchorLocation>,
+    HashMap<String, Vec<IndexEntry>>,
+);
+
 /// Creates a default layout engine for testing purposes.
 pub fn create_test_engine() -> LayoutEngine {
+    create_test_engine_with_config(Default::default())
+}
+
+pub fn create_test_engine_with_config(config: LayoutConfig) -> LayoutEngine {
     let library = SharedFontLibrary::new();
     library.load_fallback_font();
-    LayoutEngine::new(&library, Default::default())
+    LayoutEngine::new(&library, config)
 }
 
 pub fn paginate_test_nodes(
     stylesheet: Stylesheet,
     nodes: Vec<IRNode>,
-) -> Result<
-    (
-        Vec<Vec<PositionedElement>>,
-        HashMap<String, AnchorLocation>,
-        HashMap<String, Vec<IndexEntry>>,
-    ),
-    LayoutError,
-> {
-    let engine = create_test_engine();
+) -> Result<PaginatedTestOutput, LayoutError> {
+    paginate_test_nodes_with_config(stylesheet, nodes, Default::default())
+}
+
+pub fn paginate_test_nodes_with_config(
+    stylesheet: Stylesheet,
+    nodes: Vec<IRNode>,
+    config: LayoutConfig,
+) -> Result<PaginatedTestOutput, LayoutError> {
+    let engine = create_test_engine_with_config(config);
     let store = LayoutStore::new();
     let ir_root = IRNode::Root(nodes);
 
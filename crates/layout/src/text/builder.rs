@@ -18,6 +18,18 @@ pub struct InlineImageEntry<'a> {
     pub node: &'a ImageNode<'a>,
 }
 
+/// An inline sparkline, placed at byte `index` of the paragraph text.
+#[derive(Debug, Clone)]
+pub struct InlineSparklineEntry<'a> {
+    pub index: usize,
+    pub width: f32,
+    pub height: f32,
+    /// The polyline points, as fractions of the sparkline's box (see [`PathElement`]).
+    ///
+    /// [`PathElement`]: crate::elements::PathElement
+    pub points: &'a [(f32, f32)],
+}
+
 pub struct TextBuilder<'a, 'b> {
     engine: &'a LayoutEngine,
     store: &'b LayoutStore,
@@ -25,6 +37,7 @@ pub struct TextBuilder<'a, 'b> {
     span_ranges: Vec<(std::ops::Range<usize>, Arc<ComputedStyle>, usize)>,
     pub links: Vec<String>,
     pub inline_images: Vec<InlineImageEntry<'b>>,
+    pub inline_sparklines: Vec<InlineSparklineEntry<'b>>,
 }
 
 impl<'a, 'b> TextBuilder<'a, 'b> {
@@ -40,6 +53,7 @@ impl<'a, 'b> TextBuilder<'a, 'b> {
             span_ranges: Vec::new(),
             links: Vec::new(),
             inline_images: Vec::new(),
+            inline_sparklines: Vec::new(),
         }
     }
 
@@ -53,6 +67,7 @@ impl<'a, 'b> TextBuilder<'a, 'b> {
         &'b str,
         &'b [TextSpan<'b>],
         Vec<InlineImageEntry<'b>>,
+        Vec<InlineSparklineEntry<'b>>,
         Vec<String>,
     ) {
         let full_text = self.store.alloc_str(&self.raw_content);
@@ -68,6 +83,7 @@ impl<'a, 'b> TextBuilder<'a, 'b> {
             full_text,
             self.store.bump.alloc_slice_clone(&spans),
             self.inline_images,
+            self.inline_sparklines,
             self.links,
         )
     }
@@ -91,12 +107,10 @@ impl<'a, 'b> TextBuilder<'a, 'b> {
                         && Arc::ptr_eq(&last.1, &style)
                         && last.2 == current_link_idx
                     {
-                        let is_image_span = if let Some(last_img) = self.inline_images.last() {
-                            last_img.index == last.0.start
-                        } else {
-                            false
-                        };
-                        if !is_image_span {
+                        let is_object_span = self.inline_images.last().map(|img| img.index)
+                            == Some(last.0.start)
+                            || self.inline_sparklines.last().map(|s| s.index) == Some(last.0.start);
+                        if !is_object_span {
                             last.0.end = end;
                             merged = true;
                         }
@@ -157,6 +171,25 @@ impl<'a, 'b> TextBuilder<'a, 'b> {
                         self.span_ranges.push((start..end, style, current_link_idx));
                     }
                 }
+                InlineNode::Sparkline {
+                    values,
+                    width,
+                    height,
+                    ..
+                } => {
+                    let start = self.raw_content.len();
+                    self.raw_content.push('\u{FFFC}');
+                    let end = self.raw_content.len();
+                    let points = sparkline_points(values);
+                    self.inline_sparklines.push(InlineSparklineEntry {
+                        index: start,
+                        width: *width,
+                        height: *height,
+                        points: self.store.bump.alloc_slice_copy(&points),
+                    });
+                    let style = parent_style.clone();
+                    self.span_ranges.push((start..end, style, current_link_idx));
+                }
             }
         }
     }
@@ -170,3 +203,34 @@ impl<'a, 'b> TextBuilder<'a, 'b> {
             .compute_style(&meta.style_sets, meta.style_override.as_ref(), parent_style)
     }
 }
+
+/// Scales `values` to fit a unit box: the series spans the full width, and the range
+/// between its smallest and largest value spans the full height (a flat series is
+/// drawn through the middle).
+fn sparkline_points(values: &[f64]) -> Vec<(f32, f32)> {
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    let range = max - min;
+    let y = |v: f64| {
+        if range > 0.0 {
+            (1.0 - (v - min) / range) as f32
+        } else {
+            0.5
+        }
+    };
+    match values {
+        [] => Vec::new(),
+        [v] => vec![(0.0, y(*v)), (1.0, y(*v))],
+        _ => {
+            let last = (values.len() - 1) as f32;
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| (i as f32 / last, y(v)))
+                .collect()
+        }
+    }
+}
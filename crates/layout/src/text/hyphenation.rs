@@ -0,0 +1,218 @@
+//! Hyphenation dictionaries, registered per language and loaded on first use.
+//!
+//! Dictionaries use the TeX pattern format of Liang's algorithm: whitespace
+//! separated patterns such as `hy3ph` or `.ach4`, where a digit between two
+//! letters is the break priority there and odd priorities allow a break. Entries
+//! containing `-`, such as `ta-ble`, are exceptions giving a word's break points
+//! outright. Lines starting with `%` are comments.
+//!
+//! The registry lives on the [`SharedFontLibrary`], so every layout engine using
+//! the library sees the same dictionaries and parses each one at most once.
+//!
+//! [`SharedFontLibrary`]: crate::fonts::SharedFontLibrary
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// The fewest characters left before a hyphen, as in TeX's `\lefthyphenmin`.
+const LEFT_MIN: usize = 2;
+/// The fewest characters carried after a hyphen, as in TeX's `\righthyphenmin`.
+const RIGHT_MIN: usize = 3;
+
+/// A parsed hyphenation dictionary.
+#[derive(Debug, Default)]
+pub struct HyphenationPatterns {
+    /// Pattern letters -> priorities between them, one more than the letters.
+    patterns: HashMap<Box<str>, Box<[u8]>>,
+    /// Lowercased word -> character indices a break is allowed before.
+    exceptions: HashMap<Box<str>, Box<[usize]>>,
+    /// The most letters in a pattern, which bounds the substrings looked up.
+    max_pattern_len: usize,
+}
+
+impl HyphenationPatterns {
+    pub fn parse(source: &str) -> Self {
+        let mut parsed = Self::default();
+        let entries = source
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('%'))
+            .flat_map(str::split_whitespace);
+        for entry in entries {
+            if entry.contains('-') {
+                let mut breaks = Vec::new();
+                let mut word = String::new();
+                for c in entry.chars() {
+                    if c == '-' {
+                        breaks.push(word.chars().count());
+                    } else {
+                        word.extend(c.to_lowercase());
+                    }
+                }
+                parsed.exceptions.insert(word.into(), breaks.into());
+                continue;
+            }
+
+            let mut letters = String::new();
+            let mut priorities = vec![0u8];
+            for c in entry.chars() {
+                match c.to_digit(10) {
+                    Some(digit) => *priorities.last_mut().unwrap() = digit as u8,
+                    None => {
+                        letters.extend(c.to_lowercase());
+                        priorities.push(0);
+                    }
+                }
+            }
+            parsed.max_pattern_len = parsed.max_pattern_len.max(priorities.len() - 1);
+            parsed.patterns.insert(letters.into(), priorities.into());
+        }
+        parsed
+    }
+
+    /// The byte offsets in `word` where it may be hyphenated, in ascending order.
+    pub fn hyphenate(&self, word: &str) -> Vec<usize> {
+        let offsets: Vec<usize> = word.char_indices().map(|(offset, _)| offset).collect();
+        let len = offsets.len();
+        if len < LEFT_MIN + RIGHT_MIN {
+            return Vec::new();
+        }
+
+        let lower: String = word.chars().flat_map(char::to_lowercase).collect();
+        if let Some(breaks) = self.exceptions.get(lower.as_str()) {
+            return breaks
+                .iter()
+                .filter(|&&index| index > 0 && index < len)
+                .map(|&index| offsets[index])
+                .collect();
+        }
+
+        // `points[i]` is the priority before character `i` of `.word.`.
+        let dotted: Vec<char> = format!(".{lower}.").chars().collect();
+        // Lowercasing may change the character count, and with it the positions.
+        if dotted.len() != len + 2 {
+            return Vec::new();
+        }
+        let mut points = vec![0u8; dotted.len() + 1];
+        let mut key = String::new();
+        for start in 0..dotted.len() {
+            key.clear();
+            let end = dotted.len().min(start + self.max_pattern_len);
+            for &c in &dotted[start..end] {
+                key.push(c);
+                if let Some(priorities) = self.patterns.get(key.as_str()) {
+                    for (i, &priority) in priorities.iter().enumerate() {
+                        points[start + i] = points[start + i].max(priority);
+                    }
+                }
+            }
+        }
+
+        (LEFT_MIN..=len - RIGHT_MIN)
+            .filter(|&index| points[index + 1] % 2 == 1)
+            .map(|index| offsets[index])
+            .collect()
+    }
+}
+
+/// A registered dictionary, parsed the first time a word is hyphenated with it.
+struct PatternSource {
+    source: Vec<u8>,
+    patterns: OnceLock<HyphenationPatterns>,
+}
+
+impl PatternSource {
+    fn patterns(&self) -> &HyphenationPatterns {
+        self.patterns
+            .get_or_init(|| HyphenationPatterns::parse(&String::from_utf8_lossy(&self.source)))
+    }
+}
+
+/// Hyphenation dictionaries by language tag.
+///
+/// Tags are matched case-insensitively, and a tag with a region such as `en-GB`
+/// falls back to its language (`en`) when no dictionary is registered for it.
+#[derive(Default)]
+pub struct HyphenationRegistry {
+    languages: RwLock<HashMap<String, Arc<PatternSource>>>,
+}
+
+impl HyphenationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the pattern file for `language`, replacing any registered before.
+    /// The patterns are not parsed until a word is hyphenated with them.
+    pub fn register(&self, language: &str, patterns: impl Into<Vec<u8>>) {
+        let source = Arc::new(PatternSource {
+            source: patterns.into(),
+            patterns: OnceLock::new(),
+        });
+        if let Ok(mut languages) = self.languages.write() {
+            languages.insert(normalize_language(language), source);
+        }
+    }
+
+    /// Whether a dictionary is registered for `language` or its base language.
+    pub fn has_language(&self, language: &str) -> bool {
+        self.lookup(language).is_some()
+    }
+
+    /// Whether the dictionary for `language` has been parsed yet.
+    pub fn is_loaded(&self, language: &str) -> bool {
+        self.lookup(language)
+            .is_some_and(|source| source.patterns.get().is_some())
+    }
+
+    /// The byte offsets in `word` where it may be hyphenated, or none when no
+    /// dictionary is registered for `language`.
+    pub fn hyphenate(&self, language: &str, word: &str) -> Vec<usize> {
+        match self.lookup(language) {
+            Some(source) => source.patterns().hyphenate(word),
+            None => Vec::new(),
+        }
+    }
+
+    fn lookup(&self, language: &str) -> Option<Arc<PatternSource>> {
+        let languages = self.languages.read().ok()?;
+        let tag = normalize_language(language);
+        languages.get(&tag).cloned().or_else(|| {
+            let (base, _) = tag.split_once('-')?;
+            languages.get(base).cloned()
+        })
+    }
+}
+
+fn normalize_language(language: &str) -> String {
+    language.trim().to_lowercase().replace('_', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered_patterns_load_lazily_and_hyphenate() {
+        let registry = HyphenationRegistry::new();
+        // The patterns Knuth uses to hyphenate "hyphenation" in The TeXbook.
+        registry.register(
+            "en",
+            "% sample\nhy3ph he2n hena4 hen5at 1na n2at 1tio 2io o2n\nta-ble",
+        );
+        assert!(registry.has_language("en-US"));
+        assert!(!registry.is_loaded("en"));
+
+        let word = "hyphenation";
+        let breaks = registry.hyphenate("en-US", word);
+        assert!(registry.is_loaded("en"));
+        assert_eq!(breaks, vec![2, 6]);
+        let parts: Vec<&str> = [0, 2, 6, word.len()]
+            .windows(2)
+            .map(|w| &word[w[0]..w[1]])
+            .collect();
+        assert_eq!(parts, ["hy", "phen", "ation"]);
+
+        assert_eq!(registry.hyphenate("en", "Table"), vec![2]);
+        assert!(registry.hyphenate("de", word).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+//! A string-advance cache shared by every layout engine using the same font library.
+//!
+//! Documents generated from one template measure the same strings (list markers,
+//! labels, repeated values) over and over. Each worker has its own `LayoutEngine`,
+//! so the cache lives behind an `Arc` on the [`SharedFontLibrary`] and is shared by
+//! all of them.
+//!
+//! [`SharedFontLibrary`]: crate::fonts::SharedFontLibrary
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The default maximum number of cached strings.
+pub const DEFAULT_MEASUREMENT_CACHE_CAPACITY: usize = 65_536;
+
+/// Font identity and size in points (as raw bits, so it can be hashed).
+type FontSizeKey = (usize, u32);
+
+/// A thread-safe cache of string advance widths, keyed by font, font size and text.
+///
+/// When the number of cached strings reaches the capacity, the cache is cleared
+/// rather than growing without bound.
+pub struct TextMeasurementCache {
+    widths: RwLock<HashMap<FontSizeKey, HashMap<Box<str>, f32>>>,
+    capacity: usize,
+    len: AtomicUsize,
+    hits: AtomicUsize,
+    measurements: AtomicUsize,
+}
+
+impl TextMeasurementCache {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_MEASUREMENT_CACHE_CAPACITY)
+    }
+
+    /// Creates a cache holding at most `capacity` strings.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            widths: RwLock::new(HashMap::new()),
+            capacity,
+            len: AtomicUsize::new(0),
+            hits: AtomicUsize::new(0),
+            measurements: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the cached width of `text`, or computes it with `measure` and caches it.
+    ///
+    /// `font_id` identifies the loaded font, see [`FontInstance::id`].
+    ///
+    /// [`FontInstance::id`]: crate::fonts::FontInstance::id
+    pub fn get_or_measure(
+        &self,
+        font_id: usize,
+        font_size: f32,
+        text: &str,
+        measure: impl FnOnce() -> f32,
+    ) -> f32 {
+        let key = (font_id, font_size.to_bits());
+        if let Ok(widths) = self.widths.read()
+            && let Some(&width) = widths.get(&key).and_then(|texts| texts.get(text))
+        {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return width;
+        }
+
+        let width = measure();
+        self.measurements.fetch_add(1, Ordering::Relaxed);
+
+        if let Ok(mut widths) = self.widths.write() {
+            if self.len.load(Ordering::Relaxed) >= self.capacity {
+                widths.clear();
+                self.len.store(0, Ordering::Relaxed);
+            }
+            if widths
+                .entry(key)
+                .or_default()
+                .insert(text.into(), width)
+                .is_none()
+            {
+                self.len.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        width
+    }
+
+    /// The number of cached strings.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of lookups answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of strings actually measured because they were not cached.
+    pub fn measurements(&self) -> usize {
+        self.measurements.load(Ordering::Relaxed)
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut widths) = self.widths.write() {
+            widths.clear();
+            self.len.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Default for TextMeasurementCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_clears_at_capacity() {
+        let cache = TextMeasurementCache::with_capacity(2);
+        assert_eq!(cache.get_or_measure(1, 12.0, "a", || 5.0), 5.0);
+        assert_eq!(cache.get_or_measure(1, 12.0, "a", || unreachable!()), 5.0);
+        cache.get_or_measure(1, 10.0, "a", || 4.0);
+        assert_eq!(cache.len(), 2);
+
+        cache.get_or_measure(2, 12.0, "a", || 6.0);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get_or_measure(1, 12.0, "a", || 5.5), 5.5);
+        assert_eq!((cache.hits(), cache.measurements()), (1, 4));
+    }
+}
@@ -1,3 +1,6 @@
 pub mod builder;
+pub mod hyphenation;
+pub mod measure;
 pub mod shaper;
+pub mod standard_fonts;
 pub mod wrapper;
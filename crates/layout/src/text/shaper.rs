@@ -1,7 +1,8 @@
 use crate::LayoutEngine;
 use crate::interface::LayoutNode;
 use crate::style::ComputedStyle;
-use crate::text::builder::{InlineImageEntry, TextSpan};
+use crate::text::builder::{InlineImageEntry, InlineSparklineEntry, TextSpan};
+use crate::text::standard_fonts::{StandardFont, win_ansi_code};
 use rustybuzz::{Feature, UnicodeBuffer};
 use std::cell::RefCell;
 use std::sync::Arc;
@@ -29,7 +30,10 @@ pub struct ShapedRun {
     pub font_data: Option<crate::fonts::FontData>,
     pub font_size: f32,
     pub link_index: usize,
+    /// Whether the run is an atomic inline box (an image or sparkline) rather than text.
     pub is_image: bool,
+    /// The polyline of an inline sparkline, see [`crate::elements::PathElement`].
+    pub path: Option<Arc<[(f32, f32)]>>,
     pub text_range: std::ops::Range<usize>,
     pub ascender: f32,
     pub line_height: f32,
@@ -40,6 +44,7 @@ pub fn shape_text(
     engine: &LayoutEngine,
     spans: &[TextSpan],
     images: &[InlineImageEntry],
+    sparklines: &[InlineSparklineEntry],
 ) -> Vec<ShapedRun> {
     let mut runs = Vec::new();
     let mut current_char_idx = 0;
@@ -82,6 +87,7 @@ pub fn shape_text(
                 font_size: 0.0,
                 link_index: span.link_index,
                 is_image: true,
+                path: None,
                 text_range: current_char_idx..(current_char_idx + span_len),
                 ascender: height,
                 line_height: height,
@@ -95,13 +101,42 @@ pub fn shape_text(
             continue;
         }
 
+        if let Some(sparkline) = sparklines.iter().find(|s| s.index == current_char_idx) {
+            runs.push(ShapedRun {
+                glyphs: Vec::new(),
+                width: sparkline.width,
+                style: span.style.clone(),
+                font_data: None,
+                font_size: 0.0,
+                link_index: span.link_index,
+                is_image: true,
+                path: Some(sparkline.points.into()),
+                text_range: current_char_idx..(current_char_idx + span_len),
+                ascender: sparkline.height,
+                line_height: sparkline.height,
+                baseline_offset: sparkline.height,
+            });
+
+            last_style_ref = None;
+            last_font_data = None;
+
+            current_char_idx += span_len;
+            continue;
+        }
+
+        if let Some(font) = LayoutEngine::standard_font(&span.style) {
+            runs.push(shape_standard_font(font, span, current_char_idx));
+            current_char_idx += span_len;
+            continue;
+        }
+
         let font_data = if let Some(last) = last_style_ref {
             if **last == *span.style {
                 last_font_data.clone()
             } else {
                 let fd = engine
                     .get_font_for_style(&span.style)
-                    .or_else(|| engine.get_font_for_style(&engine.get_default_style()));
+                    .or_else(|| engine.get_fallback_font());
                 last_style_ref = Some(&span.style);
                 last_font_data = fd.clone();
                 fd
@@ -109,7 +144,7 @@ pub fn shape_text(
         } else {
             let fd = engine
                 .get_font_for_style(&span.style)
-                .or_else(|| engine.get_font_for_style(&engine.get_default_style()));
+                .or_else(|| engine.get_fallback_font());
             last_style_ref = Some(&span.style);
             last_font_data = fd.clone();
             fd
@@ -173,6 +208,7 @@ pub fn shape_text(
             font_size: span.style.text.font_size,
             link_index: span.link_index,
             is_image: false,
+            path: None,
             text_range: current_char_idx..(current_char_idx + span_len),
             ascender,
             line_height: style_line_height,
@@ -184,3 +220,39 @@ pub fn shape_text(
 
     runs
 }
+
+/// Lays out a span set in a PDF standard font with its AFM advance widths, one
+/// glyph per character. The PDF renderer does not kern standard-font text, so
+/// neither does this.
+fn shape_standard_font(font: StandardFont, span: &TextSpan, start: usize) -> ShapedRun {
+    let font_size = span.style.text.font_size;
+    let scale = font_size / 1000.0;
+    let glyphs: Vec<GlyphInstance> = span
+        .text
+        .char_indices()
+        .map(|(offset, c)| GlyphInstance {
+            index: u32::from(win_ansi_code(c).unwrap_or(b'?')),
+            x_offset: 0.0,
+            y_offset: 0.0,
+            x_advance: f32::from(font.char_width(c)) * scale,
+            cluster: (start + offset) as u32,
+        })
+        .collect();
+
+    let ascender = f32::from(font.ascender()) * scale;
+    let style_line_height = span.style.text.line_height;
+    ShapedRun {
+        width: glyphs.iter().map(|g| g.x_advance).sum(),
+        glyphs,
+        style: span.style.clone(),
+        font_data: None,
+        font_size,
+        link_index: span.link_index,
+        is_image: false,
+        path: None,
+        text_range: start..(start + span.text.len()),
+        ascender,
+        line_height: style_line_height,
+        baseline_offset: (style_line_height - (style_line_height - ascender)) / 2.0 + ascender,
+    }
+}
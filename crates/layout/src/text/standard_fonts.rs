@@ -0,0 +1,247 @@
+//! Metrics of the PDF standard fonts, for text set with `font-source: standard`.
+//!
+//! Every PDF viewer provides the standard fonts, so the document references them
+//! by name and embeds nothing. Layout measures them with the advance widths of
+//! their Adobe Font Metrics (AFM) files, indexed by WinAnsi code, which is the
+//! encoding the PDF renderer writes text in. Symbol and ZapfDingbats are not
+//! text fonts and are not offered.
+
+use crate::style::TextModel;
+use petty_style::{FontStyle, FontWeight};
+
+/// One of the twelve Latin text faces of the PDF standard fonts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StandardFont {
+    Helvetica,
+    HelveticaBold,
+    HelveticaOblique,
+    HelveticaBoldOblique,
+    TimesRoman,
+    TimesBold,
+    TimesItalic,
+    TimesBoldItalic,
+    Courier,
+    CourierBold,
+    CourierOblique,
+    CourierBoldOblique,
+}
+
+impl StandardFont {
+    /// The face for a text style, or `None` when its family is not one of
+    /// Helvetica, Times or Courier (or a common alias of them).
+    pub fn for_style(text: &TextModel) -> Option<Self> {
+        let bold = match text.font_weight {
+            FontWeight::Bold | FontWeight::Black => true,
+            FontWeight::Numeric(weight) => weight >= 600,
+            _ => false,
+        };
+        let slanted = text.font_style != FontStyle::Normal;
+        let faces = match text.font_family.to_lowercase().as_str() {
+            "helvetica" | "arial" | "sans-serif" => [
+                Self::Helvetica,
+                Self::HelveticaBold,
+                Self::HelveticaOblique,
+                Self::HelveticaBoldOblique,
+            ],
+            "times" | "times-roman" | "times new roman" | "serif" => [
+                Self::TimesRoman,
+                Self::TimesBold,
+                Self::TimesItalic,
+                Self::TimesBoldItalic,
+            ],
+            "courier" | "courier new" | "monospace" => [
+                Self::Courier,
+                Self::CourierBold,
+                Self::CourierOblique,
+                Self::CourierBoldOblique,
+            ],
+            _ => return None,
+        };
+        Some(faces[usize::from(bold) + 2 * usize::from(slanted)])
+    }
+
+    /// The PostScript name the font is referenced by, its `/BaseFont`.
+    pub fn base_font_name(self) -> &'static str {
+        match self {
+            Self::Helvetica => "Helvetica",
+            Self::HelveticaBold => "Helvetica-Bold",
+            Self::HelveticaOblique => "Helvetica-Oblique",
+            Self::HelveticaBoldOblique => "Helvetica-BoldOblique",
+            Self::TimesRoman => "Times-Roman",
+            Self::TimesBold => "Times-Bold",
+            Self::TimesItalic => "Times-Italic",
+            Self::TimesBoldItalic => "Times-BoldItalic",
+            Self::Courier => "Courier",
+            Self::CourierBold => "Courier-Bold",
+            Self::CourierOblique => "Courier-Oblique",
+            Self::CourierBoldOblique => "Courier-BoldOblique",
+        }
+    }
+
+    /// The ascender, in thousandths of the font size.
+    pub fn ascender(self) -> u16 {
+        match self {
+            Self::Helvetica
+            | Self::HelveticaBold
+            | Self::HelveticaOblique
+            | Self::HelveticaBoldOblique => 718,
+            Self::TimesRoman | Self::TimesItalic => 683,
+            Self::TimesBold => 676,
+            Self::TimesBoldItalic => 699,
+            Self::Courier | Self::CourierOblique => 629,
+            Self::CourierBold | Self::CourierBoldOblique => 626,
+        }
+    }
+
+    /// The advance width of `c`, in thousandths of the font size. Characters
+    /// outside WinAnsi are rendered as `?` and measured as one.
+    pub fn char_width(self, c: char) -> u16 {
+        let code = win_ansi_code(c).unwrap_or(b'?');
+        if code < 32 {
+            return 0;
+        }
+        let widths = match self {
+            Self::Helvetica | Self::HelveticaOblique => &HELVETICA,
+            Self::HelveticaBold | Self::HelveticaBoldOblique => &HELVETICA_BOLD,
+            Self::TimesRoman => &TIMES_ROMAN,
+            Self::TimesBold => &TIMES_BOLD,
+            Self::TimesItalic => &TIMES_ITALIC,
+            Self::TimesBoldItalic => &TIMES_BOLD_ITALIC,
+            Self::Courier | Self::CourierBold | Self::CourierOblique | Self::CourierBoldOblique => {
+                return 600;
+            }
+        };
+        widths[usize::from(code - 32)]
+    }
+
+    /// The advance width of `text` set at `font_size`, in points.
+    pub fn text_width(self, text: &str, font_size: f32) -> f32 {
+        let units: u32 = text.chars().map(|c| u32::from(self.char_width(c))).sum();
+        units as f32 * font_size / 1000.0
+    }
+}
+
+/// The WinAnsi code of `c`, if the encoding has it.
+pub fn win_ansi_code(c: char) -> Option<u8> {
+    let code = match c {
+        '\u{0}'..='\u{7F}' | '\u{A0}'..='\u{FF}' => c as u32 as u8,
+        '\u{20AC}' => 0x80,
+        '\u{201A}' => 0x82,
+        '\u{0192}' => 0x83,
+        '\u{201E}' => 0x84,
+        '\u{2026}' => 0x85,
+        '\u{2020}' => 0x86,
+        '\u{2021}' => 0x87,
+        '\u{02C6}' => 0x88,
+        '\u{2030}' => 0x89,
+        '\u{0160}' => 0x8A,
+        '\u{2039}' => 0x8B,
+        '\u{0152}' => 0x8C,
+        '\u{017D}' => 0x8E,
+        '\u{2018}' => 0x91,
+        '\u{2019}' => 0x92,
+        '\u{201C}' => 0x93,
+        '\u{201D}' => 0x94,
+        '\u{2022}' => 0x95,
+        '\u{2013}' => 0x96,
+        '\u{2014}' => 0x97,
+        '\u{02DC}' => 0x98,
+        '\u{2122}' => 0x99,
+        '\u{0161}' => 0x9A,
+        '\u{203A}' => 0x9B,
+        '\u{0153}' => 0x9C,
+        '\u{017E}' => 0x9E,
+        '\u{0178}' => 0x9F,
+        _ => return None,
+    };
+    Some(code)
+}
+
+// Advance widths of WinAnsi codes 32 to 255, in thousandths of the font size.
+// The oblique faces share the widths of their upright ones, and Courier is
+// monospaced at 600.
+
+const HELVETICA: [u16; 224] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+    0, 744, 0, 222, 556, 333, 1000, 556, 556, 333, 1000, 667, 333, 1000, 0, 611, 0, 0, 222, 222,
+    333, 333, 350, 556, 1000, 333, 1000, 500, 333, 944, 0, 500, 667, 278, 333, 556, 556, 556, 556,
+    260, 556, 333, 737, 370, 556, 584, 333, 737, 333, 400, 549, 333, 333, 333, 576, 537, 278, 333,
+    333, 365, 556, 834, 834, 834, 611, 667, 667, 667, 667, 667, 667, 1000, 722, 667, 667, 667, 667,
+    278, 278, 278, 278, 722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722, 722, 667, 667,
+    611, 556, 556, 556, 556, 556, 556, 889, 500, 556, 556, 556, 556, 278, 278, 278, 278, 556, 556,
+    556, 556, 556, 556, 556, 549, 611, 556, 556, 556, 556, 500, 556, 500,
+];
+const HELVETICA_BOLD: [u16; 224] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+    0, 744, 0, 278, 556, 500, 1000, 556, 556, 333, 1000, 667, 333, 1000, 0, 611, 0, 0, 278, 278,
+    500, 500, 350, 556, 1000, 333, 1000, 556, 333, 944, 0, 500, 667, 278, 333, 556, 556, 556, 556,
+    280, 556, 333, 737, 370, 556, 584, 333, 737, 333, 400, 549, 333, 333, 333, 576, 556, 278, 333,
+    333, 365, 556, 834, 834, 834, 611, 722, 722, 722, 722, 722, 722, 1000, 722, 667, 667, 667, 667,
+    278, 278, 278, 278, 722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722, 722, 667, 667,
+    611, 556, 556, 556, 556, 556, 556, 889, 556, 556, 556, 556, 556, 278, 278, 278, 278, 611, 611,
+    611, 611, 611, 611, 611, 549, 611, 611, 611, 611, 611, 556, 611, 556,
+];
+const TIMES_ROMAN: [u16; 224] = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444, 921, 722, 667, 667, 722, 611,
+    556, 722, 722, 333, 389, 722, 611, 889, 722, 722, 556, 722, 667, 556, 611, 722, 722, 944, 722,
+    722, 611, 333, 278, 333, 469, 500, 333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500,
+    278, 778, 500, 500, 500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+    0, 500, 0, 333, 500, 444, 1000, 500, 500, 333, 1000, 556, 333, 889, 0, 611, 0, 0, 333, 333,
+    444, 444, 350, 500, 1000, 333, 980, 389, 333, 722, 0, 444, 722, 250, 333, 500, 500, 500, 500,
+    200, 500, 333, 760, 276, 500, 564, 333, 760, 500, 400, 549, 300, 300, 333, 576, 453, 250, 333,
+    300, 310, 500, 750, 750, 750, 444, 722, 722, 722, 722, 722, 722, 889, 667, 611, 611, 611, 611,
+    333, 333, 333, 333, 722, 722, 722, 722, 722, 722, 722, 564, 722, 722, 722, 722, 722, 722, 556,
+    500, 444, 444, 444, 444, 444, 444, 667, 444, 444, 444, 444, 444, 278, 278, 278, 278, 500, 500,
+    500, 500, 500, 500, 500, 549, 500, 500, 500, 500, 500, 500, 500, 500,
+];
+const TIMES_BOLD: [u16; 224] = [
+    250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500, 930, 722, 667, 722, 722, 667,
+    611, 778, 778, 389, 500, 778, 667, 944, 722, 778, 611, 778, 722, 556, 667, 722, 722, 1000, 722,
+    722, 667, 333, 278, 333, 581, 500, 333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556,
+    278, 833, 556, 500, 556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+    0, 500, 0, 333, 500, 500, 1000, 500, 500, 333, 1000, 556, 333, 1000, 0, 667, 0, 0, 333, 333,
+    500, 500, 350, 500, 1000, 333, 1000, 389, 333, 722, 0, 444, 722, 250, 333, 500, 500, 500, 500,
+    220, 500, 333, 747, 300, 500, 570, 333, 747, 500, 400, 549, 300, 300, 333, 576, 540, 250, 333,
+    300, 330, 500, 750, 750, 750, 500, 722, 722, 722, 722, 722, 722, 1000, 722, 667, 667, 667, 667,
+    389, 389, 389, 389, 722, 722, 778, 778, 778, 778, 778, 570, 778, 722, 722, 722, 722, 722, 611,
+    556, 500, 500, 500, 500, 500, 500, 722, 444, 444, 444, 444, 444, 278, 278, 278, 278, 500, 556,
+    500, 500, 500, 500, 500, 549, 500, 556, 556, 556, 556, 500, 556, 500,
+];
+const TIMES_ITALIC: [u16; 224] = [
+    250, 333, 420, 500, 500, 833, 778, 214, 333, 333, 500, 675, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 675, 675, 675, 500, 920, 611, 611, 667, 722, 611,
+    611, 722, 722, 333, 444, 667, 556, 833, 667, 722, 611, 722, 611, 500, 556, 722, 611, 833, 611,
+    556, 556, 389, 278, 389, 422, 500, 333, 500, 500, 444, 500, 444, 278, 500, 500, 278, 278, 444,
+    278, 722, 500, 500, 500, 500, 389, 389, 278, 500, 444, 667, 444, 444, 389, 400, 275, 400, 541,
+    0, 500, 0, 333, 500, 556, 889, 500, 500, 333, 1000, 500, 333, 944, 0, 556, 0, 0, 333, 333, 556,
+    556, 350, 500, 889, 333, 980, 389, 333, 667, 0, 0, 556, 250, 389, 500, 500, 500, 500, 275, 500,
+    333, 760, 276, 500, 675, 333, 760, 500, 400, 549, 300, 300, 333, 576, 523, 333, 333, 300, 310,
+    500, 750, 750, 750, 500, 611, 611, 611, 611, 611, 611, 889, 667, 611, 611, 611, 611, 333, 333,
+    333, 333, 722, 667, 722, 722, 722, 722, 722, 675, 722, 722, 722, 722, 722, 556, 611, 500, 500,
+    500, 500, 500, 500, 500, 667, 444, 444, 444, 444, 444, 278, 278, 278, 278, 500, 500, 500, 500,
+    500, 500, 500, 549, 500, 500, 500, 500, 500, 444, 500, 444,
+];
+const TIMES_BOLD_ITALIC: [u16; 224] = [
+    250, 389, 555, 500, 500, 833, 778, 278, 333, 333, 500, 570, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500, 832, 667, 667, 667, 722, 667,
+    667, 722, 778, 389, 500, 667, 611, 889, 722, 722, 611, 722, 667, 556, 611, 722, 667, 889, 667,
+    611, 611, 333, 278, 333, 570, 500, 333, 500, 500, 444, 500, 444, 333, 500, 556, 278, 278, 500,
+    278, 778, 556, 500, 500, 500, 389, 389, 278, 556, 444, 667, 500, 444, 389, 348, 220, 348, 570,
+    0, 500, 0, 333, 500, 500, 1000, 500, 500, 333, 1000, 556, 333, 944, 0, 611, 0, 0, 333, 333,
+    500, 500, 350, 500, 1000, 333, 1000, 389, 333, 722, 0, 389, 611, 250, 389, 500, 500, 500, 500,
+    220, 500, 333, 747, 266, 500, 606, 333, 747, 500, 400, 549, 300, 300, 333, 576, 500, 250, 333,
+    300, 300, 500, 750, 750, 750, 500, 667, 667, 667, 667, 667, 667, 944, 667, 667, 667, 667, 667,
+    389, 389, 389, 389, 722, 722, 722, 722, 722, 722, 722, 570, 722, 722, 722, 722, 722, 611, 611,
+    500, 500, 500, 500, 500, 500, 500, 722, 444, 444, 444, 444, 444, 278, 278, 278, 278, 500, 556,
+    500, 500, 500, 500, 500, 549, 500, 556, 556, 556, 556, 444, 500, 444,
+];
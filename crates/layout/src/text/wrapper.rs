@@ -1,6 +1,7 @@
 use super::shaper::ShapedRun;
+use crate::elements::PathElement;
 use crate::{ComputedStyle, LayoutContext, LayoutElement, PositionedElement, TextElement};
-use petty_style::text::TextAlign;
+use petty_style::text::{TextAlign, WhiteSpace};
 
 #[derive(Debug, Clone)]
 pub struct LineLayout {
@@ -8,6 +9,16 @@ pub struct LineLayout {
     pub width: f32,
     pub height: f32,
     pub baseline: f32,
+    /// The ellipsis ending a line cut short by [`truncate_line`] or
+    /// [`end_line_with_ellipsis`].
+    pub ellipsis: Option<LineEllipsis>,
+}
+
+/// An ellipsis drawn after the last item of a truncated line.
+#[derive(Debug, Clone, Copy)]
+pub struct LineEllipsis {
+    pub x: f32,
+    pub width: f32,
 }
 
 #[derive(Debug, Clone)]
@@ -30,10 +41,15 @@ pub fn break_lines(
     let mut current_line_width = 0.0;
     let mut current_line_height = 0.0f32;
     let mut current_line_baseline = 0.0f32;
+    // Lines are aligned within `max_width`, but only wrap within `wrap_width`.
+    let wrap_width = match block_style.text.white_space {
+        WhiteSpace::Normal => max_width,
+        WhiteSpace::Nowrap => f32::INFINITY,
+    };
 
     for (run_idx, run) in runs.iter().enumerate() {
         if run.is_image {
-            if current_line_width + run.width > max_width && !current_line_items.is_empty() {
+            if current_line_width + run.width > wrap_width && !current_line_items.is_empty() {
                 lines.push(finalize_line(
                     current_line_items,
                     current_line_width,
@@ -111,7 +127,7 @@ pub fn break_lines(
             }
 
             if is_space {
-                if current_line_width + current_segment_width + char_width > max_width
+                if current_line_width + current_segment_width + char_width > wrap_width
                     && !current_line_items.is_empty()
                 {
                     lines.push(finalize_line(
@@ -148,7 +164,7 @@ pub fn break_lines(
                 continue;
             }
 
-            if current_line_width + current_segment_width + char_width > max_width {
+            if current_line_width + current_segment_width + char_width > wrap_width {
                 if !current_line_items.is_empty() {
                     lines.push(finalize_line(
                         current_line_items,
@@ -264,6 +280,7 @@ fn finalize_line(
             width: content_width,
             height,
             baseline,
+            ellipsis: None,
         };
     }
 
@@ -348,9 +365,95 @@ fn finalize_line(
         width: max_width,
         height,
         baseline,
+        ellipsis: None,
+    }
+}
+
+/// Ends `line` with an ellipsis if it is wider than `max_width`, see
+/// [`end_line_with_ellipsis`]. Lines that fit are unchanged.
+pub fn truncate_line(
+    line: &mut LineLayout,
+    max_width: f32,
+    ellipsis_width: f32,
+    runs: &[ShapedRun],
+    full_text: &str,
+) {
+    let content_end = line
+        .items
+        .iter()
+        .map(|item| item.x + item.width)
+        .fold(0.0f32, f32::max);
+    if content_end > max_width + 0.01 {
+        end_line_with_ellipsis(line, max_width, ellipsis_width, runs, full_text);
     }
 }
 
+/// Places an ellipsis of `ellipsis_width` after the content of `line`, first cutting
+/// the glyphs that would push it past `max_width` and any trailing spaces.
+pub fn end_line_with_ellipsis(
+    line: &mut LineLayout,
+    max_width: f32,
+    ellipsis_width: f32,
+    runs: &[ShapedRun],
+    full_text: &str,
+) {
+    let available = (max_width - ellipsis_width).max(0.0);
+    let mut kept = 0;
+    for item in &mut line.items {
+        if item.x + item.width <= available {
+            kept += 1;
+            continue;
+        }
+        let run = &runs[item.run_index];
+        if !run.is_image && run.path.is_none() {
+            let mut width = 0.0;
+            let mut glyph = item.start_glyph;
+            while glyph < item.end_glyph
+                && item.x + width + run.glyphs[glyph].x_advance <= available
+            {
+                width += run.glyphs[glyph].x_advance;
+                glyph += 1;
+            }
+            if glyph > item.start_glyph {
+                item.end_glyph = glyph;
+                item.width = width;
+                kept += 1;
+            }
+        }
+        break;
+    }
+    line.items.truncate(kept);
+
+    while let Some(item) = line.items.last_mut() {
+        let run = &runs[item.run_index];
+        if run.is_image || run.path.is_some() {
+            break;
+        }
+        while item.end_glyph > item.start_glyph {
+            let glyph = &run.glyphs[item.end_glyph - 1];
+            if full_text.as_bytes().get(glyph.cluster as usize) != Some(&b' ') {
+                break;
+            }
+            item.width -= glyph.x_advance;
+            item.end_glyph -= 1;
+        }
+        if item.end_glyph > item.start_glyph {
+            break;
+        }
+        line.items.pop();
+    }
+
+    let end = line
+        .items
+        .last()
+        .map(|item| item.x + item.width)
+        .unwrap_or(0.0);
+    line.ellipsis = Some(LineEllipsis {
+        x: end,
+        width: ellipsis_width,
+    });
+}
+
 pub fn render_lines(
     ctx: &mut LayoutContext,
     line: &LineLayout,
@@ -361,6 +464,19 @@ pub fn render_lines(
 ) {
     for item in &line.items {
         let run = &shaped_runs[item.run_index];
+        if let Some(points) = &run.path {
+            // The sparkline sits on the text baseline, or at the top of a line without text.
+            let element = PositionedElement {
+                x: item.x,
+                y: y_offset + (line.baseline - run.line_height).max(0.0),
+                width: run.width,
+                height: run.line_height,
+                element: LayoutElement::Path(PathElement::polyline(points)),
+                style: run.style.clone(),
+            };
+            ctx.push_element(element);
+            continue;
+        }
         render_run_segment(
             ctx,
             run,
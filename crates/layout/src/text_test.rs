@@ -1,11 +1,20 @@
 #![cfg(test)]
 
-use crate::test_utils::{create_paragraph, find_first_text_box_with_content, paginate_test_nodes};
-use petty_idf::{IRNode, InlineNode, NodeMetadata};
+use crate::config::LayoutConfig;
+use crate::engine::{LayoutEngine, LayoutStore};
+use crate::fonts::SharedFontLibrary;
+use crate::test_utils::{
+    create_paragraph, create_test_engine, find_first_text_box_with_content, paginate_test_nodes,
+    paginate_test_nodes_with_config,
+};
+use crate::{LayoutElement, LayoutWarning, PathElement};
+use petty_idf::{IRNode, InlineMetadata, InlineNode, NodeMetadata};
 use petty_style::dimension::{Margins, PageSize};
 use petty_style::stylesheet::{ElementStyle, PageLayout, Stylesheet};
-use petty_style::text::TextAlign;
+use petty_style::text::{TextAlign, TextOverflow, WhiteSpace};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
 
 #[test]
 fn test_text_wrapping() {
@@ -75,6 +84,7 @@ fn test_text_alignment_center() {
             id: None,
             style_sets: vec![],
             style_override: Some(style_override),
+            classes: vec![],
         },
         children: vec![InlineNode::Text(text.to_string())],
     };
@@ -173,6 +183,173 @@ fn test_text_justification() {
     );
 }
 
+#[test]
+fn test_nowrap_label_is_truncated_with_ellipsis() {
+    let stylesheet = Stylesheet {
+        page_masters: HashMap::from([(
+            "master".to_string(),
+            PageLayout {
+                size: PageSize::Custom {
+                    width: 120.0,
+                    height: 200.0,
+                },
+                margins: Some(Margins::all(10.0)), // content width 100
+                ..Default::default()
+            },
+        )]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    };
+
+    let label = IRNode::Paragraph {
+        meta: NodeMetadata {
+            style_override: Some(ElementStyle {
+                white_space: Some(WhiteSpace::Nowrap),
+                text_overflow: Some(TextOverflow::Ellipsis),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        children: vec![InlineNode::Text(
+            "Customer reference number for the quarterly statement".to_string(),
+        )],
+    };
+
+    let (pages, _, _) = paginate_test_nodes(stylesheet, vec![label]).unwrap();
+    let texts: Vec<_> = pages[0]
+        .iter()
+        .filter_map(|el| match &el.element {
+            LayoutElement::Text(t) => Some((el, t.content.as_str())),
+            _ => None,
+        })
+        .collect();
+
+    assert!(
+        texts.iter().all(|(el, _)| (el.y - 10.0).abs() < 0.01),
+        "the label stays on one line"
+    );
+    let line: String = texts.iter().map(|(_, content)| *content).collect();
+    assert!(line.starts_with("Customer"), "{}", line);
+    assert!(line.ends_with('\u{2026}'), "{}", line);
+    assert!(line.len() < "Customer reference number for the quarterly statement".len());
+
+    let right_edge = texts
+        .iter()
+        .map(|(el, _)| el.x + el.width)
+        .fold(0.0f32, f32::max);
+    assert!(right_edge <= 110.0 + 0.01, "ends at {}", right_edge);
+}
+
+#[test]
+fn test_max_lines_clamps_paragraph_with_ellipsis() {
+    let stylesheet = Stylesheet {
+        page_masters: HashMap::from([(
+            "master".to_string(),
+            PageLayout {
+                size: PageSize::Custom {
+                    width: 120.0,
+                    height: 200.0,
+                },
+                margins: Some(Margins::all(10.0)), // content width 100
+                ..Default::default()
+            },
+        )]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    };
+
+    let summary = IRNode::Paragraph {
+        meta: NodeMetadata {
+            style_override: Some(ElementStyle {
+                max_lines: Some(2),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        children: vec![InlineNode::Text(
+            "A long card summary that wraps over many lines and has to be cut short so the card keeps its size".to_string(),
+        )],
+    };
+
+    let (pages, _, _) = paginate_test_nodes(stylesheet, vec![summary]).unwrap();
+    let texts: Vec<_> = pages[0]
+        .iter()
+        .filter_map(|el| match &el.element {
+            LayoutElement::Text(t) => Some((el.y, t.content.as_str())),
+            _ => None,
+        })
+        .collect();
+
+    let mut line_ys: Vec<f32> = texts.iter().map(|(y, _)| *y).collect();
+    line_ys.dedup_by(|a, b| (*a - *b).abs() < 0.01);
+    assert_eq!(line_ys.len(), 2, "lines at {:?}", line_ys);
+
+    let last_line: String = texts
+        .iter()
+        .filter(|(y, _)| (y - line_ys[1]).abs() < 0.01)
+        .map(|(_, content)| *content)
+        .collect();
+    assert!(last_line.ends_with('\u{2026}'), "{}", last_line);
+    assert!(!last_line.contains("size"), "{}", last_line);
+}
+
+#[test]
+fn test_baseline_grid_aligns_line_baselines() {
+    let stylesheet = Stylesheet {
+        page_masters: HashMap::from([(
+            "master".to_string(),
+            PageLayout {
+                size: PageSize::Custom {
+                    width: 220.0,
+                    height: 500.0,
+                },
+                margins: Some(Margins::all(10.0)),
+                baseline_grid: Some(18.0),
+                ..Default::default()
+            },
+        )]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    };
+
+    // The heading's odd bottom margin would put the body off the grid.
+    let heading = IRNode::Paragraph {
+        meta: NodeMetadata {
+            style_override: Some(ElementStyle {
+                margin: Some(Margins {
+                    bottom: 5.0,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        children: vec![InlineNode::Text("Heading".to_string())],
+    };
+    let body = create_paragraph(
+        "Body text long enough to wrap over several lines, each of which should sit on the grid.",
+    );
+
+    let (pages, _, _) = paginate_test_nodes(stylesheet, vec![heading, body]).unwrap();
+    let mut line_ys: Vec<f32> = pages[0]
+        .iter()
+        .filter(|el| matches!(el.element, LayoutElement::Text(_)))
+        .map(|el| el.y)
+        .collect();
+    line_ys.dedup_by(|a, b| (*a - *b).abs() < 0.01);
+    assert!(line_ys.len() >= 3, "lines at {:?}", line_ys);
+
+    // Every line has the same font, so its baseline sits at a fixed offset from its top.
+    for pair in line_ys.windows(2) {
+        let gap = pair[1] - pair[0];
+        assert!(
+            (gap / 18.0 - (gap / 18.0).round()).abs() < 0.001 && gap > 0.0,
+            "lines at {:?}",
+            line_ys
+        );
+    }
+}
+
 #[test]
 fn test_widow_control() {
     // Page content height 50. Line height 14.4. Can fit 3 lines (43.2 used).
@@ -296,3 +473,164 @@ fn test_orphan_control() {
         "Second paragraph should start at the top of page 2"
     );
 }
+
+#[test]
+fn test_font_fallback_is_reported_with_node_id() {
+    let engine = create_test_engine();
+    let store = LayoutStore::new();
+    let tree = IRNode::Root(vec![
+        create_paragraph("Set in the default font"),
+        IRNode::Paragraph {
+            meta: NodeMetadata {
+                id: Some("intro".to_string()),
+                style_override: Some(ElementStyle {
+                    font_family: Some("No Such Font".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            children: vec![InlineNode::Text("Needs a fallback".to_string())],
+        },
+    ]);
+
+    engine.build_render_tree(&tree, &store).unwrap();
+
+    assert_eq!(
+        engine.take_warnings(),
+        vec![LayoutWarning::FontFallbackUsed {
+            node_id: Some("intro".to_string()),
+            family: "No Such Font".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_default_font_family_applies_to_unstyled_text() {
+    let stylesheet = || Stylesheet {
+        page_masters: HashMap::from([(
+            "master".to_string(),
+            PageLayout {
+                size: PageSize::Custom {
+                    width: 300.0,
+                    height: 300.0,
+                },
+                margins: Some(Margins::all(10.0)),
+                ..Default::default()
+            },
+        )]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    };
+    let config = LayoutConfig {
+        default_font_family: "Body Font".to_string(),
+        ..Default::default()
+    };
+
+    let (pages, _, _) =
+        paginate_test_nodes_with_config(stylesheet(), vec![create_paragraph("Hello")], config)
+            .unwrap();
+    let text = find_first_text_box_with_content(&pages[0], "Hello").unwrap();
+    assert_eq!(text.style.text.font_family.as_str(), "Body Font");
+
+    // "Body Font" is not installed, so the text is measured in the built-in family.
+    let (builtin_pages, _, _) =
+        paginate_test_nodes(stylesheet(), vec![create_paragraph("Hello")]).unwrap();
+    let builtin = find_first_text_box_with_content(&builtin_pages[0], "Hello").unwrap();
+    assert_eq!(
+        builtin.style.text.font_family.as_str(),
+        crate::config::DEFAULT_FONT_FAMILY
+    );
+    assert!(text.width > 0.0);
+    assert_eq!(text.width, builtin.width);
+}
+
+#[test]
+fn test_sparkline_flows_inline_with_text() {
+    let nodes = vec![IRNode::Paragraph {
+        meta: NodeMetadata::default(),
+        children: vec![
+            InlineNode::Text("Sales".to_string()),
+            InlineNode::Sparkline {
+                meta: InlineMetadata::default(),
+                values: vec![3.0, 1.0, 4.0, 1.0, 5.0],
+                width: 60.0,
+                height: 10.0,
+            },
+            InlineNode::Text("up".to_string()),
+        ],
+    }];
+
+    let stylesheet = Stylesheet {
+        page_masters: HashMap::from([("master".to_string(), PageLayout::default())]),
+        default_page_master_name: Some("master".to_string()),
+        ..Default::default()
+    };
+    let (pages, _, _) = paginate_test_nodes(stylesheet, nodes).unwrap();
+    let sales = find_first_text_box_with_content(&pages[0], "Sales").unwrap();
+    let up = find_first_text_box_with_content(&pages[0], "up").unwrap();
+    let sparkline = pages[0]
+        .iter()
+        .find(|el| matches!(el.element, LayoutElement::Path(_)))
+        .expect("sparkline path");
+
+    assert_eq!(sparkline.width, 60.0);
+    assert!((sparkline.x - (sales.x + sales.width)).abs() < 0.01);
+    assert!((up.x - (sparkline.x + 60.0)).abs() < 0.01);
+    // Bottom aligned with the text baseline, within the line.
+    assert!(sparkline.y >= sales.y && sparkline.y + 10.0 <= sales.y + sales.height);
+
+    let LayoutElement::Path(path) = &sparkline.element else {
+        unreachable!()
+    };
+    assert_eq!(
+        *path,
+        PathElement::polyline(&[
+            (0.0, 0.5),
+            (0.25, 1.0),
+            (0.5, 0.25),
+            (0.75, 1.0),
+            (1.0, 0.0)
+        ])
+    );
+}
+
+#[test]
+fn test_measurement_cache_is_shared_across_engines() {
+    let library = SharedFontLibrary::new();
+    library.load_fallback_font();
+    let cache = Arc::clone(library.measurement_cache());
+
+    // Warm-up: the first record measures each string once.
+    let engine = LayoutEngine::new(&library, Default::default());
+    let style = engine.get_default_style();
+    let larger = ElementStyle {
+        font_size: Some(18.0),
+        ..Default::default()
+    };
+    let larger = engine.compute_style(&[Arc::new(larger)], None, &style);
+    let width = engine.measure_text_width("Invoice total", &style);
+    let larger_width = engine.measure_text_width("Invoice total", &larger);
+    assert!(larger_width > width);
+    assert_eq!(cache.measurements(), 2);
+
+    // Later records, each on its own engine and thread, only hit the cache.
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let library = library.clone();
+            let style = Arc::clone(&style);
+            thread::spawn(move || {
+                let engine = LayoutEngine::new(&library, Default::default());
+                (0..250)
+                    .map(|_| engine.measure_text_width("Invoice total", &style))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    for worker in workers {
+        assert!(worker.join().unwrap().iter().all(|&w| w == width));
+    }
+
+    assert_eq!(cache.measurements(), 2);
+    assert_eq!(cache.hits(), 1000);
+    assert_eq!(cache.len(), 2);
+}
@@ -0,0 +1,31 @@
+//! Non-fatal issues noticed while laying out a document.
+//!
+//! Layout recovers from problems such as a missing font or an oversized element
+//! rather than failing. Each recovery is recorded as a [`LayoutWarning`] on the
+//! [`LayoutEngine`](crate::LayoutEngine), so callers can report degraded output.
+
+/// A problem that layout worked around. `node_id` is the `id` of the affected IR node,
+/// if it has one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutWarning {
+    /// Content extending past the bottom of the page was discarded under
+    /// [`OversizedPolicy::Clip`](crate::OversizedPolicy::Clip).
+    OverflowClipped {
+        node_id: Option<String>,
+        /// The page the content was clipped on, 1-indexed within the sequence.
+        page: usize,
+    },
+    /// The requested font family could not be found, so text was set in a fallback font.
+    FontFallbackUsed {
+        node_id: Option<String>,
+        family: String,
+    },
+    /// An image could not be loaded and was replaced with a placeholder.
+    ImageMissing {
+        node_id: Option<String>,
+        src: String,
+    },
+    /// A page break named a page master the stylesheet does not define, so the
+    /// following pages used the default master.
+    UnknownPageMaster { name: String },
+}
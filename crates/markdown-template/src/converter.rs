@@ -0,0 +1,320 @@
+//! Converts a stream of Markdown events into IDF nodes.
+
+use petty_idf::{
+    IRNode, InlineMetadata, InlineNode, NodeMetadata, TableBody, TableCell, TableColumnDefinition,
+    TableHeader, TableRow,
+};
+use petty_style::list::ListStyleType;
+use petty_style::stylesheet::{ElementStyle, Stylesheet};
+use petty_style::text::TextAlign;
+use petty_template_core::TemplateFlags;
+use pulldown_cmark::{Alignment, Event, Options, Parser, Tag};
+use std::iter::Peekable;
+use std::sync::Arc;
+
+pub(crate) struct Converter<'a, 's> {
+    events: Peekable<Parser<'a>>,
+    stylesheet: &'s Stylesheet,
+    pub flags: TemplateFlags,
+}
+
+impl<'a, 's> Converter<'a, 's> {
+    pub fn new(source: &'a str, stylesheet: &'s Stylesheet) -> Self {
+        let options = Options::ENABLE_TABLES | Options::ENABLE_HEADING_ATTRIBUTES;
+        Self {
+            events: Parser::new_ext(source, options).peekable(),
+            stylesheet,
+            flags: TemplateFlags::default(),
+        }
+    }
+
+    /// Converts the whole document.
+    pub fn convert(&mut self) -> Vec<IRNode> {
+        self.convert_blocks()
+    }
+
+    /// Converts block-level events until the end of the enclosing container, which
+    /// is consumed. Loose inline content, as found in tight list items, is wrapped
+    /// in paragraphs.
+    fn convert_blocks(&mut self) -> Vec<IRNode> {
+        let mut blocks = Vec::new();
+        let mut inline_run = Vec::new();
+        loop {
+            match self.events.peek() {
+                None => break,
+                Some(Event::End(_)) => {
+                    self.events.next();
+                    break;
+                }
+                Some(Event::Start(tag)) if is_block(tag) => {
+                    self.flush_inline_run(&mut inline_run, &mut blocks);
+                    if let Some(Event::Start(tag)) = self.events.next()
+                        && let Some(block) = self.convert_block(tag)
+                    {
+                        blocks.push(block);
+                    }
+                }
+                Some(Event::Rule) => {
+                    // Thematic breaks have no IDF equivalent.
+                    self.events.next();
+                    self.flush_inline_run(&mut inline_run, &mut blocks);
+                }
+                Some(_) => {
+                    if let Some(event) = self.events.next()
+                        && let Some(inline) = self.convert_inline(event)
+                    {
+                        inline_run.push(inline);
+                    }
+                }
+            }
+        }
+        self.flush_inline_run(&mut inline_run, &mut blocks);
+        blocks
+    }
+
+    fn flush_inline_run(&self, run: &mut Vec<InlineNode>, blocks: &mut Vec<IRNode>) {
+        if !run.is_empty() {
+            blocks.push(IRNode::Paragraph {
+                meta: self.node_meta(&["paragraph"]),
+                children: std::mem::take(run),
+            });
+        }
+    }
+
+    fn convert_block(&mut self, tag: Tag<'a>) -> Option<IRNode> {
+        let node = match tag {
+            Tag::Paragraph => IRNode::Paragraph {
+                meta: self.node_meta(&["paragraph"]),
+                children: self.convert_inlines(),
+            },
+            Tag::Heading {
+                level, id, classes, ..
+            } => {
+                let level = level as u8;
+                let mut meta = self.node_meta(&["heading", &format!("h{}", level)]);
+                meta.id = id.map(|id| id.into_string());
+                meta.classes = classes.into_iter().map(|c| c.into_string()).collect();
+                self.flags.has_internal_links |= meta.id.is_some();
+                IRNode::Heading {
+                    meta,
+                    level,
+                    children: self.convert_inlines(),
+                }
+            }
+            Tag::BlockQuote(_) => IRNode::Block {
+                meta: self.node_meta(&["blockquote"]),
+                children: self.convert_blocks(),
+            },
+            Tag::CodeBlock(_) => {
+                let mut code = String::new();
+                for event in self.events.by_ref() {
+                    match event {
+                        Event::Text(text) => code.push_str(&text),
+                        _ => break,
+                    }
+                }
+                IRNode::Paragraph {
+                    meta: self.node_meta(&["code-block"]),
+                    children: vec![InlineNode::Text(code.trim_end_matches('\n').to_string())],
+                }
+            }
+            Tag::List(start) => {
+                let mut meta = self.node_meta(&["list"]);
+                if start.is_some() {
+                    meta.style_override = Some(ElementStyle {
+                        list_style_type: Some(ListStyleType::Decimal),
+                        ..Default::default()
+                    });
+                }
+                IRNode::List {
+                    meta,
+                    start: start.map(|n| n as usize),
+                    children: self.convert_blocks(),
+                }
+            }
+            Tag::Item => IRNode::ListItem {
+                meta: self.node_meta(&["list-item"]),
+                children: self.convert_blocks(),
+            },
+            Tag::Table(alignments) => self.convert_table(&alignments),
+            other => {
+                log::warn!("Skipping unsupported Markdown block {:?}", other);
+                self.skip_container();
+                return None;
+            }
+        };
+        Some(node)
+    }
+
+    fn convert_table(&mut self, alignments: &[Alignment]) -> IRNode {
+        let mut header = None;
+        let mut rows = Vec::new();
+        while let Some(event) = self.events.next() {
+            match event {
+                Event::Start(Tag::TableHead) => {
+                    header = Some(Box::new(TableHeader {
+                        rows: vec![self.convert_table_row()],
+                    }));
+                }
+                Event::Start(Tag::TableRow) => rows.push(self.convert_table_row()),
+                _ => break,
+            }
+        }
+
+        let columns = alignments
+            .iter()
+            .map(|alignment| {
+                let text_align = match alignment {
+                    Alignment::None => None,
+                    Alignment::Left => Some(TextAlign::Left),
+                    Alignment::Center => Some(TextAlign::Center),
+                    Alignment::Right => Some(TextAlign::Right),
+                };
+                TableColumnDefinition {
+                    style: text_align.map(|text_align| ElementStyle {
+                        text_align: Some(text_align),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        IRNode::Table {
+            meta: self.node_meta(&["table"]),
+            columns,
+            header,
+            body: Box::new(TableBody { rows }),
+            footer: None,
+        }
+    }
+
+    /// Converts the cells of a table row (or the header) up to its end.
+    fn convert_table_row(&mut self) -> TableRow {
+        let mut cells = Vec::new();
+        while let Some(Event::Start(Tag::TableCell)) = self.events.next() {
+            let mut content = self.convert_inlines();
+            let mut children = Vec::new();
+            self.flush_inline_run(&mut content, &mut children);
+            cells.push(TableCell {
+                children,
+                col_span: 1,
+                row_span: 1,
+                ..Default::default()
+            });
+        }
+        TableRow { cells }
+    }
+
+    /// Converts inline events until the end of the enclosing element, which is consumed.
+    fn convert_inlines(&mut self) -> Vec<InlineNode> {
+        let mut inlines = Vec::new();
+        while let Some(event) = self.events.next() {
+            if let Event::End(_) = event {
+                break;
+            }
+            if let Some(inline) = self.convert_inline(event) {
+                inlines.push(inline);
+            }
+        }
+        inlines
+    }
+
+    fn convert_inline(&mut self, event: Event<'a>) -> Option<InlineNode> {
+        let inline = match event {
+            Event::Text(text) | Event::InlineMath(text) | Event::DisplayMath(text) => {
+                InlineNode::Text(text.into_string())
+            }
+            Event::Code(code) => InlineNode::StyledSpan {
+                meta: self.inline_meta(&["code"]),
+                children: vec![InlineNode::Text(code.into_string())],
+            },
+            Event::SoftBreak => InlineNode::Text(" ".to_string()),
+            Event::HardBreak => InlineNode::LineBreak,
+            Event::Start(Tag::Emphasis) => InlineNode::StyledSpan {
+                meta: self.inline_meta(&["emphasis"]),
+                children: self.convert_inlines(),
+            },
+            Event::Start(Tag::Strong) => InlineNode::StyledSpan {
+                meta: self.inline_meta(&["strong"]),
+                children: self.convert_inlines(),
+            },
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                self.flags.has_internal_links |= dest_url.starts_with('#');
+                InlineNode::Hyperlink {
+                    meta: self.inline_meta(&["link"]),
+                    href: dest_url.into_string(),
+                    children: self.convert_inlines(),
+                }
+            }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                // The alt text has no place in the IDF.
+                self.convert_inlines();
+                InlineNode::Image {
+                    meta: InlineMetadata::default(),
+                    src: dest_url.into_string(),
+                }
+            }
+            Event::Start(_) => InlineNode::StyledSpan {
+                meta: InlineMetadata::default(),
+                children: self.convert_inlines(),
+            },
+            Event::Html(html) | Event::InlineHtml(html) => {
+                log::warn!("Skipping raw HTML in Markdown: {}", html.trim());
+                return None;
+            }
+            _ => return None,
+        };
+        Some(inline)
+    }
+
+    /// Consumes events up to and including the end of the current container.
+    fn skip_container(&mut self) {
+        let mut depth = 1usize;
+        for event in self.events.by_ref() {
+            match event {
+                Event::Start(_) => depth += 1,
+                Event::End(_) => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Collects the stylesheet styles named after the given node kinds, in order.
+    fn style_sets(&self, kinds: &[&str]) -> Vec<Arc<ElementStyle>> {
+        kinds
+            .iter()
+            .filter_map(|kind| self.stylesheet.styles.get(*kind).cloned())
+            .collect()
+    }
+
+    fn node_meta(&self, kinds: &[&str]) -> NodeMetadata {
+        NodeMetadata {
+            style_sets: self.style_sets(kinds),
+            ..Default::default()
+        }
+    }
+
+    fn inline_meta(&self, kinds: &[&str]) -> InlineMetadata {
+        InlineMetadata {
+            style_sets: self.style_sets(kinds),
+            ..Default::default()
+        }
+    }
+}
+
+fn is_block(tag: &Tag) -> bool {
+    !matches!(
+        tag,
+        Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Link { .. }
+            | Tag::Image { .. }
+    )
+}
@@ -0,0 +1,13 @@
+//! Markdown template parser.
+//!
+//! This crate converts CommonMark documents (with the table extension) into the
+//! IDF tree, for documentation-style PDFs that do not need data binding. Each
+//! generated node is styled by its kind: a stylesheet style named after the kind
+//! (`heading`, `h1`–`h6`, `paragraph`, `list`, `list-item`, `table`, `blockquote`,
+//! `code-block`, `emphasis`, `strong`, `code` or `link`) is applied to every node
+//! of that kind.
+
+mod converter;
+pub mod processor;
+
+pub use processor::{CompiledMarkdownTemplate, MarkdownParser};
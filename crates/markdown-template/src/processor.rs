@@ -0,0 +1,271 @@
+// Processor that implements TemplateParser trait for Markdown documents
+use crate::converter::Converter;
+use petty_idf::{IRNode, apply_style_rules};
+use petty_style::parsers::parse_inline_css;
+use petty_style::stylesheet::{ElementStyle, Stylesheet};
+use petty_template_core::{
+    ExecutionConfig, TemplateError, TemplateFeatures, TemplateFlags, TemplateParser,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Built-in presentation for each node kind, used where the provided stylesheet
+/// does not define a style of the same name.
+const DEFAULT_KIND_STYLES: &[(&str, &str)] = &[
+    (
+        "heading",
+        "font-weight: bold; margin-top: 12pt; margin-bottom: 6pt",
+    ),
+    ("h1", "font-size: 24pt"),
+    ("h2", "font-size: 18pt"),
+    ("h3", "font-size: 15pt"),
+    ("h4", "font-size: 13pt"),
+    ("paragraph", "margin-bottom: 6pt"),
+    ("code-block", "font-family: monospace; margin-bottom: 6pt"),
+    ("blockquote", "margin-left: 18pt"),
+    ("emphasis", "font-style: italic"),
+    ("strong", "font-weight: bold"),
+    ("code", "font-family: monospace"),
+];
+
+/// Markdown template parser
+pub struct MarkdownParser;
+
+/// Compiled Markdown template. The document is static, so execution ignores the data source.
+pub struct CompiledMarkdownTemplate {
+    nodes: Vec<IRNode>,
+    stylesheet: Arc<Stylesheet>,
+    features: TemplateFlags,
+    resource_base_path: PathBuf,
+}
+
+impl petty_template_core::TemplateExecutor for CompiledMarkdownTemplate {
+    fn execute(
+        &self,
+        _data_source: &str,
+        _config: ExecutionConfig,
+    ) -> Result<Vec<IRNode>, TemplateError> {
+        Ok(self.nodes.clone())
+    }
+}
+
+impl petty_template_core::TemplateMetadata for CompiledMarkdownTemplate {
+    fn stylesheet(&self) -> Arc<Stylesheet> {
+        Arc::clone(&self.stylesheet)
+    }
+
+    fn resource_base_path(&self) -> &Path {
+        &self.resource_base_path
+    }
+
+    fn features(&self) -> TemplateFlags {
+        self.features
+    }
+}
+
+impl TemplateParser for MarkdownParser {
+    fn parse(
+        &self,
+        template_source: &str,
+        resource_base_path: PathBuf,
+    ) -> Result<TemplateFeatures, TemplateError> {
+        compile_template(template_source, resource_base_path, None)
+    }
+
+    /// Parses the document with `theme` as the provided stylesheet: its styles named
+    /// after node kinds replace the built-in presentation of those kinds.
+    fn parse_with_theme(
+        &self,
+        template_source: &str,
+        resource_base_path: PathBuf,
+        theme: &Stylesheet,
+    ) -> Result<TemplateFeatures, TemplateError> {
+        compile_template(template_source, resource_base_path, Some(theme))
+    }
+}
+
+fn compile_template(
+    template_source: &str,
+    resource_base_path: PathBuf,
+    theme: Option<&Stylesheet>,
+) -> Result<TemplateFeatures, TemplateError> {
+    let mut stylesheet = theme.cloned().unwrap_or_default();
+    stylesheet.apply_theme(&default_stylesheet());
+
+    let mut converter = Converter::new(template_source, &stylesheet);
+    let mut nodes = converter.convert();
+    let features = converter.flags;
+    // The tree is fixed at compile time, so selector rules can be resolved once.
+    apply_style_rules(&mut nodes, &stylesheet);
+
+    Ok(TemplateFeatures {
+        main_template: Arc::new(CompiledMarkdownTemplate {
+            nodes,
+            stylesheet: Arc::new(stylesheet),
+            features,
+            resource_base_path,
+        }),
+        role_templates: HashMap::new(),
+    })
+}
+
+fn default_stylesheet() -> Stylesheet {
+    let styles = DEFAULT_KIND_STYLES
+        .iter()
+        .map(|(kind, css)| {
+            let mut style = ElementStyle::default();
+            parse_inline_css(css, &mut style).expect("built-in Markdown styles are valid");
+            (kind.to_string(), Arc::new(style))
+        })
+        .collect();
+    Stylesheet {
+        styles,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petty_idf::InlineNode;
+    use petty_style::font::FontStyle;
+    use petty_style::list::ListStyleType;
+
+    fn execute(features: &TemplateFeatures) -> Vec<IRNode> {
+        features
+            .main_template
+            .execute("", ExecutionConfig::default())
+            .unwrap()
+    }
+
+    fn text(s: &str) -> InlineNode {
+        InlineNode::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_convert_markdown_document() {
+        let markdown = "# Guide {#guide}\n\
+                        \n\
+                        Read the *docs* at [the site](https://example.com) or `petty --help`.\n\
+                        \n\
+                        1. First\n\
+                        2. Second with [a link](#guide)\n\
+                        \n\
+                        ```\n\
+                        let x = 1;\n\
+                        ```\n\
+                        \n\
+                        | Name | Qty |\n\
+                        |------|----:|\n\
+                        | Pen  | 2   |\n";
+        let features = MarkdownParser.parse(markdown, PathBuf::new()).unwrap();
+        assert!(features.main_template.features().has_internal_links);
+        let nodes = execute(&features);
+        assert_eq!(nodes.len(), 5);
+
+        let IRNode::Heading {
+            meta,
+            level,
+            children,
+        } = &nodes[0]
+        else {
+            panic!("Expected heading, got {:?}", nodes[0]);
+        };
+        assert_eq!(*level, 1);
+        assert_eq!(meta.id.as_deref(), Some("guide"));
+        assert_eq!(meta.style_sets.len(), 2);
+        assert_eq!(children, &vec![text("Guide")]);
+
+        let IRNode::Paragraph { children, .. } = &nodes[1] else {
+            panic!("Expected paragraph, got {:?}", nodes[1]);
+        };
+        let InlineNode::StyledSpan {
+            meta,
+            children: emphasised,
+        } = &children[1]
+        else {
+            panic!("Expected span, got {:?}", children[1]);
+        };
+        assert_eq!(meta.style_sets[0].font_style, Some(FontStyle::Italic));
+        assert_eq!(emphasised, &vec![text("docs")]);
+        let InlineNode::Hyperlink {
+            href,
+            children: label,
+            ..
+        } = &children[3]
+        else {
+            panic!("Expected link, got {:?}", children[3]);
+        };
+        assert_eq!(href, "https://example.com");
+        assert_eq!(label, &vec![text("the site")]);
+        assert!(matches!(
+            &children[5],
+            InlineNode::StyledSpan { children, .. } if children == &vec![text("petty --help")]
+        ));
+
+        let IRNode::List {
+            meta,
+            start,
+            children,
+        } = &nodes[2]
+        else {
+            panic!("Expected list, got {:?}", nodes[2]);
+        };
+        assert_eq!(*start, Some(1));
+        let list_style = meta.style_override.as_ref().unwrap();
+        assert_eq!(list_style.list_style_type, Some(ListStyleType::Decimal));
+        assert_eq!(children.len(), 2);
+        let IRNode::ListItem { children, .. } = &children[1] else {
+            panic!("Expected list item, got {:?}", children[1]);
+        };
+        let [IRNode::Paragraph { children, .. }] = children.as_slice() else {
+            panic!("Expected a single paragraph, got {:?}", children);
+        };
+        assert!(matches!(&children[1], InlineNode::Hyperlink { href, .. } if href == "#guide"));
+
+        assert!(matches!(
+            &nodes[3],
+            IRNode::Paragraph { children, .. } if children == &vec![text("let x = 1;")]
+        ));
+
+        let IRNode::Table {
+            columns,
+            header,
+            body,
+            ..
+        } = &nodes[4]
+        else {
+            panic!("Expected table, got {:?}", nodes[4]);
+        };
+        assert_eq!(columns.len(), 2);
+        assert!(columns[0].style.is_none());
+        assert_eq!(header.as_ref().unwrap().rows[0].cells.len(), 2);
+        assert_eq!(body.rows.len(), 1);
+    }
+
+    #[test]
+    fn test_provided_stylesheet_styles_node_kinds() {
+        let mut paragraph = ElementStyle::default();
+        parse_inline_css("font-size: 9pt", &mut paragraph).unwrap();
+        let theme = Stylesheet {
+            styles: HashMap::from([("paragraph".to_string(), Arc::new(paragraph))]),
+            ..Default::default()
+        };
+
+        let features = MarkdownParser
+            .parse_with_theme("Plain *text*", PathBuf::new(), &theme)
+            .unwrap();
+        let nodes = execute(&features);
+        let IRNode::Paragraph { meta, children } = &nodes[0] else {
+            panic!("Expected paragraph, got {:?}", nodes[0]);
+        };
+        let style = &meta.style_sets[0];
+        assert_eq!(style.font_size, Some(9.0));
+        // Built-in properties not overridden by the provided style are kept.
+        assert!(style.margin.is_some());
+        assert!(
+            matches!(&children[1], InlineNode::StyledSpan { meta, .. } if !meta.style_sets.is_empty())
+        );
+    }
+}
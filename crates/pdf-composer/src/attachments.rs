@@ -0,0 +1,160 @@
+//! Embedded file attachments.
+//!
+//! Attachments are registered in the catalog's `/Names /EmbeddedFiles` name tree, so
+//! viewers list them, and in the catalog's `/AF` array, which marks them as files
+//! associated with the whole document (PDF 2.0 / PDF/A-3).
+
+use crate::ComposerError;
+use lopdf::{Dictionary, Document, Object, Stream, dictionary, text_string};
+
+/// A file to embed in the output document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// The file name shown by viewers, e.g. `invoice.json`.
+    pub name: String,
+    /// The MIME type of the content, e.g. `application/json`.
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl Attachment {
+    pub fn new(
+        name: impl Into<String>,
+        mime_type: impl Into<String>,
+        data: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            mime_type: mime_type.into(),
+            data: data.into(),
+        }
+    }
+}
+
+/// Embeds `attachments` into `doc`, merging with any embedded files already present
+/// in a flat `/EmbeddedFiles` name tree.
+pub fn embed_attachments(
+    doc: &mut Document,
+    attachments: &[Attachment],
+) -> Result<(), ComposerError> {
+    if attachments.is_empty() {
+        return Ok(());
+    }
+    let root_id = doc.trailer.get(b"Root")?.as_reference()?;
+
+    let mut entries = existing_embedded_files(doc, root_id)?;
+    let mut associated = Vec::with_capacity(attachments.len());
+    for attachment in attachments {
+        let mut file = Stream::new(
+            dictionary! {
+                "Type" => "EmbeddedFile",
+                "Subtype" => Object::Name(attachment.mime_type.clone().into_bytes()),
+                "Params" => dictionary! { "Size" => attachment.data.len() as i64 },
+            },
+            attachment.data.clone(),
+        );
+        file.compress()?;
+        let file_id = doc.add_object(file);
+
+        let spec_id = doc.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => Object::string_literal(attachment.name.as_str()),
+            "UF" => text_string(&attachment.name),
+            "EF" => dictionary! { "F" => file_id, "UF" => file_id },
+            "AFRelationship" => "Unspecified",
+        });
+        entries.retain(|(key, _)| key != attachment.name.as_bytes());
+        entries.push((
+            attachment.name.clone().into_bytes(),
+            Object::Reference(spec_id),
+        ));
+        associated.push(Object::Reference(spec_id));
+    }
+
+    // Name tree keys must be sorted.
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let names: Vec<Object> = entries
+        .into_iter()
+        .flat_map(|(key, value)| [Object::string_literal(key), value])
+        .collect();
+
+    let mut names_dict = match doc.get_dictionary(root_id)?.get(b"Names") {
+        Ok(Object::Reference(id)) => doc.get_dictionary(*id)?.clone(),
+        Ok(Object::Dictionary(dict)) => dict.clone(),
+        _ => Dictionary::new(),
+    };
+    names_dict.set("EmbeddedFiles", dictionary! { "Names" => names });
+
+    let catalog = doc.get_dictionary_mut(root_id)?;
+    catalog.set("Names", names_dict);
+    let mut af = match catalog.get(b"AF") {
+        Ok(Object::Array(existing)) => existing.clone(),
+        _ => Vec::new(),
+    };
+    af.extend(associated);
+    catalog.set("AF", af);
+    Ok(())
+}
+
+/// Reads the key/value pairs of an existing flat `/EmbeddedFiles` name tree.
+fn existing_embedded_files(
+    doc: &Document,
+    root_id: lopdf::ObjectId,
+) -> Result<Vec<(Vec<u8>, Object)>, ComposerError> {
+    let Ok(names) = doc.get_dictionary(root_id)?.get_deref(b"Names", doc) else {
+        return Ok(Vec::new());
+    };
+    let Ok(tree) = names.as_dict()?.get_deref(b"EmbeddedFiles", doc) else {
+        return Ok(Vec::new());
+    };
+    let tree = tree.as_dict()?;
+    if tree.has(b"Kids") {
+        return Err(ComposerError::Other(
+            "Cannot merge attachments into a nested /EmbeddedFiles name tree".to_string(),
+        ));
+    }
+    let Ok(flat) = tree.get(b"Names").and_then(Object::as_array) else {
+        return Ok(Vec::new());
+    };
+    Ok(flat
+        .chunks_exact(2)
+        .filter_map(|pair| Some((pair[0].as_str().ok()?.to_vec(), pair[1].clone())))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_dummy_pdf;
+
+    fn embedded_names(doc: &Document) -> Vec<Object> {
+        let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let root = doc.get_dictionary(root_id).unwrap();
+        let names = root.get(b"Names").unwrap().as_dict().unwrap();
+        let tree = names.get(b"EmbeddedFiles").unwrap().as_dict().unwrap();
+        tree.get(b"Names").unwrap().as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn test_embed_attachments_keeps_name_tree_sorted() {
+        let mut doc = create_dummy_pdf(1, "Page");
+        embed_attachments(&mut doc, &[Attachment::new("b.xml", "text/xml", "<b/>")]).unwrap();
+        embed_attachments(
+            &mut doc,
+            &[Attachment::new("a.json", "application/json", "{}")],
+        )
+        .unwrap();
+
+        let names = embedded_names(&doc);
+        let keys: Vec<&[u8]> = names
+            .iter()
+            .step_by(2)
+            .map(|k| k.as_str().unwrap())
+            .collect();
+        assert_eq!(keys, vec![b"a.json".as_slice(), b"b.xml".as_slice()]);
+
+        let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let af = doc.get_dictionary(root_id).unwrap().get(b"AF").unwrap();
+        assert_eq!(af.as_array().unwrap().len(), 2);
+    }
+}
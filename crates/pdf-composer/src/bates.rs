@@ -0,0 +1,195 @@
+//! Bates numbering: a sequential identifier stamped on every page of a document.
+//!
+//! Stamping is a separate pass over the finished page tree, so running it after
+//! [`merge_documents`](crate::merge_documents) numbers the pages of all merged
+//! documents as one sequence.
+
+use crate::page_resources::add_page_resource;
+use crate::rotation::visible_box;
+use crate::{ComposerError, overlay_content};
+use lopdf::content::{Content, Operation};
+use lopdf::{Document, Object, dictionary};
+
+/// The resource name the stamp's font is registered under on every page.
+const BATES_FONT: &str = "FBates";
+/// Advance width of every Courier glyph, as a fraction of the font size.
+const COURIER_ADVANCE: f32 = 0.6;
+
+/// The corner of the page a Bates label is placed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PageCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// How Bates labels are formatted and placed.
+///
+/// A label is the prefix followed by the page's number, zero-padded to `digits`,
+/// e.g. `ACME000042`. Labels are set in Courier so their width is known without
+/// font metrics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatesNumbering {
+    pub prefix: String,
+    /// The number of the first stamped page.
+    pub start: u64,
+    /// The minimum number of digits; shorter numbers are padded with zeros.
+    pub digits: usize,
+    pub corner: PageCorner,
+    pub font_size: f32,
+    /// Distance of the label from the page edges, in points.
+    pub margin: f32,
+}
+
+impl BatesNumbering {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            start: 1,
+            digits: 6,
+            corner: PageCorner::default(),
+            font_size: 10.0,
+            margin: 24.0,
+        }
+    }
+
+    pub fn with_start(mut self, start: u64) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn with_digits(mut self, digits: usize) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    pub fn with_corner(mut self, corner: PageCorner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    /// Formats the label for Bates number `number`.
+    pub fn label(&self, number: u64) -> String {
+        format!("{}{:0width$}", self.prefix, number, width = self.digits)
+    }
+}
+
+/// Stamps a Bates label on every page of `doc`, in page order, starting at
+/// `numbering.start`.
+///
+/// Returns the number following the last stamped page, so a set of documents kept
+/// as separate files can be numbered as one sequence by passing it as the start
+/// of the next.
+pub fn stamp_bates_numbers(
+    doc: &mut Document,
+    numbering: &BatesNumbering,
+) -> Result<u64, ComposerError> {
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Courier",
+        "Encoding" => "WinAnsiEncoding",
+    });
+
+    let mut number = numbering.start;
+    for page_id in doc.get_pages().into_values() {
+        add_page_resource(doc, page_id, "Font", BATES_FONT, font_id)?;
+        let [x0, y0, x1, y1] = visible_box(doc, page_id)?;
+
+        let label = numbering.label(number);
+        let width = label.chars().count() as f32 * COURIER_ADVANCE * numbering.font_size;
+        let x = match numbering.corner {
+            PageCorner::TopLeft | PageCorner::BottomLeft => x0 + numbering.margin,
+            PageCorner::TopRight | PageCorner::BottomRight => x1 - numbering.margin - width,
+        };
+        let y = match numbering.corner {
+            PageCorner::TopLeft | PageCorner::TopRight => {
+                y1 - numbering.margin - numbering.font_size
+            }
+            PageCorner::BottomLeft | PageCorner::BottomRight => y0 + numbering.margin,
+        };
+
+        let content = Content {
+            operations: vec![
+                Operation::new("q", vec![]),
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec![BATES_FONT.into(), numbering.font_size.into()]),
+                Operation::new("Td", vec![x.into(), y.into()]),
+                Operation::new("Tj", vec![Object::string_literal(label)]),
+                Operation::new("ET", vec![]),
+                Operation::new("Q", vec![]),
+            ],
+        };
+        overlay_content(doc, page_id, content.encode()?)?;
+        number += 1;
+    }
+    Ok(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge_documents;
+    use crate::tests::create_dummy_pdf;
+
+    #[test]
+    fn test_bates_numbers_continue_across_merged_documents() {
+        let mut doc = create_dummy_pdf(2, "First");
+        merge_documents(&mut doc, create_dummy_pdf(3, "Second"), false).unwrap();
+
+        let numbering = BatesNumbering::new("ACME").with_start(41);
+        let next = stamp_bates_numbers(&mut doc, &numbering).unwrap();
+        assert_eq!(next, 46);
+
+        for (page_num, page_id) in doc.get_pages() {
+            let content =
+                String::from_utf8_lossy(&doc.get_page_content(page_id).unwrap()).into_owned();
+            let expected = format!("(ACME{:06})", 40 + page_num);
+            assert!(
+                content.contains(&expected),
+                "page {} should show {}",
+                page_num,
+                expected
+            );
+            let fonts = doc.get_page_fonts(page_id).unwrap();
+            assert!(fonts.contains_key(BATES_FONT.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_bates_label_is_placed_in_the_chosen_corner() {
+        let mut doc = create_dummy_pdf(1, "Page");
+        let numbering = BatesNumbering::new("X")
+            .with_digits(3)
+            .with_corner(PageCorner::TopLeft);
+        stamp_bates_numbers(&mut doc, &numbering).unwrap();
+
+        let page_id = doc.page_iter().next().unwrap();
+        let content = Content::decode(&doc.get_page_content(page_id).unwrap()).unwrap();
+        let td = content
+            .operations
+            .iter()
+            .rfind(|op| op.operator == "Td")
+            .unwrap();
+        // The dummy pages are US Letter, 612 x 792 points.
+        assert_eq!(td.operands[0].as_float().unwrap(), 24.0);
+        assert_eq!(td.operands[1].as_float().unwrap(), 792.0 - 24.0 - 10.0);
+    }
+
+    #[test]
+    fn test_parent_cycle_in_page_tree_is_an_error() {
+        let mut doc = create_dummy_pdf(1, "Page");
+        let page_id = doc.page_iter().next().unwrap();
+        let page = doc.get_dictionary_mut(page_id).unwrap();
+        page.remove(b"MediaBox");
+        let pages_id = page.get(b"Parent").unwrap().as_reference().unwrap();
+        doc.get_dictionary_mut(pages_id)
+            .unwrap()
+            .set("Parent", pages_id);
+
+        let result = stamp_bates_numbers(&mut doc, &BatesNumbering::new("X"));
+        assert!(matches!(result, Err(ComposerError::Other(_))));
+    }
+}
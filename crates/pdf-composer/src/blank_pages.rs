@@ -0,0 +1,118 @@
+//! Blank page insertion, e.g. to start chapters on a recto page in duplex printing.
+
+use crate::ComposerError;
+use crate::rotation::media_box;
+use lopdf::{Document, Object, ObjectId, Stream, dictionary};
+
+/// Inserts an empty page after the first `after_index` pages of `doc`, so `0`
+/// inserts it before the first page and passing the page count appends it.
+///
+/// The page gets the media box of the page it follows (or of the first page when
+/// inserted at the start), and is spliced into that page's parent node of the page
+/// tree. Returns the ID of the new page.
+pub fn insert_blank_page(
+    doc: &mut Document,
+    after_index: usize,
+) -> Result<ObjectId, ComposerError> {
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    if after_index > pages.len() {
+        return Err(ComposerError::Other(format!(
+            "Cannot insert a page after page {} of a {}-page document",
+            after_index,
+            pages.len()
+        )));
+    }
+    let Some(&neighbor_id) = pages.get(after_index.saturating_sub(1)) else {
+        return Err(ComposerError::Other(
+            "Cannot insert a blank page into a document without pages".to_string(),
+        ));
+    };
+
+    let [x0, y0, x1, y1] = media_box(doc, neighbor_id)?;
+    let parent_id = doc
+        .get_dictionary(neighbor_id)?
+        .get(b"Parent")?
+        .as_reference()?;
+    let contents_id = doc.add_object(Stream::new(dictionary! {}, Vec::new()));
+    let page_id = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => parent_id,
+        "MediaBox" => vec![x0.into(), y0.into(), x1.into(), y1.into()],
+        "Resources" => dictionary! {},
+        "Contents" => contents_id,
+    });
+
+    let parent = doc.get_dictionary_mut(parent_id)?;
+    let mut kids = parent.get(b"Kids")?.as_array()?.clone();
+    let position = kids
+        .iter()
+        .position(|kid| kid.as_reference().ok() == Some(neighbor_id))
+        .ok_or_else(|| {
+            ComposerError::Other(format!(
+                "Page {:?} is missing from the /Kids of its parent",
+                neighbor_id
+            ))
+        })?;
+    let position = if after_index == 0 {
+        position
+    } else {
+        position + 1
+    };
+    kids.insert(position, Object::Reference(page_id));
+    parent.set("Kids", kids);
+
+    // Every ancestor of the new page counts it.
+    let mut node_id = Some(parent_id);
+    while let Some(id) = node_id {
+        let node = doc.get_dictionary_mut(id)?;
+        let count = node.get(b"Count")?.as_i64()?;
+        node.set("Count", count + 1);
+        node_id = node.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+
+    Ok(page_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_dummy_pdf;
+
+    #[test]
+    fn test_insert_blank_page_after_first_page() {
+        let mut doc = create_dummy_pdf(3, "Page");
+        let blank_id = insert_blank_page(&mut doc, 1).unwrap();
+
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 4);
+        assert_eq!(pages[&2], blank_id);
+        let pages_id = doc
+            .get_dictionary(blank_id)
+            .unwrap()
+            .get(b"Parent")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let count = doc.get_dictionary(pages_id).unwrap().get(b"Count").unwrap();
+        assert_eq!(count.as_i64().unwrap(), 4);
+
+        assert!(doc.get_page_content(blank_id).unwrap().is_empty());
+        assert_eq!(media_box(&doc, blank_id).unwrap(), [0.0, 0.0, 612.0, 792.0]);
+        let following =
+            String::from_utf8_lossy(&doc.get_page_content(pages[&3]).unwrap()).into_owned();
+        assert!(following.contains("Page 2"));
+    }
+
+    #[test]
+    fn test_insert_blank_page_at_start_and_end() {
+        let mut doc = create_dummy_pdf(2, "Page");
+        let first = insert_blank_page(&mut doc, 0).unwrap();
+        let last = insert_blank_page(&mut doc, 3).unwrap();
+
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 4);
+        assert_eq!(pages[&1], first);
+        assert_eq!(pages[&4], last);
+        assert!(insert_blank_page(&mut doc, 5).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Object deduplication for merging, so that fonts and streams that many merged
+//! documents have in common are stored once.
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// An index of the shareable objects of a document by content.
+pub(crate) struct ObjectIndex {
+    by_hash: HashMap<u64, Vec<ObjectId>>,
+}
+
+impl ObjectIndex {
+    /// Indexes the shareable objects already in `doc`.
+    pub(crate) fn new(doc: &Document) -> Self {
+        let mut index = Self {
+            by_hash: HashMap::new(),
+        };
+        for (id, object) in &doc.objects {
+            if is_shareable(object) {
+                index.insert(*id, object);
+            }
+        }
+        index
+    }
+
+    /// Returns an object of `doc` equal to `object`, if one is indexed.
+    pub(crate) fn find(&self, doc: &Document, object: &Object) -> Option<ObjectId> {
+        self.by_hash
+            .get(&content_hash(object))?
+            .iter()
+            .copied()
+            .find(|id| doc.objects.get(id) == Some(object))
+    }
+
+    pub(crate) fn insert(&mut self, id: ObjectId, object: &Object) {
+        self.by_hash
+            .entry(content_hash(object))
+            .or_default()
+            .push(id);
+    }
+}
+
+/// Whether `object` may be replaced by an equal object: streams (content, font
+/// files, images) and font and font descriptor dictionaries. These are not part
+/// of reference cycles, so replacing them after copying cannot leave a reference
+/// to the discarded copy behind.
+pub(crate) fn is_shareable(object: &Object) -> bool {
+    match object {
+        Object::Stream(_) => true,
+        Object::Dictionary(dict) => matches!(
+            dict.get(b"Type"),
+            Ok(Object::Name(kind)) if kind == b"Font" || kind == b"FontDescriptor"
+        ),
+        _ => false,
+    }
+}
+
+fn content_hash(object: &Object) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hash_object(object, &mut hasher);
+    hasher.finish()
+}
+
+fn hash_object(object: &Object, state: &mut impl Hasher) {
+    std::mem::discriminant(object).hash(state);
+    match object {
+        Object::Null => {}
+        Object::Boolean(value) => value.hash(state),
+        Object::Integer(value) => value.hash(state),
+        Object::Real(value) => value.to_bits().hash(state),
+        Object::Name(bytes) | Object::String(bytes, _) => bytes.hash(state),
+        Object::Array(items) => {
+            items.len().hash(state);
+            for item in items {
+                hash_object(item, state);
+            }
+        }
+        Object::Dictionary(dict) => hash_dictionary(dict, state),
+        Object::Stream(stream) => {
+            hash_dictionary(&stream.dict, state);
+            stream.content.hash(state);
+        }
+        Object::Reference(id) => id.hash(state),
+    }
+}
+
+/// Hashes entries in key order, as dictionaries compare equal regardless of order.
+fn hash_dictionary(dict: &Dictionary, state: &mut impl Hasher) {
+    let mut entries: Vec<_> = dict.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries.len().hash(state);
+    for (key, value) in entries {
+        key.hash(state);
+        hash_object(value, state);
+    }
+}
@@ -0,0 +1,255 @@
+//! Named destinations.
+//!
+//! Destinations are read from both the catalog's `/Dests` dictionary and a flat
+//! `/Names /Dests` name tree, and are written to the catalog's `/Dests` dictionary.
+
+use crate::ComposerError;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Returns the named destinations of `doc`, keyed by name.
+///
+/// Values are returned as stored: either a destination array, a dictionary with a
+/// `/D` entry, or a reference to one of those.
+pub fn named_destinations(doc: &Document) -> Result<BTreeMap<Vec<u8>, Object>, ComposerError> {
+    let root_id = doc.trailer.get(b"Root")?.as_reference()?;
+    let catalog = doc.get_dictionary(root_id)?;
+    let mut dests = BTreeMap::new();
+
+    if let Ok(tree) = catalog
+        .get_deref(b"Names", doc)
+        .and_then(Object::as_dict)
+        .and_then(|names| names.get_deref(b"Dests", doc))
+        .and_then(Object::as_dict)
+    {
+        if tree.has(b"Kids") {
+            return Err(ComposerError::Other(
+                "Cannot read named destinations from a nested /Dests name tree".to_string(),
+            ));
+        }
+        if let Ok(flat) = tree.get(b"Names").and_then(Object::as_array) {
+            for pair in flat.chunks_exact(2) {
+                if let Ok(name) = pair[0].as_str() {
+                    dests.insert(name.to_vec(), pair[1].clone());
+                }
+            }
+        }
+    }
+    if let Ok(dict) = catalog.get_deref(b"Dests", doc).and_then(Object::as_dict) {
+        for (name, dest) in dict.iter() {
+            dests.insert(name.clone(), dest.clone());
+        }
+    }
+    Ok(dests)
+}
+
+/// Adds `dests` to the catalog's `/Dests` dictionary. A name that is already defined
+/// keeps its existing destination.
+pub(crate) fn insert_named_destinations(
+    doc: &mut Document,
+    dests: BTreeMap<Vec<u8>, Object>,
+) -> Result<(), ComposerError> {
+    if dests.is_empty() {
+        return Ok(());
+    }
+    let root_id = doc.trailer.get(b"Root")?.as_reference()?;
+    let existing = named_destinations(doc)?;
+
+    let dests_ref = match doc.get_dictionary(root_id)?.get(b"Dests") {
+        Ok(Object::Reference(id)) => Some(*id),
+        _ => None,
+    };
+    let dests_dict: &mut Dictionary = match dests_ref {
+        Some(id) => doc.get_dictionary_mut(id)?,
+        None => {
+            let catalog = doc.get_dictionary_mut(root_id)?;
+            if !matches!(catalog.get(b"Dests"), Ok(Object::Dictionary(_))) {
+                catalog.set("Dests", Dictionary::new());
+            }
+            catalog.get_mut(b"Dests")?.as_dict_mut()?
+        }
+    };
+
+    for (name, dest) in dests {
+        if existing.contains_key(&name) {
+            log::warn!(
+                "Named destination '{}' is already defined; keeping the existing one.",
+                String::from_utf8_lossy(&name)
+            );
+            continue;
+        }
+        dests_dict.set(name, dest);
+    }
+    Ok(())
+}
+
+/// Returns `name` prefixed with `namespace:`.
+pub(crate) fn namespaced_name(namespace: &str, name: &[u8]) -> Vec<u8> {
+    let mut prefixed = Vec::with_capacity(namespace.len() + 1 + name.len());
+    prefixed.extend_from_slice(namespace.as_bytes());
+    prefixed.push(b':');
+    prefixed.extend_from_slice(name);
+    prefixed
+}
+
+/// Rewrites the targets of `holders` (see [`visit_targets`]) that name one of
+/// `names` to `namespace:name`.
+pub(crate) fn namespace_targets(
+    doc: &mut Document,
+    holders: Vec<ObjectId>,
+    names: &BTreeSet<Vec<u8>>,
+    namespace: &str,
+) -> Result<(), ComposerError> {
+    visit_targets(doc, holders, |target| {
+        namespace_target(target, names, namespace)
+    })
+}
+
+/// Adds `offset` to the targets of `holders` (see [`visit_targets`]) that are given
+/// as page indices, after the pages they address moved by `offset`.
+///
+/// Targets given as page references need no change, as merging keeps page objects
+/// (or remaps references to them when copying).
+pub(crate) fn shift_page_index_targets(
+    doc: &mut Document,
+    holders: Vec<ObjectId>,
+    offset: i64,
+) -> Result<(), ComposerError> {
+    if offset == 0 {
+        return Ok(());
+    }
+    visit_targets(doc, holders, |target| {
+        if let Object::Array(dest) = target
+            && let Some(Object::Integer(index)) = dest.first_mut()
+        {
+            *index += offset;
+        }
+    })
+}
+
+/// Returns the link annotations on `pages`.
+pub(crate) fn link_annotations(
+    doc: &Document,
+    pages: &[ObjectId],
+) -> Result<Vec<ObjectId>, ComposerError> {
+    let mut annot_ids = Vec::new();
+    for page_id in pages {
+        let page = doc.get_dictionary(*page_id)?;
+        if let Ok(annots) = page.get_deref(b"Annots", doc).and_then(Object::as_array) {
+            annot_ids.extend(annots.iter().filter_map(|a| a.as_reference().ok()));
+        }
+    }
+    Ok(annot_ids)
+}
+
+/// Returns the items of the document outline, in no particular order.
+pub(crate) fn document_outline_items(doc: &Document) -> Result<Vec<ObjectId>, ComposerError> {
+    let root_id = doc.trailer.get(b"Root")?.as_reference()?;
+    let first = doc
+        .get_dictionary(root_id)?
+        .get_deref(b"Outlines", doc)
+        .and_then(Object::as_dict)
+        .and_then(|outlines| outlines.get(b"First"))
+        .and_then(Object::as_reference);
+    match first {
+        Ok(first) => outline_items_from(doc, first),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// Returns the outline item `first`, its following siblings and all their
+/// descendants, in no particular order.
+pub(crate) fn outline_items_from(
+    doc: &Document,
+    first: ObjectId,
+) -> Result<Vec<ObjectId>, ComposerError> {
+    let mut items = Vec::new();
+    let mut pending = vec![first];
+    let mut seen = BTreeSet::new();
+    while let Some(item_id) = pending.pop() {
+        if !seen.insert(item_id) {
+            continue;
+        }
+        let item = doc.get_dictionary(item_id)?;
+        for key in [b"First".as_slice(), b"Next"] {
+            if let Ok(next) = item.get(key).and_then(Object::as_reference) {
+                pending.push(next);
+            }
+        }
+        items.push(item_id);
+    }
+    Ok(items)
+}
+
+/// Calls `visit` with the target of each of `holders`, the annotations or outline
+/// items whose `/Dest`, or `/D` of a `GoTo` action, points somewhere in the document.
+fn visit_targets(
+    doc: &mut Document,
+    holders: Vec<ObjectId>,
+    mut visit: impl FnMut(&mut Object),
+) -> Result<(), ComposerError> {
+    // Actions and destination arrays may be shared indirect objects; each is visited once.
+    let mut action_ids = BTreeSet::new();
+    let mut target_ids = BTreeSet::new();
+    for holder_id in holders {
+        let Ok(holder) = doc.get_object_mut(holder_id).and_then(Object::as_dict_mut) else {
+            continue;
+        };
+        match holder.get_mut(b"Dest") {
+            Ok(Object::Reference(id)) => {
+                target_ids.insert(*id);
+            }
+            Ok(dest) => visit(dest),
+            Err(_) => {}
+        }
+        match holder.get_mut(b"A") {
+            Ok(Object::Dictionary(action)) => {
+                if let Some(id) = visit_goto_action(action, &mut visit) {
+                    target_ids.insert(id);
+                }
+            }
+            Ok(Object::Reference(id)) => {
+                action_ids.insert(*id);
+            }
+            _ => {}
+        }
+    }
+    for action_id in action_ids {
+        if let Ok(action) = doc.get_object_mut(action_id).and_then(Object::as_dict_mut)
+            && let Some(id) = visit_goto_action(action, &mut visit)
+        {
+            target_ids.insert(id);
+        }
+    }
+    for target_id in target_ids {
+        if let Ok(target) = doc.get_object_mut(target_id) {
+            visit(target);
+        }
+    }
+    Ok(())
+}
+
+/// Visits the target of a `GoTo` action, or returns it if it is an indirect object.
+fn visit_goto_action(
+    action: &mut Dictionary,
+    visit: &mut impl FnMut(&mut Object),
+) -> Option<ObjectId> {
+    let is_goto = matches!(action.get(b"S"), Ok(Object::Name(s)) if s == b"GoTo");
+    match action.get_mut(b"D") {
+        Ok(Object::Reference(id)) if is_goto => Some(*id),
+        Ok(dest) if is_goto => {
+            visit(dest);
+            None
+        }
+        _ => None,
+    }
+}
+
+fn namespace_target(target: &mut Object, names: &BTreeSet<Vec<u8>>, namespace: &str) {
+    match target {
+        Object::Name(name) | Object::String(name, _) if names.contains(name.as_slice()) => {
+            *name = namespaced_name(namespace, name);
+        }
+        _ => {}
+    }
+}
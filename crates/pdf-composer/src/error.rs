@@ -5,6 +5,9 @@ pub enum ComposerError {
     #[error("PDF error: {0}")]
     Pdf(#[from] lopdf::Error),
 
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     #[error("{0}")]
     Other(String),
 }
@@ -0,0 +1,162 @@
+//! Page extraction: a range of pages copied out into a document of their own, or
+//! every page into a document of its own.
+
+use crate::rotation::inherited;
+use crate::{ComposerError, ObjectCopier};
+use lopdf::{Document, Object, dictionary};
+use std::ops::RangeInclusive;
+
+/// Page attributes a page may inherit from its ancestors in the page tree.
+const INHERITABLE_ATTRIBUTES: [&str; 4] = ["Resources", "MediaBox", "CropBox", "Rotate"];
+
+/// Creates a new document holding copies of the pages in `range` of `source`, given
+/// as 1-based page numbers, e.g. `3..=5`.
+///
+/// The pages are copied with everything they reference and placed in a flat page
+/// tree. Attributes they inherited from the source's page tree are set on the copies.
+/// Outlines, named destinations and page labels are not carried over.
+pub fn extract_pages(
+    source: &Document,
+    range: RangeInclusive<u32>,
+) -> Result<Document, ComposerError> {
+    let pages = source.get_pages();
+    let (first, last) = (*range.start(), *range.end());
+    if first == 0 || first > last || last as usize > pages.len() {
+        return Err(ComposerError::Other(format!(
+            "Cannot extract pages {}..={} from a {}-page document",
+            first,
+            last,
+            pages.len()
+        )));
+    }
+
+    let mut doc = Document::with_version(source.version.clone());
+    let pages_id = doc.new_object_id();
+    let mut copier = ObjectCopier::new(source, &mut doc);
+
+    let mut page_ids = Vec::new();
+    for page_number in range {
+        let source_page_id = pages[&page_number];
+        // Mapping the page's ancestors to the new page tree keeps the `/Parent`
+        // reference from copying the source's whole tree, and every other page with it.
+        let mut node_id = source_page_id;
+        while let Ok(parent_id) = source
+            .get_dictionary(node_id)?
+            .get(b"Parent")
+            .and_then(Object::as_reference)
+        {
+            copier.id_map.insert(parent_id, pages_id);
+            node_id = parent_id;
+        }
+
+        let page_id = copier.copy_object(source_page_id)?;
+        for key in INHERITABLE_ATTRIBUTES {
+            if copier
+                .target_doc
+                .get_dictionary(page_id)?
+                .has(key.as_bytes())
+            {
+                continue;
+            }
+            if let Some(value) = inherited(source, source_page_id, key.as_bytes())? {
+                let value = copier.remap_references(value.clone())?;
+                copier
+                    .target_doc
+                    .get_dictionary_mut(page_id)?
+                    .set(key, value);
+            }
+        }
+        page_ids.push(page_id);
+    }
+
+    let count = page_ids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => page_ids.into_iter().map(Object::Reference).collect::<Vec<_>>(),
+            "Count" => count,
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    Ok(doc)
+}
+
+/// Splits `source` into one single-page document per page, in page order.
+///
+/// Each document is built by [`extract_pages`] and gets its own copy of the
+/// resources its page uses, including those the pages of `source` shared.
+pub fn split_pages(source: &Document) -> Result<Vec<Document>, ComposerError> {
+    let page_count = source.get_pages().len() as u32;
+    (1..=page_count)
+        .map(|page_number| extract_pages(source, page_number..=page_number))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_dummy_pdf;
+    use lopdf::ObjectId;
+
+    #[test]
+    fn test_extract_pages_copies_only_the_range() {
+        let source = create_dummy_pdf(5, "Page");
+        let doc = extract_pages(&source, 2..=3).unwrap();
+
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        assert_eq!(pages.len(), 2);
+        for (page_id, expected) in pages.iter().zip(["Page 2", "Page 3"]) {
+            let content = doc.get_page_content(*page_id).unwrap();
+            assert!(String::from_utf8_lossy(&content).contains(expected));
+            assert!(
+                doc.get_page_fonts(*page_id)
+                    .unwrap()
+                    .contains_key(b"F1".as_slice())
+            );
+        }
+        // The other pages of the source are not dragged along through `/Parent`.
+        let page_objects = doc
+            .objects
+            .values()
+            .filter(|object| {
+                object.as_dict().is_ok_and(|dict| {
+                    dict.get(b"Type").ok() == Some(&Object::Name(b"Page".to_vec()))
+                })
+            })
+            .count();
+        assert_eq!(page_objects, 2);
+    }
+
+    #[test]
+    fn test_extract_pages_rejects_out_of_range_pages() {
+        let source = create_dummy_pdf(5, "Page");
+        for range in [0..=2, 4..=6, RangeInclusive::new(3, 2)] {
+            let error = extract_pages(&source, range).unwrap_err();
+            assert!(error.to_string().contains("5-page document"));
+        }
+    }
+
+    #[test]
+    fn test_split_pages_gives_one_document_per_page() {
+        let source = create_dummy_pdf(3, "Page");
+        let docs = split_pages(&source).unwrap();
+
+        assert_eq!(docs.len(), 3);
+        for (i, doc) in docs.iter().enumerate() {
+            let pages = doc.get_pages();
+            assert_eq!(pages.len(), 1);
+            let content = doc.get_page_content(pages[&1]).unwrap();
+            assert!(String::from_utf8_lossy(&content).contains(&format!("Page {}", i + 1)));
+            assert!(
+                doc.get_page_fonts(pages[&1])
+                    .unwrap()
+                    .contains_key(b"F1".as_slice())
+            );
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Document information dictionary (`/Info`: title, author, subject, ...) copying.
+
+use crate::{ComposerError, ObjectCopier};
+use lopdf::{Dictionary, Document, Object};
+
+/// Copies the entries of the `/Info` dictionary of `source` into that of `target`,
+/// creating it if `target` has none. Keys `target` already has are replaced only
+/// when `overwrite` is set.
+///
+/// Values are copied as they are, whether strings or not (e.g. a `/Trapped` name);
+/// objects they reference are copied along.
+pub fn copy_document_info(
+    target: &mut Document,
+    source: &Document,
+    overwrite: bool,
+) -> Result<(), ComposerError> {
+    let Some(source_info) = info_dictionary(source)? else {
+        return Ok(());
+    };
+    let mut merged = info_dictionary(target)?.cloned().unwrap_or_default();
+    let mut copier = ObjectCopier::new(source, target);
+    for (key, value) in source_info.iter() {
+        if overwrite || !merged.has(key) {
+            merged.set(key.clone(), copier.remap_references(value.clone())?);
+        }
+    }
+
+    match target.trailer.get(b"Info") {
+        Ok(Object::Reference(info_id)) => {
+            let info_id = *info_id;
+            target.objects.insert(info_id, Object::Dictionary(merged));
+        }
+        _ => {
+            let info_id = target.add_object(merged);
+            target.trailer.set("Info", info_id);
+        }
+    }
+    Ok(())
+}
+
+/// The `/Info` dictionary of `doc`, whether referenced from the trailer or inline.
+fn info_dictionary(doc: &Document) -> Result<Option<&Dictionary>, ComposerError> {
+    match doc.trailer.get(b"Info") {
+        Ok(Object::Reference(info_id)) => Ok(Some(doc.get_dictionary(*info_id)?)),
+        Ok(Object::Dictionary(info)) => Ok(Some(info)),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_dummy_pdf;
+    use lopdf::dictionary;
+
+    fn set_info(doc: &mut Document, info: Dictionary) {
+        let info_id = doc.add_object(info);
+        doc.trailer.set("Info", info_id);
+    }
+
+    fn info_value(doc: &Document, key: &[u8]) -> Option<Object> {
+        info_dictionary(doc).unwrap()?.get(key).ok().cloned()
+    }
+
+    #[test]
+    fn test_copy_document_info_fills_missing_keys() {
+        let mut source = create_dummy_pdf(1, "Cover");
+        set_info(
+            &mut source,
+            dictionary! {
+                "Title" => Object::string_literal("Annual Report"),
+                "Author" => Object::string_literal("Cover Author"),
+                "Trapped" => "False",
+            },
+        );
+        let mut target = create_dummy_pdf(2, "Body");
+        copy_document_info(&mut target, &source, false).unwrap();
+
+        assert_eq!(
+            info_value(&target, b"Title").unwrap().as_str().unwrap(),
+            b"Annual Report"
+        );
+        assert_eq!(
+            info_value(&target, b"Trapped").unwrap().as_name().unwrap(),
+            b"False"
+        );
+    }
+
+    #[test]
+    fn test_copy_document_info_keeps_target_keys_unless_overwriting() {
+        let mut source = create_dummy_pdf(1, "Cover");
+        set_info(
+            &mut source,
+            dictionary! { "Author" => Object::string_literal("Cover Author") },
+        );
+        let mut target = create_dummy_pdf(2, "Body");
+        set_info(
+            &mut target,
+            dictionary! { "Author" => Object::string_literal("Report Author") },
+        );
+
+        copy_document_info(&mut target, &source, false).unwrap();
+        assert_eq!(
+            info_value(&target, b"Author").unwrap().as_str().unwrap(),
+            b"Report Author"
+        );
+        copy_document_info(&mut target, &source, true).unwrap();
+        assert_eq!(
+            info_value(&target, b"Author").unwrap().as_str().unwrap(),
+            b"Cover Author"
+        );
+    }
+}
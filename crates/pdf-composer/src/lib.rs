@@ -1,22 +1,60 @@
 //! PDF composition utilities for merging and overlaying PDF documents.
 //!
 //! This crate provides low-level PDF manipulation using lopdf:
-//! - Deep object copying with cycle detection
-//! - Document merging (prepend/append pages)
-//! - Content overlaying (headers/footers)
-
+//! - Deep object copying with cycle detection, optionally reusing identical fonts
+//!   and streams
+//! - Document merging (prepend/append pages), preserving named destinations,
+//!   page labels and outlines
+//! - Content overlaying (headers/footers, text watermarks) and underlaying
+//!   (letterheads)
+//! - Linearization for fast web view
+//! - Embedded file attachments
+//! - Document information (`/Info`) copying
+//! - Bates numbering across merged documents
+//! - Page rotation
+//! - Blank page insertion
+//! - N-up composition (several pages per sheet)
+//! - Page range extraction into a new document, and splitting into single pages
+
+mod attachments;
+mod bates;
+mod blank_pages;
+mod dedup;
+mod destinations;
 mod error;
-
+mod extract;
+mod info;
+mod linearize;
+mod n_up;
+mod outlines;
+mod page_labels;
+mod page_resources;
+mod rotation;
+mod watermark;
+
+pub use attachments::{Attachment, embed_attachments};
+pub use bates::{BatesNumbering, PageCorner, stamp_bates_numbers};
+pub use blank_pages::insert_blank_page;
+pub use destinations::named_destinations;
 pub use error::ComposerError;
-
+pub use extract::{extract_pages, split_pages};
+pub use info::copy_document_info;
+pub use linearize::linearize;
+pub use n_up::{NUpLayout, n_up};
+pub use rotation::{page_rotation, set_page_rotation};
+pub use watermark::{WatermarkOptions, apply_text_watermark};
+
+use lopdf::content::{Content, Operation};
 use lopdf::{Document, Object, ObjectId, Stream, dictionary};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 /// A helper struct to manage the state of copying objects between documents.
 struct ObjectCopier<'a> {
     source_doc: &'a Document,
     target_doc: &'a mut Document,
     id_map: HashMap<ObjectId, ObjectId>,
+    /// The target's shareable objects, when copies of them are to be reused.
+    dedup: Option<dedup::ObjectIndex>,
 }
 
 impl<'a> ObjectCopier<'a> {
@@ -25,9 +63,17 @@ impl<'a> ObjectCopier<'a> {
             source_doc,
             target_doc,
             id_map: HashMap::new(),
+            dedup: None,
         }
     }
 
+    /// Makes the copier reuse an object already in the target instead of copying an
+    /// identical font, font descriptor or stream again.
+    fn deduplicating(mut self) -> Self {
+        self.dedup = Some(dedup::ObjectIndex::new(self.target_doc));
+        self
+    }
+
     /// Deep copies an object from the source document to the target document.
     /// It recursively copies all referenced objects, ensuring that each object
     /// is only copied once by tracking it in the `id_map`.
@@ -48,6 +94,19 @@ impl<'a> ObjectCopier<'a> {
         let obj = self.source_doc.get_object(source_id)?.clone();
         let new_obj = self.remap_references(obj)?;
 
+        // References are remapped first, so objects that differ only in the IDs
+        // the two documents gave their dependencies compare equal.
+        if let Some(index) = &mut self.dedup
+            && dedup::is_shareable(&new_obj)
+        {
+            if let Some(existing_id) = index.find(self.target_doc, &new_obj) {
+                self.target_doc.objects.remove(&new_id);
+                self.id_map.insert(source_id, existing_id);
+                return Ok(existing_id);
+            }
+            index.insert(new_id, &new_obj);
+        }
+
         // Replace the temporary Null object with the final, remapped object.
         if let Some(target_obj) = self.target_doc.objects.get_mut(&new_id) {
             *target_obj = new_obj;
@@ -105,20 +164,93 @@ impl<'a> ObjectCopier<'a> {
 /// * `prepend` - If `true`, pages from `source` are added to the beginning.
 ///   If `false`, they are appended.
 ///
-/// **Note:** This function does not yet adjust hyperlinks or outlines in the
-/// target document when prepending pages. This is a significant limitation
-/// that needs to be addressed for features like prepending a table of contents.
+/// Named destinations of `source` are copied into the target's `/Dests` dictionary
+/// under their original names; on a name clash the target's destination is kept.
+/// Use [`merge_documents_namespaced`] to keep the names of both documents apart.
+/// Page label ranges of both documents are kept, shifted to their new page positions.
+/// The outline items of `source` are copied and placed before or after the target's
+/// top-level outline items, matching where the pages go.
+///
+/// Links and outline items that address a page by its index are shifted to keep
+/// pointing at the same page. Those that reference a page object directly need no
+/// change, as the target's page objects are kept and references to source pages are
+/// remapped to their copies.
 pub fn merge_documents(
     target: &mut Document,
     source: Document,
     prepend: bool,
 ) -> Result<(), ComposerError> {
+    merge_documents_with(
+        target,
+        source,
+        &MergeOptions {
+            prepend,
+            ..MergeOptions::default()
+        },
+    )
+}
+
+/// Like [`merge_documents`], but copies the named destinations of `source` as
+/// `namespace:name`. Links inside `source` that target its own destinations by name
+/// are rewritten to match, so they keep resolving after the merge, and other documents
+/// can link to them by their namespaced name.
+pub fn merge_documents_namespaced(
+    target: &mut Document,
+    source: Document,
+    prepend: bool,
+    namespace: &str,
+) -> Result<(), ComposerError> {
+    merge_documents_with(
+        target,
+        source,
+        &MergeOptions {
+            prepend,
+            namespace: Some(namespace),
+            ..MergeOptions::default()
+        },
+    )
+}
+
+/// Options for [`merge_documents_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeOptions<'a> {
+    /// Add the pages of `source` before those of `target` instead of after them.
+    pub prepend: bool,
+    /// Copy the named destinations of `source` as `namespace:name`, as
+    /// [`merge_documents_namespaced`] does.
+    pub namespace: Option<&'a str>,
+    /// Reuse fonts, font descriptors and streams already in `target` instead of
+    /// copying identical ones from `source`. Worth enabling when merging many
+    /// documents generated alike, which would otherwise each carry their own copy.
+    pub deduplicate: bool,
+}
+
+/// Merges the pages of `source` into `target` as [`merge_documents`] does, with
+/// the given options.
+pub fn merge_documents_with(
+    target: &mut Document,
+    source: Document,
+    options: &MergeOptions,
+) -> Result<(), ComposerError> {
+    let MergeOptions {
+        prepend,
+        namespace,
+        deduplicate,
+    } = *options;
     let source_pages = source.get_pages();
     if source_pages.is_empty() {
         return Ok(());
     }
+    let source_dests = named_destinations(&source)?;
+    let source_names: BTreeSet<Vec<u8>> = source_dests.keys().cloned().collect();
+    let source_labels = page_labels::page_label_ranges(&source)?;
+    let target_page_ids: Vec<ObjectId> = target.get_pages().into_values().collect();
+    let target_outline_items = destinations::document_outline_items(target)?;
 
     let mut copier = ObjectCopier::new(&source, target);
+    if deduplicate {
+        copier = copier.deduplicating();
+    }
     let mut new_page_ids = Vec::new();
     let mut copied_page_ids = Vec::new();
 
@@ -134,6 +266,17 @@ pub fn merge_documents(
         copied_page_ids.push(new_page_id);
     }
 
+    // Destinations point at pages that were just copied, so this only remaps references.
+    let mut dests = BTreeMap::new();
+    for (name, dest) in source_dests {
+        let name = match namespace {
+            Some(namespace) => destinations::namespaced_name(namespace, &name),
+            None => name,
+        };
+        dests.insert(name, copier.remap_references(dest)?);
+    }
+    let copied_outline = outlines::copy_outline(&mut copier)?;
+
     // Now, manipulate the page tree in the target document
     let root_id = target.trailer.get(b"Root")?.as_reference()?;
     let root_dict = target.get_object_mut(root_id)?.as_dict_mut()?;
@@ -144,8 +287,6 @@ pub fn merge_documents(
     let original_count = pages_dict.get(b"Count")?.as_i64()?;
 
     if prepend {
-        // TODO: This is where link/outline adjustment for the *original* pages would need to happen.
-        // For now, we just prepend the new pages.
         let mut final_kids = new_page_ids;
         final_kids.extend(kids);
         kids = final_kids;
@@ -157,12 +298,45 @@ pub fn merge_documents(
     pages_dict.set("Count", original_count + (source.get_pages().len() as i64));
 
     // Parent references in the copied page objects need to be updated to point to the target's page tree.
-    for page_id in copied_page_ids {
-        if let Ok(Object::Dictionary(page_dict)) = target.get_object_mut(page_id) {
+    for page_id in &copied_page_ids {
+        if let Ok(Object::Dictionary(page_dict)) = target.get_object_mut(*page_id) {
             page_dict.set("Parent", Object::Reference(pages_id));
         }
     }
 
+    page_labels::merge_page_labels(
+        target,
+        source_labels,
+        original_count,
+        copied_page_ids.len() as i64,
+        prepend,
+    )?;
+
+    // Page indices of whichever document now comes second are off by the pages of the other.
+    let mut target_holders = destinations::link_annotations(target, &target_page_ids)?;
+    target_holders.extend(target_outline_items);
+    let mut source_holders = destinations::link_annotations(target, &copied_page_ids)?;
+    if let Some(copied_outline) = &copied_outline {
+        source_holders.extend(copied_outline.items.iter().copied());
+    }
+    if prepend {
+        destinations::shift_page_index_targets(
+            target,
+            target_holders,
+            copied_page_ids.len() as i64,
+        )?;
+    } else {
+        destinations::shift_page_index_targets(target, source_holders.clone(), original_count)?;
+    }
+
+    destinations::insert_named_destinations(target, dests)?;
+    if let Some(namespace) = namespace {
+        destinations::namespace_targets(target, source_holders, &source_names, namespace)?;
+    }
+    if let Some(copied_outline) = copied_outline {
+        outlines::splice_outline(target, copied_outline, prepend)?;
+    }
+
     Ok(())
 }
 
@@ -175,53 +349,109 @@ pub fn merge_documents(
 /// * `doc` - The document containing the page to modify.
 /// * `page_id` - The `ObjectId` of the page to add the overlay to.
 /// * `content_stream` - The raw bytes of the new content stream.
+///
+/// The overlay is drawn in the page's visible frame: on a page with a `/Rotate`
+/// entry it is transformed so that it appears upright, with the origin at the
+/// bottom-left corner of the page as displayed.
 pub fn overlay_content(
     doc: &mut Document,
     page_id: ObjectId,
     content_stream: Vec<u8>,
 ) -> Result<(), ComposerError> {
-    let stream = Stream::new(dictionary! {}, content_stream);
-    let new_content_id = doc.add_object(Object::Stream(stream));
-
-    let page_dict = doc.get_object_mut(page_id)?.as_dict_mut()?;
-
-    // The `get_mut` method on a `lopdf::Dictionary` returns a `Result`, not an `Option`.
-    // We match on `Ok` for success (key exists) and `Err` for failure (key not found).
-    match page_dict.get_mut(b"Contents") {
-        Ok(contents_obj) => {
-            let mut new_contents_array = match contents_obj.as_array() {
-                Ok(arr) => arr.clone(),
-                Err(_) => {
-                    // It's not an array, so it must be a single reference. Wrap it.
-                    vec![contents_obj.clone()]
-                }
+    let content_stream = in_visible_frame(doc, page_id, content_stream)?;
+    add_content_stream(doc, page_id, content_stream, false)
+}
+
+/// Transforms content on a page with a `/Rotate` entry so that it is drawn upright in
+/// the page's visible frame.
+fn in_visible_frame(
+    doc: &Document,
+    page_id: ObjectId,
+    content_stream: Vec<u8>,
+) -> Result<Vec<u8>, ComposerError> {
+    Ok(match rotation::rotation_matrix(doc, page_id)? {
+        Some(matrix) => {
+            let cm = Content {
+                operations: vec![
+                    Operation::new("q", vec![]),
+                    Operation::new("cm", matrix.into_iter().map(Object::Real).collect()),
+                ],
             };
+            let mut rotated = cm.encode()?;
+            rotated.push(b'\n');
+            rotated.extend(content_stream);
+            rotated.extend(b"\nQ");
+            rotated
+        }
+        None => content_stream,
+    })
+}
 
-            // Add the new content stream to the end, so it's drawn on top.
-            new_contents_array.push(Object::Reference(new_content_id));
+/// Adds a new content stream to an existing page, underneath its existing content.
+///
+/// This is useful for background stamps such as a letterhead, which the page's
+/// text should be drawn over. Like [`overlay_content`], the new content is drawn
+/// in the page's visible frame. It is wrapped in `q`/`Q` so that graphics state it
+/// sets does not carry over into the existing content.
+pub fn overlay_content_below(
+    doc: &mut Document,
+    page_id: ObjectId,
+    content_stream: Vec<u8>,
+) -> Result<(), ComposerError> {
+    let mut isolated = b"q\n".to_vec();
+    isolated.extend(content_stream);
+    isolated.extend(b"\nQ");
+    let content_stream = in_visible_frame(doc, page_id, isolated)?;
+    add_content_stream(doc, page_id, content_stream, true)
+}
 
-            page_dict.set("Contents", Object::Array(new_contents_array));
-        }
-        Err(_) => {
-            return Err(ComposerError::Other(format!(
-                "Page {:?} is missing a /Contents key.",
-                page_id
-            )));
-        }
-    }
+/// Adds `content_stream` as a new stream at the end of the page's `/Contents`, or at
+/// the start when `below` is set.
+fn add_content_stream(
+    doc: &mut Document,
+    page_id: ObjectId,
+    content_stream: Vec<u8>,
+    below: bool,
+) -> Result<(), ComposerError> {
+    let contents = doc
+        .get_dictionary(page_id)?
+        .get(b"Contents")
+        .map_err(|_| {
+            ComposerError::Other(format!("Page {:?} is missing a /Contents key.", page_id))
+        })?
+        .clone();
+    let mut streams = match contents {
+        Object::Array(streams) => streams,
+        // An indirect array of streams is spliced in rather than nested.
+        Object::Reference(id) => match doc.get_object(id)? {
+            Object::Array(streams) => streams.clone(),
+            // It's not an array, so it must be a single stream. Wrap it.
+            _ => vec![Object::Reference(id)],
+        },
+        other => vec![other],
+    };
 
+    let stream = Stream::new(dictionary! {}, content_stream);
+    let new_content_id = Object::Reference(doc.add_object(Object::Stream(stream)));
+    // Streams later in the array are drawn on top of earlier ones.
+    if below {
+        streams.insert(0, new_content_id);
+    } else {
+        streams.push(new_content_id);
+    }
+    doc.get_dictionary_mut(page_id)?
+        .set("Contents", Object::Array(streams));
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use lopdf::content::{Content, Operation};
     use lopdf::{Document, Object, StringFormat, dictionary};
 
     /// Creates a simple dummy PDF document with a specified number of pages.
     /// Each page has a unique text content "Page X".
-    fn create_dummy_pdf(num_pages: u32, text_prefix: &str) -> Document {
+    pub(crate) fn create_dummy_pdf(num_pages: u32, text_prefix: &str) -> Document {
         let mut doc = Document::with_version("1.7");
         let pages_id = doc.new_object_id();
         let font_id = doc.add_object(dictionary! {
@@ -300,6 +530,48 @@ mod tests {
         assert!(String::from_utf8_lossy(&page_3_content).contains("Source Page 1"));
     }
 
+    #[test]
+    fn test_merge_documents_with_dedup_shares_fonts() {
+        let count_fonts = |doc: &Document| {
+            doc.objects
+                .values()
+                .filter(|object| {
+                    matches!(object.as_dict().and_then(|d| d.get(b"Type")),
+                        Ok(Object::Name(kind)) if kind == b"Font")
+                })
+                .count()
+        };
+        let merge_ten = |deduplicate| {
+            let mut target_doc = create_dummy_pdf(1, "Page");
+            let options = MergeOptions {
+                deduplicate,
+                ..MergeOptions::default()
+            };
+            for _ in 1..10 {
+                merge_documents_with(&mut target_doc, create_dummy_pdf(1, "Page"), &options)
+                    .unwrap();
+            }
+            target_doc
+        };
+
+        let deduplicated = merge_ten(true);
+        assert_eq!(deduplicated.get_pages().len(), 10);
+        assert_eq!(count_fonts(&deduplicated), 1);
+        assert_eq!(count_fonts(&merge_ten(false)), 10);
+
+        // The pages still render with the shared font.
+        let last_page = deduplicated.get_pages()[&10];
+        let fonts = deduplicated.get_page_fonts(last_page).unwrap();
+        assert_eq!(
+            fonts[b"F1".as_slice()]
+                .get(b"BaseFont")
+                .unwrap()
+                .as_name()
+                .unwrap(),
+            b"Helvetica"
+        );
+    }
+
     #[test]
     fn test_merge_documents_prepend() {
         let mut target_doc = create_dummy_pdf(2, "Target Page");
@@ -328,6 +600,263 @@ mod tests {
         assert!(String::from_utf8_lossy(&page_4_content).contains("Target Page 1"));
     }
 
+    #[test]
+    fn test_merge_documents_prepend_keeps_links_and_outlines_on_target() {
+        let mut target_doc = create_dummy_pdf(2, "Body Page");
+        let pages = target_doc.get_pages();
+        let rect = || vec![0.into(), 0.into(), 10.into(), 10.into()];
+        // Page 2 as a page reference, and as a (zero-based) page index.
+        let by_reference = target_doc.add_object(dictionary! {
+            "Type" => "Annot", "Subtype" => "Link", "Rect" => rect(),
+            "Dest" => vec![pages[&2].into(), "Fit".into()],
+        });
+        let by_index = target_doc.add_object(dictionary! {
+            "Type" => "Annot", "Subtype" => "Link", "Rect" => rect(),
+            "A" => dictionary! { "S" => "GoTo", "D" => vec![1.into(), "Fit".into()] },
+        });
+        target_doc
+            .get_dictionary_mut(pages[&1])
+            .unwrap()
+            .set("Annots", vec![by_reference.into(), by_index.into()]);
+
+        let outlines_id = target_doc.new_object_id();
+        let item_id = target_doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Chapter 2"),
+            "Parent" => outlines_id,
+            "Dest" => vec![1.into(), "Fit".into()],
+        });
+        target_doc.objects.insert(
+            outlines_id,
+            dictionary! { "Type" => "Outlines", "First" => item_id, "Last" => item_id, "Count" => 1 }
+                .into(),
+        );
+        let root_id = target_doc
+            .trailer
+            .get(b"Root")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        target_doc
+            .get_dictionary_mut(root_id)
+            .unwrap()
+            .set("Outlines", outlines_id);
+
+        merge_documents(&mut target_doc, create_dummy_pdf(1, "Contents"), true).unwrap();
+
+        let pages = target_doc.get_pages();
+        assert_eq!(pages.len(), 3);
+        let annot = |id| target_doc.get_dictionary(id).unwrap();
+        let dest = annot(by_reference)
+            .get(b"Dest")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(dest[0].as_reference().unwrap(), pages[&3]);
+        let action = annot(by_index).get(b"A").unwrap().as_dict().unwrap();
+        let dest = action.get(b"D").unwrap().as_array().unwrap();
+        assert_eq!(dest[0].as_i64().unwrap(), 2);
+        let item = target_doc.get_dictionary(item_id).unwrap();
+        let dest = item.get(b"Dest").unwrap().as_array().unwrap();
+        assert_eq!(dest[0].as_i64().unwrap(), 2);
+    }
+
+    /// Adds a top-level bookmark titled `<prefix> <n>` for every page of `doc`.
+    fn add_page_bookmarks(doc: &mut Document, prefix: &str) {
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        let outlines_id = doc.new_object_id();
+        let item_ids: Vec<ObjectId> = (0..pages.len()).map(|_| doc.new_object_id()).collect();
+        for (i, (item_id, page_id)) in item_ids.iter().zip(&pages).enumerate() {
+            let mut item = dictionary! {
+                "Title" => Object::string_literal(format!("{} {}", prefix, i + 1)),
+                "Parent" => outlines_id,
+                "Dest" => vec![(*page_id).into(), "Fit".into()],
+            };
+            if i > 0 {
+                item.set("Prev", item_ids[i - 1]);
+            }
+            if let Some(next) = item_ids.get(i + 1) {
+                item.set("Next", *next);
+            }
+            doc.objects.insert(*item_id, item.into());
+        }
+        let outlines = dictionary! {
+            "Type" => "Outlines",
+            "First" => item_ids[0],
+            "Last" => *item_ids.last().unwrap(),
+            "Count" => item_ids.len() as i64,
+        };
+        doc.objects.insert(outlines_id, outlines.into());
+        let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        doc.get_dictionary_mut(root_id)
+            .unwrap()
+            .set("Outlines", outlines_id);
+    }
+
+    /// Returns the titles and destination pages of the top-level outline items.
+    fn top_level_bookmarks(doc: &Document) -> Vec<(String, ObjectId)> {
+        let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let outlines_id = doc
+            .get_dictionary(root_id)
+            .unwrap()
+            .get(b"Outlines")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let outlines = doc.get_dictionary(outlines_id).unwrap();
+        let mut bookmarks = Vec::new();
+        let mut next = outlines.get(b"First").and_then(Object::as_reference).ok();
+        while let Some(item_id) = next {
+            let item = doc.get_dictionary(item_id).unwrap();
+            assert_eq!(
+                item.get(b"Parent").unwrap().as_reference().unwrap(),
+                outlines_id
+            );
+            let title = String::from_utf8_lossy(item.get(b"Title").unwrap().as_str().unwrap());
+            let dest = item.get(b"Dest").unwrap().as_array().unwrap();
+            bookmarks.push((title.into_owned(), dest[0].as_reference().unwrap()));
+            next = item.get(b"Next").and_then(Object::as_reference).ok();
+        }
+        bookmarks
+    }
+
+    #[test]
+    fn test_merge_documents_combines_outlines() {
+        let mut target_doc = create_dummy_pdf(2, "Target Page");
+        add_page_bookmarks(&mut target_doc, "Target");
+        let mut source_doc = create_dummy_pdf(2, "Source Page");
+        add_page_bookmarks(&mut source_doc, "Source");
+
+        merge_documents(&mut target_doc, source_doc, false).unwrap();
+
+        let pages: Vec<ObjectId> = target_doc.get_pages().into_values().collect();
+        let bookmarks = top_level_bookmarks(&target_doc);
+        let expected: Vec<(String, ObjectId)> = ["Target 1", "Target 2", "Source 1", "Source 2"]
+            .into_iter()
+            .map(String::from)
+            .zip(pages)
+            .collect();
+        assert_eq!(bookmarks, expected);
+
+        let root_id = target_doc
+            .trailer
+            .get(b"Root")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let outlines = target_doc
+            .get_dictionary(root_id)
+            .unwrap()
+            .get_deref(b"Outlines", &target_doc)
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        assert_eq!(outlines.get(b"Count").unwrap().as_i64().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_merge_documents_creates_outline_for_source_bookmarks() {
+        let mut target_doc = create_dummy_pdf(1, "Body Page");
+        let mut source_doc = create_dummy_pdf(1, "Contents");
+        add_page_bookmarks(&mut source_doc, "Contents");
+
+        merge_documents(&mut target_doc, source_doc, true).unwrap();
+
+        let first_page = target_doc.get_pages()[&1];
+        assert_eq!(
+            top_level_bookmarks(&target_doc),
+            vec![("Contents 1".to_string(), first_page)]
+        );
+    }
+
+    /// Defines `name` as a destination on `page`, and adds a link to it on page 1.
+    fn add_named_destination(doc: &mut Document, name: &str, page: u32) {
+        let pages = doc.get_pages();
+        let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let dest = vec![pages[&page].into(), "FitH".into(), 700.into()];
+        doc.get_dictionary_mut(root_id)
+            .unwrap()
+            .set("Dests", dictionary! { name => dest });
+
+        let action_id = doc.add_object(dictionary! {
+            "S" => "GoTo",
+            "D" => Object::string_literal(name),
+        });
+        let annot_id = doc.add_object(dictionary! {
+            "Type" => "Annot", "Subtype" => "Link",
+            "Rect" => vec![0.into(), 0.into(), 10.into(), 10.into()],
+            "A" => action_id,
+        });
+        doc.get_dictionary_mut(pages[&1])
+            .unwrap()
+            .set("Annots", vec![annot_id.into()]);
+    }
+
+    #[test]
+    fn test_merge_documents_prepend_shifts_page_labels() {
+        let mut target_doc = create_dummy_pdf(2, "Body Page");
+        let root_id = target_doc
+            .trailer
+            .get(b"Root")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        target_doc.get_dictionary_mut(root_id).unwrap().set(
+            "PageLabels",
+            dictionary! { "Nums" => vec![0.into(), dictionary! { "S" => "D" }.into()] },
+        );
+        let source_doc = create_dummy_pdf(3, "Cover Page");
+
+        merge_documents(&mut target_doc, source_doc, true).unwrap();
+
+        let labels = target_doc
+            .get_dictionary(root_id)
+            .unwrap()
+            .get(b"PageLabels")
+            .and_then(Object::as_dict)
+            .unwrap();
+        let nums = labels.get(b"Nums").unwrap().as_array().unwrap();
+        let starts: Vec<i64> = nums
+            .iter()
+            .step_by(2)
+            .map(|n| n.as_i64().unwrap())
+            .collect();
+        assert_eq!(starts, vec![0, 3]);
+    }
+
+    #[test]
+    fn test_merge_documents_namespaced_keeps_destinations_resolvable() {
+        let mut target_doc = create_dummy_pdf(2, "Target Page");
+        add_named_destination(&mut target_doc, "intro", 2);
+        let mut source_doc = create_dummy_pdf(2, "Source Page");
+        add_named_destination(&mut source_doc, "intro", 2);
+
+        merge_documents_namespaced(&mut target_doc, source_doc, false, "appendix").unwrap();
+
+        let pages = target_doc.get_pages();
+        let dests = named_destinations(&target_doc).unwrap();
+        let dest_page = |name: &[u8]| dests[name].as_array().unwrap()[0].as_reference().unwrap();
+        assert_eq!(dests.len(), 2);
+        assert_eq!(dest_page(b"intro"), pages[&2]);
+        assert_eq!(dest_page(b"appendix:intro"), pages[&4]);
+
+        // The source's own link now targets its namespaced destination.
+        let link_target = |page: u32| {
+            let page_dict = target_doc.get_dictionary(pages[&page]).unwrap();
+            let annot_id = page_dict.get(b"Annots").unwrap().as_array().unwrap()[0]
+                .as_reference()
+                .unwrap();
+            let annot = target_doc.get_dictionary(annot_id).unwrap();
+            let action = annot
+                .get_deref(b"A", &target_doc)
+                .unwrap()
+                .as_dict()
+                .unwrap();
+            action.get(b"D").unwrap().as_str().unwrap().to_vec()
+        };
+        assert_eq!(link_target(1), b"intro");
+        assert_eq!(link_target(3), b"appendix:intro");
+    }
+
     #[test]
     fn test_overlay_content() {
         let mut doc = create_dummy_pdf(1, "Original Content");
@@ -364,4 +893,68 @@ mod tests {
         assert!(content_str.contains("Original Content"));
         assert!(content_str.contains("Overlay Content"));
     }
+
+    #[test]
+    fn test_overlay_content_below_goes_before_the_page_content() {
+        let mut doc = create_dummy_pdf(1, "Original Content");
+        let page_id = doc.get_pages()[&1];
+        let original = doc
+            .get_dictionary(page_id)
+            .unwrap()
+            .get(b"Contents")
+            .unwrap()
+            .clone();
+
+        overlay_content_below(&mut doc, page_id, b"BT (Letterhead) Tj ET".to_vec()).unwrap();
+
+        let contents = doc
+            .get_dictionary(page_id)
+            .unwrap()
+            .get(b"Contents")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .clone();
+        assert_eq!(contents.len(), 2);
+        assert_eq!(contents[1], original);
+        let full_content = doc.get_page_content(page_id).unwrap();
+        let content_str = String::from_utf8_lossy(&full_content);
+        let letterhead = content_str.find("Letterhead").unwrap();
+        assert!(letterhead < content_str.find("Original Content").unwrap());
+        assert!(content_str.starts_with("q\n"));
+    }
+
+    #[test]
+    fn test_overlay_content_splices_an_indirect_contents_array() {
+        let mut doc = create_dummy_pdf(1, "Original Content");
+        let page_id = doc.get_pages()[&1];
+        let original = doc
+            .get_dictionary(page_id)
+            .unwrap()
+            .get(b"Contents")
+            .unwrap()
+            .clone();
+        let array_id = doc.add_object(vec![original.clone()]);
+        doc.get_dictionary_mut(page_id)
+            .unwrap()
+            .set("Contents", array_id);
+
+        overlay_content_below(&mut doc, page_id, b"BT (Below) Tj ET".to_vec()).unwrap();
+        overlay_content(&mut doc, page_id, b"BT (Above) Tj ET".to_vec()).unwrap();
+
+        let contents = doc
+            .get_dictionary(page_id)
+            .unwrap()
+            .get(b"Contents")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .clone();
+        assert_eq!(contents.len(), 3);
+        assert_eq!(contents[1], original);
+        assert!(contents.iter().all(|stream| {
+            let id = stream.as_reference().unwrap();
+            doc.get_object(id).unwrap().as_stream().is_ok()
+        }));
+    }
 }
@@ -0,0 +1,752 @@
+//! Linearized ("Fast Web View") serialization, as described in ISO 32000-1 Annex F.
+//!
+//! A linearized file starts with a linearization parameter dictionary, followed by a
+//! first-page cross-reference section, the catalog, the primary hint stream and every
+//! object needed to display the first page. The remaining pages follow in order, then
+//! document-level objects, and finally the main cross-reference table. Viewers that
+//! understand linearization can render the first page as soon as its section arrives.
+//!
+//! Objects are renumbered so that the main cross-reference table covers the later
+//! pages (`1..=m`) and the first-page table covers everything in the first-page section.
+
+use crate::ComposerError;
+use lopdf::{Dictionary, Document, Object, ObjectId, StringFormat};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+/// Serializes `doc` as a linearized PDF.
+///
+/// Unreachable objects are dropped. Encrypted documents and documents without pages
+/// cannot be linearized.
+pub fn linearize(doc: &Document) -> Result<Vec<u8>, ComposerError> {
+    if doc.trailer.has(b"Encrypt") {
+        return Err(ComposerError::Other(
+            "Cannot linearize an encrypted document".to_string(),
+        ));
+    }
+    let root_id = doc.trailer.get(b"Root")?.as_reference()?;
+    let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    if page_ids.is_empty() {
+        return Err(ComposerError::Other(
+            "Cannot linearize a document without pages".to_string(),
+        ));
+    }
+
+    let plan = Plan::new(doc, root_id, &page_ids);
+    let bodies = plan.serialize_bodies(doc)?;
+    let trailer_tail = plan.trailer_tail(doc)?;
+
+    // Hint tables describe the file as if the hint stream were absent, so a layout with
+    // an empty hint stream provides every offset they need.
+    let empty_hint = hint_stream_object(plan.hint_number(), &[], 0)?;
+    let (_, draft) = plan.assemble(&bodies, &empty_hint, &trailer_tail, &Params::default())?;
+    let (hint_data, shared_offset) = plan.hint_tables(&bodies, &draft, empty_hint.len() as u64);
+    let hint = hint_stream_object(plan.hint_number(), &hint_data, shared_offset)?;
+
+    // The parameter dictionary and the first-page trailer use fixed-width numbers, so
+    // filling in the real values does not move anything.
+    let (_, layout) = plan.assemble(&bodies, &hint, &trailer_tail, &Params::default())?;
+    let params = Params {
+        file_length: layout.file_length,
+        hint_offset: layout.hint_offset,
+        hint_length: hint.len() as u64,
+        first_page_end: layout.first_page_end,
+        main_xref_entries: layout.main_xref_entries,
+        main_xref: layout.main_xref,
+    };
+    let (bytes, _) = plan.assemble(&bodies, &hint, &trailer_tail, &params)?;
+    Ok(bytes)
+}
+
+/// Object ordering and numbering for the linearized file.
+struct Plan {
+    version: String,
+    /// Old object ids in file order: the first-page section (catalog, then page 1).
+    first_section: Vec<ObjectId>,
+    /// Old object ids in file order: later pages, then document-level objects.
+    main_section: Vec<ObjectId>,
+    /// Index into `main_section` where each later page starts, plus the section end.
+    page_starts: Vec<usize>,
+    /// Position in `first_section` of the first page object.
+    first_page_index: usize,
+    /// Number of objects belonging to each page, including the page object.
+    page_object_counts: Vec<usize>,
+    /// Content stream ids per page, as old ids.
+    page_contents: Vec<Vec<ObjectId>>,
+    renumber: HashMap<ObjectId, u32>,
+    page_count: usize,
+}
+
+/// Fixed-width values patched into the parameter dictionary and first-page trailer.
+#[derive(Default)]
+struct Params {
+    file_length: u64,
+    hint_offset: u64,
+    hint_length: u64,
+    first_page_end: u64,
+    main_xref_entries: u64,
+    main_xref: u64,
+}
+
+/// Byte positions recorded while assembling the file.
+struct Layout {
+    file_length: u64,
+    hint_offset: u64,
+    first_page_end: u64,
+    main_xref_entries: u64,
+    main_xref: u64,
+    first_offsets: Vec<u64>,
+    main_offsets: Vec<u64>,
+}
+
+impl Plan {
+    fn new(doc: &Document, root_id: ObjectId, page_ids: &[ObjectId]) -> Self {
+        let mut boundary: HashSet<ObjectId> = page_ids.iter().copied().collect();
+        boundary.insert(root_id);
+        for (id, object) in &doc.objects {
+            if let Object::Dictionary(dict) = object
+                && dict.has_type(b"Pages")
+            {
+                boundary.insert(*id);
+            }
+        }
+
+        let mut assigned: HashSet<ObjectId> = HashSet::new();
+        assigned.insert(root_id);
+
+        let mut page_sections = Vec::with_capacity(page_ids.len());
+        for &page_id in page_ids {
+            let mut section = vec![page_id];
+            assigned.insert(page_id);
+            collect_references(doc, page_id, &boundary, &mut assigned, &mut section);
+            page_sections.push(section);
+        }
+
+        let mut document_objects = Vec::new();
+        collect_references(
+            doc,
+            root_id,
+            &HashSet::new(),
+            &mut assigned,
+            &mut document_objects,
+        );
+        for (_, value) in doc.trailer.iter() {
+            if let Object::Reference(id) = value
+                && doc.objects.contains_key(id)
+                && assigned.insert(*id)
+            {
+                document_objects.push(*id);
+                collect_references(
+                    doc,
+                    *id,
+                    &HashSet::new(),
+                    &mut assigned,
+                    &mut document_objects,
+                );
+            }
+        }
+
+        let page_object_counts = page_sections.iter().map(Vec::len).collect();
+        let page_contents = page_ids
+            .iter()
+            .map(|id| content_stream_ids(doc, *id))
+            .collect();
+
+        let mut sections = page_sections.into_iter();
+        let mut first_section = vec![root_id];
+        let first_page_index = first_section.len();
+        first_section.extend(sections.next().unwrap_or_default());
+
+        let mut main_section = Vec::new();
+        let mut page_starts = Vec::new();
+        for section in sections {
+            page_starts.push(main_section.len());
+            main_section.extend(section);
+        }
+        page_starts.push(main_section.len());
+        main_section.extend(document_objects);
+
+        // Main table: 1..=m; first-page table: the parameter dictionary, then the
+        // first-page section with the hint stream right after the catalog.
+        let mut renumber = HashMap::new();
+        for (i, id) in main_section.iter().enumerate() {
+            renumber.insert(*id, i as u32 + 1);
+        }
+        let first_number = main_section.len() as u32 + 1;
+        for (i, id) in first_section.iter().enumerate() {
+            let slot = if i == 0 { 1 } else { i + 2 };
+            renumber.insert(*id, first_number + slot as u32);
+        }
+
+        Self {
+            version: doc.version.clone(),
+            first_section,
+            main_section,
+            page_starts,
+            first_page_index,
+            page_object_counts,
+            page_contents,
+            renumber,
+            page_count: page_ids.len(),
+        }
+    }
+
+    fn first_number(&self) -> u32 {
+        self.main_section.len() as u32 + 1
+    }
+
+    fn hint_number(&self) -> u32 {
+        self.first_number() + 2
+    }
+
+    /// Total number of entries across both cross-reference sections, including object 0.
+    fn size(&self) -> u32 {
+        self.first_number() + self.first_section.len() as u32 + 2
+    }
+
+    fn serialize_bodies(&self, doc: &Document) -> Result<Bodies, ComposerError> {
+        let serialize = |ids: &[ObjectId]| -> Result<Vec<Vec<u8>>, ComposerError> {
+            ids.iter()
+                .map(|id| {
+                    let object = doc.get_object(*id)?;
+                    let mut out = Vec::new();
+                    write_indirect(&mut out, self.renumber[id], &self.rewrite(object))?;
+                    Ok(out)
+                })
+                .collect()
+        };
+        Ok(Bodies {
+            first: serialize(&self.first_section)?,
+            main: serialize(&self.main_section)?,
+        })
+    }
+
+    /// The trailer entries shared by both trailers besides `/Size` and `/Prev`.
+    fn trailer_tail(&self, doc: &Document) -> Result<Vec<u8>, ComposerError> {
+        let mut tail = Dictionary::new();
+        for (key, value) in doc.trailer.iter() {
+            if matches!(key.as_slice(), b"Root" | b"Info" | b"ID") {
+                tail.set(key.clone(), self.rewrite(value));
+            }
+        }
+        let mut out = Vec::new();
+        write_dictionary_entries(&mut out, &tail)?;
+        Ok(out)
+    }
+
+    fn rewrite(&self, object: &Object) -> Object {
+        match object {
+            Object::Reference(id) => match self.renumber.get(id) {
+                Some(number) => Object::Reference((*number, 0)),
+                None => Object::Null,
+            },
+            Object::Array(items) => Object::Array(items.iter().map(|o| self.rewrite(o)).collect()),
+            Object::Dictionary(dict) => Object::Dictionary(self.rewrite_dictionary(dict)),
+            Object::Stream(stream) => {
+                let mut stream = stream.clone();
+                stream.dict = self.rewrite_dictionary(&stream.dict);
+                Object::Stream(stream)
+            }
+            other => other.clone(),
+        }
+    }
+
+    fn rewrite_dictionary(&self, dict: &Dictionary) -> Dictionary {
+        let mut out = Dictionary::new();
+        for (key, value) in dict.iter() {
+            out.set(key.clone(), self.rewrite(value));
+        }
+        out
+    }
+
+    fn assemble(
+        &self,
+        bodies: &Bodies,
+        hint: &[u8],
+        trailer_tail: &[u8],
+        params: &Params,
+    ) -> io::Result<(Vec<u8>, Layout)> {
+        let mut out: Vec<u8> = Vec::new();
+        writeln!(out, "%PDF-{}", self.version)?;
+        out.extend_from_slice(b"%\xe2\xe3\xcf\xd3\n");
+
+        let first_number = self.first_number();
+        let parameters_offset = out.len();
+        write!(
+            out,
+            "{} 0 obj\n<</Linearized 1/L {:010}/H [{:010} {:010}]/O {}/E {:010}/N {}/T {:010}>>\nendobj\n",
+            first_number,
+            params.file_length,
+            params.hint_offset,
+            params.hint_length,
+            self.renumber[&self.first_section[self.first_page_index]],
+            params.first_page_end,
+            self.page_count,
+            params.main_xref_entries,
+        )?;
+
+        // The first-page table lists the parameter dictionary, the catalog, the hint
+        // stream and the first page's objects. Offsets are only known once written, so
+        // reserve fixed-width entries and fill them in afterwards.
+        let first_xref = out.len() as u64;
+        let entry_count = self.first_section.len() + 2;
+        writeln!(out, "xref\n{} {}", first_number, entry_count)?;
+        let entries_at = out.len();
+        out.resize(entries_at + entry_count * 20, b' ');
+        write!(out, "trailer\n<</Size {}", self.size())?;
+        out.extend_from_slice(trailer_tail);
+        write!(
+            out,
+            "/Prev {:010}>>\nstartxref\n0\n%%EOF\n",
+            params.main_xref
+        )?;
+
+        let mut first_offsets = Vec::with_capacity(self.first_section.len());
+        let mut hint_offset = 0;
+        for (i, body) in bodies.first.iter().enumerate() {
+            first_offsets.push(out.len() as u64);
+            out.extend_from_slice(body);
+            if i == 0 {
+                hint_offset = out.len() as u64;
+                out.extend_from_slice(hint);
+            }
+        }
+        let first_page_end = out.len() as u64;
+
+        let mut main_offsets = Vec::with_capacity(self.main_section.len());
+        for body in &bodies.main {
+            main_offsets.push(out.len() as u64);
+            out.extend_from_slice(body);
+        }
+
+        let main_xref = out.len() as u64;
+        write!(out, "xref\n0 {}", self.main_section.len() + 1)?;
+        let main_xref_entries = out.len() as u64;
+        out.extend_from_slice(b"\n0000000000 65535 f \n");
+        for offset in &main_offsets {
+            writeln!(out, "{:010} 00000 n ", offset)?;
+        }
+        write!(
+            out,
+            "trailer\n<</Size {}>>\nstartxref\n{}\n%%EOF\n",
+            self.main_section.len() + 1,
+            first_xref
+        )?;
+
+        let mut entries = Vec::with_capacity(entry_count * 20);
+        writeln!(entries, "{:010} 00000 n ", parameters_offset)?;
+        writeln!(entries, "{:010} 00000 n ", first_offsets[0])?;
+        writeln!(entries, "{:010} 00000 n ", hint_offset)?;
+        for offset in &first_offsets[1..] {
+            writeln!(entries, "{:010} 00000 n ", offset)?;
+        }
+        out[entries_at..entries_at + entries.len()].copy_from_slice(&entries);
+
+        let layout = Layout {
+            file_length: out.len() as u64,
+            hint_offset,
+            first_page_end,
+            main_xref_entries,
+            main_xref,
+            first_offsets,
+            main_offsets,
+        };
+        Ok((out, layout))
+    }
+
+    /// Builds the page offset and shared object hint tables. Returns the table data and
+    /// the offset of the shared object table within it.
+    fn hint_tables(&self, bodies: &Bodies, layout: &Layout, hint_length: u64) -> (Vec<u8>, usize) {
+        // Offsets after the hint stream are reported as if it were not present.
+        let first_offsets: Vec<u64> = layout
+            .first_offsets
+            .iter()
+            .enumerate()
+            .map(|(i, o)| if i == 0 { *o } else { o - hint_length })
+            .collect();
+        let main_offsets: Vec<u64> = layout
+            .main_offsets
+            .iter()
+            .map(|o| o - hint_length)
+            .collect();
+        let first_page_end = layout.first_page_end - hint_length;
+
+        let position = |id: &ObjectId| -> Option<(u64, u64)> {
+            if let Some(i) = self.first_section.iter().position(|x| x == id) {
+                return Some((first_offsets[i], bodies.first[i].len() as u64));
+            }
+            let i = self.main_section.iter().position(|x| x == id)?;
+            Some((main_offsets[i], bodies.main[i].len() as u64))
+        };
+
+        let mut pages = Vec::with_capacity(self.page_count);
+        for page in 0..self.page_count {
+            let (start, end) = if page == 0 {
+                (first_offsets[self.first_page_index], first_page_end)
+            } else {
+                let end = main_offsets
+                    .get(self.page_starts[page])
+                    .copied()
+                    .unwrap_or(layout.main_xref - hint_length);
+                (main_offsets[self.page_starts[page - 1]], end)
+            };
+            let (content_offset, content_length) = self.page_contents[page]
+                .iter()
+                .filter_map(&position)
+                .next()
+                .map(|(offset, length)| (offset - start, length))
+                .unwrap_or((0, 0));
+            pages.push(PageHint {
+                objects: self.page_object_counts[page] as u64,
+                length: end - start,
+                content_offset,
+                content_length,
+            });
+        }
+
+        let mut bits = BitWriter::default();
+        let least = |f: fn(&PageHint) -> u64| pages.iter().map(f).min().unwrap_or(0);
+        let width = |f: fn(&PageHint) -> u64, min: u64| {
+            bits_needed(pages.iter().map(f).max().unwrap_or(0) - min)
+        };
+        let least_objects = least(|p| p.objects);
+        let least_length = least(|p| p.length);
+        let least_content_offset = least(|p| p.content_offset);
+        let least_content_length = least(|p| p.content_length);
+        let objects_bits = width(|p| p.objects, least_objects);
+        let length_bits = width(|p| p.length, least_length);
+        let content_offset_bits = width(|p| p.content_offset, least_content_offset);
+        let content_length_bits = width(|p| p.content_length, least_content_length);
+
+        // Page offset hint table header (Table F.3).
+        bits.write(least_objects, 32);
+        bits.write(first_offsets[self.first_page_index], 32);
+        bits.write(objects_bits.into(), 16);
+        bits.write(least_length, 32);
+        bits.write(length_bits.into(), 16);
+        bits.write(least_content_offset, 32);
+        bits.write(content_offset_bits.into(), 16);
+        bits.write(least_content_length, 32);
+        bits.write(content_length_bits.into(), 16);
+        // No page references shared objects and no fractional positions are recorded.
+        bits.write(0, 16);
+        bits.write(0, 16);
+        bits.write(0, 16);
+        bits.write(1, 16);
+        for page in &pages {
+            bits.write(page.objects - least_objects, objects_bits);
+        }
+        for page in &pages {
+            bits.write(page.length - least_length, length_bits);
+        }
+        for page in &pages {
+            bits.write(
+                page.content_offset - least_content_offset,
+                content_offset_bits,
+            );
+        }
+        for page in &pages {
+            bits.write(
+                page.content_length - least_content_length,
+                content_length_bits,
+            );
+        }
+        bits.align();
+        let shared_offset = bits.bytes.len();
+
+        // Shared object hint table (Table F.5). Every object of the first page gets an
+        // entry; there is no separate shared object section.
+        let first_page_lengths: Vec<u64> = bodies.first[self.first_page_index..]
+            .iter()
+            .map(|b| b.len() as u64)
+            .collect();
+        let least_group = first_page_lengths.iter().copied().min().unwrap_or(0);
+        let group_bits =
+            bits_needed(first_page_lengths.iter().copied().max().unwrap_or(0) - least_group);
+        bits.write(0, 32);
+        bits.write(0, 32);
+        bits.write(first_page_lengths.len() as u64, 32);
+        bits.write(first_page_lengths.len() as u64, 32);
+        bits.write(0, 16);
+        bits.write(least_group, 32);
+        bits.write(group_bits.into(), 16);
+        for length in &first_page_lengths {
+            bits.write(length - least_group, group_bits);
+        }
+        for _ in &first_page_lengths {
+            bits.write(0, 1);
+        }
+        bits.align();
+
+        (bits.bytes, shared_offset)
+    }
+}
+
+struct Bodies {
+    first: Vec<Vec<u8>>,
+    main: Vec<Vec<u8>>,
+}
+
+struct PageHint {
+    objects: u64,
+    length: u64,
+    content_offset: u64,
+    content_length: u64,
+}
+
+/// Serializes the primary hint stream as an indirect object.
+fn hint_stream_object(number: u32, data: &[u8], shared_offset: usize) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    write!(
+        out,
+        "{} 0 obj\n<</Length {}/S {}>>\nstream\n",
+        number,
+        data.len(),
+        shared_offset
+    )?;
+    out.extend_from_slice(data);
+    out.extend_from_slice(b"\nendstream\nendobj\n");
+    Ok(out)
+}
+
+/// Depth-first walk from `id` that appends every newly reached object to `order`.
+/// Objects in `boundary` are not entered, and a page's `/Parent` is never followed.
+fn collect_references(
+    doc: &Document,
+    id: ObjectId,
+    boundary: &HashSet<ObjectId>,
+    assigned: &mut HashSet<ObjectId>,
+    order: &mut Vec<ObjectId>,
+) {
+    let mut stack = match doc.objects.get(&id) {
+        Some(object) => direct_references(object),
+        None => return,
+    };
+    stack.reverse();
+    while let Some(next) = stack.pop() {
+        if boundary.contains(&next) || !doc.objects.contains_key(&next) || !assigned.insert(next) {
+            continue;
+        }
+        order.push(next);
+        let mut children = direct_references(&doc.objects[&next]);
+        children.reverse();
+        stack.extend(children);
+    }
+}
+
+fn direct_references(object: &Object) -> Vec<ObjectId> {
+    fn visit(object: &Object, refs: &mut Vec<ObjectId>) {
+        match object {
+            Object::Reference(id) => refs.push(*id),
+            Object::Array(items) => items.iter().for_each(|o| visit(o, refs)),
+            Object::Dictionary(dict) => visit_dict(dict, refs),
+            Object::Stream(stream) => visit_dict(&stream.dict, refs),
+            _ => {}
+        }
+    }
+    fn visit_dict(dict: &Dictionary, refs: &mut Vec<ObjectId>) {
+        for (key, value) in dict.iter() {
+            if key.as_slice() != b"Parent" {
+                visit(value, refs);
+            }
+        }
+    }
+    let mut refs = Vec::new();
+    visit(object, &mut refs);
+    refs
+}
+
+fn content_stream_ids(doc: &Document, page_id: ObjectId) -> Vec<ObjectId> {
+    let Ok(page) = doc.get_dictionary(page_id) else {
+        return Vec::new();
+    };
+    match page.get(b"Contents") {
+        Ok(Object::Reference(id)) => vec![*id],
+        Ok(Object::Array(items)) => items.iter().filter_map(|o| o.as_reference().ok()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn bits_needed(value: u64) -> u8 {
+    (u64::BITS - value.leading_zeros()) as u8
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    used: u8,
+}
+
+impl BitWriter {
+    /// Appends the low `bits` bits of `value`, most significant bit first.
+    fn write(&mut self, value: u64, bits: u8) {
+        for shift in (0..bits).rev() {
+            if self.used == 0 {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> shift) & 1) as u8;
+            if let Some(last) = self.bytes.last_mut() {
+                *last |= bit << (7 - self.used);
+            }
+            self.used = (self.used + 1) % 8;
+        }
+    }
+
+    fn align(&mut self) {
+        self.used = 0;
+    }
+}
+
+fn write_indirect(out: &mut Vec<u8>, number: u32, object: &Object) -> io::Result<()> {
+    writeln!(out, "{} 0 obj", number)?;
+    write_object(out, object)?;
+    out.extend_from_slice(b"\nendobj\n");
+    Ok(())
+}
+
+fn write_object(out: &mut Vec<u8>, object: &Object) -> io::Result<()> {
+    match object {
+        Object::Null => out.extend_from_slice(b"null"),
+        Object::Boolean(b) => out.extend_from_slice(if *b { b"true" } else { b"false" }),
+        Object::Integer(i) => write!(out, "{}", i)?,
+        Object::Real(r) => write!(out, "{}", if r.is_finite() { *r } else { 0.0 })?,
+        Object::Name(name) => write_name(out, name),
+        Object::String(s, StringFormat::Literal) => {
+            out.push(b'(');
+            for &byte in s {
+                match byte {
+                    b'(' | b')' | b'\\' => out.extend_from_slice(&[b'\\', byte]),
+                    b'\r' => out.extend_from_slice(b"\\r"),
+                    _ => out.push(byte),
+                }
+            }
+            out.push(b')');
+        }
+        Object::String(s, StringFormat::Hexadecimal) => {
+            out.push(b'<');
+            for byte in s {
+                write!(out, "{:02X}", byte)?;
+            }
+            out.push(b'>');
+        }
+        Object::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b' ');
+                }
+                write_object(out, item)?;
+            }
+            out.push(b']');
+        }
+        Object::Dictionary(dict) => {
+            out.extend_from_slice(b"<<");
+            write_dictionary_entries(out, dict)?;
+            out.extend_from_slice(b">>");
+        }
+        Object::Stream(stream) => {
+            let mut dict = stream.dict.clone();
+            dict.set("Length", stream.content.len() as i64);
+            out.extend_from_slice(b"<<");
+            write_dictionary_entries(out, &dict)?;
+            out.extend_from_slice(b">>\nstream\n");
+            out.extend_from_slice(&stream.content);
+            out.extend_from_slice(b"\nendstream");
+        }
+        Object::Reference(id) => write!(out, "{} {} R", id.0, id.1)?,
+    }
+    Ok(())
+}
+
+fn write_dictionary_entries(out: &mut Vec<u8>, dict: &Dictionary) -> io::Result<()> {
+    for (key, value) in dict.iter() {
+        write_name(out, key);
+        out.push(b' ');
+        write_object(out, value)?;
+    }
+    Ok(())
+}
+
+fn write_name(out: &mut Vec<u8>, name: &[u8]) {
+    out.push(b'/');
+    for &byte in name {
+        let delimiter = b"()<>[]{}/%#".contains(&byte);
+        if delimiter || !(0x21..=0x7e).contains(&byte) {
+            out.extend_from_slice(format!("#{:02X}", byte).as_bytes());
+        } else {
+            out.push(byte);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_dummy_pdf;
+
+    /// Returns the integer following `key` in the parameter dictionary.
+    fn parameter(dict: &str, key: &str) -> u64 {
+        let start = dict.find(key).unwrap() + key.len();
+        dict[start..]
+            .trim_start()
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_linearization_dictionary_is_first_object() {
+        let doc = create_dummy_pdf(3, "Page");
+        let bytes = linearize(&doc).unwrap();
+
+        let text = String::from_utf8_lossy(&bytes);
+        let first_obj = text.find(" obj").unwrap();
+        let first_endobj = text.find("endobj").unwrap();
+        assert!(first_obj < first_endobj);
+        let first_object = &text[first_obj..first_endobj];
+        assert!(first_object.contains("/Linearized 1"));
+
+        assert_eq!(parameter(first_object, "/L "), bytes.len() as u64);
+        assert_eq!(parameter(first_object, "/N "), 3);
+        let main_xref = parameter(first_object, "/T ") as usize;
+        assert!(bytes[main_xref..].starts_with(b"\n0000000000 65535 f "));
+    }
+
+    #[test]
+    fn test_linearized_document_remains_valid() {
+        let doc = create_dummy_pdf(3, "Page");
+        let bytes = linearize(&doc).unwrap();
+
+        let loaded = Document::load_mem(&bytes).unwrap();
+        let pages = loaded.get_pages();
+        assert_eq!(pages.len(), 3);
+        for (number, page_id) in &pages {
+            let content = loaded.get_page_content(*page_id).unwrap();
+            let expected = format!("Page {}", number);
+            assert!(
+                content
+                    .windows(expected.len())
+                    .any(|w| w == expected.as_bytes()),
+                "page {} lost its content",
+                number
+            );
+        }
+
+        // The first page is the one named by /O and lives in the first-page section.
+        let text = String::from_utf8_lossy(&bytes);
+        let first_object = &text[..text.find("endobj").unwrap()];
+        assert_eq!(parameter(first_object, "/O "), u64::from(pages[&1].0));
+        let first_page_end = parameter(first_object, "/E ") as usize;
+        let first_section = String::from_utf8_lossy(&bytes[..first_page_end]);
+        assert!(first_section.contains(&format!("\n{} 0 obj", pages[&1].0)));
+        assert!(!first_section.contains(&format!("\n{} 0 obj", pages[&2].0)));
+    }
+
+    #[test]
+    fn test_linearize_rejects_documents_without_pages() {
+        let doc = Document::with_version("1.7");
+        assert!(linearize(&doc).is_err());
+    }
+}
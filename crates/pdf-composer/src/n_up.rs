@@ -0,0 +1,236 @@
+//! N-up composition: several logical pages scaled onto each output sheet.
+//!
+//! Every source page becomes a form XObject, which its sheet draws in a grid cell
+//! with a scaling transformation.
+
+use crate::rotation::media_box;
+use crate::{ComposerError, ObjectCopier};
+use lopdf::content::{Content, Operation};
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream, dictionary};
+
+/// The grid of an n-up layout, filled row by row from the top left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NUpLayout {
+    pub columns: u32,
+    pub rows: u32,
+    /// The width and height of an output sheet in points. When `None`, the sheet has
+    /// the size of the first source page, turned to landscape for grids with more
+    /// columns than rows and to portrait for grids with more rows than columns.
+    pub sheet_size: Option<(f32, f32)>,
+}
+
+impl NUpLayout {
+    pub fn new(columns: u32, rows: u32) -> Self {
+        Self {
+            columns,
+            rows,
+            sheet_size: None,
+        }
+    }
+
+    /// Two pages side by side.
+    pub fn two_up() -> Self {
+        Self::new(2, 1)
+    }
+
+    /// Four pages in a two by two grid.
+    pub fn four_up() -> Self {
+        Self::new(2, 2)
+    }
+
+    pub fn with_sheet_size(mut self, width: f32, height: f32) -> Self {
+        self.sheet_size = Some((width, height));
+        self
+    }
+}
+
+/// Creates a new document placing the pages of `source` onto sheets in the grid
+/// of `layout`. Each page is scaled uniformly to fit its cell and centered in it.
+///
+/// Only page content and resources are carried over; annotations, outlines and
+/// the `/Rotate` of the source pages are not.
+pub fn n_up(source: &Document, layout: NUpLayout) -> Result<Document, ComposerError> {
+    if layout.columns == 0 || layout.rows == 0 {
+        return Err(ComposerError::Other(
+            "An n-up layout needs at least one column and one row".to_string(),
+        ));
+    }
+    let source_pages: Vec<ObjectId> = source.get_pages().into_values().collect();
+    let Some(&first_page) = source_pages.first() else {
+        return Err(ComposerError::Other(
+            "Cannot n-up a document without pages".to_string(),
+        ));
+    };
+    let (sheet_width, sheet_height) = match layout.sheet_size {
+        Some(size) => size,
+        None => {
+            let [x0, y0, x1, y1] = media_box(source, first_page)?;
+            let (short, long) = ((x1 - x0).min(y1 - y0), (x1 - x0).max(y1 - y0));
+            match layout.columns.cmp(&layout.rows) {
+                std::cmp::Ordering::Greater => (long, short),
+                std::cmp::Ordering::Less => (short, long),
+                std::cmp::Ordering::Equal => (x1 - x0, y1 - y0),
+            }
+        }
+    };
+    let cell_width = sheet_width / layout.columns as f32;
+    let cell_height = sheet_height / layout.rows as f32;
+
+    let mut doc = Document::with_version(source.version.clone());
+    let pages_id = doc.new_object_id();
+    let mut sheet_ids = Vec::new();
+    let mut copier = ObjectCopier::new(source, &mut doc);
+
+    let per_sheet = (layout.columns * layout.rows) as usize;
+    for sheet_pages in source_pages.chunks(per_sheet) {
+        let mut operations = Vec::new();
+        let mut xobjects = Dictionary::new();
+
+        for (slot, &page_id) in sheet_pages.iter().enumerate() {
+            let [x0, y0, x1, y1] = media_box(source, page_id)?;
+            let (width, height) = (x1 - x0, y1 - y0);
+            let form_id = copy_page_as_form(&mut copier, page_id, [x0, y0, x1, y1])?;
+            let name = format!("P{}", slot + 1);
+            xobjects.set(name.as_str(), form_id);
+
+            let column = (slot as u32 % layout.columns) as f32;
+            let row = (slot as u32 / layout.columns) as f32;
+            let scale = (cell_width / width).min(cell_height / height);
+            let cell_x = column * cell_width + (cell_width - width * scale) / 2.0;
+            let cell_y =
+                sheet_height - (row + 1.0) * cell_height + (cell_height - height * scale) / 2.0;
+            operations.extend([
+                Operation::new("q", vec![]),
+                Operation::new(
+                    "cm",
+                    vec![
+                        scale.into(),
+                        0.into(),
+                        0.into(),
+                        scale.into(),
+                        (cell_x - x0 * scale).into(),
+                        (cell_y - y0 * scale).into(),
+                    ],
+                ),
+                Operation::new("Do", vec![Object::Name(name.into_bytes())]),
+                Operation::new("Q", vec![]),
+            ]);
+        }
+
+        let content = Content { operations }.encode()?;
+        let contents_id = copier
+            .target_doc
+            .add_object(Stream::new(dictionary! {}, content));
+        sheet_ids.push(copier.target_doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), sheet_width.into(), sheet_height.into()],
+            "Resources" => dictionary! { "XObject" => xobjects },
+            "Contents" => contents_id,
+        }));
+    }
+
+    let count = sheet_ids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => sheet_ids.into_iter().map(Object::Reference).collect::<Vec<_>>(),
+            "Count" => count,
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    Ok(doc)
+}
+
+/// Copies the content and resources of `page_id` into the target document as a
+/// form XObject spanning the page's media box.
+fn copy_page_as_form(
+    copier: &mut ObjectCopier,
+    page_id: ObjectId,
+    [x0, y0, x1, y1]: [f32; 4],
+) -> Result<ObjectId, ComposerError> {
+    let source = copier.source_doc;
+    let resources = match source.get_page_resources(page_id)? {
+        (Some(resources), _) => Object::Dictionary(resources.clone()),
+        (None, inherited) => match inherited.first() {
+            Some(id) => Object::Reference(*id),
+            None => Object::Dictionary(Dictionary::new()),
+        },
+    };
+    let resources = copier.remap_references(resources)?;
+    let content = source.get_page_content(page_id)?;
+
+    let form = Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Form",
+            "BBox" => vec![x0.into(), y0.into(), x1.into(), y1.into()],
+            "Resources" => resources,
+        },
+        content,
+    );
+    Ok(copier.target_doc.add_object(form))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_dummy_pdf;
+
+    #[test]
+    fn test_two_up_halves_the_page_count() {
+        let source = create_dummy_pdf(4, "Page");
+        let doc = n_up(&source, NUpLayout::two_up()).unwrap();
+
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 2);
+        for page_id in pages.values() {
+            let page = doc.get_dictionary(*page_id).unwrap();
+            let media_box = page.get(b"MediaBox").unwrap().as_array().unwrap();
+            // Letter pages side by side go on a landscape Letter sheet.
+            assert_eq!(media_box[2].as_float().unwrap(), 792.0);
+
+            let xobjects = page
+                .get(b"Resources")
+                .unwrap()
+                .as_dict()
+                .unwrap()
+                .get(b"XObject")
+                .unwrap()
+                .as_dict()
+                .unwrap();
+            assert_eq!(xobjects.len(), 2);
+            for (_, form) in xobjects.iter() {
+                let form = doc.get_object(form.as_reference().unwrap()).unwrap();
+                let form = form.as_stream().unwrap();
+                assert_eq!(
+                    form.dict.get(b"Subtype").unwrap().as_name().unwrap(),
+                    b"Form"
+                );
+                assert!(String::from_utf8_lossy(&form.content).contains("Page"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_n_up_leaves_unused_cells_of_the_last_sheet_empty() {
+        let source = create_dummy_pdf(5, "Page");
+        let doc = n_up(&source, NUpLayout::four_up()).unwrap();
+
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        assert_eq!(pages.len(), 2);
+        let last = Content::decode(&doc.get_page_content(pages[1]).unwrap()).unwrap();
+        assert_eq!(
+            last.operations
+                .iter()
+                .filter(|op| op.operator == "Do")
+                .count(),
+            1
+        );
+    }
+}
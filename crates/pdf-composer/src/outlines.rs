@@ -0,0 +1,120 @@
+//! Document outline (`/Outlines`, bookmarks) merging.
+
+use crate::{ComposerError, ObjectCopier};
+use lopdf::{Document, Object, ObjectId, dictionary};
+
+/// The top-level items of a source outline after copying them into the target,
+/// before they are linked into the target's outline.
+pub(crate) struct CopiedOutline {
+    first: ObjectId,
+    last: ObjectId,
+    /// The source outline's `/Count`: its number of visible items.
+    count: i64,
+    /// Every copied item, at all levels.
+    pub(crate) items: Vec<ObjectId>,
+}
+
+/// Deep-copies the outline items of the copier's source document into its target,
+/// remapping their destinations to the copied pages. The target's `/Outlines`
+/// dictionary is created if the source has items and the target has none.
+///
+/// Must run after the pages are copied, so that page references in destinations
+/// resolve to the copies rather than copying the pages again.
+pub(crate) fn copy_outline(
+    copier: &mut ObjectCopier,
+) -> Result<Option<CopiedOutline>, ComposerError> {
+    let source = copier.source_doc;
+    let source_root = source.trailer.get(b"Root")?.as_reference()?;
+    let Ok(Object::Reference(source_outlines_id)) =
+        source.get_dictionary(source_root)?.get(b"Outlines")
+    else {
+        return Ok(None);
+    };
+    let source_outlines = source.get_dictionary(*source_outlines_id)?;
+    let (Ok(first), Ok(last)) = (
+        source_outlines.get(b"First").and_then(Object::as_reference),
+        source_outlines.get(b"Last").and_then(Object::as_reference),
+    ) else {
+        return Ok(None);
+    };
+    let count = source_outlines
+        .get(b"Count")
+        .and_then(Object::as_i64)
+        .unwrap_or(0)
+        .max(0);
+
+    // Top-level items have the outline dictionary as their parent; mapping it to the
+    // target's outline dictionary parents the copies there instead of copying it.
+    let target_outlines_id = outline_root(copier.target_doc)?;
+    copier
+        .id_map
+        .insert(*source_outlines_id, target_outlines_id);
+
+    let first = copier.copy_object(first)?;
+    let last = copier.copy_object(last)?;
+    let items = crate::destinations::outline_items_from(copier.target_doc, first)?;
+    Ok(Some(CopiedOutline {
+        first,
+        last,
+        count,
+        items,
+    }))
+}
+
+/// Links the copied top-level items before or after those of the target's outline.
+pub(crate) fn splice_outline(
+    target: &mut Document,
+    copied: CopiedOutline,
+    prepend: bool,
+) -> Result<(), ComposerError> {
+    let outlines_id = outline_root(target)?;
+    let outlines = target.get_dictionary(outlines_id)?;
+    let existing_first = outlines.get(b"First").and_then(Object::as_reference).ok();
+    let existing_last = outlines.get(b"Last").and_then(Object::as_reference).ok();
+    let existing_count = outlines.get(b"Count").and_then(Object::as_i64).unwrap_or(0);
+
+    let (first, last) = match (prepend, existing_first, existing_last) {
+        (true, Some(existing_first), _) => {
+            link_siblings(target, copied.last, existing_first)?;
+            (copied.first, existing_last.unwrap_or(existing_first))
+        }
+        (false, _, Some(existing_last)) => {
+            link_siblings(target, existing_last, copied.first)?;
+            (existing_first.unwrap_or(existing_last), copied.last)
+        }
+        _ => (copied.first, copied.last),
+    };
+
+    let outlines = target.get_dictionary_mut(outlines_id)?;
+    outlines.set("First", first);
+    outlines.set("Last", last);
+    outlines.set("Count", existing_count.max(0) + copied.count);
+    Ok(())
+}
+
+/// Makes `next` the following sibling of `prev`.
+fn link_siblings(
+    target: &mut Document,
+    prev: ObjectId,
+    next: ObjectId,
+) -> Result<(), ComposerError> {
+    target.get_dictionary_mut(prev)?.set("Next", next);
+    target.get_dictionary_mut(next)?.set("Prev", prev);
+    Ok(())
+}
+
+/// Returns the ID of the catalog's `/Outlines` dictionary, creating it if missing.
+fn outline_root(doc: &mut Document) -> Result<ObjectId, ComposerError> {
+    let root_id = doc.trailer.get(b"Root")?.as_reference()?;
+    if let Ok(outlines_id) = doc
+        .get_dictionary(root_id)?
+        .get(b"Outlines")
+        .and_then(Object::as_reference)
+    {
+        return Ok(outlines_id);
+    }
+    let outlines_id = doc.add_object(dictionary! { "Type" => "Outlines", "Count" => 0 });
+    doc.get_dictionary_mut(root_id)?
+        .set("Outlines", outlines_id);
+    Ok(outlines_id)
+}
@@ -0,0 +1,77 @@
+//! Page label (`/PageLabels`) ranges.
+
+use crate::ComposerError;
+use lopdf::{Document, Object, dictionary};
+
+/// A page label range: the index of its first page and its label dictionary.
+pub(crate) type LabelRange = (i64, Object);
+
+/// Reads the ranges of a flat `/PageLabels` number tree, or `None` if the document
+/// has no page labels.
+pub(crate) fn page_label_ranges(doc: &Document) -> Result<Option<Vec<LabelRange>>, ComposerError> {
+    let root_id = doc.trailer.get(b"Root")?.as_reference()?;
+    let Ok(tree) = doc
+        .get_dictionary(root_id)?
+        .get_deref(b"PageLabels", doc)
+        .and_then(Object::as_dict)
+    else {
+        return Ok(None);
+    };
+    if tree.has(b"Kids") {
+        return Err(ComposerError::Other(
+            "Cannot merge page labels from a nested /PageLabels number tree".to_string(),
+        ));
+    }
+    let Ok(nums) = tree.get(b"Nums").and_then(Object::as_array) else {
+        return Ok(None);
+    };
+    let mut ranges = Vec::with_capacity(nums.len() / 2);
+    for pair in nums.chunks_exact(2) {
+        let label = match &pair[1] {
+            Object::Reference(id) => doc.get_object(*id)?.clone(),
+            label => label.clone(),
+        };
+        ranges.push((pair[0].as_i64()?, label));
+    }
+    Ok(Some(ranges))
+}
+
+/// Sets the page labels of `target` after `source_pages` pages with `source_labels`
+/// were merged into it. A document without labels contributes a decimal range, which
+/// matches how viewers number unlabelled pages.
+pub(crate) fn merge_page_labels(
+    target: &mut Document,
+    source_labels: Option<Vec<LabelRange>>,
+    target_pages: i64,
+    source_pages: i64,
+    prepend: bool,
+) -> Result<(), ComposerError> {
+    let target_labels = page_label_ranges(target)?;
+    if target_labels.is_none() && source_labels.is_none() {
+        return Ok(());
+    }
+    let decimal = || vec![(0, dictionary! { "S" => "D" }.into())];
+    let target_labels = target_labels.unwrap_or_else(decimal);
+    let source_labels = source_labels.unwrap_or_else(decimal);
+
+    let (first, second, offset) = if prepend {
+        (source_labels, target_labels, source_pages)
+    } else {
+        (target_labels, source_labels, target_pages)
+    };
+    let nums: Vec<Object> = first
+        .into_iter()
+        .chain(
+            second
+                .into_iter()
+                .map(|(start, label)| (start + offset, label)),
+        )
+        .flat_map(|(start, label)| [Object::Integer(start), label])
+        .collect();
+
+    let root_id = target.trailer.get(b"Root")?.as_reference()?;
+    target
+        .get_dictionary_mut(root_id)?
+        .set("PageLabels", dictionary! { "Nums" => nums });
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! Additions to page resource dictionaries.
+
+use crate::ComposerError;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+
+/// Registers `id` as `name` in the `category` (e.g. `Font`, `ExtGState`) of the
+/// resources of `page_id`. Inherited resources are copied onto the page first.
+///
+/// A resource dictionary shared by reference is updated in place, so callers should
+/// use names reserved for their purpose, which are harmless on the other pages.
+pub(crate) fn add_page_resource(
+    doc: &mut Document,
+    page_id: ObjectId,
+    category: &str,
+    name: &str,
+    id: ObjectId,
+) -> Result<(), ComposerError> {
+    let resources = doc.get_dictionary(page_id)?.get(b"Resources").ok().cloned();
+    let resources_id = match resources {
+        Some(Object::Reference(id)) => Some(id),
+        Some(_) => None,
+        None => {
+            let inherited = doc
+                .get_page_resources(page_id)?
+                .1
+                .first()
+                .map(|id| doc.get_dictionary(*id).cloned())
+                .transpose()?
+                .unwrap_or_default();
+            doc.get_dictionary_mut(page_id)?.set("Resources", inherited);
+            None
+        }
+    };
+    let resources = match resources_id {
+        Some(id) => doc.get_dictionary(id)?,
+        None => doc.get_dictionary(page_id)?.get(b"Resources")?.as_dict()?,
+    };
+
+    match resources.get(category.as_bytes()).ok().cloned() {
+        Some(Object::Reference(entries_id)) => {
+            doc.get_dictionary_mut(entries_id)?.set(name, id);
+        }
+        entries => {
+            let mut entries = match entries {
+                Some(Object::Dictionary(entries)) => entries,
+                _ => Dictionary::new(),
+            };
+            entries.set(name, id);
+            let resources = match resources_id {
+                Some(id) => doc.get_dictionary_mut(id)?,
+                None => doc
+                    .get_dictionary_mut(page_id)?
+                    .get_mut(b"Resources")?
+                    .as_dict_mut()?,
+            };
+            resources.set(category, entries);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Page rotation (`/Rotate`) and the page geometry derived from it.
+//!
+//! A rotated page is displayed turned clockwise by its rotation, so content drawn
+//! in default user space appears sideways. Overlays are positioned in the page's
+//! visible frame instead: [`rotation_matrix`] maps that frame back to user space.
+
+use crate::ComposerError;
+use lopdf::{Document, Object, ObjectId};
+use std::collections::HashSet;
+
+/// Sets the `/Rotate` entry of the given pages, numbered from 1 in page order.
+///
+/// `degrees` must be 0, 90, 180 or 270. The rotation is set on the page itself,
+/// overriding any value inherited from the page tree.
+pub fn set_page_rotation(
+    doc: &mut Document,
+    page_numbers: &[u32],
+    degrees: i64,
+) -> Result<(), ComposerError> {
+    if !matches!(degrees, 0 | 90 | 180 | 270) {
+        return Err(ComposerError::Other(format!(
+            "Page rotation must be 0, 90, 180 or 270 degrees, got {}",
+            degrees
+        )));
+    }
+    let pages = doc.get_pages();
+    for page_number in page_numbers {
+        let page_id = *pages
+            .get(page_number)
+            .ok_or_else(|| ComposerError::Other(format!("Document has no page {}", page_number)))?;
+        doc.get_dictionary_mut(page_id)?.set("Rotate", degrees);
+    }
+    Ok(())
+}
+
+/// The effective rotation of `page_id` in degrees, normalized to 0, 90, 180 or 270.
+pub fn page_rotation(doc: &Document, page_id: ObjectId) -> Result<i64, ComposerError> {
+    let rotate = inherited(doc, page_id, b"Rotate")?
+        .map(Object::as_i64)
+        .transpose()?
+        .unwrap_or(0);
+    Ok(rotate.rem_euclid(360) / 90 * 90)
+}
+
+/// The `/MediaBox` of `page_id` as `[x0, y0, x1, y1]`, which may be inherited from
+/// the page tree.
+pub(crate) fn media_box(doc: &Document, page_id: ObjectId) -> Result<[f32; 4], ComposerError> {
+    let malformed =
+        || ComposerError::Other(format!("Page {:?} has a malformed /MediaBox", page_id));
+    let values = inherited(doc, page_id, b"MediaBox")?
+        .ok_or_else(malformed)?
+        .as_array()?
+        .iter()
+        .map(Object::as_float)
+        .collect::<Result<Vec<_>, _>>()?;
+    values.try_into().map_err(|_| malformed())
+}
+
+/// The box of `page_id` as it is displayed: the media box with its width and height
+/// swapped for quarter-turn rotations, anchored at the media box origin.
+pub(crate) fn visible_box(doc: &Document, page_id: ObjectId) -> Result<[f32; 4], ComposerError> {
+    let [x0, y0, x1, y1] = media_box(doc, page_id)?;
+    Ok(match page_rotation(doc, page_id)? {
+        90 | 270 => [x0, y0, x0 + (y1 - y0), y0 + (x1 - x0)],
+        _ => [x0, y0, x1, y1],
+    })
+}
+
+/// The `cm` operands mapping the visible frame of `page_id` (see [`visible_box`]) to
+/// its default user space, or `None` if the page is not rotated.
+pub(crate) fn rotation_matrix(
+    doc: &Document,
+    page_id: ObjectId,
+) -> Result<Option<[f32; 6]>, ComposerError> {
+    let [x0, y0, x1, y1] = media_box(doc, page_id)?;
+    let (width, height) = (x1 - x0, y1 - y0);
+    Ok(match page_rotation(doc, page_id)? {
+        90 => Some([0.0, 1.0, -1.0, 0.0, x0 + y0 + width, y0 - x0]),
+        180 => Some([-1.0, 0.0, 0.0, -1.0, 2.0 * x0 + width, 2.0 * y0 + height]),
+        270 => Some([0.0, -1.0, 1.0, 0.0, x0 - y0, x0 + y0 + height]),
+        _ => None,
+    })
+}
+
+/// Looks up an inheritable page attribute, walking up the page tree from `page_id`.
+///
+/// A malformed tree whose `/Parent` chain loops back on itself is an error.
+pub(crate) fn inherited<'a>(
+    doc: &'a Document,
+    page_id: ObjectId,
+    key: &[u8],
+) -> Result<Option<&'a Object>, ComposerError> {
+    let mut visited = HashSet::from([page_id]);
+    let mut node = doc.get_dictionary(page_id)?;
+    loop {
+        if let Ok(value) = node.get_deref(key, doc) {
+            return Ok(Some(value));
+        }
+        match node.get(b"Parent").and_then(Object::as_reference) {
+            Ok(parent_id) if !visited.insert(parent_id) => {
+                return Err(ComposerError::Other(format!(
+                    "The page tree above page {:?} has a /Parent cycle.",
+                    page_id
+                )));
+            }
+            Ok(parent_id) => node = doc.get_dictionary(parent_id)?,
+            Err(_) => return Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overlay_content;
+    use crate::tests::create_dummy_pdf;
+    use lopdf::content::{Content, Operation};
+
+    #[test]
+    fn test_overlay_on_rotated_page_lands_in_visible_area() {
+        let mut doc = create_dummy_pdf(2, "Page");
+        set_page_rotation(&mut doc, &[2], 90).unwrap();
+        let page_id = doc.get_pages()[&2];
+        let page = doc.get_dictionary(page_id).unwrap();
+        assert_eq!(page.get(b"Rotate").unwrap().as_i64().unwrap(), 90);
+        assert_eq!(page_rotation(&doc, doc.get_pages()[&1]).unwrap(), 0);
+
+        // The rotated Letter page is displayed 792pt wide and 612pt high, so this
+        // point is near its visible bottom-right corner but outside the media box.
+        let (u, v) = (700.0, 20.0);
+        let overlay = Content {
+            operations: vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Td", vec![u.into(), v.into()]),
+                Operation::new("ET", vec![]),
+            ],
+        };
+        overlay_content(&mut doc, page_id, overlay.encode().unwrap()).unwrap();
+
+        let content = Content::decode(&doc.get_page_content(page_id).unwrap()).unwrap();
+        let cm = content
+            .operations
+            .iter()
+            .find(|op| op.operator == "cm")
+            .expect("overlay on a rotated page should be transformed");
+        let m: Vec<f32> = cm.operands.iter().map(|o| o.as_float().unwrap()).collect();
+        let x = m[0] * u + m[2] * v + m[4];
+        let y = m[1] * u + m[3] * v + m[5];
+        assert!((0.0..=612.0).contains(&x) && (0.0..=792.0).contains(&y));
+        // The visible bottom edge is the media box's right edge.
+        assert_eq!((x, y), (592.0, 700.0));
+    }
+
+    #[test]
+    fn test_set_page_rotation_rejects_bad_angles_and_pages() {
+        let mut doc = create_dummy_pdf(1, "Page");
+        assert!(set_page_rotation(&mut doc, &[1], 45).is_err());
+        assert!(set_page_rotation(&mut doc, &[2], 90).is_err());
+    }
+}
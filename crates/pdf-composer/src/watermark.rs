@@ -0,0 +1,165 @@
+//! Text watermarks, such as "DRAFT" set diagonally across a page.
+
+use crate::page_resources::add_page_resource;
+use crate::rotation::visible_box;
+use crate::{ComposerError, overlay_content};
+use lopdf::content::{Content, Operation};
+use lopdf::{Document, Object, ObjectId, dictionary};
+
+/// The resource names the watermark's font and graphics state are registered under.
+const WATERMARK_FONT: &str = "FWatermark";
+const WATERMARK_GS: &str = "GSWatermark";
+
+/// How a text watermark is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WatermarkOptions {
+    pub font_size: f32,
+    /// Counter-clockwise rotation of the text in degrees.
+    pub rotation: f32,
+    /// From 0 (invisible) to 1 (opaque).
+    pub opacity: f32,
+    /// RGB components from 0 to 1.
+    pub color: [f32; 3],
+}
+
+impl Default for WatermarkOptions {
+    fn default() -> Self {
+        Self {
+            font_size: 72.0,
+            rotation: 45.0,
+            opacity: 0.3,
+            color: [0.5, 0.5, 0.5],
+        }
+    }
+}
+
+/// Draws `text` in Helvetica Bold across the center of `page_id`, on top of its
+/// content, using [`overlay_content`].
+///
+/// The text is centered using approximate glyph widths, which is close enough for
+/// the short words watermarks use. Characters outside Latin-1 are not drawn.
+pub fn apply_text_watermark(
+    doc: &mut Document,
+    page_id: ObjectId,
+    text: &str,
+    options: WatermarkOptions,
+) -> Result<(), ComposerError> {
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica-Bold",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let opacity = options.opacity.clamp(0.0, 1.0);
+    let gs_id = doc.add_object(dictionary! {
+        "Type" => "ExtGState",
+        "ca" => opacity,
+        "CA" => opacity,
+    });
+    add_page_resource(doc, page_id, "Font", WATERMARK_FONT, font_id)?;
+    add_page_resource(doc, page_id, "ExtGState", WATERMARK_GS, gs_id)?;
+
+    let [x0, y0, x1, y1] = visible_box(doc, page_id)?;
+    let (center_x, center_y) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+    let (sin, cos) = options.rotation.to_radians().sin_cos();
+    let width = approximate_width(text) * options.font_size;
+    let [r, g, b] = options.color;
+    let encoded: Vec<u8> = text
+        .chars()
+        .filter_map(|c| u8::try_from(u32::from(c)).ok())
+        .collect();
+
+    let content = Content {
+        operations: vec![
+            Operation::new("q", vec![]),
+            Operation::new("gs", vec![Object::Name(WATERMARK_GS.into())]),
+            Operation::new("rg", vec![r.into(), g.into(), b.into()]),
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec![WATERMARK_FONT.into(), options.font_size.into()]),
+            Operation::new(
+                "Tm",
+                vec![
+                    cos.into(),
+                    sin.into(),
+                    (-sin).into(),
+                    cos.into(),
+                    center_x.into(),
+                    center_y.into(),
+                ],
+            ),
+            // Centers the text on its baseline and the baseline on its cap height.
+            Operation::new(
+                "Td",
+                vec![(-width / 2.0).into(), (-options.font_size * 0.36).into()],
+            ),
+            Operation::new("Tj", vec![Object::string_literal(encoded)]),
+            Operation::new("ET", vec![]),
+            Operation::new("Q", vec![]),
+        ],
+    };
+    overlay_content(doc, page_id, content.encode()?)
+}
+
+/// The advance width of `text` in Helvetica Bold, as a multiple of the font size,
+/// from the typical widths of its character classes.
+fn approximate_width(text: &str) -> f32 {
+    text.chars()
+        .map(|c| match c {
+            ' ' => 0.278,
+            'I' | 'i' | 'j' | 'l' | '.' | ',' | '\'' => 0.278,
+            'M' | 'W' | 'm' | 'w' => 0.889,
+            c if c.is_uppercase() => 0.722,
+            _ => 0.556,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::create_dummy_pdf;
+
+    #[test]
+    fn test_rotated_watermark_is_drawn_over_the_page() {
+        let mut doc = create_dummy_pdf(1, "Page");
+        let page_id = doc.get_pages()[&1];
+        apply_text_watermark(&mut doc, page_id, "DRAFT", WatermarkOptions::default()).unwrap();
+
+        let content = Content::decode(&doc.get_page_content(page_id).unwrap()).unwrap();
+        let tj = content.operations.last_chunk::<3>().unwrap();
+        assert_eq!(tj[0].operator, "Tj");
+        assert_eq!(tj[0].operands[0].as_str().unwrap(), b"DRAFT");
+
+        let tm = content
+            .operations
+            .iter()
+            .find(|op| op.operator == "Tm")
+            .unwrap();
+        let m: Vec<f32> = tm.operands.iter().map(|o| o.as_float().unwrap()).collect();
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+        for (value, expected) in m.iter().zip([diagonal, diagonal, -diagonal, diagonal]) {
+            assert!((value - expected).abs() < 1e-4, "{:?}", m);
+        }
+        // Centered on the Letter page.
+        assert_eq!((m[4], m[5]), (306.0, 396.0));
+
+        let (resources, _) = doc.get_page_resources(page_id).unwrap();
+        let resources = match resources {
+            Some(resources) => resources,
+            None => doc
+                .get_dictionary(doc.get_page_resources(page_id).unwrap().1[0])
+                .unwrap(),
+        };
+        let gs_id = resources
+            .get(b"ExtGState")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(WATERMARK_GS.as_bytes())
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let gs = doc.get_dictionary(gs_id).unwrap();
+        assert_eq!(gs.get(b"ca").unwrap().as_float().unwrap(), 0.3);
+    }
+}
@@ -1,4 +1,4 @@
-use petty_types::{ApiIndexEntry, TocEntry};
+use petty_types::{ApiIndexEntry, CaptionEntry, TocEntry};
 use std::collections::HashMap;
 
 // Re-export LaidOutSequence from petty-layout
@@ -21,6 +21,7 @@ pub struct HyperlinkLocation {
 pub struct Pass1Result {
     pub resolved_anchors: HashMap<String, ResolvedAnchor>,
     pub toc_entries: Vec<TocEntry>,
+    pub caption_entries: Vec<CaptionEntry>,
     pub total_pages: usize,
     pub hyperlink_locations: Vec<HyperlinkLocation>,
     pub index_entries: Vec<ApiIndexEntry>,
@@ -20,3 +20,76 @@ pub fn get_styled_font_name(base_name: &str, weight: FontWeight, italic: bool) -
 pub fn flip_y(y: f32, page_height: f32) -> f32 {
     page_height - y
 }
+
+/// How a value is brought to a [`CoordinatePrecision`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round to the nearest value, with halfway cases away from zero.
+    #[default]
+    HalfAwayFromZero,
+    /// Round to the nearest value, with halfway cases to the even neighbour.
+    HalfEven,
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceiling,
+    /// Drop the digits past the precision.
+    TowardZero,
+}
+
+/// The precision coordinates are emitted with, so that sub-pixel noise from
+/// layout arithmetic does not show up as differences in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoordinatePrecision {
+    /// Decimal places kept.
+    pub decimals: u8,
+    pub mode: RoundingMode,
+}
+
+impl CoordinatePrecision {
+    pub fn new(decimals: u8, mode: RoundingMode) -> Self {
+        Self { decimals, mode }
+    }
+
+    /// Rounds `value` to this precision.
+    pub fn round(&self, value: f32) -> f32 {
+        if !value.is_finite() {
+            return value;
+        }
+        // Scaling in f64 keeps the scaled value exact for any f32 at the
+        // precisions that are useful here.
+        let scale = 10f64.powi(self.decimals.into());
+        let scaled = f64::from(value) * scale;
+        let rounded = match self.mode {
+            RoundingMode::HalfAwayFromZero => scaled.round(),
+            RoundingMode::HalfEven => scaled.round_ties_even(),
+            RoundingMode::Floor => scaled.floor(),
+            RoundingMode::Ceiling => scaled.ceil(),
+            RoundingMode::TowardZero => scaled.trunc(),
+        };
+        (rounded / scale) as f32
+    }
+
+    /// [`flip_y`] with the result rounded to this precision.
+    pub fn flip_y(&self, y: f32, page_height: f32) -> f32 {
+        self.round(flip_y(y, page_height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coordinate_precision_rounds_with_the_chosen_mode() {
+        let two = |mode| CoordinatePrecision::new(2, mode);
+        assert_eq!(two(RoundingMode::HalfAwayFromZero).round(10.125), 10.13);
+        assert_eq!(two(RoundingMode::HalfEven).round(10.125), 10.12);
+        assert_eq!(two(RoundingMode::Floor).round(-1.001), -1.01);
+        assert_eq!(two(RoundingMode::Ceiling).round(1.001), 1.01);
+        assert_eq!(two(RoundingMode::TowardZero).round(-1.009), -1.0);
+
+        let whole = CoordinatePrecision::new(0, RoundingMode::HalfAwayFromZero);
+        assert_eq!(whole.flip_y(100.4999, 842.0), 742.0);
+    }
+}
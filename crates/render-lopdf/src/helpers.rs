@@ -5,9 +5,12 @@
 
 use crate::writer::StreamingPdfWriter;
 use lopdf::content::{Content, Operation};
-use lopdf::{Object, ObjectId, StringFormat, dictionary};
-use petty_layout::{ComputedStyle, LayoutElement, PositionedElement};
+use lopdf::{Dictionary, Object, ObjectId, Stream, StringFormat, dictionary, text_string};
+use petty_idf::FormFieldKind;
+use petty_layout::{ComputedStyle, LayoutElement, LayoutEngine, PositionedElement};
+use petty_render_core::utils::CoordinatePrecision;
 use petty_render_core::{LaidOutSequence, Pass1Result, RenderError};
+use petty_style::{PageLayout, PageNumberStyle, PageNumbering, Stylesheet};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::{Seek, Write};
@@ -49,29 +52,17 @@ pub fn create_link_annotations<W: Write + Seek>(
                     && anchor.global_page_index > 0
                     && anchor.global_page_index <= final_page_ids.len()
                 {
-                    let page_annots = link_annots_by_page.entry(global_page_idx).or_default();
-                    let target_page_id = final_page_ids[anchor.global_page_index - 1];
-                    let y_dest = page_height - anchor.y_pos;
-
-                    let dest = vec![
-                        Object::Reference(target_page_id),
-                        "FitH".into(),
-                        y_dest.into(),
-                    ];
-                    let action = dictionary! { "Type" => "Action", "S" => "GoTo", "D" => dest };
-                    let action_id = writer.buffer_object(action.into());
-                    let rect = vec![
-                        el.x.into(),
-                        (page_height - (el.y + el.height)).into(),
-                        (el.x + el.width).into(),
-                        (page_height - el.y).into(),
-                    ];
-                    let annot = dictionary! {
-                        "Type" => "Annot", "Subtype" => "Link", "Rect" => rect,
-                        "Border" => vec![0.into(), 0.into(), 0.into()], "A" => action_id,
-                    };
-                    let annot_id = writer.buffer_object(annot.into());
-                    page_annots.push(annot_id);
+                    let annot_id = create_goto_link_annotation(
+                        writer,
+                        [el.x, el.y, el.x + el.width, el.y + el.height],
+                        final_page_ids[anchor.global_page_index - 1],
+                        anchor.y_pos,
+                        page_height,
+                    );
+                    link_annots_by_page
+                        .entry(global_page_idx)
+                        .or_default()
+                        .push(annot_id);
                 }
             }
         }
@@ -80,6 +71,299 @@ pub fn create_link_annotations<W: Write + Seek>(
     Ok(link_annots_by_page)
 }
 
+/// The clickable area of a hyperlink on a single line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkFragment<'a> {
+    pub href: &'a str,
+    /// `[x0, y0, x1, y1]` in layout coordinates (origin at the top left).
+    pub rect: [f32; 4],
+}
+
+/// Collects one rectangle per line for every hyperlink on a page.
+///
+/// Consecutive text runs of the same link on the same line are merged, so a link that
+/// wraps onto two lines yields two rectangles and the gap between them stays unlinked.
+pub fn link_fragments(page_elements: &[PositionedElement]) -> Vec<LinkFragment<'_>> {
+    let mut fragments: Vec<LinkFragment<'_>> = Vec::new();
+    for el in page_elements {
+        let LayoutElement::Text(text) = &el.element else {
+            continue;
+        };
+        let Some(href) = text.href.as_deref() else {
+            continue;
+        };
+        let rect = [el.x, el.y, el.x + el.width, el.y + el.height];
+        match fragments.last_mut() {
+            Some(last) if last.href == href && (last.rect[1] - rect[1]).abs() < 0.01 => {
+                last.rect[0] = last.rect[0].min(rect[0]);
+                last.rect[2] = last.rect[2].max(rect[2]);
+                last.rect[3] = last.rect[3].max(rect[3]);
+            }
+            _ => fragments.push(LinkFragment { href, rect }),
+        }
+    }
+    fragments
+}
+
+/// Creates a `Link` annotation with a `URI` action for every line fragment of the
+/// external (non-`#fragment`) hyperlinks on a page.
+///
+/// # Returns
+/// The `ObjectId`s of the annotations, to be added to the page's `/Annots`.
+pub fn create_uri_link_annotations<W: Write + Seek>(
+    writer: &mut StreamingPdfWriter<W>,
+    page_elements: &[PositionedElement],
+    page_height: f32,
+) -> Vec<ObjectId> {
+    link_fragments(page_elements)
+        .into_iter()
+        .filter(|fragment| !fragment.href.starts_with('#'))
+        .map(|fragment| {
+            let action = dictionary! {
+                "Type" => "Action", "S" => "URI",
+                "URI" => Object::string_literal(fragment.href),
+            };
+            link_annotation(writer, fragment.rect, action.into(), page_height)
+        })
+        .collect()
+}
+
+/// Buffers a `Link` annotation with a `GoTo` action to `target_y` on `target_page_id`.
+///
+/// `rect` is `[x0, y0, x1, y1]` in layout coordinates (origin at the top left).
+pub fn create_goto_link_annotation<W: Write + Seek>(
+    writer: &mut StreamingPdfWriter<W>,
+    rect: [f32; 4],
+    target_page_id: ObjectId,
+    target_y: f32,
+    page_height: f32,
+) -> ObjectId {
+    let dest = vec![
+        Object::Reference(target_page_id),
+        "FitH".into(),
+        (page_height - target_y).into(),
+    ];
+    let action = dictionary! { "Type" => "Action", "S" => "GoTo", "D" => dest };
+    link_annotation(writer, rect, action.into(), page_height)
+}
+
+fn link_annotation<W: Write + Seek>(
+    writer: &mut StreamingPdfWriter<W>,
+    rect: [f32; 4],
+    action: Object,
+    page_height: f32,
+) -> ObjectId {
+    let action_id = writer.buffer_object(action);
+    let pdf_rect = vec![
+        rect[0].into(),
+        (page_height - rect[3]).into(),
+        rect[2].into(),
+        (page_height - rect[1]).into(),
+    ];
+    let annot = dictionary! {
+        "Type" => "Annot", "Subtype" => "Link", "Rect" => pdf_rect,
+        "Border" => vec![0.into(), 0.into(), 0.into()], "A" => action_id,
+    };
+    writer.buffer_object(annot.into())
+}
+
+/// Creates a widget annotation for every form field on a page and registers each one
+/// as a terminal field of the document's `/AcroForm`.
+///
+/// # Returns
+/// The `ObjectId`s of the widget annotations, to be added to the page's `/Annots`.
+pub fn create_form_widgets<W: Write + Seek>(
+    writer: &mut StreamingPdfWriter<W>,
+    page_elements: &[PositionedElement],
+    page_height: f32,
+) -> Result<Vec<ObjectId>, RenderError> {
+    let mut widget_ids = Vec::new();
+    for el in page_elements {
+        let LayoutElement::FormField(field) = &el.element else {
+            continue;
+        };
+        let resources_id = writer.form_resources_id();
+        let rect = vec![
+            el.x.into(),
+            (page_height - (el.y + el.height)).into(),
+            (el.x + el.width).into(),
+            (page_height - el.y).into(),
+        ];
+        let mut widget = dictionary! {
+            "Type" => "Annot", "Subtype" => "Widget", "Rect" => rect, "F" => 4,
+            "T" => text_string(&field.name),
+        };
+
+        match &field.kind {
+            FormFieldKind::Text { value } => {
+                let font_size = (el.height - 4.0).clamp(4.0, 12.0);
+                let appearance = text_field_appearance(value, el, font_size, resources_id)?;
+                widget.set("FT", "Tx");
+                widget.set("V", text_string(value));
+                widget.set("DV", text_string(value));
+                widget.set(
+                    "DA",
+                    Object::string_literal(format!("/Helv {} Tf 0 g", font_size)),
+                );
+                widget.set(
+                    "AP",
+                    dictionary! { "N" => writer.buffer_object(appearance) },
+                );
+            }
+            FormFieldKind::Checkbox { checked } => {
+                let state = if *checked { "Yes" } else { "Off" };
+                let on = checkbox_appearance(el, true, resources_id)?;
+                let off = checkbox_appearance(el, false, resources_id)?;
+                widget.set("FT", "Btn");
+                widget.set("V", state);
+                widget.set("DV", state);
+                widget.set("AS", state);
+                widget.set("DA", Object::string_literal("/ZaDb 0 Tf 0 g"));
+                widget.set("MK", dictionary! { "CA" => Object::string_literal("4") });
+                widget.set(
+                    "AP",
+                    dictionary! {
+                        "N" => dictionary! {
+                            "Yes" => writer.buffer_object(on),
+                            "Off" => writer.buffer_object(off),
+                        },
+                    },
+                );
+            }
+        }
+
+        let widget_id = writer.buffer_object(widget.into());
+        writer.add_form_field(widget_id);
+        widget_ids.push(widget_id);
+    }
+    Ok(widget_ids)
+}
+
+/// Builds the normal appearance of a text field showing its default value.
+fn text_field_appearance(
+    value: &str,
+    el: &PositionedElement,
+    font_size: f32,
+    resources_id: ObjectId,
+) -> Result<Object, RenderError> {
+    let baseline = ((el.height - font_size) / 2.0 + font_size * 0.22).max(0.0);
+    let content = Content {
+        operations: vec![
+            Operation::new("BMC", vec!["Tx".into()]),
+            Operation::new("q", vec![]),
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["Helv".into(), font_size.into()]),
+            Operation::new("g", vec![0.into()]),
+            Operation::new("Td", vec![2.into(), baseline.into()]),
+            Operation::new("Tj", vec![Object::string_literal(value)]),
+            Operation::new("ET", vec![]),
+            Operation::new("Q", vec![]),
+            Operation::new("EMC", vec![]),
+        ],
+    };
+    appearance_stream(el, content, resources_id)
+}
+
+/// Builds the on (check mark) or off (empty) appearance of a checkbox.
+fn checkbox_appearance(
+    el: &PositionedElement,
+    checked: bool,
+    resources_id: ObjectId,
+) -> Result<Object, RenderError> {
+    let mut operations = Vec::new();
+    if checked {
+        let font_size = el.width.min(el.height) * 0.8;
+        operations.extend([
+            Operation::new("q", vec![]),
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["ZaDb".into(), font_size.into()]),
+            Operation::new("g", vec![0.into()]),
+            Operation::new(
+                "Td",
+                vec![
+                    ((el.width - font_size * 0.846) / 2.0).into(),
+                    ((el.height - font_size * 0.7) / 2.0).into(),
+                ],
+            ),
+            Operation::new("Tj", vec![Object::string_literal("4")]),
+            Operation::new("ET", vec![]),
+            Operation::new("Q", vec![]),
+        ]);
+    }
+    appearance_stream(el, Content { operations }, resources_id)
+}
+
+fn appearance_stream(
+    el: &PositionedElement,
+    content: Content,
+    resources_id: ObjectId,
+) -> Result<Object, RenderError> {
+    let data = content
+        .encode()
+        .map_err(|e| RenderError::Other(format!("Failed to encode appearance stream: {e}")))?;
+    let dict = dictionary! {
+        "Type" => "XObject", "Subtype" => "Form",
+        "BBox" => vec![0.into(), 0.into(), el.width.into(), el.height.into()],
+        "Resources" => resources_id,
+    };
+    Ok(Stream::new(dict, data).into())
+}
+
+/// Builds the `/PageLabels` number tree from the page master of every page.
+///
+/// Each run of consecutive pages using the same master starts a new label range,
+/// styled by that master's `page_numbering`; masters without one are numbered in
+/// decimal. Returns `None` when no page master defines a numbering style, leaving the
+/// viewer's default numbering in place.
+pub fn build_page_labels(page_masters: &[String], stylesheet: &Stylesheet) -> Option<Dictionary> {
+    if stylesheet
+        .page_masters
+        .values()
+        .all(|master| master.page_numbering.is_none())
+    {
+        return None;
+    }
+
+    let mut nums = Vec::new();
+    let mut previous: Option<&str> = None;
+    for (page_index, master_name) in page_masters.iter().enumerate() {
+        if previous == Some(master_name.as_str()) {
+            continue;
+        }
+        previous = Some(master_name);
+        let numbering = stylesheet
+            .page_masters
+            .get(master_name)
+            .and_then(|master| master.page_numbering.clone())
+            .unwrap_or_default();
+        nums.push(Object::Integer(page_index as i64));
+        nums.push(page_label(&numbering).into());
+    }
+    Some(dictionary! { "Nums" => nums })
+}
+
+fn page_label(numbering: &PageNumbering) -> Dictionary {
+    let mut label = Dictionary::new();
+    let style = match numbering.style {
+        PageNumberStyle::Decimal => Some("D"),
+        PageNumberStyle::LowerRoman => Some("r"),
+        PageNumberStyle::UpperRoman => Some("R"),
+        PageNumberStyle::LowerAlpha => Some("a"),
+        PageNumberStyle::UpperAlpha => Some("A"),
+        PageNumberStyle::None => None,
+    };
+    if let Some(style) = style {
+        label.set("S", Object::Name(style.as_bytes().to_vec()));
+    }
+    if let Some(prefix) = &numbering.prefix {
+        label.set("P", text_string(prefix));
+    }
+    if let Some(start) = numbering.start.filter(|start| *start != 1) {
+        label.set("St", start as i64);
+    }
+    label
+}
+
 /// Creates the PDF document outline (bookmarks) from the Table of Contents entries.
 ///
 /// # Returns
@@ -225,6 +509,183 @@ pub fn render_elements_to_content(
     Ok(page_ctx.finish())
 }
 
+/// The `src` of the image `op` draws, if it is an image draw of a page context.
+pub(crate) fn drawn_image_src(op: &Operation) -> Option<&[u8]> {
+    match op.operands.first() {
+        Some(Object::Name(name)) if op.operator == "Do" => {
+            name.strip_prefix(IMAGE_PREFIX.as_bytes())
+        }
+        _ => None,
+    }
+}
+
+/// Removes the image draws from `content`, for content that is not written with
+/// [`StreamingPdfWriter::write_content_stream`] and so gets no image XObjects.
+/// Returns whether there were any.
+pub fn remove_image_draws(content: &mut Content) -> bool {
+    let count = content.operations.len();
+    content
+        .operations
+        .retain(|op| drawn_image_src(op).is_none());
+    content.operations.len() != count
+}
+
+/// Wraps `content` in a clip to the page rectangle grown by `bleed` on every side,
+/// so anything drawn past the MediaBox is cut off instead of bleeding into the
+/// viewer's background.
+pub fn clip_to_page(content: Content, page_width: f32, page_height: f32, bleed: f32) -> Content {
+    let mut operations = Vec::with_capacity(content.operations.len() + 5);
+    operations.push(Operation::new("q", vec![]));
+    operations.push(Operation::new(
+        "re",
+        vec![
+            (-bleed).into(),
+            (-bleed).into(),
+            (page_width + 2.0 * bleed).into(),
+            (page_height + 2.0 * bleed).into(),
+        ],
+    ));
+    operations.push(Operation::new("W", vec![]));
+    operations.push(Operation::new("n", vec![]));
+    operations.extend(content.operations);
+    operations.push(Operation::new("Q", vec![]));
+    Content { operations }
+}
+
+/// Rounds the coordinates in `content` to `precision`: the operands of path
+/// construction and text positioning operators, and the translation of `cm` and
+/// `Tm` matrices. Scale and rotation factors are left as they are, since rounding
+/// them would distort what they transform.
+pub fn round_coordinates(mut content: Content, precision: CoordinatePrecision) -> Content {
+    for operation in &mut content.operations {
+        let skip = match operation.operator.as_str() {
+            "m" | "l" | "c" | "v" | "y" | "re" | "Td" | "TD" => 0,
+            "cm" | "Tm" => 4,
+            _ => continue,
+        };
+        for operand in operation.operands.iter_mut().skip(skip) {
+            if let Object::Real(value) = operand {
+                *value = precision.round(*value);
+            }
+        }
+    }
+    content
+}
+
+/// The print geometry of a page master: its bleed and whether crop marks are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PrintMarks {
+    pub bleed: f32,
+    pub crop_marks: bool,
+}
+
+impl PrintMarks {
+    pub fn from_page_layout(layout: &PageLayout) -> Self {
+        Self {
+            bleed: layout.bleed.unwrap_or(0.0).max(0.0),
+            crop_marks: layout.crop_marks,
+        }
+    }
+
+    /// Sets the page's MediaBox, grown by the bleed on every side. With a bleed,
+    /// the TrimBox marks the finished page and the BleedBox the MediaBox.
+    pub fn set_page_boxes(&self, page_dict: &mut Dictionary, page_width: f32, page_height: f32) {
+        let b = self.bleed;
+        let bleed_box: Vec<Object> = vec![
+            (-b).into(),
+            (-b).into(),
+            (page_width + b).into(),
+            (page_height + b).into(),
+        ];
+        page_dict.set("MediaBox", bleed_box.clone());
+        if b > 0.0 {
+            page_dict.set("BleedBox", bleed_box);
+            page_dict.set(
+                "TrimBox",
+                vec![
+                    0.0.into(),
+                    0.0.into(),
+                    page_width.into(),
+                    page_height.into(),
+                ],
+            );
+        }
+    }
+
+    /// Appends crop marks to `content`: a short hairline along each trim edge at
+    /// every corner, drawn in the bleed area and stopping short of the trim.
+    pub fn draw_crop_marks(&self, content: &mut Content, page_width: f32, page_height: f32) {
+        if !self.crop_marks || self.bleed <= 0.0 {
+            return;
+        }
+        let (near, far) = (self.bleed / 3.0, self.bleed);
+        let ops = &mut content.operations;
+        ops.push(Operation::new("q", vec![]));
+        ops.push(Operation::new("w", vec![0.25.into()]));
+        ops.push(Operation::new("G", vec![0.into()]));
+        for (x, sx) in [(0.0, -1.0), (page_width, 1.0)] {
+            for (y, sy) in [(0.0, -1.0), (page_height, 1.0)] {
+                ops.push(Operation::new("m", vec![(x + sx * near).into(), y.into()]));
+                ops.push(Operation::new("l", vec![(x + sx * far).into(), y.into()]));
+                ops.push(Operation::new("m", vec![x.into(), (y + sy * near).into()]));
+                ops.push(Operation::new("l", vec![x.into(), (y + sy * far).into()]));
+            }
+        }
+        ops.push(Operation::new("S", vec![]));
+        ops.push(Operation::new("Q", vec![]));
+    }
+}
+
+/// System fonts for drawing text in page thumbnails, loaded on first use.
+#[cfg(feature = "thumbnails")]
+static THUMBNAIL_FONTS: Lazy<Arc<petty_render_svg::fontdb::Database>> = Lazy::new(|| {
+    let mut fontdb = petty_render_svg::fontdb::Database::new();
+    fontdb.load_system_fonts();
+    Arc::new(fontdb)
+});
+
+/// Rasterizes a page's elements and writes them as a thumbnail image (PDF 32000-1,
+/// 12.3.4), returning its object ID. Without the `thumbnails` feature there is no
+/// rasterizer, and no thumbnail is written.
+#[cfg_attr(not(feature = "thumbnails"), allow(unused_variables))]
+pub(crate) fn write_thumbnail<W: Write + Seek>(
+    writer: &mut StreamingPdfWriter<W>,
+    elements: &[PositionedElement],
+    resources: &HashMap<String, petty_idf::SharedData>,
+    font_map: &HashMap<String, String>,
+    page_width: f32,
+    page_height: f32,
+    max_size: u32,
+) -> Result<Option<ObjectId>, RenderError> {
+    #[cfg(feature = "thumbnails")]
+    {
+        let thumbnail = petty_render_svg::render_thumbnail(
+            elements.to_vec(),
+            resources,
+            font_map,
+            page_width,
+            page_height,
+            max_size,
+            Arc::clone(&THUMBNAIL_FONTS),
+        )?;
+        let mut image = Stream::new(
+            dictionary! {
+                "Width" => thumbnail.width as i64,
+                "Height" => thumbnail.height as i64,
+                "ColorSpace" => "DeviceRGB",
+                "BitsPerComponent" => 8,
+            },
+            thumbnail.rgb,
+        );
+        image
+            .compress()
+            .map_err(|e| RenderError::Other(format!("Failed to compress thumbnail: {}", e)))?;
+        Ok(Some(writer.write_object(Object::Stream(image))?))
+    }
+    #[cfg(not(feature = "thumbnails"))]
+    Ok(None)
+}
+
 /// Render multiple pages in parallel using rayon.
 ///
 /// This function takes a vector of pages (each page is a vector of positioned elements)
@@ -249,12 +710,22 @@ pub fn render_pages_parallel(
 // --- Internal Page Drawing Context ---
 
 use once_cell::sync::Lazy;
-use petty_layout::{ImageElement, TextElement};
+use petty_layout::{ImageElement, PathElement, PathSegment, TextElement};
 use petty_style::font::FontWeight;
 use petty_types::color::Color;
 
 static DEFAULT_LOPDF_FONT_NAME: Lazy<String> = Lazy::new(|| "F1".to_string());
 
+/// Prefix of the resource names of PDF standard fonts, which are followed by the
+/// font's `/BaseFont`. The writer adds the font resources as content streams
+/// use them, see [`StreamingPdfWriter::write_content_stream`].
+pub(crate) const STANDARD_FONT_PREFIX: &str = "Std-";
+
+/// Prefix of the XObject names images are drawn with, which are followed by the
+/// image's `src`. The writer embeds the images and renames their XObjects as content
+/// streams draw them, see [`StreamingPdfWriter::write_content_stream`].
+pub(crate) const IMAGE_PREFIX: &str = "Img-";
+
 struct PageContext<'a> {
     page_height: f32,
     content: Content,
@@ -285,6 +756,7 @@ impl<'a> PageContext<'a> {
         match &el.element {
             LayoutElement::Text(text) => self.draw_text(text, el)?,
             LayoutElement::Image(image) => self.draw_image(image, el)?,
+            LayoutElement::Path(path) => self.draw_path(path, el),
             _ => {}
         }
         Ok(())
@@ -352,12 +824,17 @@ impl<'a> PageContext<'a> {
         }
     }
     fn set_font(&mut self, style: &Arc<ComputedStyle>) {
-        let styled_font_name = Self::get_styled_font_name(style);
-        let internal_font_name = self
-            .font_map
-            .get(styled_font_name.as_ref())
-            .or_else(|| self.font_map.get(style.text.font_family.as_str()))
-            .unwrap_or(&DEFAULT_LOPDF_FONT_NAME);
+        let standard_font_name;
+        let internal_font_name = if let Some(font) = LayoutEngine::standard_font(style) {
+            standard_font_name = format!("{}{}", STANDARD_FONT_PREFIX, font.base_font_name());
+            &standard_font_name
+        } else {
+            let styled_font_name = Self::get_styled_font_name(style);
+            self.font_map
+                .get(styled_font_name.as_ref())
+                .or_else(|| self.font_map.get(style.text.font_family.as_str()))
+                .unwrap_or(&DEFAULT_LOPDF_FONT_NAME)
+        };
 
         if self.state.font_name != *internal_font_name
             || self.state.font_size != style.text.font_size
@@ -415,15 +892,70 @@ impl<'a> PageContext<'a> {
         self.content.operations.push(Operation::new("ET", vec![]));
         Ok(())
     }
+    /// Strokes a polyline in the element's text color, one point wide.
+    fn draw_path(&mut self, path: &PathElement, el: &PositionedElement) {
+        if path.segments.is_empty() {
+            return;
+        }
+        let page_height = self.page_height;
+        let to_pdf = |(px, py): (f32, f32)| -> [Object; 2] {
+            [
+                (el.x + px * el.width).into(),
+                (page_height - (el.y + py * el.height)).into(),
+            ]
+        };
+        let color = &el.style.text.color;
+        let rgb: Vec<Object> = vec![
+            (color.r as f32 / 255.0).into(),
+            (color.g as f32 / 255.0).into(),
+            (color.b as f32 / 255.0).into(),
+        ];
+        let ops = &mut self.content.operations;
+        ops.push(Operation::new("q", vec![]));
+        if path.filled {
+            ops.push(Operation::new("rg", rgb));
+        } else {
+            ops.push(Operation::new("w", vec![1.0.into()]));
+            ops.push(Operation::new("RG", rgb));
+        }
+        for segment in &path.segments {
+            let op = match *segment {
+                PathSegment::MoveTo(p) => Operation::new("m", to_pdf(p).to_vec()),
+                PathSegment::LineTo(p) => Operation::new("l", to_pdf(p).to_vec()),
+                PathSegment::CurveTo(points) => {
+                    Operation::new("c", points.into_iter().flat_map(to_pdf).collect())
+                }
+                PathSegment::Close => Operation::new("h", vec![]),
+            };
+            ops.push(op);
+        }
+        ops.push(Operation::new(if path.filled { "f" } else { "S" }, vec![]));
+        ops.push(Operation::new("Q", vec![]));
+    }
+    /// Draws the image scaled to the element's box. The XObject is named after the
+    /// image's `src`, see [`IMAGE_PREFIX`].
     fn draw_image(
         &mut self,
         image: &ImageElement,
-        _el: &PositionedElement,
+        el: &PositionedElement,
     ) -> Result<(), RenderError> {
-        log::warn!(
-            "Images are not supported in the lopdf streaming renderer yet: {}",
-            image.src
-        );
+        let y = self.page_height - (el.y + el.height);
+        let name = format!("{}{}", IMAGE_PREFIX, image.src);
+        let ops = &mut self.content.operations;
+        ops.push(Operation::new("q", vec![]));
+        ops.push(Operation::new(
+            "cm",
+            vec![
+                el.width.into(),
+                0.into(),
+                0.into(),
+                el.height.into(),
+                el.x.into(),
+                y.into(),
+            ],
+        ));
+        ops.push(Operation::new("Do", vec![Object::Name(name.into_bytes())]));
+        ops.push(Operation::new("Q", vec![]));
         Ok(())
     }
 }
@@ -0,0 +1,430 @@
+//! Raster image XObjects (PDF 32000-1, 8.9.5): the image data a document references,
+//! decoded and re-encoded for embedding.
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ExtendedColorType, ImageDecoder, ImageFormat, ImageReader};
+use lopdf::{Stream, dictionary};
+use petty_idf::SharedData;
+use petty_render_core::RenderError;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::Cursor;
+
+/// How raster images are encoded when they are embedded.
+#[derive(Clone, Default, PartialEq)]
+pub struct ImageOptions {
+    /// Recompresses opaque images to JPEG at this quality, from 1 to 100, when that
+    /// makes them smaller than they would otherwise be embedded.
+    pub jpeg_quality: Option<u8>,
+    /// Downsamples images whose resolution at the size they are drawn exceeds this
+    /// many pixels per inch.
+    pub max_dpi: Option<f32>,
+    /// An ICC profile (gray or RGB) to tag images that do not embed one with, as an
+    /// `/ICCBased` color space.
+    pub icc_profile: Option<SharedData>,
+}
+
+impl fmt::Debug for ImageOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageOptions")
+            .field("jpeg_quality", &self.jpeg_quality)
+            .field("max_dpi", &self.max_dpi)
+            .field("icc_profile", &self.icc_profile.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+/// The quality JPEG images are re-encoded at when they are downsampled and no
+/// [`ImageOptions::jpeg_quality`] is set.
+const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+impl ImageOptions {
+    /// The largest pixel dimensions an image drawn at `width` by `height` points
+    /// may have under [`ImageOptions::max_dpi`].
+    pub(crate) fn max_pixels(&self, width: f32, height: f32) -> Option<(u32, u32)> {
+        let dpi = self.max_dpi?;
+        let pixels = |points: f32| ((points.abs() / 72.0 * dpi).ceil() as u32).max(1);
+        Some((pixels(width), pixels(height)))
+    }
+}
+
+/// An image XObject, the soft mask holding its alpha channel and the ICC profile
+/// stream its `/ICCBased` color space refers to, if it has them.
+///
+/// The image's `/ColorSpace` is the profile's alternate device color space until the
+/// profile stream is written and can be referenced.
+pub(crate) struct ImageXObject {
+    pub image: Stream,
+    pub soft_mask: Option<Stream>,
+    pub icc_profile: Option<Stream>,
+}
+
+impl ImageXObject {
+    /// A hash of the dictionaries and data of the image, its soft mask and its ICC
+    /// profile, equal for images that embed the same.
+    pub(crate) fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        let streams = std::iter::once(&self.image)
+            .chain(&self.soft_mask)
+            .chain(&self.icc_profile);
+        for stream in streams {
+            // Entries in key order, as in `petty-pdf-composer`'s object deduplication.
+            let mut entries: Vec<_> = stream.dict.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            for (key, value) in entries {
+                key.hash(&mut hasher);
+                format!("{:?}", value).hash(&mut hasher);
+            }
+            stream.content.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// Decodes `data` (PNG or JPEG) into an image XObject, downsampled to fit within
+/// `max_pixels` if it is larger.
+///
+/// The image is tagged with the ICC profile it embeds, or else with
+/// [`ImageOptions::icc_profile`]. Gray images tagged with an RGB profile are embedded
+/// as RGB, and RGB images tagged with a gray profile as gray. Profiles of other
+/// color spaces, such as CMYK, are left out.
+///
+/// JPEG data in a color space PDF reads directly is embedded as it is, or re-encoded
+/// as JPEG when downsampled. Anything else is embedded as Flate-compressed 8-bit gray
+/// or RGB samples, with any alpha channel moved to a soft mask. With
+/// [`ImageOptions::jpeg_quality`] set, opaque images are re-encoded as JPEG instead,
+/// unless that comes out larger.
+pub(crate) fn encode_image(
+    data: &[u8],
+    options: &ImageOptions,
+    max_pixels: Option<(u32, u32)>,
+) -> Result<ImageXObject, RenderError> {
+    let decode_error =
+        |e: image::ImageError| RenderError::Other(format!("Failed to decode image: {}", e));
+    let mut decoder = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| RenderError::Other(format!("Failed to decode image: {}", e)))?
+        .into_decoder()
+        .map_err(decode_error)?;
+    let profile = decoder
+        .icc_profile()
+        .ok()
+        .flatten()
+        .or_else(|| options.icc_profile.as_deref().cloned());
+    let mut decoded = DynamicImage::from_decoder(decoder).map_err(decode_error)?;
+    let downsampled = match max_pixels {
+        Some((max_width, max_height))
+            if decoded.width() > max_width || decoded.height() > max_height =>
+        {
+            // `resize` keeps the aspect ratio, fitting the image within the bounds.
+            decoded = decoded.resize(max_width, max_height, FilterType::CatmullRom);
+            true
+        }
+        _ => false,
+    };
+    let (width, height) = (decoded.width(), decoded.height());
+    let profile = profile.and_then(|profile| match icc_components(&profile) {
+        Some(components) => Some((components, profile)),
+        None => {
+            log::warn!("Ignoring an ICC profile that is neither gray nor RGB");
+            None
+        }
+    });
+    let gray = match &profile {
+        Some((components, _)) => *components == 1,
+        None => !decoded.color().has_color(),
+    };
+    let color_space = if gray { "DeviceGray" } else { "DeviceRGB" };
+
+    let soft_mask = if decoded.color().has_alpha() {
+        let alpha: Vec<u8> = decoded.to_luma_alpha8().pixels().map(|p| p[1]).collect();
+        Some(flate_image(alpha, width, height, "DeviceGray")?)
+    } else {
+        None
+    };
+
+    let source_jpeg = image::guess_format(data).ok() == Some(ImageFormat::Jpeg)
+        && jpeg_components(data) == Some(if gray { 1 } else { 3 });
+    let mut image = if source_jpeg && !downsampled {
+        dct_image(data.to_vec(), width, height, color_space)
+    } else if source_jpeg {
+        let quality = options.jpeg_quality.unwrap_or(DEFAULT_JPEG_QUALITY);
+        dct_image(
+            encode_jpeg(&decoded, gray, quality)?,
+            width,
+            height,
+            color_space,
+        )
+    } else {
+        flate_image(samples(&decoded, gray), width, height, color_space)?
+    };
+
+    if let Some(quality) = options.jpeg_quality
+        && soft_mask.is_none()
+    {
+        let jpeg = encode_jpeg(&decoded, gray, quality)?;
+        if jpeg.len() < image.content.len() {
+            image = dct_image(jpeg, width, height, color_space);
+        }
+    }
+
+    let icc_profile = match profile {
+        Some((components, profile)) => {
+            let mut stream = Stream::new(
+                dictionary! { "N" => i64::from(components), "Alternate" => color_space },
+                profile,
+            );
+            stream.compress().map_err(|e| {
+                RenderError::Other(format!("Failed to compress ICC profile: {}", e))
+            })?;
+            Some(stream)
+        }
+        None => None,
+    };
+
+    Ok(ImageXObject {
+        image,
+        soft_mask,
+        icc_profile,
+    })
+}
+
+/// The number of color components of an ICC profile's data color space, gray or
+/// RGB, read from its header.
+fn icc_components(profile: &[u8]) -> Option<u8> {
+    match profile.get(16..20)? {
+        b"GRAY" => Some(1),
+        b"RGB " => Some(3),
+        _ => None,
+    }
+}
+
+fn encode_jpeg(decoded: &DynamicImage, gray: bool, quality: u8) -> Result<Vec<u8>, RenderError> {
+    let color_type = if gray {
+        ExtendedColorType::L8
+    } else {
+        ExtendedColorType::Rgb8
+    };
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, quality.clamp(1, 100))
+        .encode(
+            &samples(decoded, gray),
+            decoded.width(),
+            decoded.height(),
+            color_type,
+        )
+        .map_err(|e| RenderError::Other(format!("Failed to encode image as JPEG: {}", e)))?;
+    Ok(jpeg)
+}
+
+/// The image's samples as 8-bit gray or RGB, without alpha.
+fn samples(decoded: &DynamicImage, gray: bool) -> Vec<u8> {
+    if gray {
+        decoded.to_luma8().into_raw()
+    } else {
+        decoded.to_rgb8().into_raw()
+    }
+}
+
+fn image_dictionary(width: u32, height: u32, color_space: &str) -> lopdf::Dictionary {
+    dictionary! {
+        "Type" => "XObject",
+        "Subtype" => "Image",
+        "Width" => width as i64,
+        "Height" => height as i64,
+        "ColorSpace" => color_space,
+        "BitsPerComponent" => 8,
+    }
+}
+
+fn dct_image(jpeg: Vec<u8>, width: u32, height: u32, color_space: &str) -> Stream {
+    let mut dict = image_dictionary(width, height, color_space);
+    dict.set("Filter", "DCTDecode");
+    Stream::new(dict, jpeg)
+}
+
+fn flate_image(
+    samples: Vec<u8>,
+    width: u32,
+    height: u32,
+    color_space: &str,
+) -> Result<Stream, RenderError> {
+    let mut stream = Stream::new(image_dictionary(width, height, color_space), samples);
+    stream
+        .compress()
+        .map_err(|e| RenderError::Other(format!("Failed to compress image: {}", e)))?;
+    Ok(stream)
+}
+
+/// The number of color components of JPEG `data`, read from its frame header.
+fn jpeg_components(data: &[u8]) -> Option<u8> {
+    // Skips the SOI marker, then walks the marker segments up to the first SOFn.
+    let mut i = 2;
+    while i + 9 < data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        if marker == 0xFF {
+            i += 1;
+            continue;
+        }
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            return Some(data[i + 9]);
+        }
+        i += 2 + u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+    }
+    None
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use image::codecs::png::PngEncoder;
+    use image::{ImageBuffer, ImageEncoder, Rgb, Rgba};
+    use std::sync::Arc;
+
+    /// A PNG of smooth gradients with some noise, which compresses far better as JPEG.
+    pub(crate) fn photo_png(width: u32, height: u32) -> Vec<u8> {
+        let image = ImageBuffer::from_fn(width, height, |x, y| {
+            let noise = ((x * 7919 + y * 104_729) % 13) as u8;
+            Rgb([
+                (x * 255 / width) as u8 ^ noise,
+                (y * 255 / height) as u8 ^ noise,
+                ((x + y) % 256) as u8,
+            ])
+        });
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn test_large_png_is_recompressed_to_smaller_jpeg() {
+        let png = photo_png(800, 600);
+        let options = ImageOptions {
+            jpeg_quality: Some(75),
+            ..Default::default()
+        };
+        let xobject = encode_image(&png, &options, None).unwrap();
+
+        let dict = &xobject.image.dict;
+        assert_eq!(
+            dict.get(b"Filter").unwrap().as_name().unwrap(),
+            b"DCTDecode"
+        );
+        assert_eq!(dict.get(b"Width").unwrap().as_i64().unwrap(), 800);
+        assert!(xobject.image.content.len() < png.len());
+        // The embedded data is the JPEG itself.
+        assert_eq!(
+            image::guess_format(&xobject.image.content).unwrap(),
+            ImageFormat::Jpeg
+        );
+    }
+
+    #[test]
+    fn test_png_is_embedded_losslessly_without_recompression() {
+        let png = photo_png(64, 48);
+        let xobject = encode_image(&png, &ImageOptions::default(), None).unwrap();
+        let dict = &xobject.image.dict;
+        assert_eq!(
+            dict.get(b"Filter").unwrap().as_name().unwrap(),
+            b"FlateDecode"
+        );
+        assert_eq!(
+            dict.get(b"ColorSpace").unwrap().as_name().unwrap(),
+            b"DeviceRGB"
+        );
+        assert!(xobject.soft_mask.is_none());
+    }
+
+    #[test]
+    fn test_alpha_channel_goes_to_a_soft_mask_and_is_not_recompressed() {
+        let image = ImageBuffer::from_fn(32, 32, |x, _| Rgba([255, 0, 0, (x * 8) as u8]));
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let options = ImageOptions {
+            jpeg_quality: Some(50),
+            ..Default::default()
+        };
+        let xobject = encode_image(&png, &options, None).unwrap();
+
+        assert_eq!(
+            xobject
+                .image
+                .dict
+                .get(b"Filter")
+                .unwrap()
+                .as_name()
+                .unwrap(),
+            b"FlateDecode"
+        );
+        let soft_mask = xobject.soft_mask.unwrap();
+        assert_eq!(
+            soft_mask
+                .dict
+                .get(b"ColorSpace")
+                .unwrap()
+                .as_name()
+                .unwrap(),
+            b"DeviceGray"
+        );
+    }
+
+    /// The header of an ICC profile with data color space `color_space`; enough for
+    /// tagging, as profiles are embedded without being interpreted.
+    pub(crate) fn icc_profile(color_space: &[u8; 4]) -> Vec<u8> {
+        let mut profile = vec![0; 128];
+        profile[0..4].copy_from_slice(&128u32.to_be_bytes());
+        profile[12..16].copy_from_slice(b"mntr");
+        profile[16..20].copy_from_slice(color_space);
+        profile[20..24].copy_from_slice(b"XYZ ");
+        profile[36..40].copy_from_slice(b"acsp");
+        profile
+    }
+
+    #[test]
+    fn test_embedded_icc_profile_is_detected() {
+        let image = ImageBuffer::from_fn(16, 16, |x, y| Rgb([x as u8 * 16, y as u8 * 16, 0]));
+        let mut png = Vec::new();
+        let mut encoder = PngEncoder::new(&mut png);
+        encoder.set_icc_profile(icc_profile(b"RGB ")).unwrap();
+        encoder
+            .write_image(image.as_raw(), 16, 16, ExtendedColorType::Rgb8)
+            .unwrap();
+
+        let xobject = encode_image(&png, &ImageOptions::default(), None).unwrap();
+        let profile = xobject.icc_profile.unwrap();
+        assert_eq!(profile.dict.get(b"N").unwrap().as_i64().unwrap(), 3);
+        let mut content = profile.clone();
+        content.decompress().unwrap();
+        assert_eq!(content.content, icc_profile(b"RGB "));
+    }
+
+    #[test]
+    fn test_supplied_gray_icc_profile_makes_image_gray() {
+        let options = ImageOptions {
+            icc_profile: Some(Arc::new(icc_profile(b"GRAY"))),
+            ..Default::default()
+        };
+        let xobject = encode_image(&photo_png(16, 16), &options, None).unwrap();
+        let profile = xobject.icc_profile.unwrap();
+        assert_eq!(profile.dict.get(b"N").unwrap().as_i64().unwrap(), 1);
+        assert_eq!(
+            xobject
+                .image
+                .dict
+                .get(b"ColorSpace")
+                .unwrap()
+                .as_name()
+                .unwrap(),
+            b"DeviceGray"
+        );
+    }
+}
@@ -4,9 +4,11 @@
 //! for efficient PDF generation with minimal memory usage.
 
 mod helpers;
+mod images;
 mod renderer;
 mod writer;
 
 pub use helpers::*;
+pub use images::ImageOptions;
 pub use renderer::LopdfRenderer;
 pub use writer::StreamingPdfWriter;
@@ -1,9 +1,12 @@
 // src/render/lopdf_renderer.rs
 use crate::helpers;
+use crate::images::ImageOptions;
 use crate::writer::StreamingPdfWriter;
+use lopdf::content::Content;
 use lopdf::{Dictionary, Object, dictionary};
 use petty_idf::SharedData;
 use petty_layout::{LayoutEngine, PositionedElement};
+use petty_render_core::utils::CoordinatePrecision;
 use petty_render_core::{DocumentRenderer, ObjectId, RenderError};
 use petty_style::stylesheet::Stylesheet;
 use std::any::Any;
@@ -18,6 +21,15 @@ pub struct LopdfRenderer<W: Write + Seek + Send> {
     pub layout_engine: LayoutEngine,
     font_map: HashMap<String, String>,
     outline_root_id: Option<ObjectId>,
+    compress: bool,
+    clip_to_page: bool,
+    coordinate_precision: Option<CoordinatePrecision>,
+    print_marks: helpers::PrintMarks,
+    image_options: ImageOptions,
+    thumbnail_size: Option<u32>,
+    /// Images referenced by the pages, kept only to draw them in thumbnails.
+    thumbnail_resources: HashMap<String, SharedData>,
+    pending_thumbnail: Option<ObjectId>,
 }
 
 impl<W: Write + Seek + Send> LopdfRenderer<W> {
@@ -32,15 +44,110 @@ impl<W: Write + Seek + Send> LopdfRenderer<W> {
             font_map.insert(font_info.postscript_name.clone(), format!("F{}", i + 1));
         }
 
+        let print_marks =
+            helpers::PrintMarks::from_page_layout(stylesheet.get_default_page_layout());
+
         Ok(Self {
             writer: None,
             stylesheet,
             layout_engine,
             font_map,
             outline_root_id: None,
+            compress: false,
+            clip_to_page: false,
+            coordinate_precision: None,
+            print_marks,
+            image_options: ImageOptions::default(),
+            thumbnail_size: None,
+            thumbnail_resources: HashMap::new(),
+            pending_thumbnail: None,
         })
     }
 
+    /// Writes object streams and a cross-reference stream instead of a plain
+    /// xref table. See [`StreamingPdfWriter::with_compression`].
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compress = enabled;
+        self
+    }
+
+    /// Clips every content stream to the page's MediaBox. See [`helpers::clip_to_page`].
+    pub fn with_page_clip(mut self, enabled: bool) -> Self {
+        self.clip_to_page = enabled;
+        self
+    }
+
+    /// Rounds the coordinates written to content streams. See [`helpers::round_coordinates`].
+    pub fn with_coordinate_precision(mut self, precision: Option<CoordinatePrecision>) -> Self {
+        self.coordinate_precision = precision;
+        self
+    }
+
+    /// Sets how images are encoded when they are embedded, e.g. recompressed to JPEG.
+    pub fn with_image_options(mut self, options: ImageOptions) -> Self {
+        self.image_options = options;
+        self
+    }
+
+    /// Embeds a `/Thumb` image in every page, scaled so its longer side is `max_size`
+    /// pixels. Has no effect unless the `thumbnails` feature is enabled.
+    pub fn with_thumbnails(mut self, max_size: Option<u32>) -> Self {
+        self.thumbnail_size = max_size;
+        self
+    }
+
+    /// Rasterizes `elements` into a thumbnail image object for their page, if
+    /// thumbnails are enabled. The returned ID belongs in the page's `/Thumb` entry.
+    pub fn write_thumbnail(
+        &mut self,
+        elements: &[PositionedElement],
+        page_width: f32,
+        page_height: f32,
+    ) -> Result<Option<ObjectId>, RenderError> {
+        let Some(max_size) = self.thumbnail_size else {
+            return Ok(None);
+        };
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| RenderError::Other("Document not started".into()))?;
+        helpers::write_thumbnail(
+            writer,
+            elements,
+            &self.thumbnail_resources,
+            &self.font_map,
+            page_width,
+            page_height,
+            max_size,
+        )
+    }
+
+    /// Applies the page-level additions to a page's rendered content: coordinate
+    /// rounding and the page clip, when enabled, and the page master's crop marks,
+    /// which sit outside it.
+    pub fn finish_page_content(
+        &self,
+        mut content: Content,
+        page_width: f32,
+        page_height: f32,
+    ) -> Content {
+        if let Some(precision) = self.coordinate_precision {
+            content = helpers::round_coordinates(content, precision);
+        }
+        if self.clip_to_page {
+            content =
+                helpers::clip_to_page(content, page_width, page_height, self.print_marks.bleed);
+        }
+        self.print_marks
+            .draw_crop_marks(&mut content, page_width, page_height);
+        content
+    }
+
+    /// The default page master's bleed and crop-mark settings.
+    pub fn print_marks(&self) -> helpers::PrintMarks {
+        self.print_marks
+    }
+
     #[allow(dead_code)]
     pub fn writer_mut(&mut self) -> Option<&mut StreamingPdfWriter<W>> {
         self.writer.as_mut()
@@ -63,10 +170,14 @@ impl<W: Write + Seek + Send> LopdfRenderer<W> {
         let mut page_dict = dictionary! {
             "Type" => "Page",
             "Parent" => writer.pages_id,
-            "MediaBox" => vec![0.0.into(), 0.0.into(), page_width.into(), page_height.into()],
             "Contents" => Object::Array(content_stream_ids.into_iter().map(Object::Reference).collect()),
             "Resources" => writer.resources_id,
         };
+        self.print_marks
+            .set_page_boxes(&mut page_dict, page_width, page_height);
+        if let Some(thumbnail_id) = self.pending_thumbnail.take() {
+            page_dict.set("Thumb", thumbnail_id);
+        }
         if !annotations.is_empty() {
             page_dict.set(
                 "Annots",
@@ -112,14 +223,29 @@ impl<W: Write + Seek + Send + 'static> DocumentRenderer<W> for LopdfRenderer<W>
             }
         }
 
-        self.writer = Some(StreamingPdfWriter::new(writer, "1.7", font_dict)?);
+        self.writer = Some(
+            StreamingPdfWriter::new(writer, "1.7", font_dict)?
+                .with_compression(self.compress)
+                .with_image_options(self.image_options.clone()),
+        );
         Ok(())
     }
 
     fn add_resources(
         &mut self,
-        _resources: &HashMap<String, SharedData>,
+        resources: &HashMap<String, SharedData>,
     ) -> Result<(), RenderError> {
+        self.writer
+            .as_mut()
+            .ok_or_else(|| RenderError::Other("Document not started".into()))?
+            .add_image_sources(resources);
+        if self.thumbnail_size.is_some() {
+            self.thumbnail_resources.extend(
+                resources
+                    .iter()
+                    .map(|(key, data)| (key.clone(), Arc::clone(data))),
+            );
+        }
         Ok(())
     }
 
@@ -130,12 +256,14 @@ impl<W: Write + Seek + Send + 'static> DocumentRenderer<W> for LopdfRenderer<W>
         page_width: f32,
         page_height: f32,
     ) -> Result<ObjectId, RenderError> {
+        self.pending_thumbnail = self.write_thumbnail(&elements, page_width, page_height)?;
+        let content =
+            helpers::render_elements_to_content(elements, font_map, page_width, page_height)?;
+        let content = self.finish_page_content(content, page_width, page_height);
         let writer = self
             .writer
             .as_mut()
             .ok_or_else(|| RenderError::Other("Document not started".into()))?;
-        let content =
-            helpers::render_elements_to_content(elements, font_map, page_width, page_height)?;
         // Use write_content_stream to stream immediately
         let content_id = writer.write_content_stream(content)?;
         Ok(content_id)
@@ -156,10 +284,14 @@ impl<W: Write + Seek + Send + 'static> DocumentRenderer<W> for LopdfRenderer<W>
         let mut page_dict = dictionary! {
             "Type" => "Page",
             "Parent" => writer.pages_id,
-            "MediaBox" => vec![0.0.into(), 0.0.into(), page_width.into(), page_height.into()],
             "Contents" => Object::Array(content_stream_ids.into_iter().map(Object::Reference).collect()),
             "Resources" => writer.resources_id,
         };
+        self.print_marks
+            .set_page_boxes(&mut page_dict, page_width, page_height);
+        if let Some(thumbnail_id) = self.pending_thumbnail.take() {
+            page_dict.set("Thumb", thumbnail_id);
+        }
         if !annotations.is_empty() {
             page_dict.set(
                 "Annots",
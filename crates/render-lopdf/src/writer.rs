@@ -1,10 +1,21 @@
 // src/render/streaming_writer.rs
+use crate::helpers::{STANDARD_FONT_PREFIX, drawn_image_src};
+use crate::images::{self, ImageOptions, ImageXObject};
 use lopdf::content::Content;
-use lopdf::{Dictionary, Object, Stream, dictionary};
+use lopdf::{Dictionary, Object, ObjectStream, Stream, dictionary};
+use petty_idf::SharedData;
 use petty_render_core::ObjectId;
-use std::collections::BTreeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 use std::io::{self, Seek, Write};
 
+/// Maximum number of objects packed into a single object stream.
+const OBJECTS_PER_STREAM: usize = 100;
+
+/// An image's `src` and the pixel size it is downsampled to, if it is.
+type ImageKey = (String, Option<(u32, u32)>);
+
 pub struct StreamingPdfWriter<W: Write + Seek> {
     writer: W,
     object_offsets: Vec<u64>,
@@ -16,8 +27,32 @@ pub struct StreamingPdfWriter<W: Write + Seek> {
 
     page_ids: Vec<ObjectId>,
     outline_root_id: Option<ObjectId>,
+    /// Terminal form fields, listed in the catalog's `/AcroForm` dictionary.
+    form_fields: Vec<ObjectId>,
+    form_resources_id: Option<ObjectId>,
+    /// Entries of the catalog's `/Dests` dictionary.
+    named_destinations: BTreeMap<String, Object>,
+    page_labels: Option<Dictionary>,
 
     buffered_objects: BTreeMap<ObjectId, Object>,
+
+    /// Image data by `src`, for the images content streams draw.
+    image_sources: HashMap<String, SharedData>,
+    image_options: ImageOptions,
+    /// Resource names of the image XObjects written so far by `src` and the pixel
+    /// size they were downsampled to, or `None` for images that could not be embedded.
+    image_names: HashMap<ImageKey, Option<Vec<u8>>>,
+    /// Resource names of the image XObjects written so far by content hash, so that
+    /// images that embed the same share one XObject.
+    image_hashes: HashMap<u64, Vec<u8>>,
+    /// ICC profile streams of image color spaces by content hash.
+    icc_profiles: HashMap<u64, ObjectId>,
+
+    /// Set when objects are packed into object streams and indexed by an xref stream.
+    /// Holds the non-stream objects waiting for the next object stream.
+    pending_objects: Option<ObjectStream>,
+    /// Object number -> (object stream number, index within that stream).
+    compressed_locations: BTreeMap<u32, (u32, u32)>,
 }
 
 impl<W: Write + Seek> StreamingPdfWriter<W> {
@@ -44,10 +79,44 @@ impl<W: Write + Seek> StreamingPdfWriter<W> {
             resources_id,
             page_ids: Vec::new(),
             outline_root_id: None,
+            form_fields: Vec::new(),
+            form_resources_id: None,
+            named_destinations: BTreeMap::new(),
+            page_labels: None,
             buffered_objects,
+            image_sources: HashMap::new(),
+            image_options: ImageOptions::default(),
+            image_names: HashMap::new(),
+            image_hashes: HashMap::new(),
+            icc_profiles: HashMap::new(),
+            pending_objects: None,
+            compressed_locations: BTreeMap::new(),
         })
     }
 
+    /// Enables PDF 1.5 compression: content streams are Flate-encoded, other objects
+    /// are packed into object streams, and the cross-reference table is written as a
+    /// compressed xref stream. Must be called before any object is written.
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.pending_objects = enabled.then(new_object_stream);
+        self
+    }
+
+    /// Sets how the images content streams draw are encoded when they are embedded.
+    pub fn with_image_options(mut self, options: ImageOptions) -> Self {
+        self.image_options = options;
+        self
+    }
+
+    /// Makes images available to content streams, by `src`.
+    pub fn add_image_sources(&mut self, sources: &HashMap<String, SharedData>) {
+        self.image_sources.extend(
+            sources
+                .iter()
+                .map(|(src, data)| (src.clone(), SharedData::clone(data))),
+        );
+    }
+
     pub fn new_object_id(&mut self) -> ObjectId {
         self.current_id += 1;
         self.object_offsets.push(0);
@@ -61,6 +130,48 @@ impl<W: Write + Seek> StreamingPdfWriter<W> {
     }
 
     fn write_object_at_id(&mut self, id: ObjectId, object: &Object) -> io::Result<()> {
+        let Some(pending) = self.pending_objects.as_mut() else {
+            return self.write_direct_object(id, object);
+        };
+
+        match object {
+            Object::Stream(stream) => {
+                let mut stream = stream.clone();
+                stream.compress().map_err(io::Error::other)?;
+                self.write_direct_object(id, &Object::Stream(stream))
+            }
+            _ => {
+                pending
+                    .add_object(id, object.clone())
+                    .map_err(io::Error::other)?;
+                if pending.object_count() >= OBJECTS_PER_STREAM {
+                    self.flush_object_stream()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes the pending objects as one object stream.
+    fn flush_object_stream(&mut self) -> io::Result<()> {
+        let Some(pending) = self.pending_objects.replace(new_object_stream()) else {
+            return Ok(());
+        };
+        if pending.object_count() == 0 {
+            return Ok(());
+        }
+
+        let stream = pending.to_stream_object().map_err(io::Error::other)?;
+        let stream_id = self.new_object_id();
+        // Objects are stored in the stream in ascending id order.
+        for (index, id) in pending.objects.keys().enumerate() {
+            self.compressed_locations
+                .insert(id.0, (stream_id.0, index as u32));
+        }
+        self.write_direct_object(stream_id, &Object::Stream(stream))
+    }
+
+    fn write_direct_object(&mut self, id: ObjectId, object: &Object) -> io::Result<()> {
         let offset = self.writer.stream_position()?;
 
         let idx = (id.0 as usize)
@@ -79,11 +190,161 @@ impl<W: Write + Seek> StreamingPdfWriter<W> {
         Ok(())
     }
 
-    pub fn write_content_stream(&mut self, content: Content) -> io::Result<ObjectId> {
+    /// Writes a page content stream, adding a font resource for each PDF standard
+    /// font it selects that the resource dictionary does not have yet, and an image
+    /// XObject for each image it draws that has not been embedded yet.
+    pub fn write_content_stream(&mut self, mut content: Content) -> io::Result<ObjectId> {
+        self.add_standard_font_resources(&content);
+        self.add_image_resources(&mut content)?;
         let stream = Stream::new(dictionary! {}, content.encode().unwrap_or_default());
         self.write_object(Object::Stream(stream))
     }
 
+    /// Renames the image draws in `content` to the XObjects of their images,
+    /// embedding each image the first time it is drawn. Draws of images that are
+    /// missing or cannot be decoded are removed.
+    ///
+    /// The size an image is drawn at is the scale of the `cm` operation before its
+    /// draw, as page contexts write it.
+    fn add_image_resources(&mut self, content: &mut Content) -> io::Result<()> {
+        let mut operations = Vec::with_capacity(content.operations.len());
+        let mut drawn_size = (0.0, 0.0);
+        for mut op in std::mem::take(&mut content.operations) {
+            if op.operator == "cm"
+                && let [width, _, _, height, ..] = op.operands.as_slice()
+            {
+                drawn_size = (
+                    width.as_float().unwrap_or(0.0),
+                    height.as_float().unwrap_or(0.0),
+                );
+            }
+            if let Some(src) = drawn_image_src(&op) {
+                let src = String::from_utf8_lossy(src).into_owned();
+                let max_pixels = self.image_options.max_pixels(drawn_size.0, drawn_size.1);
+                match self.image_resource_name(src, max_pixels)? {
+                    Some(name) => op.operands = vec![Object::Name(name)],
+                    None => continue,
+                }
+            }
+            operations.push(op);
+        }
+        content.operations = operations;
+        Ok(())
+    }
+
+    /// The resource name of the XObject for image `src` downsampled to `max_pixels`,
+    /// which is written and added to the resource dictionary on first use.
+    fn image_resource_name(
+        &mut self,
+        src: String,
+        max_pixels: Option<(u32, u32)>,
+    ) -> io::Result<Option<Vec<u8>>> {
+        let key = (src, max_pixels);
+        if let Some(name) = self.image_names.get(&key) {
+            return Ok(name.clone());
+        }
+        let src = &key.0;
+        let name = match self.image_sources.get(src) {
+            None => {
+                log::warn!("Image '{}' was not loaded and is left out", src);
+                None
+            }
+            Some(data) => match images::encode_image(data, &self.image_options, max_pixels) {
+                Ok(xobject) => self.write_image_xobject(xobject)?,
+                Err(e) => {
+                    log::warn!("Image '{}' could not be embedded: {}", src, e);
+                    None
+                }
+            },
+        };
+        self.image_names.insert(key, name.clone());
+        Ok(name)
+    }
+
+    /// Writes `xobject` unless an identical image was written before, returning the
+    /// resource name of the XObject.
+    fn write_image_xobject(&mut self, xobject: ImageXObject) -> io::Result<Option<Vec<u8>>> {
+        let hash = xobject.content_hash();
+        if let Some(name) = self.image_hashes.get(&hash) {
+            return Ok(Some(name.clone()));
+        }
+        let mut image = xobject.image;
+        if let Some(icc_profile) = xobject.icc_profile {
+            let profile_id = self.write_icc_profile(icc_profile)?;
+            image.dict.set(
+                "ColorSpace",
+                vec![Object::Name(b"ICCBased".to_vec()), profile_id.into()],
+            );
+        }
+        if let Some(soft_mask) = xobject.soft_mask {
+            image
+                .dict
+                .set("SMask", self.write_object(Object::Stream(soft_mask))?);
+        }
+        let image_id = self.write_object(Object::Stream(image))?;
+        let name = self.add_xobject_resource(image_id);
+        if let Some(name) = &name {
+            self.image_hashes.insert(hash, name.clone());
+        }
+        Ok(name)
+    }
+
+    /// Writes an ICC profile stream unless the same profile was written before,
+    /// returning its ID.
+    fn write_icc_profile(&mut self, profile: Stream) -> io::Result<ObjectId> {
+        let mut hasher = DefaultHasher::new();
+        profile.content.hash(&mut hasher);
+        let hash = hasher.finish();
+        if let Some(id) = self.icc_profiles.get(&hash) {
+            return Ok(*id);
+        }
+        let id = self.write_object(Object::Stream(profile))?;
+        self.icc_profiles.insert(hash, id);
+        Ok(id)
+    }
+
+    /// Adds `xobject_id` to the resource dictionary's `/XObject` entries, returning
+    /// its name.
+    fn add_xobject_resource(&mut self, xobject_id: ObjectId) -> Option<Vec<u8>> {
+        let Some(Object::Dictionary(resources)) = self.buffered_objects.get_mut(&self.resources_id)
+        else {
+            return None;
+        };
+        if !resources.has(b"XObject") {
+            resources.set("XObject", Dictionary::new());
+        }
+        let xobjects = resources.get_mut(b"XObject").ok()?.as_dict_mut().ok()?;
+        let name = format!("Im{}", xobjects.len() + 1).into_bytes();
+        xobjects.set(name.clone(), xobject_id);
+        Some(name)
+    }
+
+    fn add_standard_font_resources(&mut self, content: &Content) {
+        let Some(Object::Dictionary(resources)) = self.buffered_objects.get_mut(&self.resources_id)
+        else {
+            return;
+        };
+        let Ok(Object::Dictionary(fonts)) = resources.get_mut(b"Font") else {
+            return;
+        };
+        for op in content.operations.iter().filter(|op| op.operator == "Tf") {
+            let Some(Object::Name(name)) = op.operands.first() else {
+                continue;
+            };
+            let Some(base_font) = name.strip_prefix(STANDARD_FONT_PREFIX.as_bytes()) else {
+                continue;
+            };
+            if !fonts.has(name) {
+                let font = dictionary! {
+                    "Type" => "Font", "Subtype" => "Type1",
+                    "BaseFont" => Object::Name(base_font.to_vec()),
+                    "Encoding" => "WinAnsiEncoding",
+                };
+                fonts.set(name.clone(), font);
+            }
+        }
+    }
+
     pub fn buffer_object_at_id(&mut self, id: ObjectId, object: Object) {
         let idx = (id.0 as usize).saturating_sub(1);
         if idx >= self.object_offsets.len() {
@@ -110,6 +371,44 @@ impl<W: Write + Seek> StreamingPdfWriter<W> {
         self.outline_root_id = outline_root_id;
     }
 
+    /// Defines a named destination at vertical position `top` (PDF coordinates) of a page.
+    pub fn add_named_destination(&mut self, name: impl Into<String>, page_id: ObjectId, top: f32) {
+        let dest = vec![Object::Reference(page_id), "FitH".into(), top.into()];
+        self.named_destinations.insert(name.into(), dest.into());
+    }
+
+    /// Sets the catalog's `/PageLabels` number tree.
+    pub fn set_page_labels(&mut self, page_labels: Option<Dictionary>) {
+        self.page_labels = page_labels;
+    }
+
+    /// Registers a field to be listed in the document's `/AcroForm` dictionary.
+    pub fn add_form_field(&mut self, field_id: ObjectId) {
+        self.form_fields.push(field_id);
+    }
+
+    /// Returns the resource dictionary shared by form field appearance streams and
+    /// the `/AcroForm` default resources, creating it on first use.
+    pub fn form_resources_id(&mut self) -> ObjectId {
+        if let Some(id) = self.form_resources_id {
+            return id;
+        }
+        let resources = dictionary! {
+            "Font" => dictionary! {
+                "Helv" => dictionary! {
+                    "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Helvetica",
+                    "Encoding" => "WinAnsiEncoding",
+                },
+                "ZaDb" => dictionary! {
+                    "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "ZapfDingbats",
+                },
+            },
+        };
+        let id = self.buffer_object(resources.into());
+        self.form_resources_id = Some(id);
+        id
+    }
+
     pub fn finish(mut self) -> io::Result<W> {
         let pages_dict = dictionary! {
             "Type" => "Pages",
@@ -123,6 +422,27 @@ impl<W: Write + Seek> StreamingPdfWriter<W> {
             catalog_dict.set("Outlines", outline_id);
             catalog_dict.set("PageMode", "UseOutlines");
         }
+        if let Some(page_labels) = self.page_labels.take() {
+            catalog_dict.set("PageLabels", page_labels);
+        }
+        if !self.named_destinations.is_empty() {
+            let dests: Dictionary = std::mem::take(&mut self.named_destinations)
+                .into_iter()
+                .collect();
+            catalog_dict.set("Dests", dests);
+        }
+        if !self.form_fields.is_empty() {
+            let fields: Vec<Object> = self.form_fields.iter().map(|id| (*id).into()).collect();
+            let resources_id = self.form_resources_id();
+            catalog_dict.set(
+                "AcroForm",
+                dictionary! {
+                    "Fields" => fields,
+                    "DR" => resources_id,
+                    "DA" => Object::string_literal("/Helv 0 Tf 0 g"),
+                },
+            );
+        }
         self.buffer_object_at_id(self.catalog_id, catalog_dict.into());
 
         let buffered = std::mem::take(&mut self.buffered_objects);
@@ -130,6 +450,11 @@ impl<W: Write + Seek> StreamingPdfWriter<W> {
             self.write_object_at_id(id, &object)?;
         }
 
+        if self.pending_objects.is_some() {
+            self.flush_object_stream()?;
+            return self.finish_with_xref_stream();
+        }
+
         let xref_start = self.writer.stream_position()?;
         writeln!(self.writer, "xref")?;
         writeln!(self.writer, "0 {}", self.object_offsets.len() + 1)?;
@@ -152,6 +477,71 @@ impl<W: Write + Seek> StreamingPdfWriter<W> {
         self.writer.flush()?;
         Ok(self.writer)
     }
+
+    /// Writes a cross-reference stream in place of the classic xref table and trailer.
+    fn finish_with_xref_stream(mut self) -> io::Result<W> {
+        let xref_id = self.new_object_id();
+        let xref_start = self.writer.stream_position()?;
+        self.object_offsets[xref_id.0 as usize - 1] = xref_start;
+
+        let largest = self
+            .object_offsets
+            .iter()
+            .copied()
+            .chain(
+                self.compressed_locations
+                    .values()
+                    .map(|(s, _)| u64::from(*s)),
+            )
+            .max()
+            .unwrap_or(0);
+        let field_width = (u64::BITS - largest.leading_zeros()).div_ceil(8).max(1) as usize;
+
+        // Each entry is [type, field 2, field 3] with widths [1, field_width, 2].
+        let mut entries = Vec::with_capacity((self.object_offsets.len() + 1) * (field_width + 3));
+        let mut push_entry = |kind: u8, field: u64, index: u16| {
+            entries.push(kind);
+            entries.extend_from_slice(&field.to_be_bytes()[8 - field_width..]);
+            entries.extend_from_slice(&index.to_be_bytes());
+        };
+        push_entry(0, 0, u16::MAX);
+        for (i, offset) in self.object_offsets.iter().enumerate() {
+            match self.compressed_locations.get(&(i as u32 + 1)) {
+                Some((stream, index)) => push_entry(2, u64::from(*stream), *index as u16),
+                None if *offset == 0 => push_entry(0, 0, 0),
+                None => push_entry(1, *offset, 0),
+            }
+        }
+
+        let size = (self.object_offsets.len() + 1) as i64;
+        let mut stream = Stream::new(
+            dictionary! {
+                "Type" => "XRef",
+                "Size" => size,
+                "W" => vec![1.into(), (field_width as i64).into(), 2.into()],
+                "Root" => self.catalog_id,
+            },
+            entries,
+        );
+        stream.compress().map_err(io::Error::other)?;
+
+        internal_writer::write_indirect_object_header(&mut self.writer, xref_id)?;
+        internal_writer::write_object(&mut self.writer, &Object::Stream(stream))?;
+        internal_writer::write_indirect_object_footer(&mut self.writer)?;
+
+        writeln!(self.writer, "startxref")?;
+        writeln!(self.writer, "{}", xref_start)?;
+        write!(self.writer, "%%EOF")?;
+
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+fn new_object_stream() -> ObjectStream {
+    ObjectStream::builder()
+        .max_objects(OBJECTS_PER_STREAM)
+        .build()
 }
 
 mod internal_writer {
@@ -232,3 +622,200 @@ mod internal_writer {
         writer.write_all(b">>")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::render_elements_to_content;
+    use crate::images::tests::{icc_profile, photo_png};
+    use petty_layout::{ComputedStyle, ImageElement, LayoutElement, PositionedElement};
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    const PAGE: (f32, f32) = (612.0, 792.0);
+
+    fn image(src: &str, x: f32, y: f32, width: f32, height: f32) -> PositionedElement {
+        PositionedElement {
+            x,
+            y,
+            width,
+            height,
+            element: LayoutElement::Image(ImageElement { src: src.into() }),
+            style: Arc::new(ComputedStyle::default()),
+        }
+    }
+
+    /// Writes a document with a page per entry of `pages` and loads it back.
+    fn write_document(
+        pages: Vec<Vec<PositionedElement>>,
+        sources: &HashMap<String, SharedData>,
+        options: ImageOptions,
+    ) -> lopdf::Document {
+        let mut writer = StreamingPdfWriter::new(Cursor::new(Vec::new()), "1.7", Dictionary::new())
+            .unwrap()
+            .with_image_options(options);
+        writer.add_image_sources(sources);
+        let mut page_ids = Vec::new();
+        for elements in pages {
+            let content =
+                render_elements_to_content(elements, &HashMap::new(), PAGE.0, PAGE.1).unwrap();
+            let content_id = writer.write_content_stream(content).unwrap();
+            let page = dictionary! {
+                "Type" => "Page",
+                "Parent" => writer.pages_id,
+                "Contents" => content_id,
+                "Resources" => writer.resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), PAGE.0.into(), PAGE.1.into()],
+            };
+            page_ids.push(writer.write_object(page.into()).unwrap());
+        }
+        writer.set_page_ids(page_ids);
+        let output = writer.finish().unwrap().into_inner();
+        lopdf::Document::load_mem(&output).unwrap()
+    }
+
+    /// The image XObjects of `doc`, soft masks included.
+    fn image_xobjects(doc: &lopdf::Document) -> Vec<&Stream> {
+        doc.objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .filter(|stream| {
+                stream.dict.get(b"Subtype").ok() == Some(&Object::Name(b"Image".to_vec()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_drawn_image_is_embedded_as_recompressed_xobject() {
+        let png = photo_png(800, 600);
+        let sources = HashMap::from([("photo.png".to_string(), Arc::new(png.clone()))]);
+        let options = ImageOptions {
+            jpeg_quality: Some(75),
+            ..Default::default()
+        };
+        let doc = write_document(
+            vec![vec![image("photo.png", 72.0, 72.0, 400.0, 300.0)]],
+            &sources,
+            options,
+        );
+
+        let page_id = doc.get_pages()[&1];
+        let content = Content::decode(&doc.get_page_content(page_id).unwrap()).unwrap();
+        let draw = content
+            .operations
+            .iter()
+            .find(|op| op.operator == "Do")
+            .unwrap();
+        let name = draw.operands[0].as_name().unwrap();
+        let resources_id = doc.get_page_resources(page_id).unwrap().1[0];
+        let resources = doc.get_dictionary(resources_id).unwrap();
+        let xobject_id = resources
+            .get(b"XObject")
+            .and_then(Object::as_dict)
+            .and_then(|xobjects| xobjects.get(name))
+            .and_then(Object::as_reference)
+            .unwrap();
+        let xobject = doc.get_object(xobject_id).unwrap().as_stream().unwrap();
+        assert_eq!(
+            xobject.dict.get(b"Filter").unwrap().as_name().unwrap(),
+            b"DCTDecode"
+        );
+        assert!(xobject.content.len() < png.len());
+    }
+
+    #[test]
+    fn test_image_is_downsampled_to_max_dpi_for_its_drawn_size() {
+        let sources = HashMap::from([("logo.png".to_string(), Arc::new(photo_png(1600, 800)))]);
+        let options = ImageOptions {
+            max_dpi: Some(150.0),
+            ..Default::default()
+        };
+        // Two inches by one inch: at most 300 by 150 pixels at 150 DPI.
+        let doc = write_document(
+            vec![vec![image("logo.png", 72.0, 72.0, 144.0, 72.0)]],
+            &sources,
+            options,
+        );
+
+        let images = image_xobjects(&doc);
+        assert_eq!(images.len(), 1);
+        let dict = &images[0].dict;
+        assert_eq!(dict.get(b"Width").unwrap().as_i64().unwrap(), 300);
+        assert_eq!(dict.get(b"Height").unwrap().as_i64().unwrap(), 150);
+    }
+
+    #[test]
+    fn test_identical_images_share_one_xobject_across_pages() {
+        let header = Arc::new(photo_png(120, 40));
+        // The same file, referenced by two paths.
+        let sources = HashMap::from([
+            ("header.png".to_string(), Arc::clone(&header)),
+            ("images/header.png".to_string(), header),
+        ]);
+        let pages = ["header.png", "header.png", "images/header.png"]
+            .into_iter()
+            .map(|src| vec![image(src, 36.0, 36.0, 120.0, 40.0)])
+            .collect();
+        let doc = write_document(pages, &sources, ImageOptions::default());
+
+        assert_eq!(doc.get_pages().len(), 3);
+        assert_eq!(image_xobjects(&doc).len(), 1);
+        let names: Vec<Vec<u8>> = doc
+            .get_pages()
+            .into_values()
+            .map(|page_id| {
+                let content = Content::decode(&doc.get_page_content(page_id).unwrap()).unwrap();
+                let draw = content
+                    .operations
+                    .iter()
+                    .find(|op| op.operator == "Do")
+                    .unwrap();
+                draw.operands[0].as_name().unwrap().to_vec()
+            })
+            .collect();
+        assert!(names.iter().all(|name| name == &names[0]));
+    }
+
+    #[test]
+    fn test_image_color_space_refers_to_icc_based_profile() {
+        let sources = HashMap::from([("photo.png".to_string(), Arc::new(photo_png(32, 32)))]);
+        let options = ImageOptions {
+            icc_profile: Some(Arc::new(icc_profile(b"RGB "))),
+            ..Default::default()
+        };
+        let doc = write_document(
+            vec![vec![image("photo.png", 0.0, 0.0, 32.0, 32.0)]],
+            &sources,
+            options,
+        );
+
+        let images = image_xobjects(&doc);
+        assert_eq!(images.len(), 1);
+        let color_space = images[0]
+            .dict
+            .get(b"ColorSpace")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(color_space[0].as_name().unwrap(), b"ICCBased");
+        let profile = doc
+            .get_object(color_space[1].as_reference().unwrap())
+            .unwrap()
+            .as_stream()
+            .unwrap();
+        assert_eq!(profile.dict.get(b"N").unwrap().as_i64().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_missing_image_draw_is_left_out() {
+        let doc = write_document(
+            vec![vec![image("missing.png", 0.0, 0.0, 10.0, 10.0)]],
+            &HashMap::new(),
+            ImageOptions::default(),
+        );
+        let page_id = doc.get_pages()[&1];
+        let content = Content::decode(&doc.get_page_content(page_id).unwrap()).unwrap();
+        assert!(content.operations.iter().all(|op| op.operator != "Do"));
+        assert!(image_xobjects(&doc).is_empty());
+    }
+}
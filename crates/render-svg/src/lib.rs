@@ -0,0 +1,13 @@
+//! SVG renderer.
+//!
+//! This crate provides a [`DocumentRenderer`](petty_render_core::DocumentRenderer)
+//! that writes each laid-out page as a standalone SVG document instead of a PDF,
+//! and a PNG renderer that rasterizes those pages for previews and thumbnails.
+
+mod png;
+mod renderer;
+mod svg;
+
+pub use png::{PngRenderer, Thumbnail, render_thumbnail};
+pub use renderer::SvgRenderer;
+pub use resvg::usvg::fontdb;
@@ -0,0 +1,244 @@
+use crate::SvgRenderer;
+use petty_idf::SharedData;
+use petty_layout::PositionedElement;
+use petty_render_core::{DocumentRenderer, ObjectId, RenderError};
+use resvg::tiny_skia::{Color, Pixmap, Transform};
+use resvg::usvg::{self, fontdb};
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+use std::sync::Arc;
+
+/// A renderer that rasterizes each page to a PNG, for previews and thumbnails.
+///
+/// Pages are drawn as SVG by [`SvgRenderer`] and then rasterized at `dpi`, so
+/// a page of `w × h` points becomes an image of `w·dpi/72 × h·dpi/72` pixels.
+pub struct PngRenderer<W: Write + Seek + Send> {
+    svg: SvgRenderer<W>,
+    dpi: f32,
+    fontdb: Arc<fontdb::Database>,
+}
+
+impl<W: Write + Seek + Send> PngRenderer<W> {
+    /// Creates a renderer that draws text with the system fonts.
+    pub fn new(dpi: f32) -> Self {
+        let mut fontdb = fontdb::Database::new();
+        fontdb.load_system_fonts();
+        Self::with_font_database(dpi, Arc::new(fontdb))
+    }
+
+    pub fn with_font_database(dpi: f32, fontdb: Arc<fontdb::Database>) -> Self {
+        Self {
+            svg: SvgRenderer::new(),
+            dpi,
+            fontdb,
+        }
+    }
+
+    /// Returns the PNG bytes of each page, in `page_ids` order.
+    pub fn finish_into_pages(self, page_ids: Vec<ObjectId>) -> Result<Vec<Vec<u8>>, RenderError> {
+        let options = usvg::Options {
+            fontdb: self.fontdb.clone(),
+            ..Default::default()
+        };
+        self.svg
+            .finish_into_pages(page_ids)?
+            .iter()
+            .map(|page| rasterize(page, self.dpi, &options))
+            .collect()
+    }
+}
+
+/// A small raster of a page, as 8-bit RGB samples row by row from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    pub rgb: Vec<u8>,
+}
+
+/// Rasterizes the elements of one page so that its longer side is `max_size` pixels,
+/// e.g. for a PDF page's `/Thumb` image.
+pub fn render_thumbnail(
+    elements: Vec<PositionedElement>,
+    resources: &HashMap<String, SharedData>,
+    font_map: &HashMap<String, String>,
+    page_width: f32,
+    page_height: f32,
+    max_size: u32,
+    fontdb: Arc<fontdb::Database>,
+) -> Result<Thumbnail, RenderError> {
+    let mut svg = SvgRenderer::new();
+    svg.begin_document(std::io::Cursor::new(Vec::new()))?;
+    svg.add_resources(resources)?;
+    let content = svg.render_page_content(elements, font_map, page_width, page_height)?;
+    let page = svg.write_page_object(vec![content], vec![], page_width, page_height)?;
+    let page_svg = svg.finish_into_pages(vec![page])?.remove(0);
+
+    let options = usvg::Options {
+        fontdb,
+        ..Default::default()
+    };
+    let pixmap = rasterize_to_pixmap(
+        &page_svg,
+        |size| {
+            let scale = max_size as f32 / size.width().max(size.height());
+            (
+                (size.width() * scale).round().max(1.0) as u32,
+                (size.height() * scale).round().max(1.0) as u32,
+            )
+        },
+        &options,
+    )?;
+    // The page is filled white first, so every pixel is opaque and its
+    // premultiplied samples are the plain color.
+    let rgb = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| [pixel.red(), pixel.green(), pixel.blue()])
+        .collect();
+    Ok(Thumbnail {
+        width: pixmap.width(),
+        height: pixmap.height(),
+        rgb,
+    })
+}
+
+fn rasterize(svg: &str, dpi: f32, options: &usvg::Options) -> Result<Vec<u8>, RenderError> {
+    let scale = dpi / 96.0;
+    let pixmap = rasterize_to_pixmap(
+        svg,
+        |size| {
+            (
+                (size.width() * scale).round().max(1.0) as u32,
+                (size.height() * scale).round().max(1.0) as u32,
+            )
+        },
+        options,
+    )?;
+    pixmap
+        .encode_png()
+        .map_err(|e| RenderError::Other(format!("Failed to encode PNG: {}", e)))
+}
+
+/// Renders `svg` on a white pixmap of the size `pixel_size` picks for the page.
+/// The page is sized in points, which usvg converts to 96 DPI pixels.
+fn rasterize_to_pixmap(
+    svg: &str,
+    pixel_size: impl FnOnce(usvg::Size) -> (u32, u32),
+    options: &usvg::Options,
+) -> Result<Pixmap, RenderError> {
+    let tree = usvg::Tree::from_str(svg, options)
+        .map_err(|e| RenderError::Other(format!("Failed to parse page SVG: {}", e)))?;
+    let size = tree.size();
+    let (width, height) = pixel_size(size);
+    let mut pixmap = Pixmap::new(width, height)
+        .ok_or_else(|| RenderError::Other(format!("Invalid image size {width}x{height}")))?;
+    pixmap.fill(Color::WHITE);
+    resvg::render(
+        &tree,
+        Transform::from_scale(width as f32 / size.width(), height as f32 / size.height()),
+        &mut pixmap.as_mut(),
+    );
+    Ok(pixmap)
+}
+
+impl<W: Write + Seek + Send + 'static> DocumentRenderer<W> for PngRenderer<W> {
+    fn begin_document(&mut self, writer: W) -> Result<(), RenderError> {
+        self.svg.begin_document(writer)
+    }
+
+    fn add_resources(
+        &mut self,
+        resources: &HashMap<String, SharedData>,
+    ) -> Result<(), RenderError> {
+        self.svg.add_resources(resources)
+    }
+
+    fn render_page_content(
+        &mut self,
+        elements: Vec<PositionedElement>,
+        font_map: &HashMap<String, String>,
+        page_width: f32,
+        page_height: f32,
+    ) -> Result<ObjectId, RenderError> {
+        self.svg
+            .render_page_content(elements, font_map, page_width, page_height)
+    }
+
+    fn write_page_object(
+        &mut self,
+        content_stream_ids: Vec<ObjectId>,
+        annotations: Vec<ObjectId>,
+        page_width: f32,
+        page_height: f32,
+    ) -> Result<ObjectId, RenderError> {
+        self.svg
+            .write_page_object(content_stream_ids, annotations, page_width, page_height)
+    }
+
+    fn set_outline_root(&mut self, _outline_root_id: ObjectId) {}
+
+    /// Writes the PNG of each page to the document writer, one after another.
+    fn finish(self: Box<Self>, page_ids: Vec<ObjectId>) -> Result<W, RenderError> {
+        let mut renderer = *self;
+        let mut writer = renderer.svg.take_writer()?;
+        for page in renderer.finish_into_pages(page_ids)? {
+            writer.write_all(&page)?;
+        }
+        Ok(writer)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petty_layout::style::ComputedStyleData;
+    use petty_layout::{ComputedStyle, LayoutElement, RectElement};
+    use petty_types::Color as PettyColor;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_page_is_rasterized_at_requested_dpi() {
+        let mut data = ComputedStyleData::default();
+        data.misc.background_color = Some(PettyColor {
+            r: 0,
+            g: 0,
+            b: 255,
+            a: 1.0,
+        });
+        let background = PositionedElement {
+            x: 0.0,
+            y: 0.0,
+            width: 72.0,
+            height: 72.0,
+            element: LayoutElement::Rectangle(RectElement),
+            style: Arc::new(ComputedStyle::new(data)),
+        };
+
+        let mut renderer: Box<PngRenderer<Cursor<Vec<u8>>>> = Box::new(
+            PngRenderer::with_font_database(144.0, Arc::new(fontdb::Database::new())),
+        );
+        renderer.begin_document(Cursor::new(Vec::new())).unwrap();
+        let content = renderer
+            .render_page_content(vec![background], &HashMap::new(), 595.0, 842.0)
+            .unwrap();
+        let page = renderer
+            .write_page_object(vec![content], vec![], 595.0, 842.0)
+            .unwrap();
+        let pages = renderer.finish_into_pages(vec![page]).unwrap();
+
+        assert_eq!(pages.len(), 1);
+        let image = Pixmap::decode_png(&pages[0]).unwrap();
+        // 595 x 842 points at 144 DPI.
+        assert_eq!((image.width(), image.height()), (1190, 1684));
+        let blue = image.pixel(10, 10).unwrap();
+        assert_eq!((blue.red(), blue.green(), blue.blue()), (0, 0, 255));
+        let white = image.pixel(400, 400).unwrap();
+        assert_eq!((white.red(), white.green(), white.blue()), (255, 255, 255));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::svg;
+use petty_idf::SharedData;
+use petty_layout::PositionedElement;
+use petty_render_core::{DocumentRenderer, ObjectId, RenderError};
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+
+/// A renderer that produces one standalone SVG document per page.
+///
+/// Content and page ids are handed out in the order they are created. Use
+/// [`SvgRenderer::finish_into_pages`] to get the pages individually; the
+/// [`DocumentRenderer::finish`] implementation writes them to the document
+/// writer one after another, in `page_ids` order.
+pub struct SvgRenderer<W: Write + Seek + Send> {
+    writer: Option<W>,
+    resources: HashMap<String, SharedData>,
+    contents: HashMap<ObjectId, String>,
+    pages: HashMap<ObjectId, String>,
+    next_id: u32,
+}
+
+impl<W: Write + Seek + Send> Default for SvgRenderer<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write + Seek + Send> SvgRenderer<W> {
+    pub fn new() -> Self {
+        Self {
+            writer: None,
+            resources: HashMap::new(),
+            contents: HashMap::new(),
+            pages: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    fn allocate_id(&mut self) -> ObjectId {
+        let id = (self.next_id, 0);
+        self.next_id += 1;
+        id
+    }
+
+    pub(crate) fn take_writer(&mut self) -> Result<W, RenderError> {
+        self.writer.take().ok_or_else(|| {
+            RenderError::Other("Document was never started with begin_document".into())
+        })
+    }
+
+    /// Returns the SVG document of each page, in `page_ids` order.
+    pub fn finish_into_pages(
+        mut self,
+        page_ids: Vec<ObjectId>,
+    ) -> Result<Vec<String>, RenderError> {
+        page_ids
+            .into_iter()
+            .map(|id| {
+                self.pages
+                    .remove(&id)
+                    .ok_or_else(|| RenderError::Other(format!("Unknown SVG page id {:?}", id)))
+            })
+            .collect()
+    }
+}
+
+impl<W: Write + Seek + Send + 'static> DocumentRenderer<W> for SvgRenderer<W> {
+    fn begin_document(&mut self, writer: W) -> Result<(), RenderError> {
+        self.writer = Some(writer);
+        Ok(())
+    }
+
+    fn add_resources(
+        &mut self,
+        resources: &HashMap<String, SharedData>,
+    ) -> Result<(), RenderError> {
+        self.resources
+            .extend(resources.iter().map(|(k, v)| (k.clone(), v.clone())));
+        Ok(())
+    }
+
+    fn render_page_content(
+        &mut self,
+        elements: Vec<PositionedElement>,
+        _font_map: &HashMap<String, String>,
+        _page_width: f32,
+        _page_height: f32,
+    ) -> Result<ObjectId, RenderError> {
+        let content = svg::render_elements(&elements, &self.resources);
+        let id = self.allocate_id();
+        self.contents.insert(id, content);
+        Ok(id)
+    }
+
+    /// Assembles the page's content streams into an SVG document. Links are
+    /// emitted inline as `<a>` elements, so `annotations` is ignored.
+    fn write_page_object(
+        &mut self,
+        content_stream_ids: Vec<ObjectId>,
+        _annotations: Vec<ObjectId>,
+        page_width: f32,
+        page_height: f32,
+    ) -> Result<ObjectId, RenderError> {
+        let mut content = String::new();
+        for id in content_stream_ids {
+            let stream = self
+                .contents
+                .remove(&id)
+                .ok_or_else(|| RenderError::Other(format!("Unknown SVG content id {:?}", id)))?;
+            content.push_str(&stream);
+        }
+        let id = self.allocate_id();
+        self.pages
+            .insert(id, svg::page_document(&content, page_width, page_height));
+        Ok(id)
+    }
+
+    fn set_outline_root(&mut self, _outline_root_id: ObjectId) {}
+
+    fn finish(self: Box<Self>, page_ids: Vec<ObjectId>) -> Result<W, RenderError> {
+        let mut renderer = *self;
+        let mut writer = renderer.take_writer()?;
+        for page in renderer.finish_into_pages(page_ids)? {
+            writer.write_all(page.as_bytes())?;
+        }
+        Ok(writer)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petty_layout::style::ComputedStyleData;
+    use petty_layout::{ComputedStyle, LayoutElement, RectElement, TextElement};
+    use petty_types::Color;
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    fn element(
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        element: LayoutElement,
+    ) -> PositionedElement {
+        PositionedElement {
+            x,
+            y,
+            width,
+            height,
+            element,
+            style: Arc::new(ComputedStyle::default()),
+        }
+    }
+
+    #[test]
+    fn test_renders_text_and_rect_elements_per_page() {
+        let mut data = ComputedStyleData::default();
+        data.misc.background_color = Some(Color {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 1.0,
+        });
+        let mut background = element(
+            10.0,
+            20.0,
+            100.0,
+            50.0,
+            LayoutElement::Rectangle(RectElement),
+        );
+        background.style = Arc::new(ComputedStyle::new(data));
+        let text = element(
+            72.0,
+            100.0,
+            200.0,
+            14.4,
+            LayoutElement::Text(TextElement {
+                content: "Hello <SVG>".into(),
+                href: Some("https://example.com".into()),
+                text_decoration: Default::default(),
+            }),
+        );
+
+        let mut renderer: Box<SvgRenderer<Cursor<Vec<u8>>>> = Box::default();
+        renderer.begin_document(Cursor::new(Vec::new())).unwrap();
+        let mut page_ids = Vec::new();
+        for elements in [vec![background, text], vec![]] {
+            let content = renderer
+                .render_page_content(elements, &HashMap::new(), 595.0, 842.0)
+                .unwrap();
+            page_ids.push(
+                renderer
+                    .write_page_object(vec![content], vec![], 595.0, 842.0)
+                    .unwrap(),
+            );
+        }
+        let pages = renderer.finish_into_pages(page_ids).unwrap();
+
+        assert_eq!(pages.len(), 2);
+        let first = &pages[0];
+        assert!(first.contains(r#"viewBox="0 0 595 842""#));
+        assert!(
+            first.contains(r#"<rect x="10" y="20" width="100" height="50" fill="rgb(255,0,0)"/>"#)
+        );
+        // Baseline: y + (line height - font size) / 2 + 0.8 * font size.
+        assert!(first.contains(r#"<a href="https://example.com"><text x="72" y="110.8""#));
+        assert!(first.contains(">Hello &lt;SVG&gt;</text></a>"));
+        assert!(!pages[1].contains("<text"));
+    }
+}
@@ -0,0 +1,252 @@
+//! Converts positioned elements into SVG markup.
+use petty_idf::SharedData;
+use petty_layout::{ImageElement, LayoutElement, PathElement, PathSegment, PositionedElement};
+use petty_style::{BorderStyle, FontStyle, FontWeight, TextDecoration};
+use petty_types::Color;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Renders the elements of one page into SVG fragments, in paint order.
+pub(crate) fn render_elements(
+    elements: &[PositionedElement],
+    resources: &HashMap<String, SharedData>,
+) -> String {
+    let mut out = String::new();
+    for el in elements {
+        draw_background_and_borders(&mut out, el);
+        match &el.element {
+            LayoutElement::Text(text) => {
+                if text.content.trim().is_empty() {
+                    continue;
+                }
+                if let Some(href) = &text.href {
+                    let _ = write!(out, r#"<a href="{}">"#, escape(href));
+                    draw_text(&mut out, &text.content, &text.text_decoration, el);
+                    out.push_str("</a>\n");
+                } else {
+                    draw_text(&mut out, &text.content, &text.text_decoration, el);
+                    out.push('\n');
+                }
+            }
+            LayoutElement::Image(image) => draw_image(&mut out, image, el, resources),
+            LayoutElement::Path(path) => draw_path(&mut out, path, el),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Wraps the page content in a standalone SVG document sized in points.
+pub(crate) fn page_document(content: &str, page_width: f32, page_height: f32) -> String {
+    let (w, h) = (num(page_width), num(page_height));
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="{w}pt" height="{h}pt" viewBox="0 0 {w} {h}">
+{content}</svg>
+"#
+    )
+}
+
+fn draw_background_and_borders(out: &mut String, el: &PositionedElement) {
+    let style = &el.style;
+    if let Some(bg) = &style.misc.background_color {
+        let _ = writeln!(
+            out,
+            r#"<rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+            num(el.x),
+            num(el.y),
+            num(el.width),
+            num(el.height),
+            paint("fill", bg)
+        );
+    }
+    let (left, top) = (el.x, el.y);
+    let (right, bottom) = (el.x + el.width, el.y + el.height);
+    let sides = [
+        (&style.border.top, (left, top), (right, top)),
+        (&style.border.right, (right, top), (right, bottom)),
+        (&style.border.bottom, (left, bottom), (right, bottom)),
+        (&style.border.left, (left, top), (left, bottom)),
+    ];
+    for (border, (x1, y1), (x2, y2)) in sides {
+        let Some(border) = border else { continue };
+        if border.width <= 0.0 || border.style == BorderStyle::None {
+            continue;
+        }
+        let dash = match border.style {
+            BorderStyle::Dotted => r#" stroke-dasharray="1 2""#,
+            BorderStyle::Dashed => r#" stroke-dasharray="3 3""#,
+            _ => "",
+        };
+        let _ = writeln!(
+            out,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke-width="{}"{}{}/>"#,
+            num(x1),
+            num(y1),
+            num(x2),
+            num(y2),
+            num(border.width),
+            paint("stroke", &border.color),
+            dash
+        );
+    }
+}
+
+fn draw_text(out: &mut String, content: &str, decoration: &TextDecoration, el: &PositionedElement) {
+    let text = &el.style.text;
+    // Same baseline placement as the PDF renderer: half the leading above an
+    // approximated ascent.
+    let leading = el.height - text.font_size;
+    let baseline_y = el.y + leading / 2.0 + text.font_size * 0.8;
+
+    let _ = write!(
+        out,
+        r#"<text x="{}" y="{}" font-family="{}" font-size="{}""#,
+        num(el.x),
+        num(baseline_y),
+        escape(&text.font_family),
+        num(text.font_size)
+    );
+    match text.font_weight {
+        FontWeight::Regular => {}
+        FontWeight::Thin => out.push_str(r#" font-weight="100""#),
+        FontWeight::Light => out.push_str(r#" font-weight="300""#),
+        FontWeight::Medium => out.push_str(r#" font-weight="500""#),
+        FontWeight::Bold => out.push_str(r#" font-weight="bold""#),
+        FontWeight::Black => out.push_str(r#" font-weight="900""#),
+        FontWeight::Numeric(w) => {
+            let _ = write!(out, r#" font-weight="{w}""#);
+        }
+    }
+    match text.font_style {
+        FontStyle::Normal => {}
+        FontStyle::Italic => out.push_str(r#" font-style="italic""#),
+        FontStyle::Oblique => out.push_str(r#" font-style="oblique""#),
+    }
+    match decoration {
+        TextDecoration::None => {}
+        TextDecoration::Underline => out.push_str(r#" text-decoration="underline""#),
+        TextDecoration::LineThrough => out.push_str(r#" text-decoration="line-through""#),
+    }
+    out.push_str(&paint("fill", &text.color));
+    let _ = write!(out, r#" xml:space="preserve">{}</text>"#, escape(content));
+}
+
+/// Embeds the image as a data URI when its bytes were registered as a
+/// resource, and references `src` directly otherwise.
+fn draw_image(
+    out: &mut String,
+    image: &ImageElement,
+    el: &PositionedElement,
+    resources: &HashMap<String, SharedData>,
+) {
+    let href = match resources.get(&image.src) {
+        Some(data) => format!("data:{};base64,{}", sniff_mime(data), base64(data)),
+        None => image.src.clone(),
+    };
+    let _ = writeln!(
+        out,
+        r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none" href="{}"/>"#,
+        num(el.x),
+        num(el.y),
+        num(el.width),
+        num(el.height),
+        escape(&href)
+    );
+}
+
+fn draw_path(out: &mut String, path: &PathElement, el: &PositionedElement) {
+    if path.segments.is_empty() {
+        return;
+    }
+    let point = |(px, py): (f32, f32)| {
+        format!(
+            "{} {}",
+            num(el.x + px * el.width),
+            num(el.y + py * el.height)
+        )
+    };
+    let d: Vec<String> = path
+        .segments
+        .iter()
+        .map(|segment| match *segment {
+            PathSegment::MoveTo(p) => format!("M{}", point(p)),
+            PathSegment::LineTo(p) => format!("L{}", point(p)),
+            PathSegment::CurveTo([c1, c2, end]) => {
+                format!("C{} {} {}", point(c1), point(c2), point(end))
+            }
+            PathSegment::Close => "Z".to_string(),
+        })
+        .collect();
+    let color = &el.style.text.color;
+    let paint_attrs = if path.filled {
+        paint("fill", color)
+    } else {
+        format!(r#" fill="none" stroke-width="1"{}"#, paint("stroke", color))
+    };
+    let _ = writeln!(out, r#"<path d="{}"{}/>"#, d.join(" "), paint_attrs);
+}
+
+/// A `fill`/`stroke` attribute, plus its opacity when the color is translucent.
+fn paint(attr: &str, color: &Color) -> String {
+    let mut s = format!(r#" {attr}="rgb({},{},{})""#, color.r, color.g, color.b);
+    if color.a < 1.0 {
+        let _ = write!(s, r#" {attr}-opacity="{}""#, num(color.a));
+    }
+    s
+}
+
+/// Formats a coordinate with at most three decimals.
+fn num(v: f32) -> String {
+    let rounded = (v * 1000.0).round() / 1000.0;
+    format!("{}", rounded + 0.0)
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn sniff_mime(data: &[u8]) -> &'static str {
+    if data.starts_with(b"\x89PNG") {
+        "image/png"
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        "image/jpeg"
+    } else if data.starts_with(b"GIF8") {
+        "image/gif"
+    } else if data.starts_with(b"<svg") || data.starts_with(b"<?xml") {
+        "image/svg+xml"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,269 @@
+use petty_idf::SharedData;
+use petty_layout::{LayoutElement, PositionedElement};
+use petty_render_core::{DocumentRenderer, ObjectId, RenderError};
+use serde::Serialize;
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+
+/// The geometry of one positioned element, as written by [`LayoutDumpRenderer`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DumpedBox {
+    /// One-based page number.
+    pub page: usize,
+    /// The element's index in document order, counted across all pages.
+    pub id: usize,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// The text of a text run, or the source of an image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// A renderer that writes the positioned layout as a JSON array of boxes
+/// instead of drawing it, for visual debugging and layout snapshot tests.
+///
+/// Coordinates are in points from the top-left corner of the page, exactly as
+/// layout produced them.
+pub struct LayoutDumpRenderer<W: Write + Seek + Send> {
+    writer: Option<W>,
+    contents: HashMap<ObjectId, Vec<PositionedElement>>,
+    pages: HashMap<ObjectId, Vec<PositionedElement>>,
+    next_id: u32,
+}
+
+impl<W: Write + Seek + Send> Default for LayoutDumpRenderer<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write + Seek + Send> LayoutDumpRenderer<W> {
+    pub fn new() -> Self {
+        Self {
+            writer: None,
+            contents: HashMap::new(),
+            pages: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    fn allocate_id(&mut self) -> ObjectId {
+        let id = (self.next_id, 0);
+        self.next_id += 1;
+        id
+    }
+
+    /// Returns the boxes of every page, in `page_ids` order.
+    pub fn finish_into_boxes(
+        mut self,
+        page_ids: Vec<ObjectId>,
+    ) -> Result<Vec<DumpedBox>, RenderError> {
+        let mut boxes = Vec::new();
+        for (index, page_id) in page_ids.into_iter().enumerate() {
+            let elements = self.pages.remove(&page_id).ok_or_else(|| {
+                RenderError::Other(format!("Unknown layout dump page id {:?}", page_id))
+            })?;
+            for el in elements {
+                let (kind, content) = match &el.element {
+                    LayoutElement::Text(text) => ("text", Some(text.content.clone())),
+                    LayoutElement::Rectangle(_) => ("rect", None),
+                    LayoutElement::Image(image) => ("image", Some(image.src.clone())),
+                    LayoutElement::Path(_) => ("path", None),
+                    LayoutElement::FormField(field) => ("form-field", Some(field.name.clone())),
+                    LayoutElement::PageNumberPlaceholder { target_id, .. } => {
+                        ("page-number", Some(target_id.clone()))
+                    }
+                };
+                boxes.push(DumpedBox {
+                    page: index + 1,
+                    id: boxes.len(),
+                    kind,
+                    x: el.x,
+                    y: el.y,
+                    width: el.width,
+                    height: el.height,
+                    content,
+                });
+            }
+        }
+        Ok(boxes)
+    }
+}
+
+impl<W: Write + Seek + Send + 'static> DocumentRenderer<W> for LayoutDumpRenderer<W> {
+    fn begin_document(&mut self, writer: W) -> Result<(), RenderError> {
+        self.writer = Some(writer);
+        Ok(())
+    }
+
+    fn add_resources(
+        &mut self,
+        _resources: &HashMap<String, SharedData>,
+    ) -> Result<(), RenderError> {
+        Ok(())
+    }
+
+    fn render_page_content(
+        &mut self,
+        elements: Vec<PositionedElement>,
+        _font_map: &HashMap<String, String>,
+        _page_width: f32,
+        _page_height: f32,
+    ) -> Result<ObjectId, RenderError> {
+        let id = self.allocate_id();
+        self.contents.insert(id, elements);
+        Ok(id)
+    }
+
+    fn write_page_object(
+        &mut self,
+        content_stream_ids: Vec<ObjectId>,
+        _annotations: Vec<ObjectId>,
+        _page_width: f32,
+        _page_height: f32,
+    ) -> Result<ObjectId, RenderError> {
+        let mut elements = Vec::new();
+        for id in content_stream_ids {
+            elements.extend(self.contents.remove(&id).ok_or_else(|| {
+                RenderError::Other(format!("Unknown layout dump content id {:?}", id))
+            })?);
+        }
+        let id = self.allocate_id();
+        self.pages.insert(id, elements);
+        Ok(id)
+    }
+
+    fn set_outline_root(&mut self, _outline_root_id: ObjectId) {}
+
+    fn finish(self: Box<Self>, page_ids: Vec<ObjectId>) -> Result<W, RenderError> {
+        let mut renderer = *self;
+        let mut writer = renderer.writer.take().ok_or_else(|| {
+            RenderError::Other("Document was never started with begin_document".into())
+        })?;
+        let boxes = renderer.finish_into_boxes(page_ids)?;
+        serde_json::to_writer_pretty(&mut writer, &boxes)
+            .map_err(|e| RenderError::Other(format!("Failed to write layout dump: {}", e)))?;
+        Ok(writer)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petty_idf::{IRNode, InlineNode, NodeMetadata};
+    use petty_layout::{LayoutEngine, LayoutStore, SharedFontLibrary};
+    use petty_style::dimension::{Margins, PageSize};
+    use petty_style::stylesheet::{ElementStyle, PageLayout, Stylesheet};
+    use petty_types::Color;
+    use serde_json::Value;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_dumps_positioned_boxes_as_json() {
+        let stylesheet = Stylesheet {
+            page_masters: HashMap::from([(
+                "master".to_string(),
+                PageLayout {
+                    size: PageSize::Custom {
+                        width: 200.0,
+                        height: 300.0,
+                    },
+                    margins: Some(Margins::all(10.0)),
+                    ..Default::default()
+                },
+            )]),
+            default_page_master_name: Some("master".to_string()),
+            ..Default::default()
+        };
+        let block = IRNode::Block {
+            meta: NodeMetadata {
+                style_override: Some(ElementStyle {
+                    padding: Some(Margins::all(5.0)),
+                    background_color: Some(Color {
+                        r: 200,
+                        g: 200,
+                        b: 200,
+                        a: 1.0,
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            children: vec![IRNode::Paragraph {
+                meta: NodeMetadata::default(),
+                children: vec![InlineNode::Text("Inside".to_string())],
+            }],
+        };
+        let paragraph = IRNode::Paragraph {
+            meta: NodeMetadata::default(),
+            children: vec![InlineNode::Text("Hello".to_string())],
+        };
+
+        let library = SharedFontLibrary::new();
+        library.load_fallback_font();
+        let engine = LayoutEngine::new(&library, Default::default());
+        let store = LayoutStore::new();
+        let root = engine
+            .build_render_tree(&IRNode::Root(vec![block, paragraph]), &store)
+            .unwrap();
+        let pages: Vec<_> = engine
+            .paginate(&stylesheet, root, &store)
+            .unwrap()
+            .map(|page| page.unwrap().elements)
+            .collect();
+
+        let mut renderer: Box<LayoutDumpRenderer<Cursor<Vec<u8>>>> = Box::default();
+        renderer.begin_document(Cursor::new(Vec::new())).unwrap();
+        let mut page_ids = Vec::new();
+        for elements in pages {
+            let content = renderer
+                .render_page_content(elements, &HashMap::new(), 200.0, 300.0)
+                .unwrap();
+            page_ids.push(
+                renderer
+                    .write_page_object(vec![content], vec![], 200.0, 300.0)
+                    .unwrap(),
+            );
+        }
+        let output = renderer.finish(page_ids).unwrap().into_inner();
+        let boxes: Value = serde_json::from_slice(&output).unwrap();
+
+        let boxes = boxes.as_array().unwrap();
+        let geometry = |b: &Value| {
+            (
+                b["x"].as_f64().unwrap(),
+                b["y"].as_f64().unwrap(),
+                b["width"].as_f64().unwrap(),
+                b["height"].as_f64().unwrap(),
+            )
+        };
+        assert_eq!(boxes.len(), 3);
+        assert!(boxes.iter().all(|b| b["page"] == 1));
+        let ids: Vec<_> = boxes.iter().map(|b| b["id"].as_u64().unwrap()).collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+
+        let background = boxes.iter().find(|b| b["type"] == "rect").unwrap();
+        let (x, y, width, height) = geometry(background);
+        assert_eq!((x, y, width), (10.0, 10.0, 180.0));
+        assert!((height - 24.4).abs() < 0.01);
+
+        let inside = boxes.iter().find(|b| b["content"] == "Inside").unwrap();
+        assert_eq!(inside["type"], "text");
+        let (x, y, _, _) = geometry(inside);
+        assert_eq!((x, y), (15.0, 15.0));
+
+        let hello = boxes.iter().find(|b| b["content"] == "Hello").unwrap();
+        let (x, y, _, _) = geometry(hello);
+        assert_eq!(x, 10.0);
+        assert!((y - 34.4).abs() < 0.01);
+    }
+}
@@ -0,0 +1,12 @@
+//! Text-based renderers.
+//!
+//! This crate provides [`DocumentRenderer`](petty_render_core::DocumentRenderer)
+//! implementations that produce text instead of a PDF: [`TextRenderer`] extracts
+//! the text of each laid-out page in reading order for search indexing, and
+//! [`LayoutDumpRenderer`] writes the geometry of every positioned element as JSON.
+
+mod layout_dump;
+mod renderer;
+
+pub use layout_dump::{DumpedBox, LayoutDumpRenderer};
+pub use renderer::TextRenderer;
@@ -0,0 +1,275 @@
+use petty_idf::SharedData;
+use petty_layout::{LayoutElement, PositionedElement};
+use petty_render_core::{DocumentRenderer, ObjectId, RenderError};
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+
+/// Vertical slack, in points, when deciding whether two text runs share a line
+/// or whether a line starts a new paragraph.
+const LINE_TOLERANCE: f32 = 0.5;
+
+/// A renderer that outputs the plain text of each page.
+///
+/// Text runs are read in the order layout emitted them, which is document
+/// order. Runs on the same line are concatenated, wrapped lines are joined with
+/// a space, and a vertical gap between lines (such as a paragraph margin) or a
+/// jump back up the page (a new column or table cell) starts a new paragraph.
+/// Paragraphs are separated by a blank line.
+pub struct TextRenderer<W: Write + Seek + Send> {
+    writer: Option<W>,
+    contents: HashMap<ObjectId, String>,
+    pages: HashMap<ObjectId, String>,
+    next_id: u32,
+}
+
+impl<W: Write + Seek + Send> Default for TextRenderer<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Write + Seek + Send> TextRenderer<W> {
+    pub fn new() -> Self {
+        Self {
+            writer: None,
+            contents: HashMap::new(),
+            pages: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    fn allocate_id(&mut self) -> ObjectId {
+        let id = (self.next_id, 0);
+        self.next_id += 1;
+        id
+    }
+
+    /// Returns the text of each page, in `page_ids` order.
+    pub fn finish_into_pages(
+        mut self,
+        page_ids: Vec<ObjectId>,
+    ) -> Result<Vec<String>, RenderError> {
+        page_ids
+            .into_iter()
+            .map(|id| {
+                self.pages
+                    .remove(&id)
+                    .ok_or_else(|| RenderError::Other(format!("Unknown text page id {:?}", id)))
+            })
+            .collect()
+    }
+}
+
+/// A line of text being assembled, with its vertical extent.
+struct Line {
+    text: String,
+    top: f32,
+    bottom: f32,
+    right: f32,
+}
+
+/// Extracts the text of one page's elements as paragraphs separated by blank lines.
+fn extract_text(elements: &[PositionedElement]) -> String {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut paragraph = String::new();
+    let mut line: Option<Line> = None;
+
+    for el in elements {
+        let LayoutElement::Text(text) = &el.element else {
+            continue;
+        };
+        if text.content.is_empty() {
+            continue;
+        }
+        let (top, bottom) = (el.y, el.y + el.height);
+        if let Some(current) = &mut line {
+            let same_line = top < current.bottom - LINE_TOLERANCE
+                && bottom > current.top + LINE_TOLERANCE
+                && el.x >= current.right - LINE_TOLERANCE;
+            if same_line {
+                current.text.push_str(&text.content);
+                current.top = current.top.min(top);
+                current.bottom = current.bottom.max(bottom);
+                current.right = el.x + el.width;
+                continue;
+            }
+            let new_paragraph =
+                top > current.bottom + LINE_TOLERANCE || top < current.top - LINE_TOLERANCE;
+            push_line(&mut paragraph, &current.text);
+            if new_paragraph && !paragraph.is_empty() {
+                paragraphs.push(std::mem::take(&mut paragraph));
+            }
+        }
+        line = Some(Line {
+            text: text.content.clone(),
+            top,
+            bottom,
+            right: el.x + el.width,
+        });
+    }
+    if let Some(current) = line {
+        push_line(&mut paragraph, &current.text);
+    }
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph);
+    }
+    paragraphs.join("\n\n")
+}
+
+fn push_line(paragraph: &mut String, line: &str) {
+    let line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+    if !paragraph.is_empty() {
+        paragraph.push(' ');
+    }
+    paragraph.push_str(line);
+}
+
+impl<W: Write + Seek + Send + 'static> DocumentRenderer<W> for TextRenderer<W> {
+    fn begin_document(&mut self, writer: W) -> Result<(), RenderError> {
+        self.writer = Some(writer);
+        Ok(())
+    }
+
+    fn add_resources(
+        &mut self,
+        _resources: &HashMap<String, SharedData>,
+    ) -> Result<(), RenderError> {
+        Ok(())
+    }
+
+    fn render_page_content(
+        &mut self,
+        elements: Vec<PositionedElement>,
+        _font_map: &HashMap<String, String>,
+        _page_width: f32,
+        _page_height: f32,
+    ) -> Result<ObjectId, RenderError> {
+        let text = extract_text(&elements);
+        let id = self.allocate_id();
+        self.contents.insert(id, text);
+        Ok(id)
+    }
+
+    fn write_page_object(
+        &mut self,
+        content_stream_ids: Vec<ObjectId>,
+        _annotations: Vec<ObjectId>,
+        _page_width: f32,
+        _page_height: f32,
+    ) -> Result<ObjectId, RenderError> {
+        let mut parts = Vec::with_capacity(content_stream_ids.len());
+        for id in content_stream_ids {
+            let text = self
+                .contents
+                .remove(&id)
+                .ok_or_else(|| RenderError::Other(format!("Unknown text content id {:?}", id)))?;
+            if !text.is_empty() {
+                parts.push(text);
+            }
+        }
+        let id = self.allocate_id();
+        self.pages.insert(id, parts.join("\n\n"));
+        Ok(id)
+    }
+
+    fn set_outline_root(&mut self, _outline_root_id: ObjectId) {}
+
+    /// Writes the text of every page, separating pages with a form feed.
+    fn finish(self: Box<Self>, page_ids: Vec<ObjectId>) -> Result<W, RenderError> {
+        let mut renderer = *self;
+        let mut writer = renderer.writer.take().ok_or_else(|| {
+            RenderError::Other("Document was never started with begin_document".into())
+        })?;
+        let pages = renderer.finish_into_pages(page_ids)?;
+        writer.write_all(pages.join("\n\x0c").as_bytes())?;
+        Ok(writer)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petty_idf::{IRNode, InlineNode, NodeMetadata};
+    use petty_layout::{LayoutEngine, LayoutStore, SharedFontLibrary};
+    use petty_style::dimension::{Margins, PageSize};
+    use petty_style::stylesheet::{ElementStyle, PageLayout, Stylesheet};
+    use std::io::Cursor;
+
+    fn paragraph(text: &str) -> IRNode {
+        IRNode::Paragraph {
+            meta: NodeMetadata {
+                style_override: Some(ElementStyle {
+                    margin: Some(Margins {
+                        bottom: 10.0,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            children: vec![InlineNode::Text(text.to_string())],
+        }
+    }
+
+    #[test]
+    fn test_extracts_paragraphs_in_reading_order() {
+        let first = "The first paragraph is long enough to wrap across several lines \
+                     on this narrow page.";
+        let second = "A second paragraph follows it.";
+        let stylesheet = Stylesheet {
+            page_masters: HashMap::from([(
+                "master".to_string(),
+                PageLayout {
+                    size: PageSize::Custom {
+                        width: 200.0,
+                        height: 500.0,
+                    },
+                    margins: Some(Margins::all(10.0)),
+                    ..Default::default()
+                },
+            )]),
+            default_page_master_name: Some("master".to_string()),
+            ..Default::default()
+        };
+        let library = SharedFontLibrary::new();
+        library.load_fallback_font();
+        let engine = LayoutEngine::new(&library, Default::default());
+        let store = LayoutStore::new();
+        let root = engine
+            .build_render_tree(
+                &IRNode::Root(vec![paragraph(first), paragraph(second)]),
+                &store,
+            )
+            .unwrap();
+        let pages: Vec<_> = engine
+            .paginate(&stylesheet, root, &store)
+            .unwrap()
+            .map(|page| page.unwrap().elements)
+            .collect();
+
+        let mut renderer: Box<TextRenderer<Cursor<Vec<u8>>>> = Box::default();
+        renderer.begin_document(Cursor::new(Vec::new())).unwrap();
+        let mut page_ids = Vec::new();
+        for elements in pages {
+            let content = renderer
+                .render_page_content(elements, &HashMap::new(), 200.0, 500.0)
+                .unwrap();
+            page_ids.push(
+                renderer
+                    .write_page_object(vec![content], vec![], 200.0, 500.0)
+                    .unwrap(),
+            );
+        }
+        let text = String::from_utf8(renderer.finish(page_ids).unwrap().into_inner()).unwrap();
+
+        assert_eq!(text, format!("{first}\n\n{second}"));
+    }
+}
@@ -0,0 +1,128 @@
+//! A bounded channel data source, fed by a producer thread.
+
+use crate::DataSource;
+use serde_json::Value;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SendError, SyncSender, TrySendError};
+use std::time::Duration;
+
+/// How long `next` waits for the producer before giving up, unless configured.
+pub const DEFAULT_RECV_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A data source that receives items from a bounded channel.
+///
+/// Producers send records through a [`ChannelSender`]. Once the channel holds
+/// `capacity` unconsumed items, sending blocks until the pipeline catches up, so a
+/// fast producer cannot outrun generation. The stream ends when every sender has
+/// been dropped, or when no item arrives within the receive timeout.
+pub struct ChannelDataSource {
+    receiver: Receiver<Value>,
+    timeout: Duration,
+    timed_out: bool,
+}
+
+/// The sending half of a [`ChannelDataSource`]. Cloning it allows several producers.
+#[derive(Clone)]
+pub struct ChannelSender {
+    inner: SyncSender<Value>,
+}
+
+impl ChannelDataSource {
+    /// Create a channel buffering at most `capacity` items, returning the sender
+    /// and the data source.
+    pub fn bounded(capacity: usize) -> (ChannelSender, Self) {
+        let (inner, receiver) = mpsc::sync_channel(capacity);
+        let source = Self {
+            receiver,
+            timeout: DEFAULT_RECV_TIMEOUT,
+            timed_out: false,
+        };
+        (ChannelSender { inner }, source)
+    }
+
+    /// Set how long `next` waits for an item before ending the stream.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Whether the stream ended because the producer timed out, rather than
+    /// because every sender was dropped.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+}
+
+impl DataSource for ChannelDataSource {
+    fn next(&mut self) -> Option<Value> {
+        if self.timed_out {
+            return None;
+        }
+        match self.receiver.recv_timeout(self.timeout) {
+            Ok(item) => Some(item),
+            Err(RecvTimeoutError::Timeout) => {
+                self.timed_out = true;
+                None
+            }
+            Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+impl ChannelSender {
+    /// Send an item, blocking while the channel is full. Fails if the data source
+    /// has been dropped.
+    pub fn send(&self, item: Value) -> Result<(), SendError<Value>> {
+        self.inner.send(item)
+    }
+
+    /// Send an item without blocking. Fails if the channel is full or the data
+    /// source has been dropped.
+    pub fn try_send(&self, item: Value) -> Result<(), TrySendError<Value>> {
+        self.inner.try_send(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::thread;
+
+    #[test]
+    fn test_channel_source_drains_producer_in_order() {
+        let (sender, mut source) = ChannelDataSource::bounded(4);
+        let producer = thread::spawn(move || {
+            for id in 0..50 {
+                sender.send(json!({ "id": id })).unwrap();
+            }
+        });
+
+        let mut received = Vec::new();
+        while let Some(item) = source.next() {
+            received.push(item["id"].as_i64().unwrap());
+        }
+        producer.join().unwrap();
+
+        assert_eq!(received, (0..50).collect::<Vec<_>>());
+        assert!(!source.timed_out());
+    }
+
+    #[test]
+    fn test_channel_source_backpressure_and_timeout() {
+        let (sender, source) = ChannelDataSource::bounded(1);
+        let mut source = source.with_timeout(Duration::from_millis(20));
+
+        sender.try_send(json!(1)).unwrap();
+        // The channel is full until the consumer takes the first item.
+        assert!(matches!(
+            sender.try_send(json!(2)),
+            Err(TrySendError::Full(_))
+        ));
+        assert_eq!(source.next(), Some(json!(1)));
+
+        // The sender is still alive but idle, so the wait times out.
+        assert_eq!(source.next(), None);
+        assert!(source.timed_out());
+        drop(sender);
+    }
+}
@@ -0,0 +1,183 @@
+//! A data source that streams the rows of a CSV file.
+
+use crate::DataSource;
+use serde_json::{Map, Number, Value};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Options for reading CSV data.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// The field delimiter. Defaults to `,`.
+    pub delimiter: u8,
+    /// Whether to convert numeric and boolean fields into JSON numbers and booleans.
+    /// When disabled, every field is a string. Defaults to `true`.
+    pub infer_types: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            infer_types: true,
+        }
+    }
+}
+
+/// A data source that reads CSV with a header row, yielding each record as a JSON
+/// object keyed by the header names.
+///
+/// Records are read lazily, so the size is unknown up front. A malformed record
+/// ends the stream; the error is then available from [`CsvDataSource::error`].
+pub struct CsvDataSource {
+    reader: csv::Reader<Box<dyn Read + Send>>,
+    headers: Vec<String>,
+    infer_types: bool,
+    record: csv::StringRecord,
+    error: Option<csv::Error>,
+}
+
+impl CsvDataSource {
+    /// Create a data source reading comma-separated values with type inference.
+    pub fn new<R: Read + Send + 'static>(reader: R) -> Result<Self, csv::Error> {
+        Self::with_options(reader, CsvOptions::default())
+    }
+
+    /// Create a data source with the given options. The header row is read immediately.
+    pub fn with_options<R: Read + Send + 'static>(
+        reader: R,
+        options: CsvOptions,
+    ) -> Result<Self, csv::Error> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .from_reader(Box::new(reader) as Box<dyn Read + Send>);
+        let headers = reader.headers()?.iter().map(str::to_string).collect();
+        Ok(Self {
+            reader,
+            headers,
+            infer_types: options.infer_types,
+            record: csv::StringRecord::new(),
+            error: None,
+        })
+    }
+
+    /// Create a data source reading the CSV file at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P, options: CsvOptions) -> Result<Self, csv::Error> {
+        Self::with_options(File::open(path)?, options)
+    }
+
+    /// The header names, in column order.
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// The error that ended the stream early, if any.
+    pub fn error(&self) -> Option<&csv::Error> {
+        self.error.as_ref()
+    }
+
+    fn field_value(&self, field: &str) -> Value {
+        if self.infer_types {
+            infer_value(field)
+        } else {
+            Value::String(field.to_string())
+        }
+    }
+}
+
+impl DataSource for CsvDataSource {
+    fn next(&mut self) -> Option<Value> {
+        if self.error.is_some() {
+            return None;
+        }
+        match self.reader.read_record(&mut self.record) {
+            Ok(true) => {
+                let object: Map<String, Value> = self
+                    .headers
+                    .iter()
+                    .zip(self.record.iter())
+                    .map(|(header, field)| (header.clone(), self.field_value(field)))
+                    .collect();
+                Some(Value::Object(object))
+            }
+            Ok(false) => None,
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+/// Converts a field into a boolean or number where it unambiguously is one.
+/// Numbers with leading zeros, such as postal codes, are kept as strings.
+fn infer_value(field: &str) -> Value {
+    match field {
+        "true" | "TRUE" | "True" => return Value::Bool(true),
+        "false" | "FALSE" | "False" => return Value::Bool(false),
+        _ => {}
+    }
+    let digits = field.strip_prefix('-').unwrap_or(field);
+    let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+    if !leading_zero && digits.starts_with(|c: char| c.is_ascii_digit()) {
+        if let Ok(n) = field.parse::<i64>() {
+            return Value::Number(n.into());
+        }
+        if let Some(n) = field.parse::<f64>().ok().and_then(Number::from_f64) {
+            return Value::Number(n);
+        }
+    }
+    Value::String(field.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn source(csv: &str, options: CsvOptions) -> CsvDataSource {
+        CsvDataSource::with_options(Cursor::new(csv.to_string()), options).unwrap()
+    }
+
+    #[test]
+    fn test_csv_rows_with_quotes_and_inference() {
+        let csv = "name,qty,price,paid,zip\n\
+                   \"Smith, Jane\",3,9.50,true,01234\n\
+                   \"Say \"\"hi\"\"\",-2,1e3,no,\n";
+        let mut source = source(csv, CsvOptions::default());
+
+        assert_eq!(source.headers(), ["name", "qty", "price", "paid", "zip"]);
+        assert_eq!(source.size_hint(), None);
+        assert_eq!(
+            source.next(),
+            Some(
+                json!({"name": "Smith, Jane", "qty": 3, "price": 9.5, "paid": true, "zip": "01234"})
+            )
+        );
+        assert_eq!(
+            source.next(),
+            Some(
+                json!({"name": "Say \"hi\"", "qty": -2, "price": 1000.0, "paid": "no", "zip": ""})
+            )
+        );
+        assert_eq!(source.next(), None);
+        assert!(source.error().is_none());
+    }
+
+    #[test]
+    fn test_csv_delimiter_and_errors() {
+        let options = CsvOptions {
+            delimiter: b';',
+            infer_types: false,
+        };
+        let mut source = source("id;total\n7;12.5\n8\n9;1\n", options);
+
+        assert_eq!(source.next(), Some(json!({"id": "7", "total": "12.5"})));
+        // A record with the wrong number of fields ends the stream.
+        assert_eq!(source.next(), None);
+        assert!(source.error().is_some());
+        assert_eq!(source.next(), None);
+    }
+}
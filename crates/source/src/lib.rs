@@ -6,8 +6,11 @@
 //! ## Available Sources
 //!
 //! - `IteratorDataSource`: Wraps any iterator
+//! - `FnDataSource`: Wraps a closure, such as one reading a database cursor
 //! - `VecDataSource`: In-memory vector of data
-//! - `ChannelDataSource`: Async channel receiver (feature-gated)
+//! - `CsvDataSource`: Rows of a CSV file as JSON objects
+//! - `XmlDataSource`: Repeating elements of an XML document as JSON values
+//! - `ChannelDataSource`: Bounded channel fed by a producer thread
 //!
 //! ## Example
 //!
@@ -25,6 +28,14 @@
 
 use serde_json::Value;
 
+mod channel_source;
+mod csv_source;
+mod xml_source;
+
+pub use channel_source::{ChannelDataSource, ChannelSender, DEFAULT_RECV_TIMEOUT};
+pub use csv_source::{CsvDataSource, CsvOptions};
+pub use xml_source::XmlDataSource;
+
 /// A trait for data sources that feed items into the pipeline.
 ///
 /// This abstraction allows the pipeline to work with various data sources:
@@ -53,6 +64,36 @@ pub trait DataSource: Send {
     fn has_known_size(&self) -> bool {
         self.size_hint().is_some()
     }
+
+    /// Adapts the source into an `Iterator`, for consumers that take iterators,
+    /// such as the pipeline's `generate`.
+    fn into_iterator(self) -> DataSourceIter<Self>
+    where
+        Self: Sized,
+    {
+        DataSourceIter { source: self }
+    }
+}
+
+/// An `Iterator` over the items of a `DataSource`, created by
+/// [`DataSource::into_iterator`].
+pub struct DataSourceIter<S> {
+    source: S,
+}
+
+impl<S: DataSource> Iterator for DataSourceIter<S> {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        self.source.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.source.size_hint() {
+            Some(n) => (n, Some(n)),
+            None => (0, None),
+        }
+    }
 }
 
 /// Wraps any iterator as a DataSource.
@@ -93,6 +134,52 @@ where
     }
 }
 
+/// Wraps a closure as a DataSource.
+///
+/// The closure is called for each item until it returns `None`, which makes it easy
+/// to stream rows from a database cursor or any other pull-based reader without
+/// buffering them first.
+pub struct FnDataSource<F>
+where
+    F: FnMut() -> Option<Value> + Send,
+{
+    next_fn: F,
+    size_hint: Option<usize>,
+}
+
+impl<F> FnDataSource<F>
+where
+    F: FnMut() -> Option<Value> + Send,
+{
+    /// Create a new data source from a closure, with an unknown size.
+    pub fn new(next_fn: F) -> Self {
+        Self {
+            next_fn,
+            size_hint: None,
+        }
+    }
+
+    /// Set the total number of items the closure will produce, if known up front
+    /// (for example from a `COUNT(*)` query).
+    pub fn with_size_hint(mut self, size: usize) -> Self {
+        self.size_hint = Some(size);
+        self
+    }
+}
+
+impl<F> DataSource for FnDataSource<F>
+where
+    F: FnMut() -> Option<Value> + Send,
+{
+    fn next(&mut self) -> Option<Value> {
+        (self.next_fn)()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.size_hint
+    }
+}
+
 /// A data source backed by an in-memory vector.
 ///
 /// This is the simplest data source, useful for small datasets or testing.
@@ -190,6 +277,20 @@ mod tests {
         assert_eq!(source.next(), None);
     }
 
+    #[test]
+    fn test_fn_data_source() {
+        let mut rows = vec![json!({"id": 2}), json!({"id": 1})];
+        let mut source = FnDataSource::new(move || rows.pop());
+        assert_eq!(source.size_hint(), None);
+        assert_eq!(source.next(), Some(json!({"id": 1})));
+
+        let source = FnDataSource::new(|| None).with_size_hint(0);
+        assert!(source.has_known_size());
+        let mut iter = source.into_iterator();
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        assert_eq!(iter.next(), None);
+    }
+
     #[test]
     fn test_empty_vec_source() {
         let mut source = VecDataSource::new(vec![]);
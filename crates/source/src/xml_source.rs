@@ -0,0 +1,307 @@
+//! A data source that streams the repeating elements of an XML document.
+
+use crate::DataSource;
+use quick_xml::Reader;
+use quick_xml::encoding::Decoder;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesRef, BytesStart, Event};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// A data source that yields the children of an XML document's root element, one
+/// at a time, as JSON values.
+///
+/// Elements are converted following the conventions of the JSON virtual DOM used
+/// by the XSLT engine, so a feed converted here reads the same as the equivalent
+/// JSON input:
+///
+/// - Attributes become keys prefixed with `@`.
+/// - Child elements become keys named after the element. Repeated siblings with
+///   the same name are collected into an array.
+/// - An element whose only children are `<item>` elements becomes an array.
+/// - An element with text only becomes a string. Text alongside attributes or
+///   child elements is stored under `#text`.
+///
+/// The document is read incrementally, so the size is unknown up front. Malformed
+/// XML ends the stream; the error is then available from [`XmlDataSource::error`].
+pub struct XmlDataSource {
+    reader: Reader<BufReader<Box<dyn Read + Send>>>,
+    buf: Vec<u8>,
+    record_name: Option<String>,
+    in_document: bool,
+    finished: bool,
+    error: Option<quick_xml::Error>,
+}
+
+impl XmlDataSource {
+    /// Create a data source yielding every child element of the root element.
+    pub fn new<R: Read + Send + 'static>(reader: R) -> Self {
+        Self {
+            reader: Reader::from_reader(BufReader::new(Box::new(reader) as Box<dyn Read + Send>)),
+            buf: Vec::new(),
+            record_name: None,
+            in_document: false,
+            finished: false,
+            error: None,
+        }
+    }
+
+    /// Create a data source reading the XML file at `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(Self::new(File::open(path)?))
+    }
+
+    /// Only yield child elements with the given name, skipping any others.
+    pub fn with_record_name(mut self, name: impl Into<String>) -> Self {
+        self.record_name = Some(name.into());
+        self
+    }
+
+    /// The error that ended the stream early, if any.
+    pub fn error(&self) -> Option<&quick_xml::Error> {
+        self.error.as_ref()
+    }
+
+    fn read_record(&mut self) -> Result<Option<Value>, quick_xml::Error> {
+        let decoder = self.reader.decoder();
+        let mut open: Vec<ElementBuilder> = Vec::new();
+        loop {
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Start(e) => {
+                    if !self.in_document {
+                        self.in_document = true;
+                    } else if open.is_empty() && !is_record(&self.record_name, &e) {
+                        let name = e.name().as_ref().to_vec();
+                        self.reader
+                            .read_to_end_into(quick_xml::name::QName(&name), &mut Vec::new())?;
+                    } else {
+                        open.push(ElementBuilder::new(&e, decoder)?);
+                    }
+                }
+                Event::Empty(e) => {
+                    if !self.in_document {
+                        // An empty root element has no records.
+                        return Ok(None);
+                    }
+                    if open.is_empty() && !is_record(&self.record_name, &e) {
+                        continue;
+                    }
+                    let element = ElementBuilder::new(&e, decoder)?;
+                    if let Some(record) = close_element(&mut open, element) {
+                        return Ok(Some(record));
+                    }
+                }
+                Event::End(_) => match open.pop() {
+                    // The root element itself has closed.
+                    None => return Ok(None),
+                    Some(element) => {
+                        if let Some(record) = close_element(&mut open, element) {
+                            return Ok(Some(record));
+                        }
+                    }
+                },
+                Event::Text(text) => {
+                    if let Some(element) = open.last_mut() {
+                        element.text.push_str(&text.decode()?);
+                    }
+                }
+                Event::CData(text) => {
+                    if let Some(element) = open.last_mut() {
+                        element.text.push_str(&text.decode()?);
+                    }
+                }
+                Event::GeneralRef(reference) => {
+                    if let Some(element) = open.last_mut() {
+                        push_reference(&mut element.text, &reference)?;
+                    }
+                }
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl DataSource for XmlDataSource {
+    fn next(&mut self) -> Option<Value> {
+        if self.finished {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => Some(record),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.error = Some(e);
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
+
+fn is_record(record_name: &Option<String>, element: &BytesStart) -> bool {
+    record_name
+        .as_deref()
+        .is_none_or(|name| name.as_bytes() == element.name().as_ref())
+}
+
+/// Finishes `element`, adding it to its parent. Returns the value if it was a record.
+fn close_element(open: &mut [ElementBuilder], element: ElementBuilder) -> Option<Value> {
+    let (name, value) = element.finish();
+    match open.last_mut() {
+        Some(parent) => {
+            parent.add_child(name, value);
+            None
+        }
+        None => Some(value),
+    }
+}
+
+/// Appends the text of a character or predefined entity reference.
+fn push_reference(text: &mut String, reference: &BytesRef) -> Result<(), quick_xml::Error> {
+    if let Some(c) = reference.resolve_char_ref()? {
+        text.push(c);
+        return Ok(());
+    }
+    let name = reference.decode()?;
+    match resolve_predefined_entity(&name) {
+        Some(resolved) => text.push_str(resolved),
+        None => {
+            text.push('&');
+            text.push_str(&name);
+            text.push(';');
+        }
+    }
+    Ok(())
+}
+
+struct ElementBuilder {
+    name: String,
+    fields: Map<String, Value>,
+    /// Child names that occurred more than once and are held as arrays.
+    repeated: HashSet<String>,
+    has_children: bool,
+    text: String,
+}
+
+impl ElementBuilder {
+    fn new(start: &BytesStart, decoder: Decoder) -> Result<Self, quick_xml::Error> {
+        let mut fields = Map::new();
+        for attr in start.attributes() {
+            let attr = attr?;
+            let key = format!("@{}", decoder.decode(attr.key.as_ref())?);
+            let value = attr.decode_and_unescape_value(decoder)?.into_owned();
+            fields.insert(key, Value::String(value));
+        }
+        Ok(Self {
+            name: decoder.decode(start.name().as_ref())?.into_owned(),
+            fields,
+            repeated: HashSet::new(),
+            has_children: false,
+            text: String::new(),
+        })
+    }
+
+    fn add_child(&mut self, name: String, value: Value) {
+        self.has_children = true;
+        if self.repeated.contains(&name) {
+            if let Some(Value::Array(items)) = self.fields.get_mut(&name) {
+                items.push(value);
+            }
+        } else if let Some(first) = self.fields.remove(&name) {
+            self.fields
+                .insert(name.clone(), Value::Array(vec![first, value]));
+            self.repeated.insert(name);
+        } else {
+            self.fields.insert(name, value);
+        }
+    }
+
+    fn finish(mut self) -> (String, Value) {
+        let value = if self.fields.is_empty() {
+            Value::String(self.text)
+        } else if self.fields.len() == 1 && self.has_children && self.fields.contains_key("item") {
+            match self.fields.remove("item") {
+                Some(Value::Array(items)) if self.repeated.contains("item") => Value::Array(items),
+                Some(item) => Value::Array(vec![item]),
+                None => Value::Null,
+            }
+        } else {
+            let text = self.text.trim();
+            if !text.is_empty() {
+                self.fields
+                    .insert("#text".to_string(), Value::String(text.to_string()));
+            }
+            Value::Object(self.fields)
+        };
+        (self.name, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn source(xml: &str) -> XmlDataSource {
+        XmlDataSource::new(Cursor::new(xml.to_string()))
+    }
+
+    #[test]
+    fn test_xml_records_follow_vdom_conventions() {
+        let xml = r#"<?xml version="1.0"?>
+            <!-- exported feed -->
+            <records>
+                <record id="1">
+                    <name>Smith &amp; Sons</name>
+                    <tags><item>new</item><item>vip</item></tags>
+                    <line>A</line>
+                    <line>B</line>
+                </record>
+                <summary>skipped</summary>
+                <record id="2"><name><![CDATA[<Jones>]]></name><note lang="en">Late</note></record>
+                <record id="3"/>
+            </records>"#;
+        let mut source = source(xml).with_record_name("record");
+
+        assert_eq!(source.size_hint(), None);
+        assert_eq!(
+            source.next(),
+            Some(json!({
+                "@id": "1",
+                "name": "Smith & Sons",
+                "tags": ["new", "vip"],
+                "line": ["A", "B"]
+            }))
+        );
+        assert_eq!(
+            source.next(),
+            Some(json!({
+                "@id": "2",
+                "name": "<Jones>",
+                "note": {"@lang": "en", "#text": "Late"}
+            }))
+        );
+        assert_eq!(source.next(), Some(json!({"@id": "3"})));
+        assert_eq!(source.next(), None);
+        assert!(source.error().is_none());
+    }
+
+    #[test]
+    fn test_xml_errors_end_the_stream() {
+        let mut source = source("<rows><row>1</row><row>2</wrong></rows>");
+
+        assert_eq!(source.next(), Some(json!("1")));
+        assert_eq!(source.next(), None);
+        assert!(source.error().is_some());
+        assert_eq!(source.next(), None);
+    }
+}
@@ -28,6 +28,29 @@ impl FromStr for BorderStyle {
     }
 }
 
+/// How the borders of adjacent table cells are drawn.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum BorderCollapse {
+    /// Each cell draws its own borders, with `border-spacing` between cells.
+    #[default]
+    Separate,
+    /// Adjacent cells share a single border; the wider of the two is kept.
+    Collapse,
+}
+
+impl FromStr for BorderCollapse {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "separate" => Ok(BorderCollapse::Separate),
+            "collapse" => Ok(BorderCollapse::Collapse),
+            _ => Err(format!("Invalid border-collapse value: '{}'", s)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Border {
     pub width: f32,
@@ -27,7 +27,9 @@ pub enum FlexWrap {
 #[derive(Default)]
 pub enum JustifyContent {
     #[default]
+    #[serde(alias = "start")]
     FlexStart,
+    #[serde(alias = "end")]
     FlexEnd,
     Center,
     SpaceBetween,
@@ -42,7 +44,9 @@ pub enum JustifyContent {
 pub enum AlignItems {
     #[default]
     Stretch,
+    #[serde(alias = "start")]
     FlexStart,
+    #[serde(alias = "end")]
     FlexEnd,
     Center,
     Baseline,
@@ -56,7 +60,9 @@ pub enum AlignSelf {
     #[default]
     Auto,
     Stretch,
+    #[serde(alias = "start")]
     FlexStart,
+    #[serde(alias = "end")]
     FlexEnd,
     Center,
     Baseline,
@@ -77,3 +77,15 @@ pub enum FontStyle {
     Italic,
     Oblique,
 }
+
+/// Where the metrics and glyphs of a font come from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum FontSource {
+    /// A font file loaded into the font library.
+    #[default]
+    Embedded,
+    /// One of the PDF standard fonts (Helvetica, Times or Courier), referenced by
+    /// name and measured with its built-in AFM metrics. No font file is needed.
+    Standard,
+}
@@ -4,14 +4,18 @@ pub mod flex;
 pub mod font;
 pub mod list;
 pub mod parsers;
+pub mod selector;
 pub mod stylesheet;
 pub mod text;
 
 pub use border::{Border, BorderStyle};
 pub use dimension::{Dimension, Margins, PageSize};
 pub use flex::{AlignItems, AlignSelf, FlexDirection, FlexWrap, JustifyContent};
-pub use font::{FontStyle, FontWeight};
+pub use font::{FontSource, FontStyle, FontWeight};
 pub use list::{ListStylePosition, ListStyleType};
 pub use parsers::StyleParseError;
-pub use stylesheet::{ElementStyle, PageLayout, Stylesheet};
-pub use text::{TextAlign, TextDecoration};
+pub use selector::{Selector, SelectorSubject, StyleRule};
+pub use stylesheet::{
+    ElementStyle, PageLayout, PageNumberStyle, PageNumbering, StyleKeyword, Stylesheet,
+};
+pub use text::{TextAlign, TextDecoration, TextOverflow, WhiteSpace};
@@ -3,12 +3,12 @@
 //! This module provides composable parser functions for parsing style values
 //! like lengths, dimensions, colors, and borders.
 
-use crate::border::{Border, BorderStyle};
+use crate::border::{Border, BorderCollapse, BorderStyle};
 use crate::dimension::{Dimension, Margins, PageSize};
 use crate::flex::{AlignItems, AlignSelf, FlexDirection, FlexWrap, JustifyContent};
-use crate::font::{FontStyle, FontWeight};
+use crate::font::{FontSource, FontStyle, FontWeight};
 use crate::list::ListStyleType;
-use crate::text::TextAlign;
+use crate::text::{TextAlign, TextOverflow, WhiteSpace};
 use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case, take_while_m_n};
 use nom::character::complete::{char, space0, space1};
@@ -85,10 +85,12 @@ pub fn parse_length(input: &str) -> IResult<&str, f32> {
     Ok((input, value * unit_multiplier.unwrap_or(1.0)))
 }
 
-/// Parses a dimension value (length, percentage, or "auto").
+/// Parses a dimension value (length, percentage, or "auto"). "fit-content" is accepted
+/// as an alias of "auto"; tables given either shrink to their content width.
 pub fn parse_dimension(input: &str) -> IResult<&str, Dimension> {
     alt((
         map(tag("auto"), |_| Dimension::Auto),
+        map(tag("fit-content"), |_| Dimension::Auto),
         map(pair(parse_f32, char('%')), |(val, _)| {
             Dimension::Percent(val)
         }),
@@ -255,6 +257,144 @@ pub fn parse_font_style(s: &str) -> Result<FontStyle, StyleParseError> {
     }
 }
 
+/// Parses a font-source value.
+pub fn parse_font_source(s: &str) -> Result<FontSource, StyleParseError> {
+    match s.to_lowercase().as_str() {
+        "embedded" => Ok(FontSource::Embedded),
+        "standard" => Ok(FontSource::Standard),
+        _ => Err(StyleParseError::InvalidValue {
+            property: "font-source".to_string(),
+            value: s.to_string(),
+        }),
+    }
+}
+
+/// Parses a keep-together value ("always"/"true" or "auto"/"false").
+pub fn parse_keep_together(s: &str) -> Result<bool, StyleParseError> {
+    match s.trim().to_lowercase().as_str() {
+        "always" | "true" => Ok(true),
+        "auto" | "false" => Ok(false),
+        _ => Err(StyleParseError::InvalidValue {
+            property: "keep-together".to_string(),
+            value: s.to_string(),
+        }),
+    }
+}
+
+/// Parses a border-collapse value.
+pub fn parse_border_collapse(s: &str) -> Result<BorderCollapse, StyleParseError> {
+    s.parse().map_err(|_| StyleParseError::InvalidValue {
+        property: "border-collapse".to_string(),
+        value: s.to_string(),
+    })
+}
+
+/// Parses a white-space value.
+pub fn parse_white_space(s: &str) -> Result<WhiteSpace, StyleParseError> {
+    match s.to_lowercase().as_str() {
+        "normal" => Ok(WhiteSpace::Normal),
+        "nowrap" => Ok(WhiteSpace::Nowrap),
+        _ => Err(StyleParseError::InvalidValue {
+            property: "white-space".to_string(),
+            value: s.to_string(),
+        }),
+    }
+}
+
+/// Parses a text-overflow value.
+pub fn parse_text_overflow(s: &str) -> Result<TextOverflow, StyleParseError> {
+    match s.to_lowercase().as_str() {
+        "clip" => Ok(TextOverflow::Clip),
+        "ellipsis" => Ok(TextOverflow::Ellipsis),
+        _ => Err(StyleParseError::InvalidValue {
+            property: "text-overflow".to_string(),
+            value: s.to_string(),
+        }),
+    }
+}
+
+/// Parses a max-lines value, a positive whole number of lines.
+pub fn parse_max_lines(s: &str) -> Result<usize, StyleParseError> {
+    match s.trim().parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(StyleParseError::InvalidValue {
+            property: "max-lines".to_string(),
+            value: s.to_string(),
+        }),
+    }
+}
+
+/// Parses the `flex` shorthand into `(grow, shrink, basis)`. Follows CSS: `none` is
+/// `0 0 auto`, `auto` is `1 1 auto`, unitless numbers are the grow then shrink factors,
+/// and a lone grow factor gets a basis of zero so siblings share space by ratio alone.
+pub fn parse_flex(s: &str) -> Result<(f32, f32, Dimension), StyleParseError> {
+    let invalid = || StyleParseError::InvalidValue {
+        property: "flex".to_string(),
+        value: s.to_string(),
+    };
+    match s.trim() {
+        "none" => return Ok((0.0, 0.0, Dimension::Auto)),
+        "auto" => return Ok((1.0, 1.0, Dimension::Auto)),
+        _ => {}
+    }
+
+    let mut factors = Vec::new();
+    let mut basis = None;
+    for token in s.split_whitespace() {
+        if let Ok(factor) = token.parse::<f32>() {
+            if factors.len() == 2 || basis.is_some() {
+                return Err(invalid());
+            }
+            factors.push(factor);
+        } else if basis.is_none() {
+            basis = Some(run_parser(parse_dimension, token).map_err(|_| invalid())?);
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    match (factors.as_slice(), basis) {
+        ([], None) => Err(invalid()),
+        ([], Some(basis)) => Ok((1.0, 1.0, basis)),
+        ([grow], basis) => Ok((*grow, 1.0, basis.unwrap_or(Dimension::Pt(0.0)))),
+        ([grow, shrink], basis) => Ok((*grow, *shrink, basis.unwrap_or(Dimension::Pt(0.0)))),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parses an aspect-ratio value, either a ratio such as `16 / 9` or a single number,
+/// into a positive width-to-height ratio.
+pub fn parse_aspect_ratio(s: &str) -> Result<f32, StyleParseError> {
+    let number = |part: &str| part.trim().parse::<f32>().ok().filter(|n| *n > 0.0);
+    let ratio = match s.split_once('/') {
+        Some((width, height)) => number(width).zip(number(height)).map(|(w, h)| w / h),
+        None => number(s),
+    };
+    ratio.ok_or_else(|| StyleParseError::InvalidValue {
+        property: "aspect-ratio".to_string(),
+        value: s.to_string(),
+    })
+}
+
+/// Parses the `gap` shorthand into `(row_gap, column_gap)`. A single length sets both.
+pub fn parse_gap(s: &str) -> Result<(f32, f32), StyleParseError> {
+    let parts: Vec<&str> = s.split_whitespace().collect();
+    match parts.as_slice() {
+        [both] => {
+            let gap = run_parser(parse_length, both)?;
+            Ok((gap, gap))
+        }
+        [row, column] => Ok((
+            run_parser(parse_length, row)?,
+            run_parser(parse_length, column)?,
+        )),
+        _ => Err(StyleParseError::InvalidValue {
+            property: "gap".to_string(),
+            value: s.to_string(),
+        }),
+    }
+}
+
 /// Parses a text-align value.
 pub fn parse_text_align(s: &str) -> Result<TextAlign, StyleParseError> {
     match s.to_lowercase().as_str() {
@@ -314,8 +454,8 @@ pub fn parse_flex_wrap(s: &str) -> Result<FlexWrap, StyleParseError> {
 /// Parses a justify-content value.
 pub fn parse_justify_content(s: &str) -> Result<JustifyContent, StyleParseError> {
     match s.to_lowercase().as_str() {
-        "flex-start" => Ok(JustifyContent::FlexStart),
-        "flex-end" => Ok(JustifyContent::FlexEnd),
+        "flex-start" | "start" => Ok(JustifyContent::FlexStart),
+        "flex-end" | "end" => Ok(JustifyContent::FlexEnd),
         "center" => Ok(JustifyContent::Center),
         "space-between" => Ok(JustifyContent::SpaceBetween),
         "space-around" => Ok(JustifyContent::SpaceAround),
@@ -331,8 +471,8 @@ pub fn parse_justify_content(s: &str) -> Result<JustifyContent, StyleParseError>
 pub fn parse_align_items(s: &str) -> Result<AlignItems, StyleParseError> {
     match s.to_lowercase().as_str() {
         "stretch" => Ok(AlignItems::Stretch),
-        "flex-start" => Ok(AlignItems::FlexStart),
-        "flex-end" => Ok(AlignItems::FlexEnd),
+        "flex-start" | "start" => Ok(AlignItems::FlexStart),
+        "flex-end" | "end" => Ok(AlignItems::FlexEnd),
         "center" => Ok(AlignItems::Center),
         "baseline" => Ok(AlignItems::Baseline),
         _ => Err(StyleParseError::InvalidValue {
@@ -347,8 +487,8 @@ pub fn parse_align_self(s: &str) -> Result<AlignSelf, StyleParseError> {
     match s.to_lowercase().as_str() {
         "auto" => Ok(AlignSelf::Auto),
         "stretch" => Ok(AlignSelf::Stretch),
-        "flex-start" => Ok(AlignSelf::FlexStart),
-        "flex-end" => Ok(AlignSelf::FlexEnd),
+        "flex-start" | "start" => Ok(AlignSelf::FlexStart),
+        "flex-end" | "end" => Ok(AlignSelf::FlexEnd),
         "center" => Ok(AlignSelf::Center),
         "baseline" => Ok(AlignSelf::Baseline),
         _ => Err(StyleParseError::InvalidValue {
@@ -373,7 +513,7 @@ pub fn parse_page_size(s: &str) -> Result<PageSize, StyleParseError> {
 
 // --- High-level Style Application Functions ---
 
-use crate::stylesheet::ElementStyle;
+use crate::stylesheet::{ElementStyle, StyleKeyword};
 
 /// Applies a single parsed style property to an `ElementStyle` struct.
 /// This is the central dispatcher for applying individual CSS-like properties.
@@ -382,13 +522,27 @@ pub fn apply_style_property(
     attr_name: &str,
     value: &str,
 ) -> Result<(), StyleParseError> {
+    if let Some(value) = value.trim_end().strip_suffix("!important") {
+        apply_style_property(style, attr_name, value)?;
+        style.mark_important(attr_name);
+        return Ok(());
+    }
+    if let Some(keyword) = StyleKeyword::parse(value)
+        && style.set_keyword(attr_name, keyword)
+    {
+        return Ok(());
+    }
     match attr_name {
         "font-family" => style.font_family = Some(value.to_string()),
         "font-size" => style.font_size = Some(run_parser(parse_length, value)?),
         "font-weight" => style.font_weight = Some(parse_font_weight(value)?),
         "font-style" => style.font_style = Some(parse_font_style(value)?),
+        "font-source" => style.font_source = Some(parse_font_source(value)?),
         "line-height" => style.line_height = Some(run_parser(parse_length, value)?),
         "text-align" => style.text_align = Some(parse_text_align(value)?),
+        "white-space" => style.white_space = Some(parse_white_space(value)?),
+        "text-overflow" => style.text_overflow = Some(parse_text_overflow(value)?),
+        "max-lines" => style.max_lines = Some(parse_max_lines(value)?),
         "color" => style.color = Some(run_parser(parse_color, value)?),
         "background-color" => style.background_color = Some(run_parser(parse_color, value)?),
         "border" => style.border = Some(run_parser(parse_border, value)?),
@@ -429,11 +583,22 @@ pub fn apply_style_property(
         }
         "width" => style.width = Some(run_parser(parse_dimension, value)?),
         "height" => style.height = Some(run_parser(parse_dimension, value)?),
+        "keep-together" => style.keep_together = Some(parse_keep_together(value)?),
         "list-style-type" => style.list_style_type = Some(parse_list_style_type(value)?),
+        "border-spacing" => style.border_spacing = Some(run_parser(parse_length, value)?),
+        "border-collapse" => style.border_collapse = Some(parse_border_collapse(value)?),
         "flex-direction" => style.flex_direction = Some(parse_flex_direction(value)?),
         "flex-wrap" => style.flex_wrap = Some(parse_flex_wrap(value)?),
         "justify-content" => style.justify_content = Some(parse_justify_content(value)?),
         "align-items" => style.align_items = Some(parse_align_items(value)?),
+        "aspect-ratio" => style.aspect_ratio = Some(parse_aspect_ratio(value)?),
+        "gap" => {
+            let (row, column) = parse_gap(value)?;
+            style.row_gap = Some(row);
+            style.column_gap = Some(column);
+        }
+        "row-gap" => style.row_gap = Some(run_parser(parse_length, value)?),
+        "column-gap" => style.column_gap = Some(run_parser(parse_length, value)?),
         "flex-grow" => {
             style.flex_grow = Some(value.trim().parse::<f32>().map_err(|_| {
                 StyleParseError::FloatParse(format!("Invalid flex-grow value: {}", value))
@@ -445,6 +610,12 @@ pub fn apply_style_property(
             })?)
         }
         "flex-basis" => style.flex_basis = Some(run_parser(parse_dimension, value)?),
+        "flex" => {
+            let (grow, shrink, basis) = parse_flex(value)?;
+            style.flex_grow = Some(grow);
+            style.flex_shrink = Some(shrink);
+            style.flex_basis = Some(basis);
+        }
         "align-self" => style.align_self = Some(parse_align_self(value)?),
         _ => {} // Not a style attribute, ignore.
     };
@@ -510,6 +681,40 @@ mod tests {
         assert!(run_parser(parse_dimension, "50p").is_err());
     }
 
+    #[test]
+    fn test_parse_flex() {
+        assert_eq!(parse_flex("2").unwrap(), (2.0, 1.0, Dimension::Pt(0.0)));
+        assert_eq!(parse_flex("1 0").unwrap(), (1.0, 0.0, Dimension::Pt(0.0)));
+        assert_eq!(
+            parse_flex("1 2 50%").unwrap(),
+            (1.0, 2.0, Dimension::Percent(50.0))
+        );
+        assert_eq!(
+            parse_flex("120pt").unwrap(),
+            (1.0, 1.0, Dimension::Pt(120.0))
+        );
+        assert_eq!(parse_flex("none").unwrap(), (0.0, 0.0, Dimension::Auto));
+        assert_eq!(parse_flex("auto").unwrap(), (1.0, 1.0, Dimension::Auto));
+        assert!(parse_flex("1 2 3").is_err());
+        assert!(parse_flex("wide").is_err());
+    }
+
+    #[test]
+    fn test_parse_aspect_ratio() {
+        assert_eq!(parse_aspect_ratio("16 / 9").unwrap(), 16.0 / 9.0);
+        assert_eq!(parse_aspect_ratio("4/3").unwrap(), 4.0 / 3.0);
+        assert_eq!(parse_aspect_ratio("1.5").unwrap(), 1.5);
+        assert!(parse_aspect_ratio("16 / 0").is_err());
+        assert!(parse_aspect_ratio("wide").is_err());
+    }
+
+    #[test]
+    fn test_parse_gap() {
+        assert_eq!(parse_gap("6pt").unwrap(), (6.0, 6.0));
+        assert_eq!(parse_gap("4pt 1in").unwrap(), (4.0, 72.0));
+        assert!(parse_gap("1pt 2pt 3pt").is_err());
+    }
+
     #[test]
     fn test_parse_shorthand_margins() {
         let m1 = parse_shorthand_margins("10pt").unwrap();
@@ -0,0 +1,154 @@
+//! Simple CSS-like selectors for stylesheet rules.
+//!
+//! Supported syntax is a subset of CSS: element kinds (`paragraph`), classes
+//! (`.note`), compounds of both (`block.note`) and descendant combinators
+//! (`block.note paragraph`).
+
+use crate::parsers::StyleParseError;
+use crate::stylesheet::ElementStyle;
+use std::sync::Arc;
+
+/// A parsed selector: a chain of compound selectors joined by descendant combinators.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    /// Compounds from outermost ancestor to the subject.
+    compounds: Vec<CompoundSelector>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CompoundSelector {
+    element: Option<String>,
+    classes: Vec<String>,
+}
+
+/// A node in the document tree, as seen by selector matching.
+#[derive(Debug, Clone, Copy)]
+pub struct SelectorSubject<'a> {
+    /// The node kind, e.g. `paragraph` or `list-item`.
+    pub element: &'a str,
+    pub classes: &'a [String],
+}
+
+/// A selector together with the style it applies.
+#[derive(Debug, Clone)]
+pub struct StyleRule {
+    pub selector: Selector,
+    pub style: Arc<ElementStyle>,
+}
+
+impl Selector {
+    pub fn parse(input: &str) -> Result<Self, StyleParseError> {
+        let compounds = input
+            .split_whitespace()
+            .map(CompoundSelector::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if compounds.is_empty() {
+            return Err(StyleParseError::Parse("Empty selector".to_string()));
+        }
+        Ok(Self { compounds })
+    }
+
+    /// The selector's specificity as `(classes, elements)`; higher wins.
+    pub fn specificity(&self) -> (usize, usize) {
+        self.compounds
+            .iter()
+            .fold((0, 0), |(classes, elements), c| {
+                (
+                    classes + c.classes.len(),
+                    elements + usize::from(c.element.is_some()),
+                )
+            })
+    }
+
+    /// Whether the selector matches the last node of `path`, given its ancestors
+    /// (outermost first) in the rest of `path`.
+    pub fn matches(&self, path: &[SelectorSubject]) -> bool {
+        let Some((subject, ancestors)) = path.split_last() else {
+            return false;
+        };
+        let Some((last, outer)) = self.compounds.split_last() else {
+            return false;
+        };
+        if !last.matches(subject) {
+            return false;
+        }
+        // With only descendant combinators, matching each compound against the
+        // nearest qualifying ancestor is sufficient.
+        let mut ancestors = ancestors.iter().rev();
+        outer
+            .iter()
+            .rev()
+            .all(|compound| ancestors.any(|node| compound.matches(node)))
+    }
+
+    /// Whether any compound of the selector requires `class`.
+    pub fn uses_class(&self, class: &str) -> bool {
+        self.compounds
+            .iter()
+            .any(|c| c.classes.iter().any(|own| own == class))
+    }
+}
+
+impl CompoundSelector {
+    fn parse(input: &str) -> Result<Self, StyleParseError> {
+        let mut parts = input.split('.');
+        let element = parts.next().filter(|e| !e.is_empty() && *e != "*");
+        let classes: Vec<String> = parts.map(str::to_string).collect();
+        let valid_name = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        };
+        if element.is_some_and(|e| !valid_name(e)) || !classes.iter().all(|c| valid_name(c)) {
+            return Err(StyleParseError::Parse(format!(
+                "Invalid selector '{}'",
+                input
+            )));
+        }
+        Ok(Self {
+            element: element.map(str::to_string),
+            classes,
+        })
+    }
+
+    fn matches(&self, node: &SelectorSubject) -> bool {
+        self.element.as_deref().is_none_or(|e| e == node.element)
+            && self
+                .classes
+                .iter()
+                .all(|class| node.classes.contains(class))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node<'a>(element: &'a str, classes: &'a [String]) -> SelectorSubject<'a> {
+        SelectorSubject { element, classes }
+    }
+
+    #[test]
+    fn test_selector_matching() {
+        let note = vec!["note".to_string()];
+        let none = vec![];
+        let paragraph_in_note = [node("block", &note), node("paragraph", &none)];
+        let plain_paragraph = [node("block", &none), node("paragraph", &none)];
+
+        let by_kind = Selector::parse("paragraph").unwrap();
+        assert!(by_kind.matches(&paragraph_in_note));
+        assert!(by_kind.matches(&plain_paragraph));
+
+        let descendant = Selector::parse(".note paragraph").unwrap();
+        assert!(descendant.matches(&paragraph_in_note));
+        assert!(!descendant.matches(&plain_paragraph));
+
+        let compound = Selector::parse("block.note").unwrap();
+        assert!(compound.matches(&paragraph_in_note[..1]));
+        assert!(!compound.matches(&plain_paragraph[..1]));
+
+        assert_eq!(descendant.specificity(), (1, 1));
+        assert!(Selector::parse("p > q").is_err());
+        assert!(Selector::parse("  ").is_err());
+    }
+}
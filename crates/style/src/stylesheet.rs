@@ -1,14 +1,15 @@
 //! Defines the top-level stylesheet structure that holds all styling information.
 
-use super::border::Border;
+use super::border::{Border, BorderCollapse};
 use super::dimension::{Dimension, Margins, PageSize};
 use super::flex::{AlignItems, AlignSelf, FlexDirection, FlexWrap, JustifyContent};
-use super::font::{FontStyle, FontWeight};
+use super::font::{FontSource, FontStyle, FontWeight};
 use super::list::{ListStylePosition, ListStyleType};
-use super::text::{TextAlign, TextDecoration};
+use super::selector::{SelectorSubject, StyleRule};
+use super::text::{TextAlign, TextDecoration, TextOverflow, WhiteSpace};
 use petty_types::Color;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Default)]
@@ -19,6 +20,8 @@ pub struct Stylesheet {
     pub default_page_master_name: Option<String>,
     /// A map of all named element styles.
     pub styles: HashMap<String, Arc<ElementStyle>>,
+    /// Selector-based rules, in source order.
+    pub rules: Vec<StyleRule>,
 }
 
 impl Stylesheet {
@@ -33,6 +36,10 @@ impl Stylesheet {
                 static FALLBACK_LAYOUT: PageLayout = PageLayout {
                     size: PageSize::A4,
                     margins: None,
+                    page_numbering: None,
+                    baseline_grid: None,
+                    bleed: None,
+                    crop_marks: false,
                 };
                 &FALLBACK_LAYOUT
             })
@@ -42,6 +49,65 @@ impl Stylesheet {
     pub fn get_style_by_class_name(&self, class_name: &str) -> Option<&Arc<ElementStyle>> {
         self.styles.get(class_name)
     }
+
+    /// Returns the styles of all rules matching the last node of `path`, ordered by
+    /// ascending specificity and then source order, so later entries take precedence.
+    pub fn matching_rule_styles(&self, path: &[SelectorSubject]) -> Vec<Arc<ElementStyle>> {
+        let mut matched: Vec<&StyleRule> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.selector.matches(path))
+            .collect();
+        matched.sort_by_key(|rule| rule.selector.specificity());
+        matched.into_iter().map(|rule| rule.style.clone()).collect()
+    }
+
+    /// Whether `class` is used by any selector rule.
+    pub fn uses_class(&self, class: &str) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.selector.uses_class(class))
+    }
+
+    /// Layers this stylesheet on top of a base `theme`. Styles defined in both are
+    /// merged property by property with this stylesheet's values winning; page masters
+    /// and the default master are only taken from the theme when not defined here.
+    /// Theme rules are kept, ordered before this stylesheet's rules.
+    pub fn apply_theme(&mut self, theme: &Stylesheet) {
+        for (name, theme_style) in &theme.styles {
+            let merged = match self.styles.get(name) {
+                Some(own) => {
+                    let mut merged = ElementStyle::clone(theme_style);
+                    merged.merge(own);
+                    Arc::new(merged)
+                }
+                None => Arc::clone(theme_style),
+            };
+            self.styles.insert(name.clone(), merged);
+        }
+        for (name, layout) in &theme.page_masters {
+            self.page_masters
+                .entry(name.clone())
+                .or_insert_with(|| layout.clone());
+        }
+        if self.default_page_master_name.is_none() {
+            self.default_page_master_name = theme.default_page_master_name.clone();
+        }
+        // Theme rules come first so that equally specific template rules win.
+        let own_rules = std::mem::take(&mut self.rules);
+        self.rules = theme.rules.iter().cloned().chain(own_rules).collect();
+    }
+
+    /// Combines `other` into this stylesheet, with `other` taking precedence. This is
+    /// the mirror of [`apply_theme`](Self::apply_theme): shared styles are merged
+    /// property by property with `other`'s values winning, `other`'s page masters and
+    /// default master replace ours, and its rules are ordered after ours. Merging a
+    /// chain of stylesheets in order therefore lets each one override the last.
+    pub fn merge(&mut self, other: &Stylesheet) {
+        let mut merged = other.clone();
+        merged.apply_theme(self);
+        *self = merged;
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
@@ -52,6 +118,54 @@ pub struct PageLayout {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub margins: Option<Margins>,
+    /// How pages laid out with this master are labelled in a viewer's page indicator.
+    /// A run of consecutive pages using the same master forms one numbered section.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_numbering: Option<PageNumbering>,
+    /// The spacing, in points, of a grid that paragraph line baselines are pushed down
+    /// onto, measured from the top of the content area.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_grid: Option<f32>,
+    /// For print output: how far, in points, the page extends past its trim edges.
+    /// The MediaBox grows by this much on every side and the TrimBox marks the page.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bleed: Option<f32>,
+    /// Draw crop marks at the trim corners, in the bleed area.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub crop_marks: bool,
+}
+
+/// The page label style of a section, e.g. roman numerals for front matter.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PageNumbering {
+    #[serde(default)]
+    pub style: PageNumberStyle,
+    /// A label prefix, e.g. `A-` for `A-1, A-2, ...`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// The number of the section's first page. Defaults to 1.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PageNumberStyle {
+    #[default]
+    Decimal,
+    LowerRoman,
+    UpperRoman,
+    LowerAlpha,
+    UpperAlpha,
+    /// Only the prefix is shown.
+    None,
 }
 
 #[derive(Deserialize, Serialize, Default, Clone, PartialEq, Debug)]
@@ -66,6 +180,10 @@ pub struct ElementStyle {
     pub font_weight: Option<FontWeight>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub font_style: Option<FontStyle>,
+    /// Set to `standard` to use the PDF standard font of the family instead of a
+    /// loaded font file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_source: Option<FontSource>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line_height: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -75,6 +193,13 @@ pub struct ElementStyle {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text_decoration: Option<TextDecoration>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub white_space: Option<WhiteSpace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_overflow: Option<TextOverflow>,
+    /// Clamps a paragraph to this many lines, ending the last one with an ellipsis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub widows: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub orphans: Option<usize>,
@@ -100,6 +225,9 @@ pub struct ElementStyle {
     pub width: Option<Dimension>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<Dimension>,
+    /// Moves the whole block to the next page instead of splitting it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_together: Option<bool>,
 
     // List Properties
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -112,6 +240,8 @@ pub struct ElementStyle {
     // Table Properties
     #[serde(skip_serializing_if = "Option::is_none")]
     pub border_spacing: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_collapse: Option<BorderCollapse>,
 
     // Flexbox Container
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -123,6 +253,19 @@ pub struct ElementStyle {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub align_items: Option<AlignItems>,
 
+    /// Width divided by height, used to derive whichever dimension is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<f32>,
+
+    // Container Spacing
+    /// Space between stacked children of a block, or between the lines of a wrapping
+    /// flex container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_gap: Option<f32>,
+    /// Space between adjacent items on a flex line.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column_gap: Option<f32>,
+
     // Flexbox Item
     #[serde(skip_serializing_if = "Option::is_none")]
     pub order: Option<i32>,
@@ -134,4 +277,370 @@ pub struct ElementStyle {
     pub flex_basis: Option<Dimension>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub align_self: Option<AlignSelf>,
+
+    /// Properties set to `inherit` or `initial`, keyed by their kebab-case name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keywords: BTreeMap<String, StyleKeyword>,
+    /// Kebab-case names of the properties declared `!important`.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub important: BTreeSet<String>,
+}
+
+/// A CSS-wide keyword that controls how a property is resolved against the parent.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StyleKeyword {
+    /// Take the parent's computed value, even for properties that are not inherited.
+    Inherit,
+    /// Reset to the default value, even for properties that are normally inherited.
+    Initial,
+}
+
+impl StyleKeyword {
+    /// Parses `inherit` or `initial`, ignoring case and surrounding whitespace.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "inherit" => Some(Self::Inherit),
+            "initial" => Some(Self::Initial),
+            _ => None,
+        }
+    }
+}
+
+impl ElementStyle {
+    /// Merges the properties set in `to_apply` into this style, overriding existing values.
+    pub fn merge(&mut self, to_apply: &ElementStyle) {
+        if to_apply.font_family.is_some() {
+            self.font_family = to_apply.font_family.clone();
+        }
+        if to_apply.font_size.is_some() {
+            self.font_size = to_apply.font_size;
+        }
+        if to_apply.font_weight.is_some() {
+            self.font_weight = to_apply.font_weight.clone();
+        }
+        if to_apply.font_style.is_some() {
+            self.font_style = to_apply.font_style.clone();
+        }
+        if to_apply.font_source.is_some() {
+            self.font_source = to_apply.font_source;
+        }
+        if to_apply.line_height.is_some() {
+            self.line_height = to_apply.line_height;
+        }
+        if to_apply.text_align.is_some() {
+            self.text_align = to_apply.text_align.clone();
+        }
+        if to_apply.color.is_some() {
+            self.color = to_apply.color.clone();
+        }
+        if to_apply.text_decoration.is_some() {
+            self.text_decoration = to_apply.text_decoration.clone();
+        }
+        if to_apply.white_space.is_some() {
+            self.white_space = to_apply.white_space;
+        }
+        if to_apply.text_overflow.is_some() {
+            self.text_overflow = to_apply.text_overflow;
+        }
+        if to_apply.max_lines.is_some() {
+            self.max_lines = to_apply.max_lines;
+        }
+        if to_apply.widows.is_some() {
+            self.widows = to_apply.widows;
+        }
+        if to_apply.orphans.is_some() {
+            self.orphans = to_apply.orphans;
+        }
+        if to_apply.background_color.is_some() {
+            self.background_color = to_apply.background_color.clone();
+        }
+        if to_apply.border.is_some() {
+            self.border = to_apply.border.clone();
+        }
+        if to_apply.border_top.is_some() {
+            self.border_top = to_apply.border_top.clone();
+        }
+        if to_apply.border_right.is_some() {
+            self.border_right = to_apply.border_right.clone();
+        }
+        if to_apply.border_bottom.is_some() {
+            self.border_bottom = to_apply.border_bottom.clone();
+        }
+        if to_apply.border_left.is_some() {
+            self.border_left = to_apply.border_left.clone();
+        }
+        if to_apply.margin.is_some() {
+            self.margin = to_apply.margin.clone();
+        }
+        if to_apply.padding.is_some() {
+            self.padding = to_apply.padding.clone();
+        }
+        if to_apply.width.is_some() {
+            self.width = to_apply.width.clone();
+        }
+        if to_apply.height.is_some() {
+            self.height = to_apply.height.clone();
+        }
+        if to_apply.keep_together.is_some() {
+            self.keep_together = to_apply.keep_together;
+        }
+        if to_apply.list_style_type.is_some() {
+            self.list_style_type = to_apply.list_style_type.clone();
+        }
+        if to_apply.list_style_position.is_some() {
+            self.list_style_position = to_apply.list_style_position.clone();
+        }
+        if to_apply.list_style_image.is_some() {
+            self.list_style_image = to_apply.list_style_image.clone();
+        }
+        if to_apply.border_spacing.is_some() {
+            self.border_spacing = to_apply.border_spacing;
+        }
+        if to_apply.border_collapse.is_some() {
+            self.border_collapse = to_apply.border_collapse;
+        }
+        if to_apply.flex_direction.is_some() {
+            self.flex_direction = to_apply.flex_direction.clone();
+        }
+        if to_apply.flex_wrap.is_some() {
+            self.flex_wrap = to_apply.flex_wrap.clone();
+        }
+        if to_apply.justify_content.is_some() {
+            self.justify_content = to_apply.justify_content.clone();
+        }
+        if to_apply.align_items.is_some() {
+            self.align_items = to_apply.align_items.clone();
+        }
+        if to_apply.aspect_ratio.is_some() {
+            self.aspect_ratio = to_apply.aspect_ratio;
+        }
+        if to_apply.row_gap.is_some() {
+            self.row_gap = to_apply.row_gap;
+        }
+        if to_apply.column_gap.is_some() {
+            self.column_gap = to_apply.column_gap;
+        }
+        if to_apply.order.is_some() {
+            self.order = to_apply.order;
+        }
+        if to_apply.flex_grow.is_some() {
+            self.flex_grow = to_apply.flex_grow;
+        }
+        if to_apply.flex_shrink.is_some() {
+            self.flex_shrink = to_apply.flex_shrink;
+        }
+        if to_apply.flex_basis.is_some() {
+            self.flex_basis = to_apply.flex_basis.clone();
+        }
+        if to_apply.align_self.is_some() {
+            self.align_self = to_apply.align_self.clone();
+        }
+        // A value set by `to_apply` replaces a keyword from an earlier style, and vice versa.
+        self.keywords
+            .retain(|property, _| !to_apply.has_value(property));
+        for (property, keyword) in &to_apply.keywords {
+            self.set_keyword(property, *keyword);
+        }
+        self.important.retain(|property| {
+            !to_apply.has_value(property) && !to_apply.keywords.contains_key(property)
+        });
+        self.important.extend(to_apply.important.iter().cloned());
+    }
+
+    /// Sets `property` to a CSS-wide keyword, clearing any explicit value.
+    /// Returns `false` if the property does not support keywords.
+    pub fn set_keyword(&mut self, property: &str, keyword: StyleKeyword) -> bool {
+        if !self.clear_property(property) {
+            return false;
+        }
+        self.keywords.insert(property.to_string(), keyword);
+        true
+    }
+
+    /// Marks `property` as `!important`, so it wins over normal declarations during
+    /// the cascade regardless of where they come from.
+    pub fn mark_important(&mut self, property: &str) {
+        // Side-specific margins and padding are stored in the shorthand.
+        let property = match property {
+            p if p.starts_with("margin-") => "margin",
+            p if p.starts_with("padding-") => "padding",
+            p => p,
+        };
+        self.important.insert(property.to_string());
+    }
+
+    /// Returns a style holding only this style's `!important` declarations.
+    pub fn important_declarations(&self) -> ElementStyle {
+        let mut important = ElementStyle::default();
+        for property in &self.important {
+            match self.keywords.get(property) {
+                Some(keyword) => {
+                    important.set_keyword(property, *keyword);
+                }
+                None => important.copy_property(self, property),
+            }
+        }
+        important
+    }
+}
+
+/// Generates the by-name accessors for properties that support CSS-wide keywords
+/// and `!important`, keyed by their kebab-case name.
+macro_rules! named_properties {
+    ($($name:literal => $field:ident),* $(,)?) => {
+        impl ElementStyle {
+            /// Clears `property`'s value. Returns `false` for an unknown property.
+            fn clear_property(&mut self, property: &str) -> bool {
+                match property {
+                    $($name => self.$field = None,)*
+                    _ => return false,
+                }
+                true
+            }
+
+            /// Whether an explicit value is set for `property`.
+            fn has_value(&self, property: &str) -> bool {
+                match property {
+                    $($name => self.$field.is_some(),)*
+                    _ => false,
+                }
+            }
+
+            /// Copies `property`'s value from `from`.
+            fn copy_property(&mut self, from: &ElementStyle, property: &str) {
+                match property {
+                    $($name => self.$field = from.$field.clone(),)*
+                    _ => {}
+                }
+            }
+        }
+    };
+}
+
+named_properties! {
+    "font-family" => font_family,
+    "font-size" => font_size,
+    "font-weight" => font_weight,
+    "font-style" => font_style,
+    "font-source" => font_source,
+    "line-height" => line_height,
+    "text-align" => text_align,
+    "text-decoration" => text_decoration,
+    "white-space" => white_space,
+    "text-overflow" => text_overflow,
+    "max-lines" => max_lines,
+    "color" => color,
+    "widows" => widows,
+    "orphans" => orphans,
+    "background-color" => background_color,
+    "border" => border,
+    "border-top" => border_top,
+    "border-right" => border_right,
+    "border-bottom" => border_bottom,
+    "border-left" => border_left,
+    "margin" => margin,
+    "padding" => padding,
+    "width" => width,
+    "height" => height,
+    "keep-together" => keep_together,
+    "list-style-type" => list_style_type,
+    "list-style-position" => list_style_position,
+    "border-spacing" => border_spacing,
+    "border-collapse" => border_collapse,
+    "flex-direction" => flex_direction,
+    "flex-wrap" => flex_wrap,
+    "justify-content" => justify_content,
+    "align-items" => align_items,
+    "aspect-ratio" => aspect_ratio,
+    "row-gap" => row_gap,
+    "column-gap" => column_gap,
+    "order" => order,
+    "flex-grow" => flex_grow,
+    "flex-shrink" => flex_shrink,
+    "flex-basis" => flex_basis,
+    "align-self" => align_self,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::selector::Selector;
+
+    fn stylesheet(styles: &[(&str, ElementStyle)], masters: &[(&str, PageSize)]) -> Stylesheet {
+        Stylesheet {
+            styles: styles
+                .iter()
+                .map(|(name, style)| (name.to_string(), Arc::new(style.clone())))
+                .collect(),
+            page_masters: masters
+                .iter()
+                .map(|(name, size)| {
+                    (
+                        name.to_string(),
+                        PageLayout {
+                            size: size.clone(),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_later_stylesheet_wins() {
+        let mut base = stylesheet(
+            &[
+                (
+                    "heading",
+                    ElementStyle {
+                        font_size: Some(18.0),
+                        font_weight: Some(FontWeight::Bold),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "body",
+                    ElementStyle {
+                        font_size: Some(10.0),
+                        ..Default::default()
+                    },
+                ),
+            ],
+            &[("default", PageSize::A4)],
+        );
+        base.default_page_master_name = Some("default".to_string());
+        base.rules.push(StyleRule {
+            selector: Selector::parse("paragraph").unwrap(),
+            style: Arc::new(ElementStyle::default()),
+        });
+
+        let mut overrides = stylesheet(
+            &[(
+                "heading",
+                ElementStyle {
+                    font_size: Some(24.0),
+                    ..Default::default()
+                },
+            )],
+            &[("default", PageSize::Letter)],
+        );
+        overrides.rules.push(StyleRule {
+            selector: Selector::parse(".note").unwrap(),
+            style: Arc::new(ElementStyle::default()),
+        });
+
+        base.merge(&overrides);
+
+        let heading = &base.styles["heading"];
+        assert_eq!(heading.font_size, Some(24.0));
+        assert_eq!(heading.font_weight, Some(FontWeight::Bold));
+        assert_eq!(base.styles["body"].font_size, Some(10.0));
+        assert_eq!(base.page_masters["default"].size, PageSize::Letter);
+        assert_eq!(base.default_page_master_name.as_deref(), Some("default"));
+        assert!(!base.rules[0].selector.uses_class("note"));
+        assert!(base.rules[1].selector.uses_class("note"));
+    }
 }
@@ -20,3 +20,26 @@ pub enum TextDecoration {
     Underline,
     LineThrough,
 }
+
+/// Whether text wraps onto new lines when it reaches the edge of its box.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+#[derive(Default)]
+pub enum WhiteSpace {
+    #[default]
+    Normal,
+    /// Lines only break at explicit newlines.
+    Nowrap,
+}
+
+/// How a line that does not fit its box is shown when it cannot wrap.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+#[derive(Default)]
+pub enum TextOverflow {
+    /// The text runs past the edge of the box.
+    #[default]
+    Clip,
+    /// The text is cut at the edge of the box and ends with "…".
+    Ellipsis,
+}
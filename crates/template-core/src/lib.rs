@@ -18,6 +18,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
 use thiserror::Error;
 
 /// Errors that can occur during template processing
@@ -44,6 +45,49 @@ pub enum DataSourceFormat {
     Json,
 }
 
+/// The separators written by number-formatting functions, such as XSLT's
+/// `format-number`, when the template does not choose a decimal format itself.
+///
+/// Pictures are still written with `.` and `,`; the locale only changes the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberLocale {
+    pub decimal_separator: char,
+    pub grouping_separator: char,
+}
+
+impl NumberLocale {
+    pub const fn new(decimal_separator: char, grouping_separator: char) -> Self {
+        Self {
+            decimal_separator,
+            grouping_separator,
+        }
+    }
+
+    /// Looks up the separators for a language tag such as `de` or `de-CH`.
+    /// Returns `None` for languages that are not known.
+    pub fn from_language_tag(tag: &str) -> Option<Self> {
+        let tag = tag.to_ascii_lowercase().replace('_', "-");
+        if tag == "de-ch" || tag == "de-li" {
+            return Some(Self::new('.', '\u{2019}'));
+        }
+        let language = tag.split('-').next().unwrap_or_default();
+        match language {
+            "en" | "ja" | "ko" | "zh" | "he" | "th" | "ga" => Some(Self::new('.', ',')),
+            "de" | "nl" | "da" | "es" | "it" | "pt" | "id" | "tr" | "el" | "ro" | "hr" | "sl"
+            | "sr" => Some(Self::new(',', '.')),
+            "fr" | "nb" | "nn" | "no" | "sv" | "fi" | "cs" | "sk" | "pl" | "ru" | "uk" | "hu"
+            | "bg" | "lt" | "lv" | "et" => Some(Self::new(',', '\u{a0}')),
+            _ => None,
+        }
+    }
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        Self::new('.', ',')
+    }
+}
+
 /// Configuration for an execution run.
 #[derive(Debug, Clone, Default)]
 pub struct ExecutionConfig {
@@ -51,6 +95,20 @@ pub struct ExecutionConfig {
     pub format: DataSourceFormat,
     /// If true, enables strict compliance checks.
     pub strict: bool,
+    /// The default separators for formatted numbers.
+    pub number_locale: NumberLocale,
+    /// Keeps the keys of JSON objects in source order instead of sorting them,
+    /// so child elements are visited in document order as with an XML source.
+    pub preserve_key_order: bool,
+    /// Sandboxes templates: functions that read external resources, such as XPath's
+    /// `doc()`, `unparsed-text()` and `collection()`, are rejected.
+    pub safe_mode: bool,
+    /// The most instructions a template may execute before it is aborted, so a
+    /// runaway template cannot hang its worker. `None` sets no limit.
+    pub max_steps: Option<u64>,
+    /// The time that date and time functions, such as XPath's `current-dateTime()`,
+    /// report instead of the system clock, so output is reproducible.
+    pub current_time: Option<SystemTime>,
 }
 
 /// A struct to report features found in a single template fragment.
@@ -213,4 +271,17 @@ pub trait TemplateParser {
         template_source: &str,
         resource_base_path: PathBuf,
     ) -> Result<TemplateFeatures, TemplateError>;
+
+    /// Parses a template source string layered on top of a base `theme` stylesheet.
+    /// Styles defined by the template take precedence over the theme's.
+    fn parse_with_theme(
+        &self,
+        _template_source: &str,
+        _resource_base_path: PathBuf,
+        _theme: &Stylesheet,
+    ) -> Result<TemplateFeatures, TemplateError> {
+        Err(TemplateError::ConfigError(
+            "This template language does not support themes".to_string(),
+        ))
+    }
 }
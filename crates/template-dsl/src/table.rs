@@ -120,6 +120,7 @@ pub struct Table {
     columns: Vec<Column>,
     header_children: Vec<Box<dyn TemplateBuilder>>,
     body_children: Vec<Box<dyn TemplateBuilder>>,
+    footer_children: Vec<Box<dyn TemplateBuilder>>,
 }
 
 impl Table {
@@ -149,6 +150,12 @@ impl Table {
         self
     }
 
+    /// Adds a static row to the table's footer section, repeated on every page.
+    pub fn footer_row(mut self, row: Row) -> Self {
+        self.footer_children.push(Box::new(row));
+        self
+    }
+
     /// Adds a child to the table body. Can be a `Row`, or a control flow
     /// element like `Each` or `If` that generates rows.
     pub fn child(mut self, child: impl TemplateBuilder + 'static) -> Self {
@@ -161,17 +168,13 @@ impl_styled_widget!(Table);
 
 impl TemplateBuilder for Table {
     fn build(self: Box<Self>) -> TemplateNode {
-        let header = if self.header_children.is_empty() {
-            None
-        } else {
-            Some(JsonTableHeader {
-                rows: self
-                    .header_children
-                    .into_iter()
-                    .map(|r| r.build())
-                    .collect(),
+        let section = |children: Vec<Box<dyn TemplateBuilder>>| {
+            (!children.is_empty()).then(|| JsonTableHeader {
+                rows: children.into_iter().map(|r| r.build()).collect(),
             })
         };
+        let header = section(self.header_children);
+        let footer = section(self.footer_children);
 
         TemplateNode::Static(JsonNode::Table(JsonTable {
             id: self.id,
@@ -190,6 +193,7 @@ impl TemplateBuilder for Table {
             body: JsonTableBody {
                 rows: self.body_children.into_iter().map(|r| r.build()).collect(),
             },
+            footer,
         }))
     }
 }
@@ -6,4 +6,7 @@ pub use executor::{Executor, ExecutorError, SyncExecutor};
 pub use font::{
     FontDescriptor, FontError, FontProvider, FontQuery, InMemoryFontProvider, SharedFontData,
 };
-pub use resource::{InMemoryResourceProvider, ResourceError, ResourceProvider, SharedResourceData};
+pub use resource::{
+    InMemoryResourceProvider, ResourceBudget, ResourceError, ResourceLimits, ResourceProvider,
+    SharedResourceData, image_dimensions,
+};
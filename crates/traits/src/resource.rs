@@ -3,6 +3,7 @@
 //! This trait allows the engine to load resources (images, templates, etc.)
 //! without being tied to filesystem access.
 
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::sync::Arc;
 use thiserror::Error;
@@ -21,6 +22,9 @@ pub enum ResourceError {
 
     #[error("I/O error: {0}")]
     Io(String),
+
+    #[error("Resource '{path}' exceeds a limit: {message}")]
+    LimitExceeded { path: String, message: String },
 }
 
 impl From<std::io::Error> for ResourceError {
@@ -205,6 +209,116 @@ impl ResourceProvider for InMemoryResourceProvider {
     }
 }
 
+/// Caps on the resources a document may load, for rendering untrusted templates.
+///
+/// Every limit is off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// The most bytes the loaded resources may add up to.
+    pub max_total_bytes: Option<u64>,
+    /// The most resources that may be loaded.
+    pub max_count: Option<usize>,
+    /// The largest width and height, in pixels, an image may declare.
+    pub max_image_dimensions: Option<(u32, u32)>,
+}
+
+impl ResourceLimits {
+    /// Starts counting the resources one document loads against these limits.
+    pub fn budget(&self) -> ResourceBudget {
+        ResourceBudget {
+            limits: *self,
+            count: 0,
+            total_bytes: 0,
+            admitted: HashSet::new(),
+        }
+    }
+}
+
+/// The resources a document has loaded so far, checked against [`ResourceLimits`].
+#[derive(Debug)]
+pub struct ResourceBudget {
+    limits: ResourceLimits,
+    count: usize,
+    total_bytes: u64,
+    admitted: HashSet<String>,
+}
+
+impl ResourceBudget {
+    /// Counts `data`, loaded from `path`, against the limits. A path the document
+    /// has already loaded is only counted once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ResourceError::LimitExceeded` if the resource breaks a limit, in
+    /// which case it is not counted.
+    pub fn admit(&mut self, path: &str, data: &[u8]) -> Result<(), ResourceError> {
+        if self.admitted.contains(path) {
+            return Ok(());
+        }
+        let exceeded = |message: String| ResourceError::LimitExceeded {
+            path: path.to_string(),
+            message,
+        };
+
+        if let Some(max) = self.limits.max_count
+            && self.count >= max
+        {
+            return Err(exceeded(format!("more than {} resources", max)));
+        }
+        let total_bytes = self.total_bytes + data.len() as u64;
+        if let Some(max) = self.limits.max_total_bytes
+            && total_bytes > max
+        {
+            return Err(exceeded(format!(
+                "resources total {} bytes, more than {}",
+                total_bytes, max
+            )));
+        }
+        if let Some((max_width, max_height)) = self.limits.max_image_dimensions
+            && let Some((width, height)) = image_dimensions(data)
+            && (width > max_width || height > max_height)
+        {
+            return Err(exceeded(format!(
+                "image is {}x{} pixels, more than {}x{}",
+                width, height, max_width, max_height
+            )));
+        }
+
+        self.count += 1;
+        self.total_bytes = total_bytes;
+        self.admitted.insert(path.to_string());
+        Ok(())
+    }
+}
+
+/// The pixel size a PNG, GIF or JPEG image declares in its header, read without
+/// decoding the image. `None` for other data.
+pub fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let be_u16 = |at: usize| Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?));
+    let be_u32 = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+    let le_u16 = |at: usize| Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?));
+
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((be_u32(16)?, be_u32(20)?));
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some((le_u16(6)? as u32, le_u16(8)? as u32));
+    }
+    if data.starts_with(&[0xFF, 0xD8]) {
+        // Walk the segments up to the start-of-frame marker holding the size.
+        let mut at = 2;
+        while *data.get(at)? == 0xFF {
+            let marker = *data.get(at + 1)?;
+            let is_frame = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_frame {
+                return Some((be_u16(at + 7)? as u32, be_u16(at + 5)? as u32));
+            }
+            at += 2 + be_u16(at + 2)? as usize;
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -363,4 +477,42 @@ mod tests {
         assert!(matches!(resource_err, ResourceError::Io(_)));
         assert!(resource_err.to_string().contains("file not found"));
     }
+
+    #[test]
+    fn test_budget_rejects_image_larger_than_dimension_cap() {
+        let limits = ResourceLimits {
+            max_image_dimensions: Some((1000, 1000)),
+            ..Default::default()
+        };
+        let mut budget = limits.budget();
+
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend(640u32.to_be_bytes());
+        png.extend(480u32.to_be_bytes());
+        assert!(budget.admit("small.png", &png).is_ok());
+
+        png[16..20].copy_from_slice(&20_000u32.to_be_bytes());
+        let err = budget.admit("huge.png", &png).unwrap_err();
+        assert!(matches!(
+            &err,
+            ResourceError::LimitExceeded { path, .. } if path == "huge.png"
+        ));
+        assert!(err.to_string().contains("20000x480"));
+    }
+
+    #[test]
+    fn test_budget_counts_a_path_once() {
+        let limits = ResourceLimits {
+            max_count: Some(1),
+            ..Default::default()
+        };
+        let mut budget = limits.budget();
+
+        assert!(budget.admit("logo.png", b"logo").is_ok());
+        assert!(budget.admit("logo.png", b"logo").is_ok());
+        assert!(matches!(
+            budget.admit("other.png", b"other"),
+            Err(ResourceError::LimitExceeded { .. })
+        ));
+    }
 }
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// An entry in the table of contents.
 #[derive(Debug, Clone, Default)]
@@ -11,6 +11,53 @@ pub struct TocEntry {
     pub target_id: String,
 }
 
+/// What a caption describes, which selects its label and numbering sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CaptionKind {
+    #[default]
+    Figure,
+    Table,
+}
+
+impl CaptionKind {
+    /// The label that precedes the caption number, e.g. "Table" in "Table 1: ...".
+    pub fn label(self) -> &'static str {
+        match self {
+            CaptionKind::Figure => "Figure",
+            CaptionKind::Table => "Table",
+        }
+    }
+
+    /// Tables are conventionally captioned above and figures below.
+    pub fn default_position(self) -> CaptionPosition {
+        match self {
+            CaptionKind::Figure => CaptionPosition::Below,
+            CaptionKind::Table => CaptionPosition::Above,
+        }
+    }
+}
+
+/// Whether a caption is rendered above or below the content it describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CaptionPosition {
+    Above,
+    Below,
+}
+
+/// A numbered caption, collected for the list of figures or tables.
+#[derive(Debug, Clone)]
+pub struct CaptionEntry {
+    pub kind: CaptionKind,
+    /// The caption's number among captions of the same kind.
+    pub number: usize,
+    /// The caption text, without its label.
+    pub text: String,
+    /// The anchor ID to link to the captioned content.
+    pub target_id: String,
+}
+
 /// Represents an entry for a document index (for API/serialization).
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
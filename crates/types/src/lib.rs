@@ -4,6 +4,6 @@ pub mod geometry;
 pub mod ids;
 
 pub use color::Color;
-pub use document::{ApiIndexEntry, TocEntry};
+pub use document::{ApiIndexEntry, CaptionEntry, CaptionKind, CaptionPosition, TocEntry};
 pub use geometry::{BoxConstraints, Rect, Size};
 pub use ids::{AnchorId, IndexTerm, ResourceUri};
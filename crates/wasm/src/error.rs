@@ -92,6 +92,7 @@ impl From<PipelineError> for PettyError {
             PipelineError::Json(e) => (ErrorCode::Json, e.to_string()),
             PipelineError::TemplateExecution(e) => (ErrorCode::TemplateExecution, e.to_string()),
             PipelineError::Pdf(e) => (ErrorCode::Pdf, e.to_string()),
+            PipelineError::Resource(e) => (ErrorCode::Resource, e.to_string()),
             PipelineError::Other(msg) => (ErrorCode::Unknown, msg.clone()),
         };
 
@@ -68,6 +68,7 @@ impl WasmPipeline {
         let execution_config = petty_template_core::ExecutionConfig {
             format: petty_template_core::DataSourceFormat::Json,
             strict: false,
+            ..Default::default()
         };
 
         let ir_nodes = self
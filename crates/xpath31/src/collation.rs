@@ -0,0 +1,354 @@
+//! Collation registry for string comparison.
+//!
+//! XPath and XSLT compare strings using a *collation*, identified by URI. This
+//! module provides the built-in collations and a [`CollationRegistry`] that maps
+//! URIs to implementations, used by `fn:compare`, `fn:sort` and `xsl:sort`.
+//!
+//! # Built-in Collations
+//!
+//! - [`CODEPOINT_COLLATION`]: Unicode codepoint order (the default)
+//! - [`HTML_ASCII_CASE_INSENSITIVE_COLLATION`]: ASCII case-insensitive order
+//! - [`UCA_COLLATION`]: A simple locale-aware collation. Accents and case are
+//!   only significant when base letters are equal. A `lang` query parameter
+//!   (e.g. `?lang=nb`) selects language tailorings for letters that sort after `z`.
+
+use crate::error::XPath31Error;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+pub const CODEPOINT_COLLATION: &str = "http://www.w3.org/2005/xpath-functions/collation/codepoint";
+pub const HTML_ASCII_CASE_INSENSITIVE_COLLATION: &str =
+    "http://www.w3.org/2005/xpath-functions/collation/html-ascii-case-insensitive";
+pub const UCA_COLLATION: &str = "http://www.w3.org/2013/collation/UCA";
+
+/// A string ordering identified by a collation URI.
+pub trait Collation: Send + Sync + fmt::Debug {
+    fn compare(&self, a: &str, b: &str) -> Ordering;
+
+    /// Returns a key whose codepoint order matches [`Collation::compare`].
+    fn collation_key(&self, s: &str) -> String;
+
+    fn equals(&self, a: &str, b: &str) -> bool {
+        self.compare(a, b) == Ordering::Equal
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CodepointCollation;
+
+impl Collation for CodepointCollation {
+    fn compare(&self, a: &str, b: &str) -> Ordering {
+        a.cmp(b)
+    }
+
+    fn collation_key(&self, s: &str) -> String {
+        s.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsciiCaseInsensitiveCollation;
+
+impl Collation for AsciiCaseInsensitiveCollation {
+    fn compare(&self, a: &str, b: &str) -> Ordering {
+        a.chars()
+            .map(|c| c.to_ascii_lowercase())
+            .cmp(b.chars().map(|c| c.to_ascii_lowercase()))
+    }
+
+    fn collation_key(&self, s: &str) -> String {
+        s.to_ascii_lowercase()
+    }
+}
+
+/// A three-level collation: base letters, then accents, then case.
+///
+/// This is not a full Unicode Collation Algorithm implementation, but it sorts
+/// accented Latin text the way readers expect (`é` next to `e`, not after `z`).
+#[derive(Debug, Clone, Default)]
+pub struct LocaleCollation {
+    lang: Option<String>,
+    /// Letters sorted after `z`, in order, for the selected language.
+    tailored: &'static [char],
+}
+
+impl LocaleCollation {
+    pub fn new(lang: Option<&str>) -> Self {
+        let lang = lang.map(|l| l.to_ascii_lowercase());
+        let primary_tag = lang
+            .as_deref()
+            .map(|l| l.split(['-', '_']).next().unwrap_or(l));
+        let tailored: &'static [char] = match primary_tag {
+            Some("nb" | "nn" | "no" | "da") => &['æ', 'ø', 'å'],
+            Some("sv" | "fi") => &['å', 'ä', 'ö'],
+            _ => &[],
+        };
+        Self { lang, tailored }
+    }
+
+    pub fn lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+
+    fn primary_weights(&self, s: &str) -> String {
+        let mut out = String::with_capacity(s.len());
+        for c in s.chars() {
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            if let Some(pos) = self.tailored.iter().position(|t| *t == lower) {
+                // Private-use codepoints sort after every Latin letter.
+                out.push(char::from_u32(0xE000 + pos as u32).unwrap_or(lower));
+                continue;
+            }
+            for d in lower.to_string().nfd() {
+                if !is_combining_mark(d) {
+                    out.extend(d.to_lowercase());
+                }
+            }
+        }
+        out
+    }
+
+    fn secondary_weights(&self, s: &str) -> String {
+        s.nfd().flat_map(char::to_lowercase).collect()
+    }
+
+    fn tertiary_weights(&self, s: &str) -> String {
+        // Lowercase sorts before uppercase, as in the UCA default.
+        s.chars()
+            .map(|c| if c.is_uppercase() { '2' } else { '1' })
+            .collect()
+    }
+}
+
+impl Collation for LocaleCollation {
+    fn compare(&self, a: &str, b: &str) -> Ordering {
+        self.primary_weights(a)
+            .cmp(&self.primary_weights(b))
+            .then_with(|| self.secondary_weights(a).cmp(&self.secondary_weights(b)))
+            .then_with(|| self.tertiary_weights(a).cmp(&self.tertiary_weights(b)))
+    }
+
+    fn collation_key(&self, s: &str) -> String {
+        format!(
+            "{}\u{0}{}\u{0}{}",
+            self.primary_weights(s),
+            self.secondary_weights(s),
+            self.tertiary_weights(s)
+        )
+    }
+}
+
+static BUILTIN_REGISTRY: LazyLock<CollationRegistry> = LazyLock::new(CollationRegistry::new);
+
+/// Maps collation URIs to [`Collation`] implementations.
+///
+/// UCA URIs with query parameters (`http://www.w3.org/2013/collation/UCA?lang=sv`)
+/// are resolved on demand rather than registered up front.
+#[derive(Clone)]
+pub struct CollationRegistry {
+    collations: HashMap<String, Arc<dyn Collation>>,
+    default_uri: String,
+}
+
+impl fmt::Debug for CollationRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CollationRegistry")
+            .field("collations", &self.collations.keys().collect::<Vec<_>>())
+            .field("default_uri", &self.default_uri)
+            .finish()
+    }
+}
+
+impl Default for CollationRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CollationRegistry {
+    /// Creates a registry containing the built-in collations.
+    pub fn new() -> Self {
+        let mut collations: HashMap<String, Arc<dyn Collation>> = HashMap::new();
+        collations.insert(
+            CODEPOINT_COLLATION.to_string(),
+            Arc::new(CodepointCollation),
+        );
+        collations.insert(
+            HTML_ASCII_CASE_INSENSITIVE_COLLATION.to_string(),
+            Arc::new(AsciiCaseInsensitiveCollation),
+        );
+        collations.insert(
+            UCA_COLLATION.to_string(),
+            Arc::new(LocaleCollation::new(None)),
+        );
+        Self {
+            collations,
+            default_uri: CODEPOINT_COLLATION.to_string(),
+        }
+    }
+
+    /// The shared registry of built-in collations.
+    pub fn builtin() -> &'static CollationRegistry {
+        &BUILTIN_REGISTRY
+    }
+
+    pub fn register(&mut self, uri: impl Into<String>, collation: Arc<dyn Collation>) {
+        self.collations.insert(uri.into(), collation);
+    }
+
+    /// Sets the collation used when none is specified.
+    ///
+    /// # Errors
+    /// Returns an error if `uri` does not resolve to a known collation.
+    pub fn set_default(&mut self, uri: impl Into<String>) -> Result<(), XPath31Error> {
+        let uri = uri.into();
+        self.get(&uri)?;
+        self.default_uri = uri;
+        Ok(())
+    }
+
+    pub fn default_uri(&self) -> &str {
+        &self.default_uri
+    }
+
+    /// Resolves `uri`, or the default collation when `uri` is `None`.
+    pub fn resolve(&self, uri: Option<&str>) -> Result<Arc<dyn Collation>, XPath31Error> {
+        self.get(uri.unwrap_or(&self.default_uri))
+    }
+
+    /// Looks up a collation by URI.
+    ///
+    /// # Errors
+    /// Returns `FOCH0002` if the collation is not supported.
+    pub fn get(&self, uri: &str) -> Result<Arc<dyn Collation>, XPath31Error> {
+        if let Some(collation) = self.collations.get(uri) {
+            return Ok(collation.clone());
+        }
+        if let Some(query) = uri
+            .strip_prefix(UCA_COLLATION)
+            .and_then(|r| r.strip_prefix('?'))
+        {
+            let lang = query
+                .split([';', '&'])
+                .filter_map(|param| param.split_once('='))
+                .find(|(k, _)| *k == "lang")
+                .map(|(_, v)| v);
+            return Ok(Arc::new(LocaleCollation::new(lang)));
+        }
+        Err(XPath31Error::dynamic_error(format!(
+            "FOCH0002: Unsupported collation '{}'",
+            uri
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(collation: &dyn Collation, words: &[&str]) -> Vec<String> {
+        let mut v: Vec<String> = words.iter().map(|s| s.to_string()).collect();
+        v.sort_by(|a, b| collation.compare(a, b));
+        v
+    }
+
+    #[test]
+    fn test_codepoint_vs_locale_order() {
+        let words = ["zebra", "éclair", "eagle", "Ecuador"];
+        assert_eq!(
+            sorted(&CodepointCollation, &words),
+            vec!["Ecuador", "eagle", "zebra", "éclair"]
+        );
+        assert_eq!(
+            sorted(&LocaleCollation::new(None), &words),
+            vec!["eagle", "éclair", "Ecuador", "zebra"]
+        );
+    }
+
+    #[test]
+    fn test_accent_and_case_are_lower_levels() {
+        let c = LocaleCollation::new(None);
+        assert_eq!(c.compare("resume", "résumé"), Ordering::Less);
+        assert_eq!(c.compare("résumé", "resumes"), Ordering::Less);
+        assert_eq!(c.compare("apple", "Apple"), Ordering::Less);
+        assert!(c.equals("straße", "straße"));
+    }
+
+    #[test]
+    fn test_language_tailoring() {
+        let words = ["ål", "zoo", "øl", "ost"];
+        assert_eq!(
+            sorted(&LocaleCollation::new(None), &words),
+            vec!["ål", "ost", "zoo", "øl"]
+        );
+        assert_eq!(
+            sorted(&LocaleCollation::new(Some("nb-NO")), &words),
+            vec!["ost", "zoo", "øl", "ål"]
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let c = AsciiCaseInsensitiveCollation;
+        assert!(c.equals("Hello", "hELLO"));
+        assert_eq!(c.compare("apple", "Banana"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_collation_key_matches_compare() {
+        let c = LocaleCollation::new(Some("sv"));
+        let words = ["Öl", "ol", "år", "Zeta", "éa", "ea"];
+        for a in words {
+            for b in words {
+                assert_eq!(
+                    c.compare(a, b),
+                    c.collation_key(a).cmp(&c.collation_key(b)),
+                    "{a} vs {b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_registry_resolution() {
+        let registry = CollationRegistry::new();
+        assert!(registry.get(CODEPOINT_COLLATION).is_ok());
+        assert!(registry.get(HTML_ASCII_CASE_INSENSITIVE_COLLATION).is_ok());
+        assert!(
+            registry
+                .get("http://www.w3.org/2013/collation/UCA?lang=sv")
+                .is_ok()
+        );
+        assert!(registry.get("http://example.com/unknown").is_err());
+        assert_eq!(registry.default_uri(), CODEPOINT_COLLATION);
+    }
+
+    #[test]
+    fn test_registry_custom_default() {
+        let mut registry = CollationRegistry::new();
+        registry.register("urn:reverse", Arc::new(ReverseCollation));
+        registry.set_default("urn:reverse").unwrap();
+        let c = registry.resolve(None).unwrap();
+        assert_eq!(c.compare("a", "b"), Ordering::Greater);
+        assert!(registry.set_default("urn:missing").is_err());
+    }
+
+    #[derive(Debug)]
+    struct ReverseCollation;
+
+    impl Collation for ReverseCollation {
+        fn compare(&self, a: &str, b: &str) -> Ordering {
+            b.cmp(a)
+        }
+
+        fn collation_key(&self, s: &str) -> String {
+            s.chars()
+                .map(|c| char::from_u32(0x10FFFF - c as u32).unwrap_or(c))
+                .collect()
+        }
+    }
+}
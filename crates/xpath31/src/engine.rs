@@ -6,9 +6,11 @@ use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 
 use crate::ast::*;
+use crate::collation::CollationRegistry;
 use crate::error::XPath31Error;
 use crate::functions;
 use crate::operators;
+use crate::resources::ResourceLoader;
 use crate::types::*;
 use petty_xpath1::DataSourceNode;
 
@@ -18,6 +20,8 @@ pub struct EvaluationContext<'a, 'd, N: DataSourceNode<'a>> {
     pub context_size: usize,
     pub variables: &'d HashMap<String, XdmValue<N>>,
     pub root_node: Option<N>,
+    pub collations: &'d CollationRegistry,
+    pub resources: Option<&'d dyn ResourceLoader>,
     _marker: PhantomData<&'a ()>,
 }
 
@@ -33,10 +37,25 @@ impl<'a, 'd, N: DataSourceNode<'a> + Clone + 'a> EvaluationContext<'a, 'd, N> {
             context_size: 1,
             variables,
             root_node,
+            collations: CollationRegistry::builtin(),
+            resources: None,
             _marker: PhantomData,
         }
     }
 
+    /// Uses `collations` instead of the built-in registry for string comparison.
+    pub fn with_collations(mut self, collations: &'d CollationRegistry) -> Self {
+        self.collations = collations;
+        self
+    }
+
+    /// Lets `doc()`, `unparsed-text()` and `collection()` read resources through
+    /// `resources`.
+    pub fn with_resources(mut self, resources: &'d dyn ResourceLoader) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+
     pub fn with_context_item(&self, item: XdmItem<N>) -> Self {
         Self {
             context_item: Some(item),
@@ -44,6 +63,8 @@ impl<'a, 'd, N: DataSourceNode<'a> + Clone + 'a> EvaluationContext<'a, 'd, N> {
             context_size: self.context_size,
             variables: self.variables,
             root_node: self.root_node,
+            collations: self.collations,
+            resources: self.resources,
             _marker: PhantomData,
         }
     }
@@ -55,6 +76,8 @@ impl<'a, 'd, N: DataSourceNode<'a> + Clone + 'a> EvaluationContext<'a, 'd, N> {
             context_size: size,
             variables: self.variables,
             root_node: self.root_node,
+            collations: self.collations,
+            resources: self.resources,
             _marker: PhantomData,
         }
     }
@@ -723,7 +723,10 @@ pub fn fn_random_number_generator_permute<N: Clone>(
     Ok(XdmValue::from_items(items))
 }
 
-pub fn fn_doc<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+pub fn fn_doc<'a, N: DataSourceNode<'a> + Clone>(
+    args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
     if args.len() != 1 {
         return Err(XPath31Error::function("doc", "Expected 1 argument"));
     }
@@ -731,6 +734,9 @@ pub fn fn_doc<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Er
         return Ok(XdmValue::empty());
     }
     let uri = args[0].to_string_value();
+    if let Some(resources) = ctx.resources {
+        resources.authorize("doc", &uri)?;
+    }
     Err(XPath31Error::function(
         "doc",
         format!(
@@ -750,42 +756,87 @@ pub fn fn_doc_available<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>,
     Ok(XdmValue::from_bool(false))
 }
 
-pub fn fn_collection<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+pub fn fn_collection<'a, N: DataSourceNode<'a> + Clone>(
+    args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
     if args.len() > 1 {
         return Err(XPath31Error::function(
             "collection",
             "Expected 0 or 1 arguments",
         ));
     }
+    authorize_collection("collection", &args, ctx)?;
     Ok(XdmValue::empty())
 }
 
-pub fn fn_uri_collection<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+pub fn fn_uri_collection<'a, N: DataSourceNode<'a> + Clone>(
+    args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
     if args.len() > 1 {
         return Err(XPath31Error::function(
             "uri-collection",
             "Expected 0 or 1 arguments",
         ));
     }
+    authorize_collection("uri-collection", &args, ctx)?;
     Ok(XdmValue::empty())
 }
 
-pub fn fn_unparsed_text<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+fn authorize_collection<'a, N: DataSourceNode<'a> + Clone>(
+    function: &str,
+    args: &[XdmValue<N>],
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<(), XPath31Error> {
+    match (ctx.resources, args.first()) {
+        (Some(resources), Some(uri)) if !uri.is_empty() => {
+            resources.authorize(function, &uri.to_string_value())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Reads the text resource named by the first argument through the context's
+/// resource loader.
+fn load_unparsed_text<'a, N: DataSourceNode<'a> + Clone>(
+    function: &str,
+    args: &[XdmValue<N>],
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<Option<String>, XPath31Error> {
     if args.is_empty() || args.len() > 2 {
         return Err(XPath31Error::function(
-            "unparsed-text",
+            function,
             "Expected 1 or 2 arguments",
         ));
     }
+    if args[0].is_empty() {
+        return Ok(None);
+    }
     let uri = args[0].to_string_value();
-    Err(XPath31Error::function(
-        "unparsed-text",
-        format!("External text loading not available (requested: {})", uri),
-    ))
+    let Some(resources) = ctx.resources else {
+        return Err(XPath31Error::function(
+            function,
+            format!("External text loading not available (requested: {})", uri),
+        ));
+    };
+    resources.authorize(function, &uri)?;
+    resources.load_text(function, &uri).map(Some)
 }
 
-pub fn fn_unparsed_text_available<N: Clone>(
+pub fn fn_unparsed_text<'a, N: DataSourceNode<'a> + Clone>(
     args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
+    Ok(match load_unparsed_text("unparsed-text", &args, ctx)? {
+        Some(text) => XdmValue::from_string(text),
+        None => XdmValue::empty(),
+    })
+}
+
+pub fn fn_unparsed_text_available<'a, N: DataSourceNode<'a> + Clone>(
+    args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
 ) -> Result<XdmValue<N>, XPath31Error> {
     if args.is_empty() || args.len() > 2 {
         return Err(XPath31Error::function(
@@ -793,23 +844,25 @@ pub fn fn_unparsed_text_available<N: Clone>(
             "Expected 1 or 2 arguments",
         ));
     }
-    Ok(XdmValue::from_bool(false))
+    let available = matches!(
+        load_unparsed_text("unparsed-text-available", &args, ctx),
+        Ok(Some(_))
+    );
+    Ok(XdmValue::from_bool(available))
 }
 
-pub fn fn_unparsed_text_lines<N: Clone>(
+pub fn fn_unparsed_text_lines<'a, N: DataSourceNode<'a> + Clone>(
     args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
 ) -> Result<XdmValue<N>, XPath31Error> {
-    if args.is_empty() || args.len() > 2 {
-        return Err(XPath31Error::function(
-            "unparsed-text-lines",
-            "Expected 1 or 2 arguments",
-        ));
-    }
-    let uri = args[0].to_string_value();
-    Err(XPath31Error::function(
-        "unparsed-text-lines",
-        format!("External text loading not available (requested: {})", uri),
-    ))
+    let Some(text) = load_unparsed_text("unparsed-text-lines", &args, ctx)? else {
+        return Ok(XdmValue::empty());
+    };
+    let lines = text
+        .lines()
+        .map(|line| XdmItem::Atomic(AtomicValue::String(line.to_string())))
+        .collect();
+    Ok(XdmValue::from_items(lines))
 }
 
 pub fn fn_parse_xml<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
@@ -932,40 +985,6 @@ pub fn fn_has_children<'a, N: DataSourceNode<'a> + Clone>(
     }
 }
 
-pub fn fn_serialize<'a, N: DataSourceNode<'a> + Clone>(
-    args: Vec<XdmValue<N>>,
-) -> Result<XdmValue<N>, XPath31Error> {
-    if args.is_empty() || args.len() > 2 {
-        return Err(XPath31Error::function(
-            "serialize",
-            "Expected 1 or 2 arguments",
-        ));
-    }
-
-    let mut result = String::new();
-    for item in args[0].items() {
-        match item {
-            XdmItem::Node(n) => {
-                result.push_str(&n.string_value());
-            }
-            XdmItem::Atomic(a) => {
-                result.push_str(&a.to_string_value());
-            }
-            XdmItem::Map(m) => {
-                result.push_str(&format!("{}", m));
-            }
-            XdmItem::Array(a) => {
-                result.push_str(&format!("{}", a));
-            }
-            XdmItem::Function(f) => {
-                result.push_str(&format!("{}", f));
-            }
-        }
-    }
-
-    Ok(XdmValue::from_string(result))
-}
-
 pub fn fn_path<'a, N: DataSourceNode<'a> + Clone>(
     mut args: Vec<XdmValue<N>>,
     ctx: &EvaluationContext<'a, '_, N>,
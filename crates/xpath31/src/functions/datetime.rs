@@ -1,5 +1,7 @@
+use crate::engine::EvaluationContext;
 use crate::error::XPath31Error;
 use crate::types::{AtomicValue, XdmValue};
+use petty_xpath1::DataSourceNode;
 use regex::Regex;
 use std::fmt;
 use std::sync::LazyLock;
@@ -387,31 +389,42 @@ impl fmt::Display for Timezone {
     }
 }
 
-fn get_current_utc_datetime() -> DateTime {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default();
+/// The current date and time: the `::current-dateTime` the host fixed for the
+/// evaluation, or else the system clock, in UTC.
+fn get_current_utc_datetime<'a, N: DataSourceNode<'a> + Clone>(
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> DateTime {
+    ctx.variables
+        .get("::current-dateTime")
+        .and_then(|fixed| DateTime::parse(&fixed.to_string_value()))
+        .unwrap_or_else(|| DateTime::from_system_time(SystemTime::now()))
+}
 
-    let total_secs = now.as_secs();
-    let nanos = now.subsec_nanos();
+impl DateTime {
+    /// The UTC date and time of `time`, which must not be before the Unix epoch.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let now = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let total_secs = now.as_secs();
+        let nanos = now.subsec_nanos();
 
-    let days_since_epoch = (total_secs / 86400) as i64;
-    let secs_in_day = (total_secs % 86400) as u32;
+        let days_since_epoch = (total_secs / 86400) as i64;
+        let secs_in_day = (total_secs % 86400) as u32;
 
-    let hour = (secs_in_day / 3600) as u8;
-    let minute = ((secs_in_day % 3600) / 60) as u8;
-    let second = (secs_in_day % 60) as f64 + (nanos as f64 / 1_000_000_000.0);
+        let hour = (secs_in_day / 3600) as u8;
+        let minute = ((secs_in_day % 3600) / 60) as u8;
+        let second = (secs_in_day % 60) as f64 + (nanos as f64 / 1_000_000_000.0);
 
-    let (year, month, day) = days_to_ymd(days_since_epoch + 719_468);
+        let (year, month, day) = days_to_ymd(days_since_epoch + 719_468);
 
-    DateTime {
-        year,
-        month,
-        day,
-        hour,
-        minute,
-        second,
-        timezone: Some(Timezone { offset_minutes: 0 }),
+        DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            timezone: Some(Timezone { offset_minutes: 0 }),
+        }
     }
 }
 
@@ -433,25 +446,31 @@ fn days_to_ymd(days: i64) -> (i32, u8, u8) {
     (year as i32, m as u8, d as u8)
 }
 
-pub fn fn_current_datetime<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+pub fn fn_current_datetime<'a, N: DataSourceNode<'a> + Clone>(
+    args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
     if !args.is_empty() {
         return Err(XPath31Error::function(
             "current-dateTime",
             "expects no arguments",
         ));
     }
-    let dt = get_current_utc_datetime();
+    let dt = get_current_utc_datetime(ctx);
     Ok(XdmValue::from_atomic(AtomicValue::DateTime(dt.to_string())))
 }
 
-pub fn fn_current_date<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+pub fn fn_current_date<'a, N: DataSourceNode<'a> + Clone>(
+    args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
     if !args.is_empty() {
         return Err(XPath31Error::function(
             "current-date",
             "expects no arguments",
         ));
     }
-    let dt = get_current_utc_datetime();
+    let dt = get_current_utc_datetime(ctx);
     let date = Date {
         year: dt.year,
         month: dt.month,
@@ -461,14 +480,17 @@ pub fn fn_current_date<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>,
     Ok(XdmValue::from_atomic(AtomicValue::Date(date.to_string())))
 }
 
-pub fn fn_current_time<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+pub fn fn_current_time<'a, N: DataSourceNode<'a> + Clone>(
+    args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
     if !args.is_empty() {
         return Err(XPath31Error::function(
             "current-time",
             "expects no arguments",
         ));
     }
-    let dt = get_current_utc_datetime();
+    let dt = get_current_utc_datetime(ctx);
     let time = Time {
         hour: dt.hour,
         minute: dt.minute,
@@ -2142,6 +2164,15 @@ pub fn fn_parse_ietf_date<N: Clone>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use petty_xpath1::tests::MockNode;
+    use std::collections::HashMap;
+
+    fn create_empty_ctx() -> EvaluationContext<'static, 'static, MockNode<'static>> {
+        static VARS: std::sync::OnceLock<HashMap<String, XdmValue<MockNode<'static>>>> =
+            std::sync::OnceLock::new();
+        let vars = VARS.get_or_init(HashMap::new);
+        EvaluationContext::new(None, None, vars)
+    }
 
     #[test]
     fn test_parse_datetime() {
@@ -2242,7 +2273,7 @@ mod tests {
 
     #[test]
     fn test_current_datetime_returns_real_time() {
-        let result: XdmValue<()> = fn_current_datetime(vec![]).unwrap();
+        let result = fn_current_datetime(vec![], &create_empty_ctx()).unwrap();
         let dt_str = result.to_string_value();
         let dt = DateTime::parse(&dt_str).expect("current-dateTime should return valid dateTime");
         assert!(dt.year >= 2024, "Year should be 2024 or later");
@@ -2251,7 +2282,7 @@ mod tests {
 
     #[test]
     fn test_current_date_returns_real_date() {
-        let result: XdmValue<()> = fn_current_date(vec![]).unwrap();
+        let result = fn_current_date(vec![], &create_empty_ctx()).unwrap();
         let d_str = result.to_string_value();
         let d = Date::parse(&d_str).expect("current-date should return valid date");
         assert!(d.year >= 2024, "Year should be 2024 or later");
@@ -2259,7 +2290,7 @@ mod tests {
 
     #[test]
     fn test_current_time_returns_valid_time() {
-        let result: XdmValue<()> = fn_current_time(vec![]).unwrap();
+        let result = fn_current_time(vec![], &create_empty_ctx()).unwrap();
         let t_str = result.to_string_value();
         let t = Time::parse(&t_str).expect("current-time should return valid time");
         assert!(t.hour <= 23, "Hour should be valid");
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::ast::{Expression, Param};
+use crate::collation::Collation;
 use crate::engine::{EvaluationContext, evaluate};
 use crate::error::XPath31Error;
 use crate::types::*;
@@ -258,9 +259,13 @@ pub fn fn_sort<'a, N: DataSourceNode<'a> + Clone + 'a>(
         None
     };
 
-    if args.len() >= 2 {
-        args.remove(1);
-    }
+    let collation_uri = if args.len() >= 2 {
+        let arg = args.remove(1);
+        (!arg.is_empty()).then(|| arg.to_string_value())
+    } else {
+        None
+    };
+    let collation = ctx.collations.resolve(collation_uri.as_deref())?;
 
     let seq = args.remove(0);
     let mut items: Vec<XdmItem<N>> = seq.into_items();
@@ -276,31 +281,44 @@ pub fn fn_sort<'a, N: DataSourceNode<'a> + Clone + 'a>(
             )?;
             keyed.push((item, key));
         }
-        keyed.sort_by(|a, b| compare_values(&a.1, &b.1));
+        keyed.sort_by(|a, b| compare_values(&a.1, &b.1, collation.as_ref()));
         items = keyed.into_iter().map(|(item, _)| item).collect();
     } else {
-        items.sort_by(|a, b| compare_items(a, b));
+        items.sort_by(|a, b| compare_items(a, b, collation.as_ref()));
     }
 
     Ok(XdmValue::from_items(items))
 }
 
-fn compare_values<N: Clone>(a: &XdmValue<N>, b: &XdmValue<N>) -> std::cmp::Ordering {
-    let a_str = a.to_string_value();
-    let b_str = b.to_string_value();
-    a_str.cmp(&b_str)
+fn compare_values<N: Clone>(
+    a: &XdmValue<N>,
+    b: &XdmValue<N>,
+    collation: &dyn Collation,
+) -> std::cmp::Ordering {
+    match (a.items(), b.items()) {
+        ([XdmItem::Atomic(av)], [XdmItem::Atomic(bv)]) => compare_atomics(av, bv, collation),
+        _ => collation.compare(&a.to_string_value(), &b.to_string_value()),
+    }
 }
 
-fn compare_items<N>(a: &XdmItem<N>, b: &XdmItem<N>) -> std::cmp::Ordering {
+fn compare_items<N>(
+    a: &XdmItem<N>,
+    b: &XdmItem<N>,
+    collation: &dyn Collation,
+) -> std::cmp::Ordering {
     match (a, b) {
-        (XdmItem::Atomic(av), XdmItem::Atomic(bv)) => compare_atomics(av, bv),
+        (XdmItem::Atomic(av), XdmItem::Atomic(bv)) => compare_atomics(av, bv, collation),
         _ => std::cmp::Ordering::Equal,
     }
 }
 
-fn compare_atomics(a: &AtomicValue, b: &AtomicValue) -> std::cmp::Ordering {
+fn compare_atomics(
+    a: &AtomicValue,
+    b: &AtomicValue,
+    collation: &dyn Collation,
+) -> std::cmp::Ordering {
     match (a, b) {
-        (AtomicValue::String(s1), AtomicValue::String(s2)) => s1.cmp(s2),
+        (AtomicValue::String(s1), AtomicValue::String(s2)) => collation.compare(s1, s2),
         (AtomicValue::Integer(i1), AtomicValue::Integer(i2)) => i1.cmp(i2),
         (AtomicValue::Double(d1), AtomicValue::Double(d2)) => {
             d1.partial_cmp(d2).unwrap_or(std::cmp::Ordering::Equal)
@@ -311,7 +329,7 @@ fn compare_atomics(a: &AtomicValue, b: &AtomicValue) -> std::cmp::Ordering {
         (AtomicValue::Double(d), AtomicValue::Integer(i)) => d
             .partial_cmp(&(*i as f64))
             .unwrap_or(std::cmp::Ordering::Equal),
-        _ => a.to_string_value().cmp(&b.to_string_value()),
+        _ => collation.compare(&a.to_string_value(), &b.to_string_value()),
     }
 }
 
@@ -614,9 +632,13 @@ pub fn array_sort<'a, N: DataSourceNode<'a> + Clone + 'a>(
         None
     };
 
-    if args.len() >= 2 {
-        args.remove(1);
-    }
+    let collation_uri = if args.len() >= 2 {
+        let arg = args.remove(1);
+        (!arg.is_empty()).then(|| arg.to_string_value())
+    } else {
+        None
+    };
+    let collation = ctx.collations.resolve(collation_uri.as_deref())?;
 
     let arr_val = args.remove(0);
 
@@ -633,10 +655,10 @@ pub fn array_sort<'a, N: DataSourceNode<'a> + Clone + 'a>(
             let key = invoke_function(&func, vec![member.clone()], ctx, local_vars)?;
             keyed.push((member, key));
         }
-        keyed.sort_by(|a, b| compare_values(&a.1, &b.1));
+        keyed.sort_by(|a, b| compare_values(&a.1, &b.1, collation.as_ref()));
         members = keyed.into_iter().map(|(m, _)| m).collect();
     } else {
-        members.sort_by(|a, b| compare_values(a, b));
+        members.sort_by(|a, b| compare_values(a, b, collation.as_ref()));
     }
 
     Ok(XdmValue::from_array(XdmArray::from_members(members)))
@@ -933,4 +955,38 @@ mod tests {
 
         assert_eq!(result.to_double(), 6.0);
     }
+
+    #[test]
+    fn test_sort_with_collation() {
+        let (_, vars) = make_ctx();
+        let ctx: EvaluationContext<'_, '_, MockNode<'_>> =
+            EvaluationContext::new(None, None, &vars);
+        let words = || {
+            XdmValue::from_items(vec![
+                XdmItem::Atomic(AtomicValue::String("zebra".to_string())),
+                XdmItem::Atomic(AtomicValue::String("éclair".to_string())),
+                XdmItem::Atomic(AtomicValue::String("eagle".to_string())),
+            ])
+        };
+        let as_strings = |v: XdmValue<MockNode<'_>>| -> Vec<String> {
+            v.items()
+                .iter()
+                .filter_map(|i| i.as_atomic().map(AtomicValue::to_string_value))
+                .collect()
+        };
+
+        let codepoint = fn_sort(vec![words()], &ctx, &HashMap::new()).unwrap();
+        assert_eq!(as_strings(codepoint), vec!["eagle", "zebra", "éclair"]);
+
+        let locale = fn_sort(
+            vec![
+                words(),
+                XdmValue::from_string("http://www.w3.org/2013/collation/UCA?lang=fr"),
+            ],
+            &ctx,
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert_eq!(as_strings(locale), vec!["eagle", "éclair", "zebra"]);
+    }
 }
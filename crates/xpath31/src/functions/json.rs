@@ -296,7 +296,9 @@ fn add_value_to_parent(
     }
 }
 
-fn json_value_to_xdm<N: Clone>(value: &serde_json::Value) -> Result<XdmValue<N>, XPath31Error> {
+/// Converts a JSON value to XDM as `parse-json` does: objects become maps, arrays
+/// become arrays and `null` the empty sequence.
+pub fn json_value_to_xdm<N: Clone>(value: &serde_json::Value) -> Result<XdmValue<N>, XPath31Error> {
     match value {
         serde_json::Value::Null => Ok(XdmValue::empty()),
         serde_json::Value::Bool(b) => Ok(XdmValue::from_bool(*b)),
@@ -7,8 +7,11 @@ mod math;
 mod numeric;
 mod regex;
 mod sequence;
+mod serialize;
 mod string;
 
+pub use json::json_value_to_xdm;
+
 use std::collections::HashMap;
 
 use crate::ast::QName;
@@ -49,7 +52,7 @@ pub fn call_function<'a, N: DataSourceNode<'a> + Clone + 'a>(
         (Some("fn") | None, "string-join") => string::fn_string_join(args),
         (Some("fn") | None, "substring-before") => string::fn_substring_before(args),
         (Some("fn") | None, "substring-after") => string::fn_substring_after(args),
-        (Some("fn") | None, "compare") => string::fn_compare(args),
+        (Some("fn") | None, "compare") => string::fn_compare(args, ctx),
         (Some("fn") | None, "codepoints-to-string") => string::fn_codepoints_to_string(args),
         (Some("fn") | None, "string-to-codepoints") => string::fn_string_to_codepoints(args),
         (Some("fn") | None, "encode-for-uri") => string::fn_encode_for_uri(args),
@@ -59,9 +62,9 @@ pub fn call_function<'a, N: DataSourceNode<'a> + Clone + 'a>(
         (Some("fn") | None, "base-uri") => string::fn_base_uri(args, ctx),
         (Some("fn") | None, "static-base-uri") => string::fn_static_base_uri(args),
         (Some("fn") | None, "contains-token") => string::fn_contains_token(args),
-        (Some("fn") | None, "default-collation") => string::fn_default_collation(args),
+        (Some("fn") | None, "default-collation") => string::fn_default_collation(args, ctx),
         (Some("fn") | None, "default-language") => string::fn_default_language(args),
-        (Some("fn") | None, "collation-key") => string::fn_collation_key(args),
+        (Some("fn") | None, "collation-key") => string::fn_collation_key(args, ctx),
 
         (Some("fn") | None, "abs") => numeric::fn_abs(args),
         (Some("fn") | None, "ceiling") => numeric::fn_ceiling(args),
@@ -159,18 +162,31 @@ pub fn call_function<'a, N: DataSourceNode<'a> + Clone + 'a>(
                 ));
             }
             let key_name = args[0].to_string_value();
-            let key_value = args[1].to_string_value();
 
+            // The index is built by the XSLT executor from its `xsl:key` declarations.
             let index_var_name = format!("::key-index:{}", key_name);
-            if let Some(index_map) = local_vars.get(&index_var_name)
-                && let Some(XdmItem::Map(map)) = index_map.first()
-            {
+            let Some(XdmItem::Map(map)) = local_vars.get(&index_var_name).and_then(|v| v.first())
+            else {
+                return Ok(XdmValue::empty());
+            };
+
+            // Each value in a sequence is looked up; the result is their union.
+            let mut nodes = Vec::new();
+            for item in args[1].items() {
+                let key_value = match item {
+                    XdmItem::Node(n) => n.string_value(),
+                    other => XdmValue::from_item(other.clone()).to_string_value(),
+                };
                 let lookup_key = crate::types::AtomicValue::String(key_value);
-                if let Some(result) = map.get(&lookup_key) {
-                    return Ok(result.clone());
+                if let Some(found) = map.get(&lookup_key) {
+                    nodes.extend(found.to_nodes());
                 }
             }
-            Ok(XdmValue::empty())
+            if args[1].len() > 1 {
+                nodes.sort();
+                nodes.dedup();
+            }
+            Ok(XdmValue::from_nodes(nodes))
         }
 
         (Some("fn") | None, "regex-group") => {
@@ -255,9 +271,9 @@ pub fn call_function<'a, N: DataSourceNode<'a> + Clone + 'a>(
         (Some("fn") | None, "analyze-string") => regex::fn_analyze_string(args),
         (Some("fn") | None, "matches") => regex::fn_matches(args),
 
-        (Some("fn") | None, "current-dateTime") => datetime::fn_current_datetime(args),
-        (Some("fn") | None, "current-date") => datetime::fn_current_date(args),
-        (Some("fn") | None, "current-time") => datetime::fn_current_time(args),
+        (Some("fn") | None, "current-dateTime") => datetime::fn_current_datetime(args, ctx),
+        (Some("fn") | None, "current-date") => datetime::fn_current_date(args, ctx),
+        (Some("fn") | None, "current-time") => datetime::fn_current_time(args, ctx),
         (Some("fn") | None, "dateTime") => datetime::fn_datetime(args),
         (Some("fn") | None, "format-dateTime") => datetime::fn_format_datetime(args),
         (Some("fn") | None, "format-date") => datetime::fn_format_date(args),
@@ -319,16 +335,18 @@ pub fn call_function<'a, N: DataSourceNode<'a> + Clone + 'a>(
         }
         (Some("fn") | None, "parse-ietf-date") => datetime::fn_parse_ietf_date(args),
 
-        (Some("fn") | None, "doc") => core::fn_doc(args),
+        (Some("fn") | None, "doc") => core::fn_doc(args, ctx),
         (Some("fn") | None, "doc-available") => core::fn_doc_available(args),
-        (Some("fn") | None, "collection") => core::fn_collection(args),
-        (Some("fn") | None, "uri-collection") => core::fn_uri_collection(args),
-        (Some("fn") | None, "unparsed-text") => core::fn_unparsed_text(args),
-        (Some("fn") | None, "unparsed-text-available") => core::fn_unparsed_text_available(args),
-        (Some("fn") | None, "unparsed-text-lines") => core::fn_unparsed_text_lines(args),
+        (Some("fn") | None, "collection") => core::fn_collection(args, ctx),
+        (Some("fn") | None, "uri-collection") => core::fn_uri_collection(args, ctx),
+        (Some("fn") | None, "unparsed-text") => core::fn_unparsed_text(args, ctx),
+        (Some("fn") | None, "unparsed-text-available") => {
+            core::fn_unparsed_text_available(args, ctx)
+        }
+        (Some("fn") | None, "unparsed-text-lines") => core::fn_unparsed_text_lines(args, ctx),
         (Some("fn") | None, "parse-xml") => core::fn_parse_xml(args),
         (Some("fn") | None, "parse-xml-fragment") => core::fn_parse_xml_fragment(args),
-        (Some("fn") | None, "serialize") => core::fn_serialize(args),
+        (Some("fn") | None, "serialize") => serialize::fn_serialize(args),
         (Some("fn") | None, "path") => core::fn_path(args, ctx),
 
         _ => Err(XPath31Error::function(full_name, "Unknown function")),
@@ -322,11 +322,24 @@ pub fn fn_format_number<'a, N: DataSourceNode<'a> + Clone + 'a>(
         return Ok(XdmValue::from_string(String::new()));
     }
 
-    let value = value_seq.to_double();
+    // The value is atomized, so a node such as `price` formats its string value.
+    let value = match value_seq.first() {
+        Some(XdmItem::Node(node)) => node.string_value().trim().parse().unwrap_or(f64::NAN),
+        _ => value_seq.to_double(),
+    };
 
     let format = lookup_decimal_format(ctx, format_name.as_deref());
+    // The number locale only replaces the default format; the picture is still read
+    // with the default separators.
+    let locale = match format_name {
+        None if !ctx.variables.contains_key("::decimal-format:") => ctx
+            .variables
+            .get("::number-locale")
+            .and_then(|val| parse_decimal_format_from_string(&val.to_string_value())),
+        _ => None,
+    };
 
-    let result = format_number_with_picture(value, &picture, &format)?;
+    let result = format_number_with_picture(value, &picture, &format, locale.as_ref())?;
     Ok(XdmValue::from_string(result))
 }
 
@@ -371,10 +384,13 @@ fn parse_decimal_format_from_string(s: &str) -> Option<DecimalFormat> {
     Some(df)
 }
 
+/// Formats `value`, writing the decimal and grouping separators of `locale` instead
+/// of those of `format` when given.
 fn format_number_with_picture(
     value: f64,
     picture: &str,
     format: &DecimalFormat,
+    locale: Option<&DecimalFormat>,
 ) -> Result<String, XPath31Error> {
     // Handle special values
     if value.is_nan() {
@@ -409,7 +425,21 @@ fn format_number_with_picture(
     };
 
     // Format the number
-    let formatted = format_scaled_number(scaled_value, &parsed, format);
+    let mut formatted = format_scaled_number(scaled_value, &parsed, format);
+    if let Some(locale) = locale {
+        formatted = formatted
+            .chars()
+            .map(|ch| {
+                if ch == format.decimal_separator {
+                    locale.decimal_separator
+                } else if ch == format.grouping_separator {
+                    locale.grouping_separator
+                } else {
+                    ch
+                }
+            })
+            .collect();
+    }
 
     // Apply prefix/suffix and minus sign
     let mut result = String::new();
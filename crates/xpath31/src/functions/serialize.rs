@@ -0,0 +1,333 @@
+//! `fn:serialize` and the serialization parameters it understands.
+//!
+//! The parameters are passed as a map, e.g. `map { 'method': 'json', 'indent': true() }`:
+//!
+//! - `method`: `xml` (the default) writes nodes as markup, `json` writes maps,
+//!   arrays and atomic values as JSON, and `text` writes string values only.
+//! - `indent`: puts each element of element-only content on its own, indented
+//!   line, or pretty-prints JSON. Off by default.
+//! - `omit-xml-declaration`: whether the `xml` method leaves out the
+//!   `<?xml ...?>` declaration. On by default.
+
+use crate::error::XPath31Error;
+use crate::types::{AtomicValue, XdmItem, XdmMap, XdmValue};
+use petty_xpath1::{DataSourceNode, NodeType, QName};
+
+const INDENT: &str = "  ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    Xml,
+    Json,
+    Text,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SerializationParams {
+    method: Method,
+    indent: bool,
+    omit_xml_declaration: bool,
+}
+
+impl Default for SerializationParams {
+    fn default() -> Self {
+        Self {
+            method: Method::Xml,
+            indent: false,
+            omit_xml_declaration: true,
+        }
+    }
+}
+
+impl SerializationParams {
+    fn from_arg<N: Clone>(arg: Option<&XdmValue<N>>) -> Result<Self, XPath31Error> {
+        let mut params = Self::default();
+        let map = match arg.map(|value| value.items()) {
+            None | Some([]) => return Ok(params),
+            Some([XdmItem::Map(map)]) => map,
+            Some(_) => {
+                return Err(XPath31Error::function(
+                    "serialize",
+                    "Serialization parameters must be a map",
+                ));
+            }
+        };
+
+        if let Some(method) = param(map, "method") {
+            params.method = match method.to_string_value().trim() {
+                "xml" => Method::Xml,
+                "json" => Method::Json,
+                "text" => Method::Text,
+                other => {
+                    return Err(XPath31Error::function(
+                        "serialize",
+                        format!("Unsupported serialization method '{}'", other),
+                    ));
+                }
+            };
+        }
+        if let Some(indent) = param(map, "indent") {
+            params.indent = yes_no(indent, "indent")?;
+        }
+        if let Some(omit) = param(map, "omit-xml-declaration") {
+            params.omit_xml_declaration = yes_no(omit, "omit-xml-declaration")?;
+        }
+        Ok(params)
+    }
+}
+
+fn param<'m, N: Clone>(map: &'m XdmMap<N>, name: &str) -> Option<&'m XdmValue<N>> {
+    map.get(&AtomicValue::String(name.to_string()))
+}
+
+/// A boolean parameter, given as `true()`/`false()` or as `yes`/`no`.
+fn yes_no<N: Clone>(value: &XdmValue<N>, name: &str) -> Result<bool, XPath31Error> {
+    if let [XdmItem::Atomic(AtomicValue::Boolean(b))] = value.items() {
+        return Ok(*b);
+    }
+    match value.to_string_value().trim() {
+        "yes" | "true" | "1" => Ok(true),
+        "no" | "false" | "0" => Ok(false),
+        other => Err(XPath31Error::function(
+            "serialize",
+            format!("Invalid value '{}' for the '{}' parameter", other, name),
+        )),
+    }
+}
+
+pub fn fn_serialize<'a, N: DataSourceNode<'a> + Clone>(
+    args: Vec<XdmValue<N>>,
+) -> Result<XdmValue<N>, XPath31Error> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(XPath31Error::function(
+            "serialize",
+            "Expected 1 or 2 arguments",
+        ));
+    }
+    let params = SerializationParams::from_arg(args.get(1))?;
+
+    let result = match params.method {
+        Method::Text => serialize_text(&args[0]),
+        Method::Xml => serialize_xml(&args[0], params)?,
+        Method::Json => serialize_json(&args[0], params)?,
+    };
+    Ok(XdmValue::from_string(result))
+}
+
+fn serialize_text<'a, N: DataSourceNode<'a> + Clone>(value: &XdmValue<N>) -> String {
+    let mut result = String::new();
+    for item in value.items() {
+        match item {
+            XdmItem::Node(n) => result.push_str(&n.string_value()),
+            XdmItem::Atomic(a) => result.push_str(&a.to_string_value()),
+            XdmItem::Map(m) => result.push_str(&m.to_string()),
+            XdmItem::Array(a) => result.push_str(&a.to_string()),
+            XdmItem::Function(f) => result.push_str(&f.to_string()),
+        }
+    }
+    result
+}
+
+fn serialize_xml<'a, N: DataSourceNode<'a> + Clone>(
+    value: &XdmValue<N>,
+    params: SerializationParams,
+) -> Result<String, XPath31Error> {
+    let mut out = String::new();
+    if !params.omit_xml_declaration {
+        out.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        if params.indent {
+            out.push('\n');
+        }
+    }
+    for (i, item) in value.items().iter().enumerate() {
+        match item {
+            XdmItem::Node(n) => {
+                if params.indent && i > 0 {
+                    out.push('\n');
+                }
+                write_node(*n, 0, params.indent, &mut out);
+            }
+            XdmItem::Atomic(a) => {
+                if i > 0 && matches!(value.items()[i - 1], XdmItem::Atomic(_)) {
+                    out.push(' ');
+                }
+                out.push_str(&escape_text(&a.to_string_value()));
+            }
+            _ => {
+                return Err(XPath31Error::function(
+                    "serialize",
+                    "SENR0001: Maps, arrays and functions cannot be serialized as XML",
+                ));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn write_node<'a, N: DataSourceNode<'a>>(node: N, depth: usize, indent: bool, out: &mut String) {
+    match node.node_type() {
+        NodeType::Root => {
+            let children = content_children(node, indent);
+            for (i, child) in children.into_iter().enumerate() {
+                if indent && i > 0 {
+                    out.push('\n');
+                }
+                write_node(child, depth, indent, out);
+            }
+        }
+        NodeType::Element => {
+            let name = node.name().map(qualified_name).unwrap_or_default();
+            out.push('<');
+            out.push_str(&name);
+            for attribute in node.attributes() {
+                out.push(' ');
+                write_node(attribute, depth, indent, out);
+            }
+
+            let children = content_children(node, indent);
+            if children.is_empty() {
+                out.push_str("/>");
+                return;
+            }
+            out.push('>');
+            // Only element-only content is indented: breaking lines inside mixed
+            // content would change the text.
+            let element_only = children
+                .iter()
+                .all(|child| child.node_type() != NodeType::Text);
+            for child in children {
+                if indent && element_only {
+                    out.push('\n');
+                    out.push_str(&INDENT.repeat(depth + 1));
+                }
+                write_node(child, depth + 1, indent, out);
+            }
+            if indent && element_only {
+                out.push('\n');
+                out.push_str(&INDENT.repeat(depth));
+            }
+            out.push_str("</");
+            out.push_str(&name);
+            out.push('>');
+        }
+        NodeType::Attribute => {
+            let name = node.name().map(qualified_name).unwrap_or_default();
+            out.push_str(&format!(
+                "{}=\"{}\"",
+                name,
+                escape_attribute(&node.string_value())
+            ));
+        }
+        NodeType::Text => out.push_str(&escape_text(&node.string_value())),
+        NodeType::Comment => out.push_str(&format!("<!--{}-->", node.string_value())),
+        NodeType::ProcessingInstruction => {
+            let target = node.name().map(qualified_name).unwrap_or_default();
+            let data = node.string_value();
+            if data.is_empty() {
+                out.push_str(&format!("<?{}?>", target));
+            } else {
+                out.push_str(&format!("<?{} {}?>", target, data));
+            }
+        }
+    }
+}
+
+/// The children to write; when indenting, whitespace-only text is dropped as the
+/// indentation replaces it.
+fn content_children<'a, N: DataSourceNode<'a>>(node: N, indent: bool) -> Vec<N> {
+    node.children()
+        .filter(|child| {
+            !(indent
+                && child.node_type() == NodeType::Text
+                && child.string_value().trim().is_empty())
+        })
+        .collect()
+}
+
+fn qualified_name(name: QName<'_>) -> String {
+    match name.prefix {
+        Some(prefix) => format!("{}:{}", prefix, name.local_part),
+        None => name.local_part.to_string(),
+    }
+}
+
+fn escape_text(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn escape_attribute(s: &str) -> String {
+    escape_text(s).replace('"', "&quot;")
+}
+
+fn serialize_json<'a, N: DataSourceNode<'a> + Clone>(
+    value: &XdmValue<N>,
+    params: SerializationParams,
+) -> Result<String, XPath31Error> {
+    let json = value_to_json(value)?;
+    let result = if params.indent {
+        serde_json::to_string_pretty(&json)
+    } else {
+        serde_json::to_string(&json)
+    };
+    result.map_err(|e| XPath31Error::function("serialize", e.to_string()))
+}
+
+fn value_to_json<'a, N: DataSourceNode<'a> + Clone>(
+    value: &XdmValue<N>,
+) -> Result<serde_json::Value, XPath31Error> {
+    match value.items() {
+        [] => Ok(serde_json::Value::Null),
+        [item] => item_to_json(item),
+        _ => Err(XPath31Error::function(
+            "serialize",
+            "SERE0023: The json method cannot serialize a sequence of more than one item",
+        )),
+    }
+}
+
+fn item_to_json<'a, N: DataSourceNode<'a> + Clone>(
+    item: &XdmItem<N>,
+) -> Result<serde_json::Value, XPath31Error> {
+    Ok(match item {
+        XdmItem::Node(n) => {
+            let mut xml = String::new();
+            write_node(*n, 0, false, &mut xml);
+            serde_json::Value::String(xml)
+        }
+        XdmItem::Atomic(a) => atomic_to_json(a),
+        XdmItem::Map(m) => {
+            let mut object = serde_json::Map::new();
+            for (key, value) in m.entries() {
+                object.insert(key.to_string_value(), value_to_json(value)?);
+            }
+            serde_json::Value::Object(object)
+        }
+        XdmItem::Array(a) => {
+            serde_json::Value::Array(a.iter().map(value_to_json).collect::<Result<Vec<_>, _>>()?)
+        }
+        XdmItem::Function(_) => {
+            return Err(XPath31Error::function(
+                "serialize",
+                "SERE0021: Functions cannot be serialized as JSON",
+            ));
+        }
+    })
+}
+
+fn atomic_to_json(value: &AtomicValue) -> serde_json::Value {
+    match value {
+        AtomicValue::Boolean(b) => serde_json::Value::Bool(*b),
+        AtomicValue::Integer(i) => serde_json::Value::from(*i),
+        AtomicValue::Double(d) => serde_json::Number::from_f64(*d)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        AtomicValue::Decimal(d) => d
+            .to_string()
+            .parse::<serde_json::Number>()
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|_| serde_json::Value::String(d.to_string())),
+        other => serde_json::Value::String(other.to_string_value()),
+    }
+}
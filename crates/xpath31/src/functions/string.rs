@@ -36,7 +36,11 @@ pub fn fn_string<'a, N: DataSourceNode<'a> + Clone>(
             None => String::new(),
         }
     } else {
-        args.remove(0).to_string_value()
+        match args.remove(0).first() {
+            Some(XdmItem::Node(n)) => n.string_value(),
+            Some(XdmItem::Atomic(a)) => a.to_string_value(),
+            _ => String::new(),
+        }
     };
     Ok(XdmValue::from_string(s))
 }
@@ -341,13 +345,22 @@ pub fn fn_substring_after<N: Clone>(
     }
 }
 
-pub fn fn_compare<N: Clone>(mut args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+pub fn fn_compare<'a, N: DataSourceNode<'a> + Clone>(
+    mut args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
     if args.len() < 2 || args.len() > 3 {
         return Err(XPath31Error::function(
             "compare",
             "Expected 2 or 3 arguments",
         ));
     }
+    let collation_uri = if args.len() == 3 {
+        Some(args.remove(2).to_string_value())
+    } else {
+        None
+    };
+    let collation = ctx.collations.resolve(collation_uri.as_deref())?;
     let s2 = args.remove(1);
     let s1 = args.remove(0);
 
@@ -358,7 +371,7 @@ pub fn fn_compare<N: Clone>(mut args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, X
     let str1 = s1.to_string_value();
     let str2 = s2.to_string_value();
 
-    let result = match str1.cmp(&str2) {
+    let result = match collation.compare(&str1, &str2) {
         std::cmp::Ordering::Less => -1,
         std::cmp::Ordering::Equal => 0,
         std::cmp::Ordering::Greater => 1,
@@ -672,7 +685,10 @@ pub fn fn_contains_token<N: Clone>(
     Ok(XdmValue::from_bool(contains))
 }
 
-pub fn fn_default_collation<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+pub fn fn_default_collation<'a, N: DataSourceNode<'a> + Clone>(
+    args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
     if !args.is_empty() {
         return Err(XPath31Error::function(
             "default-collation",
@@ -680,7 +696,7 @@ pub fn fn_default_collation<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue
         ));
     }
     Ok(XdmValue::from_string(
-        "http://www.w3.org/2005/xpath-functions/collation/codepoint".to_string(),
+        ctx.collations.default_uri().to_string(),
     ))
 }
 
@@ -694,7 +710,10 @@ pub fn fn_default_language<N: Clone>(args: Vec<XdmValue<N>>) -> Result<XdmValue<
     Ok(XdmValue::from_string("en".to_string()))
 }
 
-pub fn fn_collation_key<N: Clone>(mut args: Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error> {
+pub fn fn_collation_key<'a, N: DataSourceNode<'a> + Clone>(
+    mut args: Vec<XdmValue<N>>,
+    ctx: &EvaluationContext<'a, '_, N>,
+) -> Result<XdmValue<N>, XPath31Error> {
     if args.is_empty() || args.len() > 2 {
         return Err(XPath31Error::function(
             "collation-key",
@@ -702,12 +721,19 @@ pub fn fn_collation_key<N: Clone>(mut args: Vec<XdmValue<N>>) -> Result<XdmValue
         ));
     }
 
+    let collation_uri = if args.len() == 2 {
+        Some(args.remove(1).to_string_value())
+    } else {
+        None
+    };
+    let collation = ctx.collations.resolve(collation_uri.as_deref())?;
+
     let input = args.remove(0);
     if input.is_empty() {
         return Ok(XdmValue::from_string(String::new()));
     }
 
-    let key = input.to_string_value();
+    let key = collation.collation_key(&input.to_string_value());
     Ok(XdmValue::from_string(key))
 }
 
@@ -715,6 +741,7 @@ pub fn fn_collation_key<N: Clone>(mut args: Vec<XdmValue<N>>) -> Result<XdmValue
 mod tests {
     use super::*;
     use petty_xpath1::tests::MockNode;
+    use std::collections::HashMap;
 
     fn eval<N: Clone>(
         f: fn(Vec<XdmValue<N>>) -> Result<XdmValue<N>, XPath31Error>,
@@ -837,30 +864,74 @@ mod tests {
         assert_eq!(result.to_string_value(), "");
     }
 
+    fn compare_with(args: Vec<XdmValue<MockNode<'static>>>) -> XdmValue<MockNode<'static>> {
+        let vars = HashMap::new();
+        let ctx = EvaluationContext::new(None, None, &vars);
+        fn_compare(args, &ctx).unwrap()
+    }
+
     #[test]
     fn test_compare() {
-        let result: XdmValue<()> = fn_compare(vec![
+        let result = compare_with(vec![
             XdmValue::from_string("abc"),
             XdmValue::from_string("def"),
-        ])
-        .unwrap();
+        ]);
         assert_eq!(result.to_double(), -1.0);
 
-        let result: XdmValue<()> = fn_compare(vec![
+        let result = compare_with(vec![
             XdmValue::from_string("abc"),
             XdmValue::from_string("abc"),
-        ])
-        .unwrap();
+        ]);
         assert_eq!(result.to_double(), 0.0);
 
-        let result: XdmValue<()> = fn_compare(vec![
+        let result = compare_with(vec![
             XdmValue::from_string("def"),
             XdmValue::from_string("abc"),
-        ])
-        .unwrap();
+        ]);
         assert_eq!(result.to_double(), 1.0);
     }
 
+    #[test]
+    fn test_compare_with_collation() {
+        let codepoint = compare_with(vec![
+            XdmValue::from_string("éclair"),
+            XdmValue::from_string("zebra"),
+        ]);
+        assert_eq!(codepoint.to_double(), 1.0);
+
+        let locale = compare_with(vec![
+            XdmValue::from_string("éclair"),
+            XdmValue::from_string("zebra"),
+            XdmValue::from_string("http://www.w3.org/2013/collation/UCA?lang=fr"),
+        ]);
+        assert_eq!(locale.to_double(), -1.0);
+
+        let case_insensitive = compare_with(vec![
+            XdmValue::from_string("HELLO"),
+            XdmValue::from_string("hello"),
+            XdmValue::from_string(
+                "http://www.w3.org/2005/xpath-functions/collation/html-ascii-case-insensitive",
+            ),
+        ]);
+        assert_eq!(case_insensitive.to_double(), 0.0);
+    }
+
+    #[test]
+    fn test_compare_unknown_collation() {
+        let vars = HashMap::new();
+        let ctx: EvaluationContext<'_, '_, MockNode<'static>> =
+            EvaluationContext::new(None, None, &vars);
+        let result = fn_compare(
+            vec![
+                XdmValue::from_string("a"),
+                XdmValue::from_string("b"),
+                XdmValue::from_string("http://example.com/no-such-collation"),
+            ],
+            &ctx,
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_codepoints_to_string() {
         let result: XdmValue<()> = fn_codepoints_to_string(vec![XdmValue::from_items(vec![
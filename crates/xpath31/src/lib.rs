@@ -20,17 +20,21 @@
 //! ```
 
 pub mod ast;
+pub mod collation;
 pub mod engine;
 pub mod error;
 pub mod functions;
 pub mod operators;
 pub mod parser;
+pub mod resources;
 pub mod types;
 
 pub use ast::{ArrayConstructorKind, Expression, LookupKey, Param, Quantifier, SequenceType};
+pub use collation::{Collation, CollationRegistry};
 pub use engine::{EvaluationContext, evaluate};
 pub use error::XPath31Error;
 pub use parser::parse_expression;
+pub use resources::ResourceLoader;
 pub use types::{AtomicValue, XdmArray, XdmFunction, XdmItem, XdmMap, XdmValue};
 
 pub use petty_xpath1::{DataSourceNode, NodeType, QName};
@@ -0,0 +1,19 @@
+//! Access to the external resources read by `doc()`, `unparsed-text()` and
+//! `collection()`.
+//!
+//! The engine does no I/O of its own. A host that lets expressions read resources
+//! supplies a [`ResourceLoader`] with [`EvaluationContext::with_resources`], which
+//! also decides which URIs may be read at all; without one these functions report
+//! that loading is not available.
+//!
+//! [`EvaluationContext::with_resources`]: crate::EvaluationContext::with_resources
+
+use crate::error::XPath31Error;
+
+pub trait ResourceLoader {
+    /// Whether `function` may read `uri`; the error explains a refusal.
+    fn authorize(&self, function: &str, uri: &str) -> Result<(), XPath31Error>;
+
+    /// The text of the resource at `uri`, once `authorize` has allowed it.
+    fn load_text(&self, function: &str, uri: &str) -> Result<String, XPath31Error>;
+}
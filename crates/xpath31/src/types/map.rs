@@ -2,31 +2,32 @@ use super::{AtomicValue, XdmItem, XdmValue};
 use indexmap::IndexMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
+/// An immutable XDM map. The entries are shared, so cloning a map is cheap and
+/// updates copy them on write.
 #[derive(Debug, Clone)]
 pub struct XdmMap<N> {
-    entries: IndexMap<AtomicValue, XdmValue<N>>,
+    entries: Arc<IndexMap<AtomicValue, XdmValue<N>>>,
 }
 
 impl<N: Clone> XdmMap<N> {
     pub fn new() -> Self {
         Self {
-            entries: IndexMap::new(),
+            entries: Arc::new(IndexMap::new()),
         }
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            entries: IndexMap::with_capacity(capacity),
+            entries: Arc::new(IndexMap::with_capacity(capacity)),
         }
     }
 
     pub fn from_entries(entries: Vec<(AtomicValue, XdmValue<N>)>) -> Self {
-        let mut map = Self::with_capacity(entries.len());
-        for (key, value) in entries {
-            map.entries.insert(key, value);
+        Self {
+            entries: Arc::new(entries.into_iter().collect()),
         }
-        map
     }
 
     pub fn get(&self, key: &AtomicValue) -> Option<&XdmValue<N>> {
@@ -39,13 +40,13 @@ impl<N: Clone> XdmMap<N> {
 
     pub fn put(&self, key: AtomicValue, value: XdmValue<N>) -> Self {
         let mut new_map = self.clone();
-        new_map.entries.insert(key, value);
+        Arc::make_mut(&mut new_map.entries).insert(key, value);
         new_map
     }
 
     pub fn remove(&self, key: &AtomicValue) -> Self {
         let mut new_map = self.clone();
-        new_map.entries.shift_remove(key);
+        Arc::make_mut(&mut new_map.entries).shift_remove(key);
         new_map
     }
 
@@ -71,8 +72,9 @@ impl<N: Clone> XdmMap<N> {
 
     pub fn merge(&self, other: &XdmMap<N>) -> Self {
         let mut result = self.clone();
+        let entries = Arc::make_mut(&mut result.entries);
         for (k, v) in other.entries.iter() {
-            result.entries.insert(k.clone(), v.clone());
+            entries.insert(k.clone(), v.clone());
         }
         result
     }
@@ -104,7 +106,7 @@ impl<N: Eq + Clone> Eq for XdmMap<N> {}
 impl<N: Hash + Clone> Hash for XdmMap<N> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.entries.len().hash(state);
-        for (key, value) in &self.entries {
+        for (key, value) in self.entries.iter() {
             key.hash(state);
             value.hash(state);
         }
@@ -115,7 +117,7 @@ impl<N: fmt::Debug> fmt::Display for XdmMap<N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "map {{ ")?;
         let mut first = true;
-        for (k, v) in &self.entries {
+        for (k, v) in self.entries.iter() {
             if !first {
                 write!(f, ", ")?;
             }
@@ -105,12 +105,44 @@ pub enum SortDataType {
     Number,
 }
 
+/// The `case-order` of an `<xsl:sort>`: which case wins when strings differ only by case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseOrder {
+    UpperFirst,
+    LowerFirst,
+}
+
+impl CaseOrder {
+    /// Compares `a` and `b` case-insensitively using `compare`, breaking ties by case.
+    pub fn compare(
+        self,
+        a: &str,
+        b: &str,
+        compare: impl Fn(&str, &str) -> std::cmp::Ordering,
+    ) -> std::cmp::Ordering {
+        compare(&a.to_lowercase(), &b.to_lowercase()).then_with(|| {
+            a.chars()
+                .zip(b.chars())
+                .find(|(ca, cb)| ca != cb)
+                .map(|(ca, cb)| {
+                    let upper_first = ca.is_uppercase().cmp(&cb.is_uppercase()).reverse();
+                    match self {
+                        CaseOrder::UpperFirst => upper_first,
+                        CaseOrder::LowerFirst => upper_first.reverse(),
+                    }
+                })
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    }
+}
+
 /// Represents a compiled `<xsl:sort>` instruction.
 #[derive(Debug, Clone, PartialEq)]
 pub struct SortKey {
     pub select: Expression,
     pub order: SortOrder,
     pub data_type: SortDataType,
+    pub case_order: Option<CaseOrder>,
 }
 
 /// A part of an Attribute Value Template.
@@ -164,6 +196,9 @@ pub enum XsltInstruction {
     },
     ValueOf {
         select: Expression,
+        /// Set by `petty:normalize-space="yes"`: trims the value and collapses its inner
+        /// whitespace runs to single spaces.
+        normalize_space: bool,
     },
     CopyOf {
         select: Expression,
@@ -43,8 +43,23 @@ pub trait StylesheetBuilder {
 pub fn compile(
     full_xslt_str: &str,
     resource_base_path: PathBuf,
+) -> Result<CompiledStylesheet, XsltError> {
+    compile_with_theme(full_xslt_str, resource_base_path, None)
+}
+
+/// Compiles an XSLT stylesheet on top of a base `theme`. Attribute sets are merged
+/// over theme styles of the same name, and theme styles can be used directly in
+/// `use-attribute-sets`.
+pub fn compile_with_theme(
+    full_xslt_str: &str,
+    resource_base_path: PathBuf,
+    theme: Option<&Stylesheet>,
 ) -> Result<CompiledStylesheet, XsltError> {
     let mut builder = CompilerBuilder::new();
+    if let Some(theme) = theme {
+        builder.stylesheet.styles = theme.styles.clone();
+        builder.theme = Some(theme.clone());
+    }
     parser::parse_stylesheet_content(full_xslt_str, &mut builder)?;
     builder.finalize(resource_base_path)
 }
@@ -100,6 +115,8 @@ pub(crate) enum BuilderState {
 /// A stateful builder that constructs a `CompiledStylesheet` from parser events.
 pub struct CompilerBuilder {
     pub(crate) stylesheet: Stylesheet,
+    /// The base stylesheet the template's own definitions are layered on.
+    pub(crate) theme: Option<Stylesheet>,
     pub(crate) template_rules: HashMap<Option<String>, Vec<TemplateRule>>,
     pub(crate) named_templates: HashMap<String, Arc<NamedTemplate>>,
     pub(crate) role_template_modes: HashMap<String, String>,
@@ -113,6 +130,7 @@ impl CompilerBuilder {
     fn new() -> Self {
         Self {
             stylesheet: Stylesheet::default(),
+            theme: None,
             template_rules: HashMap::new(),
             named_templates: HashMap::new(),
             role_template_modes: HashMap::new(),
@@ -129,6 +147,9 @@ impl CompilerBuilder {
             self.stylesheet.default_page_master_name =
                 self.stylesheet.page_masters.keys().next().cloned();
         }
+        if let Some(theme) = &self.theme {
+            self.stylesheet.apply_theme(theme);
+        }
 
         for rules in self.template_rules.values_mut() {
             rules.sort_by(|a, b| {
@@ -1,4 +1,4 @@
-use crate::ast::{PreparsedTemplate, SortDataType, SortKey, SortOrder, XsltInstruction};
+use crate::ast::{CaseOrder, PreparsedTemplate, SortDataType, SortKey, SortOrder, XsltInstruction};
 use crate::compiler::{BuilderState, CompilerBuilder};
 use crate::error::XsltError;
 use crate::util::{
@@ -47,10 +47,16 @@ impl CompilerBuilder {
                 Some("number") => SortDataType::Number,
                 _ => SortDataType::Text,
             };
+            let case_order = match get_attr_owned_optional(&attrs, b"case-order")?.as_deref() {
+                Some("upper-first") => Some(CaseOrder::UpperFirst),
+                Some("lower-first") => Some(CaseOrder::LowerFirst),
+                _ => None,
+            };
             sort_keys.push(SortKey {
                 select: select_expr,
                 order,
                 data_type,
+                case_order,
             });
         } else {
             return Err(XsltError::TemplateStructure {
@@ -175,6 +175,14 @@ impl CompilerBuilder {
         current_state: BuilderState,
     ) -> Result<(), XsltError> {
         if let BuilderState::AttributeSet { name, style } = current_state {
+            let style = match self.theme.as_ref().and_then(|t| t.styles.get(&name)) {
+                Some(base) => {
+                    let mut merged = ElementStyle::clone(base);
+                    merged.merge(&style);
+                    merged
+                }
+                None => style,
+            };
             self.stylesheet.styles.insert(name, Arc::new(style));
         }
         Ok(())
@@ -75,6 +75,8 @@ impl CompilerBuilder {
         let select_str = get_attr_owned_required(&attrs, b"select", b"xsl:value-of", pos, source)?;
         let instr = XsltInstruction::ValueOf {
             select: self.parse_xpath_and_detect_features(&select_str)?,
+            normalize_space: get_attr_owned_optional(&attrs, b"petty:normalize-space")?
+                .is_some_and(|v| v == "yes"),
         };
         if let Some(parent) = self.instruction_stack.last_mut() {
             parent.push(instr);
@@ -19,10 +19,23 @@ struct VNodeData<'a> {
     attributes: Vec<usize>,
 }
 
+/// Options for building a [`JsonVDocument`].
+#[derive(Debug, Clone, Default)]
+pub struct JsonVDocumentOptions {
+    /// Keeps object keys in source order. By default they are sorted
+    /// alphabetically, so the element order does not depend on how the JSON
+    /// object was built.
+    pub preserve_key_order: bool,
+    /// The element name of the items of an array, by the array's key, e.g.
+    /// `orders` -> `order`. Arrays whose key is not listed use `item`.
+    pub item_names: HashMap<String, String>,
+}
+
 #[derive(Debug)]
 pub struct JsonVDocument<'a> {
     nodes: Vec<VNodeData<'a>>,
     parent_map: HashMap<usize, usize>,
+    options: &'a JsonVDocumentOptions,
     _lifetime_marker: std::marker::PhantomData<&'a Value>,
 }
 
@@ -37,9 +50,16 @@ pub struct JsonVNode<'a> {
 impl<'a> JsonVDocument<'a> {
     /// Parses a `serde_json::Value` into a navigable VDOM document.
     pub fn new(json_value: &'a Value) -> Self {
+        static DEFAULT_OPTIONS: std::sync::LazyLock<JsonVDocumentOptions> =
+            std::sync::LazyLock::new(JsonVDocumentOptions::default);
+        Self::with_options(json_value, &DEFAULT_OPTIONS)
+    }
+
+    pub fn with_options(json_value: &'a Value, options: &'a JsonVDocumentOptions) -> Self {
         let mut doc = JsonVDocument {
             nodes: Vec::new(),
             parent_map: HashMap::new(),
+            options,
             _lifetime_marker: std::marker::PhantomData,
         };
         doc.build(json_value);
@@ -51,7 +71,13 @@ impl<'a> JsonVDocument<'a> {
         JsonVNode { id: 0, tree: self }
     }
 
-    // REMOVED the hardcoded singularize function
+    /// The element name of the items of the array under `key`.
+    fn item_name(&self, key: &str) -> &'a str {
+        self.options
+            .item_names
+            .get(key)
+            .map_or("item", String::as_str)
+    }
 
     fn build(&mut self, json_value: &'a Value) {
         // Create the top-level root node (ID 0)
@@ -95,11 +121,13 @@ impl<'a> JsonVDocument<'a> {
                     attributes: Vec::new(),
                 });
 
-                // Collect keys and sort them alphabetically to ensure deterministic order
-                let mut sorted_keys: Vec<_> = obj.keys().map(|k| k.as_str()).collect();
-                sorted_keys.sort_unstable(); // Use unstable sort for efficiency
+                // Unless source order is kept, sort keys alphabetically to ensure deterministic order
+                let mut keys: Vec<_> = obj.keys().map(|k| k.as_str()).collect();
+                if !self.options.preserve_key_order {
+                    keys.sort_unstable(); // Use unstable sort for efficiency
+                }
 
-                for key_str in sorted_keys {
+                for key_str in keys {
                     let child_val = &obj[key_str];
 
                     if let Some(attr_name) = key_str.strip_prefix('@') {
@@ -123,8 +151,7 @@ impl<'a> JsonVDocument<'a> {
                         self.nodes[current_id].children.push(container_id);
 
                         // Create children for each item within the container.
-                        // Use "item" as the standard name for array elements.
-                        let item_name = "item";
+                        let item_name = self.item_name(key_str);
                         for item in arr {
                             if !item.is_null() {
                                 let item_id = self.build_recursive(item, container_id, item_name);
@@ -155,8 +182,7 @@ impl<'a> JsonVDocument<'a> {
                     attributes: vec![],
                 });
 
-                // Use "item" as the standard name for array elements
-                let item_name = "item";
+                let item_name = self.item_name(name);
                 for item in arr {
                     if !item.is_null() {
                         let child_id = self.build_recursive(item, current_id, item_name);
@@ -477,6 +503,63 @@ mod tests {
         assert_eq!(root.string_value(), "HelloWorld");
     }
 
+    #[test]
+    fn test_json_vdom_preserves_key_order_when_enabled() {
+        let data: Value = serde_json::from_str(
+            r#"{"row": {"@z": "1", "@a": "2", "zeta": 1, "alpha": 2, "mid": 3}}"#,
+        )
+        .unwrap();
+        let names = |doc: &JsonVDocument<'_>| {
+            let row = doc.root_node().children().next().unwrap();
+            let local = |n: JsonVNode<'_>| n.name().unwrap().local_part.to_string();
+            (
+                row.children().map(local).collect::<Vec<_>>(),
+                row.attributes().map(local).collect::<Vec<_>>(),
+            )
+        };
+
+        let sorted = JsonVDocument::new(&data);
+        assert_eq!(names(&sorted).0, ["alpha", "mid", "zeta"]);
+        assert_eq!(names(&sorted).1, ["a", "z"]);
+
+        let options = JsonVDocumentOptions {
+            preserve_key_order: true,
+            ..Default::default()
+        };
+        let preserved = JsonVDocument::with_options(&data, &options);
+        assert_eq!(names(&preserved).0, ["zeta", "alpha", "mid"]);
+        assert_eq!(names(&preserved).1, ["z", "a"]);
+        assert_eq!(preserved.root_node().string_value(), "123");
+    }
+
+    #[test]
+    fn test_json_vdom_names_array_items_by_key() {
+        let data = json!({
+            "shop": {
+                "orders": [ { "id": "o1" }, { "id": "o2" } ],
+                "tags": [ "new", "sale" ]
+            }
+        });
+        let options = JsonVDocumentOptions {
+            item_names: HashMap::from([("orders".to_string(), "order".to_string())]),
+            ..Default::default()
+        };
+        let doc = JsonVDocument::with_options(&data, &options);
+        let shop = doc.root_node().children().next().unwrap();
+        let item_names = |container: JsonVNode<'_>| {
+            container
+                .children()
+                .map(|item| item.name().unwrap().local_part.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let containers: Vec<_> = shop.children().collect();
+        assert_eq!(containers[0].name().unwrap().local_part, "orders");
+        assert_eq!(item_names(containers[0]), ["order", "order"]);
+        assert_eq!(containers[1].name().unwrap().local_part, "tags");
+        assert_eq!(item_names(containers[1]), ["item", "item"]);
+    }
+
     #[test]
     fn test_json_vdom_primitive_array() {
         let data = json!({
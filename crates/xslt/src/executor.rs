@@ -298,7 +298,10 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor<'s, 'a, N> {
     ) -> Result<(), ExecutionError> {
         match instruction {
             XsltInstruction::Text(text) => executor_handlers::literals::handle_text(text, builder),
-            XsltInstruction::ValueOf { select } => {
+            XsltInstruction::ValueOf {
+                select,
+                normalize_space,
+            } => {
                 let merged_vars = self.get_merged_variables();
                 let e_ctx = self.get_eval_context(
                     context_node,
@@ -306,7 +309,12 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor<'s, 'a, N> {
                     context_position,
                     context_size,
                 );
-                executor_handlers::literals::handle_value_of(select, &e_ctx, builder)?
+                executor_handlers::literals::handle_value_of(
+                    select,
+                    *normalize_space,
+                    &e_ctx,
+                    builder,
+                )?
             }
             XsltInstruction::CopyOf { select } => {
                 let result = {
@@ -607,7 +615,13 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor<'s, 'a, N> {
                         let num_b = val_b.to_number();
                         num_a.partial_cmp(&num_b).unwrap_or(Ordering::Equal)
                     }
-                    SortDataType::Text => val_a.to_string().cmp(&val_b.to_string()),
+                    SortDataType::Text => {
+                        let (text_a, text_b) = (val_a.to_string(), val_b.to_string());
+                        match key.case_order {
+                            Some(case_order) => case_order.compare(&text_a, &text_b, str::cmp),
+                            None => text_a.cmp(&text_b),
+                        }
+                    }
                 };
                 let final_ordering = if key.order == SortOrder::Descending {
                     ordering.reverse()
@@ -15,12 +15,17 @@ pub(crate) fn handle_text(text: &str, builder: &mut dyn OutputBuilder) {
 
 pub(crate) fn handle_value_of<'a, N: DataSourceNode<'a> + 'a>(
     select: &petty_xpath1::Expression,
+    normalize_space: bool,
     e_ctx: &engine::EvaluationContext<'a, '_, N>,
     builder: &mut dyn OutputBuilder,
 ) -> Result<(), ExecutionError> {
     let result = petty_xpath1::evaluate(select, e_ctx)?;
     let content = result.to_string();
-    builder.add_text(&content);
+    if normalize_space {
+        builder.add_text(&crate::util::normalize_space(&content));
+    } else {
+        builder.add_text(&content);
+    }
     Ok(())
 }
 
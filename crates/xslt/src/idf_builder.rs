@@ -136,6 +136,7 @@ impl OutputBuilder for IdfBuilder {
                 id: styles.id.clone(),
                 style_sets: styles.style_sets.clone(),
                 style_override: styles.style_override.clone(),
+                classes: Vec::new(),
             },
             children: vec![],
         };
@@ -155,6 +156,7 @@ impl OutputBuilder for IdfBuilder {
                 id: styles.id.clone(),
                 style_sets: styles.style_sets.clone(),
                 style_override: styles.style_override.clone(),
+                classes: Vec::new(),
             },
             children: vec![],
         };
@@ -170,6 +172,7 @@ impl OutputBuilder for IdfBuilder {
                 id: styles.id.clone(),
                 style_sets: styles.style_sets.clone(),
                 style_override: styles.style_override.clone(),
+                classes: Vec::new(),
             },
             children: vec![],
         };
@@ -185,6 +188,7 @@ impl OutputBuilder for IdfBuilder {
                 id: styles.id.clone(),
                 style_sets: styles.style_sets.clone(),
                 style_override: styles.style_override.clone(),
+                classes: Vec::new(),
             },
             start: None,
             children: vec![],
@@ -201,6 +205,7 @@ impl OutputBuilder for IdfBuilder {
                 id: styles.id.clone(),
                 style_sets: styles.style_sets.clone(),
                 style_override: styles.style_override.clone(),
+                classes: Vec::new(),
             },
             children: vec![],
         };
@@ -217,6 +222,7 @@ impl OutputBuilder for IdfBuilder {
                 id: styles.id.clone(),
                 style_sets: styles.style_sets.clone(),
                 style_override: styles.style_override.clone(),
+                classes: Vec::new(),
             },
         };
         self.node_stack.push(node);
@@ -232,10 +238,12 @@ impl OutputBuilder for IdfBuilder {
                 id: styles.id.clone(),
                 style_sets: styles.style_sets.clone(),
                 style_override: styles.style_override.clone(),
+                classes: Vec::new(),
             },
             columns: vec![],
             header: None,
             body: Box::new(TableBody::default()),
+            footer: None,
         };
         self.node_stack.push(node);
     }
@@ -381,6 +389,7 @@ impl OutputBuilder for IdfBuilder {
                 id: styles.id.clone(),
                 style_sets: styles.style_sets.clone(),
                 style_override: styles.style_override.clone(),
+                classes: Vec::new(),
             },
             level,
             children: vec![],
@@ -1,6 +1,6 @@
 use super::executor::{self, ExecutionError};
 use super::{ast, compiler};
-use crate::datasources::json::JsonVDocument;
+use crate::datasources::json::{JsonVDocument, JsonVDocumentOptions};
 use crate::datasources::xml::XmlDocument;
 use crate::error::XsltError;
 use petty_idf::IRNode;
@@ -45,7 +45,11 @@ impl petty_template_core::TemplateExecutor for XsltTemplate {
             DataSourceFormat::Json => {
                 let json_data: serde_json::Value = serde_json::from_str(data_source_str)
                     .map_err(|e: serde_json::Error| XsltError::from(e))?;
-                let doc = JsonVDocument::new(&json_data);
+                let options = JsonVDocumentOptions {
+                    preserve_key_order: config.preserve_key_order,
+                    ..Default::default()
+                };
+                let doc = JsonVDocument::with_options(&json_data, &options);
                 let root_node = doc.root_node();
                 let mut executor =
                     executor::TemplateExecutor::new(&self.compiled, root_node, config.strict)?;
@@ -79,31 +83,49 @@ impl TemplateParser for XsltParser {
         template_source: &str,
         resource_base_path: PathBuf,
     ) -> Result<TemplateFeatures, TemplateError> {
-        let compiled_stylesheet = compiler::compile(template_source, resource_base_path)
-            .map_err(|e: XsltError| -> TemplateError { e.into() })?;
-        let compiled_arc = Arc::new(compiled_stylesheet);
+        build_features(compiler::compile(template_source, resource_base_path))
+    }
+
+    fn parse_with_theme(
+        &self,
+        template_source: &str,
+        resource_base_path: PathBuf,
+        theme: &Stylesheet,
+    ) -> Result<TemplateFeatures, TemplateError> {
+        build_features(compiler::compile_with_theme(
+            template_source,
+            resource_base_path,
+            Some(theme),
+        ))
+    }
+}
 
-        // --- Create Main Template ---
-        let main_template: Arc<dyn CompiledTemplate> = Arc::new(XsltTemplate {
+fn build_features(
+    compiled: Result<ast::CompiledStylesheet, XsltError>,
+) -> Result<TemplateFeatures, TemplateError> {
+    let compiled_stylesheet = compiled.map_err(|e: XsltError| -> TemplateError { e.into() })?;
+    let compiled_arc = Arc::new(compiled_stylesheet);
+
+    // --- Create Main Template ---
+    let main_template: Arc<dyn CompiledTemplate> = Arc::new(XsltTemplate {
+        compiled: Arc::clone(&compiled_arc),
+        entry_mode: None, // `None` mode means start with the default template rules.
+    });
+
+    // --- Create Role Templates ---
+    let mut role_templates = HashMap::new();
+    for (role_name, mode_name) in &compiled_arc.role_template_modes {
+        let role_template: Arc<dyn CompiledTemplate> = Arc::new(XsltTemplate {
             compiled: Arc::clone(&compiled_arc),
-            entry_mode: None, // `None` mode means start with the default template rules.
+            entry_mode: Some(mode_name.clone()),
         });
-
-        // --- Create Role Templates ---
-        let mut role_templates = HashMap::new();
-        for (role_name, mode_name) in &compiled_arc.role_template_modes {
-            let role_template: Arc<dyn CompiledTemplate> = Arc::new(XsltTemplate {
-                compiled: Arc::clone(&compiled_arc),
-                entry_mode: Some(mode_name.clone()),
-            });
-            role_templates.insert(role_name.clone(), role_template);
-        }
-
-        Ok(TemplateFeatures {
-            main_template,
-            role_templates,
-        })
+        role_templates.insert(role_name.clone(), role_template);
     }
+
+    Ok(TemplateFeatures {
+        main_template,
+        role_templates,
+    })
 }
 
 #[cfg(test)]
@@ -201,6 +223,7 @@ mod tests {
         let non_strict_config = ExecutionConfig {
             format: DataSourceFormat::Xml,
             strict: false,
+            ..Default::default()
         };
         let result_non_strict = compiled.execute(data, non_strict_config).unwrap();
         assert!(result_non_strict[0].get_text_content().is_empty());
@@ -209,6 +232,7 @@ mod tests {
         let strict_config = ExecutionConfig {
             format: DataSourceFormat::Xml,
             strict: true,
+            ..Default::default()
         };
         let result_strict = compiled.execute(data, strict_config);
         assert!(result_strict.is_err());
@@ -239,6 +263,7 @@ mod tests {
         let non_strict_config = ExecutionConfig {
             format: DataSourceFormat::Xml,
             strict: false,
+            ..Default::default()
         };
         assert!(compiled.execute(data, non_strict_config).is_ok());
 
@@ -246,6 +271,7 @@ mod tests {
         let strict_config = ExecutionConfig {
             format: DataSourceFormat::Xml,
             strict: true,
+            ..Default::default()
         };
         let result_strict = compiled.execute(data, strict_config);
         assert!(result_strict.is_err());
@@ -423,6 +449,34 @@ mod tests {
         assert_eq!(root_children[2].get_text_content().trim(), "Widget - 15.50");
     }
 
+    #[test]
+    fn test_xslt_sort_case_order() {
+        let xslt = r#"
+            <xsl:stylesheet version="1.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <root>
+                        <xsl:for-each select="data/w">
+                            <xsl:sort select="." case-order="lower-first"/>
+                            <p><xsl:value-of select="."/></p>
+                        </xsl:for-each>
+                    </root>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        let data = "<data><w>banana</w><w>Apple</w><w>apple</w></data>";
+
+        let parser = XsltParser;
+        let compiled = parser.parse(xslt, PathBuf::new()).unwrap().main_template;
+        let result_tree = compiled.execute(data, ExecutionConfig::default()).unwrap();
+
+        let root_children = match &result_tree[0] {
+            IRNode::Block { children, .. } => children,
+            _ => panic!("Expected root block"),
+        };
+        let words: Vec<String> = root_children.iter().map(|c| c.get_text_content()).collect();
+        assert_eq!(words, vec!["apple", "Apple", "banana"]);
+    }
+
     #[test]
     fn test_xslt_attribute() {
         let xslt = r#"
@@ -876,6 +930,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_value_of_normalize_space() {
+        let xslt = r#"<xsl:stylesheet version="1.0"
+                xmlns:xsl="http://www.w3.org/1999/XSL/Transform"
+                xmlns:petty="https://petty.rs/ns/1.0">
+            <xsl:template match="/">
+                <p>[<xsl:value-of select="name"/>]</p>
+                <p>[<xsl:value-of select="name" petty:normalize-space="yes"/>]</p>
+            </xsl:template>
+        </xsl:stylesheet>"#;
+
+        let compiled = XsltParser
+            .parse(xslt, PathBuf::new())
+            .unwrap()
+            .main_template;
+        let config = ExecutionConfig {
+            format: DataSourceFormat::Json,
+            ..Default::default()
+        };
+        let result = compiled
+            .execute(r#"{ "name": "  Ada \n\t Lovelace  " }"#, config)
+            .unwrap();
+
+        assert_eq!(result[0].get_text_content(), "[  Ada \n\t Lovelace  ]");
+        assert_eq!(result[1].get_text_content(), "[Ada Lovelace]");
+    }
+
     // Helper to get all text from an IRNode for simple assertions
     trait TestTextContent {
         fn get_text_content(&self) -> String;
@@ -13,8 +13,12 @@ const STYLE_PROPERTIES: &[&[u8]] = &[
     b"font-size",
     b"font-weight",
     b"font-style",
+    b"font-source",
     b"line-height",
     b"text-align",
+    b"white-space",
+    b"text-overflow",
+    b"max-lines",
     b"color",
     b"background-color",
     b"border",
@@ -32,14 +36,22 @@ const STYLE_PROPERTIES: &[&[u8]] = &[
     b"padding-left",
     b"width",
     b"height",
+    b"aspect-ratio",
+    b"keep-together",
     b"list-style-type",
+    b"border-spacing",
+    b"border-collapse",
     b"flex-direction",
     b"flex-wrap",
     b"justify-content",
     b"align-items",
+    b"gap",
+    b"row-gap",
+    b"column-gap",
     b"flex-grow",
     b"flex-shrink",
     b"flex-basis",
+    b"flex",
     b"align-self",
 ];
 
@@ -82,6 +94,15 @@ pub(crate) fn get_line_col_from_pos(xml_str: &str, pos: usize) -> (usize, usize)
     (line, col)
 }
 
+/// Trims `text` and collapses each inner run of XML whitespace to a single space, as
+/// XPath's `normalize-space()` does. Other Unicode spaces, like no-break spaces, are kept.
+pub fn normalize_space(text: &str) -> String {
+    text.split([' ', '\t', '\n', '\r'])
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Parses an Attribute Value Template string like "Hello {user/name}" into parts.
 pub(crate) fn parse_avt(
     builder: &mut CompilerBuilder,
@@ -35,8 +35,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 pub use petty_xslt::ast::{
-    AttributeValueTemplate, AvtPart, Param, PreparsedStyles, SortDataType, SortKey, SortOrder,
-    When, WithParam,
+    AttributeValueTemplate, AvtPart, CaseOrder, Param, PreparsedStyles, SortDataType, SortKey,
+    SortOrder, When, WithParam,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -448,6 +448,9 @@ pub struct ShadowAttribute {
 pub enum Xslt3Instruction {
     Text(String),
     TextValueTemplate(TextValueTemplate),
+    /// `petty:raw-text`: content emitted exactly as written, bypassing text value
+    /// templates, whitespace stripping and character maps.
+    RawText(String),
 
     ContentTag {
         tag_name: Vec<u8>,
@@ -499,6 +502,9 @@ pub enum Xslt3Instruction {
     ValueOf {
         select: Expression,
         separator: Option<AttributeValueTemplate>,
+        /// Set by `petty:normalize-space="yes"`: trims the value and collapses its inner
+        /// whitespace runs to single spaces.
+        normalize_space: bool,
     },
     CopyOf {
         select: Expression,
@@ -702,6 +708,7 @@ pub struct SortKey3 {
     pub select: Expression,
     pub order: SortOrder,
     pub data_type: SortDataType,
+    pub case_order: Option<CaseOrder>,
     pub collation: Option<String>,
     pub stable: Option<bool>,
 }
@@ -75,6 +75,7 @@ pub enum BuilderState3 {
     },
     InstructionBody(OwnedAttributes),
     XslText,
+    RawText,
     // XSLT 3.0 specific states
     Try {
         body_instructions: Vec<Xslt3Instruction>,
@@ -472,6 +473,7 @@ impl StylesheetBuilder3 for CompilerBuilder3 {
     ) -> Result<(), Xslt3Error> {
         let name_binding = e.name();
         let name = name_binding.as_ref();
+        self.reject_raw_text_child(name)?;
         self.push_expand_text(&attrs);
 
         if let Some(BuilderState3::Sortable {
@@ -593,6 +595,9 @@ impl StylesheetBuilder3 for CompilerBuilder3 {
             b"xsl:text" => {
                 self.state_stack.push(BuilderState3::XslText);
             }
+            b"petty:raw-text" => {
+                self.state_stack.push(BuilderState3::RawText);
+            }
             b"xsl:if" => {
                 self.state_stack.push(BuilderState3::InstructionBody(attrs));
             }
@@ -668,6 +673,7 @@ impl StylesheetBuilder3 for CompilerBuilder3 {
     ) -> Result<(), Xslt3Error> {
         let name_binding = e.name();
         let name = name_binding.as_ref();
+        self.reject_raw_text_child(name)?;
 
         if name != b"xsl:sort"
             && name != b"xsl:merge-key"
@@ -718,6 +724,12 @@ impl StylesheetBuilder3 for CompilerBuilder3 {
             b"xsl:break" => {
                 self.handle_break(&attrs, pos, source)?;
             }
+            b"xsl:message" => {
+                self.handle_message_start(&attrs)?;
+                if let Some(state) = self.state_stack.pop() {
+                    self.handle_message_end(state, Vec::new())?;
+                }
+            }
             b"xsl:output" => {
                 self.handle_output(&attrs)?;
             }
@@ -910,6 +922,9 @@ impl StylesheetBuilder3 for CompilerBuilder3 {
             b"xsl:text" => {
                 self.handle_text_end(body)?;
             }
+            b"petty:raw-text" => {
+                self.handle_raw_text_end(body)?;
+            }
             b"xsl:if" => {
                 self.handle_if_end(current_state, body, pos, source)?;
             }
@@ -1005,6 +1020,13 @@ impl StylesheetBuilder3 for CompilerBuilder3 {
             *saw_non_sort_child = true;
         }
 
+        if matches!(self.state_stack.last(), Some(BuilderState3::RawText)) {
+            if let Some(body) = self.instruction_stack.last_mut() {
+                body.push(Xslt3Instruction::Text(text));
+            }
+            return Ok(());
+        }
+
         let is_in_xsl_text = matches!(self.state_stack.last(), Some(BuilderState3::XslText));
         let should_expand = self.current_expand_text() && !is_in_xsl_text;
 
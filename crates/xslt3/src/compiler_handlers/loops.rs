@@ -5,7 +5,7 @@ use crate::compiler::{
     BuilderState3, CompilerBuilder3, OwnedAttributes, get_attr_optional, get_attr_required,
 };
 use crate::error::Xslt3Error;
-use petty_xslt::ast::{SortDataType, SortOrder};
+use petty_xslt::ast::{CaseOrder, SortDataType, SortOrder};
 
 impl CompilerBuilder3 {
     pub(crate) fn handle_for_each_end(
@@ -172,6 +172,12 @@ impl CompilerBuilder3 {
             })
             .unwrap_or(SortDataType::Text);
 
+        let case_order = match get_attr_optional(attrs, b"case-order")?.as_deref() {
+            Some("upper-first") => Some(CaseOrder::UpperFirst),
+            Some("lower-first") => Some(CaseOrder::LowerFirst),
+            _ => None,
+        };
+
         let collation = get_attr_optional(attrs, b"collation")?;
         let stable = get_attr_optional(attrs, b"stable")?.map(|s| s == "yes");
 
@@ -179,12 +185,15 @@ impl CompilerBuilder3 {
             select,
             order,
             data_type,
+            case_order,
             collation,
             stable,
         };
 
-        if let Some(BuilderState3::Sortable { sort_keys, .. }) = self.state_stack.last_mut() {
-            sort_keys.push(sort_key);
+        match self.state_stack.last_mut() {
+            Some(BuilderState3::Sortable { sort_keys, .. })
+            | Some(BuilderState3::PerformSort { sort_keys, .. }) => sort_keys.push(sort_key),
+            _ => {}
         }
         Ok(())
     }
@@ -340,6 +340,36 @@ impl CompilerBuilder3 {
         Ok(())
     }
 
+    /// Joins the verbatim text collected inside `petty:raw-text` into a single instruction.
+    pub(crate) fn handle_raw_text_end(
+        &mut self,
+        body: Vec<Xslt3Instruction>,
+    ) -> Result<(), Xslt3Error> {
+        let text: String = body
+            .into_iter()
+            .filter_map(|instr| match instr {
+                Xslt3Instruction::Text(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+        if let Some(parent) = self.instruction_stack.last_mut() {
+            parent.push(Xslt3Instruction::RawText(text));
+        }
+        Ok(())
+    }
+
+    /// `petty:raw-text` may only hold character data, so its content can never
+    /// introduce structure into the result tree.
+    pub(crate) fn reject_raw_text_child(&self, name: &[u8]) -> Result<(), Xslt3Error> {
+        if matches!(self.state_stack.last(), Some(BuilderState3::RawText)) {
+            return Err(Xslt3Error::compile(format!(
+                "petty:raw-text may only contain text, found element <{}>",
+                String::from_utf8_lossy(name)
+            )));
+        }
+        Ok(())
+    }
+
     pub(crate) fn handle_copy_end(
         &mut self,
         current_state: BuilderState3,
@@ -263,7 +263,14 @@ impl CompilerBuilder3 {
             None
         };
 
-        let instr = Xslt3Instruction::ValueOf { select, separator };
+        let normalize_space =
+            get_attr_optional(attrs, b"petty:normalize-space")?.is_some_and(|v| v == "yes");
+
+        let instr = Xslt3Instruction::ValueOf {
+            select,
+            separator,
+            normalize_space,
+        };
 
         if let Some(parent) = self.instruction_stack.last_mut() {
             parent.push(instr);
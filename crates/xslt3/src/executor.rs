@@ -28,18 +28,23 @@ use crate::ast::{
     TextValueTemplate, TvtPart, Xslt3Instruction,
 };
 use crate::error::Xslt3Error;
+use crate::messages::MessageSink;
 use crate::streaming::{parse_and_stream, parse_and_stream_with_accumulators};
 use petty_idf::IRNode;
-use petty_traits::ResourceProvider;
+use petty_template_core::NumberLocale;
+use petty_traits::{ResourceProvider, SharedResourceData};
 use petty_xpath1::XPathValue;
 use petty_xpath1::datasource::{DataSourceNode, NodeType};
+use petty_xpath31::functions::datetime::DateTime;
 use petty_xpath31::types::{XdmItem, XdmValue};
+use petty_xpath31::{CollationRegistry, ResourceLoader, XPath31Error};
 use petty_xslt::ast::{AttributeValueTemplate, PreparsedStyles};
 use petty_xslt::idf_builder::IdfBuilder;
 use petty_xslt::output::{OutputBuilder, OutputSink};
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 #[derive(Debug, Clone)]
 pub enum ExecutionError {
@@ -54,6 +59,7 @@ pub enum ExecutionError {
     Break,
     NextIteration(Vec<(String, String)>),
     NoMatchingTemplate { node_name: String },
+    StepLimitExceeded(u64),
 }
 
 impl std::fmt::Display for ExecutionError {
@@ -82,6 +88,9 @@ impl std::fmt::Display for ExecutionError {
                     node_name
                 )
             }
+            ExecutionError::StepLimitExceeded(limit) => {
+                write!(f, "Step limit of {} instructions exceeded", limit)
+            }
         }
     }
 }
@@ -124,10 +133,20 @@ pub struct TemplateExecutor3<'s, 'a, N: DataSourceNode<'a>> {
     pub(crate) regex_match: Option<String>,
     pub(crate) regex_groups: Vec<String>,
     pub(crate) resource_provider: Option<Arc<dyn ResourceProvider>>,
+    pub(crate) safe_mode: bool,
+    pub(crate) allowed_resources: Option<Arc<dyn ResourceProvider>>,
     pub(crate) output_sink: Option<Arc<dyn OutputSink>>,
+    pub(crate) message_sink: Option<Arc<dyn MessageSink>>,
     pub(crate) active_result_documents: Vec<String>,
     pub(crate) last_constructed_value: Option<XdmValue<N>>,
-    pub(crate) key_indexes: HashMap<String, HashMap<String, Vec<N>>>,
+    /// The `xsl:key` indexes, built once per source document, as the map values that
+    /// `key()` looks values up in.
+    pub(crate) key_indexes: HashMap<String, XdmValue<N>>,
+    pub(crate) collations: Option<Arc<CollationRegistry>>,
+    pub(crate) number_locale: NumberLocale,
+    pub(crate) step_limit: Option<u64>,
+    pub(crate) steps: u64,
+    pub(crate) current_time: Option<SystemTime>,
     _marker: PhantomData<&'a ()>,
 }
 
@@ -159,10 +178,18 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
             regex_match: None,
             regex_groups: Vec::new(),
             resource_provider: None,
+            safe_mode: false,
+            allowed_resources: None,
             output_sink: None,
+            message_sink: None,
             active_result_documents: Vec::new(),
             last_constructed_value: None,
             key_indexes: HashMap::new(),
+            collations: None,
+            number_locale: NumberLocale::default(),
+            step_limit: None,
+            steps: 0,
+            current_time: None,
             _marker: PhantomData,
         };
 
@@ -181,16 +208,29 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
 
             self.build_key_index_recursive(
                 self.root_node,
-                &key_decl.match_pattern.clone(),
-                &key_decl.use_expr.clone(),
+                &key_decl.match_pattern,
+                &key_decl.use_expr,
                 &mut index,
             )?;
 
-            self.key_indexes.insert(key_name.clone(), index);
+            let entries = index
+                .into_iter()
+                .map(|(value, nodes)| {
+                    (
+                        petty_xpath31::types::AtomicValue::String(value),
+                        XdmValue::from_nodes(nodes),
+                    )
+                })
+                .collect();
+            let map = petty_xpath31::XdmMap::from_entries(entries);
+            self.key_indexes
+                .insert(key_name.clone(), XdmValue::from_map(map));
         }
         Ok(())
     }
 
+    /// Indexes every node matching `match_pattern` under each value of its `use_expr`;
+    /// a node whose `use` value is a sequence is found by any of its items.
     fn build_key_index_recursive(
         &self,
         node: N,
@@ -199,11 +239,21 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         index: &mut HashMap<String, Vec<N>>,
     ) -> Result<(), Xslt3Error> {
         if self.node_matches_key_pattern(node, match_pattern) {
-            let key_value = self
-                .evaluate_xpath31(use_expr, node, 1, 1)
+            let key_values = self
+                .evaluate_xpath31_xdm(use_expr, node, 1, 1)
                 .map_err(|e| Xslt3Error::runtime(e.to_string()))?;
 
-            index.entry(key_value).or_default().push(node);
+            for item in key_values.items() {
+                let value = match item {
+                    XdmItem::Node(n) => n.string_value(),
+                    XdmItem::Atomic(a) => a.to_string_value(),
+                    _ => continue,
+                };
+                let nodes = index.entry(value).or_default();
+                if nodes.last() != Some(&node) {
+                    nodes.push(node);
+                }
+            }
         }
 
         for child in node.children() {
@@ -214,17 +264,9 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
     }
 
     fn node_matches_key_pattern(&self, node: N, pattern: &str) -> bool {
-        let pattern = pattern.trim();
-
-        if pattern == "*" {
-            return node.node_type() == petty_xpath1::datasource::NodeType::Element;
-        }
-
-        if let Some(name) = node.name() {
-            name.local_part == pattern
-        } else {
-            false
-        }
+        pattern
+            .split('|')
+            .any(|alternative| self.pattern_matches(alternative.trim(), node))
     }
 
     /// Set the resource provider for loading external documents (xsl:stream, xsl:source-document).
@@ -238,6 +280,58 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         self.resource_provider = Some(provider);
     }
 
+    /// Sandbox the stylesheet: `doc()`, `unparsed-text()`, `collection()`,
+    /// `xsl:stream` and `xsl:source-document` may then only read the resources that
+    /// [`with_allowed_resources`](Self::with_allowed_resources) provides, never the
+    /// resource provider.
+    pub fn set_safe_mode(&mut self, enabled: bool) {
+        self.safe_mode = enabled;
+    }
+
+    /// Set the resources a stylesheet may still read in safe mode.
+    pub fn with_allowed_resources(mut self, resources: Arc<dyn ResourceProvider>) -> Self {
+        self.allowed_resources = Some(resources);
+        self
+    }
+
+    /// Loads `uri` for `reader`, the function or instruction reading it.
+    fn load_resource(&self, reader: &str, uri: &str) -> Result<SharedResourceData, ExecutionError> {
+        let provider = if self.safe_mode {
+            self.allowed_resources
+                .as_ref()
+                .filter(|resources| resources.exists(uri))
+                .ok_or_else(|| {
+                    ExecutionError::Resource(format!(
+                        "{} may not read '{}' in safe mode",
+                        reader, uri
+                    ))
+                })?
+        } else {
+            self.resource_provider.as_ref().ok_or_else(|| {
+                ExecutionError::Stream(format!("No resource provider configured for {}", reader))
+            })?
+        };
+        provider
+            .load(uri)
+            .map_err(|e| ExecutionError::Resource(format!("Failed to load '{}': {}", uri, e)))
+    }
+
+    /// Abort execution with [`ExecutionError::StepLimitExceeded`] once more than `limit`
+    /// instructions have run. `xsl:try` cannot catch this error.
+    pub fn set_step_limit(&mut self, limit: Option<u64>) {
+        self.step_limit = limit;
+    }
+
+    /// Fix the time `current-dateTime()`, `current-date()` and `current-time()` report,
+    /// instead of reading the system clock. Global variables are evaluated again with it.
+    pub fn set_current_time(&mut self, time: Option<SystemTime>) -> Result<(), Xslt3Error> {
+        if time == self.current_time {
+            return Ok(());
+        }
+        self.current_time = time;
+        self.initialize_global_variables()
+    }
+
     /// Set the output sink for multi-document output (xsl:result-document).
     pub fn with_output_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
         self.output_sink = Some(sink);
@@ -249,6 +343,74 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         self.output_sink = Some(sink);
     }
 
+    /// Set the sink that receives `xsl:message` output.
+    pub fn with_message_sink(mut self, sink: Arc<dyn MessageSink>) -> Self {
+        self.message_sink = Some(sink);
+        self
+    }
+
+    /// Set the message sink (mutable version).
+    pub fn set_message_sink(&mut self, sink: Arc<dyn MessageSink>) {
+        self.message_sink = Some(sink);
+    }
+
+    /// Set the collation registry used by `xsl:sort`, `fn:compare` and `fn:sort`.
+    pub fn with_collations(mut self, collations: Arc<CollationRegistry>) -> Self {
+        self.collations = Some(collations);
+        self
+    }
+
+    pub(crate) fn collations(&self) -> &CollationRegistry {
+        self.collations
+            .as_deref()
+            .unwrap_or_else(|| CollationRegistry::builtin())
+    }
+
+    /// Set the separators `format-number` writes when the stylesheet neither names nor
+    /// declares a default decimal format. Global variables are evaluated again with them.
+    pub fn set_number_locale(&mut self, locale: NumberLocale) -> Result<(), Xslt3Error> {
+        if locale == self.number_locale {
+            return Ok(());
+        }
+        self.number_locale = locale;
+        self.initialize_global_variables()
+    }
+
+    /// Set a global variable from the host, visible to every expression as `$name`.
+    ///
+    /// This is how values reach a stylesheet's `xsl:param`s. The stylesheet's own global
+    /// variables are evaluated when the executor is created, so an injected value of the
+    /// same name replaces them but is not visible while they are evaluated.
+    pub fn set_global_variable(&mut self, name: impl Into<String>, value: impl Into<XdmValue<N>>) {
+        if let Some(globals) = self.variable_stack.first_mut() {
+            globals.insert(name.into(), value.into());
+        }
+    }
+
+    /// Set a global variable from the host (builder version).
+    pub fn with_global_variable(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<XdmValue<N>>,
+    ) -> Self {
+        self.set_global_variable(name, value);
+        self
+    }
+
+    /// Set a global variable from any serializable value, converted as `parse-json`
+    /// converts JSON: structs and maps become XDM maps, sequences become arrays.
+    pub fn set_global_variable_serde<T: serde::Serialize + ?Sized>(
+        &mut self,
+        name: impl Into<String>,
+        value: &T,
+    ) -> Result<(), Xslt3Error> {
+        let json = serde_json::to_value(value).map_err(|e| Xslt3Error::runtime(e.to_string()))?;
+        let value = petty_xpath31::functions::json_value_to_xdm(&json)
+            .map_err(|e| Xslt3Error::runtime(e.to_string()))?;
+        self.set_global_variable(name, value);
+        Ok(())
+    }
+
     fn initialize_global_variables(&mut self) -> Result<(), Xslt3Error> {
         for (name, var) in &self.stylesheet.global_variables {
             let value = self
@@ -375,10 +537,20 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         context_size: usize,
         builder: &mut dyn OutputBuilder,
     ) -> Result<(), ExecutionError> {
+        self.steps += 1;
+        if let Some(limit) = self.step_limit
+            && self.steps > limit
+        {
+            return Err(ExecutionError::StepLimitExceeded(limit));
+        }
+
         match instruction {
             Xslt3Instruction::Text(text) => {
                 self.handle_text(text, builder);
             }
+            Xslt3Instruction::RawText(text) => {
+                builder.add_text(text);
+            }
             Xslt3Instruction::TextValueTemplate(tvt) => {
                 self.handle_text_value_template(
                     tvt,
@@ -388,10 +560,15 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
                     builder,
                 )?;
             }
-            Xslt3Instruction::ValueOf { select, separator } => {
+            Xslt3Instruction::ValueOf {
+                select,
+                separator,
+                normalize_space,
+            } => {
                 self.handle_value_of(
                     select,
                     separator,
+                    *normalize_space,
                     context_node,
                     context_position,
                     context_size,
@@ -455,7 +632,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
                 group_adjacent,
                 group_starting_with,
                 group_ending_with,
-                sort_keys: _,
+                sort_keys,
                 body,
             } => {
                 self.handle_for_each_group(
@@ -464,6 +641,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
                     group_adjacent.as_ref(),
                     group_starting_with.as_deref(),
                     group_ending_with.as_deref(),
+                    sort_keys,
                     body,
                     context_node,
                     context_position,
@@ -934,12 +1112,6 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
     ) -> Result<XdmValue<N>, ExecutionError> {
         let mut xdm_vars = self.get_merged_variables();
 
-        if let Some(key) = &self.current_grouping_key {
-            xdm_vars.insert(
-                "::current-grouping-key".to_string(),
-                XdmValue::from_string(key.clone()),
-            );
-        }
         if !self.current_group.is_empty() {
             let items: Vec<XdmItem<N>> = self
                 .current_group
@@ -947,6 +1119,12 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
                 .map(|n| XdmItem::Node(*n))
                 .collect();
             xdm_vars.insert("::current-group".to_string(), XdmValue::from_items(items));
+            // Groups formed by group-starting-with/-ending-with have no key.
+            let key = match &self.current_grouping_key {
+                Some(key) => XdmValue::from_string(key.clone()),
+                None => XdmValue::empty(),
+            };
+            xdm_vars.insert("::current-grouping-key".to_string(), key);
         }
 
         if let Some(key) = &self.current_merge_key {
@@ -974,17 +1152,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         }
 
         for (key_name, key_index) in &self.key_indexes {
-            let mut map_entries = Vec::new();
-            for (value, nodes) in key_index {
-                let key = petty_xpath31::types::AtomicValue::String(value.clone());
-                let node_items: Vec<XdmItem<N>> = nodes.iter().map(|n| XdmItem::Node(*n)).collect();
-                map_entries.push((key, XdmValue::from_items(node_items)));
-            }
-            let xdm_map = petty_xpath31::XdmMap::from_entries(map_entries);
-            xdm_vars.insert(
-                format!("::key-index:{}", key_name),
-                XdmValue::from_items(vec![XdmItem::Map(xdm_map)]),
-            );
+            xdm_vars.insert(format!("::key-index:{}", key_name), key_index.clone());
         }
 
         for (name, df) in &self.stylesheet.decimal_formats {
@@ -995,6 +1163,22 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
             let encoded = encode_decimal_format(df);
             xdm_vars.insert(var_name, XdmValue::from_string(encoded));
         }
+        if let Some(time) = self.current_time {
+            xdm_vars.insert(
+                "::current-dateTime".to_string(),
+                XdmValue::from_string(DateTime::from_system_time(time).to_string()),
+            );
+        }
+        if self.number_locale != NumberLocale::default() {
+            let encoded = format!(
+                "ds={}\x1Fgs={}",
+                self.number_locale.decimal_separator, self.number_locale.grouping_separator
+            );
+            xdm_vars.insert(
+                "::number-locale".to_string(),
+                XdmValue::from_string(encoded),
+            );
+        }
 
         let context_item = if let Some(ref match_str) = self.regex_match {
             Some(XdmItem::Atomic(petty_xpath31::types::AtomicValue::String(
@@ -1005,7 +1189,10 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         };
         let root = Some(self.root_node);
 
-        let xdm_ctx = petty_xpath31::EvaluationContext::new(context_item, root, &xdm_vars);
+        let resources = XPathResources(self);
+        let xdm_ctx = petty_xpath31::EvaluationContext::new(context_item, root, &xdm_vars)
+            .with_collations(self.collations())
+            .with_resources(&resources);
 
         let result = petty_xpath31::evaluate(expr, &xdm_ctx, &xdm_vars)?;
         Ok(result)
@@ -1360,13 +1547,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
     ) -> Result<(), ExecutionError> {
         let href_value = self.evaluate_avt(href, context_node, context_position, context_size)?;
 
-        let provider = self.resource_provider.as_ref().ok_or_else(|| {
-            ExecutionError::Stream("No resource provider configured for xsl:stream".into())
-        })?;
-
-        let xml_content = provider.load(&href_value).map_err(|e| {
-            ExecutionError::Resource(format!("Failed to load '{}': {}", href_value, e))
-        })?;
+        let xml_content = self.load_resource("xsl:stream", &href_value)?;
 
         let xml_str = String::from_utf8_lossy(&xml_content);
 
@@ -1488,13 +1669,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
     ) -> Result<(), ExecutionError> {
         let href_value = self.evaluate_avt(href, context_node, context_position, context_size)?;
 
-        let provider = self.resource_provider.as_ref().ok_or_else(|| {
-            ExecutionError::Stream("No resource provider configured for xsl:source-document".into())
-        })?;
-
-        let xml_content = provider.load(&href_value).map_err(|e| {
-            ExecutionError::Resource(format!("Failed to load '{}': {}", href_value, e))
-        })?;
+        let xml_content = self.load_resource("xsl:source-document", &href_value)?;
 
         let xml_str = String::from_utf8_lossy(&xml_content);
 
@@ -1724,3 +1899,31 @@ fn apply_character_maps(
     }
     result
 }
+
+/// The executor's resources as `doc()`, `unparsed-text()` and `collection()` read them.
+struct XPathResources<'e, 's, 'a, N: DataSourceNode<'a>>(&'e TemplateExecutor3<'s, 'a, N>);
+
+impl<'a, N: DataSourceNode<'a> + 'a> ResourceLoader for XPathResources<'_, '_, 'a, N> {
+    fn authorize(&self, function: &str, uri: &str) -> Result<(), XPath31Error> {
+        let executor = self.0;
+        let allowed = executor
+            .allowed_resources
+            .as_ref()
+            .is_some_and(|resources| resources.exists(uri));
+        if executor.safe_mode && !allowed {
+            return Err(XPath31Error::function(
+                function,
+                format!("access to '{}' is denied in safe mode", uri),
+            ));
+        }
+        Ok(())
+    }
+
+    fn load_text(&self, function: &str, uri: &str) -> Result<String, XPath31Error> {
+        let bytes = self
+            .0
+            .load_resource(function, uri)
+            .map_err(|e| XPath31Error::function(function, e.to_string()))?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
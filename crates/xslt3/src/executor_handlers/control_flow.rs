@@ -114,6 +114,14 @@ impl ErrorInfo {
                 line_number: None,
                 column_number: None,
             },
+            ExecutionError::StepLimitExceeded(limit) => Self {
+                code: "XTDE0000".to_string(),
+                description: format!("Step limit of {} instructions exceeded", limit),
+                value: None,
+                module: None,
+                line_number: None,
+                column_number: None,
+            },
         }
     }
 }
@@ -241,6 +249,9 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         context_size: usize,
         builder: &mut dyn OutputBuilder,
     ) -> Result<(), ExecutionError> {
+        if let ExecutionError::StepLimitExceeded(_) = error {
+            return Err(error.clone());
+        }
         let error_info = ErrorInfo::from_execution_error(error);
         let error_code = error_info.code.clone();
 
@@ -4,9 +4,9 @@
 
 use crate::ast::PreparsedTemplate;
 use crate::executor::{ExecutionError, TemplateExecutor3};
+use crate::executor_handlers::xslt3_elements::TextCollector;
 use petty_xpath1::datasource::DataSourceNode;
 use petty_xpath31::Expression;
-use petty_xslt::idf_builder::IdfBuilder;
 
 impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
     pub(crate) fn handle_assert(
@@ -21,7 +21,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
             self.evaluate_xpath31(test, context_node, context_position, context_size)?;
         if condition.is_empty() || condition == "false" || condition == "0" {
             let msg = if let Some(msg_body) = message {
-                let mut msg_builder = IdfBuilder::new();
+                let mut msg_builder = TextCollector::new();
                 self.execute_template(
                     msg_body,
                     context_node,
@@ -29,7 +29,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
                     context_size,
                     &mut msg_builder,
                 )?;
-                "Assertion failed".to_string()
+                msg_builder.into_text()
             } else {
                 "Assertion failed".to_string()
             };
@@ -51,7 +51,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         let msg = if let Some(sel) = select {
             self.evaluate_xpath31(sel, context_node, context_position, context_size)?
         } else if let Some(body_template) = body {
-            let mut msg_builder = IdfBuilder::new();
+            let mut msg_builder = TextCollector::new();
             self.execute_template(
                 body_template,
                 context_node,
@@ -59,11 +59,17 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
                 context_size,
                 &mut msg_builder,
             )?;
-            String::new()
+            msg_builder.into_text()
         } else {
             String::new()
         };
 
+        match &self.message_sink {
+            Some(sink) => sink.message(&msg, terminate),
+            None if terminate => log::error!("xsl:message: {}", msg),
+            None => log::info!("xsl:message: {}", msg),
+        }
+
         if terminate {
             let code = error_code.clone().unwrap_or_else(|| "XTMM9000".to_string());
             return Err(ExecutionError::DynamicError { code, message: msg });
@@ -255,6 +255,10 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         Ok(())
     }
 
+    /// Computes the number list for `node` as XSLT defines it for each `level`.
+    ///
+    /// `count` defaults to nodes with the same name as `node`; `from` bounds the
+    /// search, so numbering restarts inside each node it matches.
     fn compute_number_value(
         &self,
         node: N,
@@ -262,28 +266,55 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         count: Option<&str>,
         from: Option<&str>,
     ) -> Result<Vec<usize>, ExecutionError> {
+        let node_name = node.name().map(|q| q.local_part).unwrap_or("");
+        let count_pattern = count.unwrap_or(node_name);
+
         match level {
-            NumberLevel::Single => {
-                let num = self.count_preceding_siblings_for_number(node, count, from)?;
-                Ok(vec![num])
+            NumberLevel::Single => Ok(self
+                .counted_ancestors_for_number(node, count_pattern, from)
+                .into_iter()
+                .next()
+                .map(|counted| {
+                    vec![self.count_preceding_siblings_for_number(counted, count_pattern)]
+                })
+                .unwrap_or_default()),
+            NumberLevel::Multiple => {
+                let mut numbers: Vec<usize> = self
+                    .counted_ancestors_for_number(node, count_pattern, from)
+                    .into_iter()
+                    .map(|counted| self.count_preceding_siblings_for_number(counted, count_pattern))
+                    .collect();
+                numbers.reverse();
+                Ok(numbers)
             }
-            NumberLevel::Multiple => self.count_ancestors_for_number(node, count, from),
             NumberLevel::Any => {
-                let num = self.count_preceding_any_for_number(node, count, from)?;
-                Ok(vec![num])
+                let mut position = 0;
+                let root = self.find_document_root_for_number(node);
+                self.count_in_document_order(root, node, count_pattern, from, &mut position);
+                Ok(if position > 0 { vec![position] } else { vec![] })
             }
         }
     }
 
-    fn count_preceding_siblings_for_number(
-        &self,
-        node: N,
-        count: Option<&str>,
-        _from: Option<&str>,
-    ) -> Result<usize, ExecutionError> {
-        let node_name = node.name().map(|q| q.local_part).unwrap_or("");
-        let count_pattern = count.unwrap_or(node_name);
+    /// The ancestor-or-self nodes of `node` matching `count`, nearest first, stopping
+    /// at the nearest ancestor matching `from`.
+    fn counted_ancestors_for_number(&self, node: N, count: &str, from: Option<&str>) -> Vec<N> {
+        let mut counted = Vec::new();
+        let mut current = Some(node);
+        while let Some(n) = current {
+            if from.is_some_and(|from| self.node_matches_number_pattern(n, from)) {
+                break;
+            }
+            if self.node_matches_number_pattern(n, count) {
+                counted.push(n);
+            }
+            current = n.parent();
+        }
+        counted
+    }
 
+    /// One more than the number of preceding siblings of `node` matching `count`.
+    fn count_preceding_siblings_for_number(&self, node: N, count: &str) -> usize {
         let mut position = 1;
 
         if let Some(parent) = node.parent() {
@@ -291,75 +322,37 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
                 if sibling == node {
                     break;
                 }
-                if self.node_matches_number_pattern(sibling, count_pattern) {
+                if self.node_matches_number_pattern(sibling, count) {
                     position += 1;
                 }
             }
         }
 
-        Ok(position)
-    }
-
-    fn count_ancestors_for_number(
-        &self,
-        node: N,
-        count: Option<&str>,
-        _from: Option<&str>,
-    ) -> Result<Vec<usize>, ExecutionError> {
-        let mut numbers = Vec::new();
-        let node_name = node.name().map(|q| q.local_part).unwrap_or("");
-        let count_pattern = count.unwrap_or(node_name);
-
-        let mut current = Some(node);
-        while let Some(n) = current {
-            if self.node_matches_number_pattern(n, count_pattern) {
-                let pos = self.count_preceding_siblings_for_number(n, Some(count_pattern), None)?;
-                numbers.push(pos);
-            }
-            current = n.parent();
-        }
-
-        numbers.reverse();
-        Ok(numbers)
-    }
-
-    fn count_preceding_any_for_number(
-        &self,
-        node: N,
-        count: Option<&str>,
-        _from: Option<&str>,
-    ) -> Result<usize, ExecutionError> {
-        let node_name = node.name().map(|q| q.local_part).unwrap_or("");
-        let count_pattern = count.unwrap_or(node_name);
-
-        let mut position = 0;
-        let root = self.find_document_root_for_number(node);
-
-        self.count_in_document_order(root, node, count_pattern, &mut position);
-
-        Ok(position)
+        position
     }
 
+    /// Counts the nodes matching `pattern` up to and including `target` in document
+    /// order, restarting from zero at each node matching `from`.
     fn count_in_document_order(
         &self,
         current: N,
         target: N,
         pattern: &str,
+        from: Option<&str>,
         count: &mut usize,
     ) -> bool {
-        if current == target {
-            if self.node_matches_number_pattern(current, pattern) {
-                *count += 1;
-            }
-            return true;
+        if from.is_some_and(|from| self.node_matches_number_pattern(current, from)) {
+            *count = 0;
         }
-
         if self.node_matches_number_pattern(current, pattern) {
             *count += 1;
         }
+        if current == target {
+            return true;
+        }
 
         for child in current.children() {
-            if self.count_in_document_order(child, target, pattern, count) {
+            if self.count_in_document_order(child, target, pattern, from, count) {
                 return true;
             }
         }
@@ -376,17 +369,14 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
     }
 
     fn node_matches_number_pattern(&self, node: N, pattern: &str) -> bool {
-        if pattern == "*" {
-            return node.node_type() == petty_xpath1::datasource::NodeType::Element;
-        }
-
-        if let Some(name) = node.name() {
-            name.local_part == pattern
-        } else {
-            false
-        }
+        pattern
+            .split('|')
+            .any(|alternative| self.pattern_matches(alternative.trim(), node))
     }
 
+    /// Formats `numbers` with a format picture such as `1.a`, `(i)` or `1.1.`: leading
+    /// and trailing punctuation wrap the result, and the alphanumeric tokens and the
+    /// separators between them apply in turn, the last of each repeating as needed.
     fn format_numbers(
         &self,
         numbers: &[usize],
@@ -394,31 +384,43 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         grouping_separator: &Option<String>,
         grouping_size: &Option<u32>,
     ) -> String {
-        if numbers.is_empty() {
-            return String::new();
-        }
-
-        let format_tokens = self.parse_format_string(format);
-        let mut result = String::new();
+        let is_format_token = |t: &String| t.chars().next().is_some_and(|c| c.is_alphanumeric());
+        let mut tokens = self.parse_format_string(format);
+        let prefix = if tokens.first().is_some_and(|t| !is_format_token(t)) {
+            tokens.remove(0)
+        } else {
+            String::new()
+        };
+        let suffix = if tokens.last().is_some_and(|t| !is_format_token(t)) {
+            tokens.pop().unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let (formats, separators): (Vec<_>, Vec<_>) =
+            tokens.iter().partition(|t| is_format_token(t));
 
+        let mut result = prefix;
         for (i, &num) in numbers.iter().enumerate() {
             if i > 0 {
-                if let Some(sep) = format_tokens.get(i * 2 - 1) {
-                    result.push_str(sep);
-                } else {
-                    result.push('.');
-                }
+                let sep = separators
+                    .get(i - 1)
+                    .or(separators.last())
+                    .map_or(".", |s| s.as_str());
+                result.push_str(sep);
             }
 
-            let fmt = format_tokens
-                .get(i * 2)
-                .or_else(|| format_tokens.first())
-                .map(|s| s.as_str())
-                .unwrap_or("1");
-
-            let formatted = self.format_single_number(num, fmt, grouping_separator, grouping_size);
-            result.push_str(&formatted);
+            let fmt = formats
+                .get(i)
+                .or(formats.last())
+                .map_or("1", |s| s.as_str());
+            result.push_str(&self.format_single_number(
+                num,
+                fmt,
+                grouping_separator,
+                grouping_size,
+            ));
         }
+        result.push_str(&suffix);
 
         result
     }
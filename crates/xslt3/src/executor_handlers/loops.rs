@@ -6,16 +6,37 @@ use crate::ast::{IterateParam, NextIterationParam, PreparsedTemplate, SortKey3,
 use crate::executor::{ExecutionError, TemplateExecutor3};
 use petty_xpath1::datasource::DataSourceNode;
 use petty_xpath31::types::XdmValue;
-use petty_xslt::ast::{AttributeValueTemplate, SortDataType, SortOrder};
+use petty_xslt::ast::{AttributeValueTemplate, CaseOrder, SortDataType, SortOrder};
 use petty_xslt::output::OutputBuilder;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
-enum SortValue {
+/// Groups formed by xsl:for-each-group, each with its grouping key if it has one.
+type Groups<N> = Vec<(Option<String>, Vec<N>)>;
+
+pub(crate) enum SortValue {
     Text(String),
     Number(f64),
 }
 
+fn compare_sort_values(
+    a: &SortValue,
+    b: &SortValue,
+    case_order: Option<CaseOrder>,
+    compare_text: impl Fn(&str, &str) -> Ordering,
+) -> Ordering {
+    match (a, b) {
+        (SortValue::Text(ta), SortValue::Text(tb)) => match case_order {
+            Some(case_order) => case_order.compare(ta, tb, compare_text),
+            None => compare_text(ta, tb),
+        },
+        (SortValue::Number(na), SortValue::Number(nb)) => {
+            na.partial_cmp(nb).unwrap_or(Ordering::Equal)
+        }
+        _ => Ordering::Equal,
+    }
+}
+
 impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
     pub(crate) fn handle_for_each_group(
         &mut self,
@@ -24,6 +45,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         group_adjacent: Option<&petty_xpath31::Expression>,
         group_starting_with: Option<&str>,
         group_ending_with: Option<&str>,
+        sort_keys: &[SortKey3],
         body: &PreparsedTemplate,
         context_node: N,
         context_position: usize,
@@ -33,33 +55,47 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         let nodes =
             self.evaluate_xpath31_nodes(select, context_node, context_position, context_size)?;
 
-        let groups_with_keys: Vec<(String, Vec<N>)> =
-            if let Some(group_adjacent_expr) = group_adjacent {
-                self.group_adjacent(&nodes, group_adjacent_expr)?
-            } else if let Some(pattern) = group_starting_with {
-                self.group_starting_with(&nodes, pattern)?
-            } else if let Some(pattern) = group_ending_with {
-                self.group_ending_with(&nodes, pattern)?
-            } else if let Some(group_by_expr) = group_by {
-                self.group_by(&nodes, group_by_expr)?
-            } else {
-                self.group_by_string_value(&nodes)
-            };
+        let groups_with_keys: Groups<N> = if let Some(group_adjacent_expr) = group_adjacent {
+            self.group_adjacent(&nodes, group_adjacent_expr)?
+        } else if let Some(pattern) = group_starting_with {
+            self.group_starting_with(&nodes, pattern)?
+        } else if let Some(pattern) = group_ending_with {
+            self.group_ending_with(&nodes, pattern)?
+        } else if let Some(group_by_expr) = group_by {
+            self.group_by(&nodes, group_by_expr)?
+        } else {
+            self.group_by_string_value(&nodes)
+        };
+        let groups_with_keys = self.sort_groups(groups_with_keys, sort_keys, context_node)?;
+
+        // The body may itself group, so the enclosing group is put back afterwards.
+        let outer_key = self.current_grouping_key.take();
+        let outer_group = std::mem::take(&mut self.current_group);
+        let result = self.execute_groups(groups_with_keys, body, context_node, builder);
+        self.current_grouping_key = outer_key;
+        self.current_group = outer_group;
+        result
+    }
 
+    /// Runs `body` once per group, with the group as `current-group()` and its
+    /// key, if any, as `current-grouping-key()`.
+    fn execute_groups(
+        &mut self,
+        groups_with_keys: Groups<N>,
+        body: &PreparsedTemplate,
+        context_node: N,
+        builder: &mut dyn OutputBuilder,
+    ) -> Result<(), ExecutionError> {
         let group_count = groups_with_keys.len();
         for (i, (key, group_nodes)) in groups_with_keys.into_iter().enumerate() {
-            self.current_grouping_key = Some(key);
-            self.current_group = group_nodes.clone();
+            self.current_grouping_key = key;
+            self.current_group = group_nodes;
 
-            let first_node = group_nodes.first().copied().unwrap_or(context_node);
+            let first_node = self.current_group.first().copied().unwrap_or(context_node);
             self.push_scope();
             self.execute_template(body, first_node, i + 1, group_count, builder)?;
             self.pop_scope();
         }
-
-        self.current_grouping_key = None;
-        self.current_group.clear();
-
         Ok(())
     }
 
@@ -67,7 +103,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         &mut self,
         nodes: &[N],
         group_by_expr: &petty_xpath31::Expression,
-    ) -> Result<Vec<(String, Vec<N>)>, ExecutionError> {
+    ) -> Result<Groups<N>, ExecutionError> {
         let mut groups: HashMap<String, Vec<N>> = HashMap::new();
         let mut group_order: Vec<String> = Vec::new();
 
@@ -83,12 +119,12 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
             .into_iter()
             .map(|key| {
                 let nodes = groups.remove(&key).unwrap_or_default();
-                (key, nodes)
+                (Some(key), nodes)
             })
             .collect())
     }
 
-    fn group_by_string_value(&self, nodes: &[N]) -> Vec<(String, Vec<N>)> {
+    fn group_by_string_value(&self, nodes: &[N]) -> Groups<N> {
         let mut groups: HashMap<String, Vec<N>> = HashMap::new();
         let mut group_order: Vec<String> = Vec::new();
 
@@ -104,7 +140,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
             .into_iter()
             .map(|key| {
                 let nodes = groups.remove(&key).unwrap_or_default();
-                (key, nodes)
+                (Some(key), nodes)
             })
             .collect()
     }
@@ -113,15 +149,15 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         &mut self,
         nodes: &[N],
         group_adjacent_expr: &petty_xpath31::Expression,
-    ) -> Result<Vec<(String, Vec<N>)>, ExecutionError> {
-        let mut result: Vec<(String, Vec<N>)> = Vec::new();
+    ) -> Result<Groups<N>, ExecutionError> {
+        let mut result: Groups<N> = Vec::new();
 
         for node in nodes {
             let key = self.evaluate_xpath31(group_adjacent_expr, *node, 1, 1)?;
 
             match result.last_mut() {
-                Some(last_group) if last_group.0 == key => last_group.1.push(*node),
-                _ => result.push((key, vec![*node])),
+                Some((Some(last_key), group)) if *last_key == key => group.push(*node),
+                _ => result.push((Some(key), vec![*node])),
             }
         }
 
@@ -132,16 +168,15 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         &mut self,
         nodes: &[N],
         pattern: &str,
-    ) -> Result<Vec<(String, Vec<N>)>, ExecutionError> {
-        let mut result: Vec<(String, Vec<N>)> = Vec::new();
-        let mut group_counter = 0;
+    ) -> Result<Groups<N>, ExecutionError> {
+        // Pattern-based groups have no grouping key.
+        let mut result: Groups<N> = Vec::new();
 
         for node in nodes {
             let matches_pattern = self.node_matches_pattern(*node, pattern)?;
 
             if matches_pattern || result.is_empty() {
-                group_counter += 1;
-                result.push((format!("group-{}", group_counter), vec![*node]));
+                result.push((None, vec![*node]));
             } else if let Some(last_group) = result.last_mut() {
                 last_group.1.push(*node);
             }
@@ -154,26 +189,20 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         &mut self,
         nodes: &[N],
         pattern: &str,
-    ) -> Result<Vec<(String, Vec<N>)>, ExecutionError> {
-        let mut result: Vec<(String, Vec<N>)> = Vec::new();
+    ) -> Result<Groups<N>, ExecutionError> {
+        let mut result: Groups<N> = Vec::new();
         let mut current_group: Vec<N> = Vec::new();
-        let mut group_counter = 0;
 
         for node in nodes {
             current_group.push(*node);
 
             if self.node_matches_pattern(*node, pattern)? {
-                group_counter += 1;
-                result.push((
-                    format!("group-{}", group_counter),
-                    std::mem::take(&mut current_group),
-                ));
+                result.push((None, std::mem::take(&mut current_group)));
             }
         }
 
         if !current_group.is_empty() {
-            group_counter += 1;
-            result.push((format!("group-{}", group_counter), current_group));
+            result.push((None, current_group));
         }
 
         Ok(result)
@@ -199,43 +228,48 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         Ok(node_name == pattern)
     }
 
-    fn apply_sort_keys(
-        &mut self,
-        mut nodes: Vec<N>,
+    /// Evaluates each sort key for `node`, evaluated at `position` within `size` items.
+    fn sort_values(
+        &self,
+        node: N,
+        position: usize,
+        size: usize,
         sort_keys: &[SortKey3],
-    ) -> Result<Vec<N>, ExecutionError> {
-        if sort_keys.is_empty() {
-            return Ok(nodes);
-        }
-
-        let mut sort_data: Vec<(N, Vec<SortValue>)> = Vec::with_capacity(nodes.len());
-
-        for node in nodes.drain(..) {
-            let mut values = Vec::with_capacity(sort_keys.len());
-            for key in sort_keys {
-                let val = self.evaluate_xpath31(&key.select, node, 1, 1)?;
-                let sort_val = match key.data_type {
+    ) -> Result<Vec<SortValue>, ExecutionError> {
+        sort_keys
+            .iter()
+            .map(|key| {
+                let val = self.evaluate_xpath31(&key.select, node, position, size)?;
+                Ok(match key.data_type {
                     SortDataType::Number => {
-                        SortValue::Number(val.parse::<f64>().unwrap_or(f64::NAN))
+                        SortValue::Number(val.trim().parse::<f64>().unwrap_or(f64::NAN))
                     }
                     SortDataType::Text => SortValue::Text(val),
-                };
-                values.push(sort_val);
-            }
-            sort_data.push((node, values));
-        }
+                })
+            })
+            .collect()
+    }
 
-        sort_data.sort_by(|a, b| {
-            for (i, (val_a, val_b)) in a.1.iter().zip(b.1.iter()).enumerate() {
-                let cmp = match (val_a, val_b) {
-                    (SortValue::Text(ta), SortValue::Text(tb)) => ta.cmp(tb),
-                    (SortValue::Number(na), SortValue::Number(nb)) => {
-                        na.partial_cmp(nb).unwrap_or(Ordering::Equal)
-                    }
-                    _ => Ordering::Equal,
-                };
+    /// Sorts `items` by their precomputed sort values, honouring each key's
+    /// `order`, `case-order` and `collation`. The sort is stable.
+    pub(crate) fn sort_by_values<T>(
+        &self,
+        items: &mut [(T, Vec<SortValue>)],
+        sort_keys: &[SortKey3],
+    ) -> Result<(), ExecutionError> {
+        let collations = sort_keys
+            .iter()
+            .map(|key| self.collations().resolve(key.collation.as_deref()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        items.sort_by(|a, b| {
+            for (i, key) in sort_keys.iter().enumerate() {
+                let collation = collations[i].as_ref();
+                let cmp = compare_sort_values(&a.1[i], &b.1[i], key.case_order, |x, y| {
+                    collation.compare(x, y)
+                });
                 if cmp != Ordering::Equal {
-                    return if sort_keys[i].order == SortOrder::Descending {
+                    return if key.order == SortOrder::Descending {
                         cmp.reverse()
                     } else {
                         cmp
@@ -244,10 +278,66 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
             }
             Ordering::Equal
         });
+        Ok(())
+    }
 
+    pub(crate) fn apply_sort_keys(
+        &self,
+        nodes: Vec<N>,
+        sort_keys: &[SortKey3],
+    ) -> Result<Vec<N>, ExecutionError> {
+        if sort_keys.is_empty() {
+            return Ok(nodes);
+        }
+
+        let size = nodes.len();
+        let mut sort_data = nodes
+            .into_iter()
+            .enumerate()
+            .map(|(i, node)| Ok((node, self.sort_values(node, i + 1, size, sort_keys)?)))
+            .collect::<Result<Vec<_>, ExecutionError>>()?;
+
+        self.sort_by_values(&mut sort_data, sort_keys)?;
         Ok(sort_data.into_iter().map(|(node, _)| node).collect())
     }
 
+    /// Sorts groups for `xsl:for-each-group`, evaluating keys with each group
+    /// as the current group and its first item as the context.
+    fn sort_groups(
+        &mut self,
+        groups: Groups<N>,
+        sort_keys: &[SortKey3],
+        context_node: N,
+    ) -> Result<Groups<N>, ExecutionError> {
+        if sort_keys.is_empty() {
+            return Ok(groups);
+        }
+
+        let outer_key = self.current_grouping_key.take();
+        let outer_group = std::mem::take(&mut self.current_group);
+        let size = groups.len();
+        let mut sort_data = Vec::with_capacity(size);
+        let mut result = Ok(());
+        for (i, (key, group_nodes)) in groups.into_iter().enumerate() {
+            self.current_grouping_key = key.clone();
+            self.current_group = group_nodes.clone();
+            let first_node = group_nodes.first().copied().unwrap_or(context_node);
+            match self.sort_values(first_node, i + 1, size, sort_keys) {
+                Ok(values) => sort_data.push(((key, group_nodes), values)),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        self.current_grouping_key = outer_key;
+        self.current_group = outer_group;
+        result?;
+
+        self.sort_by_values(&mut sort_data, sort_keys)?;
+        Ok(sort_data.into_iter().map(|(group, _)| group).collect())
+    }
+
     pub(crate) fn handle_for_each(
         &mut self,
         select: &petty_xpath31::Expression,
@@ -432,51 +522,60 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
 mod tests {
     use super::*;
 
-    fn compare_sort_values(a: &SortValue, b: &SortValue) -> Ordering {
-        match (a, b) {
-            (SortValue::Text(ta), SortValue::Text(tb)) => ta.cmp(tb),
-            (SortValue::Number(na), SortValue::Number(nb)) => {
-                na.partial_cmp(nb).unwrap_or(Ordering::Equal)
-            }
-            _ => Ordering::Equal,
-        }
+    fn compare(a: &SortValue, b: &SortValue) -> Ordering {
+        compare_sort_values(a, b, None, str::cmp)
     }
 
     #[test]
     fn test_sort_value_text_comparison() {
         let a = SortValue::Text("apple".to_string());
         let b = SortValue::Text("banana".to_string());
-        assert_eq!(compare_sort_values(&a, &b), Ordering::Less);
+        assert_eq!(compare(&a, &b), Ordering::Less);
 
         let c = SortValue::Text("zebra".to_string());
-        assert_eq!(compare_sort_values(&c, &a), Ordering::Greater);
+        assert_eq!(compare(&c, &a), Ordering::Greater);
     }
 
     #[test]
     fn test_sort_value_number_comparison() {
         let a = SortValue::Number(10.0);
         let b = SortValue::Number(2.0);
-        assert_eq!(compare_sort_values(&a, &b), Ordering::Greater);
+        assert_eq!(compare(&a, &b), Ordering::Greater);
 
         let c = SortValue::Number(-5.0);
-        assert_eq!(compare_sort_values(&c, &a), Ordering::Less);
+        assert_eq!(compare(&c, &a), Ordering::Less);
     }
 
     #[test]
     fn test_sort_value_number_nan_handling() {
         let a = SortValue::Number(f64::NAN);
         let b = SortValue::Number(5.0);
-        assert_eq!(compare_sort_values(&a, &b), Ordering::Equal);
+        assert_eq!(compare(&a, &b), Ordering::Equal);
     }
 
     #[test]
     fn test_sort_value_equal() {
         let a = SortValue::Text("same".to_string());
         let b = SortValue::Text("same".to_string());
-        assert_eq!(compare_sort_values(&a, &b), Ordering::Equal);
+        assert_eq!(compare(&a, &b), Ordering::Equal);
 
         let c = SortValue::Number(42.0);
         let d = SortValue::Number(42.0);
-        assert_eq!(compare_sort_values(&c, &d), Ordering::Equal);
+        assert_eq!(compare(&c, &d), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_sort_value_case_order() {
+        let upper = SortValue::Text("Apple".to_string());
+        let lower = SortValue::Text("apple".to_string());
+        let banana = SortValue::Text("banana".to_string());
+
+        let upper_first = |a, b| compare_sort_values(a, b, Some(CaseOrder::UpperFirst), str::cmp);
+        let lower_first = |a, b| compare_sort_values(a, b, Some(CaseOrder::LowerFirst), str::cmp);
+
+        assert_eq!(upper_first(&upper, &lower), Ordering::Less);
+        assert_eq!(lower_first(&upper, &lower), Ordering::Greater);
+        assert_eq!(upper_first(&banana, &upper), Ordering::Greater);
+        assert_eq!(lower_first(&lower, &banana), Ordering::Less);
     }
 }
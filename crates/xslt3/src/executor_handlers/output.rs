@@ -31,12 +31,17 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
         &mut self,
         select: &Expression,
         separator: &Option<AttributeValueTemplate>,
+        normalize_space: bool,
         context_node: N,
         context_position: usize,
         context_size: usize,
         builder: &mut dyn OutputBuilder,
     ) -> Result<(), ExecutionError> {
-        let value = self.evaluate_xpath31(select, context_node, context_position, context_size)?;
+        let mut value =
+            self.evaluate_xpath31(select, context_node, context_position, context_size)?;
+        if normalize_space {
+            value = petty_xslt::util::normalize_space(&value);
+        }
         let _sep = if let Some(avt) = separator {
             self.evaluate_avt(avt, context_node, context_position, context_size)?
         } else {
@@ -262,124 +262,141 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
 
         for nodes in branch_results {
             for node in nodes {
-                self.output_ir_node(&node, builder);
+                output_ir_node(&node, builder)?;
             }
         }
 
         Ok(())
     }
+}
 
-    fn output_ir_node(&self, node: &petty_idf::IRNode, builder: &mut dyn OutputBuilder) {
-        let default_styles = petty_xslt::ast::PreparsedStyles::default();
-
-        match node {
-            petty_idf::IRNode::Root(children) => {
-                for child in children {
-                    self.output_ir_node(child, builder);
-                }
+/// Replays an IR node built by a fork branch into `builder`.
+///
+/// Form fields cannot be expressed through [`OutputBuilder`], so rather than being
+/// dropped they fail the transformation.
+fn output_ir_node(
+    node: &petty_idf::IRNode,
+    builder: &mut dyn OutputBuilder,
+) -> Result<(), ExecutionError> {
+    let default_styles = petty_xslt::ast::PreparsedStyles::default();
+
+    match node {
+        petty_idf::IRNode::Root(children) => {
+            for child in children {
+                output_ir_node(child, builder)?;
             }
-            petty_idf::IRNode::Block { children, .. } => {
-                builder.start_block(&default_styles);
-                for child in children {
-                    self.output_ir_node(child, builder);
-                }
-                builder.end_block();
+        }
+        petty_idf::IRNode::Block { children, .. } => {
+            builder.start_block(&default_styles);
+            for child in children {
+                output_ir_node(child, builder)?;
             }
-            petty_idf::IRNode::FlexContainer { children, .. } => {
-                builder.start_flex_container(&default_styles);
-                for child in children {
-                    self.output_ir_node(child, builder);
-                }
-                builder.end_flex_container();
+            builder.end_block();
+        }
+        petty_idf::IRNode::FlexContainer { children, .. } => {
+            builder.start_flex_container(&default_styles);
+            for child in children {
+                output_ir_node(child, builder)?;
             }
-            petty_idf::IRNode::Paragraph { children, .. } => {
-                builder.start_paragraph(&default_styles);
-                for child in children {
-                    self.output_inline_node(child, builder);
-                }
-                builder.end_paragraph();
+            builder.end_flex_container();
+        }
+        petty_idf::IRNode::Paragraph { children, .. } => {
+            builder.start_paragraph(&default_styles);
+            for child in children {
+                output_inline_node(child, builder);
             }
-            petty_idf::IRNode::Heading {
-                level, children, ..
-            } => {
-                builder.start_heading(&default_styles, *level);
-                for child in children {
-                    self.output_inline_node(child, builder);
-                }
-                builder.end_heading();
+            builder.end_paragraph();
+        }
+        petty_idf::IRNode::Heading {
+            level, children, ..
+        } => {
+            builder.start_heading(&default_styles, *level);
+            for child in children {
+                output_inline_node(child, builder);
             }
-            petty_idf::IRNode::List { children, .. } => {
-                builder.start_list(&default_styles);
-                for child in children {
-                    self.output_ir_node(child, builder);
-                }
-                builder.end_list();
+            builder.end_heading();
+        }
+        petty_idf::IRNode::List { children, .. } => {
+            builder.start_list(&default_styles);
+            for child in children {
+                output_ir_node(child, builder)?;
             }
-            petty_idf::IRNode::ListItem { children, .. } => {
-                builder.start_list_item(&default_styles);
-                for child in children {
-                    self.output_ir_node(child, builder);
-                }
-                builder.end_list_item();
+            builder.end_list();
+        }
+        petty_idf::IRNode::ListItem { children, .. } => {
+            builder.start_list_item(&default_styles);
+            for child in children {
+                output_ir_node(child, builder)?;
             }
-            petty_idf::IRNode::Table { body, .. } => {
-                builder.start_table(&default_styles);
-                for row in &body.rows {
-                    builder.start_table_row(&default_styles);
-                    for cell in &row.cells {
-                        builder.start_table_cell(&default_styles);
-                        for child in &cell.children {
-                            self.output_ir_node(child, builder);
-                        }
-                        builder.end_table_cell();
+            builder.end_list_item();
+        }
+        petty_idf::IRNode::Table { body, .. } => {
+            builder.start_table(&default_styles);
+            for row in &body.rows {
+                builder.start_table_row(&default_styles);
+                for cell in &row.cells {
+                    builder.start_table_cell(&default_styles);
+                    for child in &cell.children {
+                        output_ir_node(child, builder)?;
                     }
-                    builder.end_table_row();
+                    builder.end_table_cell();
                 }
-                builder.end_table();
-            }
-            petty_idf::IRNode::Image { .. } => {
-                builder.start_image(&default_styles);
-                builder.end_image();
+                builder.end_table_row();
             }
-            petty_idf::IRNode::PageBreak { master_name } => {
-                builder.add_page_break(master_name.clone());
-            }
-            petty_idf::IRNode::IndexMarker { .. } => {}
+            builder.end_table();
+        }
+        petty_idf::IRNode::Image { .. } => {
+            builder.start_image(&default_styles);
+            builder.end_image();
+        }
+        petty_idf::IRNode::PageBreak { master_name } => {
+            builder.add_page_break(master_name.clone());
         }
+        petty_idf::IRNode::FormField { name, .. } => {
+            return Err(ExecutionError::Stream(format!(
+                "Form field '{}' cannot be output from xsl:fork: form fields are not supported in streaming mode",
+                name
+            )));
+        }
+        petty_idf::IRNode::IndexMarker { .. }
+        | petty_idf::IRNode::BarChart { .. }
+        | petty_idf::IRNode::PieChart { .. } => {}
+        petty_idf::IRNode::Caption { content, .. } => output_ir_node(content, builder)?,
     }
+    Ok(())
+}
 
-    fn output_inline_node(&self, node: &petty_idf::InlineNode, builder: &mut dyn OutputBuilder) {
-        let default_styles = petty_xslt::ast::PreparsedStyles::default();
+fn output_inline_node(node: &petty_idf::InlineNode, builder: &mut dyn OutputBuilder) {
+    let default_styles = petty_xslt::ast::PreparsedStyles::default();
 
-        match node {
-            petty_idf::InlineNode::Text(text) => {
-                builder.add_text(text);
-            }
-            petty_idf::InlineNode::StyledSpan { children, .. } => {
-                builder.start_styled_span(&default_styles);
-                for child in children {
-                    self.output_inline_node(child, builder);
-                }
-                builder.end_styled_span();
-            }
-            petty_idf::InlineNode::Hyperlink { children, .. } => {
-                builder.start_hyperlink(&default_styles);
-                for child in children {
-                    self.output_inline_node(child, builder);
-                }
-                builder.end_hyperlink();
+    match node {
+        petty_idf::InlineNode::Text(text) => {
+            builder.add_text(text);
+        }
+        petty_idf::InlineNode::StyledSpan { children, .. } => {
+            builder.start_styled_span(&default_styles);
+            for child in children {
+                output_inline_node(child, builder);
             }
-            petty_idf::InlineNode::PageReference { children, .. } => {
-                for child in children {
-                    self.output_inline_node(child, builder);
-                }
+            builder.end_styled_span();
+        }
+        petty_idf::InlineNode::Hyperlink { children, .. } => {
+            builder.start_hyperlink(&default_styles);
+            for child in children {
+                output_inline_node(child, builder);
             }
-            petty_idf::InlineNode::Image { .. } => {
-                builder.start_image(&default_styles);
-                builder.end_image();
+            builder.end_hyperlink();
+        }
+        petty_idf::InlineNode::PageReference { children, .. } => {
+            for child in children {
+                output_inline_node(child, builder);
             }
-            petty_idf::InlineNode::LineBreak => {}
         }
+        petty_idf::InlineNode::Image { .. } => {
+            builder.start_image(&default_styles);
+            builder.end_image();
+        }
+        petty_idf::InlineNode::LineBreak | petty_idf::InlineNode::Sparkline { .. } => {}
     }
 }
 
@@ -404,6 +421,28 @@ mod tests {
         Ordering::Equal
     }
 
+    #[test]
+    fn test_fork_output_rejects_form_fields() {
+        let field = petty_idf::IRNode::FormField {
+            meta: Default::default(),
+            name: "signature".into(),
+            kind: petty_idf::FormFieldKind::Checkbox { checked: false },
+        };
+        let block = petty_idf::IRNode::Block {
+            meta: Default::default(),
+            children: vec![field],
+        };
+        let mut builder = IdfBuilder::new();
+
+        let error = output_ir_node(&block, &mut builder).unwrap_err();
+        assert!(matches!(&error, ExecutionError::Stream(msg) if msg.contains("'signature'")));
+        assert!(
+            error
+                .to_string()
+                .contains("not supported in streaming mode")
+        );
+    }
+
     #[test]
     fn test_compare_merge_keys_ascending() {
         let a = vec!["1".to_string(), "apple".to_string()];
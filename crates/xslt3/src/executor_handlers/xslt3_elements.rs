@@ -12,18 +12,18 @@ use petty_xslt::buffering_builder::BufferingOutputBuilder;
 use petty_xslt::output::OutputBuilder;
 use regex::Regex;
 
-struct TextCollector {
+pub(crate) struct TextCollector {
     text: String,
 }
 
 impl TextCollector {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             text: String::new(),
         }
     }
 
-    fn into_text(self) -> String {
+    pub(crate) fn into_text(self) -> String {
         self.text
     }
 }
@@ -463,7 +463,7 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
             })
             .collect();
 
-        let sorted = self.sort_nodes(&nodes, sort_keys)?;
+        let sorted = self.apply_sort_keys(nodes, sort_keys)?;
 
         for (i, node) in sorted.iter().enumerate() {
             self.execute_template(body, *node, i + 1, sorted.len(), builder)?;
@@ -471,48 +471,6 @@ impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
 
         Ok(())
     }
-
-    pub(crate) fn sort_nodes(
-        &self,
-        nodes: &[N],
-        sort_keys: &[SortKey3],
-    ) -> Result<Vec<N>, ExecutionError> {
-        if nodes.is_empty() || sort_keys.is_empty() {
-            return Ok(nodes.to_vec());
-        }
-
-        let mut indexed: Vec<(usize, N)> = nodes.iter().enumerate().map(|(i, n)| (i, *n)).collect();
-
-        let first_key = &sort_keys[0];
-        let node_count = nodes.len();
-
-        indexed.sort_by(|a, b| {
-            let a_val = self
-                .evaluate_xpath31(&first_key.select, a.1, a.0 + 1, node_count)
-                .unwrap_or_default();
-            let b_val = self
-                .evaluate_xpath31(&first_key.select, b.1, b.0 + 1, node_count)
-                .unwrap_or_default();
-
-            let cmp = match first_key.data_type {
-                petty_xslt::ast::SortDataType::Number => {
-                    let a_num: f64 = a_val.parse().unwrap_or(f64::NAN);
-                    let b_num: f64 = b_val.parse().unwrap_or(f64::NAN);
-                    a_num
-                        .partial_cmp(&b_num)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                }
-                _ => a_val.cmp(&b_val),
-            };
-
-            match first_key.order {
-                petty_xslt::ast::SortOrder::Descending => cmp.reverse(),
-                petty_xslt::ast::SortOrder::Ascending => cmp,
-            }
-        });
-
-        Ok(indexed.into_iter().map(|(_, n)| n).collect())
-    }
 }
 
 impl<'s, 'a, N: DataSourceNode<'a> + 'a> TemplateExecutor3<'s, 'a, N> {
@@ -115,6 +115,7 @@ pub mod ast;
 pub mod compiler;
 pub mod error;
 pub mod executor;
+pub mod messages;
 pub mod packages;
 pub mod processor;
 pub mod resolver;
@@ -127,6 +128,7 @@ pub use ast::{CompiledStylesheet3, PreparsedTemplate, Xslt3Features, Xslt3Instru
 pub use compiler::{CompilerBuilder3, StylesheetBuilder3};
 pub use error::Xslt3Error;
 pub use executor::{ExecutionError, TemplateExecutor3};
+pub use messages::{MessageCollector, MessageSink};
 pub use processor::{Xslt3Parser, XsltVersion, detect_xslt_version};
 pub use resolver::{CachingStylesheetResolver, StylesheetResolver, compile_stylesheet};
 
@@ -0,0 +1,37 @@
+//! Destinations for `xsl:message` output.
+//!
+//! Messages are diagnostics, not document content, so the executor hands them to a
+//! `MessageSink` instead of the output builder. Without a sink they go to the log.
+
+use std::sync::{Arc, Mutex};
+
+/// Receives the text of each `xsl:message` evaluated during a transformation.
+pub trait MessageSink: Send + Sync {
+    /// Called once per message. `terminate` is true when the message is about to
+    /// stop the transformation.
+    fn message(&self, text: &str, terminate: bool);
+}
+
+/// A `MessageSink` that keeps every message in memory, in the order emitted.
+#[derive(Debug, Clone, Default)]
+pub struct MessageCollector {
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl MessageCollector {
+    /// Create a new empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The messages collected so far.
+    pub fn messages(&self) -> Vec<String> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+impl MessageSink for MessageCollector {
+    fn message(&self, text: &str, _terminate: bool) {
+        self.messages.lock().unwrap().push(text.to_string());
+    }
+}
@@ -6,7 +6,7 @@ use petty_template_core::{
     CompiledTemplate, DataSourceFormat, ExecutionConfig, TemplateError, TemplateExecutor,
     TemplateFeatures, TemplateFlags, TemplateMetadata, TemplateParser,
 };
-use petty_xslt::datasources::json::JsonVDocument;
+use petty_xslt::datasources::json::{JsonVDocument, JsonVDocumentOptions};
 use petty_xslt::datasources::xml::XmlDocument;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -81,6 +81,14 @@ impl TemplateExecutor for Xslt3Template {
                 let root_node = doc.root_node();
                 let mut executor = TemplateExecutor3::new(&self.compiled, root_node, false)
                     .map_err(|e| TemplateError::ExecutionError(e.to_string()))?;
+                executor.set_safe_mode(config.safe_mode);
+                executor.set_step_limit(config.max_steps);
+                executor
+                    .set_current_time(config.current_time)
+                    .map_err(|e| TemplateError::ExecutionError(e.to_string()))?;
+                executor
+                    .set_number_locale(config.number_locale)
+                    .map_err(|e| TemplateError::ExecutionError(e.to_string()))?;
                 executor
                     .build_tree()
                     .map_err(|e| TemplateError::ExecutionError(e.to_string()))
@@ -88,10 +96,22 @@ impl TemplateExecutor for Xslt3Template {
             DataSourceFormat::Json => {
                 let json_value: serde_json::Value = serde_json::from_str(data_source)
                     .map_err(|e| TemplateError::ParseError(format!("JSON parse error: {}", e)))?;
-                let doc = JsonVDocument::new(&json_value);
+                let options = JsonVDocumentOptions {
+                    preserve_key_order: config.preserve_key_order,
+                    ..Default::default()
+                };
+                let doc = JsonVDocument::with_options(&json_value, &options);
                 let root_node = doc.root_node();
                 let mut executor = TemplateExecutor3::new(&self.compiled, root_node, false)
                     .map_err(|e| TemplateError::ExecutionError(e.to_string()))?;
+                executor.set_safe_mode(config.safe_mode);
+                executor.set_step_limit(config.max_steps);
+                executor
+                    .set_current_time(config.current_time)
+                    .map_err(|e| TemplateError::ExecutionError(e.to_string()))?;
+                executor
+                    .set_number_locale(config.number_locale)
+                    .map_err(|e| TemplateError::ExecutionError(e.to_string()))?;
                 executor
                     .build_tree()
                     .map_err(|e| TemplateError::ExecutionError(e.to_string()))
@@ -169,4 +189,73 @@ mod tests {
         let result = parser.parse(xslt, PathBuf::new());
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_key_resolves_json_records_by_id() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:key name="customer-by-id" match="customers/item" use="id"/>
+                <xsl:template match="/">
+                    <xsl:for-each select="//orders/item">
+                        <p>
+                            <xsl:for-each select="key('customer-by-id', customer)">
+                                <xsl:value-of select="name"/>
+                            </xsl:for-each>
+                        </p>
+                    </xsl:for-each>
+                    <p><xsl:value-of select="count(key('customer-by-id', ('c1', 'c2', 'c9')))"/></p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        let data = r#"{
+            "customers": [ { "id": "c1", "name": "Ada" }, { "id": "c2", "name": "Grace" } ],
+            "orders": [ { "customer": "c2" }, { "customer": "c1" }, { "customer": "c3" } ]
+        }"#;
+
+        let template = Xslt3Parser::new()
+            .parse(xslt, PathBuf::new())
+            .unwrap()
+            .main_template;
+        let config = ExecutionConfig {
+            format: DataSourceFormat::Json,
+            ..Default::default()
+        };
+        let nodes = template.execute(data, config).unwrap();
+        let texts: Vec<String> = nodes
+            .iter()
+            .map(|n| crate::test_helpers::get_text_content(std::slice::from_ref(n)))
+            .collect();
+        assert_eq!(texts, ["Grace", "Ada", "", "2"]);
+    }
+
+    #[test]
+    fn test_fixed_current_time_is_reported_by_date_functions() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:variable name="stamp" select="current-dateTime()"/>
+                <xsl:template match="/">
+                    <p><xsl:value-of select="current-date()"/></p>
+                    <p><xsl:value-of select="$stamp"/></p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let template = Xslt3Parser::new()
+            .parse(xslt, PathBuf::new())
+            .unwrap()
+            .main_template;
+        // 2024-02-29T13:45:30Z
+        let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_709_214_330);
+        let config = ExecutionConfig {
+            format: DataSourceFormat::Json,
+            current_time: Some(time),
+            ..Default::default()
+        };
+        let nodes = template.execute("{}", config).unwrap();
+        let texts: Vec<String> = nodes
+            .iter()
+            .map(|n| crate::test_helpers::get_text_content(std::slice::from_ref(n)))
+            .collect();
+        assert_eq!(texts, ["2024-02-29Z", "2024-02-29T13:45:30Z"]);
+    }
 }
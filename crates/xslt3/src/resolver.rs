@@ -3,8 +3,8 @@ use crate::compiler::{CompilerBuilder3, StylesheetBuilder3};
 use crate::error::Xslt3Error;
 use petty_traits::ResourceProvider;
 use quick_xml::Reader;
-use quick_xml::escape::unescape;
-use quick_xml::events::Event;
+use quick_xml::escape::{resolve_predefined_entity, unescape};
+use quick_xml::events::{BytesRef, Event};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::{Arc, RwLock};
@@ -193,10 +193,22 @@ pub fn compile_stylesheet(source: &str) -> Result<CompiledStylesheet3, Xslt3Erro
     let mut reader = Reader::from_str(source);
     reader.config_mut().trim_text(false);
     let mut buf = Vec::new();
+    // Character data arrives split around entity references and CDATA sections;
+    // it is gathered here so the builder sees each text run as one piece.
+    let mut pending_text = String::new();
 
     loop {
         let pos = reader.buffer_position() as usize;
-        match reader.read_event_into(&mut buf) {
+        let event = reader.read_event_into(&mut buf);
+        if !pending_text.is_empty()
+            && !matches!(
+                event,
+                Ok(Event::Text(_) | Event::GeneralRef(_) | Event::CData(_))
+            )
+        {
+            builder.text(std::mem::take(&mut pending_text))?;
+        }
+        match event {
             Ok(Event::Start(ref e)) => {
                 let owned_e = e.to_owned();
                 let attrs: Vec<(Vec<u8>, Vec<u8>)> = owned_e
@@ -221,10 +233,16 @@ pub fn compile_stylesheet(source: &str) -> Result<CompiledStylesheet3, Xslt3Erro
             Ok(Event::Text(ref e)) => {
                 let raw_text = std::str::from_utf8(e.as_ref())
                     .map_err(|e| Xslt3Error::parse(e.to_string()))?;
-                let text = unescape(raw_text)
-                    .map_err(|e| Xslt3Error::parse(e.to_string()))?
-                    .into_owned();
-                builder.text(text)?;
+                let text = unescape(raw_text).map_err(|e| Xslt3Error::parse(e.to_string()))?;
+                pending_text.push_str(&text);
+            }
+            Ok(Event::GeneralRef(ref e)) => {
+                push_reference(&mut pending_text, e)?;
+            }
+            Ok(Event::CData(ref e)) => {
+                let text = std::str::from_utf8(e.as_ref())
+                    .map_err(|e| Xslt3Error::parse(e.to_string()))?;
+                pending_text.push_str(text);
             }
             Ok(Event::Eof) => break,
             Err(e) => return Err(Xslt3Error::parse(e.to_string())),
@@ -236,6 +254,24 @@ pub fn compile_stylesheet(source: &str) -> Result<CompiledStylesheet3, Xslt3Erro
     builder.finalize()
 }
 
+fn push_reference(text: &mut String, reference: &BytesRef) -> Result<(), Xslt3Error> {
+    if let Some(c) = reference
+        .resolve_char_ref()
+        .map_err(|e| Xslt3Error::parse(e.to_string()))?
+    {
+        text.push(c);
+        return Ok(());
+    }
+    let name = reference
+        .decode()
+        .map_err(|e| Xslt3Error::parse(e.to_string()))?;
+    match resolve_predefined_entity(&name) {
+        Some(resolved) => text.push_str(resolved),
+        None => return Err(Xslt3Error::parse(format!("Unknown entity '&{};'", name))),
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -304,7 +304,7 @@ impl StreamabilityAnalyzer {
         use crate::error::Xslt3Error;
 
         match instruction {
-            Xslt3Instruction::Text(_) => Ok(()),
+            Xslt3Instruction::Text(_) | Xslt3Instruction::RawText(_) => Ok(()),
             Xslt3Instruction::TextValueTemplate(tvt) => {
                 for part in &tvt.0 {
                     if let crate::ast::TvtPart::Dynamic(expr) = part {
@@ -636,6 +636,7 @@ mod tests {
         let template = PreparsedTemplate(vec![Xslt3Instruction::ValueOf {
             select: Expression::Literal(Literal::String("test".to_string())),
             separator: None,
+            normalize_space: false,
         }]);
         let result = StreamabilityAnalyzer::validate_streaming_template(&template);
         assert!(result.is_ok());
@@ -651,6 +652,7 @@ mod tests {
                 vec![make_step(Axis::PrecedingSibling, "item")],
             )),
             separator: None,
+            normalize_space: false,
         }]);
         let result = StreamabilityAnalyzer::validate_streaming_template(&template);
         assert!(result.is_err());
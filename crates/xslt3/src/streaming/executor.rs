@@ -171,13 +171,14 @@ impl<'s> StreamingExecutor<'s> {
         node: &StreamedNode,
     ) -> Result<(), Xslt3Error> {
         match instruction {
-            Xslt3Instruction::Text(text) => {
+            Xslt3Instruction::Text(text) | Xslt3Instruction::RawText(text) => {
                 self.builder.add_text(text);
                 Ok(())
             }
             Xslt3Instruction::ValueOf {
                 select,
                 separator: _,
+                normalize_space,
             } => {
                 let result = StreamabilityAnalyzer::analyze_expression(select);
                 if !result.streamable {
@@ -194,7 +195,12 @@ impl<'s> StreamingExecutor<'s> {
                     Posture::Striding => self.evaluate_striding_expression(select, node)?,
                     _ => node.string_value(),
                 };
-                self.builder.add_text(&value);
+                if *normalize_space {
+                    self.builder
+                        .add_text(&petty_xslt::util::normalize_space(&value));
+                } else {
+                    self.builder.add_text(&value);
+                }
                 Ok(())
             }
             Xslt3Instruction::If { test, body } => {
@@ -1006,6 +1012,7 @@ mod tests {
         let template = PreparsedTemplate(vec![Xslt3Instruction::ValueOf {
             select: Expression::Literal(Literal::String("Literal Value".to_string())),
             separator: None,
+            normalize_space: false,
         }]);
 
         let node = StreamedNode::element(QName::new("test".to_string()), vec![], 0, 1);
@@ -1034,6 +1041,7 @@ mod tests {
                 }],
             }),
             separator: None,
+            normalize_space: false,
         }]);
 
         let node = StreamedNode::element(QName::new("test".to_string()), vec![], 0, 1);
@@ -693,6 +693,72 @@ mod executor_tests {
         assert_eq!(text, "Alice");
     }
 
+    #[test]
+    fn test_execute_value_of_normalize_space() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0"
+                xmlns:xsl="http://www.w3.org/1999/XSL/Transform"
+                xmlns:petty="https://petty.rs/ns/1.0">
+                <xsl:template match="/">
+                    <output>[<xsl:value-of select="/root/name"/>]</output>
+                    <output>[<xsl:value-of select="/root/name" petty:normalize-space="yes"/>]</output>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let xml = "<root><name>  Ada \n   Lovelace </name></root>";
+        let nodes = execute_xslt3(xslt, xml).unwrap();
+        assert_eq!(get_text_content(&nodes[..1]), "[  Ada \n   Lovelace ]");
+        assert_eq!(get_text_content(&nodes[1..]), "[Ada Lovelace]");
+    }
+
+    #[test]
+    fn test_execute_raw_text_is_emitted_verbatim() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" expand-text="yes"
+                xmlns:xsl="http://www.w3.org/1999/XSL/Transform"
+                xmlns:petty="https://petty.rs/ns/1.0">
+                <xsl:character-map name="m">
+                    <xsl:output-character character="*" string="[star]"/>
+                </xsl:character-map>
+                <xsl:output use-character-maps="m"/>
+                <xsl:template match="/">
+                    <p>{1 + 1} *</p>
+                    <p><petty:raw-text>  {x} * &lt;b&gt;&amp;<![CDATA[<i>&nbsp;</i>]]>
+  end</petty:raw-text></p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let nodes = execute_xslt3(xslt, "<root/>").unwrap();
+        assert_eq!(get_text_content(&nodes[..1]), "2 [star]");
+        match &nodes[1] {
+            petty_idf::IRNode::Paragraph { children, .. } => assert_eq!(
+                children,
+                &vec![petty_idf::InlineNode::Text(
+                    "  {x} * <b>&<i>&nbsp;</i>\n  end".to_string()
+                )]
+            ),
+            other => panic!("expected paragraph, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_execute_raw_text_rejects_child_elements() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0"
+                xmlns:xsl="http://www.w3.org/1999/XSL/Transform"
+                xmlns:petty="https://petty.rs/ns/1.0">
+                <xsl:template match="/">
+                    <p><petty:raw-text>text <b>bold</b></petty:raw-text></p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let err = parse_stylesheet(xslt).unwrap_err();
+        assert!(err.to_string().contains("petty:raw-text"), "{}", err);
+    }
+
     #[test]
     fn test_execute_for_each() {
         let xslt = r#"
@@ -1449,6 +1515,46 @@ mod grouping_tests {
             "group-ending-with should produce output"
         );
     }
+
+    #[test]
+    fn test_group_by_sets_current_group_and_key_in_body() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <xsl:for-each-group select="/data/item" group-by="@category">
+                        <p>
+                            <xsl:value-of select="current-grouping-key()"/>=<xsl:value-of select="string-join(for $i in current-group() return string($i), ',')"/>
+                            <xsl:for-each-group select="current-group()" group-by="@size">[<xsl:value-of select="current-grouping-key()"/>]</xsl:for-each-group>
+                            <xsl:value-of select="current-grouping-key()"/>;
+                        </p>
+                    </xsl:for-each-group>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        let xml = r#"<data><item category="A" size="s">Item1</item><item category="B" size="m">Item2</item><item category="A" size="l">Item3</item></data>"#;
+
+        let text = get_text_content(&execute_xslt3(xslt, xml).unwrap());
+        let text: String = text.split_whitespace().collect();
+        // The key is restored after the nested group finishes.
+        assert_eq!(text, "A=Item1,Item3[s][l]A;B=Item2[m]B;");
+    }
+
+    #[test]
+    fn test_group_adjacent_sets_current_group_and_key_in_body() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <xsl:for-each-group select="/data/item" group-adjacent="@type">
+                        <p><xsl:value-of select="current-grouping-key()"/>:<xsl:value-of select="string-join(for $i in current-group() return string($i), ',')"/>;</p>
+                    </xsl:for-each-group>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        let xml = r#"<data><item type="header">H1</item><item type="para">P1</item><item type="para">P2</item><item type="header">H2</item></data>"#;
+
+        let text = get_text_content(&execute_xslt3(xslt, xml).unwrap());
+        assert_eq!(text, "header:H1;para:P1,P2;header:H2;");
+    }
 }
 
 mod analyze_string_tests {
@@ -3426,6 +3532,62 @@ mod number_tests {
         );
     }
 
+    #[test]
+    fn test_number_single_counts_nearest_matching_ancestor() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0"
+                xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <result>
+                        <xsl:for-each select="//para">
+                            <xsl:number count="chapter" format="(A)"/>
+                            <xsl:number count="para" from="chapter" format="1;"/>
+                        </xsl:for-each>
+                    </result>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let xml =
+            r#"<book><chapter><para/><note/><para/></chapter><chapter><para/></chapter></book>"#;
+        let nodes = execute_xslt3(xslt, xml).unwrap();
+        assert_eq!(get_text_content(&nodes), "(A)1;(A)2;(B)1;");
+    }
+
+    #[test]
+    fn test_number_multiple_builds_hierarchical_numbers() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0"
+                xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <result>
+                        <xsl:for-each select="//title">
+                            <xsl:number level="multiple" count="chapter|section" format="1.a "/>
+                        </xsl:for-each>
+                        <xsl:for-each select="//section/title">
+                            <xsl:number level="multiple" count="section" from="part" format="[1]"/>
+                        </xsl:for-each>
+                    </result>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let xml = r#"
+            <part>
+                <chapter><title/>
+                    <section><title/></section>
+                    <section><title/><section><title/></section></section>
+                </chapter>
+                <chapter><title/><section><title/></section></chapter>
+            </part>
+        "#;
+        let nodes = execute_xslt3(xslt, xml).unwrap();
+        assert_eq!(
+            get_text_content(&nodes),
+            "1 1.a 1.b 1.b.a 2 2.a [1][2][2.1][1]"
+        );
+    }
+
     #[test]
     fn test_number_grouping() {
         let xslt = r#"
@@ -5319,3 +5481,416 @@ mod fallback_instruction_tests {
         );
     }
 }
+
+mod collation_tests {
+    use super::*;
+
+    fn sort_words(collation_attr: &str) -> String {
+        let xslt = format!(
+            r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <p>
+                        <xsl:for-each select="/words/w">
+                            <xsl:sort select="." {collation_attr}/>
+                            <xsl:value-of select="."/>
+                            <xsl:text>,</xsl:text>
+                        </xsl:for-each>
+                    </p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#
+        );
+        let xml = "<words><w>zebra</w><w>éclair</w><w>eagle</w><w>Ecuador</w></words>";
+        let result = execute_xslt3(&xslt, xml).expect("sort should execute");
+        get_text_content(&result)
+    }
+
+    #[test]
+    fn test_sort_codepoint_vs_locale_collation() {
+        assert_eq!(sort_words(""), "Ecuador,eagle,zebra,éclair,");
+        assert_eq!(
+            sort_words(r#"collation="http://www.w3.org/2013/collation/UCA?lang=fr""#),
+            "eagle,éclair,Ecuador,zebra,"
+        );
+    }
+
+    #[test]
+    fn test_compare_with_collation_argument() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <p>
+                        <xsl:value-of select="compare('éclair', 'zebra')"/>
+                        <xsl:text>|</xsl:text>
+                        <xsl:value-of select="compare('éclair', 'zebra', 'http://www.w3.org/2013/collation/UCA')"/>
+                    </p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        let result = execute_xslt3(xslt, "<root/>").expect("compare should execute");
+        assert_eq!(get_text_content(&result), "1|-1");
+    }
+
+    #[test]
+    fn test_unknown_sort_collation_is_error() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <xsl:for-each select="/words/w">
+                        <xsl:sort select="." collation="http://example.com/unknown"/>
+                        <xsl:value-of select="."/>
+                    </xsl:for-each>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        assert!(execute_xslt3(xslt, "<words><w>a</w></words>").is_err());
+    }
+}
+
+mod sort_tests {
+    use super::*;
+
+    const PRODUCTS: &str = r#"<products>
+        <product><name>widget</name><price>10</price></product>
+        <product><name>Gadget</name><price>25</price></product>
+        <product><name>bolt</name><price>2</price></product>
+        <product><name>anchor</name><price>25</price></product>
+        <product><name>Cable</name><price>10</price></product>
+    </products>"#;
+
+    fn run(sorts: &str, instruction: &str) -> String {
+        let body = match instruction {
+            "for-each" => format!(
+                r#"<xsl:for-each select="/products/product">{sorts}<xsl:value-of select="name"/><xsl:text>,</xsl:text></xsl:for-each>"#
+            ),
+            _ => format!(
+                r#"<xsl:apply-templates select="/products/product">{sorts}</xsl:apply-templates>"#
+            ),
+        };
+        let xslt = format!(
+            r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/"><p>{body}</p></xsl:template>
+                <xsl:template match="product"><xsl:value-of select="name"/><xsl:text>,</xsl:text></xsl:template>
+            </xsl:stylesheet>
+        "#
+        );
+        let result = execute_xslt3(&xslt, PRODUCTS).expect("sort should execute");
+        get_text_content(&result)
+    }
+
+    #[test]
+    fn test_numeric_descending_then_text_ascending() {
+        let sorts = r#"
+            <xsl:sort select="price" data-type="number" order="descending"/>
+            <xsl:sort select="name" collation="http://www.w3.org/2005/xpath-functions/collation/html-ascii-case-insensitive"/>"#;
+        let expected = "anchor,Gadget,Cable,widget,bolt,";
+        assert_eq!(run(sorts, "for-each"), expected);
+        assert_eq!(run(sorts, "apply-templates"), expected);
+    }
+
+    #[test]
+    fn test_numeric_sort_is_not_lexical() {
+        let sorts = r#"<xsl:sort select="price" data-type="number"/>"#;
+        assert_eq!(run(sorts, "for-each"), "bolt,widget,Cable,Gadget,anchor,");
+
+        let sorts = r#"<xsl:sort select="price"/>"#;
+        assert_eq!(run(sorts, "for-each"), "widget,Cable,bolt,Gadget,anchor,");
+    }
+
+    #[test]
+    fn test_case_order() {
+        let xml = "<w><i>b</i><i>A</i><i>a</i><i>B</i></w>";
+        let xslt = |case_order: &str| {
+            format!(
+                r#"
+                <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                    <xsl:template match="/">
+                        <p><xsl:for-each select="/w/i"><xsl:sort select="." case-order="{case_order}"/><xsl:value-of select="."/></xsl:for-each></p>
+                    </xsl:template>
+                </xsl:stylesheet>
+            "#
+            )
+        };
+        let upper = execute_xslt3(&xslt("upper-first"), xml).expect("upper-first");
+        assert_eq!(get_text_content(&upper), "AaBb");
+        let lower = execute_xslt3(&xslt("lower-first"), xml).expect("lower-first");
+        assert_eq!(get_text_content(&lower), "aAbB");
+    }
+
+    #[test]
+    fn test_for_each_group_sort() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <p>
+                        <xsl:for-each-group select="/products/product" group-by="price">
+                            <xsl:sort select="current-grouping-key()" data-type="number" order="descending"/>
+                            <xsl:value-of select="current-grouping-key()"/>
+                            <xsl:text>;</xsl:text>
+                        </xsl:for-each-group>
+                    </p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        let result = execute_xslt3(xslt, PRODUCTS).expect("group sort should execute");
+        assert_eq!(get_text_content(&result), "25;10;2;");
+    }
+
+    #[test]
+    fn test_perform_sort_multiple_keys() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <p>
+                        <xsl:perform-sort select="/products/product">
+                            <xsl:sort select="price" data-type="number"/>
+                            <xsl:sort select="name" order="descending"/>
+                            <xsl:value-of select="name"/>
+                            <xsl:text>,</xsl:text>
+                        </xsl:perform-sort>
+                    </p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        let result = execute_xslt3(xslt, PRODUCTS).expect("perform-sort should execute");
+        // Ascending by price; equal prices fall back to descending name order.
+        assert_eq!(
+            get_text_content(&result),
+            "bolt,widget,Cable,anchor,Gadget,"
+        );
+    }
+}
+
+mod message_tests {
+    use super::*;
+    use crate::executor::{ExecutionError, TemplateExecutor3};
+    use crate::messages::MessageCollector;
+    use petty_idf::IRNode;
+    use petty_xslt::datasources::xml::XmlDocument;
+    use std::sync::Arc;
+
+    fn execute_with_messages(
+        xslt_source: &str,
+        xml_data: &str,
+    ) -> (Result<Vec<IRNode>, ExecutionError>, MessageCollector) {
+        let stylesheet = parse_stylesheet(xslt_source).unwrap();
+        let doc = XmlDocument::parse(xml_data).unwrap();
+        let collector = MessageCollector::new();
+        let mut executor = TemplateExecutor3::new(&stylesheet, doc.root_node(), false)
+            .unwrap()
+            .with_message_sink(Arc::new(collector.clone()));
+        (executor.build_tree(), collector)
+    }
+
+    #[test]
+    fn test_message_is_captured_and_execution_continues() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <xsl:message>Processing <xsl:value-of select="count(//item)"/> items</xsl:message>
+                    <xsl:message select="'second'"/>
+                    <p>Done</p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let (result, collector) = execute_with_messages(xslt, "<root><item/><item/></root>");
+        let text = get_text_content(&result.unwrap());
+        assert_eq!(text.trim(), "Done");
+        assert_eq!(collector.messages(), vec!["Processing 2 items", "second"]);
+    }
+
+    #[test]
+    fn test_message_terminate_stops_execution() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <p>Before</p>
+                    <xsl:message terminate="yes">Missing invoice number</xsl:message>
+                    <p>After</p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let (result, collector) = execute_with_messages(xslt, "<root/>");
+        match result {
+            Err(ExecutionError::DynamicError { code, message }) => {
+                assert_eq!(code, "XTMM9000");
+                assert_eq!(message, "Missing invoice number");
+            }
+            other => panic!("expected termination, got {:?}", other),
+        }
+        assert_eq!(collector.messages(), vec!["Missing invoice number"]);
+    }
+}
+
+mod injected_variable_tests {
+    use super::*;
+    use crate::executor::TemplateExecutor3;
+    use petty_xpath31::XdmValue;
+    use petty_xslt::datasources::xml::XmlDocument;
+
+    #[test]
+    fn test_injected_string_and_number_variables_are_visible_to_templates() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:param name="customer"/>
+                <xsl:template match="/">
+                    <p><xsl:value-of select="$customer"/> owes <xsl:value-of select="$total * 2"/></p>
+                    <p><xsl:value-of select="$settings?currency"/></p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let stylesheet = parse_stylesheet(xslt).unwrap();
+        let doc = XmlDocument::parse("<root/>").unwrap();
+        let mut executor = TemplateExecutor3::new(&stylesheet, doc.root_node(), false)
+            .unwrap()
+            .with_global_variable("customer", "Acme Corp")
+            .with_global_variable("total", XdmValue::from_double(21.5));
+        executor
+            .set_global_variable_serde("settings", &serde_json::json!({ "currency": "EUR" }))
+            .unwrap();
+
+        let text = get_text_content(&executor.build_tree().unwrap());
+        assert_eq!(text.trim(), "Acme Corp owes 43EUR");
+    }
+}
+
+mod safe_mode_tests {
+    use super::*;
+    use crate::executor::{ExecutionError, TemplateExecutor3};
+    use petty_idf::IRNode;
+    use petty_traits::InMemoryResourceProvider;
+    use petty_xslt::datasources::xml::XmlDocument;
+    use std::sync::Arc;
+
+    fn execute_in_safe_mode(
+        xslt_source: &str,
+        allowed: Option<Arc<InMemoryResourceProvider>>,
+    ) -> Result<Vec<IRNode>, ExecutionError> {
+        let stylesheet = parse_stylesheet(xslt_source).unwrap();
+        let doc = XmlDocument::parse("<root/>").unwrap();
+        let mut executor = TemplateExecutor3::new(&stylesheet, doc.root_node(), false).unwrap();
+        if let Some(allowed) = allowed {
+            executor = executor.with_allowed_resources(allowed);
+        }
+        executor.set_safe_mode(true);
+        executor.build_tree()
+    }
+
+    #[test]
+    fn test_safe_mode_denies_resources_the_allowlist_does_not_provide() {
+        let read_doc = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <p><xsl:value-of select="doc('file:///etc/passwd')"/></p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        let err = execute_in_safe_mode(read_doc, None).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("access to 'file:///etc/passwd' is denied in safe mode"),
+            "{}",
+            err
+        );
+
+        let read_text = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <p><xsl:value-of select="unparsed-text('file:///etc/passwd')"/></p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+        let allowed = Arc::new(InMemoryResourceProvider::new());
+        allowed
+            .add("file:///etc/passwd", b"root:x:0:0".to_vec())
+            .unwrap();
+        let text =
+            get_text_content(&execute_in_safe_mode(read_text, Some(allowed.clone())).unwrap());
+        assert_eq!(text.trim(), "root:x:0:0");
+
+        let err = execute_in_safe_mode(
+            &read_text.replace("file:///etc/passwd", "file:///etc/shadow"),
+            Some(allowed),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("denied in safe mode"), "{}", err);
+    }
+}
+
+mod step_limit_tests {
+    use super::*;
+    use crate::executor::{ExecutionError, TemplateExecutor3};
+    use petty_xslt::datasources::xml::XmlDocument;
+
+    #[test]
+    fn test_large_loop_aborts_at_step_limit() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <xsl:try>
+                        <xsl:for-each select="//item">
+                            <p><xsl:value-of select="position()"/></p>
+                        </xsl:for-each>
+                        <xsl:catch><p>caught</p></xsl:catch>
+                    </xsl:try>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let stylesheet = parse_stylesheet(xslt).unwrap();
+        let xml = format!("<root>{}</root>", "<item/>".repeat(100_000));
+        let doc = XmlDocument::parse(&xml).unwrap();
+        let mut executor = TemplateExecutor3::new(&stylesheet, doc.root_node(), false).unwrap();
+        executor.set_step_limit(Some(1000));
+
+        match executor.build_tree() {
+            Err(ExecutionError::StepLimitExceeded(limit)) => assert_eq!(limit, 1000),
+            other => panic!("expected the step limit to abort the loop, got {:?}", other),
+        }
+        assert_eq!(executor.steps, 1001);
+    }
+}
+
+mod serialize_tests {
+    use super::*;
+
+    const ORDER: &str = "<order id=\"7\"><item>Pen</item><item>Ink &amp; nib</item></order>";
+
+    #[test]
+    fn test_serialize_with_indent_puts_elements_on_indented_lines() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <p><xsl:value-of select="serialize(/order, map { 'indent': true() })"/></p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let text = get_text_content(&execute_xslt3(xslt, ORDER).unwrap());
+        assert_eq!(
+            text,
+            "<order id=\"7\">\n  <item>Pen</item>\n  <item>Ink &amp; nib</item>\n</order>"
+        );
+    }
+
+    #[test]
+    fn test_serialize_with_json_method() {
+        let xslt = r#"
+            <xsl:stylesheet version="3.0" xmlns:xsl="http://www.w3.org/1999/XSL/Transform">
+                <xsl:template match="/">
+                    <p><xsl:value-of select="serialize(
+                        map { 'count': count(/order/item), 'items': array { for $i in /order/item return string($i) } },
+                        map { 'method': 'json' })"/></p>
+                </xsl:template>
+            </xsl:stylesheet>
+        "#;
+
+        let text = get_text_content(&execute_xslt3(xslt, ORDER).unwrap());
+        assert_eq!(text, r#"{"count":2,"items":["Pen","Ink & nib"]}"#);
+    }
+}
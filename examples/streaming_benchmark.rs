@@ -83,7 +83,15 @@ fn create_context() -> PipelineContext {
         role_templates: Arc::new(features.role_templates),
         font_library: Arc::new(library),
         resource_provider: Arc::new(petty_resource::InMemoryResourceProvider::new()),
+        unresolved_resources: Default::default(),
+        resource_limits: Default::default(),
         cache_config: Default::default(),
+        output: Default::default(),
+        number_locale: Default::default(),
+        preserve_key_order: false,
+        safe_mode: false,
+        max_template_steps: None,
+        current_time: None,
         adaptive: None,
     }
 }
@@ -148,7 +148,7 @@ pub use petty_core::PipelineError;
 
 // Document types
 pub use layout::LaidOutSequence;
-pub use petty_core::{ApiIndexEntry, TocEntry};
+pub use petty_core::{ApiIndexEntry, CaptionEntry, TocEntry};
 
 // IR types
 pub use idf::{IRNode, InlineNode};
@@ -161,21 +161,27 @@ pub use layout::{LayoutEngine, LayoutStore, Paginator, PositionedElement};
 
 // Template types
 pub use template_core::{
-    CompiledTemplate, DataSourceFormat, ExecutionConfig, TemplateExecutor, TemplateFeatures,
-    TemplateFlags, TemplateMetadata, TemplateParser,
+    CompiledTemplate, DataSourceFormat, ExecutionConfig, NumberLocale, TemplateExecutor,
+    TemplateFeatures, TemplateFlags, TemplateMetadata, TemplateParser,
 };
 
 // Geometry and colors
 pub use types_base::{AnchorId, BoxConstraints, Color, IndexTerm, Rect, ResourceUri, Size};
 
+// Rendering options
+pub use render_core::utils::{CoordinatePrecision, RoundingMode};
+
 // Traits for extensibility
-pub use traits::{Executor, FontProvider, ResourceProvider};
+pub use traits::{Executor, FontProvider, ResourceError, ResourceLimits, ResourceProvider};
 
 // Pipeline module (orchestration layer - stays in main crate)
 pub mod pipeline;
 
 // Public API
-pub use crate::pipeline::{GenerationMode, PdfBackend, PipelineBuilder, ProcessingMode};
+pub use crate::pipeline::{
+    AutoModeThresholds, DocumentPostprocessor, GenerationMode, PageCount, PdfBackend,
+    PdfOutputOptions, PipelineBuilder, ProcessingMode, UnresolvedResourcePolicy,
+};
 
 // Helper trait for error conversion
 pub(crate) trait MapRenderError<T> {
@@ -197,6 +203,7 @@ impl<T> MapComposerError<T> for Result<T, pdf_composer::ComposerError> {
     fn map_composer_err(self) -> Result<T, PipelineError> {
         self.map_err(|e| match e {
             pdf_composer::ComposerError::Pdf(lopdf_err) => PipelineError::Pdf(lopdf_err),
+            pdf_composer::ComposerError::Io(io_err) => PipelineError::Io(io_err),
             pdf_composer::ComposerError::Other(msg) => PipelineError::Other(msg),
         })
     }
@@ -5,6 +5,7 @@
 
 use petty_core::error::PipelineError;
 use petty_core::parser::processor::{TemplateFeatures, TemplateParser};
+use petty_core::style_types::stylesheet::Stylesheet;
 use std::path::PathBuf;
 
 /// Adapter that wraps a petty_template_core::TemplateParser to implement
@@ -32,4 +33,17 @@ where
             self.inner.parse(template_source, resource_base_path)?,
         ))
     }
+
+    fn parse_with_theme(
+        &self,
+        template_source: &str,
+        resource_base_path: PathBuf,
+        theme: &Stylesheet,
+    ) -> Result<TemplateFeatures, PipelineError> {
+        Ok(TemplateFeatures::from_core(self.inner.parse_with_theme(
+            template_source,
+            resource_base_path,
+            theme,
+        )?))
+    }
 }
@@ -27,6 +27,11 @@
 //! controller.record_item_processed(Duration::from_millis(50));
 //! controller.record_queue_depth(10);
 //!
+//! // Observe scaling decisions
+//! controller.on_scaling_change(|event| {
+//!     log::info!("workers {} -> {}", event.previous_workers, event.current_workers);
+//! });
+//!
 //! // Check if scaling is recommended (requires adaptive-scaling feature)
 //! #[cfg(feature = "adaptive-scaling")]
 //! if controller.should_scale_up() {
@@ -34,8 +39,8 @@
 //! }
 //! ```
 
-use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
 /// Configuration for the adaptive controller.
@@ -103,10 +108,37 @@ pub struct AdaptiveController {
     current_queue_depth: AtomicUsize,
     /// Last adjustment timestamp (nanos since start_time)
     last_adjustment_nanos: AtomicU64,
+    /// Number of times a worker was added
+    scale_up_count: AtomicUsize,
+    /// Number of times a worker was removed
+    scale_down_count: AtomicUsize,
     /// Start time for throughput calculation
     start_time: Instant,
     /// Configuration
     config: AdaptiveConfig,
+    /// Callbacks invoked after every worker count change
+    scaling_listeners: RwLock<Vec<ScalingCallback>>,
+}
+
+/// A callback invoked when the adaptive controller changes the worker count.
+pub type ScalingCallback = Arc<dyn Fn(&ScalingEvent) + Send + Sync>;
+
+/// Describes a single change in worker count.
+#[derive(Debug, Clone)]
+pub struct ScalingEvent {
+    /// Worker count before the change
+    pub previous_workers: usize,
+    /// Worker count after the change
+    pub current_workers: usize,
+    /// Metrics snapshot taken right after the change
+    pub metrics: AdaptiveMetrics,
+}
+
+impl ScalingEvent {
+    /// Returns true if this event added a worker.
+    pub fn is_scale_up(&self) -> bool {
+        self.current_workers > self.previous_workers
+    }
 }
 
 impl AdaptiveController {
@@ -126,8 +158,11 @@ impl AdaptiveController {
             queue_high_water: AtomicUsize::new(0),
             current_queue_depth: AtomicUsize::new(0),
             last_adjustment_nanos: AtomicU64::new(0),
+            scale_up_count: AtomicUsize::new(0),
+            scale_down_count: AtomicUsize::new(0),
             start_time: Instant::now(),
             config,
+            scaling_listeners: RwLock::new(Vec::new()),
         }
     }
 
@@ -248,6 +283,8 @@ impl AdaptiveController {
     pub fn increment_workers(&self) -> usize {
         let new_count = self.current_worker_count.fetch_add(1, Ordering::AcqRel) + 1;
         self.update_last_adjustment();
+        self.scale_up_count.fetch_add(1, Ordering::AcqRel);
+        self.notify_scaling_change(new_count - 1, new_count);
         new_count
     }
 
@@ -257,9 +294,41 @@ impl AdaptiveController {
     pub fn decrement_workers(&self) -> usize {
         let old = self.current_worker_count.fetch_sub(1, Ordering::AcqRel);
         self.update_last_adjustment();
+        self.scale_down_count.fetch_add(1, Ordering::AcqRel);
+        self.notify_scaling_change(old, old.saturating_sub(1));
         old.saturating_sub(1)
     }
 
+    /// Registers a callback invoked after every worker count change.
+    ///
+    /// Callbacks run synchronously on the thread that changed the worker count,
+    /// so they should be cheap (e.g. logging or forwarding to a metrics sink).
+    pub fn on_scaling_change<F>(&self, callback: F)
+    where
+        F: Fn(&ScalingEvent) + Send + Sync + 'static,
+    {
+        if let Ok(mut listeners) = self.scaling_listeners.write() {
+            listeners.push(Arc::new(callback));
+        }
+    }
+
+    fn notify_scaling_change(&self, previous_workers: usize, current_workers: usize) {
+        let Ok(listeners) = self.scaling_listeners.read() else {
+            return;
+        };
+        if listeners.is_empty() {
+            return;
+        }
+        let event = ScalingEvent {
+            previous_workers,
+            current_workers,
+            metrics: self.metrics(),
+        };
+        for listener in listeners.iter() {
+            listener(&event);
+        }
+    }
+
     /// Get the suggested worker count adjustment.
     ///
     /// Returns: -1 for scale down, 0 for no change, +1 for scale up.
@@ -283,6 +352,8 @@ impl AdaptiveController {
             throughput: self.throughput(),
             avg_item_time: self.avg_item_time(),
             elapsed: self.start_time.elapsed(),
+            scale_ups: self.scale_up_count.load(Ordering::Acquire),
+            scale_downs: self.scale_down_count.load(Ordering::Acquire),
         }
     }
 
@@ -334,6 +405,10 @@ pub struct AdaptiveMetrics {
     pub avg_item_time: Option<Duration>,
     /// Total elapsed time since controller creation
     pub elapsed: Duration,
+    /// Number of workers added by adaptive scaling
+    pub scale_ups: usize,
+    /// Number of workers removed by adaptive scaling
+    pub scale_downs: usize,
 }
 
 impl AdaptiveMetrics {
@@ -718,6 +793,52 @@ mod tests {
         assert!(metrics.avg_item_time.is_some());
     }
 
+    #[test]
+    fn test_scaling_changes_are_reported() {
+        use std::sync::Mutex;
+
+        let config = AdaptiveConfig {
+            min_workers: 2,
+            max_workers: 8,
+            scale_up_threshold: 2.0,
+            scale_down_threshold: 0.5,
+            adjustment_cooldown: Duration::from_millis(0),
+            scaling_check_interval: 10,
+            max_in_flight_buffer: 2,
+        };
+        let controller = Arc::new(AdaptiveController::with_config(2, config));
+        let manager = WorkerManager::new(Arc::clone(&controller));
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        controller.on_scaling_change(move |event| {
+            sink.lock()
+                .unwrap()
+                .push((event.previous_workers, event.current_workers));
+        });
+
+        // Synthetic load: a deep queue triggers two scale-ups.
+        for _ in 0..2 {
+            controller.record_queue_depth(20);
+            assert_eq!(manager.check_and_adjust(), 1);
+            manager.worker_spawned();
+        }
+        let metrics = controller.metrics();
+        assert_eq!(metrics.current_workers, 4);
+        assert_eq!(metrics.queue_depth, 20);
+        assert_eq!(metrics.scale_ups, 2);
+
+        // The queue drains: one worker is asked to shut down.
+        controller.record_queue_depth(0);
+        assert_eq!(manager.check_and_adjust(), -1);
+        assert!(manager.should_worker_shutdown());
+        let metrics = controller.metrics();
+        assert_eq!(metrics.current_workers, 3);
+        assert_eq!(metrics.scale_downs, 1);
+
+        assert_eq!(*events.lock().unwrap(), vec![(2, 3), (3, 4), (4, 3)]);
+    }
+
     #[test]
     fn test_metrics_health_check() {
         let metrics = AdaptiveMetrics {
@@ -728,6 +849,8 @@ mod tests {
             throughput: 50.0,
             avg_item_time: Some(Duration::from_millis(20)),
             elapsed: Duration::from_secs(2),
+            scale_ups: 0,
+            scale_downs: 0,
         };
 
         assert!(metrics.is_healthy()); // 6 <= 4*2 = 8
@@ -21,14 +21,18 @@ impl<T: Read + Seek + Send> ReadSeekSend for T {}
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Document {
-    /// The total number of pages in the main body of the document.
+    /// The number of pages in the main body of the document. Role templates and
+    /// page overlays see the whole document's count instead, including the pages
+    /// role templates add.
     pub page_count: usize,
     /// The ISO 8601 timestamp of when the document was built.
     pub build_timestamp: String,
     /// A hierarchical list of all headings in the document.
     pub headings: Vec<Heading>,
-    /// A list of all figures in the document.
+    /// A list of all captioned figures in the document, for a list of figures.
     pub figures: Vec<Figure>,
+    /// A list of all captioned tables in the document, for a list of tables.
+    pub tables: Vec<Figure>,
     /// A list of all index entries.
     pub index_entries: Vec<IndexEntry>,
     /// A list of all named anchors and their locations.
@@ -51,12 +55,16 @@ pub struct Heading {
     pub page_number: usize,
 }
 
-/// Represents a figure (e.g., an image with a caption).
+/// Represents a captioned figure or table.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Figure {
     /// The unique ID of the figure.
     pub id: String,
+    /// The caption's number among captions of the same kind.
+    pub number: usize,
+    /// The numbered label, e.g. "Figure 2" or "Table 1".
+    pub label: String,
     /// The caption text associated with the figure.
     pub caption: Option<String>,
     /// The page number on which this figure appears.
@@ -136,9 +144,12 @@ mod tests {
             ],
             figures: vec![Figure {
                 id: "fig-1".to_string(),
+                number: 1,
+                label: "Figure 1".to_string(),
                 caption: Some("My Figure".to_string()),
                 page_number: 3,
             }],
+            tables: vec![],
             index_entries: vec![IndexEntry {
                 text: "topic".to_string(),
                 page_number: 5,
@@ -175,10 +186,13 @@ mod tests {
           "figures": [
             {
               "id": "fig-1",
+              "number": 1,
+              "label": "Figure 1",
               "caption": "My Figure",
               "pageNumber": 3
             }
           ],
+          "tables": [],
           "indexEntries": [
             {
               "text": "topic",
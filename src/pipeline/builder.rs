@@ -1,5 +1,8 @@
-use super::adaptive::{AdaptiveConfig, AdaptiveScalingFacade};
-use super::config::{GenerationMode, PdfBackend, PipelineCacheConfig, ProcessingMode};
+use super::adaptive::{AdaptiveConfig, AdaptiveScalingFacade, ScalingCallback, ScalingEvent};
+use super::config::{
+    AutoModeThresholds, GenerationMode, PdfBackend, PdfOutputOptions, PipelineCacheConfig,
+    ProcessingMode, UnresolvedResourcePolicy,
+};
 use super::orchestrator::DocumentPipeline;
 use crate::pipeline::context::PipelineContext;
 use crate::pipeline::provider::Provider;
@@ -11,9 +14,15 @@ use crate::pipeline::renderer::streaming::SinglePassStreamingRenderer;
 use petty_core::error::PipelineError;
 use petty_core::layout::fonts::SharedFontLibrary;
 use petty_core::parser::processor::{TemplateFeatures, TemplateParser};
-use petty_core::traits::ResourceProvider;
+use petty_core::style_types::stylesheet::Stylesheet;
+use petty_core::traits::{ResourceLimits, ResourceProvider};
+use petty_html_template::HtmlParser;
 use petty_json_template::JsonParser;
+use petty_markdown_template::MarkdownParser;
+use petty_pdf_composer::Attachment;
+use petty_render_core::utils::CoordinatePrecision;
 use petty_resource::FilesystemResourceProvider;
+use petty_template_core::NumberLocale;
 use petty_template_dsl::Template;
 use petty_xslt::XsltParser;
 use petty_xslt3::{Xslt3Parser, XsltVersion, detect_xslt_version};
@@ -21,22 +30,40 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
+
+/// The longer side of a page thumbnail in pixels, the size viewers traditionally
+/// show in their page panel.
+const DEFAULT_THUMBNAIL_SIZE: u32 = 106;
 
 /// A builder for creating a `DocumentPipeline`.
 pub struct PipelineBuilder {
     template_features: Option<TemplateFeatures>,
+    /// Base stylesheet that templates are layered on top of.
+    theme: Option<Stylesheet>,
     pdf_backend: PdfBackend,
     // Use the thread-safe SharedFontLibrary instead of FontManager
     font_library: SharedFontLibrary,
     resource_provider: Arc<dyn ResourceProvider>,
+    unresolved_resources: UnresolvedResourcePolicy,
+    resource_limits: ResourceLimits,
     generation_mode: GenerationMode,
+    auto_mode: AutoModeThresholds,
     processing_mode: ProcessingMode,
     cache_config: PipelineCacheConfig,
+    output: PdfOutputOptions,
+    number_locale: NumberLocale,
+    preserve_key_order: bool,
+    safe_mode: bool,
+    max_template_steps: Option<u64>,
+    current_time: Option<SystemTime>,
     debug: bool,
     /// Optional explicit worker count (None = auto-detect from env or CPU count)
     worker_count: Option<usize>,
     /// Maximum workers for adaptive scaling (None = use AdaptiveConfig default)
     max_workers: Option<usize>,
+    /// Observer for adaptive worker count changes
+    scaling_callback: Option<ScalingCallback>,
     /// Buffer size for pipelining (rendered pages buffered before writing)
     /// Higher values trade memory for throughput. Default: 64.
     render_buffer_size: usize,
@@ -53,15 +80,26 @@ impl Default for PipelineBuilder {
 
         Self {
             template_features: None,
+            theme: None,
             pdf_backend: Default::default(),
             font_library,
             resource_provider,
+            unresolved_resources: Default::default(),
+            resource_limits: Default::default(),
             generation_mode: Default::default(),
+            auto_mode: Default::default(),
             processing_mode: Default::default(),
             cache_config: Default::default(),
+            output: Default::default(),
+            number_locale: Default::default(),
+            preserve_key_order: false,
+            safe_mode: false,
+            max_template_steps: None,
+            current_time: None,
             debug: false,
             worker_count: None,
             max_workers: None,
+            scaling_callback: None,
             render_buffer_size: 16, // Default: buffer 16 pages (benchmarks show smaller is optimal)
         }
     }
@@ -74,7 +112,7 @@ impl PipelineBuilder {
     }
 
     /// Configures the pipeline by loading a template from a file.
-    /// The template language (XSLT, JSON) is inferred from the file extension.
+    /// The template language (XSLT, JSON, HTML, Markdown) is inferred from the file extension.
     /// For XSLT files, the version is auto-detected from the version attribute.
     pub fn with_template_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self, PipelineError> {
         let path_ref = path.as_ref();
@@ -98,12 +136,13 @@ impl PipelineBuilder {
             "xslt" | "xsl" | "fo" => self.get_xslt_parser_for_source(&template_source),
             _ => self.get_parser_for_extension(extension)?,
         };
-        self.template_features = Some(parser.parse(&template_source, resource_base_path)?);
+        self.template_features =
+            Some(self.parse_template(parser.as_ref(), &template_source, resource_base_path)?);
         Ok(self)
     }
 
     /// Configures the pipeline with a template from a string.
-    /// The `extension` argument is used to select the correct parser ("json", "xslt", etc.).
+    /// The `extension` argument is used to select the correct parser ("json", "xslt", "html", "md", etc.).
     /// For XSLT, the version is auto-detected from the version attribute.
     pub fn with_template_source(
         mut self,
@@ -115,7 +154,8 @@ impl PipelineBuilder {
             "xslt" | "xsl" | "fo" => self.get_xslt_parser_for_source(source),
             _ => self.get_parser_for_extension(extension)?,
         };
-        self.template_features = Some(parser.parse(source, resource_base_path)?);
+        self.template_features =
+            Some(self.parse_template(parser.as_ref(), source, resource_base_path)?);
         Ok(self)
     }
 
@@ -126,7 +166,24 @@ impl PipelineBuilder {
         let template_source = template.to_json()?;
         let parser = TemplateParserAdapter::new(JsonParser);
         let resource_base_path = PathBuf::new();
-        self.template_features = Some(parser.parse(&template_source, resource_base_path)?);
+        self.template_features =
+            Some(self.parse_template(&parser, &template_source, resource_base_path)?);
+        Ok(self)
+    }
+
+    /// Registers a base stylesheet (theme) that every template is layered on top of,
+    /// so shared typography and page masters can live in one place. Styles with the
+    /// same name are merged property by property, with the template's values winning.
+    ///
+    /// The theme is applied when the template is compiled, so it must be registered
+    /// before `with_template_file`, `with_template_source` or `with_template_object`.
+    pub fn with_theme(mut self, theme: Stylesheet) -> Result<Self, PipelineError> {
+        if self.template_features.is_some() {
+            return Err(PipelineError::Config(
+                "`with_theme` must be called before the template is configured.".to_string(),
+            ));
+        }
+        self.theme = Some(theme);
         Ok(self)
     }
 
@@ -144,6 +201,14 @@ impl PipelineBuilder {
         self
     }
 
+    /// Registers hyphenation patterns for `language` (e.g. "en", "de-CH"), in TeX
+    /// pattern format. Dictionaries are parsed the first time they are used.
+    pub fn with_hyphenation_patterns(self, language: &str, patterns: impl Into<Vec<u8>>) -> Self {
+        self.font_library
+            .register_hyphenation_patterns(language, patterns);
+        self
+    }
+
     /// Selects the PDF rendering backend to use.
     pub fn with_pdf_backend(mut self, backend: PdfBackend) -> Self {
         self.pdf_backend = backend;
@@ -157,12 +222,125 @@ impl PipelineBuilder {
         self
     }
 
+    /// Streams templates without hard multi-pass dependencies when more than
+    /// `records` records are expected (see [`with_expected_record_count`](Self::with_expected_record_count)).
+    ///
+    /// Only affects [`GenerationMode::Auto`]. See [`AutoModeThresholds`] for details.
+    pub fn with_streaming_threshold(mut self, records: usize) -> Self {
+        self.auto_mode.streaming_record_threshold = Some(records);
+        self
+    }
+
+    /// Tells [`GenerationMode::Auto`] how many records to expect, so it can apply
+    /// the streaming threshold.
+    pub fn with_expected_record_count(mut self, count: usize) -> Self {
+        self.auto_mode.expected_record_count = Some(count);
+        self
+    }
+
     /// Configures the memory management and caching behavior of the pipeline.
     pub fn with_cache_config(mut self, config: PipelineCacheConfig) -> Self {
         self.cache_config = config;
         self
     }
 
+    /// Sets the font family of text whose style does not name one, and of text
+    /// whose family cannot be found. Defaults to the built-in Helvetica.
+    pub fn with_default_font_family(mut self, family: impl Into<String>) -> Self {
+        self.cache_config.default_font_family = family.into();
+        self
+    }
+
+    /// Writes a linearized ("Fast Web View") PDF, so the first page can be shown
+    /// before the whole file has downloaded.
+    ///
+    /// The rendered document is reloaded in memory to reorder its objects, which costs
+    /// memory proportional to the output size on the streaming pipeline.
+    pub fn with_linearization(mut self, enabled: bool) -> Self {
+        self.output.linearize = enabled;
+        self
+    }
+
+    /// Compresses the output with object streams and a cross-reference stream
+    /// (PDF 1.5+), which typically makes files noticeably smaller.
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.output.compress = enabled;
+        self
+    }
+
+    /// Clips every page's content to the page area, so content that overflows
+    /// the page edges is cut off rather than drawn outside the MediaBox.
+    pub fn with_page_clipping(mut self, enabled: bool) -> Self {
+        self.output.clip_to_page = enabled;
+        self
+    }
+
+    /// Rounds the coordinates written to page content to `precision`, e.g. to keep
+    /// snapshot tests stable against sub-pixel differences in layout.
+    pub fn with_coordinate_precision(mut self, precision: CoordinatePrecision) -> Self {
+        self.output.coordinate_precision = Some(precision);
+        self
+    }
+
+    /// Recompresses embedded raster images to JPEG at `quality` (1 to 100), for
+    /// those that come out smaller that way. Images with transparency are kept
+    /// lossless.
+    pub fn with_jpeg_quality(mut self, quality: u8) -> Self {
+        self.output.images.jpeg_quality = Some(quality);
+        self
+    }
+
+    /// Downsamples embedded raster images to at most `dpi` pixels per inch at the
+    /// size they are drawn, e.g. so a large logo in a small box does not inflate
+    /// the file.
+    pub fn with_max_image_dpi(mut self, dpi: f32) -> Self {
+        self.output.images.max_dpi = Some(dpi);
+        self
+    }
+
+    /// Tags embedded raster images that carry no ICC profile of their own with
+    /// `profile` (a gray or RGB ICC profile), for color-managed workflows.
+    pub fn with_image_icc_profile(mut self, profile: impl Into<Vec<u8>>) -> Self {
+        self.output.images.icc_profile = Some(Arc::new(profile.into()));
+        self
+    }
+
+    /// Embeds a small rasterized preview of every page as its `/Thumb` image.
+    /// Thumbnails are drawn from the laid-out page, so text needs system fonts;
+    /// without the `native` feature no thumbnails are written.
+    pub fn with_page_thumbnails(mut self, enabled: bool) -> Self {
+        self.output.thumbnail_size = enabled.then_some(DEFAULT_THUMBNAIL_SIZE);
+        self
+    }
+
+    /// Embeds a file in the output, e.g. the JSON or XML an invoice was generated
+    /// from. The file is listed in the `/EmbeddedFiles` name tree and the catalog's
+    /// `/AF` (associated files) array.
+    pub fn with_attachment(
+        mut self,
+        name: impl Into<String>,
+        mime_type: impl Into<String>,
+        data: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.output
+            .attachments
+            .push(Attachment::new(name, mime_type, data));
+        self
+    }
+
+    /// Registers a hook that can edit the composed `lopdf::Document` after rendering
+    /// and before it is written, for features the engine does not support natively.
+    ///
+    /// Like linearization, this makes the streaming pipeline buffer the whole
+    /// document in memory.
+    pub fn with_document_postprocessor<F>(mut self, postprocessor: F) -> Self
+    where
+        F: Fn(&mut lopdf::Document) + Send + Sync + 'static,
+    {
+        self.output.postprocessor = Some(Arc::new(postprocessor));
+        self
+    }
+
     /// Enables debug features, such as dumping the layout IR tree.
     pub fn with_debug(mut self, debug: bool) -> Self {
         self.debug = debug;
@@ -253,6 +431,29 @@ impl PipelineBuilder {
         self
     }
 
+    /// Registers a callback invoked each time adaptive scaling changes the worker count.
+    ///
+    /// Only has an effect with `ProcessingMode::WithMetrics` or `ProcessingMode::Adaptive`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let pipeline = PipelineBuilder::new()
+    ///     .with_template_file("template.json")?
+    ///     .with_processing_mode(ProcessingMode::Adaptive)
+    ///     .with_scaling_callback(|event| {
+    ///         log::info!("workers: {} -> {}", event.previous_workers, event.current_workers);
+    ///     })
+    ///     .build()?;
+    /// ```
+    pub fn with_scaling_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ScalingEvent) + Send + Sync + 'static,
+    {
+        self.scaling_callback = Some(Arc::new(callback));
+        self
+    }
+
     /// Sets the processing mode for the pipeline.
     ///
     /// This controls how work items are processed and whether metrics are collected.
@@ -295,6 +496,68 @@ impl PipelineBuilder {
         self
     }
 
+    /// Sets what happens when an image or other resource cannot be loaded.
+    ///
+    /// Defaults to [`UnresolvedResourcePolicy::SkipWithPlaceholder`], which draws a
+    /// placeholder box and continues. Use `Fail` to abort generation instead, or
+    /// `Callback` to supply fallback bytes.
+    pub fn with_unresolved_resource_policy(mut self, policy: UnresolvedResourcePolicy) -> Self {
+        self.unresolved_resources = policy;
+        self
+    }
+
+    /// Caps how many bytes and resources each document may load and how large its
+    /// images may be, so untrusted templates cannot exhaust memory. A document that
+    /// breaks a limit fails to generate, whatever the unresolved resource policy.
+    pub fn with_resource_limits(mut self, limits: ResourceLimits) -> Self {
+        self.resource_limits = limits;
+        self
+    }
+
+    /// Sets the decimal and grouping separators that number-formatting functions
+    /// write when the template does not choose a decimal format itself, e.g.
+    /// `NumberLocale::from_language_tag("de")` for `1.234,56`.
+    pub fn with_number_locale(mut self, locale: NumberLocale) -> Self {
+        self.number_locale = locale;
+        self
+    }
+
+    /// Keeps the keys of JSON data records in source order instead of sorting
+    /// them alphabetically, so XSLT templates process child elements in the
+    /// same order as they would for the equivalent XML document.
+    pub fn with_preserved_key_order(mut self, enabled: bool) -> Self {
+        self.preserve_key_order = enabled;
+        self
+    }
+
+    /// Sandboxes templates so they cannot read files or URLs, for services that
+    /// render templates they do not trust. See [`ExecutionConfig::safe_mode`].
+    ///
+    /// [`ExecutionConfig::safe_mode`]: petty_template_core::ExecutionConfig::safe_mode
+    pub fn with_safe_mode(mut self, enabled: bool) -> Self {
+        self.safe_mode = enabled;
+        self
+    }
+
+    /// Aborts a document whose template executes more than `steps` instructions,
+    /// so a stylesheet that loops or recurses without end fails instead of
+    /// hanging a worker. See [`ExecutionConfig::max_steps`].
+    ///
+    /// [`ExecutionConfig::max_steps`]: petty_template_core::ExecutionConfig::max_steps
+    pub fn with_max_template_steps(mut self, steps: u64) -> Self {
+        self.max_template_steps = Some(steps);
+        self
+    }
+
+    /// Fixes the time templates see as the current date and time, for reproducible
+    /// builds and stable test output. See [`ExecutionConfig::current_time`].
+    ///
+    /// [`ExecutionConfig::current_time`]: petty_template_core::ExecutionConfig::current_time
+    pub fn with_current_time(mut self, time: SystemTime) -> Self {
+        self.current_time = Some(time);
+        self
+    }
+
     /// Consumes the builder and creates the `DocumentPipeline`.
     /// This is where the generation strategy is selected and instantiated.
     pub fn build(mut self) -> Result<DocumentPipeline, PipelineError> {
@@ -323,10 +586,13 @@ impl PipelineBuilder {
                 AdaptiveConfig::default()
             };
 
-            Some(Arc::new(AdaptiveScalingFacade::new(
-                initial_workers,
-                config,
-            )))
+            let facade = AdaptiveScalingFacade::new(initial_workers, config);
+            if let Some(callback) = self.scaling_callback.take() {
+                facade
+                    .controller()
+                    .on_scaling_change(move |event| callback(event));
+            }
+            Some(Arc::new(facade))
         } else {
             None
         };
@@ -336,7 +602,15 @@ impl PipelineBuilder {
             role_templates: Arc::new(template_features.role_templates),
             font_library: Arc::new(self.font_library),
             resource_provider: self.resource_provider,
+            unresolved_resources: self.unresolved_resources,
+            resource_limits: self.resource_limits,
             cache_config: self.cache_config,
+            output: self.output,
+            number_locale: self.number_locale,
+            preserve_key_order: self.preserve_key_order,
+            safe_mode: self.safe_mode,
+            max_template_steps: self.max_template_steps,
+            current_time: self.current_time,
             adaptive: adaptive.clone(),
         });
 
@@ -357,13 +631,7 @@ impl PipelineBuilder {
                 renderer = self.create_simple_renderer();
             }
             GenerationMode::Auto => {
-                let flags = features.main_template.features();
-                if !features.role_templates.is_empty()
-                    || flags.uses_index_function
-                    || flags.has_table_of_contents
-                    || flags.has_page_number_placeholders
-                    || flags.has_internal_links
-                {
+                if self.requires_composing(features) {
                     log::info!(
                         "Template uses advanced features. Selecting Metadata Generating pipeline."
                     );
@@ -382,6 +650,23 @@ impl PipelineBuilder {
         Ok((provider, renderer))
     }
 
+    /// Decides whether `GenerationMode::Auto` needs the composing pipeline.
+    fn requires_composing(&self, features: &TemplateFeatures) -> bool {
+        if features.has_dependencies() {
+            return true;
+        }
+        if !features.main_template.features().has_internal_links {
+            return false;
+        }
+        if self.auto_mode.prefers_streaming() {
+            log::info!(
+                "Template has internal links, but the expected dataset exceeds the streaming threshold."
+            );
+            return false;
+        }
+        true
+    }
+
     /// Creates the streaming renderer for simple templates.
     fn create_simple_renderer(&self) -> Renderer {
         log::info!(
@@ -397,6 +682,18 @@ impl PipelineBuilder {
         ))
     }
 
+    fn parse_template(
+        &self,
+        parser: &dyn TemplateParser,
+        source: &str,
+        resource_base_path: PathBuf,
+    ) -> Result<TemplateFeatures, PipelineError> {
+        match &self.theme {
+            Some(theme) => parser.parse_with_theme(source, resource_base_path, theme),
+            None => parser.parse(source, resource_base_path),
+        }
+    }
+
     fn get_parser_for_extension(
         &self,
         extension: &str,
@@ -406,6 +703,8 @@ impl PipelineBuilder {
         match extension {
             "xslt" | "xsl" | "fo" => Ok(Box::new(TemplateParserAdapter::new(XsltParser))),
             "json" => Ok(Box::new(TemplateParserAdapter::new(JsonParser))),
+            "html" | "htm" => Ok(Box::new(TemplateParserAdapter::new(HtmlParser))),
+            "md" | "markdown" => Ok(Box::new(TemplateParserAdapter::new(MarkdownParser))),
             _ => Err(PipelineError::Config(format!(
                 "Unsupported template file extension: .{}",
                 extension
@@ -432,3 +731,143 @@ impl PipelineBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn builder_for(template: serde_json::Value) -> PipelineBuilder {
+        PipelineBuilder::new()
+            .with_template_source(&template.to_string(), "json")
+            .unwrap()
+    }
+
+    fn selects_composing(builder: &PipelineBuilder) -> bool {
+        let features = builder.template_features.as_ref().unwrap();
+        let (_, renderer) = builder.select_components(features).unwrap();
+        matches!(renderer, Renderer::Composing(_))
+    }
+
+    fn stylesheet() -> serde_json::Value {
+        json!({ "defaultPageMaster": "default", "pageMasters": { "default": { "size": "A4" } } })
+    }
+
+    #[test]
+    fn test_toc_forces_composing_above_threshold() {
+        let builder = builder_for(json!({
+            "_stylesheet": stylesheet(),
+            "_template": { "type": "Block", "children": [ { "type": "TableOfContents" } ] }
+        }))
+        .with_streaming_threshold(100)
+        .with_expected_record_count(10_000);
+
+        assert!(selects_composing(&builder));
+    }
+
+    #[test]
+    fn test_large_dataset_picks_streaming_under_threshold() {
+        let template = json!({
+            "_stylesheet": stylesheet(),
+            "_template": { "type": "Paragraph", "children": [
+                { "type": "Hyperlink", "href": "#top", "children": [ { "type": "Text", "content": "Top" } ] }
+            ] }
+        });
+
+        // Internal links alone select composing by default...
+        assert!(selects_composing(&builder_for(template.clone())));
+
+        // ...but a dataset above the configured threshold is streamed.
+        let builder = builder_for(template.clone())
+            .with_streaming_threshold(100)
+            .with_expected_record_count(10_000);
+        assert!(!selects_composing(&builder));
+
+        // At or below the threshold, composing is kept.
+        let builder = builder_for(template)
+            .with_streaming_threshold(100)
+            .with_expected_record_count(100);
+        assert!(selects_composing(&builder));
+    }
+
+    #[test]
+    fn test_html_template_source_is_parsed() {
+        let builder = PipelineBuilder::new()
+            .with_template_source(
+                r##"<h1 id="top">Title</h1><p><a href="#top">Top</a></p>"##,
+                "html",
+            )
+            .unwrap();
+        assert!(selects_composing(&builder));
+
+        let builder = PipelineBuilder::new()
+            .with_template_source("<p>Plain</p>", "htm")
+            .unwrap();
+        assert!(!selects_composing(&builder));
+    }
+
+    #[test]
+    fn test_theme_is_merged_beneath_template_styles() {
+        use petty_core::idf::IRNode;
+        use petty_core::layout::style::{compute_style, get_default_style};
+        use petty_core::style_types::stylesheet::ElementStyle;
+        use petty_core::types_base::Color;
+        use petty_template_core::{DataSourceFormat, ExecutionConfig};
+        use std::collections::HashMap;
+
+        let theme = Stylesheet {
+            styles: HashMap::from([(
+                "body".to_string(),
+                Arc::new(ElementStyle {
+                    font_family: Some("Times".to_string()),
+                    font_size: Some(11.0),
+                    color: Some(Color::gray(50)),
+                    ..Default::default()
+                }),
+            )]),
+            ..Default::default()
+        };
+        let template = json!({
+            "_stylesheet": {
+                "defaultPageMaster": "default",
+                "pageMasters": { "default": { "size": "A4" } },
+                "styles": { "body": { "color": "#2a4d69" } }
+            },
+            "_template": { "type": "Paragraph", "styleNames": ["body"], "children": [
+                { "type": "Text", "content": "Themed" }
+            ] }
+        });
+
+        let builder = PipelineBuilder::new()
+            .with_theme(theme)
+            .unwrap()
+            .with_template_source(&template.to_string(), "json")
+            .unwrap();
+        let features = builder.template_features.as_ref().unwrap();
+        let config = ExecutionConfig {
+            format: DataSourceFormat::Json,
+            strict: false,
+            ..Default::default()
+        };
+        let ir = features.main_template.execute("{}", config).unwrap();
+        let Some(IRNode::Paragraph { meta, .. }) = ir.first() else {
+            panic!("expected a paragraph, got {ir:?}");
+        };
+
+        let style = compute_style(&meta.style_sets, None, &get_default_style());
+        assert_eq!(style.text.font_family.as_str(), "Times");
+        assert_eq!(style.text.font_size, 11.0);
+        assert_eq!(
+            style.text.color,
+            Color {
+                r: 0x2a,
+                g: 0x4d,
+                b: 0x69,
+                a: 1.0
+            }
+        );
+
+        // A theme cannot be applied to an already compiled template.
+        assert!(builder.with_theme(Stylesheet::default()).is_err());
+    }
+}
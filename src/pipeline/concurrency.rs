@@ -30,17 +30,17 @@ use log::{debug, info, warn};
 use lopdf::dictionary;
 use petty_core::ApiIndexEntry;
 use petty_core::error::PipelineError;
-use petty_layout::LayoutEngine;
+use petty_core::traits::ResourceBudget;
+use petty_layout::{LayoutEngine, PositionedElement};
 use petty_render_core::DocumentRenderer;
 use petty_render_core::{HyperlinkLocation, Pass1Result, ResolvedAnchor};
-use petty_render_lopdf::LopdfRenderer;
-use petty_template_core::{DataSourceFormat, ExecutionConfig};
+use petty_render_lopdf::{LopdfRenderer, StreamingPdfWriter};
 #[cfg(feature = "rayon-executor")]
 use rayon::prelude::*;
 use serde_json::Value;
 use std::collections::{BTreeMap, HashMap};
 use std::io::{Seek, Write};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 use tokio::task;
@@ -214,6 +214,8 @@ pub struct DynamicWorkerPool {
     context: Arc<PipelineContext>,
     /// Receiver for work items (cloned for each new worker)
     work_receiver: WorkItemReceiver,
+    /// The resource budget of the document being generated, shared by all workers
+    resource_budget: Arc<Mutex<ResourceBudget>>,
     /// Worker manager for scaling decisions
     worker_manager: Arc<WorkerManager>,
     /// Handles for dynamically spawned workers
@@ -229,6 +231,7 @@ impl DynamicWorkerPool {
     ///
     /// * `context` - Pipeline context with shared resources
     /// * `work_receiver` - Receiver for work items (will be cloned for new workers)
+    /// * `resource_budget` - The document's resource budget, shared with the initial workers
     /// * `worker_manager` - Manager for scaling decisions
     /// * `initial_worker_count` - Number of initially spawned workers (for ID assignment)
     ///
@@ -238,12 +241,14 @@ impl DynamicWorkerPool {
     pub fn new(
         context: Arc<PipelineContext>,
         work_receiver: WorkItemReceiver,
+        resource_budget: Arc<Mutex<ResourceBudget>>,
         worker_manager: Arc<WorkerManager>,
         initial_worker_count: usize,
     ) -> Self {
         Self {
             context,
             work_receiver,
+            resource_budget,
             worker_manager,
             worker_handles: Vec::new(),
             next_worker_id: initial_worker_count,
@@ -295,8 +300,11 @@ impl DynamicWorkerPool {
         let tx_clone = result_sender.clone();
         let current_font_lib = self.context.font_library.clone();
         let template_clone = Arc::clone(&self.context.compiled_template);
+        let exec_config = self.context.execution_config();
         let resource_provider_clone = Arc::clone(&self.context.resource_provider);
-        let cache_config = self.context.cache_config;
+        let unresolved_resources = self.context.unresolved_resources.clone();
+        let resource_budget = Arc::clone(&self.resource_budget);
+        let cache_config = self.context.cache_config.clone();
         let adaptive_controller = self.context.adaptive_controller();
         let worker_manager = Some(Arc::clone(&self.worker_manager));
 
@@ -311,18 +319,16 @@ impl DynamicWorkerPool {
                 let (index, work_result) = match result {
                     Ok(work_item) => {
                         // Use pre-serialized string from producer
-                        let exec_config = ExecutionConfig {
-                            format: DataSourceFormat::Json,
-                            strict: false,
-                        };
                         let layout_result = template_clone
-                            .execute(&work_item.serialized, exec_config)
+                            .execute(&work_item.serialized, exec_config.clone())
                             .and_then(|ir_nodes| {
                                 finish_layout_and_resource_loading(
                                     worker_id,
                                     ir_nodes,
                                     work_item.data.clone(),
                                     resource_provider_clone.as_ref(),
+                                    &unresolved_resources,
+                                    &resource_budget,
                                     &mut layout_engine,
                                     &template_clone.stylesheet(),
                                     false,
@@ -578,12 +584,11 @@ pub(crate) async fn producer_task<I>(
 pub(crate) fn spawn_workers(
     num_threads: usize,
     context: &PipelineContext,
+    resource_budget: &Arc<Mutex<ResourceBudget>>,
     rx: WorkItemReceiver,
     tx: LayoutResultSender,
 ) -> Vec<task::JoinHandle<()>> {
     let mut handles = Vec::new();
-    let cache_config = context.cache_config;
-
     for worker_id in 0..num_threads {
         let rx_clone = rx.clone();
         let tx_clone = tx.clone();
@@ -593,7 +598,11 @@ pub(crate) fn spawn_workers(
         // avoiding repeated I/O and cache misses.
         let current_font_lib = context.font_library.clone();
         let template_clone = Arc::clone(&context.compiled_template);
+        let exec_config = context.execution_config();
         let resource_provider_clone = Arc::clone(&context.resource_provider);
+        let unresolved_resources = context.unresolved_resources.clone();
+        let resource_budget = Arc::clone(resource_budget);
+        let cache_config = context.cache_config.clone();
 
         // Clone the adaptive controller for metrics recording
         let adaptive_controller = context.adaptive_controller();
@@ -620,18 +629,16 @@ pub(crate) fn spawn_workers(
                 let (index, work_result) = match result {
                     Ok(work_item) => {
                         // Use pre-serialized string from producer (no redundant serialization!)
-                        let exec_config = ExecutionConfig {
-                            format: DataSourceFormat::Json,
-                            strict: false,
-                        };
                         let layout_result = template_clone
-                            .execute(&work_item.serialized, exec_config)
+                            .execute(&work_item.serialized, exec_config.clone())
                             .and_then(|ir_nodes| {
                                 finish_layout_and_resource_loading(
                                     worker_id,
                                     ir_nodes,
                                     work_item.data.clone(),
                                     resource_provider_clone.as_ref(),
+                                    &unresolved_resources,
+                                    &resource_budget,
                                     &mut layout_engine,
                                     &template_clone.stylesheet(),
                                     false,
@@ -777,7 +784,8 @@ where
     let mut next_sequence_idx = 0;
     let mut all_page_ids = Vec::new();
     let mut pass1_result = Pass1Result::default();
-    let mut global_page_offset = 0;
+    let mut pending_links = Vec::new();
+    let mut page_masters = Vec::new();
 
     let font_map: HashMap<String, String> = renderer
         .layout_engine
@@ -828,47 +836,40 @@ where
             let process_start = Instant::now();
             let seq = res?;
 
+            let page_offset = all_page_ids.len();
+
+            // Anchors are resolved in both modes: streaming needs them for link annotations.
+            for (name, anchor) in &seq.defined_anchors {
+                pass1_result.resolved_anchors.insert(
+                    name.clone(),
+                    ResolvedAnchor {
+                        global_page_index: page_offset + anchor.local_page_index + 1,
+                        y_pos: anchor.y_pos,
+                    },
+                );
+            }
+
             // Analysis pass: collect metadata
             if perform_analysis {
                 pass1_result.toc_entries.extend(seq.toc_entries.clone());
-                for (name, anchor) in &seq.defined_anchors {
-                    pass1_result.resolved_anchors.insert(
-                        name.clone(),
-                        ResolvedAnchor {
-                            global_page_index: global_page_offset + anchor.local_page_index + 1,
-                            y_pos: anchor.y_pos,
-                        },
-                    );
-                }
+                pass1_result
+                    .caption_entries
+                    .extend(seq.caption_entries.clone());
                 for (term, locations) in &seq.index_entries {
                     for loc in locations {
                         pass1_result.index_entries.push(ApiIndexEntry {
                             text: term.clone(),
-                            page_number: global_page_offset + loc.local_page_index + 1,
+                            page_number: page_offset + loc.local_page_index + 1,
                         });
                     }
                 }
                 for (local_page_idx, page_elements) in seq.pages.iter().enumerate() {
-                    let current_global_page_idx = global_page_offset + local_page_idx + 1;
-                    for el in page_elements {
-                        use petty_layout::LayoutElement;
-                        let href = match &el.element {
-                            LayoutElement::Text(t) => t.href.as_ref(),
-                            _ => None,
-                        };
-                        if let Some(href_str) = href
-                            && let Some(target_id) = href_str.strip_prefix('#')
-                        {
-                            pass1_result.hyperlink_locations.push(HyperlinkLocation {
-                                global_page_index: current_global_page_idx,
-                                rect: [el.x, el.y, el.x + el.width, el.y + el.height],
-                                target_id: target_id.to_string(),
-                            });
-                        }
-                    }
+                    pass1_result.hyperlink_locations.extend(internal_links(
+                        page_elements,
+                        page_offset + local_page_idx + 1,
+                    ));
                 }
                 pass1_result.total_pages += seq.pages.len();
-                global_page_offset += seq.pages.len();
             } else if !seq.toc_entries.is_empty() || !seq.index_entries.is_empty() {
                 return Err(PipelineError::Config(
                     "Template uses advanced features (ToC/Index) which require Auto (metadata) mode."
@@ -877,10 +878,33 @@ where
             }
 
             renderer.add_resources(&seq.resources).map_render_err()?;
+            page_masters.extend(seq.page_masters);
+
+            let print_marks = renderer.print_marks();
 
             // Parallel page rendering (when feature is enabled)
             #[cfg(feature = "parallel-render")]
             {
+                let mut thumbnails = Vec::with_capacity(seq.pages.len());
+                for page in &seq.pages {
+                    thumbnails.push(
+                        renderer
+                            .write_thumbnail(page, page_width, page_height)
+                            .map_render_err()?,
+                    );
+                }
+                let writer = renderer.writer_mut().unwrap();
+                let mut page_annots = Vec::with_capacity(seq.pages.len());
+                for (local_page_idx, page) in seq.pages.iter().enumerate() {
+                    page_annots.push(page_annotations(
+                        writer,
+                        page,
+                        page_offset + local_page_idx + 1,
+                        page_height,
+                        !perform_analysis,
+                        &mut pending_links,
+                    )?);
+                }
                 let content_results = petty_render_lopdf::render_pages_parallel(
                     seq.pages,
                     &font_map,
@@ -888,20 +912,32 @@ where
                     page_height,
                 );
 
-                for content_result in content_results {
-                    let content = content_result.map_render_err()?;
+                for ((content_result, annots), thumbnail) in
+                    content_results.into_iter().zip(page_annots).zip(thumbnails)
+                {
+                    let content = renderer.finish_page_content(
+                        content_result.map_render_err()?,
+                        page_width,
+                        page_height,
+                    );
                     let writer = renderer.writer_mut().unwrap();
                     let content_id = writer
                         .write_content_stream(content)
                         .map_err(|e| PipelineError::Render(e.into()))?;
 
-                    let page_dict = dictionary! {
+                    let mut page_dict = dictionary! {
                         "Type" => "Page",
                         "Parent" => writer.pages_id,
-                        "MediaBox" => vec![0.0.into(), 0.0.into(), page_width.into(), page_height.into()],
                         "Contents" => content_id,
                         "Resources" => writer.resources_id,
                     };
+                    print_marks.set_page_boxes(&mut page_dict, page_width, page_height);
+                    if let Some(thumbnail) = thumbnail {
+                        page_dict.set("Thumb", thumbnail);
+                    }
+                    if let Some(annots) = annots {
+                        page_dict.set("Annots", annots);
+                    }
 
                     let page_id = writer.write_object(page_dict.into())?;
                     all_page_ids.push(page_id);
@@ -911,7 +947,18 @@ where
             // Sequential page rendering (default)
             #[cfg(not(feature = "parallel-render"))]
             {
-                for page_elements in seq.pages {
+                for (local_page_idx, page_elements) in seq.pages.into_iter().enumerate() {
+                    let annots = page_annotations(
+                        renderer.writer_mut().unwrap(),
+                        &page_elements,
+                        page_offset + local_page_idx + 1,
+                        page_height,
+                        !perform_analysis,
+                        &mut pending_links,
+                    )?;
+                    let thumbnail = renderer
+                        .write_thumbnail(&page_elements, page_width, page_height)
+                        .map_render_err()?;
                     let content = petty_render_lopdf::render_elements_to_content(
                         page_elements,
                         &font_map,
@@ -919,18 +966,25 @@ where
                         page_height,
                     )
                     .map_render_err()?;
+                    let content = renderer.finish_page_content(content, page_width, page_height);
                     let writer = renderer.writer_mut().unwrap();
                     let content_id = writer
                         .write_content_stream(content)
                         .map_err(|e| PipelineError::Render(e.into()))?;
 
-                    let page_dict = dictionary! {
+                    let mut page_dict = dictionary! {
                         "Type" => "Page",
                         "Parent" => writer.pages_id,
-                        "MediaBox" => vec![0.0.into(), 0.0.into(), page_width.into(), page_height.into()],
                         "Contents" => content_id,
                         "Resources" => writer.resources_id,
                     };
+                    print_marks.set_page_boxes(&mut page_dict, page_width, page_height);
+                    if let Some(thumbnail) = thumbnail {
+                        page_dict.set("Thumb", thumbnail);
+                    }
+                    if let Some(annots) = annots {
+                        page_dict.set("Annots", annots);
+                    }
 
                     let page_id = writer.write_object(page_dict.into())?;
                     all_page_ids.push(page_id);
@@ -945,9 +999,138 @@ where
         }
     }
 
+    let page_labels = petty_render_lopdf::build_page_labels(&page_masters, &renderer.stylesheet);
+    // Every anchor becomes a named destination, so other documents can link to it.
+    let writer = renderer.writer_mut().unwrap();
+    writer.set_page_labels(page_labels);
+    for (name, anchor) in &pass1_result.resolved_anchors {
+        if let Some(page_id) = all_page_ids.get(anchor.global_page_index - 1) {
+            writer.add_named_destination(name.as_str(), *page_id, page_height - anchor.y_pos);
+        }
+    }
+
+    if !pending_links.is_empty() {
+        resolve_pending_links(
+            writer,
+            pending_links,
+            &pass1_result,
+            &all_page_ids,
+            page_height,
+        );
+    }
+
     Ok((all_page_ids, pass1_result))
 }
 
+/// A page whose `/Annots` array was reserved because it holds internal links, which
+/// may point at anchors on pages that have not been laid out yet.
+struct PendingPageLinks {
+    annots_id: lopdf::ObjectId,
+    /// Annotations that were created when the page was written.
+    annots: Vec<lopdf::ObjectId>,
+    links: Vec<HyperlinkLocation>,
+}
+
+/// Collects the `#fragment` hyperlinks on a page.
+fn internal_links(
+    page_elements: &[PositionedElement],
+    page_index: usize,
+) -> Vec<HyperlinkLocation> {
+    petty_render_lopdf::link_fragments(page_elements)
+        .into_iter()
+        .filter_map(|fragment| {
+            Some(HyperlinkLocation {
+                global_page_index: page_index,
+                rect: fragment.rect,
+                target_id: fragment.href.strip_prefix('#')?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Builds the `/Annots` entry for a page: form widgets and external links, plus a
+/// reserved array for internal links when `link_internal` is set. Returns `None` if the
+/// page has no annotations.
+fn page_annotations<W: Write + Seek>(
+    writer: &mut StreamingPdfWriter<W>,
+    page_elements: &[PositionedElement],
+    page_index: usize,
+    page_height: f32,
+    link_internal: bool,
+    pending_links: &mut Vec<PendingPageLinks>,
+) -> Result<Option<lopdf::Object>, PipelineError> {
+    let mut annots = petty_render_lopdf::create_form_widgets(writer, page_elements, page_height)
+        .map_render_err()?;
+    annots.extend(petty_render_lopdf::create_uri_link_annotations(
+        writer,
+        page_elements,
+        page_height,
+    ));
+    let links = if link_internal {
+        internal_links(page_elements, page_index)
+    } else {
+        Vec::new()
+    };
+
+    if !links.is_empty() {
+        let annots_id = writer.new_object_id();
+        pending_links.push(PendingPageLinks {
+            annots_id,
+            annots,
+            links,
+        });
+        return Ok(Some(annots_id.into()));
+    }
+    Ok((!annots.is_empty()).then(|| {
+        annots
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>()
+            .into()
+    }))
+}
+
+/// Writes the reserved `/Annots` arrays once every anchor in the document is known.
+fn resolve_pending_links<W: Write + Seek>(
+    writer: &mut StreamingPdfWriter<W>,
+    pending_links: Vec<PendingPageLinks>,
+    pass1_result: &Pass1Result,
+    page_ids: &[lopdf::ObjectId],
+    page_height: f32,
+) {
+    for page in pending_links {
+        let mut annots = page.annots;
+        for link in page.links {
+            let target = pass1_result
+                .resolved_anchors
+                .get(&link.target_id)
+                .and_then(|anchor| {
+                    Some((
+                        page_ids.get(anchor.global_page_index.checked_sub(1)?)?,
+                        anchor,
+                    ))
+                });
+            match target {
+                Some((target_page_id, anchor)) => {
+                    annots.push(petty_render_lopdf::create_goto_link_annotation(
+                        writer,
+                        link.rect,
+                        *target_page_id,
+                        anchor.y_pos,
+                        page_height,
+                    ));
+                }
+                None => warn!(
+                    "[CONSUMER] Link target '#{}' on page {} was not found.",
+                    link.target_id, link.global_page_index
+                ),
+            }
+        }
+        let annots: Vec<lopdf::Object> = annots.into_iter().map(Into::into).collect();
+        writer.buffer_object_at_id(page.annots_id, annots.into());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,13 @@
 // Reuse the configuration struct defined in the core layout module to ensure synergy.
 pub use crate::layout::LayoutConfig as PipelineCacheConfig;
 
+use petty_core::idf::SharedData;
+use petty_pdf_composer::Attachment;
+use petty_render_core::utils::CoordinatePrecision;
+use petty_render_lopdf::ImageOptions;
+use std::fmt;
+use std::sync::Arc;
+
 /// An enum to select the desired PDF rendering backend.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum PdfBackend {
@@ -29,6 +36,87 @@ pub enum GenerationMode {
     ForceStreaming,
 }
 
+/// Tuning for how [`GenerationMode::Auto`] chooses between the streaming and
+/// composing (metadata) pipelines.
+///
+/// Templates with hard multi-pass dependencies (a table of contents, "page X of Y"
+/// placeholders, role templates or `petty:index()`) always use the composing pipeline.
+/// Templates whose only multi-pass feature is internal links use it too, unless the
+/// expected dataset is larger than `streaming_record_threshold`. Above that size the
+/// cost of buffering the whole dataset outweighs resolving links across records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AutoModeThresholds {
+    /// Stream templates without hard dependencies when more than this many records
+    /// are expected. `None` (the default) never switches.
+    pub streaming_record_threshold: Option<usize>,
+    /// The number of records the caller expects to generate, if known.
+    pub expected_record_count: Option<usize>,
+}
+
+impl AutoModeThresholds {
+    /// Returns true if the expected dataset is large enough to prefer streaming.
+    pub fn prefers_streaming(&self) -> bool {
+        matches!(
+            (self.streaming_record_threshold, self.expected_record_count),
+            (Some(threshold), Some(count)) if count > threshold
+        )
+    }
+}
+
+/// A user hook that edits the finished `lopdf` document before it is written,
+/// e.g. to add annotations or attachments the engine does not produce itself.
+pub type DocumentPostprocessor = dyn Fn(&mut lopdf::Document) + Send + Sync;
+
+/// Options applied when the final PDF is serialized.
+#[derive(Clone, Default)]
+pub struct PdfOutputOptions {
+    /// Write a linearized ("Fast Web View") file, so viewers can display the first
+    /// page before the rest of the document has downloaded.
+    pub linearize: bool,
+    /// Pack objects into compressed object streams and write a cross-reference
+    /// stream (PDF 1.5+). Linearized output keeps its content streams compressed
+    /// but is written without object streams.
+    pub compress: bool,
+    /// Clip each page's content to its MediaBox, so overflowing content cannot
+    /// draw outside the page.
+    pub clip_to_page: bool,
+    /// Round the coordinates in page content to this precision, so sub-pixel
+    /// noise from layout does not make otherwise identical output differ.
+    pub coordinate_precision: Option<CoordinatePrecision>,
+    /// How raster images are encoded when they are embedded.
+    pub images: ImageOptions,
+    /// Embed a `/Thumb` image of this size (the longer side, in pixels) in every
+    /// page, for viewers that show page previews from embedded thumbnails.
+    pub thumbnail_size: Option<u32>,
+    /// Files embedded in the document and listed as associated files.
+    pub attachments: Vec<Attachment>,
+    /// Runs on the composed document after rendering and before serialization.
+    pub postprocessor: Option<Arc<DocumentPostprocessor>>,
+}
+
+impl fmt::Debug for PdfOutputOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PdfOutputOptions")
+            .field("linearize", &self.linearize)
+            .field("compress", &self.compress)
+            .field("clip_to_page", &self.clip_to_page)
+            .field("coordinate_precision", &self.coordinate_precision)
+            .field("images", &self.images)
+            .field("thumbnail_size", &self.thumbnail_size)
+            .field("attachments", &self.attachments)
+            .field("postprocessor", &self.postprocessor.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl PdfOutputOptions {
+    /// Returns true if the rendered output must be reloaded as a whole document
+    /// before it can be written.
+    pub fn requires_document_pass(&self) -> bool {
+        self.linearize || !self.attachments.is_empty() || self.postprocessor.is_some()
+    }
+}
+
 /// Configuration for how work items are processed by the pipeline.
 ///
 /// This controls both metrics collection and adaptive worker scaling behavior.
@@ -64,3 +152,32 @@ pub enum ProcessingMode {
     /// Without the feature, this behaves the same as `WithMetrics`.
     Adaptive,
 }
+
+/// A user handler for resources the `ResourceProvider` could not load.
+///
+/// Receives the resource URI and returns fallback bytes, or `None` to fall back
+/// to a placeholder.
+pub type ResourceFallbackFn = dyn Fn(&str) -> Option<SharedData> + Send + Sync;
+
+/// Controls what happens when a referenced resource (e.g. an image) cannot be loaded.
+#[derive(Clone, Default)]
+pub enum UnresolvedResourcePolicy {
+    /// Abort generation with an error naming the missing resource.
+    Fail,
+    /// Log a warning and draw a placeholder box in place of the missing image. (Default)
+    #[default]
+    SkipWithPlaceholder,
+    /// Ask a user handler for fallback bytes. If the handler returns `None`,
+    /// a placeholder is drawn as with `SkipWithPlaceholder`.
+    Callback(Arc<ResourceFallbackFn>),
+}
+
+impl fmt::Debug for UnresolvedResourcePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fail => write!(f, "Fail"),
+            Self::SkipWithPlaceholder => write!(f, "SkipWithPlaceholder"),
+            Self::Callback(_) => write!(f, "Callback(..)"),
+        }
+    }
+}
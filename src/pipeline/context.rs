@@ -1,10 +1,12 @@
 use crate::pipeline::adaptive::{AdaptiveController, AdaptiveScalingFacade, WorkerManager};
-use crate::pipeline::config::PipelineCacheConfig;
+use crate::pipeline::config::{PdfOutputOptions, PipelineCacheConfig, UnresolvedResourcePolicy};
 use petty_core::layout::fonts::SharedFontLibrary;
 use petty_core::parser::processor::CompiledTemplate;
-use petty_core::traits::ResourceProvider;
+use petty_core::traits::{ResourceLimits, ResourceProvider};
+use petty_template_core::{DataSourceFormat, ExecutionConfig, NumberLocale};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 /// A container for all shared, read-only resources needed during a pipeline run.
 /// This will be created once by the `PipelineBuilder` and passed to the various
@@ -12,6 +14,7 @@ use std::sync::Arc;
 ///
 /// The context holds trait objects for platform-agnostic abstractions:
 /// - `ResourceProvider`: Loads images and other external resources
+/// - `UnresolvedResourcePolicy`: Decides what happens when a resource cannot be loaded
 /// - `AdaptiveScalingFacade`: Optional facade for metrics and adaptive scaling
 ///
 /// # Adaptive Scaling
@@ -25,13 +28,43 @@ pub struct PipelineContext {
     pub role_templates: Arc<HashMap<String, Arc<dyn CompiledTemplate>>>,
     pub font_library: Arc<SharedFontLibrary>,
     pub resource_provider: Arc<dyn ResourceProvider>,
+    /// What to do when the resource provider cannot load a referenced resource.
+    pub unresolved_resources: UnresolvedResourcePolicy,
+    /// Caps on the resources each document may load.
+    pub resource_limits: ResourceLimits,
     pub cache_config: PipelineCacheConfig,
+    /// How the final PDF is serialized.
+    pub output: PdfOutputOptions,
+    /// The default separators for numbers formatted by templates.
+    pub number_locale: NumberLocale,
+    /// Whether templates see JSON object keys in source order, see
+    /// [`ExecutionConfig::preserve_key_order`].
+    pub preserve_key_order: bool,
+    /// Whether templates run sandboxed, see [`ExecutionConfig::safe_mode`].
+    pub safe_mode: bool,
+    /// The instruction budget of each template run, see [`ExecutionConfig::max_steps`].
+    pub max_template_steps: Option<u64>,
+    /// The fixed current time of templates, see [`ExecutionConfig::current_time`].
+    pub current_time: Option<SystemTime>,
     /// Optional adaptive scaling facade for metrics collection and dynamic scaling.
     /// Replaces the separate `adaptive_controller` and `worker_manager` fields.
     pub adaptive: Option<Arc<AdaptiveScalingFacade>>,
 }
 
 impl PipelineContext {
+    /// The configuration for executing a template against one JSON data record.
+    pub fn execution_config(&self) -> ExecutionConfig {
+        ExecutionConfig {
+            format: DataSourceFormat::Json,
+            strict: false,
+            number_locale: self.number_locale,
+            preserve_key_order: self.preserve_key_order,
+            safe_mode: self.safe_mode,
+            max_steps: self.max_template_steps,
+            current_time: self.current_time,
+        }
+    }
+
     /// Get the adaptive controller if available.
     ///
     /// This is a convenience method that extracts the controller from the facade.
@@ -39,13 +39,18 @@ pub(crate) mod worker;
 
 // Core public API
 pub use builder::PipelineBuilder;
-pub use config::{GenerationMode, PdfBackend, ProcessingMode};
+pub use config::{
+    AutoModeThresholds, DocumentPostprocessor, GenerationMode, PdfBackend, PdfOutputOptions,
+    ProcessingMode, UnresolvedResourcePolicy,
+};
+pub use orchestrator::PageCount;
 
 // Adaptive scaling API
 // Public API exports for adaptive scaling and metrics collection (always available)
 #[allow(unused_imports)]
 pub use adaptive::{
-    AdaptiveConfig, AdaptiveController, AdaptiveMetrics, AdaptiveScalingFacade, WorkerManager,
+    AdaptiveConfig, AdaptiveController, AdaptiveMetrics, AdaptiveScalingFacade, ScalingCallback,
+    ScalingEvent, WorkerManager,
 };
 
 // Re-export Rayon configuration for parallel rendering
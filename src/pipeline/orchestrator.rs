@@ -1,17 +1,32 @@
 // src/pipeline/orchestrator.rs
+use crate::MapComposerError;
 use crate::pipeline::adaptive::{AdaptiveMetrics, AdaptiveScalingFacade};
 use crate::pipeline::context::PipelineContext;
 use crate::pipeline::provider::{DataSourceProvider, Provider};
-use crate::pipeline::renderer::{Renderer, RenderingStrategy};
+use crate::pipeline::renderer::{Renderer, RenderingStrategy, write_document};
+use crate::pipeline::worker::{finish_layout_and_resource_loading, hash_sequence_content};
 use petty_core::error::PipelineError;
+use petty_layout::LayoutEngine;
+use petty_pdf_composer::merge_documents;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::io;
-use std::path::Path;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tokio::runtime::Builder;
 use tokio::task;
 
+/// The number of pages a document will have, as reported by
+/// [`DocumentPipeline::page_count`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageCount {
+    /// Pages in the whole document.
+    pub total: usize,
+    /// Pages per sequence, one entry per input record, in input order.
+    pub sequences: Vec<usize>,
+}
+
 /// The main document generation pipeline.
 ///
 /// This struct holds the configured provider and renderer and orchestrates
@@ -129,6 +144,73 @@ impl DocumentPipeline {
         .unwrap() // Propagate panics from the spawned task
     }
 
+    /// Computes a stable hash of the logical content of the document `data` would
+    /// generate, as a lowercase hex SHA-256 digest.
+    ///
+    /// The hash covers the IR produced by the template and the bytes of every image
+    /// it references, but not the rendered output. Identical input therefore hashes
+    /// identically even though the PDF bytes differ between runs (generated IDs,
+    /// timestamps), which makes it suitable as a cache or deduplication key.
+    pub fn content_hash<I>(&self, data: I) -> Result<String, PipelineError>
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        let template = &self.context.compiled_template;
+        let mut hasher = Sha256::new();
+
+        for record in data {
+            let nodes = template.execute(&record.to_string(), self.context.execution_config())?;
+            // Separates sequences so that content cannot shift across a boundary.
+            hasher.update((nodes.len() as u64).to_le_bytes());
+            hash_sequence_content(&nodes, self.context.resource_provider.as_ref(), &mut hasher);
+        }
+
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+
+    /// Counts the pages the document generated from `data` will have, running
+    /// the template and layout but not rendering.
+    ///
+    /// This is the first pass of a two-pass generation: the count can be fed back
+    /// as data, e.g. for a cover page or "Page X of Y" footers. Pages added by
+    /// role templates, such as a generated table of contents, are not included,
+    /// as those are laid out from the finished document's metadata.
+    pub fn page_count<I>(&self, data: I) -> Result<PageCount, PipelineError>
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        let context = &self.context;
+        let template = &context.compiled_template;
+        let stylesheet = template.stylesheet();
+        let mut layout_engine =
+            LayoutEngine::new(&context.font_library, context.cache_config.clone());
+        let resource_budget = Mutex::new(context.resource_limits.budget());
+        let mut count = PageCount::default();
+
+        for record in data {
+            let nodes = template.execute(&record.to_string(), context.execution_config())?;
+            let sequence = finish_layout_and_resource_loading(
+                0,
+                nodes,
+                Arc::new(record),
+                context.resource_provider.as_ref(),
+                &context.unresolved_resources,
+                &resource_budget,
+                &mut layout_engine,
+                &stylesheet,
+                false,
+            )?;
+            count.total += sequence.pages.len();
+            count.sequences.push(sequence.pages.len());
+        }
+
+        Ok(count)
+    }
+
     /// A convenience method to generate a document to a file path from a dataset in memory
     /// or a lazy iterator.
     pub fn generate_to_file<P, I>(&self, data: I, path: P) -> Result<(), PipelineError>
@@ -137,21 +219,90 @@ impl DocumentPipeline {
         I: IntoIterator<Item = Value> + Send + 'static,
         I::IntoIter: Send + 'static,
     {
-        let output_path = path.as_ref();
-        if let Some(parent_dir) = output_path.parent() {
-            fs::create_dir_all(parent_dir)?;
-        }
-        let file = fs::File::create(output_path)?;
-        let writer = io::BufWriter::new(file);
-
-        let rt = Builder::new_multi_thread()
-            .enable_all()
-            .build()
-            .expect("Failed to create Tokio runtime");
+        let writer = create_output_file(path.as_ref())?;
+        let rt = create_runtime();
 
         rt.block_on(self.generate(data.into_iter(), writer))?;
         Ok(())
     }
+
+    /// Generates a document from `data` and appends its pages to the existing PDF
+    /// at `path`, rewriting the file in place.
+    ///
+    /// The existing document is the merge target, so its metadata, outlines and
+    /// page labels are kept; only the pages of the new content are copied over.
+    /// The file is left untouched if loading, generation or merging fails.
+    pub fn append_to_file<P, I>(&self, data: I, path: P) -> Result<(), PipelineError>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = Value> + Send + 'static,
+        I::IntoIter: Send + 'static,
+    {
+        let path = path.as_ref();
+        let mut existing = lopdf::Document::load(path)?;
+
+        let rt = create_runtime();
+        let generated =
+            rt.block_on(self.generate(data.into_iter(), io::Cursor::new(Vec::new())))?;
+        let appended = lopdf::Document::load_mem(generated.get_ref())?;
+        merge_documents(&mut existing, appended, false).map_composer_err()?;
+
+        let mut combined = Vec::new();
+        write_document(&mut existing, &self.context.output, &mut combined)?;
+        fs::write(path, combined)?;
+        Ok(())
+    }
+
+    /// Generates one independent document per record, using the same template for each.
+    ///
+    /// `path_for` is called with the zero-based index and the record to choose where
+    /// that record's document is written. Returns the paths of the written files, in
+    /// input order. Generation stops at the first error.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let paths = pipeline.generate_split(invoices, |i, record| {
+    ///     format!("out/invoice-{}.pdf", record["id"].as_str().unwrap_or(&i.to_string()))
+    /// })?;
+    /// ```
+    pub fn generate_split<I, F, P>(
+        &self,
+        data: I,
+        mut path_for: F,
+    ) -> Result<Vec<PathBuf>, PipelineError>
+    where
+        I: IntoIterator<Item = Value>,
+        F: FnMut(usize, &Value) -> P,
+        P: Into<PathBuf>,
+    {
+        let rt = create_runtime();
+        let mut outputs = Vec::new();
+
+        for (index, record) in data.into_iter().enumerate() {
+            let output_path = path_for(index, &record).into();
+            let writer = create_output_file(&output_path)?;
+            rt.block_on(self.generate(std::iter::once(record), writer))?;
+            outputs.push(output_path);
+        }
+
+        Ok(outputs)
+    }
+}
+
+fn create_output_file(output_path: &Path) -> Result<io::BufWriter<fs::File>, PipelineError> {
+    if let Some(parent_dir) = output_path.parent() {
+        fs::create_dir_all(parent_dir)?;
+    }
+    let file = fs::File::create(output_path)?;
+    Ok(io::BufWriter::new(file))
+}
+
+fn create_runtime() -> tokio::runtime::Runtime {
+    Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create Tokio runtime")
 }
 
 #[cfg(test)]
@@ -211,6 +362,95 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_fn_data_source_drives_generation() {
+        use crate::source::{DataSource, FnDataSource};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let template_json = json!({
+            "_stylesheet": {
+                "defaultPageMaster": "default",
+                "pageMasters": { "default": { "size": "A4", "margins": "1cm" } }
+            },
+            "_template": {
+                "type": "Paragraph",
+                "children": [ { "type": "Text", "content": "Row {{id}}" } ]
+            }
+        });
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_json.to_string(), "json")
+            .unwrap()
+            .with_pdf_backend(PdfBackend::Lopdf)
+            .build()
+            .unwrap();
+
+        // Stands in for a database cursor: three rows, then exhausted.
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cursor_calls = Arc::clone(&calls);
+        let source = FnDataSource::new(move || {
+            let call = cursor_calls.fetch_add(1, Ordering::SeqCst) + 1;
+            (call <= 3).then(|| json!({ "id": call }))
+        })
+        .with_size_hint(3);
+
+        let writer = Cursor::new(Vec::new());
+        let writer = pipeline
+            .generate(source.into_iterator(), writer)
+            .await
+            .unwrap();
+
+        let pdf_content = String::from_utf8_lossy(writer.get_ref()).into_owned();
+        for row in ["Row 1", "Row 2", "Row 3"] {
+            assert!(pdf_content.contains(row), "Output should contain '{}'", row);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_content_hash_is_stable_across_runs() {
+        let template_json = json!({
+            "_stylesheet": {
+                "defaultPageMaster": "default",
+                "pageMasters": { "default": { "size": "A4", "margins": "1cm" } }
+            },
+            "_template": {
+                "type": "Block",
+                "children": [
+                    { "type": "TableOfContents" },
+                    { "type": "Heading", "level": 1, "children": [ { "type": "Text", "content": "Invoice {{id}}" } ] },
+                    { "type": "Paragraph", "children": [ { "type": "Text", "content": "Total {{total}}" } ] }
+                ]
+            }
+        });
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_json.to_string(), "json")
+            .unwrap()
+            .with_generation_mode(GenerationMode::Auto)
+            .with_pdf_backend(PdfBackend::Lopdf)
+            .build()
+            .unwrap();
+        let data = vec![json!({"id": 7, "total": 12}), json!({"id": 8, "total": 3})];
+
+        let first = pipeline.content_hash(data.clone()).unwrap();
+        let second = pipeline.content_hash(data.clone()).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 64);
+
+        let changed = vec![json!({"id": 7, "total": 13}), json!({"id": 8, "total": 3})];
+        assert_ne!(pipeline.content_hash(changed).unwrap(), first);
+
+        // The rendered bytes are not stable: headings get randomly suffixed IDs.
+        let first_pdf = pipeline
+            .generate(data.clone().into_iter(), Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+        let second_pdf = pipeline
+            .generate(data.into_iter(), Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+        assert_ne!(first_pdf.get_ref(), second_pdf.get_ref());
+    }
+
     #[tokio::test]
     async fn test_metadata_pipeline_with_links_and_outlines() {
         // This test verifies the advanced path (MetadataGeneratingProvider -> ComposingRenderer)
@@ -270,6 +510,215 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_streaming_internal_link_is_goto_to_anchor_page() {
+        let template_json = json!({
+            "_stylesheet": { "defaultPageMaster": "default", "pageMasters": { "default": { "size": "A4", "margins": "1cm" } } },
+            "_template": { "type": "Block", "children": [
+                { "type": "Paragraph", "children": [ { "type": "Hyperlink", "href": "#details", "children": [ { "type": "Text", "content": "See details" } ] } ] },
+                { "type": "PageBreak" },
+                { "type": "Block", "id": "details", "children": [ { "type": "Paragraph", "children": [ { "type": "Text", "content": "Details" } ] } ] }
+            ]}
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_str, "json")
+            .unwrap()
+            .with_generation_mode(GenerationMode::ForceStreaming)
+            .build()
+            .unwrap();
+
+        let data = vec![json!({})];
+        let writer = pipeline
+            .generate(data.into_iter(), Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+
+        let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 2);
+
+        let annots = doc
+            .get_dictionary(pages[&1])
+            .unwrap()
+            .get_deref(b"Annots", &doc)
+            .and_then(lopdf::Object::as_array)
+            .expect("Page 1 should have an Annots array for the hyperlink");
+        assert_eq!(annots.len(), 1);
+
+        let annot = doc
+            .get_dictionary(annots[0].as_reference().unwrap())
+            .unwrap();
+        assert_eq!(annot.get(b"Subtype").unwrap().as_name().unwrap(), b"Link");
+        let action = annot
+            .get_deref(b"A", &doc)
+            .and_then(lopdf::Object::as_dict)
+            .unwrap();
+        assert_eq!(action.get(b"S").unwrap().as_name().unwrap(), b"GoTo");
+        assert!(action.get(b"URI").is_err());
+        let dest = action.get(b"D").unwrap().as_array().unwrap();
+        assert_eq!(dest[0].as_reference().unwrap(), pages[&2]);
+    }
+
+    #[tokio::test]
+    async fn test_anchors_are_emitted_as_named_destinations() {
+        let template_json = json!({
+            "_stylesheet": { "defaultPageMaster": "default", "pageMasters": { "default": { "size": "A4", "margins": "1cm" } } },
+            "_template": { "type": "Block", "children": [
+                { "type": "Paragraph", "children": [ { "type": "Text", "content": "Intro" } ] },
+                { "type": "PageBreak" },
+                { "type": "Block", "id": "summary", "children": [ { "type": "Paragraph", "children": [ { "type": "Text", "content": "Summary" } ] } ] }
+            ]}
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_str, "json")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let data = vec![json!({})];
+        let writer = pipeline
+            .generate(data.into_iter(), Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+
+        let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+        let dests = petty_pdf_composer::named_destinations(&doc).unwrap();
+        let dest = dests[b"summary".as_slice()].as_array().unwrap();
+        assert_eq!(dest[0].as_reference().unwrap(), doc.get_pages()[&2]);
+    }
+
+    #[tokio::test]
+    async fn test_page_labels_follow_page_master_numbering() {
+        let template_json = json!({
+            "_stylesheet": {
+                "defaultPageMaster": "front",
+                "pageMasters": {
+                    "front": { "size": "A4", "margins": "1cm", "pageNumbering": { "style": "lower-roman" } },
+                    "body": { "size": "A4", "margins": "1cm", "pageNumbering": { "style": "decimal" } }
+                }
+            },
+            "_template": { "type": "Block", "children": [
+                { "type": "Paragraph", "children": [ { "type": "Text", "content": "Preface" } ] },
+                { "type": "PageBreak" },
+                { "type": "Paragraph", "children": [ { "type": "Text", "content": "Contents" } ] },
+                { "type": "PageBreak", "masterName": "body" },
+                { "type": "Paragraph", "children": [ { "type": "Text", "content": "Chapter 1" } ] },
+                { "type": "PageBreak" },
+                { "type": "Paragraph", "children": [ { "type": "Text", "content": "Chapter 2" } ] }
+            ]}
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_str, "json")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let data = vec![json!({})];
+        let writer = pipeline
+            .generate(data.into_iter(), Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+
+        let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+        assert_eq!(doc.get_pages().len(), 4);
+        let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let nums = doc
+            .get_dictionary(root_id)
+            .unwrap()
+            .get_deref(b"PageLabels", &doc)
+            .and_then(lopdf::Object::as_dict)
+            .and_then(|labels| labels.get(b"Nums"))
+            .and_then(lopdf::Object::as_array)
+            .expect("The catalog should have a /PageLabels number tree");
+
+        // i, ii, 1, 2
+        assert_eq!(nums.len(), 4);
+        assert_eq!(nums[0].as_i64().unwrap(), 0);
+        let style = |label: &lopdf::Object| {
+            label
+                .as_dict()
+                .unwrap()
+                .get(b"S")
+                .unwrap()
+                .as_name()
+                .unwrap()
+                .to_vec()
+        };
+        assert_eq!(style(&nums[1]), b"r");
+        assert_eq!(nums[2].as_i64().unwrap(), 2);
+        assert_eq!(style(&nums[3]), b"D");
+    }
+
+    #[tokio::test]
+    async fn test_wrapped_uri_link_gets_one_rect_per_line() {
+        let link_text = "the full terms and conditions that apply to every order placed through \
+                         our online store, including shipping, returns and warranty claims";
+        let template_json = json!({
+            "_stylesheet": { "defaultPageMaster": "default", "pageMasters": { "default": { "size": "A4", "margins": "1cm" } } },
+            "_template": { "type": "Paragraph", "children": [
+                { "type": "Text", "content": "Before ordering, please read " },
+                { "type": "Hyperlink", "href": "https://example.com/terms", "children": [ { "type": "Text", "content": link_text } ] }
+            ]}
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_str, "json")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let data = vec![json!({})];
+        let writer = pipeline
+            .generate(data.into_iter(), Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+
+        let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+        let annots = doc
+            .get_dictionary(doc.get_pages()[&1])
+            .unwrap()
+            .get_deref(b"Annots", &doc)
+            .and_then(lopdf::Object::as_array)
+            .expect("Page 1 should have link annotations");
+        assert_eq!(annots.len(), 2, "Expected one link rect per wrapped line");
+
+        let rects: Vec<Vec<f32>> = annots
+            .iter()
+            .map(|annot| {
+                let annot = doc.get_dictionary(annot.as_reference().unwrap()).unwrap();
+                let action = annot
+                    .get_deref(b"A", &doc)
+                    .and_then(lopdf::Object::as_dict)
+                    .unwrap();
+                assert_eq!(action.get(b"S").unwrap().as_name().unwrap(), b"URI");
+                assert_eq!(
+                    action.get(b"URI").unwrap().as_str().unwrap(),
+                    b"https://example.com/terms"
+                );
+                annot
+                    .get(b"Rect")
+                    .unwrap()
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|v| v.as_float().unwrap())
+                    .collect()
+            })
+            .collect();
+
+        // The second line sits entirely below the first, so the gap is not linked.
+        assert!(rects[1][3] <= rects[0][1] + 0.01);
+        // The first fragment starts after the leading text; the second at the margin.
+        assert!(rects[0][0] > rects[1][0]);
+    }
+
     #[test]
     fn test_generate_to_file_creates_file() {
         // Simple test to ensure the convenience method works.
@@ -295,4 +744,297 @@ mod tests {
         let metadata = fs::metadata(&output_path).unwrap();
         assert!(metadata.len() > 0);
     }
+
+    #[tokio::test]
+    async fn test_linearized_output_starts_with_parameter_dictionary() {
+        let template_json = json!({
+            "_stylesheet": { "defaultPageMaster": "default", "pageMasters": { "default": { "size": "A4", "margins": "1cm" } } },
+            "_template": { "type": "Paragraph", "children": [ { "type": "Text", "content": "Hello {{name}}" } ] }
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_str, "json")
+            .unwrap()
+            .with_linearization(true)
+            .build()
+            .unwrap();
+
+        let data = vec![json!({"name": "Alice"}), json!({"name": "Bob"})];
+        let writer = pipeline
+            .generate(data.into_iter(), Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+        let bytes = writer.into_inner();
+
+        let content = String::from_utf8_lossy(&bytes);
+        let first_object = &content[..content.find("endobj").unwrap()];
+        assert!(first_object.contains(" 0 obj\n<</Linearized 1"));
+
+        let doc = lopdf::Document::load_mem(&bytes).expect("Linearized output should load");
+        assert_eq!(doc.get_pages().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_output_uses_object_and_xref_streams() {
+        let template_json = json!({
+            "_stylesheet": { "defaultPageMaster": "default", "pageMasters": { "default": { "size": "A4", "margins": "1cm" } } },
+            "_template": { "type": "Paragraph", "children": [ { "type": "Text", "content": "Hello {{name}}" } ] }
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+        let data: Vec<Value> = (0..20)
+            .map(|i| json!({ "name": format!("record {}", i) }))
+            .collect();
+
+        let mut outputs = Vec::new();
+        for compress in [false, true] {
+            let pipeline = PipelineBuilder::new()
+                .with_template_source(&template_str, "json")
+                .unwrap()
+                .with_compression(compress)
+                .build()
+                .unwrap();
+            let writer = pipeline
+                .generate(data.clone().into_iter(), Cursor::new(Vec::new()))
+                .await
+                .unwrap();
+            outputs.push(writer.into_inner());
+        }
+        let (plain, compressed) = (&outputs[0], &outputs[1]);
+
+        assert!(
+            compressed.len() < plain.len(),
+            "compressed output ({} bytes) should be smaller than plain output ({} bytes)",
+            compressed.len(),
+            plain.len()
+        );
+
+        let doc = lopdf::Document::load_mem(compressed).expect("Compressed output should load");
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 20);
+        let first_page = doc.get_page_content(pages[&1]).unwrap();
+        assert!(String::from_utf8_lossy(&first_page).contains("record 0"));
+        assert!(matches!(
+            doc.reference_table.cross_reference_type,
+            lopdf::xref::XrefType::CrossReferenceStream
+        ));
+        let content = String::from_utf8_lossy(compressed);
+        assert!(content.contains("/Type /ObjStm") || content.contains("/Type/ObjStm"));
+        assert!(!content.contains("\nxref\n"));
+    }
+
+    #[tokio::test]
+    async fn test_page_clipping_wraps_content_in_page_clip() {
+        // A word too long to wrap overflows the narrow page's right edge.
+        let template_json = json!({
+            "_stylesheet": { "defaultPageMaster": "default", "pageMasters": { "default": { "size": { "width": 100, "height": 100 }, "margins": "10pt" } } },
+            "_template": { "type": "Paragraph", "children": [ { "type": "Text", "content": "Overflowingunbreakablewordthatrunsoffthepage" } ] }
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+
+        let mut contents = Vec::new();
+        for clip in [false, true] {
+            let pipeline = PipelineBuilder::new()
+                .with_template_source(&template_str, "json")
+                .unwrap()
+                .with_page_clipping(clip)
+                .build()
+                .unwrap();
+            let writer = pipeline
+                .generate(vec![json!({})].into_iter(), Cursor::new(Vec::new()))
+                .await
+                .unwrap();
+            let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+            let page_id = doc.get_pages()[&1];
+            let content = doc.get_and_decode_page_content(page_id).unwrap();
+            contents.push(content.operations);
+        }
+        let (unclipped, clipped) = (&contents[0], &contents[1]);
+
+        let is_clip = |ops: &[lopdf::content::Operation]| {
+            ops.windows(4).any(|w| {
+                w[0].operator == "q"
+                    && w[1].operator == "re"
+                    && w[1]
+                        .operands
+                        .iter()
+                        .map(|o| o.as_float().unwrap())
+                        .eq([0.0, 0.0, 100.0, 100.0])
+                    && w[2].operator == "W"
+                    && w[3].operator == "n"
+            })
+        };
+        assert!(
+            is_clip(clipped),
+            "expected a page clip at the start of the content"
+        );
+        assert_eq!(clipped.first().unwrap().operator, "q");
+        assert_eq!(clipped.last().unwrap().operator, "Q");
+        assert!(!is_clip(unclipped));
+    }
+
+    #[tokio::test]
+    async fn test_page_count_matches_rendered_page_count() {
+        let template_json = json!({
+            "_stylesheet": { "defaultPageMaster": "default", "pageMasters": { "default": { "size": { "width": 200, "height": 200 }, "margins": "1cm" } } },
+            "_template": { "type": "Block", "children": [ {
+                "each": "sections",
+                "template": { "type": "Block", "children": [
+                    { "type": "Paragraph", "children": [ { "type": "Text", "content": "Section {{this}}" } ] },
+                    { "type": "PageBreak" }
+                ] }
+            } ] }
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_str, "json")
+            .unwrap()
+            .build()
+            .unwrap();
+        let data = vec![json!({ "sections": [1, 2, 3] }), json!({ "sections": [1] })];
+
+        let count = pipeline.page_count(data.clone()).unwrap();
+        assert_eq!(count.sequences.len(), 2);
+        assert!(count.sequences[0] > count.sequences[1]);
+        assert_eq!(count.total, count.sequences.iter().sum::<usize>());
+
+        let writer = pipeline
+            .generate(data.into_iter(), Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+        let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+        assert_eq!(count.total, doc.get_pages().len());
+        assert!(count.total >= 4);
+    }
+
+    #[test]
+    fn test_append_to_file_adds_pages_and_keeps_metadata() {
+        let template_json = json!({
+            "_stylesheet": { "defaultPageMaster": "default", "pageMasters": { "default": { "size": { "width": 200, "height": 200 }, "margins": "1cm" } } },
+            "_template": { "type": "Paragraph", "children": [ { "type": "Text", "content": "Section {{name}}" } ] }
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_str, "json")
+            .unwrap()
+            .build()
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("existing.pdf");
+
+        pipeline
+            .generate_to_file(vec![json!({ "name": "1" }), json!({ "name": "2" })], &path)
+            .unwrap();
+        let mut existing = lopdf::Document::load(&path).unwrap();
+        assert_eq!(existing.get_pages().len(), 2);
+        let mut info = lopdf::Dictionary::new();
+        info.set("Title", lopdf::Object::string_literal("Original Report"));
+        let info_id = existing.add_object(info);
+        existing.trailer.set("Info", info_id);
+        existing.save(&path).unwrap();
+
+        pipeline
+            .append_to_file(vec![json!({ "name": "Addendum" })], &path)
+            .unwrap();
+
+        let doc = lopdf::Document::load(&path).unwrap();
+        assert_eq!(doc.get_pages().len(), 3);
+        let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        let info = doc.get_dictionary(info_id).unwrap();
+        assert_eq!(
+            info.get(b"Title").unwrap().as_str().unwrap(),
+            b"Original Report"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_page_thumbnails_are_embedded_when_enabled() {
+        let template_json = json!({
+            "_stylesheet": { "defaultPageMaster": "default", "pageMasters": { "default": { "size": { "width": 200, "height": 100 }, "margins": "1cm" } } },
+            "_template": { "type": "Paragraph", "children": [ { "type": "Text", "content": "Section {{name}}" } ] }
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+        let pipeline = PipelineBuilder::new()
+            .with_template_source(&template_str, "json")
+            .unwrap()
+            .with_page_thumbnails(true)
+            .build()
+            .unwrap();
+        let data = vec![json!({ "name": "1" }), json!({ "name": "2" })];
+
+        let writer = pipeline
+            .generate(data.into_iter(), Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+        let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 2);
+        for page_id in pages.values() {
+            let page = doc.get_dictionary(*page_id).unwrap();
+            let thumb_id = page.get(b"Thumb").unwrap().as_reference().unwrap();
+            let thumb = doc.get_object(thumb_id).unwrap().as_stream().unwrap();
+            // The 200 x 100 point page scaled to 106 pixels on its longer side.
+            assert_eq!(thumb.dict.get(b"Width").unwrap().as_i64().unwrap(), 106);
+            assert_eq!(thumb.dict.get(b"Height").unwrap().as_i64().unwrap(), 53);
+            assert_eq!(thumb.decompressed_content().unwrap().len(), 106 * 53 * 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coordinate_precision_rounds_emitted_coordinates() {
+        use petty_render_core::utils::{CoordinatePrecision, RoundingMode};
+
+        let template_json = json!({
+            "_stylesheet": {
+                "defaultPageMaster": "default",
+                "pageMasters": { "default": { "size": { "width": 200.33, "height": 150.77 }, "margins": "10.123pt" } },
+                "styles": { "boxed": { "border": "1pt solid #000000", "padding": "3.141pt" } }
+            },
+            "_template": { "type": "Block", "styleNames": ["boxed"], "children": [ { "type": "Paragraph", "children": [ { "type": "Text", "content": "Rounded" } ] } ] }
+        });
+        let template_str = serde_json::to_string(&template_json).unwrap();
+
+        let mut coordinates = Vec::new();
+        for precision in [
+            None,
+            Some(CoordinatePrecision::new(1, RoundingMode::HalfEven)),
+        ] {
+            let mut builder = PipelineBuilder::new()
+                .with_template_source(&template_str, "json")
+                .unwrap();
+            if let Some(precision) = precision {
+                builder = builder.with_coordinate_precision(precision);
+            }
+            let writer = builder
+                .build()
+                .unwrap()
+                .generate(vec![json!({})].into_iter(), Cursor::new(Vec::new()))
+                .await
+                .unwrap();
+            let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+            let content = doc
+                .get_and_decode_page_content(doc.get_pages()[&1])
+                .unwrap();
+            let values: Vec<f32> = content
+                .operations
+                .iter()
+                .filter(|op| ["m", "l", "re", "Td"].contains(&op.operator.as_str()))
+                .flat_map(|op| op.operands.iter().map(|o| o.as_float().unwrap()))
+                .collect();
+            assert!(!values.is_empty());
+            coordinates.push(values);
+        }
+
+        let is_tenths = |v: &f32| ((v * 10.0) - (v * 10.0).round()).abs() < 1e-3;
+        assert!(
+            !coordinates[0].iter().all(is_tenths),
+            "expected sub-tenth coordinates without rounding: {:?}",
+            coordinates[0]
+        );
+        assert!(
+            coordinates[1].iter().all(is_tenths),
+            "expected coordinates rounded to one decimal: {:?}",
+            coordinates[1]
+        );
+    }
 }
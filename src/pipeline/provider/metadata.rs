@@ -1,5 +1,5 @@
 use crate::MapRenderError;
-use crate::pipeline::api::{Anchor, Document, Heading, Hyperlink, PreparedDataSources};
+use crate::pipeline::api::{Anchor, Document, Figure, Heading, Hyperlink, PreparedDataSources};
 use crate::pipeline::concurrency::{
     DynamicWorkerPool, producer_task, run_in_order_streaming_consumer, spawn_workers,
 };
@@ -8,6 +8,7 @@ use crate::pipeline::provider::DataSourceProvider;
 use chrono::Utc;
 use log::info;
 use petty_core::error::PipelineError;
+use petty_core::idf::CaptionKind;
 use petty_core::layout::LayoutEngine;
 use petty_render_core::DocumentRenderer;
 use petty_render_core::Pass1Result;
@@ -16,7 +17,7 @@ use serde_json::Value;
 #[cfg(not(feature = "tempfile"))]
 use std::io::Cursor;
 use std::io::{BufWriter, Seek, SeekFrom};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::Semaphore;
 use tokio::task;
 
@@ -101,13 +102,21 @@ impl DataSourceProvider for MetadataGeneratingProvider {
         let (tx2, rx2) = async_channel::bounded(channel_buffer_size);
 
         let producer = task::spawn(producer_task(data_iterator, tx1, semaphore.clone()));
-        let workers = spawn_workers(num_layout_threads, context, rx1.clone(), tx2.clone());
+        let resource_budget = Arc::new(Mutex::new(context.resource_limits.budget()));
+        let workers = spawn_workers(
+            num_layout_threads,
+            context,
+            &resource_budget,
+            rx1.clone(),
+            tx2.clone(),
+        );
 
         // Create dynamic worker pool for adaptive scaling (when worker manager is available)
         let mut worker_pool = context.worker_manager().map(|wm| {
             DynamicWorkerPool::new(
                 Arc::new(context.clone()),
                 rx1.clone(),
+                Arc::clone(&resource_budget),
                 wm,
                 num_layout_threads,
             )
@@ -159,12 +168,16 @@ impl DataSourceProvider for MetadataGeneratingProvider {
 
         let final_buf_writer = {
             let final_layout_engine =
-                LayoutEngine::new(&context.font_library, context.cache_config);
+                LayoutEngine::new(&context.font_library, context.cache_config.clone());
             let final_stylesheet = context.compiled_template.stylesheet();
 
             // Pass Arc<Stylesheet> correctly
             let mut renderer = LopdfRenderer::new(final_layout_engine, final_stylesheet.clone())
-                .map_render_err()?;
+                .map_render_err()?
+                .with_page_clip(context.output.clip_to_page)
+                .with_coordinate_precision(context.output.coordinate_precision)
+                .with_image_options(context.output.images.clone())
+                .with_thumbnails(context.output.thumbnail_size);
             renderer.begin_document(buf_writer).map_render_err()?;
 
             let (page_width, page_height) = renderer
@@ -243,6 +256,25 @@ fn build_document_from_pass1_result(pass1_result: Pass1Result) -> Document {
         })
         .collect();
 
+    let mut figures = Vec::new();
+    let mut tables = Vec::new();
+    for entry in &pass1_result.caption_entries {
+        let Some(anchor) = pass1_result.resolved_anchors.get(&entry.target_id) else {
+            continue;
+        };
+        let listing = match entry.kind {
+            CaptionKind::Figure => &mut figures,
+            CaptionKind::Table => &mut tables,
+        };
+        listing.push(Figure {
+            id: entry.target_id.clone(),
+            number: entry.number,
+            label: format!("{} {}", entry.kind.label(), entry.number),
+            caption: Some(entry.text.clone()),
+            page_number: anchor.global_page_index,
+        });
+    }
+
     let anchors = pass1_result
         .resolved_anchors
         .iter()
@@ -267,7 +299,8 @@ fn build_document_from_pass1_result(pass1_result: Pass1Result) -> Document {
         page_count: pass1_result.total_pages,
         build_timestamp: Utc::now().to_rfc3339(),
         headings,
-        figures: vec![],
+        figures,
+        tables,
         index_entries: pass1_result.index_entries,
         anchors,
         hyperlinks,
@@ -280,7 +313,7 @@ mod tests {
     use crate::pipeline::adapters::TemplateParserAdapter;
     use crate::pipeline::api::IndexEntry;
     use crate::pipeline::context::PipelineContext;
-    use crate::pipeline::worker::TocEntry;
+    use crate::pipeline::worker::{CaptionEntry, TocEntry};
     use petty_core::layout::fonts::SharedFontLibrary;
     use petty_core::parser::processor::TemplateParser;
     use petty_json_template::JsonParser;
@@ -334,6 +367,20 @@ mod tests {
                     target_id: "nonexistent".to_string(),
                 },
             ],
+            caption_entries: vec![
+                CaptionEntry {
+                    kind: CaptionKind::Table,
+                    number: 1,
+                    text: "Totals".to_string(),
+                    target_id: "some-other-anchor".to_string(),
+                },
+                CaptionEntry {
+                    kind: CaptionKind::Figure,
+                    number: 1,
+                    text: "Unplaced".to_string(),
+                    target_id: "nonexistent".to_string(),
+                },
+            ],
             resolved_anchors,
             hyperlink_locations: vec![HyperlinkLocation {
                 global_page_index: 1,
@@ -353,6 +400,10 @@ mod tests {
         assert_eq!(doc.anchors.len(), 3);
         assert_eq!(doc.hyperlinks.len(), 1);
         assert_eq!(doc.index_entries.len(), 1);
+        assert!(doc.figures.is_empty());
+        assert_eq!(doc.tables.len(), 1);
+        assert_eq!(doc.tables[0].label, "Table 1");
+        assert_eq!(doc.tables[0].page_number, 3);
     }
 
     #[tokio::test]
@@ -383,7 +434,15 @@ mod tests {
             role_templates: Arc::new(features.role_templates),
             font_library: Arc::new(library),
             resource_provider: Arc::new(petty_resource::InMemoryResourceProvider::new()),
+            unresolved_resources: Default::default(),
+            resource_limits: Default::default(),
             cache_config: Default::default(),
+            output: Default::default(),
+            number_locale: Default::default(),
+            preserve_key_order: false,
+            safe_mode: false,
+            max_template_steps: None,
+            current_time: None,
             adaptive: None,
         };
 
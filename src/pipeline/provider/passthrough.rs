@@ -54,7 +54,15 @@ mod tests {
             role_templates: Arc::new(HashMap::new()),
             font_library: Arc::new(SharedFontLibrary::new()),
             resource_provider: Arc::new(petty_resource::InMemoryResourceProvider::new()),
+            unresolved_resources: Default::default(),
+            resource_limits: Default::default(),
             cache_config: Default::default(),
+            output: Default::default(),
+            number_locale: Default::default(),
+            preserve_key_order: false,
+            safe_mode: false,
+            max_template_steps: None,
+            current_time: None,
             adaptive: None,
         };
 
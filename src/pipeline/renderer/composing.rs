@@ -1,23 +1,41 @@
 use crate::pipeline::api::{Anchor, Document, PreparedDataSources};
 use crate::pipeline::context::PipelineContext;
-use crate::pipeline::renderer::RenderingStrategy;
+use crate::pipeline::renderer::{RenderingStrategy, write_document};
 use crate::{MapComposerError, MapRenderError};
 use log::{info, warn};
 use lopdf::{Document as LopdfDocument, Object, ObjectId, StringFormat, dictionary};
 use petty_core::error::PipelineError;
 use petty_core::layout::{LayoutEngine, LayoutStore};
-use petty_core::parser::processor::{DataSourceFormat, ExecutionConfig};
-use petty_layout::{LayoutElement, PositionedElement};
+use petty_layout::PositionedElement;
 use petty_pdf_composer::{merge_documents, overlay_content};
 use petty_render_core::DocumentRenderer;
-use petty_render_lopdf::LopdfRenderer;
+use petty_render_lopdf::{
+    LopdfRenderer, create_form_widgets, create_uri_link_annotations, link_fragments,
+};
 use serde_json::json;
 use std::collections::HashMap;
 use std::io::{Cursor, Seek, Write};
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct ComposingRenderer;
 
+/// Roles whose pages go before the body, in document order.
+const PREPEND_ROLES: [&str; 5] = [
+    "cover-page",
+    "preface",
+    "table-of-contents",
+    "list-of-figures",
+    "list-of-tables",
+];
+/// Roles whose pages go after the body.
+const APPEND_ROLES: [&str; 1] = ["back-cover"];
+
+/// How many times the role pages are counted before the total is taken as settled.
+/// A second pass is needed whenever there are role pages; more only when a role's
+/// length depends on the total it shows.
+const MAX_PAGE_COUNT_PASSES: usize = 4;
+
 struct PendingLink {
     local_page_idx: usize,
     rect: [f32; 4],
@@ -40,6 +58,7 @@ impl RenderingStrategy for ComposingRenderer {
                     .to_string(),
             )
         })?;
+        let doc_metadata = Arc::new(with_total_page_count(context, &doc_metadata)?);
 
         let mut body_artifact = sources.body_artifact.ok_or_else(|| {
             PipelineError::Config(
@@ -67,23 +86,21 @@ impl RenderingStrategy for ComposingRenderer {
         let (page_width, page_height) = stylesheet.get_default_page_layout().size.dimensions_pt();
         let mut prepended_pages = 0;
 
-        let prepend_roles = ["cover-page", "preface", "table-of-contents"];
-        let append_roles = ["back-cover"];
-
-        for role in prepend_roles.iter().rev() {
+        for role in PREPEND_ROLES.iter().rev() {
             if let Some(template) = context.role_templates.get(*role) {
                 info!("[COMPOSER] Executing prepend role template: '{}'", role);
                 let doc_json_str = serde_json::to_string(&*doc_metadata)?;
-                let exec_config = ExecutionConfig {
-                    format: DataSourceFormat::Json,
-                    strict: false,
-                };
-                let ir_nodes = template.execute(&doc_json_str, exec_config)?;
+                let ir_nodes = template.execute(&doc_json_str, context.execution_config())?;
 
-                let layout_engine = LayoutEngine::new(&context.font_library, context.cache_config);
+                let layout_engine =
+                    LayoutEngine::new(&context.font_library, context.cache_config.clone());
                 // Pass Arc<Stylesheet> correctly
-                let mut temp_renderer =
-                    LopdfRenderer::new(layout_engine, stylesheet.clone()).map_render_err()?;
+                let mut temp_renderer = LopdfRenderer::new(layout_engine, stylesheet.clone())
+                    .map_render_err()?
+                    .with_page_clip(context.output.clip_to_page)
+                    .with_coordinate_precision(context.output.coordinate_precision)
+                    .with_image_options(context.output.images.clone())
+                    .with_thumbnails(context.output.thumbnail_size);
                 temp_renderer
                     .begin_document(Cursor::new(Vec::new()))
                     .map_render_err()?;
@@ -119,11 +136,19 @@ impl RenderingStrategy for ComposingRenderer {
                         .collect();
 
                     for page_elements in laid_out_pages {
+                        let writer = temp_renderer.writer_mut().unwrap();
+                        let mut annots = create_form_widgets(writer, &page_elements, page_height)
+                            .map_render_err()?;
+                        annots.extend(create_uri_link_annotations(
+                            writer,
+                            &page_elements,
+                            page_height,
+                        ));
                         let content_id = temp_renderer
                             .render_page_content(page_elements, &font_map, page_width, page_height)
                             .map_render_err()?;
                         let page_id = temp_renderer
-                            .write_page_object(vec![content_id], vec![], page_width, page_height)
+                            .write_page_object(vec![content_id], annots, page_width, page_height)
                             .map_render_err()?;
                         new_page_ids.push(page_id);
                     }
@@ -166,19 +191,20 @@ impl RenderingStrategy for ComposingRenderer {
             }
         }
 
-        for role in append_roles.iter() {
+        for role in APPEND_ROLES.iter() {
             if let Some(template) = context.role_templates.get(*role) {
                 info!("[COMPOSER] Executing append role template: '{}'", role);
                 let doc_json_str = serde_json::to_string(&*doc_metadata)?;
-                let exec_config = ExecutionConfig {
-                    format: DataSourceFormat::Json,
-                    strict: false,
-                };
-                let ir_nodes = template.execute(&doc_json_str, exec_config)?;
-
-                let layout_engine = LayoutEngine::new(&context.font_library, context.cache_config);
-                let mut temp_renderer =
-                    LopdfRenderer::new(layout_engine, stylesheet.clone()).map_render_err()?;
+                let ir_nodes = template.execute(&doc_json_str, context.execution_config())?;
+
+                let layout_engine =
+                    LayoutEngine::new(&context.font_library, context.cache_config.clone());
+                let mut temp_renderer = LopdfRenderer::new(layout_engine, stylesheet.clone())
+                    .map_render_err()?
+                    .with_page_clip(context.output.clip_to_page)
+                    .with_coordinate_precision(context.output.coordinate_precision)
+                    .with_image_options(context.output.images.clone())
+                    .with_thumbnails(context.output.thumbnail_size);
                 temp_renderer
                     .begin_document(Cursor::new(Vec::new()))
                     .map_render_err()?;
@@ -212,11 +238,19 @@ impl RenderingStrategy for ComposingRenderer {
                         .collect();
 
                     for page_elements in laid_out_pages {
+                        let writer = temp_renderer.writer_mut().unwrap();
+                        let mut annots = create_form_widgets(writer, &page_elements, page_height)
+                            .map_render_err()?;
+                        annots.extend(create_uri_link_annotations(
+                            writer,
+                            &page_elements,
+                            page_height,
+                        ));
                         let content_id = temp_renderer
                             .render_page_content(page_elements, &font_map, page_width, page_height)
                             .map_render_err()?;
                         let page_id = temp_renderer
-                            .write_page_object(vec![content_id], vec![], page_width, page_height)
+                            .write_page_object(vec![content_id], annots, page_width, page_height)
                             .map_render_err()?;
                         new_page_ids.push(page_id);
                     }
@@ -243,19 +277,24 @@ impl RenderingStrategy for ComposingRenderer {
         for (role, template) in context.role_templates.iter() {
             if overlay_roles.contains(&role.as_str()) {
                 info!("[COMPOSER] Executing overlay role template: '{}'", role);
-                let layout_engine = LayoutEngine::new(&context.font_library, context.cache_config);
+                let layout_engine =
+                    LayoutEngine::new(&context.font_library, context.cache_config.clone());
 
                 for (i, page_id) in page_ids.iter().enumerate() {
                     let page_number = i + 1;
+                    // The page's position lets headers and footers differ on the first
+                    // page or between left and right pages.
                     let overlay_context_val = json!({
-                        "document": &*doc_metadata, "page_number": page_number, "page_count": final_page_count
+                        "document": &*doc_metadata,
+                        "page_number": page_number,
+                        "page_count": final_page_count,
+                        "is_first": page_number == 1,
+                        "is_last": page_number == final_page_count,
+                        "is_odd": page_number % 2 == 1,
                     });
                     let overlay_context_str = serde_json::to_string(&overlay_context_val)?;
-                    let exec_config = ExecutionConfig {
-                        format: DataSourceFormat::Json,
-                        strict: false,
-                    };
-                    let ir_nodes = template.execute(&overlay_context_str, exec_config)?;
+                    let ir_nodes =
+                        template.execute(&overlay_context_str, context.execution_config())?;
 
                     let store = LayoutStore::new();
                     let ir_root = petty_core::idf::IRNode::Root(ir_nodes);
@@ -286,13 +325,19 @@ impl RenderingStrategy for ComposingRenderer {
                             .enumerate()
                             .map(|(i, f)| (f.postscript_name.clone(), format!("F{}", i + 1)))
                             .collect();
-                        let content = petty_render_lopdf::render_elements_to_content(
+                        let mut content = petty_render_lopdf::render_elements_to_content(
                             elements,
                             &font_map,
                             page_width,
                             page_height,
                         )
                         .map_render_err()?;
+                        if petty_render_lopdf::remove_image_draws(&mut content) {
+                            warn!(
+                                "[COMPOSER] Images in the overlay template for role '{}' are not drawn.",
+                                role
+                            );
+                        }
                         overlay_content(&mut main_doc, *page_id, content.encode()?)
                             .map_composer_err()?;
                     }
@@ -334,24 +379,70 @@ impl RenderingStrategy for ComposingRenderer {
             info!("[COMPOSER] No fixups required. Passing document through.");
         }
 
-        main_doc.save_to(&mut writer)?;
+        write_document(&mut main_doc, &context.output, &mut writer)?;
         info!("[COMPOSER] Composition complete. Final document saved.");
 
         Ok(writer)
     }
 }
 
+/// Returns `document` with its page count raised to the whole document's, so
+/// role templates and page overlays see the total including role pages.
+///
+/// Pass 1 lays out the prepend and append roles to count their pages; pass 2,
+/// the rendering, then has the settled total to substitute into headers,
+/// footers and cover pages.
+fn with_total_page_count(
+    context: &PipelineContext,
+    document: &Document,
+) -> Result<Document, PipelineError> {
+    let roles: Vec<_> = PREPEND_ROLES
+        .iter()
+        .chain(APPEND_ROLES.iter())
+        .filter_map(|role| context.role_templates.get(*role))
+        .collect();
+    let mut document = document.clone();
+    if roles.is_empty() {
+        return Ok(document);
+    }
+
+    let stylesheet = context.compiled_template.stylesheet();
+    let layout_engine = LayoutEngine::new(&context.font_library, context.cache_config.clone());
+    let body_pages = document.page_count;
+    for _ in 0..MAX_PAGE_COUNT_PASSES {
+        let doc_json_str = serde_json::to_string(&document)?;
+        let mut total = body_pages;
+        for template in &roles {
+            let ir_nodes = template.execute(&doc_json_str, context.execution_config())?;
+            let store = LayoutStore::new();
+            let ir_root = petty_core::idf::IRNode::Root(ir_nodes);
+            let root_node = layout_engine
+                .build_render_tree(&ir_root, &store)
+                .map_err(PipelineError::Layout)?;
+            for page in layout_engine
+                .paginate(&stylesheet, root_node, &store)
+                .map_err(PipelineError::Layout)?
+            {
+                page?;
+                total += 1;
+            }
+        }
+        if total == document.page_count {
+            break;
+        }
+        document.page_count = total;
+    }
+    Ok(document)
+}
+
 fn collect_links_from_layout(pages: &[Vec<PositionedElement>]) -> Vec<PendingLink> {
     let mut links = Vec::new();
     for (page_idx, elements) in pages.iter().enumerate() {
-        for el in elements {
-            if let LayoutElement::Text(text_node) = &el.element
-                && let Some(href) = &text_node.href
-                && let Some(target) = href.strip_prefix('#')
-            {
+        for fragment in link_fragments(elements) {
+            if let Some(target) = fragment.href.strip_prefix('#') {
                 links.push(PendingLink {
                     local_page_idx: page_idx,
-                    rect: [el.x, el.y, el.x + el.width, el.y + el.height],
+                    rect: fragment.rect,
                     target_id: target.to_string(),
                 });
             }
@@ -10,11 +10,15 @@
 //! - [`ComposingRenderer`]: Two-pass renderer for templates with forward references
 //!   (ToC, page numbers, internal links). Renders body first, then composes with metadata.
 
+use crate::MapComposerError;
 use crate::pipeline::api::PreparedDataSources;
+use crate::pipeline::config::PdfOutputOptions;
 use crate::pipeline::context::PipelineContext;
 use crate::pipeline::renderer::composing::ComposingRenderer;
 use crate::pipeline::renderer::streaming::SinglePassStreamingRenderer;
+use lopdf::{Document as LopdfDocument, SaveOptions};
 use petty_core::error::PipelineError;
+use petty_pdf_composer::{embed_attachments, linearize};
 use std::io::{Seek, Write};
 
 pub mod composing;
@@ -67,3 +71,31 @@ pub trait RenderingStrategy {
     where
         W: Write + Seek + Send + 'static;
 }
+
+/// Serializes a finished document, applying the configured output options.
+pub(crate) fn write_document<W: Write>(
+    doc: &mut LopdfDocument,
+    options: &PdfOutputOptions,
+    writer: &mut W,
+) -> Result<(), PipelineError> {
+    embed_attachments(doc, &options.attachments).map_composer_err()?;
+    if let Some(postprocessor) = &options.postprocessor {
+        postprocessor(doc);
+    }
+    if options.linearize {
+        if options.compress {
+            doc.compress();
+        }
+        writer.write_all(&linearize(doc).map_composer_err()?)?;
+    } else if options.compress {
+        doc.compress();
+        let save_options = SaveOptions::builder()
+            .use_object_streams(true)
+            .use_xref_streams(true)
+            .build();
+        doc.save_with_options(writer, save_options)?;
+    } else {
+        doc.save_to(writer)?;
+    }
+    Ok(())
+}
@@ -5,13 +5,14 @@ use crate::pipeline::concurrency::{
 };
 use crate::pipeline::config::PdfBackend;
 use crate::pipeline::context::PipelineContext;
-use crate::pipeline::renderer::RenderingStrategy;
+use crate::pipeline::renderer::{RenderingStrategy, write_document};
 use log::{info, warn};
+use lopdf::Document as LopdfDocument;
 use petty_core::error::PipelineError;
 use petty_render_core::DocumentRenderer;
 use petty_render_lopdf::LopdfRenderer;
-use std::io::{Seek, Write};
-use std::sync::Arc;
+use std::io::{Cursor, Seek, Write};
+use std::sync::{Arc, Mutex};
 use tokio::sync::Semaphore;
 use tokio::task;
 
@@ -135,7 +136,7 @@ impl RenderingStrategy for SinglePassStreamingRenderer {
         &self,
         context: &PipelineContext,
         sources: PreparedDataSources,
-        writer: W,
+        mut writer: W,
     ) -> Result<W, PipelineError>
     where
         W: Write + Seek + Send + 'static,
@@ -161,6 +162,31 @@ impl RenderingStrategy for SinglePassStreamingRenderer {
             ));
         }
 
+        if context.output.requires_document_pass() {
+            // Output options that reorder objects need the whole document, so render
+            // into memory first and serialize the reloaded result.
+            let buffer = self.stream_pages(context, sources, Cursor::new(Vec::new()))?;
+            let mut doc = LopdfDocument::load_mem(buffer.get_ref())?;
+            write_document(&mut doc, &context.output, &mut writer)?;
+            return Ok(writer);
+        }
+
+        self.stream_pages(context, sources, writer)
+    }
+}
+
+impl SinglePassStreamingRenderer {
+    /// Runs the producer, layout workers and in-order consumer, writing pages as they
+    /// are laid out.
+    fn stream_pages<W>(
+        &self,
+        context: &PipelineContext,
+        sources: PreparedDataSources,
+        writer: W,
+    ) -> Result<W, PipelineError>
+    where
+        W: Write + Seek + Send + 'static,
+    {
         // Use dynamic worker count configuration (no artificial cap)
         let num_layout_threads = self.get_worker_count();
 
@@ -201,13 +227,21 @@ impl RenderingStrategy for SinglePassStreamingRenderer {
         let (tx2, rx2) = async_channel::bounded(results_channel_size);
 
         let producer = task::spawn(producer_task(sources.data_iterator, tx1, semaphore.clone()));
-        let workers = spawn_workers(num_layout_threads, context, rx1.clone(), tx2.clone());
+        let resource_budget = Arc::new(Mutex::new(context.resource_limits.budget()));
+        let workers = spawn_workers(
+            num_layout_threads,
+            context,
+            &resource_budget,
+            rx1.clone(),
+            tx2.clone(),
+        );
 
         // Create dynamic worker pool for adaptive scaling (when worker manager is available)
         let mut worker_pool = context.worker_manager().map(|wm| {
             DynamicWorkerPool::new(
                 Arc::new(context.clone()),
                 rx1.clone(),
+                Arc::clone(&resource_budget),
                 wm,
                 num_layout_threads,
             )
@@ -232,12 +266,18 @@ impl RenderingStrategy for SinglePassStreamingRenderer {
 
         // --- Consumer Stage ---
         info!("[CONSUMER] Started in-order streaming consumer. Awaiting laid-out sequences.");
-        let final_layout_engine = LayoutEngine::new(&context.font_library, context.cache_config);
+        let final_layout_engine =
+            LayoutEngine::new(&context.font_library, context.cache_config.clone());
         let final_stylesheet = context.compiled_template.stylesheet();
 
         // Pass Arc<Stylesheet> correctly
-        let mut renderer =
-            LopdfRenderer::new(final_layout_engine, final_stylesheet.clone()).map_render_err()?;
+        let mut renderer = LopdfRenderer::new(final_layout_engine, final_stylesheet.clone())
+            .map_render_err()?
+            .with_compression(context.output.compress)
+            .with_page_clip(context.output.clip_to_page)
+            .with_coordinate_precision(context.output.coordinate_precision)
+            .with_image_options(context.output.images.clone())
+            .with_thumbnails(context.output.thumbnail_size);
         renderer.begin_document(writer).map_render_err()?;
 
         let (page_width, page_height) = renderer
@@ -315,7 +355,15 @@ mod tests {
             role_templates: Arc::new(features.role_templates),
             font_library: Arc::new(library),
             resource_provider: Arc::new(petty_resource::InMemoryResourceProvider::new()),
+            unresolved_resources: Default::default(),
+            resource_limits: Default::default(),
             cache_config: Default::default(),
+            output: Default::default(),
+            number_locale: Default::default(),
+            preserve_key_order: false,
+            safe_mode: false,
+            max_template_steps: None,
+            current_time: None,
             adaptive: None,
         };
 
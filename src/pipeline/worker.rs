@@ -1,25 +1,34 @@
 // src/pipeline/worker.rs
 
+use crate::pipeline::config::UnresolvedResourcePolicy;
 use log::{debug, info, trace};
+use petty_core::Color;
 use petty_core::error::PipelineError;
-use petty_core::idf::{IRNode, InlineNode, SharedData};
-use petty_core::layout::{IndexEntry, LayoutEngine, LayoutStore};
-use petty_core::style_types::stylesheet::Stylesheet;
-use petty_core::traits::ResourceProvider;
+use petty_core::idf::{
+    IRNode, InlineNode, SharedData, number_captions, table_rows, table_rows_mut,
+};
+use petty_core::layout::{IndexEntry, LayoutEngine, LayoutStore, LayoutWarning};
+use petty_core::style_types::border::{Border, BorderStyle};
+use petty_core::style_types::stylesheet::{ElementStyle, Stylesheet};
+use petty_core::traits::{ResourceBudget, ResourceProvider};
 use rand::Rng;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 // Re-export from petty-core
-pub use petty_core::{LaidOutSequence, TocEntry};
+pub use petty_core::{CaptionEntry, LaidOutSequence, TocEntry};
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn finish_layout_and_resource_loading(
     worker_id: usize,
     ir_nodes: Vec<IRNode>,
     _context_arc: Arc<Value>,
     resource_provider: &dyn ResourceProvider,
+    unresolved_resources: &UnresolvedResourcePolicy,
+    resource_budget: &Mutex<ResourceBudget>,
     layout_engine: &mut LayoutEngine,
     stylesheet: &Stylesheet,
     debug_mode: bool,
@@ -29,7 +38,8 @@ pub(super) fn finish_layout_and_resource_loading(
     let prep_start = Instant::now();
     let mut ir_nodes_with_ids = ir_nodes;
     ensure_heading_ids(&mut ir_nodes_with_ids);
-    let tree = IRNode::Root(ir_nodes_with_ids);
+    number_captions(&mut ir_nodes_with_ids);
+    let mut tree = IRNode::Root(ir_nodes_with_ids);
     if prep_start.elapsed().as_millis() > 1 {
         trace!(
             "[WORKER-{}] IR Prep took {:?}",
@@ -43,7 +53,14 @@ pub(super) fn finish_layout_and_resource_loading(
     }
 
     let resource_start = Instant::now();
-    let resources = collect_and_load_resources(&tree, resource_provider)?;
+    let mut warnings = Vec::new();
+    let resources = collect_and_load_resources(
+        &mut tree,
+        resource_provider,
+        unresolved_resources,
+        resource_budget,
+        &mut warnings,
+    )?;
     if resource_start.elapsed().as_millis() > 5 {
         debug!(
             "[WORKER-{}] Resource load took {:?}",
@@ -54,6 +71,8 @@ pub(super) fn finish_layout_and_resource_loading(
 
     let mut toc_entries = Vec::new();
     collect_toc_entries(&tree, &mut toc_entries);
+    let mut caption_entries = Vec::new();
+    collect_caption_entries(&tree, &mut caption_entries);
 
     let layout_phase_start = Instant::now();
 
@@ -61,6 +80,8 @@ pub(super) fn finish_layout_and_resource_loading(
     let store = LayoutStore::new();
     // Reset stats for clean metrics per sequence
     layout_engine.reset_stats();
+    // Drop warnings left over from a sequence that failed part-way.
+    layout_engine.take_warnings();
 
     let build_tree_start = Instant::now();
     let root_render_node = layout_engine
@@ -79,18 +100,22 @@ pub(super) fn finish_layout_and_resource_loading(
         .map_err(PipelineError::Layout)?;
 
     let mut pages = Vec::new();
+    let mut page_masters = Vec::new();
     let mut defined_anchors = HashMap::new();
     let mut index_entries: HashMap<String, Vec<IndexEntry>> = HashMap::new();
 
     for page_res in iterator {
         let page = page_res.map_err(PipelineError::Layout)?;
         pages.push(page.elements);
+        page_masters.push(page.master_name);
         defined_anchors.extend(page.anchors);
         for (k, v) in page.index_entries {
             index_entries.entry(k).or_default().extend(v);
         }
     }
 
+    warnings.extend(layout_engine.take_warnings());
+
     let layout_total = layout_phase_start.elapsed();
     let pages_count = pages.len();
     if layout_total.as_millis() > 50 {
@@ -117,10 +142,13 @@ pub(super) fn finish_layout_and_resource_loading(
 
     Ok(LaidOutSequence {
         pages,
+        page_masters,
         resources,
         defined_anchors,
         toc_entries,
+        caption_entries,
         index_entries,
+        warnings,
     })
 }
 
@@ -136,6 +164,22 @@ fn ensure_heading_ids(nodes: &mut [IRNode]) {
                     meta.id = Some(format!("{}-{}", slug, suffix));
                 }
             }
+            IRNode::Caption {
+                meta,
+                kind,
+                children,
+                content,
+                ..
+            } => {
+                if meta.id.is_none() {
+                    let text = extract_text_from_inlines(children);
+                    let slug = slug::slugify(format!("{} {}", kind.label(), text));
+                    let mut rng = rand::rng();
+                    let suffix: u32 = rng.random();
+                    meta.id = Some(format!("{}-{}", slug, suffix));
+                }
+                ensure_heading_ids(std::slice::from_mut(content.as_mut()));
+            }
             IRNode::Root(children)
             | IRNode::Block { children, .. }
             | IRNode::FlexContainer { children, .. }
@@ -143,15 +187,8 @@ fn ensure_heading_ids(nodes: &mut [IRNode]) {
             | IRNode::ListItem { children, .. } => {
                 ensure_heading_ids(children);
             }
-            IRNode::Table { header, body, .. } => {
-                if let Some(h) = header {
-                    for row in &mut h.rows {
-                        for cell in &mut row.cells {
-                            ensure_heading_ids(&mut cell.children);
-                        }
-                    }
-                }
-                for row in &mut body.rows {
+            IRNode::Table { .. } => {
+                for row in table_rows_mut(node) {
                     for cell in &mut row.cells {
                         ensure_heading_ids(&mut cell.children);
                     }
@@ -189,20 +226,54 @@ fn collect_toc_entries(node: &IRNode, entries: &mut Vec<TocEntry>) {
                 collect_toc_entries(child, entries);
             }
         }
-        IRNode::Table { header, body, .. } => {
-            if let Some(h) = header {
-                for row in &h.rows {
-                    for cell in &row.cells {
-                        for child in &cell.children {
-                            collect_toc_entries(child, entries);
-                        }
+        IRNode::Caption { content, .. } => collect_toc_entries(content, entries),
+        IRNode::Table { .. } => {
+            for row in table_rows(node) {
+                for cell in &row.cells {
+                    for child in &cell.children {
+                        collect_toc_entries(child, entries);
                     }
                 }
             }
-            for row in &body.rows {
+        }
+        _ => {}
+    }
+}
+
+fn collect_caption_entries(node: &IRNode, entries: &mut Vec<CaptionEntry>) {
+    match node {
+        IRNode::Caption {
+            meta,
+            kind,
+            number,
+            children,
+            content,
+            ..
+        } => {
+            if let (Some(number), Some(id)) = (number, &meta.id) {
+                entries.push(CaptionEntry {
+                    kind: *kind,
+                    number: *number,
+                    text: extract_text_from_inlines(children),
+                    target_id: id.clone(),
+                });
+            }
+            collect_caption_entries(content, entries);
+        }
+        IRNode::Root(children)
+        | IRNode::Block { children, .. }
+        | IRNode::FlexContainer { children, .. }
+        | IRNode::List { children, .. }
+        | IRNode::ListItem { children, .. } => {
+            for child in children {
+                collect_caption_entries(child, entries);
+            }
+        }
+        IRNode::Table { .. } => {
+            for row in table_rows(node) {
                 for cell in &row.cells {
                     for child in &cell.children {
-                        collect_toc_entries(child, entries);
+                        collect_caption_entries(child, entries);
                     }
                 }
             }
@@ -222,7 +293,7 @@ fn extract_text_from_inlines(inlines: &[InlineNode]) -> String {
                 text.push_str(&extract_text_from_inlines(children));
             }
             InlineNode::LineBreak => text.push(' '),
-            InlineNode::Image { .. } => {}
+            InlineNode::Image { .. } | InlineNode::Sparkline { .. } => {}
         }
     }
     text
@@ -238,6 +309,14 @@ fn collect_image_uris(node: &IRNode, uris: &mut HashSet<String>) {
                 collect_inline_image_uris(inline, uris);
             }
         }
+        IRNode::Caption {
+            children, content, ..
+        } => {
+            for inline in children {
+                collect_inline_image_uris(inline, uris);
+            }
+            collect_image_uris(content, uris);
+        }
         IRNode::Root(children)
         | IRNode::Block { children, .. }
         | IRNode::FlexContainer { children, .. }
@@ -247,17 +326,8 @@ fn collect_image_uris(node: &IRNode, uris: &mut HashSet<String>) {
                 collect_image_uris(child, uris);
             }
         }
-        IRNode::Table { header, body, .. } => {
-            if let Some(h) = header {
-                for row in &h.rows {
-                    for cell in &row.cells {
-                        for child in &cell.children {
-                            collect_image_uris(child, uris);
-                        }
-                    }
-                }
-            }
-            for row in &body.rows {
+        IRNode::Table { .. } => {
+            for row in table_rows(node) {
                 for cell in &row.cells {
                     for child in &cell.children {
                         collect_image_uris(child, uris);
@@ -269,6 +339,35 @@ fn collect_image_uris(node: &IRNode, uris: &mut HashSet<String>) {
     }
 }
 
+/// Feeds the content of a sequence's IR, followed by every image it references and
+/// the bytes that image resolves to, into `hasher`.
+///
+/// The nodes must be hashed as the template produced them, before heading IDs are
+/// generated, as those carry a random suffix.
+pub(super) fn hash_sequence_content(
+    nodes: &[IRNode],
+    provider: &dyn ResourceProvider,
+    hasher: &mut Sha256,
+) {
+    // The IR holds no hash maps, so its debug form is a stable serialization.
+    hasher.update(format!("{:?}", nodes).as_bytes());
+
+    let mut uris = HashSet::new();
+    for node in nodes {
+        collect_image_uris(node, &mut uris);
+    }
+    let mut uris: Vec<_> = uris.into_iter().collect();
+    uris.sort();
+    for uri in uris {
+        hasher.update(uri.as_bytes());
+        // Unresolvable images contribute only their URI.
+        if let Ok(data) = provider.load(&uri) {
+            hasher.update((data.len() as u64).to_le_bytes());
+            hasher.update(data.as_slice());
+        }
+    }
+}
+
 fn collect_inline_image_uris(inline: &InlineNode, uris: &mut HashSet<String>) {
     match inline {
         InlineNode::Image { src, .. } => {
@@ -286,24 +385,324 @@ fn collect_inline_image_uris(inline: &InlineNode, uris: &mut HashSet<String>) {
 }
 
 fn collect_and_load_resources(
-    node: &IRNode,
+    node: &mut IRNode,
     provider: &dyn ResourceProvider,
+    policy: &UnresolvedResourcePolicy,
+    budget: &Mutex<ResourceBudget>,
+    warnings: &mut Vec<LayoutWarning>,
 ) -> Result<HashMap<String, SharedData>, PipelineError> {
     let mut uris = HashSet::new();
     collect_image_uris(node, &mut uris);
 
+    let admit = |uri: &str, data: &[u8]| -> Result<(), PipelineError> {
+        budget
+            .lock()
+            .map_err(|_| PipelineError::Other("Resource budget lock poisoned".to_string()))?
+            .admit(uri, data)?;
+        Ok(())
+    };
     let mut resources = HashMap::new();
+    let mut missing = HashSet::new();
     for uri in uris {
         if !uri.is_empty() {
             match provider.load(&uri) {
                 Ok(data) => {
+                    admit(&uri, &data)?;
                     resources.insert(uri, data);
                 }
                 Err(e) => {
-                    log::warn!("Failed to load image resource '{}': {}", uri, e);
+                    let fallback = match policy {
+                        UnresolvedResourcePolicy::Fail => {
+                            return Err(PipelineError::Other(format!(
+                                "Failed to load image resource '{}': {}",
+                                uri, e
+                            )));
+                        }
+                        UnresolvedResourcePolicy::SkipWithPlaceholder => None,
+                        UnresolvedResourcePolicy::Callback(handler) => handler(&uri),
+                    };
+                    match fallback {
+                        Some(data) => {
+                            admit(&uri, &data)?;
+                            resources.insert(uri, data);
+                        }
+                        None => {
+                            log::warn!("Failed to load image resource '{}': {}", uri, e);
+                            missing.insert(uri);
+                        }
+                    }
                 }
             }
         }
     }
+
+    if !missing.is_empty() {
+        replace_missing_images(std::slice::from_mut(node), &missing, warnings);
+    }
     Ok(resources)
 }
+
+/// Turns images whose source could not be loaded, block or inline, into empty,
+/// outlined boxes of the same size so the gap in the layout stays visible,
+/// recording a warning for each.
+fn replace_missing_images(
+    nodes: &mut [IRNode],
+    missing: &HashSet<String>,
+    warnings: &mut Vec<LayoutWarning>,
+) {
+    for node in nodes {
+        match node {
+            IRNode::Image { meta, src } => {
+                if missing.contains(src.as_str()) {
+                    warnings.push(LayoutWarning::ImageMissing {
+                        node_id: meta.id.clone(),
+                        src: std::mem::take(src),
+                    });
+                    set_placeholder_style(&mut meta.style_override);
+                }
+            }
+            IRNode::Root(children)
+            | IRNode::Block { children, .. }
+            | IRNode::FlexContainer { children, .. }
+            | IRNode::List { children, .. }
+            | IRNode::ListItem { children, .. } => {
+                replace_missing_images(children, missing, warnings);
+            }
+            IRNode::Paragraph { children, .. } | IRNode::Heading { children, .. } => {
+                replace_missing_inline_images(children, missing, warnings);
+            }
+            IRNode::Caption {
+                children, content, ..
+            } => {
+                replace_missing_inline_images(children, missing, warnings);
+                replace_missing_images(std::slice::from_mut(content.as_mut()), missing, warnings);
+            }
+            IRNode::Table { .. } => {
+                for row in table_rows_mut(node) {
+                    for cell in &mut row.cells {
+                        replace_missing_images(&mut cell.children, missing, warnings);
+                    }
+                }
+            }
+            IRNode::PageBreak { .. }
+            | IRNode::IndexMarker { .. }
+            | IRNode::FormField { .. }
+            | IRNode::BarChart { .. }
+            | IRNode::PieChart { .. } => {}
+        }
+    }
+}
+
+fn replace_missing_inline_images(
+    inlines: &mut [InlineNode],
+    missing: &HashSet<String>,
+    warnings: &mut Vec<LayoutWarning>,
+) {
+    for inline in inlines {
+        match inline {
+            InlineNode::Image { meta, src } => {
+                if missing.contains(src.as_str()) {
+                    warnings.push(LayoutWarning::ImageMissing {
+                        node_id: None,
+                        src: std::mem::take(src),
+                    });
+                    set_placeholder_style(&mut meta.style_override);
+                }
+            }
+            InlineNode::StyledSpan { children, .. }
+            | InlineNode::Hyperlink { children, .. }
+            | InlineNode::PageReference { children, .. } => {
+                replace_missing_inline_images(children, missing, warnings);
+            }
+            InlineNode::Text(_) | InlineNode::Sparkline { .. } | InlineNode::LineBreak => {}
+        }
+    }
+}
+
+/// Outlines a missing image's box with a dashed border on a light background.
+fn set_placeholder_style(style_override: &mut Option<ElementStyle>) {
+    let style = style_override.get_or_insert_with(Default::default);
+    style.border.get_or_insert(Border {
+        width: 1.0,
+        style: BorderStyle::Dashed,
+        color: Color::gray(128),
+    });
+    style
+        .background_color
+        .get_or_insert_with(|| Color::gray(240));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petty_core::idf::{CaptionKind, CaptionPosition, NodeMetadata, TableColumnDefinition};
+    use petty_core::layout::LayoutElement;
+    use petty_core::layout::fonts::SharedFontLibrary;
+    use petty_core::style_types::stylesheet::PageLayout;
+    use petty_core::traits::ResourceLimits;
+    use petty_resource::InMemoryResourceProvider;
+
+    fn layout_nodes(
+        nodes: Vec<IRNode>,
+        policy: UnresolvedResourcePolicy,
+    ) -> Result<LaidOutSequence, PipelineError> {
+        let library = SharedFontLibrary::new();
+        library.load_fallback_font();
+        let mut engine = LayoutEngine::new(&library, Default::default());
+        let stylesheet = Stylesheet {
+            page_masters: HashMap::from([("default".to_string(), PageLayout::default())]),
+            default_page_master_name: Some("default".to_string()),
+            ..Default::default()
+        };
+        finish_layout_and_resource_loading(
+            0,
+            nodes,
+            Arc::new(Value::Null),
+            &InMemoryResourceProvider::new(),
+            &policy,
+            &Mutex::new(ResourceLimits::default().budget()),
+            &mut engine,
+            &stylesheet,
+            false,
+        )
+    }
+
+    fn missing_image() -> IRNode {
+        IRNode::Image {
+            meta: NodeMetadata::default(),
+            src: "missing.png".to_string(),
+        }
+    }
+
+    fn layout_missing_image(
+        policy: UnresolvedResourcePolicy,
+    ) -> Result<LaidOutSequence, PipelineError> {
+        layout_nodes(vec![missing_image()], policy)
+    }
+
+    fn count_rectangles(seq: &LaidOutSequence) -> usize {
+        seq.pages[0]
+            .iter()
+            .filter(|el| matches!(el.element, LayoutElement::Rectangle(_)))
+            .count()
+    }
+
+    #[test]
+    fn test_missing_image_draws_placeholder() {
+        let seq = layout_missing_image(UnresolvedResourcePolicy::SkipWithPlaceholder).unwrap();
+        assert!(seq.resources.is_empty());
+        assert!(count_rectangles(&seq) > 0, "Expected a placeholder box");
+        assert_eq!(
+            seq.warnings,
+            vec![LayoutWarning::ImageMissing {
+                node_id: None,
+                src: "missing.png".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_missing_inline_image_is_replaced() {
+        let paragraph = IRNode::Paragraph {
+            meta: NodeMetadata::default(),
+            children: vec![
+                InlineNode::Text("Logo: ".to_string()),
+                InlineNode::Hyperlink {
+                    meta: Default::default(),
+                    href: "https://example.com".to_string(),
+                    children: vec![InlineNode::Image {
+                        meta: Default::default(),
+                        src: "missing.png".to_string(),
+                    }],
+                },
+            ],
+        };
+        let flex = IRNode::FlexContainer {
+            meta: NodeMetadata::default(),
+            children: vec![paragraph],
+        };
+        let seq = layout_nodes(vec![flex], UnresolvedResourcePolicy::SkipWithPlaceholder).unwrap();
+        assert_eq!(
+            seq.warnings,
+            vec![LayoutWarning::ImageMissing {
+                node_id: None,
+                src: "missing.png".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_missing_image_fails() {
+        match layout_missing_image(UnresolvedResourcePolicy::Fail) {
+            Err(err) => assert!(err.to_string().contains("missing.png")),
+            Ok(_) => panic!("Expected generation to fail for a missing image"),
+        }
+    }
+
+    #[test]
+    fn test_missing_image_callback_supplies_fallback() {
+        let policy = UnresolvedResourcePolicy::Callback(Arc::new(|uri: &str| {
+            (uri == "missing.png").then(|| Arc::new(vec![0u8; 4]))
+        }));
+        let seq = layout_missing_image(policy).unwrap();
+        assert!(seq.resources.contains_key("missing.png"));
+        assert_eq!(count_rectangles(&seq), 0);
+    }
+
+    #[test]
+    fn test_captioned_tables_are_numbered_and_collected() {
+        let caption = |text: &str| IRNode::Caption {
+            meta: NodeMetadata::default(),
+            kind: CaptionKind::Table,
+            position: CaptionPosition::Above,
+            number: None,
+            children: vec![InlineNode::Text(text.to_string())],
+            content: Box::new(IRNode::Table {
+                meta: NodeMetadata::default(),
+                columns: vec![TableColumnDefinition::default()],
+                header: None,
+                body: Box::default(),
+                footer: None,
+            }),
+        };
+        let nodes = vec![caption("Revenue"), caption("Expenses")];
+        let seq = layout_nodes(nodes, UnresolvedResourcePolicy::default()).unwrap();
+
+        let texts: Vec<&str> = seq.pages[0]
+            .iter()
+            .filter_map(|el| match &el.element {
+                LayoutElement::Text(t) => Some(t.content.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(texts.iter().any(|t| t.starts_with("Table 1")));
+        assert!(texts.iter().any(|t| t.starts_with("Table 2")));
+
+        let entries: Vec<(usize, &str)> = seq
+            .caption_entries
+            .iter()
+            .map(|e| (e.number, e.text.as_str()))
+            .collect();
+        assert_eq!(entries, vec![(1, "Revenue"), (2, "Expenses")]);
+        assert!(
+            seq.caption_entries
+                .iter()
+                .all(|e| seq.defined_anchors.contains_key(&e.target_id))
+        );
+    }
+
+    #[test]
+    fn test_missing_image_inside_caption_draws_placeholder() {
+        let nodes = vec![IRNode::Caption {
+            meta: NodeMetadata::default(),
+            kind: CaptionKind::Figure,
+            position: CaptionPosition::Below,
+            number: None,
+            children: vec![InlineNode::Text("Logo".to_string())],
+            content: Box::new(missing_image()),
+        }];
+        let seq = layout_nodes(nodes, UnresolvedResourcePolicy::SkipWithPlaceholder).unwrap();
+        assert!(count_rectangles(&seq) > 0, "Expected a placeholder box");
+        assert_eq!(seq.warnings.len(), 1);
+    }
+}
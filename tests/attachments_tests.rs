@@ -0,0 +1,56 @@
+mod common;
+
+use common::fixtures::*;
+use petty::PipelineBuilder;
+use serde_json::json;
+use std::io::Cursor;
+
+#[tokio::test]
+async fn test_attachment_is_embedded_and_extractable() {
+    let template_str = template_with_styles(json!({}), paragraph("Invoice {{id}}")).to_string();
+    let source = br#"{"id":42,"total":"99.00"}"#;
+
+    let pipeline = PipelineBuilder::new()
+        .with_template_source(&template_str, "json")
+        .unwrap()
+        .with_attachment("invoice.json", "application/json", source.as_slice())
+        .build()
+        .unwrap();
+
+    let data = vec![json!({"id": 42})];
+    let writer = pipeline
+        .generate(data.into_iter(), Cursor::new(Vec::new()))
+        .await
+        .unwrap();
+
+    let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+    let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+    let catalog = doc.get_dictionary(root_id).unwrap();
+    let names = catalog
+        .get_deref(b"Names", &doc)
+        .and_then(lopdf::Object::as_dict)
+        .and_then(|names| names.get(b"EmbeddedFiles"))
+        .and_then(lopdf::Object::as_dict)
+        .and_then(|tree| tree.get(b"Names"))
+        .and_then(lopdf::Object::as_array)
+        .expect("The document should have an /EmbeddedFiles name tree");
+    assert_eq!(names[0].as_str().unwrap(), b"invoice.json");
+
+    let spec_id = names[1].as_reference().unwrap();
+    let af = catalog.get(b"AF").unwrap().as_array().unwrap();
+    assert_eq!(af[0].as_reference().unwrap(), spec_id);
+
+    let spec = doc.get_dictionary(spec_id).unwrap();
+    let file_id = spec
+        .get(b"EF")
+        .and_then(lopdf::Object::as_dict)
+        .and_then(|ef| ef.get(b"F"))
+        .and_then(lopdf::Object::as_reference)
+        .unwrap();
+    let file = doc.get_object(file_id).unwrap().as_stream().unwrap();
+    assert_eq!(
+        file.dict.get(b"Subtype").unwrap().as_name().unwrap(),
+        b"application/json"
+    );
+    assert_eq!(file.get_plain_content().unwrap(), source);
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::fixtures::*;
+use common::pdf_assertions::{filled_rectangles, page_operations};
+use common::{TestResult, generate_pdf_from_json_with_data};
+use serde_json::json;
+
+#[test]
+fn test_bar_chart_draws_bars_proportional_to_values() -> TestResult {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let content = json!({
+        "type": "BarChart",
+        "styleOverride": { "width": { "pt": 240.0 }, "height": { "pt": 120.0 } },
+        "bars": [
+            { "label": "Q1", "value": 10 },
+            { "label": "Q2", "value": "{{q2}}" },
+            { "label": "Q3", "value": 40 }
+        ]
+    });
+    let template = template_with_styles(json!({}), content);
+
+    let pdf = generate_pdf_from_json_with_data(&template, json!({ "q2": 20 }))?;
+    for label in ["Q1", "Q2", "Q3"] {
+        assert_pdf_contains_text!(pdf, label);
+    }
+
+    // Three bars plus the two axis lines.
+    let rects = filled_rectangles(&pdf.doc, 1);
+    assert_eq!(rects.len(), 5, "unexpected rectangles: {:?}", rects);
+
+    let mut bars: Vec<_> = rects
+        .into_iter()
+        .filter(|&(_, _, w, h)| w > 1.0 && h > 1.0)
+        .collect();
+    bars.sort_by(|a, b| a.0.total_cmp(&b.0));
+    assert_eq!(bars.len(), 3);
+
+    let (_, base_y, width, tallest) = bars[2];
+    assert!(tallest > 50.0 && tallest < 120.0);
+    for (bar, value) in bars.iter().zip([10.0, 20.0, 40.0]) {
+        assert!((bar.1 - base_y).abs() < 0.01, "bars share a baseline");
+        assert!((bar.2 - width).abs() < 0.01, "bars have equal widths");
+        assert!((bar.3 - tallest * value / 40.0).abs() < 0.01);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_sparkline_is_drawn_as_polyline_across_its_width() -> TestResult {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let content = json!({
+        "type": "Paragraph",
+        "children": [
+            { "type": "Text", "content": "Trend " },
+            { "type": "Sparkline", "values": "trend", "width": 80.0, "height": 12.0 },
+            { "type": "Text", "content": " rising" }
+        ]
+    });
+    let template = template_with_styles(json!({}), content);
+
+    let pdf = generate_pdf_from_json_with_data(&template, json!({ "trend": [1, 3, 2, 5] }))?;
+    assert_pdf_contains_text!(pdf, "rising");
+
+    let ops = page_operations(&pdf.doc, 1);
+    let start = ops
+        .iter()
+        .position(|op| op.operator == "m")
+        .expect("moveto");
+    let xs: Vec<f32> = ops[start..]
+        .iter()
+        .take_while(|op| op.operator == "m" || op.operator == "l")
+        .map(|op| op.operands[0].as_float().unwrap())
+        .collect();
+    assert_eq!(xs.len(), 4, "one segment between each pair of values");
+    assert_eq!(ops[start + xs.len()].operator, "S");
+    assert!((xs[3] - xs[0] - 80.0).abs() < 0.01);
+    Ok(())
+}
+
+#[test]
+fn test_pie_chart_wedge_angles_are_proportional_to_values() -> TestResult {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let content = json!({
+        "type": "PieChart",
+        "styleOverride": { "width": { "pt": 240.0 }, "height": { "pt": 120.0 } },
+        "slices": [
+            { "label": "North", "value": 1 },
+            { "label": "South", "value": "{{south}}" },
+            { "label": "West", "value": 3 }
+        ]
+    });
+    let template = template_with_styles(json!({}), content);
+
+    let pdf = generate_pdf_from_json_with_data(&template, json!({ "south": 2 }))?;
+    for label in ["North", "South", "West"] {
+        assert_pdf_contains_text!(pdf, label);
+    }
+
+    // Each wedge is `m` (center), `l` (arc start), `c`... (arc), `h`, `f`.
+    let ops = page_operations(&pdf.doc, 1);
+    let point = |operands: &[lopdf::Object]| {
+        let n = operands.len();
+        (
+            operands[n - 2].as_float().unwrap(),
+            operands[n - 1].as_float().unwrap(),
+        )
+    };
+    let mut sweeps = Vec::new();
+    for (start, _) in ops.iter().enumerate().filter(|(_, op)| op.operator == "m") {
+        let end = start
+            + ops[start..]
+                .iter()
+                .position(|op| op.operator == "f")
+                .unwrap();
+        let path = &ops[start..end];
+        assert_eq!(path[1].operator, "l");
+        assert!(path[2..path.len() - 1].iter().all(|op| op.operator == "c"));
+        assert_eq!(path[path.len() - 1].operator, "h");
+
+        let center = point(&path[0].operands);
+        // Clockwise from twelve o'clock, with the PDF's y axis pointing up.
+        let angle = |(x, y): (f32, f32)| (x - center.0).atan2(y - center.1);
+        let from = angle(point(&path[1].operands));
+        let to = angle(point(&path[path.len() - 2].operands));
+        sweeps.push((to - from).rem_euclid(std::f32::consts::TAU).to_degrees());
+    }
+    assert_eq!(sweeps.len(), 3, "one filled path per slice");
+    for (sweep, expected) in sweeps.iter().zip([60.0, 120.0, 180.0]) {
+        assert!((sweep - expected).abs() < 0.1, "sweeps {:?}", sweeps);
+    }
+    Ok(())
+}
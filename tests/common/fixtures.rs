@@ -243,3 +243,15 @@ pub fn table_row(cells: Vec<Value>) -> Value {
         "children": cells
     })
 }
+
+/// Create a template whose single page master is given in full
+pub fn template_with_page_master(page_master: Value, styles: Value, content: Value) -> Value {
+    json!({
+        "_stylesheet": {
+            "defaultPageMaster": "default",
+            "pageMasters": { "default": page_master },
+            "styles": styles
+        },
+        "_template": content
+    })
+}
@@ -2,6 +2,7 @@
 #![allow(dead_code)]
 
 use lopdf::Document as LopdfDocument;
+use lopdf::content::Operation;
 use std::collections::BTreeMap;
 
 /// Extract all text content from a PDF document
@@ -308,6 +309,36 @@ pub fn get_page_dimensions(doc: &LopdfDocument, page_num: u32) -> Option<(f32, f
     None
 }
 
+/// Decode the content stream operations of a page (1-indexed)
+pub fn page_operations(doc: &LopdfDocument, page_num: u32) -> Vec<Operation> {
+    doc.get_pages()
+        .get(&page_num)
+        .and_then(|page_id| doc.get_and_decode_page_content(*page_id).ok())
+        .map(|content| content.operations)
+        .unwrap_or_default()
+}
+
+/// Get the (x, y, width, height) of every filled rectangle (`re` followed by `f`) on a page
+pub fn filled_rectangles(doc: &LopdfDocument, page_num: u32) -> Vec<(f32, f32, f32, f32)> {
+    let mut rects = Vec::new();
+    let mut pending = None;
+    for op in page_operations(doc, page_num) {
+        match op.operator.as_str() {
+            "re" => {
+                let nums: Vec<f32> = op
+                    .operands
+                    .iter()
+                    .filter_map(|o| o.as_float().ok())
+                    .collect();
+                pending = (nums.len() == 4).then(|| (nums[0], nums[1], nums[2], nums[3]));
+            }
+            "f" => rects.extend(pending.take()),
+            _ => pending = None,
+        }
+    }
+    rects
+}
+
 /// Check if PDF has outlines (bookmarks/TOC)
 pub fn has_outlines(doc: &LopdfDocument) -> bool {
     if let Ok(catalog_ref) = doc.trailer.get(b"Root") {
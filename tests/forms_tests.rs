@@ -0,0 +1,66 @@
+mod common;
+
+use common::fixtures::*;
+use petty::PipelineBuilder;
+use serde_json::json;
+use std::io::Cursor;
+
+#[tokio::test]
+async fn test_form_fields_are_listed_in_acroform() {
+    let template_str = template_with_styles(
+        json!({}),
+        block(vec![
+            json!({ "type": "TextField", "name": "customer", "value": "{{name}}" }),
+            json!({ "type": "Checkbox", "name": "consent", "checked": true }),
+        ]),
+    )
+    .to_string();
+
+    let pipeline = PipelineBuilder::new()
+        .with_template_source(&template_str, "json")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let data = vec![json!({"name": "Jane Doe"})];
+    let writer = pipeline
+        .generate(data.into_iter(), Cursor::new(Vec::new()))
+        .await
+        .unwrap();
+
+    let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+    let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+    let acro_form = doc
+        .get_dictionary(root_id)
+        .unwrap()
+        .get_deref(b"AcroForm", &doc)
+        .and_then(lopdf::Object::as_dict)
+        .expect("The catalog should have an /AcroForm dictionary");
+    let fields = acro_form.get(b"Fields").unwrap().as_array().unwrap();
+    assert_eq!(fields.len(), 2);
+
+    let field = |i: usize| {
+        doc.get_dictionary(fields[i].as_reference().unwrap())
+            .unwrap()
+    };
+    let text = field(0);
+    assert_eq!(text.get(b"FT").unwrap().as_name().unwrap(), b"Tx");
+    assert_eq!(text.get(b"T").unwrap().as_str().unwrap(), b"customer");
+    assert_eq!(text.get(b"V").unwrap().as_str().unwrap(), b"Jane Doe");
+    assert!(text.get(b"AP").is_ok());
+
+    let checkbox = field(1);
+    assert_eq!(checkbox.get(b"FT").unwrap().as_name().unwrap(), b"Btn");
+    assert_eq!(checkbox.get(b"V").unwrap().as_name().unwrap(), b"Yes");
+    assert_eq!(checkbox.get(b"AS").unwrap().as_name().unwrap(), b"Yes");
+
+    let page_id = doc.get_pages()[&1];
+    let annots = doc
+        .get_dictionary(page_id)
+        .unwrap()
+        .get(b"Annots")
+        .unwrap()
+        .as_array()
+        .unwrap();
+    assert_eq!(annots, fields);
+}
@@ -1,5 +1,5 @@
 use lopdf::Document as LopdfDocument;
-use petty::PipelineBuilder;
+use petty::{NumberLocale, PipelineBuilder};
 use serde_json::json;
 use std::collections::BTreeMap;
 use std::io::Cursor;
@@ -934,6 +934,61 @@ fn test_xslt3_text_value_templates() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_number_locale_sets_default_format_number_separators()
+-> Result<(), Box<dyn std::error::Error>> {
+    let xslt_template = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xsl:stylesheet version="3.0"
+                xmlns:xsl="http://www.w3.org/1999/XSL/Transform"
+                xmlns:fo="http://www.w3.org/1999/XSL/Format"
+                expand-text="yes">
+
+    <xsl:decimal-format name="us"/>
+    <fo:simple-page-master page-width="210mm" page-height="297mm" margin="2cm"/>
+
+    <xsl:template match="/">
+        <fo:block>
+            <fo:block>Total: {format-number(invoice/total, '#,##0.00')}</fo:block>
+            <fo:block>Units: {format-number(invoice/units, '#,##0')}</fo:block>
+            <fo:block>Explicit: {format-number(invoice/total, '#,##0.00', 'us')}</fo:block>
+        </fo:block>
+    </xsl:template>
+
+</xsl:stylesheet>"#;
+
+    let pipeline = PipelineBuilder::new()
+        .with_template_source(xslt_template, "xslt")?
+        .with_number_locale(NumberLocale::from_language_tag("de-DE").unwrap())
+        .build()?;
+
+    let data = vec![json!({ "invoice": { "total": 1234.56, "units": 1500000 } })];
+    let result = tokio::runtime::Runtime::new()?.block_on(async {
+        pipeline
+            .generate(data.into_iter(), Cursor::new(Vec::new()))
+            .await
+    })?;
+
+    let extracted_text = extract_text_from_pdf(&result.into_inner())?;
+    assert!(
+        extracted_text.contains("Total: 1.234,56"),
+        "{}",
+        extracted_text
+    );
+    assert!(
+        extracted_text.contains("Units: 1.500.000"),
+        "{}",
+        extracted_text
+    );
+    // A named decimal format is an explicit choice and keeps its own separators.
+    assert!(
+        extracted_text.contains("Explicit: 1,234.56"),
+        "{}",
+        extracted_text
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_xslt3_iterate_instruction() -> Result<(), Box<dyn std::error::Error>> {
     let _ = env_logger::builder().is_test(true).try_init();
@@ -158,3 +158,99 @@ fn test_mixed_content_across_pages() -> TestResult {
     assert_pdf_contains_text!(pdf, "Point 1");
     Ok(())
 }
+
+#[test]
+fn test_page_footer_shows_total_including_role_pages() -> TestResult {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let template = json!({
+        "_stylesheet": {
+            "defaultPageMaster": "default",
+            "pageMasters": { "default": { "size": "A4", "margins": "2cm" } },
+            "styles": {}
+        },
+        "_roles": {
+            "cover-page": paragraph("Annual report ({{pageCount}} pages)"),
+            "page-footer": paragraph("Page {{page_number}} of {{document.pageCount}}")
+        },
+        "_template": block(vec![
+            heading(1, "Introduction", Some("intro")),
+            page_break(),
+            heading(1, "Results", Some("results")),
+            page_break(),
+            heading(1, "Outlook", Some("outlook")),
+        ])
+    });
+
+    let pdf = generate_pdf_from_json(&template)?;
+    let total = pdf.page_count();
+    assert_eq!(total, 4, "the cover page followed by three body pages");
+    assert_pdf_contains_text!(pdf, "Annual report (4 pages)");
+    for page in 1..=total {
+        let text = pdf.doc.extract_text(&[page as u32])?;
+        let footer = format!("Page {} of {}", page, total);
+        assert!(
+            text.contains(&footer),
+            "page {page} should show '{footer}', but its text was:\n{text}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_page_header_varies_on_first_and_even_pages() -> TestResult {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let header = json!({
+        "if": "is_first",
+        "then": paragraph("Title page header"),
+        "else": {
+            "if": "is_odd",
+            "then": paragraph("Right-hand header"),
+            "else": paragraph("Left-hand header")
+        }
+    });
+    let template = json!({
+        "_stylesheet": {
+            "defaultPageMaster": "default",
+            "pageMasters": { "default": { "size": "A4", "margins": "2cm" } },
+            "styles": {}
+        },
+        "_roles": {
+            "page-header": header,
+            "page-footer": {
+                "if": "is_last",
+                "then": paragraph("The end"),
+                "else": paragraph("Continued")
+            }
+        },
+        "_template": block(vec![
+            paragraph("One"),
+            page_break(),
+            paragraph("Two"),
+            page_break(),
+            paragraph("Three"),
+            page_break(),
+            paragraph("Four"),
+        ])
+    });
+
+    let pdf = generate_pdf_from_json(&template)?;
+    assert_pdf_page_count!(pdf, 4);
+    let expected = [
+        ("Title page header", "Continued"),
+        ("Left-hand header", "Continued"),
+        ("Right-hand header", "Continued"),
+        ("Left-hand header", "The end"),
+    ];
+    let headers = ["Title page header", "Left-hand header", "Right-hand header"];
+    for (page, (header, footer)) in expected.into_iter().enumerate() {
+        let text = pdf.doc.extract_text(&[page as u32 + 1])?;
+        assert!(text.contains(header), "page {}: {text}", page + 1);
+        assert!(text.contains(footer), "page {}: {text}", page + 1);
+        for other in headers.iter().filter(|h| **h != header) {
+            assert!(!text.contains(other), "page {}: {text}", page + 1);
+        }
+    }
+    Ok(())
+}
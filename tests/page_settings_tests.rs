@@ -138,3 +138,47 @@ fn test_margin_units_mm() -> TestResult {
     assert_pdf_page_count!(pdf, 1);
     Ok(())
 }
+
+#[test]
+fn test_bleed_grows_media_box_and_draws_crop_marks() -> TestResult {
+    let template = template_with_page_master(
+        json!({ "size": { "width": 100, "height": 200 }, "margins": "10pt", "bleed": 9, "cropMarks": true }),
+        json!({}),
+        paragraph("Print me"),
+    );
+    let pdf = generate_pdf_from_json(&template)?;
+
+    let page_id = pdf.doc.get_pages()[&1];
+    let page = pdf.doc.get_dictionary(page_id)?;
+    let rect = |key: &[u8]| -> Vec<f32> {
+        page.get(key)
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o.as_float().unwrap())
+            .collect()
+    };
+    assert_eq!(rect(b"TrimBox"), vec![0.0, 0.0, 100.0, 200.0]);
+    assert_eq!(rect(b"MediaBox"), vec![-9.0, -9.0, 109.0, 209.0]);
+    assert_eq!(rect(b"BleedBox"), rect(b"MediaBox"));
+
+    // Each corner gets a horizontal and a vertical mark, from 3pt to 9pt past the trim.
+    let content = pdf.doc.get_and_decode_page_content(page_id)?;
+    let point = |op: &lopdf::content::Operation| -> (f32, f32) {
+        (
+            op.operands[0].as_float().unwrap(),
+            op.operands[1].as_float().unwrap(),
+        )
+    };
+    let marks: Vec<_> = content
+        .operations
+        .windows(2)
+        .filter(|w| w[0].operator == "m" && w[1].operator == "l")
+        .map(|w| (point(&w[0]), point(&w[1])))
+        .collect();
+    assert_eq!(marks.len(), 8);
+    assert!(marks.contains(&((-3.0, 0.0), (-9.0, 0.0))));
+    assert!(marks.contains(&((100.0, 203.0), (100.0, 209.0))));
+    Ok(())
+}
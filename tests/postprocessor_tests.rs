@@ -0,0 +1,64 @@
+mod common;
+
+use common::fixtures::*;
+use lopdf::{Object, Stream, dictionary};
+use petty::PipelineBuilder;
+use serde_json::json;
+use std::io::Cursor;
+
+#[tokio::test]
+async fn test_document_postprocessor_adds_file_attachment() {
+    let template = template_with_styles(json!({}), paragraph("Hello {{name}}"));
+    let pipeline = PipelineBuilder::new()
+        .with_template_source(&template.to_string(), "json")
+        .unwrap()
+        .with_document_postprocessor(|doc| {
+            let file_id = doc.add_object(Stream::new(
+                dictionary! { "Type" => "EmbeddedFile" },
+                b"id,name\n1,Alice\n".to_vec(),
+            ));
+            let spec_id = doc.add_object(dictionary! {
+                "Type" => "Filespec",
+                "F" => Object::string_literal("data.csv"),
+                "EF" => dictionary! { "F" => file_id },
+            });
+            let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+            let names = dictionary! {
+                "EmbeddedFiles" => dictionary! {
+                    "Names" => vec![Object::string_literal("data.csv"), spec_id.into()],
+                },
+            };
+            doc.get_dictionary_mut(root_id).unwrap().set("Names", names);
+        })
+        .build()
+        .unwrap();
+
+    let data = vec![json!({"name": "Alice"})];
+    let writer = pipeline
+        .generate(data.into_iter(), Cursor::new(Vec::new()))
+        .await
+        .unwrap();
+
+    let doc = lopdf::Document::load_mem(&writer.into_inner()).unwrap();
+    let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+    let names = doc
+        .get_dictionary(root_id)
+        .and_then(|root| root.get(b"Names"))
+        .and_then(Object::as_dict)
+        .and_then(|names| names.get(b"EmbeddedFiles"))
+        .and_then(Object::as_dict)
+        .and_then(|files| files.get(b"Names"))
+        .and_then(Object::as_array)
+        .expect("The attachment name tree should survive serialization");
+    let spec = doc
+        .get_dictionary(names[1].as_reference().unwrap())
+        .unwrap();
+    let file_id = spec
+        .get(b"EF")
+        .and_then(Object::as_dict)
+        .and_then(|ef| ef.get(b"F"))
+        .and_then(Object::as_reference)
+        .unwrap();
+    let file = doc.get_object(file_id).unwrap().as_stream().unwrap();
+    assert_eq!(file.content, b"id,name\n1,Alice\n");
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::fixtures::*;
+use petty::resource::InMemoryResourceProvider;
+use petty::{PipelineBuilder, PipelineError, ResourceError, ResourceLimits};
+use serde_json::json;
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// A PNG signature and header declaring a `width` x `height` image, which is all
+/// the dimension limit reads.
+fn png_header(width: u32, height: u32) -> Vec<u8> {
+    let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    png.extend(width.to_be_bytes());
+    png.extend(height.to_be_bytes());
+    png
+}
+
+#[tokio::test]
+async fn test_image_over_dimension_cap_fails_with_resource_error() {
+    let provider = InMemoryResourceProvider::new();
+    provider.add("huge.png", png_header(20_000, 480)).unwrap();
+    let template = template_with_styles(
+        json!({}),
+        block(vec![json!({ "type": "Image", "src": "huge.png" })]),
+    );
+
+    let pipeline = PipelineBuilder::new()
+        .with_template_source(&template.to_string(), "json")
+        .unwrap()
+        .with_resource_provider(Arc::new(provider))
+        .with_resource_limits(ResourceLimits {
+            max_image_dimensions: Some((4096, 4096)),
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    let result = pipeline
+        .generate(vec![json!({})].into_iter(), Cursor::new(Vec::new()))
+        .await;
+    assert!(
+        matches!(
+            &result,
+            Err(PipelineError::Resource(ResourceError::LimitExceeded { path, .. }))
+                if path == "huge.png"
+        ),
+        "expected a resource limit error, got {:?}",
+        result.err()
+    );
+}
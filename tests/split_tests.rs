@@ -0,0 +1,41 @@
+mod common;
+
+use common::TestResult;
+use common::fixtures::*;
+use petty::PipelineBuilder;
+use serde_json::{Value, json};
+
+#[test]
+fn test_generate_split_writes_one_file_per_record() -> TestResult {
+    let temp_dir = tempfile::tempdir()?;
+
+    let template = template_with_styles(json!({}), paragraph("Hello {{name}}"));
+    let pipeline = PipelineBuilder::new()
+        .with_template_source(&serde_json::to_string(&template)?, "json")?
+        .build()?;
+
+    let names = ["Alice", "Bob", "Carol"];
+    let data: Vec<Value> = names.iter().map(|n| json!({ "name": n })).collect();
+    let outputs = pipeline.generate_split(data, |i, _| {
+        temp_dir.path().join(format!("record-{}.pdf", i))
+    })?;
+
+    assert_eq!(outputs.len(), 3);
+    for (i, path) in outputs.iter().enumerate() {
+        assert_eq!(path, &temp_dir.path().join(format!("record-{}.pdf", i)));
+        let bytes = std::fs::read(path)?;
+        let doc = lopdf::Document::load_mem(&bytes).expect("Each output should be a valid PDF");
+        assert_eq!(doc.get_pages().len(), 1);
+
+        let content = String::from_utf8_lossy(&bytes);
+        for (j, name) in names.iter().enumerate() {
+            assert_eq!(
+                content.contains(&format!("Hello {}", name)),
+                i == j,
+                "record-{}.pdf should only contain its own record",
+                i
+            );
+        }
+    }
+    Ok(())
+}
@@ -268,3 +268,45 @@ fn test_bold_italic_combination() -> TestResult {
     // );
     Ok(())
 }
+
+#[test]
+fn test_standard_font_is_referenced_without_embedding() -> TestResult {
+    // Right-aligned, so the text's x position reveals its measured width.
+    let template = template_with_page_master(
+        json!({ "size": { "width": 200, "height": 100 }, "margins": "10pt" }),
+        json!({ "std": { "font-family": "Helvetica", "font-source": "standard", "text-align": "right" } }),
+        styled_paragraph("Hello", &["std"]),
+    );
+    let pdf = generate_pdf_from_json(&template)?;
+    let doc = &pdf.doc;
+
+    let has_font_file = doc.objects.values().any(|object| {
+        object.as_dict().is_ok_and(|dict| {
+            [&b"FontFile"[..], b"FontFile2", b"FontFile3"]
+                .iter()
+                .any(|key| dict.has(key))
+        })
+    });
+    assert!(!has_font_file, "standard fonts must not be embedded");
+
+    let page_id = doc.get_pages()[&1];
+    let (_, resource_ids) = doc.get_page_resources(page_id)?;
+    let font = doc
+        .get_dictionary(resource_ids[0])
+        .and_then(|resources| resources.get(b"Font"))
+        .and_then(lopdf::Object::as_dict)
+        .and_then(|fonts| fonts.get(b"Std-Helvetica"))
+        .and_then(lopdf::Object::as_dict)?;
+    assert_eq!(font.get(b"BaseFont")?.as_name()?, b"Helvetica");
+    assert!(!font.has(b"FontDescriptor"));
+
+    let content = doc.get_and_decode_page_content(page_id)?;
+    let ops = &content.operations;
+    let tf = ops.iter().find(|op| op.operator == "Tf").unwrap();
+    assert_eq!(tf.operands[0].as_name()?, b"Std-Helvetica");
+    // AFM widths of "Hello": H 722 + e 556 + l 222 + l 222 + o 556 = 2278 units.
+    let td = ops.iter().find(|op| op.operator == "Td").unwrap();
+    let x = td.operands[0].as_float()?;
+    assert!((x - (190.0 - 2.278 * 12.0)).abs() < 0.01, "x = {x}");
+    Ok(())
+}
@@ -1,6 +1,7 @@
 mod common;
 
-use common::{TestResult, generate_pdf_from_xslt};
+use common::fixtures::{paragraph, table, table_cell, table_row};
+use common::{TestResult, generate_pdf_from_json, generate_pdf_from_xslt};
 use serde_json::json;
 
 #[test]
@@ -170,3 +171,75 @@ fn test_toc_link_count() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn test_lists_of_figures_and_tables_reference_caption_pages() -> TestResult {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let listing = |title: &str, items: &str| {
+        json!({
+            "type": "Block",
+            "children": [
+                paragraph(title),
+                {
+                    "each": items,
+                    "template": {
+                        "type": "Paragraph",
+                        "children": [
+                            { "type": "Text", "content": "{{label}}: {{caption}} ... page {{pageNumber}}" }
+                        ]
+                    }
+                }
+            ]
+        })
+    };
+    let caption = |kind: &str, text: &str, content: serde_json::Value| {
+        json!({
+            "type": "Caption",
+            "kind": kind,
+            "children": [{ "type": "Text", "content": text }],
+            "content": content
+        })
+    };
+    let small_table = |text: &str| {
+        table(
+            vec![json!({})],
+            None,
+            vec![table_row(vec![table_cell(text)])],
+        )
+    };
+
+    let template = json!({
+        "_stylesheet": {
+            "defaultPageMaster": "default",
+            "pageMasters": { "default": { "size": "A4", "margins": "2cm" } },
+            "styles": {}
+        },
+        "_roles": {
+            "list-of-figures": listing("List of Figures", "figures"),
+            "list-of-tables": listing("List of Tables", "tables")
+        },
+        "_template": {
+            "type": "Block",
+            "children": [
+                caption("table", "Revenue", small_table("North")),
+                { "type": "PageBreak" },
+                caption("figure", "Growth chart", paragraph("Chart placeholder")),
+                { "type": "PageBreak" },
+                caption("table", "Expenses", small_table("South"))
+            ]
+        }
+    });
+
+    let pdf = generate_pdf_from_json(&template)?;
+
+    // One page for each listing, followed by the three body pages.
+    assert_pdf_page_count!(pdf, 5);
+    assert_pdf_contains_text!(pdf, "List of Figures");
+    assert_pdf_contains_text!(pdf, "List of Tables");
+    assert_pdf_contains_text!(pdf, "Table 1: Revenue ... page 1");
+    assert_pdf_contains_text!(pdf, "Figure 1: Growth chart ... page 2");
+    assert_pdf_contains_text!(pdf, "Table 2: Expenses ... page 3");
+
+    Ok(())
+}